@@ -0,0 +1,412 @@
+use std::{
+    error, fmt, fs,
+    io::{self, BufRead, BufReader},
+    net::IpAddr,
+    str::FromStr,
+};
+
+use crate::netgroup::Netgroups;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Permission {
+    Grant,
+    Deny,
+}
+
+/// A line of `access.conf`, `permission : users : origins`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Rule {
+    pub permission: Permission,
+    pub users: Vec<String>,
+    pub origins: Vec<String>,
+}
+
+impl FromStr for Rule {
+    type Err = ParseAccessError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let fields = s.splitn(3, ':').collect::<Vec<_>>();
+
+        let [permission, users, origins] = fields[..] else {
+            return Err(ParseAccessError::InvalidEntry(s.to_owned()));
+        };
+
+        let list = |field: &str| {
+            field
+                .split(|c: char| c.is_whitespace() || c == ',')
+                .filter(|item| !item.is_empty())
+                .map(String::from)
+                .collect::<Vec<_>>()
+        };
+
+        let rule = Rule {
+            permission: match permission.trim() {
+                "+" => Permission::Grant,
+                "-" => Permission::Deny,
+                _ => return Err(ParseAccessError::InvalidEntry(s.to_owned())),
+            },
+            users: list(users),
+            origins: list(origins),
+        };
+
+        if rule.users.is_empty() || rule.origins.is_empty() {
+            return Err(ParseAccessError::InvalidEntry(s.to_owned()));
+        }
+
+        Ok(rule)
+    }
+}
+
+impl fmt::Display for Rule {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let permission = match self.permission {
+            Permission::Grant => "+",
+            Permission::Deny => "-",
+        };
+
+        write!(
+            f,
+            "{} : {} : {}",
+            permission,
+            self.users.join(" "),
+            self.origins.join(" ")
+        )
+    }
+}
+
+/// The user logging in.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct User {
+    pub name: String,
+    /// Names of the groups the user is a member of, including the primary one.
+    pub groups: Vec<String>,
+}
+
+///
+/// The rules of `access.conf`, evaluated with `permits`.
+///
+/// ```no_run
+/// let access = unixism::access::parse_default().unwrap().collect::<unixism::access::Access>();
+/// ```
+#[derive(Debug, Default, Clone)]
+pub struct Access {
+    pub rules: Vec<Rule>,
+    /// Netgroups `@netgroup` items are matched against, none unless set.
+    pub netgroups: Netgroups,
+}
+
+impl Access {
+    ///
+    /// Decides the login the way pam_access does: the first rule matching both the user and the origin
+    /// wins, and a login no rule matches is permitted. `origin` is a tty name, a host name or an address.
+    ///
+    /// User items are `ALL`, names compared with the user and its groups, `(group)`, `@netgroup`
+    /// and `user@origin`. Origin items are `ALL`, `LOCAL` for origins without a dot, names, `.domain`
+    /// suffixes, `192.168.` prefixes, `network/prefix` or `network/mask` and `@netgroup`.
+    /// Host names are never resolved. `EXCEPT` excludes the items after it from the items before it.
+    ///
+    /// ```no_run
+    /// use unixism::access::{self, Access, User};
+    ///
+    /// let access = access::parse_default().unwrap().collect::<Access>();
+    /// let user = User {
+    ///     name: "alice".to_owned(),
+    ///     groups: vec!["alice".to_owned(), "wheel".to_owned()],
+    /// };
+    ///
+    /// println!("{}", access.permits(&user, "192.168.1.10"));
+    /// ```
+    pub fn permits(&self, user: &User, origin: &str) -> bool {
+        self.rules
+            .iter()
+            .find(|rule| {
+                list_matches(&rule.users, &|item| self.user_matches(item, user, origin))
+                    && list_matches(&rule.origins, &|item| self.origin_matches(item, origin))
+            })
+            .is_none_or(|rule| rule.permission == Permission::Grant)
+    }
+
+    fn user_matches(&self, item: &str, user: &User, origin: &str) -> bool {
+        if let Some((name, host)) = item.split_once('@').filter(|(name, _)| !name.is_empty()) {
+            return self.user_matches(name, user, origin) && self.origin_matches(host, origin);
+        }
+
+        if let Some(netgroup) = item.strip_prefix('@') {
+            return self
+                .netgroups
+                .contains(netgroup, None, Some(&user.name), None)
+                .unwrap_or(false);
+        }
+
+        if let Some(group) = item
+            .strip_prefix('(')
+            .and_then(|item| item.strip_suffix(')'))
+        {
+            return user.groups.iter().any(|name| name == group);
+        }
+
+        item == "ALL" || item == user.name || user.groups.iter().any(|group| group == item)
+    }
+
+    fn origin_matches(&self, item: &str, origin: &str) -> bool {
+        if let Some(netgroup) = item.strip_prefix('@') {
+            return self
+                .netgroups
+                .contains(netgroup, Some(origin), None, None)
+                .unwrap_or(false);
+        }
+
+        let address = origin.parse::<IpAddr>().ok();
+
+        match item {
+            "ALL" => true,
+            "LOCAL" => address.is_none() && !origin.contains('.'),
+            item if item.starts_with('.') => origin
+                .to_ascii_lowercase()
+                .ends_with(&item.to_ascii_lowercase()),
+            item if item.ends_with('.') && address.is_some_and(|address| address.is_ipv4()) => {
+                origin.starts_with(item)
+            }
+            item if item.contains('/') => address.is_some_and(|address| in_network(item, address)),
+            item => match (item.parse::<IpAddr>(), address) {
+                (Ok(item), Some(address)) => item == address,
+                _ => item.eq_ignore_ascii_case(origin.strip_prefix("/dev/").unwrap_or(origin)),
+            },
+        }
+    }
+}
+
+impl FromIterator<Rule> for Access {
+    fn from_iter<I>(iter: I) -> Self
+    where
+        I: IntoIterator<Item = Rule>,
+    {
+        Access {
+            rules: iter.into_iter().collect(),
+            netgroups: Netgroups::default(),
+        }
+    }
+}
+
+/// Whether an item before `EXCEPT` matches and none of the items after it, which may have an `EXCEPT` of their own.
+fn list_matches<F>(items: &[String], matches: &F) -> bool
+where
+    F: Fn(&str) -> bool,
+{
+    let except = items
+        .iter()
+        .position(|item| item == "EXCEPT")
+        .unwrap_or(items.len());
+
+    items[..except].iter().any(|item| matches(item))
+        && (except == items.len() || !list_matches(&items[except + 1..], matches))
+}
+
+/// Whether the address is in the `network/prefix` or `network/mask`.
+fn in_network(network: &str, address: IpAddr) -> bool {
+    let Some((network, prefix)) = network.split_once('/') else {
+        return false;
+    };
+    let Ok(network) = network.parse::<IpAddr>() else {
+        return false;
+    };
+
+    let prefix = match prefix.parse::<u32>() {
+        Ok(prefix) => prefix,
+        Err(_) => match prefix.parse::<IpAddr>() {
+            Ok(IpAddr::V4(mask)) => u32::from(mask).leading_ones(),
+            _ => return false,
+        },
+    };
+
+    let (network, address, bits) = match (network, address) {
+        (IpAddr::V4(network), IpAddr::V4(address)) => (
+            u128::from(u32::from(network)) << 96,
+            u128::from(u32::from(address)) << 96,
+            32,
+        ),
+        (IpAddr::V6(network), IpAddr::V6(address)) => {
+            (u128::from(network), u128::from(address), 128)
+        }
+        _ => return false,
+    };
+
+    if prefix > bits {
+        return false;
+    }
+
+    let mask = u128::MAX.checked_shl(128 - prefix).unwrap_or(0);
+
+    network & mask == address & mask
+}
+
+#[derive(Debug)]
+pub enum ParseAccessError {
+    InvalidEntry(String),
+    IOError(io::Error),
+}
+
+impl error::Error for ParseAccessError {}
+
+impl fmt::Display for ParseAccessError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::InvalidEntry(entry) => write!(f, "invalid entry: {}", entry),
+            Self::IOError(error) => write!(f, "{}", error),
+        }
+    }
+}
+
+impl From<io::Error> for ParseAccessError {
+    fn from(value: io::Error) -> Self {
+        ParseAccessError::IOError(value)
+    }
+}
+
+///
+/// ```no_run
+/// let rules = unixism::access::parse(std::fs::File::open("/etc/security/access.conf").unwrap()).unwrap();
+///
+/// for rule in rules {
+///     println!("{}", rule);
+/// }
+/// ```
+pub fn parse<R>(reader: R) -> Result<impl Iterator<Item = Rule>, ParseAccessError>
+where
+    R: io::Read,
+{
+    let mut rules = Vec::new();
+
+    for line in BufReader::new(reader).lines() {
+        let line = line?;
+        let line = line.split('#').next().unwrap_or_default().trim();
+
+        if line.is_empty() {
+            continue;
+        }
+
+        rules.push(line.parse::<Rule>()?);
+    }
+
+    Ok(rules.into_iter())
+}
+
+///
+/// Same as parse, but parses the `/etc/security/access.conf` as default.
+///
+/// ```no_run
+/// let rules = unixism::access::parse_default().unwrap();
+/// ```
+pub fn parse_default() -> Result<impl Iterator<Item = Rule>, ParseAccessError> {
+    parse(fs::File::open("/etc/security/access.conf")?)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Cursor;
+
+    use crate::netgroup;
+
+    use super::*;
+
+    fn user(name: &str, groups: &[&str]) -> User {
+        User {
+            name: name.to_owned(),
+            groups: groups.iter().map(|group| group.to_string()).collect(),
+        }
+    }
+
+    #[test]
+    fn it_parse() {
+        let rules = parse(Cursor::new(
+            r#"
+# Login access control table.
++ : root : LOCAL 127.0.0.1
+- : ALL EXCEPT (wheel) shutdown sync : LOCAL
++:@admins:10.0.0.0/8, .example.com
+        "#,
+        ))
+        .unwrap()
+        .collect::<Vec<_>>();
+
+        assert_eq!(3, rules.len());
+        assert_eq!(
+            Rule {
+                permission: Permission::Deny,
+                users: vec!["ALL", "EXCEPT", "(wheel)", "shutdown", "sync"]
+                    .into_iter()
+                    .map(String::from)
+                    .collect(),
+                origins: vec!["LOCAL".to_owned()],
+            },
+            rules[1]
+        );
+        assert_eq!(
+            "+ : @admins : 10.0.0.0/8 .example.com",
+            rules[2].to_string()
+        );
+
+        assert!(parse(Cursor::new("* : root : ALL")).is_err());
+        assert!(parse(Cursor::new("+ : root")).is_err());
+        assert!(parse(Cursor::new("+ : : ALL")).is_err());
+    }
+
+    #[test]
+    fn it_permits() {
+        let mut access = parse(Cursor::new(
+            r#"
++ : root : LOCAL
+- : root : ALL
++ : @admins : 10.0.0.0/8 .example.com
++ : bob@192.168. : ALL
+- : ALL EXCEPT (wheel) deploy : 192.168.1.0/255.255.255.0 EXCEPT 192.168.1.10
++ : ops : 2001:db8::/32
+- : ALL : ALL
+        "#,
+        ))
+        .unwrap()
+        .collect::<Access>();
+
+        access.netgroups = netgroup::parse(Cursor::new("admins (-,carol,)\n"))
+            .unwrap()
+            .collect();
+
+        let root = user("root", &["root"]);
+        assert!(access.permits(&root, "tty1"));
+        assert!(access.permits(&root, "/dev/tty1"));
+        assert!(!access.permits(&root, "10.0.0.1"));
+
+        let carol = user("carol", &["carol"]);
+        assert!(access.permits(&carol, "10.1.2.3"));
+        assert!(access.permits(&carol, "host.EXAMPLE.com"));
+        assert!(!access.permits(&carol, "11.0.0.1"));
+
+        let bob = user("bob", &["bob"]);
+        assert!(access.permits(&bob, "192.168.7.7"));
+        assert!(!access.permits(&bob, "10.0.0.1"));
+
+        let alice = user("alice", &["alice", "wheel"]);
+        let eve = user("eve", &["eve"]);
+        assert!(!access.permits(&alice, "192.168.1.20"));
+        assert!(!access.permits(&eve, "192.168.1.20"));
+        assert!(!access.permits(&eve, "192.168.1.10"));
+
+        let dave = user("dave", &["dave", "ops"]);
+        assert!(access.permits(&dave, "2001:db8::1"));
+        assert!(!access.permits(&dave, "2001:db9::1"));
+
+        assert!(Access::default().permits(&eve, "anywhere"));
+    }
+
+    #[test]
+    fn it_list_matches() {
+        let items = |items: &str| items.split(' ').map(String::from).collect::<Vec<_>>();
+        let matches = |item: &str| ["a", "b", "c"].contains(&item);
+
+        assert!(list_matches(&items("x a"), &matches));
+        assert!(!list_matches(&items("a EXCEPT b a"), &matches));
+        assert!(list_matches(&items("a EXCEPT x"), &matches));
+        assert!(list_matches(&items("a EXCEPT b EXCEPT c"), &matches));
+        assert!(!list_matches(&items("x EXCEPT a"), &matches));
+    }
+}
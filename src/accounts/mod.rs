@@ -0,0 +1,267 @@
+use std::{collections::HashMap, fmt, fs, os::unix::fs::PermissionsExt};
+
+use crate::{group::Group, passwd, shadow};
+
+/// A consistency problem found across the account databases.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Warning {
+    DuplicateUserName(String),
+    DuplicateUid { uid: u32, users: Vec<String> },
+    DuplicateGroupName(String),
+    DuplicateGid { gid: u32, groups: Vec<String> },
+    MissingPrimaryGroup { user: String, gid: u32 },
+    UnknownGroupMember { group: String, user: String },
+    PasswdWithoutShadow(String),
+    ShadowWithoutPasswd(String),
+    WorldReadableHome { user: String, home: String },
+}
+
+impl fmt::Display for Warning {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::DuplicateUserName(name) => write!(f, "user {} is defined more than once", name),
+            Self::DuplicateUid { uid, users } => {
+                write!(f, "uid {} is shared by users {}", uid, users.join(", "))
+            }
+            Self::DuplicateGroupName(name) => {
+                write!(f, "group {} is defined more than once", name)
+            }
+            Self::DuplicateGid { gid, groups } => {
+                write!(f, "gid {} is shared by groups {}", gid, groups.join(", "))
+            }
+            Self::MissingPrimaryGroup { user, gid } => {
+                write!(
+                    f,
+                    "user {} references nonexistent primary gid {}",
+                    user, gid
+                )
+            }
+            Self::UnknownGroupMember { group, user } => {
+                write!(f, "group {} lists nonexistent user {}", group, user)
+            }
+            Self::PasswdWithoutShadow(user) => {
+                write!(
+                    f,
+                    "user {} has a shadowed password but no shadow entry",
+                    user
+                )
+            }
+            Self::ShadowWithoutPasswd(user) => {
+                write!(f, "shadow entry {} has no passwd counterpart", user)
+            }
+            Self::WorldReadableHome { user, home } => {
+                write!(
+                    f,
+                    "home directory {} of user {} is world-readable",
+                    home, user
+                )
+            }
+        }
+    }
+}
+
+///
+/// Checks passwd, group and shadow entries against each other.
+///
+/// ```no_run
+/// use unixism::{accounts, group, passwd, shadow};
+///
+/// let users = passwd::parse_default().unwrap().collect::<Vec<_>>();
+/// let groups = group::parse_default().unwrap().collect::<Vec<_>>();
+/// let shadow = shadow::parse_default().unwrap().collect::<Vec<_>>();
+///
+/// for warning in accounts::lint(&users, &groups, &shadow) {
+///     println!("{warning}");
+/// }
+/// ```
+pub fn lint(users: &[passwd::Entry], groups: &[Group], shadow: &[shadow::Entry]) -> Vec<Warning> {
+    let mut warnings = Vec::new();
+
+    for name in duplicates(users.iter().map(|user| user.name.as_str())) {
+        warnings.push(Warning::DuplicateUserName(name.to_owned()));
+    }
+
+    for (uid, names) in shared_ids(users.iter().map(|user| (user.uid, user.name.as_str()))) {
+        warnings.push(Warning::DuplicateUid { uid, users: names });
+    }
+
+    for name in duplicates(groups.iter().map(|group| group.name.as_str())) {
+        warnings.push(Warning::DuplicateGroupName(name.to_owned()));
+    }
+
+    for (gid, names) in shared_ids(groups.iter().map(|group| (group.gid, group.name.as_str()))) {
+        warnings.push(Warning::DuplicateGid { gid, groups: names });
+    }
+
+    for user in users {
+        if !groups.iter().any(|group| group.gid == user.gid) {
+            warnings.push(Warning::MissingPrimaryGroup {
+                user: user.name.clone(),
+                gid: user.gid,
+            });
+        }
+    }
+
+    for group in groups {
+        for member in &group.members {
+            if !users.iter().any(|user| &user.name == member) {
+                warnings.push(Warning::UnknownGroupMember {
+                    group: group.name.clone(),
+                    user: member.clone(),
+                });
+            }
+        }
+    }
+
+    for user in users {
+        if user.passwd == "x" && !shadow.iter().any(|entry| entry.name == user.name) {
+            warnings.push(Warning::PasswdWithoutShadow(user.name.clone()));
+        }
+    }
+
+    for entry in shadow {
+        if !users.iter().any(|user| user.name == entry.name) {
+            warnings.push(Warning::ShadowWithoutPasswd(entry.name.clone()));
+        }
+    }
+
+    warnings
+}
+
+///
+/// Checks the home directories of regular users on disk, reporting world-readable ones.
+/// Missing directories and system users (uid below `uid_min`) are skipped.
+///
+/// ```no_run
+/// let users = unixism::passwd::parse_default().unwrap().collect::<Vec<_>>();
+///
+/// for warning in unixism::accounts::lint_homes(&users, 1000) {
+///     println!("{warning}");
+/// }
+/// ```
+pub fn lint_homes(users: &[passwd::Entry], uid_min: u32) -> Vec<Warning> {
+    users
+        .iter()
+        .filter(|user| user.uid >= uid_min && !user.home.is_empty())
+        .filter(|user| {
+            fs::metadata(&user.home)
+                .map(|metadata| metadata.is_dir() && metadata.permissions().mode() & 0o004 != 0)
+                .unwrap_or(false)
+        })
+        .map(|user| Warning::WorldReadableHome {
+            user: user.name.clone(),
+            home: user.home.clone(),
+        })
+        .collect()
+}
+
+fn duplicates<'a, I>(names: I) -> Vec<&'a str>
+where
+    I: Iterator<Item = &'a str>,
+{
+    let mut seen = Vec::new();
+    let mut duplicated = Vec::new();
+
+    for name in names {
+        if seen.contains(&name) {
+            if !duplicated.contains(&name) {
+                duplicated.push(name);
+            }
+        } else {
+            seen.push(name);
+        }
+    }
+
+    duplicated
+}
+
+fn shared_ids<'a, I>(ids: I) -> Vec<(u32, Vec<String>)>
+where
+    I: Iterator<Item = (u32, &'a str)>,
+{
+    let mut order = Vec::new();
+    let mut names: HashMap<u32, Vec<String>> = HashMap::new();
+
+    for (id, name) in ids {
+        let entry = names.entry(id).or_default();
+
+        if entry.is_empty() {
+            order.push(id);
+        }
+
+        if !entry.iter().any(|existing| existing == name) {
+            entry.push(name.to_owned());
+        }
+    }
+
+    order
+        .into_iter()
+        .filter_map(|id| {
+            let names = names.remove(&id)?;
+
+            (names.len() > 1).then_some((id, names))
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Cursor;
+
+    use super::*;
+    use crate::group;
+
+    #[test]
+    fn it_lint() {
+        let users = passwd::parse(Cursor::new(
+            r#"
+root:x:0:0:root:/root:/bin/bash
+toor:x:0:0::/root:/bin/sh
+alice:x:1000:1000::/home/alice:/bin/sh
+bob:*:1001:2000::/home/bob:/bin/sh
+        "#,
+        ))
+        .unwrap()
+        .collect::<Vec<_>>();
+
+        let groups = group::parse(Cursor::new(
+            r#"
+root:x:0:
+alice:x:1000:
+wheel:x:10:alice,carol
+        "#,
+        ))
+        .unwrap()
+        .collect::<Vec<_>>();
+
+        let shadow = shadow::parse(Cursor::new(
+            r#"
+root:*:19000:0:99999:7:::
+alice:*:19000:0:99999:7:::
+ghost:*:19000:0:99999:7:::
+        "#,
+        ))
+        .unwrap()
+        .collect::<Vec<_>>();
+
+        assert_eq!(
+            vec![
+                Warning::DuplicateUid {
+                    uid: 0,
+                    users: vec!["root".to_owned(), "toor".to_owned()]
+                },
+                Warning::MissingPrimaryGroup {
+                    user: "bob".to_owned(),
+                    gid: 2000
+                },
+                Warning::UnknownGroupMember {
+                    group: "wheel".to_owned(),
+                    user: "carol".to_owned()
+                },
+                Warning::PasswdWithoutShadow("toor".to_owned()),
+                Warning::ShadowWithoutPasswd("ghost".to_owned()),
+            ],
+            lint(&users, &groups, &shadow)
+        );
+    }
+}
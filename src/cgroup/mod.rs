@@ -0,0 +1,486 @@
+use std::{
+    collections::HashMap,
+    error, fmt, fs,
+    io::{self, BufRead, BufReader},
+    num::ParseIntError,
+    path::{Path, PathBuf},
+    str::FromStr,
+};
+
+use crate::mounts::{self, MountInfo, ParseMountsError};
+
+/// Where the unified hierarchy is mounted on systemd systems and in containers.
+pub const UNIFIED_ROOT: &str = "/sys/fs/cgroup";
+
+/// A line of `/proc/<pid>/cgroup`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Entry {
+    /// Zero for the unified cgroup v2 hierarchy.
+    pub hierarchy: u32,
+    /// The v1 controllers of the hierarchy, empty for v2.
+    pub controllers: Vec<String>,
+    /// The path relative to the hierarchy mount point.
+    pub path: String,
+}
+
+impl FromStr for Entry {
+    type Err = ParseCgroupError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut fields = s.splitn(3, ':');
+
+        let (Some(hierarchy), Some(controllers), Some(path)) =
+            (fields.next(), fields.next(), fields.next())
+        else {
+            return Err(ParseCgroupError::InvalidEntry(s.to_owned()));
+        };
+
+        Ok(Entry {
+            hierarchy: hierarchy.parse()?,
+            controllers: controllers
+                .split(',')
+                .filter(|controller| !controller.is_empty())
+                .map(String::from)
+                .collect(),
+            path: path.to_owned(),
+        })
+    }
+}
+
+/// Returns the path of the unified hierarchy entry, `None` on pure cgroup v1 systems.
+pub fn unified(entries: &[Entry]) -> Option<&str> {
+    entries
+        .iter()
+        .find(|entry| entry.hierarchy == 0 && entry.controllers.is_empty())
+        .map(|entry| entry.path.as_str())
+}
+
+/// The `cpu.max` bandwidth limit.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CpuMax {
+    /// Microseconds of CPU time per period, `None` when unlimited.
+    pub quota: Option<u64>,
+    /// The period in microseconds.
+    pub period: u64,
+}
+
+impl CpuMax {
+    /// The limit as a number of CPUs, like `1.5`, `None` when unlimited.
+    pub fn cpus(&self) -> Option<f64> {
+        match (self.quota, self.period) {
+            (Some(quota), period) if period > 0 => Some(quota as f64 / period as f64),
+            _ => None,
+        }
+    }
+}
+
+impl FromStr for CpuMax {
+    type Err = ParseCgroupError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let fields = s.split_whitespace().collect::<Vec<_>>();
+
+        let (quota, period) = match fields[..] {
+            [quota] => (quota, "100000"),
+            [quota, period] => (quota, period),
+            _ => return Err(ParseCgroupError::InvalidEntry(s.to_owned())),
+        };
+
+        Ok(CpuMax {
+            quota: limit(quota)?,
+            period: period.parse()?,
+        })
+    }
+}
+
+/// The `cpu.stat` counters, times in microseconds.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct CpuStat {
+    pub usage_usec: u64,
+    pub user_usec: u64,
+    pub system_usec: u64,
+    /// The throttling counters, only present when the cpu controller is enabled.
+    pub nr_periods: Option<u64>,
+    pub nr_throttled: Option<u64>,
+    pub throttled_usec: Option<u64>,
+    pub others: HashMap<String, u64>,
+}
+
+/// A row of `io.stat`.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct IoStat {
+    pub major: u32,
+    pub minor: u32,
+    pub rbytes: u64,
+    pub wbytes: u64,
+    pub rios: u64,
+    pub wios: u64,
+    pub dbytes: u64,
+    pub dios: u64,
+}
+
+impl FromStr for IoStat {
+    type Err = ParseCgroupError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut fields = s.split_whitespace();
+
+        let (major, minor) = fields
+            .next()
+            .and_then(|device| device.split_once(':'))
+            .ok_or_else(|| ParseCgroupError::InvalidEntry(s.to_owned()))?;
+
+        let mut stat = IoStat {
+            major: major.parse()?,
+            minor: minor.parse()?,
+            ..IoStat::default()
+        };
+
+        for field in fields {
+            let (key, value) = field
+                .split_once('=')
+                .ok_or_else(|| ParseCgroupError::InvalidEntry(s.to_owned()))?;
+
+            match key {
+                "rbytes" => stat.rbytes = value.parse()?,
+                "wbytes" => stat.wbytes = value.parse()?,
+                "rios" => stat.rios = value.parse()?,
+                "wios" => stat.wios = value.parse()?,
+                "dbytes" => stat.dbytes = value.parse()?,
+                "dios" => stat.dios = value.parse()?,
+                _ => {}
+            }
+        }
+
+        Ok(stat)
+    }
+}
+
+/// Parses a limit where `max` means unlimited.
+fn limit(s: &str) -> Result<Option<u64>, ParseCgroupError> {
+    match s {
+        "max" => Ok(None),
+        s => Ok(Some(s.parse()?)),
+    }
+}
+
+///
+/// A cgroup v2 directory, read lazily by its accessors.
+/// Files of controllers which aren't enabled for the cgroup fail with a `NotFound` IO error.
+///
+/// ```no_run
+/// let cgroup = unixism::cgroup::Cgroup::current().unwrap();
+///
+/// println!("cpus: {:?}, memory: {:?}", cgroup.cpu_max().unwrap().cpus(), cgroup.memory_max().unwrap());
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Cgroup {
+    path: PathBuf,
+}
+
+impl Cgroup {
+    /// The cgroup at `path` relative to the hierarchy mounted at `root`.
+    pub fn at<P>(root: P, path: &str) -> Self
+    where
+        P: AsRef<Path>,
+    {
+        Cgroup {
+            path: root.as_ref().join(path.trim_start_matches('/')),
+        }
+    }
+
+    ///
+    /// The cgroup of the current process in the unified hierarchy, found through the `cgroup2`
+    /// mount of `/proc/self/mountinfo`, so hybrid systems with `/sys/fs/cgroup/unified` work too.
+    pub fn current() -> Result<Self, ParseCgroupError> {
+        let entries = parse_default()?.collect::<Vec<_>>();
+        let mountinfo = mounts::parse_mountinfo_default()?.collect::<Vec<_>>();
+
+        Self::locate(&entries, &mountinfo)
+    }
+
+    fn locate(entries: &[Entry], mountinfo: &[MountInfo]) -> Result<Self, ParseCgroupError> {
+        let path = unified(entries).ok_or(ParseCgroupError::NoUnifiedHierarchy)?;
+
+        let mount = mountinfo
+            .iter()
+            .find(|mount| mount.fstype == "cgroup2")
+            .ok_or(ParseCgroupError::NoUnifiedHierarchy)?;
+
+        // The mount may expose only a subtree, e.g. in a container without a cgroup namespace.
+        let path = match mount.root.as_str() {
+            "/" => path,
+            root => path
+                .strip_prefix(root)
+                .filter(|rest| rest.is_empty() || rest.starts_with('/'))
+                .unwrap_or(path),
+        };
+
+        Ok(Cgroup::at(&mount.mount_point, path))
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    fn read(&self, file: &str) -> Result<String, ParseCgroupError> {
+        Ok(fs::read_to_string(self.path.join(file))?.trim().to_owned())
+    }
+
+    pub fn cpu_max(&self) -> Result<CpuMax, ParseCgroupError> {
+        self.read("cpu.max")?.parse()
+    }
+
+    /// The hard memory limit in bytes, `None` when unlimited.
+    pub fn memory_max(&self) -> Result<Option<u64>, ParseCgroupError> {
+        limit(&self.read("memory.max")?)
+    }
+
+    /// The memory usage in bytes, including the page cache.
+    pub fn memory_current(&self) -> Result<u64, ParseCgroupError> {
+        Ok(self.read("memory.current")?.parse()?)
+    }
+
+    pub fn cpu_stat(&self) -> Result<CpuStat, ParseCgroupError> {
+        let mut stat = CpuStat::default();
+
+        for line in self.read("cpu.stat")?.lines() {
+            let fields = line.split_whitespace().collect::<Vec<_>>();
+
+            let [key, value] = fields[..] else {
+                return Err(ParseCgroupError::InvalidEntry(line.to_owned()));
+            };
+
+            let value = value.parse()?;
+
+            match key {
+                "usage_usec" => stat.usage_usec = value,
+                "user_usec" => stat.user_usec = value,
+                "system_usec" => stat.system_usec = value,
+                "nr_periods" => stat.nr_periods = Some(value),
+                "nr_throttled" => stat.nr_throttled = Some(value),
+                "throttled_usec" => stat.throttled_usec = Some(value),
+                key => {
+                    stat.others.insert(key.to_owned(), value);
+                }
+            }
+        }
+
+        Ok(stat)
+    }
+
+    pub fn io_stat(&self) -> Result<Vec<IoStat>, ParseCgroupError> {
+        self.read("io.stat")?
+            .lines()
+            .filter(|line| !line.trim().is_empty())
+            .map(str::parse)
+            .collect()
+    }
+
+    pub fn pids_current(&self) -> Result<u64, ParseCgroupError> {
+        Ok(self.read("pids.current")?.parse()?)
+    }
+
+    /// The limit of tasks, `None` when unlimited.
+    pub fn pids_max(&self) -> Result<Option<u64>, ParseCgroupError> {
+        limit(&self.read("pids.max")?)
+    }
+}
+
+#[derive(Debug)]
+pub enum ParseCgroupError {
+    InvalidEntry(String),
+    /// The process isn't in a cgroup v2 hierarchy.
+    NoUnifiedHierarchy,
+    ParseIntError(ParseIntError),
+    Mounts(ParseMountsError),
+    IOError(io::Error),
+}
+
+impl error::Error for ParseCgroupError {}
+
+impl fmt::Display for ParseCgroupError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::InvalidEntry(entry) => write!(f, "invalid entry: {}", entry),
+            Self::NoUnifiedHierarchy => write!(f, "no cgroup v2 hierarchy"),
+            Self::ParseIntError(error) => write!(f, "{}", error),
+            Self::Mounts(error) => write!(f, "{}", error),
+            Self::IOError(error) => write!(f, "{}", error),
+        }
+    }
+}
+
+impl From<ParseMountsError> for ParseCgroupError {
+    fn from(value: ParseMountsError) -> Self {
+        ParseCgroupError::Mounts(value)
+    }
+}
+
+impl From<ParseIntError> for ParseCgroupError {
+    fn from(value: ParseIntError) -> Self {
+        ParseCgroupError::ParseIntError(value)
+    }
+}
+
+impl From<io::Error> for ParseCgroupError {
+    fn from(value: io::Error) -> Self {
+        ParseCgroupError::IOError(value)
+    }
+}
+
+///
+/// ```no_run
+/// let entries = unixism::cgroup::parse(std::fs::File::open("/proc/1/cgroup").unwrap()).unwrap();
+///
+/// for entry in entries {
+///     println!("{} {:?} {}", entry.hierarchy, entry.controllers, entry.path);
+/// }
+/// ```
+pub fn parse<R>(reader: R) -> Result<impl Iterator<Item = Entry>, ParseCgroupError>
+where
+    R: io::Read,
+{
+    let mut entries = Vec::new();
+
+    for line in BufReader::new(reader).lines() {
+        let line = line?;
+        let line = line.trim();
+
+        if line.is_empty() {
+            continue;
+        }
+
+        entries.push(line.parse::<Entry>()?);
+    }
+
+    Ok(entries.into_iter())
+}
+
+///
+/// Same as parse, but parses the `/proc/self/cgroup` as default.
+///
+/// ```no_run
+/// let entries = unixism::cgroup::parse_default().unwrap();
+/// ```
+pub fn parse_default() -> Result<impl Iterator<Item = Entry>, ParseCgroupError> {
+    parse(fs::File::open("/proc/self/cgroup")?)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Cursor;
+
+    use super::*;
+    use crate::tempdir::TempDir;
+
+    #[test]
+    fn it_parse() {
+        let entries = parse(Cursor::new(
+            r#"
+12:cpu,cpuacct:/docker/abc
+1:name=systemd:/docker/abc
+0::/system.slice/docker-abc.scope
+        "#,
+        ))
+        .unwrap()
+        .collect::<Vec<_>>();
+
+        assert_eq!(3, entries.len());
+        assert_eq!(vec!["cpu", "cpuacct"], entries[0].controllers);
+        assert_eq!(Some("/system.slice/docker-abc.scope"), unified(&entries));
+        assert_eq!(None, unified(&entries[..2]));
+
+        assert!(parse(Cursor::new("0:/")).is_err());
+        assert!(parse(Cursor::new("x::/")).is_err());
+    }
+
+    #[test]
+    fn it_locate() {
+        let entries = parse(Cursor::new(
+            "1:name=systemd:/user.slice\n0::/user.slice/session-1.scope\n",
+        ))
+        .unwrap()
+        .collect::<Vec<_>>();
+
+        let hybrid = mounts::parse_mountinfo(Cursor::new(
+            r#"
+25 1 0:23 / /sys/fs/cgroup ro,nosuid,nodev,noexec shared:9 - tmpfs tmpfs ro,mode=755
+26 25 0:24 / /sys/fs/cgroup/unified rw,nosuid,nodev,noexec,relatime shared:10 - cgroup2 cgroup2 rw,nsdelegate
+27 25 0:25 / /sys/fs/cgroup/systemd rw,nosuid,nodev,noexec,relatime shared:11 - cgroup cgroup rw,name=systemd
+        "#,
+        ))
+        .unwrap()
+        .collect::<Vec<_>>();
+
+        assert_eq!(
+            Path::new("/sys/fs/cgroup/unified/user.slice/session-1.scope"),
+            Cgroup::locate(&entries, &hybrid).unwrap().path()
+        );
+
+        let container = mounts::parse_mountinfo(Cursor::new(
+            "30 20 0:26 /user.slice /sys/fs/cgroup rw,nosuid,nodev,noexec,relatime - cgroup2 cgroup2 rw\n",
+        ))
+        .unwrap()
+        .collect::<Vec<_>>();
+
+        assert_eq!(
+            Path::new("/sys/fs/cgroup/session-1.scope"),
+            Cgroup::locate(&entries, &container).unwrap().path()
+        );
+
+        assert!(matches!(
+            Cgroup::locate(&entries[..1], &hybrid),
+            Err(ParseCgroupError::NoUnifiedHierarchy)
+        ));
+        assert!(matches!(
+            Cgroup::locate(&entries, &hybrid[2..]),
+            Err(ParseCgroupError::NoUnifiedHierarchy)
+        ));
+    }
+
+    #[test]
+    fn it_read() {
+        let root = TempDir::new("cgroup");
+        let dir = root.join("system.slice/app.service");
+        fs::create_dir_all(&dir).unwrap();
+
+        fs::write(dir.join("cpu.max"), "150000 100000\n").unwrap();
+        fs::write(dir.join("memory.max"), "max\n").unwrap();
+        fs::write(dir.join("memory.current"), "52428800\n").unwrap();
+        fs::write(
+            dir.join("cpu.stat"),
+            "usage_usec 1000\nuser_usec 600\nsystem_usec 400\nnr_periods 10\nnr_throttled 2\nthrottled_usec 5000\nnr_bursts 0\n",
+        )
+        .unwrap();
+        fs::write(
+            dir.join("io.stat"),
+            "8:0 rbytes=4096 wbytes=8192 rios=1 wios=2 dbytes=0 dios=0\n259:0 rbytes=1 wbytes=2 rios=3 wios=4\n",
+        )
+        .unwrap();
+        fs::write(dir.join("pids.current"), "7\n").unwrap();
+        fs::write(dir.join("pids.max"), "512\n").unwrap();
+
+        let cgroup = Cgroup::at(&root, "/system.slice/app.service");
+
+        assert_eq!(Some(1.5), cgroup.cpu_max().unwrap().cpus());
+        assert_eq!(None, cgroup.memory_max().unwrap());
+        assert_eq!(52428800, cgroup.memory_current().unwrap());
+
+        let stat = cgroup.cpu_stat().unwrap();
+        assert_eq!(600, stat.user_usec);
+        assert_eq!(Some(2), stat.nr_throttled);
+        assert_eq!(Some(&0), stat.others.get("nr_bursts"));
+
+        let io = cgroup.io_stat().unwrap();
+        assert_eq!(2, io.len());
+        assert_eq!(8192, io[0].wbytes);
+        assert_eq!(259, io[1].major);
+
+        assert_eq!(7, cgroup.pids_current().unwrap());
+        assert_eq!(Some(512), cgroup.pids_max().unwrap());
+
+        assert_eq!(None, "max 100000".parse::<CpuMax>().unwrap().cpus());
+        assert!(Cgroup::at(&root, "/missing").memory_current().is_err());
+    }
+}
@@ -0,0 +1,65 @@
+/// Which entries a compat line refers to.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum CompatTarget {
+    /// A bare `+` or `-`, all entries of the NIS map.
+    All,
+    Name(String),
+    Netgroup(String),
+}
+
+///
+/// A glibc `compat` mode line like `+`, `+user`, `-user` or `+@netgroup`.
+///
+/// Fields following an included target override the ones coming from NIS,
+/// empty fields mean the NIS value is kept.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Compat {
+    Include {
+        target: CompatTarget,
+        overrides: Vec<String>,
+    },
+    Exclude(CompatTarget),
+}
+
+///
+/// Whether `name` can be written as the name of an entry. Names starting with `+` or `-` would be
+/// read back as compat lines, and ones starting with whitespace aren't names glibc matches.
+pub(crate) fn valid_name(name: &str) -> bool {
+    !name.is_empty() && !name.starts_with(['+', '-']) && !name.starts_with(char::is_whitespace)
+}
+
+impl Compat {
+    /// Parses `line` if it is a compat line, i.e. starts with `+` or `-`.
+    pub(crate) fn parse(line: &str) -> Option<Self> {
+        let include = match line.chars().next()? {
+            '+' => true,
+            '-' => false,
+            _ => return None,
+        };
+
+        let mut fields = line[1..].split(':');
+
+        let target = match fields.next().unwrap_or_default() {
+            "" => CompatTarget::All,
+            name => match name.strip_prefix('@') {
+                Some(netgroup) => CompatTarget::Netgroup(netgroup.to_owned()),
+                None => CompatTarget::Name(name.to_owned()),
+            },
+        };
+
+        if include {
+            let overrides = fields.map(String::from).collect::<Vec<_>>();
+
+            Some(Compat::Include {
+                target,
+                overrides: if overrides.iter().all(String::is_empty) {
+                    Vec::new()
+                } else {
+                    overrides
+                },
+            })
+        } else {
+            Some(Compat::Exclude(target))
+        }
+    }
+}
@@ -0,0 +1,196 @@
+use std::{
+    fs,
+    io::{self, BufRead, BufReader},
+    path::Path,
+};
+
+use super::ParseCronError;
+
+/// Who may use the command when neither the allow nor the deny list exists.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Fallback {
+    AllUsers,
+    RootOnly,
+}
+
+///
+/// The allow and deny lists of `crontab(1)` or `at(1)`, `None` when the file doesn't exist.
+/// An existing allow list takes precedence over the deny list, root is always allowed.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Access {
+    pub allow: Option<Vec<String>>,
+    pub deny: Option<Vec<String>>,
+    pub fallback: Fallback,
+}
+
+impl Access {
+    pub fn load<P>(allow: P, deny: P, fallback: Fallback) -> Result<Self, ParseCronError>
+    where
+        P: AsRef<Path>,
+    {
+        Ok(Access {
+            allow: load_list(allow.as_ref())?,
+            deny: load_list(deny.as_ref())?,
+            fallback,
+        })
+    }
+
+    pub fn permits(&self, user: &str) -> bool {
+        if user == "root" {
+            return true;
+        }
+
+        match (&self.allow, &self.deny) {
+            (Some(allow), _) => allow.iter().any(|allowed| allowed == user),
+            (None, Some(deny)) => !deny.iter().any(|denied| denied == user),
+            (None, None) => self.fallback == Fallback::AllUsers,
+        }
+    }
+}
+
+fn load_list(path: &Path) -> Result<Option<Vec<String>>, ParseCronError> {
+    match fs::File::open(path) {
+        Ok(file) => Ok(Some(parse_list(file)?)),
+        Err(error) if error.kind() == io::ErrorKind::NotFound => Ok(None),
+        Err(error) => Err(error.into()),
+    }
+}
+
+///
+/// Parses an allow or deny list, one user name per line.
+///
+/// ```no_run
+/// let users = unixism::cron::parse_list(std::fs::File::open("/etc/cron.deny").unwrap()).unwrap();
+/// ```
+pub fn parse_list<R>(reader: R) -> Result<Vec<String>, ParseCronError>
+where
+    R: io::Read,
+{
+    let mut users = Vec::new();
+
+    for line in BufReader::new(reader).lines() {
+        let line = line?;
+        let line = line.trim();
+
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+
+        users.push(line.to_owned());
+    }
+
+    Ok(users)
+}
+
+///
+/// Reads `/etc/cron.allow` and `/etc/cron.deny`, all users may use cron when neither exists.
+///
+/// ```no_run
+/// let access = unixism::cron::cron_access().unwrap();
+/// ```
+pub fn cron_access() -> Result<Access, ParseCronError> {
+    Access::load("/etc/cron.allow", "/etc/cron.deny", Fallback::AllUsers)
+}
+
+///
+/// Reads `/etc/at.allow` and `/etc/at.deny`, only root may use at when neither exists.
+///
+/// ```no_run
+/// let access = unixism::cron::at_access().unwrap();
+/// ```
+pub fn at_access() -> Result<Access, ParseCronError> {
+    Access::load("/etc/at.allow", "/etc/at.deny", Fallback::RootOnly)
+}
+
+///
+/// Whether the user may install a crontab.
+///
+/// ```no_run
+/// if !unixism::cron::may_use_cron("alice").unwrap() {
+///     eprintln!("alice is not allowed to use crontab");
+/// }
+/// ```
+pub fn may_use_cron(user: &str) -> Result<bool, ParseCronError> {
+    Ok(cron_access()?.permits(user))
+}
+
+///
+/// Whether the user may schedule jobs with at.
+///
+/// ```no_run
+/// println!("{}", unixism::cron::may_use_at("alice").unwrap());
+/// ```
+pub fn may_use_at(user: &str) -> Result<bool, ParseCronError> {
+    Ok(at_access()?.permits(user))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Cursor;
+
+    use super::*;
+    use crate::tempdir::TempDir;
+
+    #[test]
+    fn it_permits() {
+        let users = parse_list(Cursor::new(
+            r#"
+# users allowed to run crontab
+alice
+  bob
+        "#,
+        ))
+        .unwrap();
+        assert_eq!(vec!["alice", "bob"], users);
+
+        let access = Access {
+            allow: Some(users.clone()),
+            deny: Some(vec!["alice".to_owned()]),
+            fallback: Fallback::RootOnly,
+        };
+        assert!(access.permits("alice"));
+        assert!(!access.permits("carol"));
+        assert!(access.permits("root"));
+
+        let access = Access {
+            allow: None,
+            deny: Some(users),
+            fallback: Fallback::RootOnly,
+        };
+        assert!(!access.permits("bob"));
+        assert!(access.permits("carol"));
+
+        // An empty deny list allows everyone, unlike a missing one with the root-only fallback.
+        let access = Access {
+            allow: None,
+            deny: Some(Vec::new()),
+            fallback: Fallback::RootOnly,
+        };
+        assert!(access.permits("carol"));
+
+        let access = Access {
+            allow: None,
+            deny: None,
+            fallback: Fallback::RootOnly,
+        };
+        assert!(!access.permits("carol"));
+        assert!(access.permits("root"));
+    }
+
+    #[test]
+    fn it_load() {
+        let dir = TempDir::new("cron-access");
+        fs::write(dir.join("at.deny"), "daemon\nnobody\n").unwrap();
+
+        let access = Access::load(
+            dir.join("at.allow"),
+            dir.join("at.deny"),
+            Fallback::RootOnly,
+        )
+        .unwrap();
+
+        assert_eq!(None, access.allow);
+        assert!(!access.permits("nobody"));
+        assert!(access.permits("alice"));
+    }
+}
@@ -0,0 +1,335 @@
+use std::{
+    fmt, fs,
+    io::{self, Write},
+    os::unix::fs::PermissionsExt,
+    path::{Path, PathBuf},
+};
+
+use super::{parse_system, CronEntry, Crontab, Line, ParseCronError};
+
+/// A crontab of `/etc/cron.d`, with the file name it came from.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CronFile {
+    pub name: String,
+    pub crontab: Crontab,
+}
+
+/// Whether cron reads the file: hidden files, editor backups and package manager leftovers are skipped.
+fn is_crontab_name(name: &str) -> bool {
+    !name.starts_with('.')
+        && !name.ends_with('~')
+        && !name.contains(".dpkg-")
+        && ![".rpmsave", ".rpmorig", ".rpmnew", ".swp"]
+            .iter()
+            .any(|suffix| name.ends_with(suffix))
+}
+
+/// Whether every cron implementation reads the file, Debian's being the strictest.
+fn is_portable_name(name: &str) -> bool {
+    !name.is_empty()
+        && name
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '-')
+}
+
+///
+/// Parses every crontab in `dir`, sorted by file name.
+///
+/// ```no_run
+/// for file in unixism::cron::parse_dir("/etc/cron.d").unwrap() {
+///     for entry in file.crontab.entries() {
+///         println!("{}: {}", file.name, entry);
+///     }
+/// }
+/// ```
+pub fn parse_dir<P>(dir: P) -> Result<Vec<CronFile>, ParseCronError>
+where
+    P: AsRef<Path>,
+{
+    let mut files = Vec::new();
+
+    for entry in fs::read_dir(dir)? {
+        let entry = entry?;
+        let name = entry.file_name().to_string_lossy().into_owned();
+
+        if !is_crontab_name(&name) || !entry.file_type()?.is_file() {
+            continue;
+        }
+
+        files.push(CronFile {
+            name,
+            crontab: parse_system(fs::File::open(entry.path())?)?,
+        });
+    }
+
+    files.sort_by(|a, b| a.name.cmp(&b.name));
+
+    Ok(files)
+}
+
+///
+/// Same as parse_dir, but parses the `/etc/cron.d` as default.
+///
+/// ```no_run
+/// let files = unixism::cron::parse_dir_default().unwrap();
+/// ```
+pub fn parse_dir_default() -> Result<Vec<CronFile>, ParseCronError> {
+    parse_dir("/etc/cron.d")
+}
+
+///
+/// Edits a cron.d file owned by the application, keeping comments and variables.
+/// Entries are identified by their command. The file is created on save if missing,
+/// always ends with a newline, as cron silently ignores an unterminated last line,
+/// and is replaced via a hidden temporary file, which cron doesn't pick up.
+///
+/// ```no_run
+/// use unixism::cron::{CronEntry, Editor, Schedule};
+///
+/// let mut editor = Editor::open("backup").unwrap();
+///
+/// editor.set_variable("MAILTO", "ops@example.com");
+/// editor.install(CronEntry {
+///     schedule: Schedule::Daily,
+///     user: Some("root".to_owned()),
+///     command: "/usr/local/bin/backup".to_owned(),
+/// });
+/// editor.save().unwrap();
+/// ```
+#[derive(Debug)]
+pub struct Editor {
+    path: PathBuf,
+    crontab: Crontab,
+}
+
+impl Editor {
+    /// Opens `/etc/cron.d/<name>`, the name may only contain letters, digits, `_` and `-`.
+    pub fn open(name: &str) -> Result<Self, ParseCronError> {
+        if !is_portable_name(name) {
+            return Err(ParseCronError::InvalidEntry(name.to_owned()));
+        }
+
+        Self::open_at(Path::new("/etc/cron.d").join(name))
+    }
+
+    pub fn open_at<P>(path: P) -> Result<Self, ParseCronError>
+    where
+        P: AsRef<Path>,
+    {
+        let crontab = match fs::File::open(path.as_ref()) {
+            Ok(file) => parse_system(file)?,
+            Err(error) if error.kind() == io::ErrorKind::NotFound => Crontab::default(),
+            Err(error) => return Err(error.into()),
+        };
+
+        Ok(Self {
+            path: path.as_ref().to_owned(),
+            crontab,
+        })
+    }
+
+    pub fn crontab(&self) -> &Crontab {
+        &self.crontab
+    }
+
+    pub fn get(&self, command: &str) -> Option<&CronEntry> {
+        self.crontab
+            .entries()
+            .find(|entry| entry.command == command)
+    }
+
+    /// Adds the entry, replacing the one with the same command in place.
+    pub fn install(&mut self, entry: CronEntry) {
+        for line in &mut self.crontab.lines {
+            if let Line::Entry(existing) = line {
+                if existing.command == entry.command {
+                    *existing = entry;
+
+                    return;
+                }
+            }
+        }
+
+        self.crontab.lines.push(Line::Entry(entry));
+    }
+
+    /// Applies `f` to the entry with the command, returns false if there is no such entry.
+    pub fn update<F>(&mut self, command: &str, f: F) -> bool
+    where
+        F: FnOnce(&mut CronEntry),
+    {
+        for line in &mut self.crontab.lines {
+            if let Line::Entry(entry) = line {
+                if entry.command == command {
+                    f(entry);
+
+                    return true;
+                }
+            }
+        }
+
+        false
+    }
+
+    pub fn remove(&mut self, command: &str) -> Option<CronEntry> {
+        let position = self
+            .crontab
+            .lines
+            .iter()
+            .position(|line| matches!(line, Line::Entry(entry) if entry.command == command))?;
+
+        match self.crontab.lines.remove(position) {
+            Line::Entry(entry) => Some(entry),
+            _ => None,
+        }
+    }
+
+    /// Sets the variable in place, or inserts it before the first entry so it applies to all of them.
+    pub fn set_variable(&mut self, name: &str, value: &str) {
+        for line in &mut self.crontab.lines {
+            if let Line::Variable {
+                name: key,
+                value: existing,
+            } = line
+            {
+                if key == name {
+                    *existing = value.to_owned();
+
+                    return;
+                }
+            }
+        }
+
+        let position = self
+            .crontab
+            .lines
+            .iter()
+            .position(|line| matches!(line, Line::Entry(_)))
+            .unwrap_or(self.crontab.lines.len());
+
+        self.crontab.lines.insert(
+            position,
+            Line::Variable {
+                name: name.to_owned(),
+                value: value.to_owned(),
+            },
+        );
+    }
+
+    pub fn save(&self) -> Result<(), ParseCronError> {
+        let name = self
+            .path
+            .file_name()
+            .map(|name| name.to_string_lossy().into_owned())
+            .unwrap_or_default();
+        let temp = self.path.with_file_name(format!(".{}.new", name));
+
+        let mut file = fs::File::create(&temp)?;
+        file.write_all(self.to_string().as_bytes())?;
+        // cron refuses files writable by group or others.
+        file.set_permissions(fs::Permissions::from_mode(0o644))?;
+        file.sync_all()?;
+        drop(file);
+
+        Ok(fs::rename(&temp, &self.path)?)
+    }
+}
+
+impl fmt::Display for Editor {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.crontab)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cron::Schedule;
+    use crate::tempdir::TempDir;
+
+    #[test]
+    fn it_parse_dir() {
+        let dir = TempDir::new("crond");
+
+        fs::write(dir.join("certbot"), "0 */12 * * * root certbot -q renew\n").unwrap();
+        fs::write(dir.join("anacron"), "30 7 * * * root anacron -s\n").unwrap();
+        fs::write(dir.join("anacron.dpkg-old"), "30 7 * * * root anacron\n").unwrap();
+        fs::write(dir.join(".placeholder"), "").unwrap();
+        fs::write(dir.join("certbot~"), "garbage").unwrap();
+
+        let files = parse_dir(&dir).unwrap();
+
+        assert_eq!(
+            vec!["anacron", "certbot"],
+            files
+                .iter()
+                .map(|file| file.name.as_str())
+                .collect::<Vec<_>>()
+        );
+        assert_eq!(
+            "certbot -q renew",
+            files[1].crontab.entries().next().unwrap().command
+        );
+    }
+
+    #[test]
+    fn it_edit() {
+        let dir = TempDir::new("cron-editor");
+        let path = dir.join("app");
+
+        let entry = |schedule: &str, command: &str| CronEntry {
+            schedule: Schedule::Fields(Box::new(schedule.parse().unwrap())),
+            user: Some("app".to_owned()),
+            command: command.to_owned(),
+        };
+
+        let mut editor = Editor::open_at(&path).unwrap();
+        editor.install(entry("*/5 * * * *", "/srv/app/bin/queue"));
+        editor.install(entry("0 3 * * *", "/srv/app/bin/cleanup"));
+        editor.set_variable("MAILTO", "");
+        editor.save().unwrap();
+
+        assert_eq!(
+            "MAILTO=\n*/5 * * * * app /srv/app/bin/queue\n0 3 * * * app /srv/app/bin/cleanup\n",
+            fs::read_to_string(&path).unwrap()
+        );
+        assert_eq!(
+            0o644,
+            fs::metadata(&path).unwrap().permissions().mode() & 0o777
+        );
+
+        let mut editor = Editor::open_at(&path).unwrap();
+        editor.install(entry("*/10 * * * *", "/srv/app/bin/queue"));
+        assert!(
+            editor.update("/srv/app/bin/cleanup", |entry| entry.schedule =
+                Schedule::Hourly)
+        );
+        assert!(!editor.update("/bin/missing", |_| {}));
+        editor.set_variable("MAILTO", "ops@example.com");
+        editor.save().unwrap();
+
+        assert_eq!(
+            "MAILTO=ops@example.com\n*/10 * * * * app /srv/app/bin/queue\n@hourly app /srv/app/bin/cleanup\n",
+            fs::read_to_string(&path).unwrap()
+        );
+
+        let mut editor = Editor::open_at(&path).unwrap();
+        assert_eq!(
+            "/srv/app/bin/queue",
+            editor.remove("/srv/app/bin/queue").unwrap().command
+        );
+        assert!(editor.get("/srv/app/bin/queue").is_none());
+        editor.save().unwrap();
+
+        assert_eq!(
+            vec!["app"],
+            fs::read_dir(&dir)
+                .unwrap()
+                .map(|entry| entry.unwrap().file_name().into_string().unwrap())
+                .collect::<Vec<_>>()
+        );
+
+        assert!(Editor::open("app.conf").is_err());
+        assert!(Editor::open("../passwd").is_err());
+    }
+}
@@ -0,0 +1,237 @@
+use std::fmt;
+
+use super::{
+    split_field, time::days_in_month, variable, CronEntry, Field, Fields, MONTHS, WEEKDAYS,
+};
+
+/// A problem found in a crontab, lines are numbered from 1.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Warning {
+    InvalidEntry { line: usize },
+    InvalidField { line: usize, field: String },
+    ImpossibleDate { line: usize },
+    MissingUser { line: usize },
+    UnescapedPercent { line: usize },
+    MissingNewline,
+}
+
+impl fmt::Display for Warning {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::InvalidEntry { line } => write!(f, "line {}: not a crontab entry", line),
+            Self::InvalidField { line, field } => {
+                write!(f, "line {}: invalid or out of range field {}", line, field)
+            }
+            Self::ImpossibleDate { line } => {
+                write!(
+                    f,
+                    "line {}: the day of month never occurs in the months",
+                    line
+                )
+            }
+            Self::MissingUser { line } => {
+                write!(f, "line {}: the user column is missing", line)
+            }
+            Self::UnescapedPercent { line } => {
+                write!(
+                    f,
+                    "line {}: unescaped % in the command is turned into a newline",
+                    line
+                )
+            }
+            Self::MissingNewline => {
+                write!(f, "the last line has no newline and is ignored by cron")
+            }
+        }
+    }
+}
+
+///
+/// Checks the crontab text for fields out of range, day of month and month combinations that
+/// never occur, a missing user column in system crontabs, unescaped `%` in commands
+/// and a missing final newline.
+///
+/// ```no_run
+/// let content = std::fs::read_to_string("/etc/crontab").unwrap();
+///
+/// for warning in unixism::cron::lint(&content, true) {
+///     println!("{warning}");
+/// }
+/// ```
+pub fn lint(content: &str, system: bool) -> Vec<Warning> {
+    let mut warnings = Vec::new();
+
+    for (i, line) in content.lines().enumerate() {
+        let number = i + 1;
+        let line = line.trim();
+
+        if line.is_empty() || line.starts_with('#') || variable(line).is_some() {
+            continue;
+        }
+
+        let rest = if line.starts_with('@') {
+            if CronEntry::parse(line, false).is_err() {
+                warnings.push(Warning::InvalidEntry { line: number });
+                continue;
+            }
+
+            split_field(line).map(|(_, rest)| rest).unwrap_or_default()
+        } else {
+            match lint_fields(line, number, &mut warnings) {
+                Some(rest) => rest,
+                None => continue,
+            }
+        };
+
+        let command = if system {
+            match split_field(rest) {
+                Some((user, command)) if is_user_name(user) && !command.is_empty() => command,
+                _ => {
+                    warnings.push(Warning::MissingUser { line: number });
+                    continue;
+                }
+            }
+        } else {
+            rest
+        };
+
+        if has_unescaped_percent(command) {
+            warnings.push(Warning::UnescapedPercent { line: number });
+        }
+    }
+
+    if !content.is_empty() && !content.ends_with('\n') {
+        warnings.push(Warning::MissingNewline);
+    }
+
+    warnings
+}
+
+/// Checks the five time fields, returning the rest of the line if they are valid.
+fn lint_fields<'a>(line: &'a str, number: usize, warnings: &mut Vec<Warning>) -> Option<&'a str> {
+    let mut rest = line;
+    let mut fields = Vec::with_capacity(5);
+
+    for _ in 0..5 {
+        let Some((field, tail)) = split_field(rest) else {
+            warnings.push(Warning::InvalidEntry { line: number });
+            return None;
+        };
+
+        fields.push(field);
+        rest = tail;
+    }
+
+    let limits: [(u32, u32, &[&str]); 5] = [
+        (0, 59, &[]),
+        (0, 23, &[]),
+        (1, 31, &[]),
+        (1, 12, &MONTHS),
+        (0, 7, &WEEKDAYS),
+    ];
+
+    let mut valid = true;
+    for (field, (min, max, names)) in fields.iter().zip(limits) {
+        if Field::parse(field, min, max, names).is_err() {
+            warnings.push(Warning::InvalidField {
+                line: number,
+                field: (*field).to_owned(),
+            });
+            valid = false;
+        }
+    }
+
+    if !valid {
+        return None;
+    }
+
+    if let Ok(fields) = fields.join(" ").parse::<Fields>() {
+        if never_occurs(&fields) {
+            warnings.push(Warning::ImpossibleDate { line: number });
+        }
+    }
+
+    Some(rest)
+}
+
+/// Whether the day of month never falls in the months, which matters only when it can't be
+/// satisfied by the day of week instead.
+fn never_occurs(fields: &Fields) -> bool {
+    if !fields.day_of_month.any && !fields.day_of_week.any {
+        return false;
+    }
+
+    // A leap year, so February 29 counts as possible.
+    !(1..=12)
+        .filter(|month| fields.month.contains(*month))
+        .any(|month| (1..=days_in_month(2024, month)).any(|day| fields.day_of_month.contains(day)))
+}
+
+fn is_user_name(user: &str) -> bool {
+    !user.starts_with('-')
+        && user
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || matches!(c, '_' | '-' | '.' | '$'))
+}
+
+fn has_unescaped_percent(command: &str) -> bool {
+    let mut escaped = false;
+
+    for c in command.chars() {
+        match c {
+            '%' if !escaped => return true,
+            '\\' => escaped = !escaped,
+            _ => escaped = false,
+        }
+    }
+
+    false
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn it_lint() {
+        let content = r#"
+SHELL=/bin/sh
+0 3 * * * root /usr/local/bin/backup
+61 * * * * root /bin/true
+0 0 30 feb * root /bin/never
+0 0 30 feb mon root /bin/mondays
+0 0 31 4,6 * root /bin/never
+0 0 29 2 * root /bin/leap
+*/5 * * * * /usr/bin/php /srv/app/artisan schedule:run
+0 0 * * * root tar czf /backup/$(date +%Y-%m-%d).tgz /srv
+0 0 * * * root tar czf /backup/$(date +\%Y-\%m-\%d).tgz /srv
+@fortnightly root /bin/true
+0 0 * *
+@daily root /bin/last"#;
+
+        assert_eq!(
+            vec![
+                Warning::InvalidField {
+                    line: 4,
+                    field: "61".to_owned()
+                },
+                Warning::ImpossibleDate { line: 5 },
+                Warning::ImpossibleDate { line: 7 },
+                Warning::MissingUser { line: 9 },
+                Warning::UnescapedPercent { line: 10 },
+                Warning::InvalidEntry { line: 12 },
+                Warning::InvalidEntry { line: 13 },
+                Warning::MissingNewline,
+            ],
+            lint(content, true)
+        );
+
+        assert_eq!(
+            Vec::<Warning>::new(),
+            lint(
+                "*/5 * * * * /usr/bin/php /srv/app/artisan schedule:run\n",
+                false
+            )
+        );
+    }
+}
@@ -0,0 +1,593 @@
+use std::{
+    error, fmt, fs,
+    io::{self, BufRead, BufReader},
+    str::FromStr,
+};
+
+mod access;
+mod crond;
+mod lint;
+pub mod systemd;
+mod time;
+
+pub use access::{at_access, cron_access, may_use_at, may_use_cron, parse_list, Access, Fallback};
+pub use crond::{parse_dir, parse_dir_default, CronFile, Editor};
+pub use lint::{lint, Warning};
+pub use time::{DateTime, Occurrences};
+
+const MONTHS: [&str; 12] = [
+    "jan", "feb", "mar", "apr", "may", "jun", "jul", "aug", "sep", "oct", "nov", "dec",
+];
+
+const WEEKDAYS: [&str; 7] = ["sun", "mon", "tue", "wed", "thu", "fri", "sat"];
+
+/// A `start-end/step` element of a time field, single values have `start == end`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Range {
+    pub start: u32,
+    pub end: u32,
+    pub step: u32,
+}
+
+impl Range {
+    pub fn contains(&self, value: u32) -> bool {
+        self.start <= value && value <= self.end && (value - self.start).is_multiple_of(self.step)
+    }
+}
+
+/// A time field of an entry, a comma separated list of ranges.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Field {
+    pub ranges: Vec<Range>,
+    /// Whether the field starts with `*`, which changes how day of month and day of week combine.
+    pub any: bool,
+    text: String,
+}
+
+impl Field {
+    fn parse(s: &str, min: u32, max: u32, names: &[&str]) -> Result<Self, ParseCronError> {
+        let invalid = || ParseCronError::InvalidField(s.to_owned());
+
+        let value = |s: &str| -> Result<u32, ParseCronError> {
+            let lower = s.to_ascii_lowercase();
+
+            let value = match names.iter().position(|name| *name == lower) {
+                // Month names start at 1, weekday names at 0.
+                Some(i) => i as u32 + min,
+                None => s.parse().map_err(|_| invalid())?,
+            };
+
+            if value < min || value > max {
+                return Err(invalid());
+            }
+
+            Ok(value)
+        };
+
+        let mut ranges = Vec::new();
+
+        for item in s.split(',') {
+            let (range, step) = match item.split_once('/') {
+                Some((range, step)) => (range, Some(step.parse::<u32>().map_err(|_| invalid())?)),
+                None => (item, None),
+            };
+
+            if step == Some(0) {
+                return Err(invalid());
+            }
+
+            let (start, end) = match range {
+                "*" => (min, max),
+                range => match range.split_once('-') {
+                    Some((start, end)) => (value(start)?, value(end)?),
+                    // `N/step` runs from N to the end of the field.
+                    None if step.is_some() => (value(range)?, max),
+                    None => (value(range)?, value(range)?),
+                },
+            };
+
+            if start > end {
+                return Err(invalid());
+            }
+
+            ranges.push(Range {
+                start,
+                end,
+                step: step.unwrap_or(1),
+            });
+        }
+
+        Ok(Field {
+            ranges,
+            any: s.starts_with('*'),
+            text: s.to_owned(),
+        })
+    }
+
+    pub fn contains(&self, value: u32) -> bool {
+        self.ranges.iter().any(|range| range.contains(value))
+    }
+}
+
+impl fmt::Display for Field {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.text)
+    }
+}
+
+/// The five time fields of an entry.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Fields {
+    pub minute: Field,
+    pub hour: Field,
+    pub day_of_month: Field,
+    pub month: Field,
+    /// Day of week from 0 to 7, both 0 and 7 being Sunday.
+    pub day_of_week: Field,
+}
+
+impl FromStr for Fields {
+    type Err = ParseCronError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let fields = s.split_whitespace().collect::<Vec<_>>();
+
+        let [minute, hour, day_of_month, month, day_of_week] = fields[..] else {
+            return Err(ParseCronError::InvalidEntry(s.to_owned()));
+        };
+
+        Ok(Fields {
+            minute: Field::parse(minute, 0, 59, &[])?,
+            hour: Field::parse(hour, 0, 23, &[])?,
+            day_of_month: Field::parse(day_of_month, 1, 31, &[])?,
+            month: Field::parse(month, 1, 12, &MONTHS)?,
+            day_of_week: Field::parse(day_of_week, 0, 7, &WEEKDAYS)?,
+        })
+    }
+}
+
+impl fmt::Display for Fields {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} {} {} {} {}",
+            self.minute, self.hour, self.day_of_month, self.month, self.day_of_week
+        )
+    }
+}
+
+/// When an entry runs, either time fields or one of the `@` shortcuts.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Schedule {
+    Reboot,
+    Yearly,
+    Monthly,
+    Weekly,
+    Daily,
+    Hourly,
+    Fields(Box<Fields>),
+}
+
+impl Schedule {
+    /// Returns the time fields the schedule stands for, `None` for `@reboot`.
+    pub fn fields(&self) -> Option<Fields> {
+        let fields = match self {
+            Self::Reboot => return None,
+            Self::Yearly => "0 0 1 1 *",
+            Self::Monthly => "0 0 1 * *",
+            Self::Weekly => "0 0 * * 0",
+            Self::Daily => "0 0 * * *",
+            Self::Hourly => "0 * * * *",
+            Self::Fields(fields) => return Some(fields.as_ref().clone()),
+        };
+
+        fields.parse().ok()
+    }
+}
+
+impl fmt::Display for Schedule {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Reboot => write!(f, "@reboot"),
+            Self::Yearly => write!(f, "@yearly"),
+            Self::Monthly => write!(f, "@monthly"),
+            Self::Weekly => write!(f, "@weekly"),
+            Self::Daily => write!(f, "@daily"),
+            Self::Hourly => write!(f, "@hourly"),
+            Self::Fields(fields) => write!(f, "{}", fields),
+        }
+    }
+}
+
+/// A job line of a crontab.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CronEntry {
+    pub schedule: Schedule,
+    /// The user to run as, only present in `/etc/crontab` and `/etc/cron.d`.
+    pub user: Option<String>,
+    pub command: String,
+}
+
+impl CronEntry {
+    fn parse(s: &str, system: bool) -> Result<Self, ParseCronError> {
+        let invalid = || ParseCronError::InvalidEntry(s.to_owned());
+
+        let (schedule, rest) = match s.strip_prefix('@') {
+            Some(rest) => {
+                let (name, rest) = split_field(rest).ok_or_else(invalid)?;
+
+                let schedule = match name {
+                    "reboot" => Schedule::Reboot,
+                    "yearly" | "annually" => Schedule::Yearly,
+                    "monthly" => Schedule::Monthly,
+                    "weekly" => Schedule::Weekly,
+                    "daily" | "midnight" => Schedule::Daily,
+                    "hourly" => Schedule::Hourly,
+                    _ => return Err(invalid()),
+                };
+
+                (schedule, rest)
+            }
+            None => {
+                let mut rest = s;
+                let mut fields = Vec::with_capacity(5);
+
+                for _ in 0..5 {
+                    let (field, tail) = split_field(rest).ok_or_else(invalid)?;
+                    fields.push(field);
+                    rest = tail;
+                }
+
+                (Schedule::Fields(Box::new(fields.join(" ").parse()?)), rest)
+            }
+        };
+
+        let (user, command) = if system {
+            let (user, command) = split_field(rest).ok_or_else(invalid)?;
+
+            (Some(user.to_owned()), command)
+        } else {
+            (None, rest)
+        };
+
+        if command.is_empty() {
+            return Err(invalid());
+        }
+
+        Ok(CronEntry {
+            schedule,
+            user,
+            command: command.to_owned(),
+        })
+    }
+}
+
+impl fmt::Display for CronEntry {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.schedule)?;
+
+        if let Some(user) = &self.user {
+            write!(f, " {}", user)?;
+        }
+
+        write!(f, " {}", self.command)
+    }
+}
+
+/// Splits the leading whitespace separated field off the line.
+fn split_field(s: &str) -> Option<(&str, &str)> {
+    let s = s.trim_start();
+    let end = s.find(char::is_whitespace)?;
+
+    Some((&s[..end], s[end..].trim_start()))
+}
+
+/// A line of a crontab.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Line {
+    Entry(CronEntry),
+    /// An environment assignment like `MAILTO=root`, applying to the entries after it.
+    Variable {
+        name: String,
+        value: String,
+    },
+    /// A comment or blank line, kept verbatim.
+    Other(String),
+}
+
+impl fmt::Display for Line {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Entry(entry) => write!(f, "{}", entry),
+            Self::Variable { name, value } => write!(f, "{}={}", name, value),
+            Self::Other(line) => write!(f, "{}", line),
+        }
+    }
+}
+
+/// A parsed crontab, keeping all lines in order.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct Crontab {
+    pub lines: Vec<Line>,
+}
+
+impl Crontab {
+    pub fn entries(&self) -> impl Iterator<Item = &CronEntry> {
+        self.lines.iter().filter_map(|line| match line {
+            Line::Entry(entry) => Some(entry),
+            _ => None,
+        })
+    }
+
+    /// Returns the value of the environment variable as last assigned in the file.
+    pub fn variable(&self, name: &str) -> Option<&str> {
+        self.lines.iter().rev().find_map(|line| match line {
+            Line::Variable { name: key, value } if key == name => Some(value.as_str()),
+            _ => None,
+        })
+    }
+
+    /// Returns the environment the entry runs with, the assignments above it in order.
+    pub fn environment(&self, entry: &CronEntry) -> Vec<(&str, &str)> {
+        let mut environment = Vec::new();
+
+        for line in &self.lines {
+            match line {
+                Line::Entry(candidate) if std::ptr::eq(candidate, entry) => break,
+                Line::Variable { name, value } => {
+                    environment.retain(|(key, _)| key != name);
+                    environment.push((name.as_str(), value.as_str()));
+                }
+                _ => {}
+            }
+        }
+
+        environment
+    }
+}
+
+impl fmt::Display for Crontab {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for line in &self.lines {
+            writeln!(f, "{}", line)?;
+        }
+
+        Ok(())
+    }
+}
+
+#[derive(Debug)]
+pub enum ParseCronError {
+    InvalidEntry(String),
+    InvalidField(String),
+    InvalidCalendar(String),
+    IOError(io::Error),
+}
+
+impl error::Error for ParseCronError {}
+
+impl fmt::Display for ParseCronError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::InvalidEntry(entry) => write!(f, "invalid crontab entry: {}", entry),
+            Self::InvalidField(field) => write!(f, "invalid time field: {}", field),
+            Self::InvalidCalendar(spec) => write!(f, "invalid calendar expression: {}", spec),
+            Self::IOError(error) => write!(f, "{}", error),
+        }
+    }
+}
+
+impl From<io::Error> for ParseCronError {
+    fn from(value: io::Error) -> Self {
+        ParseCronError::IOError(value)
+    }
+}
+
+/// Parses `NAME=value`, the way cron tells assignments from entries.
+fn variable(line: &str) -> Option<(String, String)> {
+    if line.starts_with(|c: char| c.is_ascii_digit() || c == '*' || c == '@') {
+        return None;
+    }
+
+    let (name, value) = line.split_once('=')?;
+    let name = name.trim();
+
+    if name.is_empty() || name.contains(char::is_whitespace) {
+        return None;
+    }
+
+    let value = value.trim();
+    let value = ['"', '\'']
+        .iter()
+        .find_map(|quote| value.strip_prefix(*quote)?.strip_suffix(*quote))
+        .unwrap_or(value);
+
+    Some((name.to_owned(), value.to_owned()))
+}
+
+fn parse_lines<R>(reader: R, system: bool) -> Result<Crontab, ParseCronError>
+where
+    R: io::Read,
+{
+    let mut crontab = Crontab::default();
+
+    for line in BufReader::new(reader).lines() {
+        let line = line?;
+        let trimmed = line.trim();
+
+        if trimmed.is_empty() || trimmed.starts_with('#') {
+            crontab.lines.push(Line::Other(line));
+            continue;
+        }
+
+        crontab.lines.push(match variable(trimmed) {
+            Some((name, value)) => Line::Variable { name, value },
+            None => Line::Entry(CronEntry::parse(trimmed, system)?),
+        });
+    }
+
+    Ok(crontab)
+}
+
+///
+/// Parses a user crontab, the format without a user column edited by `crontab -e`.
+///
+/// ```no_run
+/// let crontab = unixism::cron::parse(std::fs::File::open("/var/spool/cron/crontabs/alice").unwrap()).unwrap();
+///
+/// for entry in crontab.entries() {
+///     println!("{} {}", entry.schedule, entry.command);
+/// }
+/// ```
+pub fn parse<R>(reader: R) -> Result<Crontab, ParseCronError>
+where
+    R: io::Read,
+{
+    parse_lines(reader, false)
+}
+
+///
+/// Parses a system crontab like `/etc/crontab`, where the user to run as follows the time fields.
+///
+/// ```no_run
+/// let crontab = unixism::cron::parse_system(std::fs::File::open("/etc/cron.d/certbot").unwrap()).unwrap();
+///
+/// println!("MAILTO={:?}", crontab.variable("MAILTO"));
+/// ```
+pub fn parse_system<R>(reader: R) -> Result<Crontab, ParseCronError>
+where
+    R: io::Read,
+{
+    parse_lines(reader, true)
+}
+
+///
+/// Same as parse_system, but parses the `/etc/crontab` as default.
+///
+/// ```no_run
+/// let crontab = unixism::cron::parse_default().unwrap();
+/// ```
+pub fn parse_default() -> Result<Crontab, ParseCronError> {
+    parse_system(fs::File::open("/etc/crontab")?)
+}
+
+///
+/// Parses the crontab of the user from `/var/spool/cron/crontabs` (Debian) or `/var/spool/cron` (Red Hat).
+///
+/// ```no_run
+/// let crontab = unixism::cron::parse_user("alice").unwrap();
+/// ```
+pub fn parse_user(user: &str) -> Result<Crontab, ParseCronError> {
+    match fs::File::open(format!("/var/spool/cron/crontabs/{}", user)) {
+        Ok(file) => parse(file),
+        Err(error) if error.kind() == io::ErrorKind::NotFound => {
+            parse(fs::File::open(format!("/var/spool/cron/{}", user))?)
+        }
+        Err(error) => Err(error.into()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Cursor;
+
+    use super::*;
+
+    #[test]
+    fn it_parse_system() {
+        let crontab = parse_system(Cursor::new(
+            r#"
+# /etc/crontab: system-wide crontab
+SHELL=/bin/sh
+PATH = "/usr/local/sbin:/usr/local/bin:/sbin:/bin:/usr/sbin:/usr/bin"
+
+17 *	* * *	root    cd / && run-parts --report /etc/cron.hourly
+25 6	* * *	root	test -x /usr/sbin/anacron || ( cd / && run-parts --report /etc/cron.daily )
+*/15 9-17 * jan-mar,dec mon-fri www-data /usr/bin/php /srv/app/artisan schedule:run
+MAILTO=ops@example.com
+@reboot root /usr/local/bin/warmup
+        "#,
+        ))
+        .unwrap();
+
+        let entries = crontab.entries().collect::<Vec<_>>();
+        assert_eq!(4, entries.len());
+
+        assert_eq!(Some("root"), entries[0].user.as_deref());
+        assert_eq!(
+            "cd / && run-parts --report /etc/cron.hourly",
+            entries[0].command
+        );
+
+        let Schedule::Fields(fields) = &entries[2].schedule else {
+            panic!("expected time fields");
+        };
+        assert_eq!(
+            vec![Range {
+                start: 0,
+                end: 59,
+                step: 15
+            }],
+            fields.minute.ranges
+        );
+        assert!(fields.minute.any);
+        assert!(fields.month.contains(2) && fields.month.contains(12) && !fields.month.contains(4));
+        assert!(fields.day_of_week.contains(1) && !fields.day_of_week.contains(0));
+        assert!(!fields.day_of_week.any);
+        assert_eq!("*/15 9-17 * jan-mar,dec mon-fri", fields.to_string());
+
+        assert_eq!(Schedule::Reboot, entries[3].schedule);
+        assert_eq!(
+            "/usr/local/sbin:/usr/local/bin:/sbin:/bin:/usr/sbin:/usr/bin",
+            crontab.variable("PATH").unwrap()
+        );
+        assert_eq!(
+            vec![
+                ("SHELL", "/bin/sh"),
+                (
+                    "PATH",
+                    "/usr/local/sbin:/usr/local/bin:/sbin:/bin:/usr/sbin:/usr/bin"
+                ),
+                ("MAILTO", "ops@example.com"),
+            ],
+            crontab.environment(entries[3])
+        );
+        assert_eq!(2, crontab.environment(entries[0]).len());
+
+        assert_eq!("@reboot root /usr/local/bin/warmup", entries[3].to_string());
+    }
+
+    #[test]
+    fn it_parse() {
+        let crontab = parse(Cursor::new(
+            r#"
+@daily /home/alice/bin/backup
+0 4 1,15 * 7 /home/alice/bin/report --format=pdf 2>&1
+        "#,
+        ))
+        .unwrap();
+
+        let entries = crontab.entries().collect::<Vec<_>>();
+        assert_eq!(Schedule::Daily, entries[0].schedule);
+        assert_eq!(None, entries[0].user);
+        assert_eq!(
+            "0 0 * * *",
+            entries[0].schedule.fields().unwrap().to_string()
+        );
+        assert_eq!(
+            "/home/alice/bin/report --format=pdf 2>&1",
+            entries[1].command
+        );
+
+        for invalid in [
+            "60 * * * * cmd",
+            "* * * * *",
+            "*/0 * * * * cmd",
+            "5-1 * * * * cmd",
+            "* * * foo * cmd",
+            "@fortnightly cmd",
+        ] {
+            assert!(parse(Cursor::new(invalid)).is_err(), "{invalid}");
+        }
+    }
+}
@@ -0,0 +1,626 @@
+use std::{fmt, str::FromStr};
+
+use super::{
+    time::{days_in_month, DateTime},
+    CronEntry, Field, ParseCronError, Range,
+};
+
+/// Weekday names in systemd order, Monday first, with their cron numbers.
+const WEEKDAYS: [(&str, &str, u32); 7] = [
+    ("mon", "monday", 1),
+    ("tue", "tuesday", 2),
+    ("wed", "wednesday", 3),
+    ("thu", "thursday", 4),
+    ("fri", "friday", 5),
+    ("sat", "saturday", 6),
+    ("sun", "sunday", 0),
+];
+
+/// How far ahead occurrences are searched.
+const SEARCH_YEARS: i32 = 400;
+
+/// A date or time component of a calendar expression, no ranges meaning `*`.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct Component {
+    pub ranges: Vec<Range>,
+}
+
+impl Component {
+    fn parse(s: &str, min: u32, max: u32) -> Result<Self, ParseCronError> {
+        let invalid = || ParseCronError::InvalidCalendar(s.to_owned());
+        let value = |s: &str| -> Result<u32, ParseCronError> {
+            s.parse::<u32>()
+                .ok()
+                .filter(|value| (min..=max).contains(value))
+                .ok_or_else(invalid)
+        };
+
+        if s == "*" {
+            return Ok(Component::default());
+        }
+
+        let mut ranges = Vec::new();
+
+        for item in s.split(',') {
+            let (range, step) = match item.split_once('/') {
+                Some((range, step)) => (range, Some(step.parse::<u32>().map_err(|_| invalid())?)),
+                None => (item, None),
+            };
+
+            if step == Some(0) {
+                return Err(invalid());
+            }
+
+            let (start, end) = match range.split_once("..") {
+                Some((start, end)) => (value(start)?, value(end)?),
+                None if range == "*" => (min, max),
+                None if step.is_some() => (value(range)?, max),
+                None => (value(range)?, value(range)?),
+            };
+
+            if start > end {
+                return Err(invalid());
+            }
+
+            ranges.push(Range {
+                start,
+                end,
+                step: step.unwrap_or(1),
+            });
+        }
+
+        Ok(Component { ranges })
+    }
+
+    pub fn contains(&self, value: u32) -> bool {
+        self.ranges.is_empty() || self.ranges.iter().any(|range| range.contains(value))
+    }
+
+    fn write(&self, f: &mut fmt::Formatter<'_>, width: usize, max: u32) -> fmt::Result {
+        if self.ranges.is_empty() {
+            return write!(f, "*");
+        }
+
+        for (i, range) in self.ranges.iter().enumerate() {
+            if i > 0 {
+                write!(f, ",")?;
+            }
+
+            write!(f, "{:0width$}", range.start)?;
+
+            // `start/step` repeats up to the maximum, so it only fits when the range ends at its last step.
+            let last = range.start + (range.end - range.start) / range.step * range.step;
+
+            if range.step > 1 && last + range.step > max {
+                write!(f, "/{}", range.step)?;
+            } else {
+                if range.end != range.start {
+                    write!(f, "..{:0width$}", range.end)?;
+                }
+                if range.step > 1 {
+                    write!(f, "/{}", range.step)?;
+                }
+            }
+        }
+
+        Ok(())
+    }
+}
+
+///
+/// A systemd calendar expression as used by `OnCalendar=`, see `systemd.time(7)`.
+/// Fractional seconds aren't supported and the timezone is kept but not applied.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CalendarSpec {
+    /// Weekdays numbered as in cron, Sunday being 0, empty meaning any day.
+    pub weekdays: Vec<u32>,
+    pub year: Component,
+    pub month: Component,
+    pub day: Component,
+    /// Whether the day counts back from the end of the month, written as `~`, 1 being the last day.
+    pub day_from_end: bool,
+    pub hour: Component,
+    pub minute: Component,
+    pub second: Component,
+    pub timezone: Option<String>,
+}
+
+impl CalendarSpec {
+    pub fn matches(&self, time: &DateTime) -> bool {
+        self.matches_day(time)
+            && self.hour.contains(time.hour)
+            && self.minute.contains(time.minute)
+            && self.second.contains(time.second)
+    }
+
+    fn matches_day(&self, time: &DateTime) -> bool {
+        let day = if self.day_from_end {
+            days_in_month(time.year, time.month) - time.day + 1
+        } else {
+            time.day
+        };
+
+        self.year.contains(time.year as u32)
+            && self.month.contains(time.month)
+            && self.day.contains(day)
+            && (self.weekdays.is_empty() || self.weekdays.contains(&time.weekday()))
+    }
+
+    ///
+    /// Returns the first time strictly after `time` the expression elapses, like `systemd-analyze calendar`.
+    ///
+    /// ```
+    /// use unixism::cron::{systemd::CalendarSpec, DateTime};
+    ///
+    /// let spec = "Mon..Fri *-*-* 09:00".parse::<CalendarSpec>().unwrap();
+    /// let next = spec.next_after(&"2024-09-06 12:00".parse::<DateTime>().unwrap());
+    ///
+    /// assert_eq!(Some("2024-09-09 09:00".parse::<DateTime>().unwrap()), next);
+    /// ```
+    pub fn next_after(&self, time: &DateTime) -> Option<DateTime> {
+        let mut time = time.add_seconds(1);
+        let limit = time.year + SEARCH_YEARS;
+
+        while time.year <= limit {
+            if !self.year.contains(time.year as u32) {
+                time = DateTime::new(time.year + 1, 1, 1, 0, 0, 0)?;
+            } else if !self.month.contains(time.month) {
+                let (year, month) = match time.month {
+                    12 => (time.year + 1, 1),
+                    month => (time.year, month + 1),
+                };
+                time = DateTime::new(year, month, 1, 0, 0, 0)?;
+            } else if !self.matches_day(&time) {
+                time = DateTime::new(time.year, time.month, time.day, 0, 0, 0)?.add_seconds(86400);
+            } else if !self.hour.contains(time.hour) {
+                time = DateTime::new(time.year, time.month, time.day, time.hour, 0, 0)?
+                    .add_seconds(3600);
+            } else if !self.minute.contains(time.minute) {
+                time = DateTime::new(time.year, time.month, time.day, time.hour, time.minute, 0)?
+                    .add_seconds(60);
+            } else if !self.second.contains(time.second) {
+                time = time.add_seconds(1);
+            } else {
+                return Some(time);
+            }
+        }
+
+        None
+    }
+}
+
+fn shorthand(s: &str) -> Option<&'static str> {
+    Some(match s {
+        "minutely" => "*-*-* *:*:00",
+        "hourly" => "*-*-* *:00:00",
+        "daily" => "*-*-* 00:00:00",
+        "weekly" => "Mon *-*-* 00:00:00",
+        "monthly" => "*-*-01 00:00:00",
+        "yearly" | "annually" => "*-01-01 00:00:00",
+        "quarterly" => "*-01,04,07,10-01 00:00:00",
+        "semiannually" => "*-01,07-01 00:00:00",
+        _ => return None,
+    })
+}
+
+fn weekday(s: &str) -> Option<usize> {
+    let s = s.to_ascii_lowercase();
+
+    WEEKDAYS
+        .iter()
+        .position(|(short, long, _)| *short == s || *long == s)
+}
+
+fn parse_weekdays(s: &str) -> Result<Vec<u32>, ParseCronError> {
+    let invalid = || ParseCronError::InvalidCalendar(s.to_owned());
+    let mut weekdays = Vec::new();
+
+    for item in s.split(',') {
+        let (start, end) = match item.split_once("..") {
+            Some((start, end)) => (weekday(start), weekday(end)),
+            None => (weekday(item), weekday(item)),
+        };
+
+        let (Some(start), Some(end)) = (start, end) else {
+            return Err(invalid());
+        };
+
+        if start > end {
+            return Err(invalid());
+        }
+
+        weekdays.extend(WEEKDAYS[start..=end].iter().map(|(_, _, number)| *number));
+    }
+
+    weekdays.sort_unstable();
+    weekdays.dedup();
+
+    Ok(weekdays)
+}
+
+impl FromStr for CalendarSpec {
+    type Err = ParseCronError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let invalid = || ParseCronError::InvalidCalendar(s.to_owned());
+
+        let s = s.trim();
+        if let Some(expanded) = shorthand(&s.to_ascii_lowercase()) {
+            return expanded.parse();
+        }
+
+        let mut tokens = s.split_whitespace().peekable();
+
+        let weekdays = match tokens.peek() {
+            Some(token) if token.starts_with(|c: char| c.is_ascii_alphabetic()) => {
+                let weekdays = parse_weekdays(token)?;
+                tokens.next();
+                weekdays
+            }
+            _ => Vec::new(),
+        };
+
+        let date = tokens.next_if(|token| token.contains(['-', '~']) && !token.contains(':'));
+        let time = tokens.next_if(|token| token.contains(':'));
+        let timezone = tokens.next().map(String::from);
+
+        if tokens.next().is_some() || (weekdays.is_empty() && date.is_none() && time.is_none()) {
+            return Err(invalid());
+        }
+
+        let (year, month, day, day_from_end) = match date {
+            None => (
+                Component::default(),
+                Component::default(),
+                Component::default(),
+                false,
+            ),
+            Some(date) => {
+                let (rest, day, day_from_end) = match date.rsplit_once('~') {
+                    Some((rest, day)) => (rest, day, true),
+                    None => {
+                        let (rest, day) = date.rsplit_once('-').ok_or_else(invalid)?;
+                        (rest, day, false)
+                    }
+                };
+
+                let (year, month) = match rest.split_once('-') {
+                    Some((year, month)) => (Some(year), month),
+                    None => (None, rest),
+                };
+
+                let year = match year {
+                    None | Some("*") => Component::default(),
+                    Some(year) if year.len() == 2 => {
+                        let short = Component::parse(year, 0, 99)?;
+
+                        Component {
+                            ranges: short
+                                .ranges
+                                .into_iter()
+                                .map(|range| {
+                                    let century = if range.start < 70 { 2000 } else { 1900 };
+
+                                    Range {
+                                        start: range.start + century,
+                                        end: range.end + century,
+                                        step: range.step,
+                                    }
+                                })
+                                .collect(),
+                        }
+                    }
+                    Some(year) => Component::parse(year, 1970, 2199)?,
+                };
+
+                let day = Component::parse(day, 1, 31)?;
+                if day_from_end && day.ranges.iter().any(|range| range.start != range.end) {
+                    return Err(invalid());
+                }
+
+                (year, Component::parse(month, 1, 12)?, day, day_from_end)
+            }
+        };
+
+        let (hour, minute, second) = match time {
+            None => {
+                let midnight = Component::parse("0", 0, 0)?;
+                (midnight.clone(), midnight.clone(), midnight)
+            }
+            Some(time) => {
+                let parts = time.split(':').collect::<Vec<_>>();
+
+                let (hour, minute, second) = match parts[..] {
+                    [hour, minute] => (hour, minute, "00"),
+                    [hour, minute, second] => (hour, minute, second),
+                    _ => return Err(invalid()),
+                };
+
+                (
+                    Component::parse(hour, 0, 23)?,
+                    Component::parse(minute, 0, 59)?,
+                    Component::parse(second, 0, 59)?,
+                )
+            }
+        };
+
+        Ok(CalendarSpec {
+            weekdays,
+            year,
+            month,
+            day,
+            day_from_end,
+            hour,
+            minute,
+            second,
+            timezone,
+        })
+    }
+}
+
+impl fmt::Display for CalendarSpec {
+    /// Writes the normalized form, as `systemd-analyze calendar` prints it.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if !self.weekdays.is_empty() {
+            let days = WEEKDAYS
+                .iter()
+                .map(|(_, _, number)| self.weekdays.contains(number))
+                .collect::<Vec<_>>();
+
+            let mut runs = Vec::new();
+            let mut i = 0;
+
+            while i < days.len() {
+                if !days[i] {
+                    i += 1;
+                    continue;
+                }
+
+                let start = i;
+                while i + 1 < days.len() && days[i + 1] {
+                    i += 1;
+                }
+
+                let name = |i: usize| {
+                    let short = WEEKDAYS[i].0;
+                    format!("{}{}", short[..1].to_ascii_uppercase(), &short[1..])
+                };
+
+                runs.push(match i - start {
+                    0 => name(start),
+                    1 => format!("{},{}", name(start), name(i)),
+                    _ => format!("{}..{}", name(start), name(i)),
+                });
+                i += 1;
+            }
+
+            write!(f, "{} ", runs.join(","))?;
+        }
+
+        self.year.write(f, 4, 2199)?;
+        write!(f, "-")?;
+        self.month.write(f, 2, 12)?;
+        write!(f, "{}", if self.day_from_end { "~" } else { "-" })?;
+        self.day.write(f, 2, 31)?;
+        write!(f, " ")?;
+        self.hour.write(f, 2, 23)?;
+        write!(f, ":")?;
+        self.minute.write(f, 2, 59)?;
+        write!(f, ":")?;
+        self.second.write(f, 2, 59)?;
+
+        if let Some(timezone) = &self.timezone {
+            write!(f, " {}", timezone)?;
+        }
+
+        Ok(())
+    }
+}
+
+fn component(field: &Field, min: u32, max: u32) -> Component {
+    let any = field
+        .ranges
+        .iter()
+        .any(|range| range.start == min && range.end == max && range.step == 1);
+
+    Component {
+        ranges: if any {
+            Vec::new()
+        } else {
+            field.ranges.clone()
+        },
+    }
+}
+
+///
+/// Converts the cron entry to `OnCalendar=` expressions firing at the same times.
+/// Two expressions are returned when both day fields are restricted, since cron fires when
+/// either matches, and systemd timers accept several `OnCalendar=` lines.
+/// `@reboot` has no calendar equivalent, it converts to nothing and maps to `OnBootSec=` instead.
+///
+/// ```no_run
+/// let crontab = unixism::cron::parse_default().unwrap();
+///
+/// for entry in crontab.entries() {
+///     for spec in unixism::cron::systemd::on_calendar(entry) {
+///         println!("OnCalendar={spec}");
+///     }
+/// }
+/// ```
+pub fn on_calendar(entry: &CronEntry) -> Vec<CalendarSpec> {
+    let Some(fields) = entry.schedule.fields() else {
+        return Vec::new();
+    };
+
+    let mut weekdays = (0..7)
+        .filter(|day| {
+            fields.day_of_week.contains(*day) || (*day == 0 && fields.day_of_week.contains(7))
+        })
+        .collect::<Vec<_>>();
+    if weekdays.len() == 7 {
+        weekdays.clear();
+    }
+
+    let spec = CalendarSpec {
+        weekdays,
+        year: Component::default(),
+        month: component(&fields.month, 1, 12),
+        day: component(&fields.day_of_month, 1, 31),
+        day_from_end: false,
+        hour: component(&fields.hour, 0, 23),
+        minute: component(&fields.minute, 0, 59),
+        second: Component {
+            ranges: vec![Range {
+                start: 0,
+                end: 0,
+                step: 1,
+            }],
+        },
+        timezone: None,
+    };
+
+    if fields.day_of_month.any || fields.day_of_week.any {
+        // A starred field like `*/2` still restricts, but combines with AND as systemd does.
+        return vec![spec];
+    }
+
+    vec![
+        CalendarSpec {
+            weekdays: Vec::new(),
+            ..spec.clone()
+        },
+        CalendarSpec {
+            day: Component::default(),
+            ..spec
+        },
+    ]
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Cursor;
+
+    use super::*;
+    use crate::cron::parse;
+
+    fn at(s: &str) -> DateTime {
+        s.parse().unwrap()
+    }
+
+    fn spec(s: &str) -> CalendarSpec {
+        s.parse().unwrap()
+    }
+
+    #[test]
+    fn it_parse() {
+        for (expression, normalized) in [
+            ("daily", "*-*-* 00:00:00"),
+            ("weekly", "Mon *-*-* 00:00:00"),
+            ("quarterly", "*-01,04,07,10-01 00:00:00"),
+            ("Sat,Sun 20:00", "Sat,Sun *-*-* 20:00:00"),
+            ("Mon..Fri", "Mon..Fri *-*-* 00:00:00"),
+            (
+                "mon,tue,wed,sun *-*-* 9..17:00/15",
+                "Mon..Wed,Sun *-*-* 09..17:00/15:00",
+            ),
+            ("2024-02-29", "2024-02-29 00:00:00"),
+            ("24-1-1 5:3", "2024-01-01 05:03:00"),
+            ("*-*~01 23:59:59 UTC", "*-*~01 23:59:59 UTC"),
+            ("12-25 08:00", "*-12-25 08:00:00"),
+        ] {
+            assert_eq!(normalized, spec(expression).to_string(), "{expression}");
+        }
+
+        for invalid in [
+            "",
+            "Funday",
+            "Fri..Mon",
+            "*-13-01",
+            "25:00",
+            "*-*-* 00:00:00 UTC extra",
+            "*-*~1..3",
+            "*-*-*/0",
+        ] {
+            assert!(invalid.parse::<CalendarSpec>().is_err(), "{invalid}");
+        }
+    }
+
+    #[test]
+    fn it_next_after() {
+        assert_eq!(
+            Some(at("2024-09-09 09:00")),
+            spec("Mon..Fri 09:00").next_after(&at("2024-09-06 12:00"))
+        );
+        assert_eq!(
+            Some(at("2024-09-06 12:00:30")),
+            spec("*:*:00/30").next_after(&at("2024-09-06 12:00:00"))
+        );
+        assert_eq!(
+            Some(at("2024-02-29 00:00")),
+            spec("*-*~01").next_after(&at("2024-02-01 00:00"))
+        );
+        assert_eq!(
+            Some(at("2028-02-29 00:00")),
+            spec("*-02-29").next_after(&at("2024-03-01 00:00"))
+        );
+        assert_eq!(None, spec("2020-01-01").next_after(&at("2024-01-01 00:00")));
+        assert_eq!(None, spec("*-02-30").next_after(&at("2024-01-01 00:00")));
+    }
+
+    #[test]
+    fn it_on_calendar() {
+        let crontab = parse(Cursor::new(
+            r#"
+*/15 9-17 * jan-mar,dec mon-fri /bin/a
+30 4 1,15 * 5 /bin/b
+0 0 * * 0-2 /bin/c
+@weekly /bin/d
+@reboot /bin/e
+0 0 1-29/10 * * /bin/f
+0 0 1-31/10 * * /bin/g
+        "#,
+        ))
+        .unwrap();
+
+        let converted = crontab
+            .entries()
+            .map(|entry| {
+                on_calendar(entry)
+                    .iter()
+                    .map(CalendarSpec::to_string)
+                    .collect::<Vec<_>>()
+            })
+            .collect::<Vec<_>>();
+
+        assert_eq!(
+            vec![
+                vec!["Mon..Fri *-01..03,12-* 09..17:00/15:00"],
+                vec!["*-*-01,15 04:30:00", "Fri *-*-* 04:30:00"],
+                vec!["Mon,Tue,Sun *-*-* 00:00:00"],
+                vec!["Sun *-*-* 00:00:00"],
+                vec![],
+                vec!["*-*-01..29/10 00:00:00"],
+                vec!["*-*-01/10 00:00:00"],
+            ],
+            converted
+        );
+
+        // The converted expressions fire exactly when cron does.
+        for entry in crontab.entries() {
+            let specs = on_calendar(entry);
+            let Some(mut cron) = entry.next_after(&at("2024-01-01 00:00")) else {
+                continue;
+            };
+
+            for _ in 0..50 {
+                let systemd = specs
+                    .iter()
+                    .filter_map(|spec| spec.next_after(&cron.add_seconds(-1)))
+                    .min();
+
+                assert_eq!(Some(cron), systemd, "{entry}");
+                cron = entry.next_after(&cron).unwrap();
+            }
+        }
+    }
+}
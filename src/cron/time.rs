@@ -0,0 +1,438 @@
+use std::{fmt, str::FromStr};
+
+use super::{CronEntry, Fields, ParseCronError, Schedule};
+
+/// How far ahead or back occurrences are searched, enough for any weekday to fall on February 29.
+const SEARCH_YEARS: i32 = 30;
+
+const SECONDS_PER_DAY: i64 = 86400;
+
+/// A calendar date and time without a timezone, with second precision.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct DateTime {
+    pub year: i32,
+    pub month: u32,
+    pub day: u32,
+    pub hour: u32,
+    pub minute: u32,
+    pub second: u32,
+}
+
+impl DateTime {
+    /// Returns `None` if the date or time doesn't exist.
+    pub fn new(
+        year: i32,
+        month: u32,
+        day: u32,
+        hour: u32,
+        minute: u32,
+        second: u32,
+    ) -> Option<Self> {
+        let valid = (1..=12).contains(&month)
+            && day >= 1
+            && day <= days_in_month(year, month)
+            && hour < 24
+            && minute < 60
+            && second < 60;
+
+        valid.then_some(DateTime {
+            year,
+            month,
+            day,
+            hour,
+            minute,
+            second,
+        })
+    }
+
+    /// Converts seconds since the Unix epoch, treating them as UTC.
+    pub fn from_unix(seconds: i64) -> Self {
+        let days = seconds.div_euclid(SECONDS_PER_DAY);
+        let time = seconds.rem_euclid(SECONDS_PER_DAY) as u32;
+
+        // Howard Hinnant's civil_from_days.
+        let z = days + 719468;
+        let era = z.div_euclid(146097);
+        let doe = z.rem_euclid(146097);
+        let yoe = (doe - doe / 1460 + doe / 36524 - doe / 146096) / 365;
+        let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+        let mp = (5 * doy + 2) / 153;
+        let day = (doy - (153 * mp + 2) / 5 + 1) as u32;
+        let month = if mp < 10 { mp + 3 } else { mp - 9 } as u32;
+        let year = (yoe + era * 400 + i64::from(month <= 2)) as i32;
+
+        DateTime {
+            year,
+            month,
+            day,
+            hour: time / 3600,
+            minute: time / 60 % 60,
+            second: time % 60,
+        }
+    }
+
+    /// Returns the seconds since the Unix epoch, treating the time as UTC.
+    pub fn to_unix(&self) -> i64 {
+        // Howard Hinnant's days_from_civil.
+        let year = i64::from(self.year) - i64::from(self.month <= 2);
+        let era = year.div_euclid(400);
+        let yoe = year.rem_euclid(400);
+        let month = i64::from(self.month);
+        let doy = (153 * (if month > 2 { month - 3 } else { month + 9 }) + 2) / 5
+            + i64::from(self.day)
+            - 1;
+        let doe = yoe * 365 + yoe / 4 - yoe / 100 + doy;
+        let days = era * 146097 + doe - 719468;
+
+        days * SECONDS_PER_DAY
+            + i64::from(self.hour) * 3600
+            + i64::from(self.minute) * 60
+            + i64::from(self.second)
+    }
+
+    /// Day of week from 0 to 6, Sunday being 0 as in cron.
+    pub fn weekday(&self) -> u32 {
+        // 1970-01-01 was a Thursday.
+        (self.to_unix().div_euclid(SECONDS_PER_DAY) + 4).rem_euclid(7) as u32
+    }
+
+    pub(super) fn add_seconds(&self, seconds: i64) -> Self {
+        Self::from_unix(self.to_unix() + seconds)
+    }
+
+    fn start_of_minute(&self) -> Self {
+        DateTime { second: 0, ..*self }
+    }
+}
+
+impl FromStr for DateTime {
+    type Err = ParseCronError;
+
+    /// Accepts `YYYY-MM-DD HH:MM[:SS]`, with a space or a `T` between the date and the time.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let invalid = || ParseCronError::InvalidField(s.to_owned());
+
+        let (date, time) = s.split_once([' ', 'T']).ok_or_else(invalid)?;
+        let date = date
+            .splitn(3, '-')
+            .map(str::parse::<i64>)
+            .collect::<Result<Vec<_>, _>>()
+            .map_err(|_| invalid())?;
+        let time = time
+            .split(':')
+            .map(str::parse::<u32>)
+            .collect::<Result<Vec<_>, _>>()
+            .map_err(|_| invalid())?;
+
+        let (&[year, month, day], &[hour, minute, ..]) = (&date[..], &time[..]) else {
+            return Err(invalid());
+        };
+
+        if time.len() > 3 {
+            return Err(invalid());
+        }
+
+        DateTime::new(
+            i32::try_from(year).map_err(|_| invalid())?,
+            u32::try_from(month).map_err(|_| invalid())?,
+            u32::try_from(day).map_err(|_| invalid())?,
+            hour,
+            minute,
+            time.get(2).copied().unwrap_or(0),
+        )
+        .ok_or_else(invalid)
+    }
+}
+
+impl fmt::Display for DateTime {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{:04}-{:02}-{:02} {:02}:{:02}:{:02}",
+            self.year, self.month, self.day, self.hour, self.minute, self.second
+        )
+    }
+}
+
+fn is_leap_year(year: i32) -> bool {
+    year % 4 == 0 && (year % 100 != 0 || year % 400 == 0)
+}
+
+pub(super) fn days_in_month(year: i32, month: u32) -> u32 {
+    match month {
+        2 if is_leap_year(year) => 29,
+        2 => 28,
+        4 | 6 | 9 | 11 => 30,
+        _ => 31,
+    }
+}
+
+impl Fields {
+    ///
+    /// Whether the entry runs at the minute of the time.
+    /// When neither day field starts with `*`, a day matches if either of them does, as in cron.
+    pub fn matches(&self, time: &DateTime) -> bool {
+        self.minute.contains(time.minute)
+            && self.hour.contains(time.hour)
+            && self.month.contains(time.month)
+            && self.matches_day(time)
+    }
+
+    fn matches_day(&self, time: &DateTime) -> bool {
+        let weekday = time.weekday();
+        let day_of_month = self.day_of_month.contains(time.day);
+        let day_of_week =
+            self.day_of_week.contains(weekday) || (weekday == 0 && self.day_of_week.contains(7));
+
+        if self.day_of_month.any || self.day_of_week.any {
+            day_of_month && day_of_week
+        } else {
+            day_of_month || day_of_week
+        }
+    }
+
+    fn next_after(&self, time: &DateTime) -> Option<DateTime> {
+        let mut time = time.start_of_minute().add_seconds(60);
+        let limit = time.year + SEARCH_YEARS;
+
+        while time.year <= limit {
+            if !self.month.contains(time.month) {
+                let (year, month) = match time.month {
+                    12 => (time.year + 1, 1),
+                    month => (time.year, month + 1),
+                };
+                time = DateTime::new(year, month, 1, 0, 0, 0)?;
+            } else if !self.matches_day(&time) {
+                time = DateTime::new(time.year, time.month, time.day, 0, 0, 0)?
+                    .add_seconds(SECONDS_PER_DAY);
+            } else if !self.hour.contains(time.hour) {
+                time = DateTime::new(time.year, time.month, time.day, time.hour, 0, 0)?
+                    .add_seconds(3600);
+            } else if !self.minute.contains(time.minute) {
+                time = time.add_seconds(60);
+            } else {
+                return Some(time);
+            }
+        }
+
+        None
+    }
+
+    fn prev_before(&self, time: &DateTime) -> Option<DateTime> {
+        let mut time = match time.second {
+            0 => time.add_seconds(-60),
+            _ => time.start_of_minute(),
+        };
+        let limit = time.year - SEARCH_YEARS;
+
+        while time.year >= limit {
+            if !self.month.contains(time.month) {
+                time = DateTime::new(time.year, time.month, 1, 0, 0, 0)?.add_seconds(-60);
+            } else if !self.matches_day(&time) {
+                time = DateTime::new(time.year, time.month, time.day, 0, 0, 0)?.add_seconds(-60);
+            } else if !self.hour.contains(time.hour) {
+                time = DateTime::new(time.year, time.month, time.day, time.hour, 0, 0)?
+                    .add_seconds(-60);
+            } else if !self.minute.contains(time.minute) {
+                time = time.add_seconds(-60);
+            } else {
+                return Some(time);
+            }
+        }
+
+        None
+    }
+}
+
+impl Schedule {
+    ///
+    /// Returns the first time strictly after `time` the schedule fires, `None` for `@reboot`
+    /// and for schedules that never fire, like `0 0 30 2 *`.
+    ///
+    /// ```no_run
+    /// use unixism::cron::{self, DateTime};
+    ///
+    /// let crontab = cron::parse_default().unwrap();
+    /// let now = DateTime::from_unix(1_700_000_000);
+    ///
+    /// for entry in crontab.entries() {
+    ///     println!("{:?} {}", entry.schedule.next_after(&now), entry.command);
+    /// }
+    /// ```
+    pub fn next_after(&self, time: &DateTime) -> Option<DateTime> {
+        self.fields()?.next_after(time)
+    }
+
+    /// Returns the last time strictly before `time` the schedule fired.
+    pub fn prev_before(&self, time: &DateTime) -> Option<DateTime> {
+        self.fields()?.prev_before(time)
+    }
+
+    ///
+    /// Iterates over the times the schedule fires after `time`.
+    ///
+    /// ```
+    /// use unixism::cron::{DateTime, Schedule};
+    ///
+    /// let schedule = Schedule::Fields(Box::new("*/15 9-17 * * mon-fri".parse().unwrap()));
+    /// let start = "2024-01-01 00:00".parse::<DateTime>().unwrap();
+    ///
+    /// let times = schedule.upcoming(&start).take(3).map(|time| time.to_string()).collect::<Vec<_>>();
+    ///
+    /// assert_eq!(vec!["2024-01-01 09:00:00", "2024-01-01 09:15:00", "2024-01-01 09:30:00"], times);
+    /// ```
+    pub fn upcoming(&self, time: &DateTime) -> Occurrences {
+        Occurrences {
+            fields: self.fields(),
+            time: *time,
+        }
+    }
+}
+
+impl CronEntry {
+    pub fn next_after(&self, time: &DateTime) -> Option<DateTime> {
+        self.schedule.next_after(time)
+    }
+
+    pub fn prev_before(&self, time: &DateTime) -> Option<DateTime> {
+        self.schedule.prev_before(time)
+    }
+
+    pub fn upcoming(&self, time: &DateTime) -> Occurrences {
+        self.schedule.upcoming(time)
+    }
+}
+
+/// Upcoming run times of a schedule, see [`Schedule::upcoming`].
+#[derive(Debug, Clone)]
+pub struct Occurrences {
+    fields: Option<Fields>,
+    time: DateTime,
+}
+
+impl Iterator for Occurrences {
+    type Item = DateTime;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let next = self.fields.as_ref()?.next_after(&self.time);
+
+        match next {
+            Some(time) => self.time = time,
+            None => self.fields = None,
+        }
+
+        next
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn at(s: &str) -> DateTime {
+        s.parse().unwrap()
+    }
+
+    fn fields(s: &str) -> Schedule {
+        Schedule::Fields(Box::new(s.parse().unwrap()))
+    }
+
+    #[test]
+    fn it_date_time() {
+        let time = at("2024-02-29 13:45:10");
+
+        assert_eq!(1709214310, time.to_unix());
+        assert_eq!(time, DateTime::from_unix(time.to_unix()));
+        assert_eq!(4, time.weekday());
+        assert_eq!(0, at("1970-01-04 00:00").weekday());
+        assert_eq!(at("1969-12-31 23:59:59"), DateTime::from_unix(-1));
+        assert_eq!("2024-02-29 13:45:10", time.to_string());
+
+        assert!("2023-02-29 00:00".parse::<DateTime>().is_err());
+        assert!("2024-01-01".parse::<DateTime>().is_err());
+        assert!("2024-01-01 24:00".parse::<DateTime>().is_err());
+    }
+
+    #[test]
+    fn it_next_after() {
+        let schedule = fields("*/15 9-17 * jan-mar,dec mon-fri");
+
+        assert_eq!(
+            Some(at("2024-01-01 09:00")),
+            schedule.next_after(&at("2023-12-31 23:59:30"))
+        );
+        assert_eq!(
+            Some(at("2024-01-02 09:00")),
+            schedule.next_after(&at("2024-01-01 17:45"))
+        );
+        assert_eq!(
+            Some(at("2024-12-02 09:00")),
+            schedule.next_after(&at("2024-03-29 17:50"))
+        );
+
+        // Both day fields are restricted, so either of them matches.
+        let schedule = fields("0 12 13 * fri");
+        assert_eq!(
+            vec![
+                at("2024-09-06 12:00"),
+                at("2024-09-13 12:00"),
+                at("2024-09-20 12:00"),
+                at("2024-09-27 12:00"),
+                at("2024-10-04 12:00"),
+                at("2024-10-11 12:00"),
+                at("2024-10-13 12:00"),
+            ],
+            schedule
+                .upcoming(&at("2024-09-01 00:00"))
+                .take(7)
+                .collect::<Vec<_>>()
+        );
+
+        // `*/2` in day of month still restricts it, but the star makes the days combine with AND.
+        let schedule = fields("0 0 */2 * 0");
+        assert_eq!(
+            Some(at("2024-09-15 00:00")),
+            schedule.next_after(&at("2024-09-01 00:00"))
+        );
+
+        assert_eq!(
+            Some(at("2028-02-29 00:00")),
+            fields("0 0 29 2 *").next_after(&at("2024-03-01 00:00"))
+        );
+        assert_eq!(
+            Some(at("2024-09-08 00:00")),
+            fields("0 0 * * 7").next_after(&at("2024-09-02 00:00"))
+        );
+        assert_eq!(
+            None,
+            fields("0 0 30 2 *").next_after(&at("2024-01-01 00:00"))
+        );
+        assert_eq!(None, Schedule::Reboot.next_after(&at("2024-01-01 00:00")));
+        assert_eq!(
+            Some(at("2025-01-01 00:00")),
+            Schedule::Yearly.next_after(&at("2024-01-01 00:00"))
+        );
+    }
+
+    #[test]
+    fn it_prev_before() {
+        let schedule = fields("30 4 1,15 * *");
+
+        assert_eq!(
+            Some(at("2024-09-15 04:30")),
+            schedule.prev_before(&at("2024-10-01 04:30"))
+        );
+        assert_eq!(
+            Some(at("2024-10-01 04:30")),
+            schedule.prev_before(&at("2024-10-01 04:30:01"))
+        );
+        assert_eq!(
+            Some(at("2023-12-15 04:30")),
+            schedule.prev_before(&at("2024-01-01 00:00"))
+        );
+        assert_eq!(
+            Some(at("2024-02-29 23:59")),
+            fields("59 23 * feb *").prev_before(&at("2024-03-10 00:00"))
+        );
+    }
+}
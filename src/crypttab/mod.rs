@@ -0,0 +1,293 @@
+use std::{
+    error, fmt, fs,
+    io::{self, BufRead, BufReader},
+    num::ParseIntError,
+    str::FromStr,
+};
+
+use crate::fstab::Spec;
+
+/// An option of the fourth crypttab field.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum CrypttabOption {
+    Luks,
+    Plain,
+    Bitlk,
+    Tcrypt,
+    Discard,
+    Readonly,
+    Noauto,
+    Nofail,
+    Swap,
+    Tmp(Option<String>),
+    Tries(u32),
+    Timeout(String),
+    Header(String),
+    Keyslot(u32),
+    Cipher(String),
+    Hash(String),
+    Size(u32),
+    /// Options with a value unknown to this crate.
+    Value {
+        name: String,
+        value: String,
+    },
+    /// Flags unknown to this crate.
+    Flag(String),
+}
+
+impl FromStr for CrypttabOption {
+    type Err = ParseCrypttabError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let option = match s.split_once('=') {
+            None => match s {
+                "luks" => CrypttabOption::Luks,
+                "plain" => CrypttabOption::Plain,
+                "bitlk" => CrypttabOption::Bitlk,
+                "tcrypt" => CrypttabOption::Tcrypt,
+                "discard" => CrypttabOption::Discard,
+                "readonly" | "read-only" => CrypttabOption::Readonly,
+                "noauto" => CrypttabOption::Noauto,
+                "nofail" => CrypttabOption::Nofail,
+                "swap" => CrypttabOption::Swap,
+                "tmp" => CrypttabOption::Tmp(None),
+                flag => CrypttabOption::Flag(flag.to_owned()),
+            },
+            Some(("tmp", fstype)) => CrypttabOption::Tmp(Some(fstype.to_owned())),
+            Some(("tries", tries)) => CrypttabOption::Tries(tries.parse()?),
+            Some(("timeout", timeout)) => CrypttabOption::Timeout(timeout.to_owned()),
+            Some(("header", header)) => CrypttabOption::Header(header.to_owned()),
+            Some(("key-slot" | "keyslot", slot)) => CrypttabOption::Keyslot(slot.parse()?),
+            Some(("cipher", cipher)) => CrypttabOption::Cipher(cipher.to_owned()),
+            Some(("hash", hash)) => CrypttabOption::Hash(hash.to_owned()),
+            Some(("size", size)) => CrypttabOption::Size(size.parse()?),
+            Some((name, value)) => CrypttabOption::Value {
+                name: name.to_owned(),
+                value: value.to_owned(),
+            },
+        };
+
+        Ok(option)
+    }
+}
+
+impl fmt::Display for CrypttabOption {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            CrypttabOption::Luks => write!(f, "luks"),
+            CrypttabOption::Plain => write!(f, "plain"),
+            CrypttabOption::Bitlk => write!(f, "bitlk"),
+            CrypttabOption::Tcrypt => write!(f, "tcrypt"),
+            CrypttabOption::Discard => write!(f, "discard"),
+            CrypttabOption::Readonly => write!(f, "readonly"),
+            CrypttabOption::Noauto => write!(f, "noauto"),
+            CrypttabOption::Nofail => write!(f, "nofail"),
+            CrypttabOption::Swap => write!(f, "swap"),
+            CrypttabOption::Tmp(None) => write!(f, "tmp"),
+            CrypttabOption::Tmp(Some(fstype)) => write!(f, "tmp={}", fstype),
+            CrypttabOption::Tries(tries) => write!(f, "tries={}", tries),
+            CrypttabOption::Timeout(timeout) => write!(f, "timeout={}", timeout),
+            CrypttabOption::Header(header) => write!(f, "header={}", header),
+            CrypttabOption::Keyslot(slot) => write!(f, "key-slot={}", slot),
+            CrypttabOption::Cipher(cipher) => write!(f, "cipher={}", cipher),
+            CrypttabOption::Hash(hash) => write!(f, "hash={}", hash),
+            CrypttabOption::Size(size) => write!(f, "size={}", size),
+            CrypttabOption::Value { name, value } => write!(f, "{}={}", name, value),
+            CrypttabOption::Flag(flag) => write!(f, "{}", flag),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CrypttabEntry {
+    /// Name of the mapped device under `/dev/mapper`.
+    pub name: String,
+    pub device: Spec,
+    /// The key file, `None` when the password is asked for interactively.
+    pub keyfile: Option<String>,
+    pub options: Vec<CrypttabOption>,
+}
+
+impl FromStr for CrypttabEntry {
+    type Err = ParseCrypttabError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let fields = s.split_whitespace().collect::<Vec<_>>();
+
+        let (name, device, keyfile, options) = match fields[..] {
+            [name, device] => (name, device, None, None),
+            [name, device, keyfile] => (name, device, Some(keyfile), None),
+            [name, device, keyfile, options] => (name, device, Some(keyfile), Some(options)),
+            _ => return Err(ParseCrypttabError::InvalidEntry(s.to_owned())),
+        };
+
+        Ok(CrypttabEntry {
+            name: name.to_owned(),
+            device: Spec::from(device),
+            keyfile: keyfile
+                .filter(|keyfile| !matches!(*keyfile, "none" | "-"))
+                .map(str::to_owned),
+            options: options
+                .unwrap_or_default()
+                .split(',')
+                .filter(|option| !option.is_empty())
+                .map(str::parse)
+                .collect::<Result<_, _>>()?,
+        })
+    }
+}
+
+impl fmt::Display for CrypttabEntry {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} {} {}",
+            self.name,
+            self.device,
+            self.keyfile.as_deref().unwrap_or("none")
+        )?;
+
+        if !self.options.is_empty() {
+            let options = self
+                .options
+                .iter()
+                .map(CrypttabOption::to_string)
+                .collect::<Vec<_>>();
+
+            write!(f, " {}", options.join(","))?;
+        }
+
+        Ok(())
+    }
+}
+
+#[derive(Debug)]
+pub enum ParseCrypttabError {
+    InvalidEntry(String),
+    ParseIntError(ParseIntError),
+    IOError(io::Error),
+}
+
+impl error::Error for ParseCrypttabError {}
+
+impl fmt::Display for ParseCrypttabError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::InvalidEntry(entry) => write!(f, "invalid crypttab entry: {}", entry),
+            Self::ParseIntError(error) => write!(f, "{}", error),
+            Self::IOError(error) => write!(f, "{}", error),
+        }
+    }
+}
+
+impl From<ParseIntError> for ParseCrypttabError {
+    fn from(value: ParseIntError) -> Self {
+        ParseCrypttabError::ParseIntError(value)
+    }
+}
+
+impl From<io::Error> for ParseCrypttabError {
+    fn from(value: io::Error) -> Self {
+        ParseCrypttabError::IOError(value)
+    }
+}
+
+///
+/// ```no_run
+/// let entries = unixism::crypttab::parse(std::fs::File::open("/etc/crypttab").unwrap()).unwrap();
+///
+/// for entry in entries {
+///     println!("/dev/mapper/{} from {} ({:?})", entry.name, entry.device, entry.options);
+/// }
+/// ```
+pub fn parse<R>(reader: R) -> Result<impl Iterator<Item = CrypttabEntry>, ParseCrypttabError>
+where
+    R: io::Read,
+{
+    let mut entries = Vec::new();
+
+    for line in BufReader::new(reader).lines() {
+        let line = line?;
+        let line = line.trim();
+
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+
+        entries.push(line.parse::<CrypttabEntry>()?);
+    }
+
+    Ok(entries.into_iter())
+}
+
+///
+/// Same as parse, but parses the `/etc/crypttab` as default.
+///
+/// ```no_run
+/// let entries = unixism::crypttab::parse_default().unwrap();
+/// ```
+pub fn parse_default() -> Result<impl Iterator<Item = CrypttabEntry>, ParseCrypttabError> {
+    parse(fs::File::open("/etc/crypttab")?)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Cursor;
+
+    use super::*;
+
+    #[test]
+    fn it_parse() {
+        let entries = parse(Cursor::new(
+            r#"
+# <target name> <source device> <key file> <options>
+cryptroot UUID=6f1c2d3e-4a5b-4c6d-8e9f-0a1b2c3d4e5f none luks,discard,tries=3
+cryptswap /dev/sda3 /dev/urandom swap,cipher=aes-xts-plain64,size=256
+cryptdata PARTLABEL=data /etc/keys/data.key luks,header=/boot/data.hdr,nofail,x-systemd.device-timeout=10
+crypthome /dev/sdb1
+        "#,
+        ))
+        .unwrap()
+        .collect::<Vec<_>>();
+
+        assert_eq!(4, entries.len());
+        assert_eq!(
+            CrypttabEntry {
+                name: "cryptroot".to_owned(),
+                device: Spec::Uuid("6f1c2d3e-4a5b-4c6d-8e9f-0a1b2c3d4e5f".to_owned()),
+                keyfile: None,
+                options: vec![
+                    CrypttabOption::Luks,
+                    CrypttabOption::Discard,
+                    CrypttabOption::Tries(3),
+                ],
+            },
+            entries[0]
+        );
+        assert_eq!(Some("/dev/urandom"), entries[1].keyfile.as_deref());
+        assert!(entries[1].options.contains(&CrypttabOption::Size(256)));
+        assert_eq!(
+            vec![
+                CrypttabOption::Luks,
+                CrypttabOption::Header("/boot/data.hdr".to_owned()),
+                CrypttabOption::Nofail,
+                CrypttabOption::Value {
+                    name: "x-systemd.device-timeout".to_owned(),
+                    value: "10".to_owned()
+                },
+            ],
+            entries[2].options
+        );
+        assert!(entries[3].options.is_empty());
+
+        assert_eq!(
+            "cryptdata PARTLABEL=data /etc/keys/data.key luks,header=/boot/data.hdr,nofail,x-systemd.device-timeout=10",
+            entries[2].to_string()
+        );
+        assert_eq!("crypthome /dev/sdb1 none", entries[3].to_string());
+
+        assert!(parse(Cursor::new("cryptroot")).is_err());
+        assert!(parse(Cursor::new("cryptroot /dev/sda2 none tries=x")).is_err());
+    }
+}
@@ -0,0 +1,697 @@
+use std::{
+    error, fmt,
+    io::{self, Read, Write},
+    net::{IpAddr, Ipv4Addr, Ipv6Addr, SocketAddr, TcpStream, UdpSocket},
+    sync::atomic::{AtomicUsize, Ordering},
+    time::Duration,
+};
+
+use super::resolv::{Config, ConfigOption, Nameserver, MAXNS};
+
+const DEFAULT_TIMEOUT: usize = 5;
+const DEFAULT_ATTEMPTS: usize = 2;
+const MAX_UDP_SIZE: usize = 512;
+const MAX_POINTERS: usize = 64;
+
+const CLASS_IN: u16 = 1;
+const RCODE_NXDOMAIN: u8 = 3;
+
+#[derive(Debug)]
+pub enum ClientError {
+    NoNameservers,
+    InvalidName(String),
+    InvalidResponse,
+    ServerFailure(u8),
+    IOError(io::Error),
+}
+
+impl error::Error for ClientError {}
+
+impl fmt::Display for ClientError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::NoNameservers => write!(f, "no nameservers configured"),
+            Self::InvalidName(name) => write!(f, "invalid domain name: {}", name),
+            Self::InvalidResponse => write!(f, "invalid response received"),
+            Self::ServerFailure(rcode) => write!(f, "server responded with rcode {}", rcode),
+            Self::IOError(error) => write!(f, "{}", error),
+        }
+    }
+}
+
+impl From<io::Error> for ClientError {
+    fn from(value: io::Error) -> Self {
+        ClientError::IOError(value)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RecordType {
+    A,
+    PTR,
+    AAAA,
+}
+
+impl RecordType {
+    fn code(self) -> u16 {
+        match self {
+            Self::A => 1,
+            Self::PTR => 12,
+            Self::AAAA => 28,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Record {
+    A(Ipv4Addr),
+    AAAA(Ipv6Addr),
+    PTR(String),
+}
+
+///
+/// A minimal stub resolver driven by `resolv::Config`.
+///
+/// Honors the `rotate`, `timeout`, `attempts` and `use-vc` options, and qualifies names
+/// using the search list the same way libc does.
+///
+/// ```no_run
+/// use unixism::dns::{client::Client, resolv};
+///
+/// let client = Client::new(resolv::parse_default().unwrap());
+///
+/// for ip in client.lookup_ip("example.com").unwrap() {
+///     println!("{ip}");
+/// }
+///
+/// for name in client.reverse("8.8.8.8".parse().unwrap()).unwrap() {
+///     println!("{name}");
+/// }
+/// ```
+#[derive(Debug)]
+pub struct Client {
+    config: Config,
+    next: AtomicUsize,
+}
+
+impl Client {
+    pub fn new(config: Config) -> Self {
+        Self {
+            config,
+            next: AtomicUsize::new(0),
+        }
+    }
+
+    pub fn lookup_ipv4(&self, name: &str) -> Result<Vec<Ipv4Addr>, ClientError> {
+        Ok(self
+            .lookup(name, RecordType::A)?
+            .into_iter()
+            .filter_map(|record| match record {
+                Record::A(ip) => Some(ip),
+                _ => None,
+            })
+            .collect())
+    }
+
+    pub fn lookup_ipv6(&self, name: &str) -> Result<Vec<Ipv6Addr>, ClientError> {
+        Ok(self
+            .lookup(name, RecordType::AAAA)?
+            .into_iter()
+            .filter_map(|record| match record {
+                Record::AAAA(ip) => Some(ip),
+                _ => None,
+            })
+            .collect())
+    }
+
+    ///
+    /// Queries both A and AAAA records, skipping AAAA when the `no-aaaa` option is set.
+    /// When one of the queries fails, the addresses of the other are returned if it has any.
+    pub fn lookup_ip(&self, name: &str) -> Result<Vec<IpAddr>, ClientError> {
+        let ipv4 = self.lookup_ipv4(name);
+        let ipv6 = if self.config.options.contains(&ConfigOption::NOAAAA) {
+            Ok(Vec::new())
+        } else {
+            self.lookup_ipv6(name)
+        };
+
+        match (ipv4, ipv6) {
+            (Ok(ipv4), Ok(ipv6)) => Ok(ipv4
+                .into_iter()
+                .map(IpAddr::V4)
+                .chain(ipv6.into_iter().map(IpAddr::V6))
+                .collect()),
+            (Ok(ipv4), Err(_)) if !ipv4.is_empty() => {
+                Ok(ipv4.into_iter().map(IpAddr::V4).collect())
+            }
+            (Err(_), Ok(ipv6)) if !ipv6.is_empty() => {
+                Ok(ipv6.into_iter().map(IpAddr::V6).collect())
+            }
+            (Err(error), _) | (_, Err(error)) => Err(error),
+        }
+    }
+
+    pub fn reverse(&self, ip: IpAddr) -> Result<Vec<String>, ClientError> {
+        Ok(self
+            .query(&reverse_name(ip), RecordType::PTR)?
+            .unwrap_or_default()
+            .into_iter()
+            .filter_map(|record| match record {
+                Record::PTR(name) => Some(name),
+                _ => None,
+            })
+            .collect())
+    }
+
+    ///
+    /// Queries `name` qualified by the search list, returning the records of the first name that has any.
+    /// Like glibc, a name whose query fails is skipped, and the error is returned only when no name has records.
+    pub fn lookup(&self, name: &str, rtype: RecordType) -> Result<Vec<Record>, ClientError> {
+        let mut failure = None;
+
+        for qualified in self.config.qualify(name) {
+            match self.query(&qualified, rtype) {
+                Ok(Some(records)) if !records.is_empty() => return Ok(records),
+                Ok(_) => continue,
+                Err(error) => failure = Some(error),
+            }
+        }
+
+        match failure {
+            Some(error) => Err(error),
+            None => Ok(Vec::new()),
+        }
+    }
+
+    /// Queries the fully qualified `name` as is, `None` means the name does not exist.
+    pub fn query(&self, name: &str, rtype: RecordType) -> Result<Option<Vec<Record>>, ClientError> {
+        let servers = self.servers();
+        if servers.is_empty() {
+            return Err(ClientError::NoNameservers);
+        }
+
+        let packet = encode_query(query_id()?, name, rtype)?;
+
+        let mut last_error = ClientError::NoNameservers;
+
+        for _ in 0..self.attempts() {
+            for server in &servers {
+                let server = match server.socket_addr() {
+                    Ok(server) => server,
+                    Err(error) => {
+                        last_error = error.into();
+                        continue;
+                    }
+                };
+
+                match self.exchange(server, &packet) {
+                    Ok(response) => return Ok(response),
+                    Err(error) => last_error = error,
+                }
+            }
+        }
+
+        Err(last_error)
+    }
+
+    fn servers(&self) -> Vec<&Nameserver> {
+        let mut servers = self
+            .config
+            .nameservers
+            .iter()
+            .take(MAXNS)
+            .collect::<Vec<_>>();
+
+        if !servers.is_empty() && self.config.options.contains(&ConfigOption::ROTATE) {
+            let start = self.next.fetch_add(1, Ordering::Relaxed) % servers.len();
+            servers.rotate_left(start);
+        }
+
+        servers
+    }
+
+    fn timeout(&self) -> Duration {
+        let timeout = self
+            .config
+            .options
+            .iter()
+            .rev()
+            .find_map(|option| match option {
+                ConfigOption::Timeout(timeout) => Some(*timeout),
+                _ => None,
+            })
+            .unwrap_or(DEFAULT_TIMEOUT);
+
+        Duration::from_secs(timeout.max(1) as u64)
+    }
+
+    fn attempts(&self) -> usize {
+        self.config
+            .options
+            .iter()
+            .rev()
+            .find_map(|option| match option {
+                ConfigOption::Attempts(attempts) => Some(*attempts),
+                _ => None,
+            })
+            .unwrap_or(DEFAULT_ATTEMPTS)
+            .max(1)
+    }
+
+    fn exchange(
+        &self,
+        server: SocketAddr,
+        packet: &[u8],
+    ) -> Result<Option<Vec<Record>>, ClientError> {
+        if !self.config.options.contains(&ConfigOption::USEVC) {
+            let response = exchange_udp(server, packet, self.timeout())?;
+
+            match decode_response(packet, &response)? {
+                Response::Truncated => {}
+                Response::Records(records) => return Ok(records),
+            }
+        }
+
+        let response = exchange_tcp(server, packet, self.timeout())?;
+
+        match decode_response(packet, &response)? {
+            Response::Truncated => Err(ClientError::InvalidResponse),
+            Response::Records(records) => Ok(records),
+        }
+    }
+}
+
+fn exchange_udp(
+    server: SocketAddr,
+    packet: &[u8],
+    timeout: Duration,
+) -> Result<Vec<u8>, ClientError> {
+    let socket = UdpSocket::bind(match server {
+        SocketAddr::V4(_) => SocketAddr::from((Ipv4Addr::UNSPECIFIED, 0)),
+        SocketAddr::V6(_) => SocketAddr::from((Ipv6Addr::UNSPECIFIED, 0)),
+    })?;
+    socket.set_read_timeout(Some(timeout))?;
+    socket.connect(server)?;
+    socket.send(packet)?;
+
+    let mut buf = vec![0; MAX_UDP_SIZE];
+    loop {
+        let size = socket.recv(&mut buf)?;
+
+        // Responses for other queries, or spoofed ones, may arrive on the same port, skip them.
+        if answers(packet, &buf[..size]) {
+            buf.truncate(size);
+
+            return Ok(buf);
+        }
+    }
+}
+
+fn exchange_tcp(
+    server: SocketAddr,
+    packet: &[u8],
+    timeout: Duration,
+) -> Result<Vec<u8>, ClientError> {
+    let mut stream = TcpStream::connect_timeout(&server, timeout)?;
+    stream.set_read_timeout(Some(timeout))?;
+    stream.set_write_timeout(Some(timeout))?;
+
+    let mut request = Vec::with_capacity(packet.len() + 2);
+    request.extend_from_slice(&(packet.len() as u16).to_be_bytes());
+    request.extend_from_slice(packet);
+    stream.write_all(&request)?;
+
+    let mut length = [0; 2];
+    stream.read_exact(&mut length)?;
+
+    let mut buf = vec![0; u16::from_be_bytes(length) as usize];
+    stream.read_exact(&mut buf)?;
+
+    Ok(buf)
+}
+
+/// Takes the query ID from the kernel random source, as a predictable one allows spoofing responses.
+fn query_id() -> io::Result<u16> {
+    let mut id = [0u8; 2];
+
+    // SAFETY: the buffer is valid for writes of its length.
+    match unsafe { libc::getrandom(id.as_mut_ptr().cast(), id.len(), 0) } {
+        2 => Ok(u16::from_ne_bytes(id)),
+        -1 => Err(io::Error::last_os_error()),
+        _ => Err(io::Error::new(
+            io::ErrorKind::UnexpectedEof,
+            "short read from getrandom",
+        )),
+    }
+}
+
+fn reverse_name(ip: IpAddr) -> String {
+    match ip {
+        IpAddr::V4(ip) => {
+            let [a, b, c, d] = ip.octets();
+
+            format!("{}.{}.{}.{}.in-addr.arpa", d, c, b, a)
+        }
+        IpAddr::V6(ip) => {
+            let mut name = String::new();
+
+            for byte in ip.octets().iter().rev() {
+                name.push_str(&format!("{:x}.{:x}.", byte & 0x0f, byte >> 4));
+            }
+
+            name + "ip6.arpa"
+        }
+    }
+}
+
+fn encode_query(id: u16, name: &str, rtype: RecordType) -> Result<Vec<u8>, ClientError> {
+    let mut packet = Vec::with_capacity(MAX_UDP_SIZE);
+
+    packet.extend_from_slice(&id.to_be_bytes());
+    // Standard query with recursion desired.
+    packet.extend_from_slice(&0x0100u16.to_be_bytes());
+    // One question, no answer, authority or additional records.
+    packet.extend_from_slice(&[0, 1, 0, 0, 0, 0, 0, 0]);
+
+    let name = name.trim_end_matches('.');
+    if name.len() > 253 {
+        return Err(ClientError::InvalidName(name.to_owned()));
+    }
+
+    for label in name.split('.').filter(|label| !label.is_empty()) {
+        if label.len() > 63 {
+            return Err(ClientError::InvalidName(name.to_owned()));
+        }
+
+        packet.push(label.len() as u8);
+        packet.extend_from_slice(label.as_bytes());
+    }
+    packet.push(0);
+
+    packet.extend_from_slice(&rtype.code().to_be_bytes());
+    packet.extend_from_slice(&CLASS_IN.to_be_bytes());
+
+    Ok(packet)
+}
+
+#[derive(Debug, PartialEq, Eq)]
+enum Response {
+    Truncated,
+    Records(Option<Vec<Record>>),
+}
+
+/// Whether `message` is a response to `query`: the ID matches and the single question is echoed back.
+fn answers(query: &[u8], message: &[u8]) -> bool {
+    let question = |packet: &[u8]| {
+        let (name, end) = read_name(packet, 12).ok()?;
+
+        Some((
+            name.to_ascii_lowercase(),
+            packet.get(end..end + 4)?.to_vec(),
+        ))
+    };
+
+    message.len() >= 12
+        && message[..2] == query[..2]
+        && message[2] & 0x80 != 0
+        && read_u16(message, 4).ok() == Some(1)
+        && question(message).is_some()
+        && question(message) == question(query)
+}
+
+fn decode_response(query: &[u8], message: &[u8]) -> Result<Response, ClientError> {
+    if !answers(query, message) {
+        return Err(ClientError::InvalidResponse);
+    }
+
+    if message[2] & 0x02 != 0 {
+        return Ok(Response::Truncated);
+    }
+
+    match message[3] & 0x0f {
+        0 => {}
+        RCODE_NXDOMAIN => return Ok(Response::Records(None)),
+        rcode => return Err(ClientError::ServerFailure(rcode)),
+    }
+
+    let questions = read_u16(message, 4)?;
+    let answers = read_u16(message, 6)?;
+
+    let mut pos = 12;
+    for _ in 0..questions {
+        pos = read_name(message, pos)?.1 + 4;
+    }
+
+    let mut records = Vec::new();
+    for _ in 0..answers {
+        pos = read_name(message, pos)?.1;
+
+        let rtype = read_u16(message, pos)?;
+        let class = read_u16(message, pos + 2)?;
+        let length = read_u16(message, pos + 8)? as usize;
+        let data = pos + 10;
+        let rdata = message
+            .get(data..data + length)
+            .ok_or(ClientError::InvalidResponse)?;
+
+        if class == CLASS_IN {
+            match rtype {
+                1 if length == 4 => records.push(Record::A(Ipv4Addr::new(
+                    rdata[0], rdata[1], rdata[2], rdata[3],
+                ))),
+                28 if length == 16 => {
+                    let mut octets = [0; 16];
+                    octets.copy_from_slice(rdata);
+
+                    records.push(Record::AAAA(Ipv6Addr::from(octets)))
+                }
+                12 => records.push(Record::PTR(read_name(message, data)?.0)),
+                _ => {}
+            }
+        }
+
+        pos = data + length;
+    }
+
+    Ok(Response::Records(Some(records)))
+}
+
+fn read_u16(message: &[u8], pos: usize) -> Result<u16, ClientError> {
+    message
+        .get(pos..pos + 2)
+        .map(|bytes| u16::from_be_bytes([bytes[0], bytes[1]]))
+        .ok_or(ClientError::InvalidResponse)
+}
+
+/// Reads a possibly compressed name, returning it along with the position right after it.
+fn read_name(message: &[u8], mut pos: usize) -> Result<(String, usize), ClientError> {
+    let mut labels = Vec::new();
+    let mut end = None;
+
+    for _ in 0..MAX_POINTERS {
+        loop {
+            let length = *message.get(pos).ok_or(ClientError::InvalidResponse)? as usize;
+
+            match length {
+                0 => {
+                    return Ok((labels.join("."), end.unwrap_or(pos + 1)));
+                }
+                length if length & 0xc0 == 0xc0 => {
+                    end.get_or_insert(pos + 2);
+                    pos = read_u16(message, pos)? as usize & 0x3fff;
+                    break;
+                }
+                length => {
+                    let label = message
+                        .get(pos + 1..pos + 1 + length)
+                        .ok_or(ClientError::InvalidResponse)?;
+
+                    labels.push(String::from_utf8_lossy(label).into_owned());
+                    pos += 1 + length;
+                }
+            }
+        }
+    }
+
+    Err(ClientError::InvalidResponse)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::thread;
+
+    use super::*;
+
+    fn answer(query: &[u8], records: &[(u16, &[u8])]) -> Vec<u8> {
+        let mut response = query.to_vec();
+        response[2] |= 0x80;
+        response[7] = records.len() as u8;
+
+        for (rtype, rdata) in records {
+            // Pointer to the question name.
+            response.extend_from_slice(&[0xc0, 12]);
+            response.extend_from_slice(&rtype.to_be_bytes());
+            response.extend_from_slice(&CLASS_IN.to_be_bytes());
+            response.extend_from_slice(&300u32.to_be_bytes());
+            response.extend_from_slice(&(rdata.len() as u16).to_be_bytes());
+            response.extend_from_slice(rdata);
+        }
+
+        response
+    }
+
+    #[test]
+    fn it_decodes_response() {
+        let query = encode_query(42, "example.com", RecordType::A).unwrap();
+        assert_eq!(29, query.len());
+
+        let response = answer(
+            &query,
+            &[(1, &[93, 184, 216, 34]), (1, &[93, 184, 216, 35])],
+        );
+        assert_eq!(
+            Response::Records(Some(vec![
+                Record::A(Ipv4Addr::new(93, 184, 216, 34)),
+                Record::A(Ipv4Addr::new(93, 184, 216, 35)),
+            ])),
+            decode_response(&query, &response).unwrap()
+        );
+
+        let ptr_query = encode_query(7, "34.216.184.93.in-addr.arpa", RecordType::PTR).unwrap();
+        let ptr = answer(&ptr_query, &[(12, &[4, b'h', b'o', b's', b't', 0xc0, 12])]);
+        assert_eq!(
+            Response::Records(Some(vec![Record::PTR(
+                "host.34.216.184.93.in-addr.arpa".to_owned()
+            )])),
+            decode_response(&ptr_query, &ptr).unwrap()
+        );
+
+        let mut nxdomain = query.clone();
+        nxdomain[2] |= 0x80;
+        nxdomain[3] |= RCODE_NXDOMAIN;
+        assert_eq!(
+            Response::Records(None),
+            decode_response(&query, &nxdomain).unwrap()
+        );
+
+        let other_id = encode_query(43, "example.com", RecordType::A).unwrap();
+        assert!(decode_response(&other_id, &response).is_err());
+
+        let other_name = encode_query(42, "example.org", RecordType::A).unwrap();
+        assert!(decode_response(&other_name, &response).is_err());
+
+        let other_type = encode_query(42, "example.com", RecordType::AAAA).unwrap();
+        assert!(decode_response(&other_type, &response).is_err());
+
+        let upper = encode_query(42, "EXAMPLE.com", RecordType::A).unwrap();
+        assert!(decode_response(&upper, &response).is_ok());
+
+        assert!(query_id().is_ok());
+    }
+
+    #[test]
+    fn it_builds_reverse_names() {
+        assert_eq!(
+            "34.216.184.93.in-addr.arpa",
+            reverse_name("93.184.216.34".parse().unwrap())
+        );
+        assert_eq!(
+            "1.0.0.0.0.0.0.0.0.0.0.0.0.0.0.0.0.0.0.0.0.0.0.0.8.b.d.0.1.0.0.2.ip6.arpa",
+            reverse_name("2001:db8::1".parse().unwrap())
+        );
+    }
+
+    #[test]
+    fn it_exchanges_over_udp() {
+        let server = UdpSocket::bind("127.0.0.1:0").unwrap();
+        let addr = server.local_addr().unwrap();
+
+        let handle = thread::spawn(move || {
+            let mut buf = [0; MAX_UDP_SIZE];
+            let (size, peer) = server.recv_from(&mut buf).unwrap();
+
+            // A spoofed response with the right ID but another question is skipped.
+            let id = u16::from_be_bytes([buf[0], buf[1]]);
+            let spoofed = encode_query(id, "example.org", RecordType::AAAA).unwrap();
+            server
+                .send_to(&answer(&spoofed, &[(28, &[0; 16])]), peer)
+                .unwrap();
+
+            server
+                .send_to(
+                    &answer(
+                        &buf[..size],
+                        &[(
+                            28,
+                            &[0x20, 0x01, 0x0d, 0xb8, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 1],
+                        )],
+                    ),
+                    peer,
+                )
+                .unwrap();
+        });
+
+        let client = Client::new(Config::default());
+        let packet = encode_query(1, "example.com", RecordType::AAAA).unwrap();
+
+        assert_eq!(
+            Some(vec![Record::AAAA("2001:db8::1".parse().unwrap())]),
+            client.exchange(addr, &packet).unwrap()
+        );
+
+        handle.join().unwrap();
+    }
+
+    #[test]
+    fn it_survives_failing_queries() {
+        let server = UdpSocket::bind("127.0.0.1:0").unwrap();
+        let addr = server.local_addr().unwrap();
+        server
+            .set_read_timeout(Some(Duration::from_millis(500)))
+            .unwrap();
+
+        // SERVFAIL for names under `broken` and every AAAA query, one A record otherwise.
+        let handle = thread::spawn(move || {
+            let mut buf = [0; MAX_UDP_SIZE];
+
+            while let Ok((size, peer)) = server.recv_from(&mut buf) {
+                let query = &buf[..size];
+                let (name, end) = read_name(query, 12).unwrap();
+
+                let response = if name.ends_with(".broken") || query[end..end + 2] == [0, 28] {
+                    let mut response = query.to_vec();
+                    response[2] |= 0x80;
+                    response[3] |= 2;
+                    response
+                } else {
+                    answer(query, &[(1, &[192, 0, 2, 1])])
+                };
+
+                server.send_to(&response, peer).unwrap();
+            }
+        });
+
+        let mut config = crate::dns::resolv::parse(
+            "search broken example.com\noptions attempts:1 timeout:1".as_bytes(),
+        )
+        .unwrap();
+        config.nameservers.push(Nameserver {
+            ip: addr.ip(),
+            port: Some(addr.port()),
+            scope: None,
+        });
+        let client = Client::new(config);
+
+        assert_eq!(
+            vec![IpAddr::V4(Ipv4Addr::new(192, 0, 2, 1))],
+            client.lookup_ip("host").unwrap()
+        );
+        assert!(matches!(
+            client.lookup_ipv6("host"),
+            Err(ClientError::ServerFailure(2))
+        ));
+
+        drop(client);
+        handle.join().unwrap();
+    }
+}
@@ -0,0 +1,181 @@
+use std::{
+    error, fmt, fs,
+    io::{self, BufRead, BufReader},
+};
+
+/// A lookup service of the `order` keyword.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Source {
+    Hosts,
+    Bind,
+    Nis,
+    Other(String),
+}
+
+impl From<&str> for Source {
+    fn from(s: &str) -> Self {
+        match s.to_ascii_lowercase().as_str() {
+            "hosts" => Source::Hosts,
+            "bind" => Source::Bind,
+            "nis" => Source::Nis,
+            _ => Source::Other(s.to_owned()),
+        }
+    }
+}
+
+/// The value of the `spoof` keyword.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Spoof {
+    Off,
+    NoWarn,
+    Warn,
+}
+
+/// The legacy resolver configuration of `/etc/host.conf`.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct HostConf {
+    pub order: Vec<Source>,
+    /// Whether all addresses of a host in /etc/hosts are returned rather than the first.
+    pub multi: bool,
+    pub nospoof: bool,
+    pub spoofalert: bool,
+    pub spoof: Option<Spoof>,
+    pub reorder: bool,
+    /// Domains stripped from names found by address lookups.
+    pub trim: Vec<String>,
+}
+
+#[derive(Debug)]
+pub enum ParseHostConfError {
+    InvalidValue(String),
+    IOError(io::Error),
+}
+
+impl error::Error for ParseHostConfError {}
+
+impl fmt::Display for ParseHostConfError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::InvalidValue(line) => write!(f, "invalid value: {}", line),
+            Self::IOError(error) => write!(f, "{}", error),
+        }
+    }
+}
+
+impl From<io::Error> for ParseHostConfError {
+    fn from(value: io::Error) -> Self {
+        ParseHostConfError::IOError(value)
+    }
+}
+
+///
+/// Keywords and values are case-insensitive and unknown keywords are skipped, as glibc does.
+///
+/// ```no_run
+/// let conf = unixism::dns::hostconf::parse(std::fs::File::open("/etc/host.conf").unwrap()).unwrap();
+///
+/// println!("order {:?}, multi {}", conf.order, conf.multi);
+/// ```
+pub fn parse<R>(reader: R) -> Result<HostConf, ParseHostConfError>
+where
+    R: io::Read,
+{
+    let mut conf = HostConf::default();
+
+    for line in BufReader::new(reader).lines() {
+        let line = line?;
+        let line = line.split('#').next().unwrap_or_default().trim();
+
+        if line.is_empty() {
+            continue;
+        }
+
+        let (keyword, value) = line.split_once(char::is_whitespace).unwrap_or((line, ""));
+        let value = value.trim();
+        let list = || {
+            value
+                .split([',', ':', ';', ' ', '\t'])
+                .filter(|item| !item.is_empty())
+        };
+        let lowercase = value.to_ascii_lowercase();
+        let switch = || match lowercase.as_str() {
+            "on" => Ok(true),
+            "off" => Ok(false),
+            _ => Err(ParseHostConfError::InvalidValue(line.to_owned())),
+        };
+
+        match keyword.to_ascii_lowercase().as_str() {
+            "order" => conf.order = list().map(Source::from).collect(),
+            "multi" => conf.multi = switch()?,
+            "nospoof" => conf.nospoof = switch()?,
+            "spoofalert" => conf.spoofalert = switch()?,
+            "reorder" => conf.reorder = switch()?,
+            "trim" => conf.trim.extend(list().map(String::from)),
+            "spoof" => {
+                conf.spoof = Some(match lowercase.as_str() {
+                    "off" => Spoof::Off,
+                    "nowarn" => Spoof::NoWarn,
+                    "warn" => Spoof::Warn,
+                    _ => return Err(ParseHostConfError::InvalidValue(line.to_owned())),
+                })
+            }
+            _ => {}
+        }
+    }
+
+    Ok(conf)
+}
+
+///
+/// Same as parse, but parses the `/etc/host.conf` as default.
+///
+/// ```no_run
+/// let conf = unixism::dns::hostconf::parse_default().unwrap();
+/// ```
+pub fn parse_default() -> Result<HostConf, ParseHostConfError> {
+    parse(fs::File::open("/etc/host.conf")?)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Cursor;
+
+    use super::*;
+
+    #[test]
+    fn it_parse() {
+        let conf = parse(Cursor::new(
+            r#"
+# The "order" line is only used by old versions of the C library.
+order hosts,bind nis
+multi On
+NOSPOOF on   # log spoofing attempts
+lookup file bind
+spoof Warn
+trim corp.example.com:lan
+trim .local
+        "#,
+        ))
+        .unwrap();
+
+        assert_eq!(
+            HostConf {
+                order: vec![Source::Hosts, Source::Bind, Source::Nis],
+                multi: true,
+                nospoof: true,
+                spoofalert: false,
+                spoof: Some(Spoof::Warn),
+                reorder: false,
+                trim: vec![
+                    "corp.example.com".to_owned(),
+                    "lan".to_owned(),
+                    ".local".to_owned()
+                ],
+            },
+            conf
+        );
+
+        assert!(parse(Cursor::new("multi yes")).is_err());
+        assert!(parse(Cursor::new("spoof loud")).is_err());
+    }
+}
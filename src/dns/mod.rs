@@ -1 +1,4 @@
+pub mod client;
+pub mod hostconf;
 pub mod resolv;
+pub mod resolvconf;
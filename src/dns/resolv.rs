@@ -1,6 +1,7 @@
 use std::{
-    error, fmt, fs,
+    error, ffi, fmt, fs,
     io::{self, BufRead, BufReader},
+    mem,
     net::{self, AddrParseError},
     num::ParseIntError,
     str::FromStr,
@@ -9,6 +10,7 @@ use std::{
 #[derive(Debug)]
 pub enum ParseConfigError {
     UnknownOption(String),
+    InvalidNameserver(String),
     IPAddrParseError(AddrParseError),
     ParseIntError(ParseIntError),
     IOError(io::Error),
@@ -20,6 +22,7 @@ impl fmt::Display for ParseConfigError {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         match self {
             Self::UnknownOption(unknown) => write!(f, "unknown option parsed: {}", unknown),
+            Self::InvalidNameserver(nameserver) => write!(f, "invalid nameserver: {}", nameserver),
             Self::IPAddrParseError(error) => write!(f, "{}", error),
             Self::ParseIntError(error) => write!(f, "{}", error),
             Self::IOError(error) => write!(f, "{}", error),
@@ -45,7 +48,127 @@ impl From<io::Error> for ParseConfigError {
     }
 }
 
-#[derive(Debug, PartialEq, Eq)]
+/// Default port nameservers listen on.
+pub const NAMESERVER_PORT: u16 = 53;
+
+///
+/// A nameserver address with the optional port and scope (interface) suffixes
+/// accepted by musl and BSD resolvers, e.g. `127.0.0.1:5353` or `[fe80::1%eth0]:53`.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Nameserver {
+    pub ip: net::IpAddr,
+    pub port: Option<u16>,
+    pub scope: Option<String>,
+}
+
+impl Nameserver {
+    pub fn new(ip: net::IpAddr) -> Self {
+        Self {
+            ip,
+            port: None,
+            scope: None,
+        }
+    }
+
+    ///
+    /// Returns the socket address to query, using the default port if none is set.
+    /// Numeric scopes are used as is, interface names are resolved with `if_nametoindex`
+    /// and an unknown interface is an error.
+    pub fn socket_addr(&self) -> io::Result<net::SocketAddr> {
+        let port = self.port.unwrap_or(NAMESERVER_PORT);
+
+        match self.ip {
+            net::IpAddr::V4(ip) => Ok(net::SocketAddr::V4(net::SocketAddrV4::new(ip, port))),
+            net::IpAddr::V6(ip) => Ok(net::SocketAddr::V6(net::SocketAddrV6::new(
+                ip,
+                port,
+                0,
+                match self.scope.as_deref() {
+                    Some(scope) => scope_id(scope)?,
+                    None => 0,
+                },
+            ))),
+        }
+    }
+}
+
+fn scope_id(scope: &str) -> io::Result<u32> {
+    if let Ok(id) = scope.parse() {
+        return Ok(id);
+    }
+
+    let name = ffi::CString::new(scope)
+        .map_err(|_| io::Error::new(io::ErrorKind::InvalidInput, scope.to_owned()))?;
+
+    match unsafe { libc::if_nametoindex(name.as_ptr()) } {
+        0 => Err(io::Error::new(
+            io::ErrorKind::NotFound,
+            format!("unknown interface: {}", scope),
+        )),
+        id => Ok(id),
+    }
+}
+
+impl From<net::IpAddr> for Nameserver {
+    fn from(value: net::IpAddr) -> Self {
+        Nameserver::new(value)
+    }
+}
+
+impl FromStr for Nameserver {
+    type Err = ParseConfigError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (host, port) = if let Some(bracketed) = s.strip_prefix('[') {
+            let (host, rest) = bracketed
+                .split_once(']')
+                .ok_or_else(|| ParseConfigError::InvalidNameserver(s.to_owned()))?;
+
+            match rest {
+                "" => (host, None),
+                rest => match rest.strip_prefix(':') {
+                    Some(port) => (host, Some(port)),
+                    None => return Err(ParseConfigError::InvalidNameserver(s.to_owned())),
+                },
+            }
+        } else if s.matches(':').count() == 1 {
+            let (host, port) = s.split_once(':').unwrap_or_default();
+
+            (host, Some(port))
+        } else {
+            (s, None)
+        };
+
+        let (ip, scope) = match host.split_once('%') {
+            Some((ip, scope)) if !scope.is_empty() => (ip, Some(scope.to_owned())),
+            Some(_) => return Err(ParseConfigError::InvalidNameserver(s.to_owned())),
+            None => (host, None),
+        };
+
+        Ok(Self {
+            ip: ip.parse()?,
+            port: port.map(str::parse).transpose()?,
+            scope,
+        })
+    }
+}
+
+impl fmt::Display for Nameserver {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let host = match &self.scope {
+            Some(scope) => format!("{}%{}", self.ip, scope),
+            None => self.ip.to_string(),
+        };
+
+        match (self.port, self.ip) {
+            (Some(port), net::IpAddr::V6(_)) => write!(f, "[{}]:{}", host, port),
+            (Some(port), net::IpAddr::V4(_)) => write!(f, "{}:{}", host, port),
+            (None, _) => write!(f, "{}", host),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
 pub struct IPPair(pub net::IpAddr, pub Option<net::IpAddr>);
 
 impl FromStr for IPPair {
@@ -65,7 +188,7 @@ impl FromStr for IPPair {
     }
 }
 
-#[derive(Debug, PartialEq, Eq)]
+#[derive(Debug, Clone, PartialEq, Eq)]
 pub enum ConfigOption {
     DEBUG,
     NDots(usize),
@@ -85,6 +208,76 @@ pub enum ConfigOption {
     USEVC,
     NORELOAD,
     TRUSTAD,
+    /// An option the parser does not know, kept as is in lenient mode.
+    Unknown {
+        name: String,
+        value: Option<String>,
+    },
+}
+
+impl ConfigOption {
+    fn parse(s: &str, lenient: bool) -> Result<Self, ParseConfigError> {
+        match s.parse::<ConfigOption>() {
+            Err(ParseConfigError::UnknownOption(_)) if lenient => {
+                let (name, value) = match s.split_once(":") {
+                    Some((name, value)) => (name, Some(value.to_owned())),
+                    None => (s, None),
+                };
+
+                Ok(ConfigOption::Unknown {
+                    name: name.to_owned(),
+                    value,
+                })
+            }
+            result => result,
+        }
+    }
+
+    fn same_kind(&self, other: &ConfigOption) -> bool {
+        match (self, other) {
+            (Self::Unknown { name, .. }, Self::Unknown { name: other, .. }) => name == other,
+            _ => mem::discriminant(self) == mem::discriminant(other),
+        }
+    }
+}
+
+impl fmt::Display for IPPair {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.1 {
+            Some(netmask) => write!(f, "{}/{}", self.0, netmask),
+            None => write!(f, "{}", self.0),
+        }
+    }
+}
+
+impl fmt::Display for ConfigOption {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::DEBUG => write!(f, "debug"),
+            Self::NDots(ndots) => write!(f, "ndots:{}", ndots),
+            Self::Timeout(timeout) => write!(f, "timeout:{}", timeout),
+            Self::Attempts(attempts) => write!(f, "attempts:{}", attempts),
+            Self::ROTATE => write!(f, "rotate"),
+            Self::NOAAAA => write!(f, "no-aaaa"),
+            Self::NOCHECKNAME => write!(f, "no-check-names"),
+            Self::INET6 => write!(f, "inet6"),
+            Self::IP6BSTRING => write!(f, "ip6-bytestring"),
+            Self::IP6DOTINT => write!(f, "ip6-dotint"),
+            Self::NOIP6DOTINT => write!(f, "no-ip6-dotint"),
+            Self::EDNS0 => write!(f, "edns0"),
+            Self::SNGLKUP => write!(f, "single-request"),
+            Self::SNGLKUPREOP => write!(f, "single-request-reopen"),
+            Self::NOTLDQUERY => write!(f, "no-tld-query"),
+            Self::USEVC => write!(f, "use-vc"),
+            Self::NORELOAD => write!(f, "no-reload"),
+            Self::TRUSTAD => write!(f, "trust-ad"),
+            Self::Unknown {
+                name,
+                value: Some(value),
+            } => write!(f, "{}:{}", name, value),
+            Self::Unknown { name, value: None } => write!(f, "{}", name),
+        }
+    }
 }
 
 impl FromStr for ConfigOption {
@@ -124,10 +317,12 @@ impl FromStr for ConfigOption {
     }
 }
 
-#[derive(Debug, Default)]
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
 pub struct Config {
-    pub nameservers: Vec<net::IpAddr>,
+    pub nameservers: Vec<Nameserver>,
     pub search_domains: Vec<String>,
+    domain: Option<String>,
+    has_search: bool,
     pub sort_list: Vec<IPPair>,
     pub options: Vec<ConfigOption>,
 }
@@ -139,8 +334,14 @@ impl Config {
         for item in items {
             match item {
                 ConfigItem::Nameserver(nameserver) => config.nameservers.push(nameserver),
-                ConfigItem::SearchDomains(domains) => config.search_domains.extend(domains),
-                ConfigItem::Domain(domain) => config.search_domains.push(domain),
+                ConfigItem::SearchDomains(domains) => {
+                    config.search_domains.extend(domains);
+                    config.has_search = true;
+                }
+                ConfigItem::Domain(domain) => {
+                    config.search_domains.push(domain.clone());
+                    config.domain = Some(domain);
+                }
                 ConfigItem::SortList(lists) => config.sort_list.extend(lists),
                 ConfigItem::Options(options) => config.options.extend(options),
             }
@@ -148,28 +349,283 @@ impl Config {
 
         config
     }
+
+    /// The domain of the last `domain` line, which is also added to the search domains.
+    pub fn domain(&self) -> Option<&str> {
+        self.domain.as_deref()
+    }
+
+    ///
+    /// Checks the config for mistakes the resolver silently tolerates.
+    ///
+    /// ```
+    /// use unixism::dns::resolv::{self, LintWarning};
+    ///
+    /// let config = resolv::parse("nameserver 0.0.0.0\nnameserver 0.0.0.0".as_bytes()).unwrap();
+    ///
+    /// for warning in config.lint() {
+    ///     println!("{warning}");
+    /// }
+    /// ```
+    pub fn lint(&self) -> Vec<LintWarning> {
+        let mut warnings = Vec::new();
+
+        if self.nameservers.len() > MAXNS {
+            warnings.push(LintWarning::TooManyNameservers(self.nameservers.len()));
+        }
+
+        for (i, nameserver) in self.nameservers.iter().enumerate() {
+            if self.nameservers[..i].contains(nameserver) {
+                warnings.push(LintWarning::DuplicateNameserver(nameserver.clone()));
+            }
+
+            let unreachable = match nameserver.ip {
+                net::IpAddr::V4(ip) => {
+                    ip.is_unspecified() || ip.is_broadcast() || ip.is_multicast()
+                }
+                net::IpAddr::V6(ip) => ip.is_unspecified() || ip.is_multicast(),
+            };
+
+            if unreachable {
+                warnings.push(LintWarning::UnreachableNameserver(nameserver.clone()));
+            }
+        }
+
+        if self.search_domains.len() > MAXDNSRCH {
+            warnings.push(LintWarning::TooManySearchDomains(self.search_domains.len()));
+        }
+
+        let length = self
+            .search_domains
+            .iter()
+            .map(|domain| domain.len() + 1)
+            .sum();
+        if length > MAXSEARCHLEN {
+            warnings.push(LintWarning::SearchListTooLong(length));
+        }
+
+        if self.domain.is_some() && self.has_search {
+            warnings.push(LintWarning::DomainAndSearch);
+        }
+
+        warnings
+    }
+
+    ///
+    /// Builds the ordered list of fully qualified names a libc resolver would try for `name`,
+    /// applying the `ndots` rule and the search domains.
+    ///
+    /// ```
+    /// let config = unixism::dns::resolv::parse("search corp.example.com example.com\noptions ndots:2".as_bytes()).unwrap();
+    ///
+    /// assert_eq!(
+    ///     vec!["myhost.corp.example.com", "myhost.example.com", "myhost"],
+    ///     config.qualify("myhost")
+    /// );
+    /// ```
+    pub fn qualify(&self, name: &str) -> Vec<String> {
+        if let Some(absolute) = name.strip_suffix('.') {
+            return vec![absolute.to_owned()];
+        }
+
+        let mut ndots = 1;
+        let mut no_tld_query = false;
+
+        for option in &self.options {
+            match option {
+                ConfigOption::NDots(n) => ndots = *n,
+                ConfigOption::NOTLDQUERY => no_tld_query = true,
+                _ => {}
+            }
+        }
+
+        let dots = name.matches('.').count();
+        let mut names = Vec::new();
+
+        if dots >= ndots {
+            names.push(name.to_owned());
+        }
+
+        for domain in &self.search_domains {
+            let qualified = match domain.trim_end_matches('.') {
+                "" => name.to_owned(),
+                domain => format!("{}.{}", name, domain),
+            };
+
+            if !names.contains(&qualified) {
+                names.push(qualified);
+            }
+        }
+
+        let searched = !self.search_domains.is_empty();
+        let as_is = name.to_owned();
+
+        if (dots > 0 || !searched || !no_tld_query) && !names.contains(&as_is) {
+            names.push(as_is);
+        }
+
+        names
+    }
+}
+
+#[cfg(feature = "hickory")]
+impl Config {
+    ///
+    /// Converts the config into `hickory_resolver` config and options.
+    /// Search domains that are not valid domain names and nameservers scoped
+    /// to an unknown interface are skipped.
+    ///
+    /// ```no_run
+    /// let (config, opts) = unixism::dns::resolv::parse_default().unwrap().to_hickory();
+    ///
+    /// println!("nameservers: {}, ndots: {}", config.name_servers().len(), opts.ndots);
+    /// ```
+    pub fn to_hickory(
+        &self,
+    ) -> (
+        hickory_resolver::config::ResolverConfig,
+        hickory_resolver::config::ResolverOpts,
+    ) {
+        use hickory_resolver::{
+            config::{LookupIpStrategy, NameServerConfig, Protocol, ResolverConfig, ResolverOpts},
+            Name,
+        };
+        use std::time::Duration;
+
+        let use_vc = self.options.contains(&ConfigOption::USEVC);
+
+        let mut name_servers = Vec::new();
+        for nameserver in &self.nameservers {
+            let Ok(address) = nameserver.socket_addr() else {
+                continue;
+            };
+
+            if !use_vc {
+                name_servers.push(NameServerConfig::new(address, Protocol::Udp));
+            }
+
+            name_servers.push(NameServerConfig::new(address, Protocol::Tcp));
+        }
+
+        let config = ResolverConfig::from_parts(
+            self.domain
+                .as_deref()
+                .and_then(|domain| Name::from_str_relaxed(domain).ok()),
+            self.search_domains
+                .iter()
+                .filter_map(|domain| Name::from_str_relaxed(domain).ok())
+                .collect(),
+            name_servers,
+        );
+
+        let mut opts = ResolverOpts::default();
+
+        for option in &self.options {
+            match option {
+                ConfigOption::NDots(ndots) => opts.ndots = *ndots,
+                ConfigOption::Timeout(timeout) => {
+                    opts.timeout = Duration::from_secs(*timeout as u64)
+                }
+                ConfigOption::Attempts(attempts) => opts.attempts = *attempts,
+                ConfigOption::ROTATE => opts.rotate = true,
+                ConfigOption::NOCHECKNAME => opts.check_names = false,
+                ConfigOption::EDNS0 => opts.edns0 = true,
+                ConfigOption::TRUSTAD => opts.authentic_data = true,
+                ConfigOption::NOAAAA => opts.ip_strategy = LookupIpStrategy::Ipv4Only,
+                ConfigOption::INET6 => opts.ip_strategy = LookupIpStrategy::Ipv6thenIpv4,
+                _ => {}
+            }
+        }
+
+        (config, opts)
+    }
+}
+
+/// Maximum number of nameservers the resolver uses.
+pub const MAXNS: usize = 3;
+
+/// Maximum number of search domains supported by older resolvers.
+pub const MAXDNSRCH: usize = 6;
+
+/// Maximum length of the search list in characters supported by older resolvers.
+pub const MAXSEARCHLEN: usize = 256;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum LintWarning {
+    TooManyNameservers(usize),
+    DuplicateNameserver(Nameserver),
+    UnreachableNameserver(Nameserver),
+    TooManySearchDomains(usize),
+    SearchListTooLong(usize),
+    DomainAndSearch,
+}
+
+impl fmt::Display for LintWarning {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::TooManyNameservers(count) => write!(
+                f,
+                "{} nameservers configured, only the first {} are used",
+                count, MAXNS
+            ),
+            Self::DuplicateNameserver(ip) => write!(f, "nameserver {} is duplicated", ip),
+            Self::UnreachableNameserver(ip) => write!(f, "nameserver {} is unreachable", ip),
+            Self::TooManySearchDomains(count) => write!(
+                f,
+                "{} search domains configured, older resolvers use only {}",
+                count, MAXDNSRCH
+            ),
+            Self::SearchListTooLong(length) => write!(
+                f,
+                "search list is {} characters long, older resolvers support only {}",
+                length, MAXSEARCHLEN
+            ),
+            Self::DomainAndSearch => write!(
+                f,
+                "both domain and search are present, only the last one is used by the resolver"
+            ),
+        }
+    }
 }
 
 #[derive(Debug, PartialEq, Eq)]
 pub enum ConfigItem {
-    Nameserver(net::IpAddr),
+    Nameserver(Nameserver),
     Domain(String),
     SearchDomains(Vec<String>),
     SortList(Vec<IPPair>),
     Options(Vec<ConfigOption>),
 }
 
-impl FromStr for ConfigItem {
-    type Err = ParseConfigError;
+impl fmt::Display for ConfigItem {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fn join<T: fmt::Display>(items: &[T]) -> String {
+            items
+                .iter()
+                .map(ToString::to_string)
+                .collect::<Vec<_>>()
+                .join(" ")
+        }
 
-    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match self {
+            Self::Nameserver(nameserver) => write!(f, "nameserver {}", nameserver),
+            Self::Domain(domain) => write!(f, "domain {}", domain),
+            Self::SearchDomains(domains) => write!(f, "search {}", join(domains)),
+            Self::SortList(pairs) => write!(f, "sortlist {}", join(pairs)),
+            Self::Options(options) => write!(f, "options {}", join(options)),
+        }
+    }
+}
+
+impl ConfigItem {
+    fn parse(s: &str, lenient: bool) -> Result<Self, ParseConfigError> {
         match s {
             s if s.starts_with("nameserver") => Ok(ConfigItem::Nameserver(
                 s.split_once("nameserver")
                     .unwrap_or_default()
                     .1
                     .trim()
-                    .parse::<net::IpAddr>()?,
+                    .parse::<Nameserver>()?,
             )),
             s if s.starts_with("domain") => Ok(ConfigItem::Domain(
                 s.split_once("domain")
@@ -182,7 +638,6 @@ impl FromStr for ConfigItem {
                 s.split_once("search")
                     .unwrap_or_default()
                     .1
-                    .trim()
                     .split_whitespace()
                     .map(String::from)
                     .collect::<Vec<_>>(),
@@ -191,7 +646,6 @@ impl FromStr for ConfigItem {
                 s.split_once("sortlist")
                     .unwrap_or_default()
                     .1
-                    .trim()
                     .split_whitespace()
                     .map(|line| line.parse::<IPPair>())
                     .collect::<Result<Vec<IPPair>, ParseConfigError>>()?,
@@ -200,9 +654,8 @@ impl FromStr for ConfigItem {
                 s.split_once("options")
                     .unwrap_or_default()
                     .1
-                    .trim()
                     .split_whitespace()
-                    .map(|line| line.parse::<ConfigOption>())
+                    .map(|line| ConfigOption::parse(line, lenient))
                     .collect::<Result<Vec<ConfigOption>, ParseConfigError>>()?,
             )),
             unknown => Err(ParseConfigError::UnknownOption(unknown.to_owned())),
@@ -210,6 +663,85 @@ impl FromStr for ConfigItem {
     }
 }
 
+impl FromStr for ConfigItem {
+    type Err = ParseConfigError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        ConfigItem::parse(s, false)
+    }
+}
+
+/// Defines which of the merged configs takes precedence.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum MergeStrategy {
+    /// Earlier configs win: their nameservers come first and their options override later ones.
+    #[default]
+    First,
+    /// Later configs win, like fragments applied on top of each other.
+    Last,
+}
+
+///
+/// Merges several resolv.conf fragments into one config.
+///
+/// Nameservers, search domains and sort list entries are unioned in precedence order
+/// without duplicates, options of the same kind are taken from the config with the highest precedence.
+///
+/// ```
+/// use unixism::dns::resolv::{self, ConfigOption, MergeStrategy};
+///
+/// let dhcp = resolv::parse("nameserver 10.0.0.1\nsearch lan\noptions timeout:5".as_bytes()).unwrap();
+/// let vpn = resolv::parse("nameserver 10.8.0.1\nsearch corp lan\noptions timeout:1".as_bytes()).unwrap();
+///
+/// let config = resolv::merge(&[dhcp, vpn], MergeStrategy::Last);
+///
+/// assert_eq!(vec!["10.8.0.1".parse::<resolv::Nameserver>().unwrap(), "10.0.0.1".parse().unwrap()], config.nameservers);
+/// assert_eq!(vec!["corp".to_owned(), "lan".to_owned()], config.search_domains);
+/// assert_eq!(vec![ConfigOption::Timeout(1)], config.options);
+/// ```
+pub fn merge(configs: &[Config], strategy: MergeStrategy) -> Config {
+    let ordered: Vec<&Config> = match strategy {
+        MergeStrategy::First => configs.iter().collect(),
+        MergeStrategy::Last => configs.iter().rev().collect(),
+    };
+
+    let mut merged = Config::default();
+
+    for config in ordered {
+        for nameserver in &config.nameservers {
+            if !merged.nameservers.contains(nameserver) {
+                merged.nameservers.push(nameserver.clone());
+            }
+        }
+
+        for domain in &config.search_domains {
+            if !merged.search_domains.contains(domain) {
+                merged.search_domains.push(domain.clone());
+            }
+        }
+
+        if merged.domain.is_none() {
+            merged.domain.clone_from(&config.domain);
+        }
+
+        merged.has_search |= config.has_search;
+
+        for pair in &config.sort_list {
+            if !merged.sort_list.contains(pair) {
+                merged.sort_list.push(pair.clone());
+            }
+        }
+
+        for option in &config.options {
+            if !merged.options.iter().any(|merged| merged.same_kind(option)) {
+                merged.options.push(option.clone());
+            }
+        }
+    }
+
+    merged
+}
+
 ///
 /// ```no_run
 /// use std::net::{IpAddr, Ipv4Addr};
@@ -242,6 +774,37 @@ impl FromStr for ConfigItem {
 /// }
 /// ```
 pub fn parse<R>(reader: R) -> Result<Config, ParseConfigError>
+where
+    R: io::Read,
+{
+    parse_items(reader, false)
+}
+
+///
+/// Same as parse, but keeps unknown options as `ConfigOption::Unknown` instead of failing,
+/// so vendor-specific options survive a round trip.
+///
+/// ```
+/// use unixism::dns::resolv::{self, ConfigOption};
+///
+/// let config = resolv::parse_lenient("options rotate vendor-flag:2".as_bytes()).unwrap();
+///
+/// assert_eq!(
+///     vec![
+///         ConfigOption::ROTATE,
+///         ConfigOption::Unknown { name: "vendor-flag".to_owned(), value: Some("2".to_owned()) },
+///     ],
+///     config.options
+/// );
+/// ```
+pub fn parse_lenient<R>(reader: R) -> Result<Config, ParseConfigError>
+where
+    R: io::Read,
+{
+    parse_items(reader, true)
+}
+
+fn parse_items<R>(reader: R, lenient: bool) -> Result<Config, ParseConfigError>
 where
     R: io::Read,
 {
@@ -249,7 +812,7 @@ where
         .lines()
         .map(Result::unwrap_or_default)
         .filter(|line| !line.is_empty() && !line.starts_with("#") && !line.starts_with(" "))
-        .map(|line| line.parse::<ConfigItem>())
+        .map(|line| ConfigItem::parse(&line, lenient))
         .collect::<Result<Vec<ConfigItem>, ParseConfigError>>()?;
 
     Ok(Config::from_items(items))
@@ -265,9 +828,42 @@ pub fn parse_default() -> Result<Config, ParseConfigError> {
     parse(fs::File::open("/etc/resolv.conf")?)
 }
 
+///
+/// Same as parse, but reads from `tokio::io::AsyncRead`.
+///
+/// ```no_run
+/// # async fn run() {
+/// let file = tokio::fs::File::open("/etc/resolv.conf").await.unwrap();
+/// let config = unixism::dns::resolv::parse_async(file).await.unwrap();
+/// # }
+/// ```
+#[cfg(feature = "async")]
+pub async fn parse_async<R>(mut reader: R) -> Result<Config, ParseConfigError>
+where
+    R: tokio::io::AsyncRead + Unpin,
+{
+    let mut buf = Vec::new();
+    tokio::io::AsyncReadExt::read_to_end(&mut reader, &mut buf).await?;
+
+    parse(io::Cursor::new(buf))
+}
+
+///
+/// Same as parse_default, but reads the `/etc/resolv.conf` asynchronously.
+///
+/// ```no_run
+/// # async fn run() {
+/// let config = unixism::dns::resolv::parse_default_async().await.unwrap();
+/// # }
+/// ```
+#[cfg(feature = "async")]
+pub async fn parse_default_async() -> Result<Config, ParseConfigError> {
+    parse_async(tokio::fs::File::open("/etc/resolv.conf").await?).await
+}
+
 #[cfg(test)]
 mod tests {
-    use std::{io::Cursor, net::IpAddr, vec};
+    use std::{io::Cursor, vec};
 
     use super::*;
 
@@ -289,13 +885,14 @@ sortlist 130.155.160.0/255.255.240.0 130.155.0.0
             search_domains,
             sort_list,
             options,
+            ..
         } = config.unwrap();
 
         assert_eq!(2, nameservers.len());
         assert_eq!(
             vec![
-                "127.0.0.53".parse::<IpAddr>().unwrap(),
-                "127.0.0.52".parse::<IpAddr>().unwrap(),
+                "127.0.0.53".parse::<Nameserver>().unwrap(),
+                "127.0.0.52".parse::<Nameserver>().unwrap(),
             ],
             nameservers
         );
@@ -328,4 +925,307 @@ sortlist 130.155.160.0/255.255.240.0 130.155.0.0
             options
         );
     }
+
+    #[test]
+    fn it_merge() {
+        let dhcp = parse(Cursor::new(
+            r#"
+nameserver 192.168.1.1
+nameserver 10.8.0.1
+search lan
+options timeout:5 rotate
+        "#,
+        ))
+        .unwrap();
+
+        let vpn = parse(Cursor::new(
+            r#"
+nameserver 10.8.0.1
+search corp lan
+options timeout:1 ndots:2
+        "#,
+        ))
+        .unwrap();
+
+        let first = merge(&[dhcp.clone(), vpn.clone()], MergeStrategy::First);
+        assert_eq!(
+            vec![
+                "192.168.1.1".parse::<Nameserver>().unwrap(),
+                "10.8.0.1".parse::<Nameserver>().unwrap(),
+            ],
+            first.nameservers
+        );
+        assert_eq!(
+            vec!["lan".to_owned(), "corp".to_owned()],
+            first.search_domains
+        );
+        assert_eq!(
+            vec![
+                ConfigOption::Timeout(5),
+                ConfigOption::ROTATE,
+                ConfigOption::NDots(2),
+            ],
+            first.options
+        );
+
+        let last = merge(&[dhcp, vpn], MergeStrategy::Last);
+        assert_eq!(
+            vec![
+                "10.8.0.1".parse::<Nameserver>().unwrap(),
+                "192.168.1.1".parse::<Nameserver>().unwrap(),
+            ],
+            last.nameservers
+        );
+        assert_eq!(
+            vec!["corp".to_owned(), "lan".to_owned()],
+            last.search_domains
+        );
+        assert_eq!(
+            vec![
+                ConfigOption::Timeout(1),
+                ConfigOption::NDots(2),
+                ConfigOption::ROTATE,
+            ],
+            last.options
+        );
+    }
+
+    #[test]
+    fn it_lint() {
+        let config = parse(Cursor::new(
+            r#"
+nameserver 127.0.0.53
+nameserver 0.0.0.0
+nameserver 127.0.0.53
+nameserver 8.8.8.8
+domain example.com
+search a b c d e f
+        "#,
+        ))
+        .unwrap();
+
+        assert_eq!(
+            vec![
+                LintWarning::TooManyNameservers(4),
+                LintWarning::UnreachableNameserver("0.0.0.0".parse().unwrap()),
+                LintWarning::DuplicateNameserver("127.0.0.53".parse().unwrap()),
+                LintWarning::TooManySearchDomains(7),
+                LintWarning::DomainAndSearch,
+            ],
+            config.lint()
+        );
+
+        assert!(parse(Cursor::new("nameserver 127.0.0.53\nsearch lan"))
+            .unwrap()
+            .lint()
+            .is_empty());
+        assert!(parse(Cursor::new("nameserver 127.0.0.53\ndomain lan"))
+            .unwrap()
+            .lint()
+            .is_empty());
+
+        let config = parse(Cursor::new("nameserver 127.0.0.53\ndomain a\nsearch b")).unwrap();
+        assert_eq!(Some("a"), config.domain());
+        assert_eq!(vec![LintWarning::DomainAndSearch], config.lint());
+    }
+
+    #[test]
+    fn it_parse_lenient() {
+        let input = "options timeout:2 vendor-flag no-cache:yes";
+
+        assert!(matches!(
+            parse(Cursor::new(input)),
+            Err(ParseConfigError::UnknownOption(option)) if option == "vendor-flag"
+        ));
+
+        assert_eq!(
+            vec![
+                ConfigOption::Timeout(2),
+                ConfigOption::Unknown {
+                    name: "vendor-flag".to_owned(),
+                    value: None
+                },
+                ConfigOption::Unknown {
+                    name: "no-cache".to_owned(),
+                    value: Some("yes".to_owned())
+                },
+            ],
+            parse_lenient(Cursor::new(input)).unwrap().options
+        );
+    }
+
+    #[test]
+    fn it_qualify() {
+        let config = parse(Cursor::new(
+            r#"
+search corp.example.com example.com
+options ndots:2
+        "#,
+        ))
+        .unwrap();
+
+        assert_eq!(
+            vec!["myhost.corp.example.com", "myhost.example.com", "myhost"],
+            config.qualify("myhost")
+        );
+        assert_eq!(
+            vec!["a.b.c", "a.b.c.corp.example.com", "a.b.c.example.com"],
+            config.qualify("a.b.c")
+        );
+        assert_eq!(
+            vec!["host.example.com"],
+            config.qualify("host.example.com.")
+        );
+
+        let config = parse(Cursor::new("search lan .\noptions no-tld-query")).unwrap();
+        assert_eq!(vec!["myhost.lan", "myhost"], config.qualify("myhost"));
+
+        let config = parse(Cursor::new("search lan\noptions no-tld-query")).unwrap();
+        assert_eq!(vec!["myhost.lan"], config.qualify("myhost"));
+    }
+
+    #[cfg(feature = "async")]
+    #[tokio::test]
+    async fn it_parse_async() {
+        let config = parse_async(Cursor::new("nameserver 127.0.0.53\noptions rotate"))
+            .await
+            .unwrap();
+
+        assert_eq!(
+            vec!["127.0.0.53".parse::<Nameserver>().unwrap()],
+            config.nameservers
+        );
+        assert_eq!(vec![ConfigOption::ROTATE], config.options);
+    }
+
+    #[cfg(feature = "hickory")]
+    #[test]
+    fn it_converts_to_hickory() {
+        use hickory_resolver::config::Protocol;
+
+        let (config, opts) = parse(Cursor::new(
+            r#"
+nameserver 127.0.0.53
+search corp.example.com
+options ndots:3 timeout:2 attempts:4 rotate use-vc
+        "#,
+        ))
+        .unwrap()
+        .to_hickory();
+
+        assert_eq!(1, config.name_servers().len());
+        assert_eq!(
+            "127.0.0.53:53".parse::<net::SocketAddr>().unwrap(),
+            config.name_servers()[0].socket_addr
+        );
+        assert_eq!(Protocol::Tcp, config.name_servers()[0].protocol);
+        assert_eq!(1, config.search().len());
+        assert_eq!("corp.example.com", config.search()[0].to_string());
+
+        assert_eq!(3, opts.ndots);
+        assert_eq!(std::time::Duration::from_secs(2), opts.timeout);
+        assert_eq!(4, opts.attempts);
+        assert!(opts.rotate);
+    }
+
+    #[test]
+    fn it_parse_nameserver_suffixes() {
+        let config = parse(Cursor::new(
+            r#"
+nameserver 127.0.0.1:5353
+nameserver ::1
+nameserver fe80::1%eth0
+nameserver [fe80::1%2]:5353
+nameserver [2001:db8::1]
+        "#,
+        ))
+        .unwrap();
+
+        assert_eq!(
+            vec![
+                Nameserver {
+                    ip: "127.0.0.1".parse().unwrap(),
+                    port: Some(5353),
+                    scope: None
+                },
+                Nameserver::new("::1".parse().unwrap()),
+                Nameserver {
+                    ip: "fe80::1".parse().unwrap(),
+                    port: None,
+                    scope: Some("eth0".to_owned())
+                },
+                Nameserver {
+                    ip: "fe80::1".parse().unwrap(),
+                    port: Some(5353),
+                    scope: Some("2".to_owned())
+                },
+                Nameserver::new("2001:db8::1".parse().unwrap()),
+            ],
+            config.nameservers
+        );
+
+        assert_eq!(
+            vec![
+                "127.0.0.1:5353",
+                "::1",
+                "fe80::1%eth0",
+                "[fe80::1%2]:5353",
+                "2001:db8::1"
+            ],
+            config
+                .nameservers
+                .iter()
+                .map(ToString::to_string)
+                .collect::<Vec<_>>()
+        );
+        assert_eq!(
+            "[fe80::1%2]:5353".parse::<net::SocketAddr>().unwrap(),
+            config.nameservers[3].socket_addr().unwrap()
+        );
+        assert!(Nameserver {
+            ip: "fe80::1".parse().unwrap(),
+            port: None,
+            scope: Some("unixism-nonexistent".to_owned())
+        }
+        .socket_addr()
+        .is_err());
+
+        if std::path::Path::new("/sys/class/net/lo").exists() {
+            let lo = Nameserver {
+                ip: "fe80::1".parse().unwrap(),
+                port: None,
+                scope: Some("lo".to_owned()),
+            };
+            match lo.socket_addr().unwrap() {
+                net::SocketAddr::V6(addr) => assert_ne!(0, addr.scope_id()),
+                addr => panic!("unexpected address: {}", addr),
+            }
+        }
+
+        assert!("[::1".parse::<Nameserver>().is_err());
+        assert!("fe80::1%".parse::<Nameserver>().is_err());
+        assert!("127.0.0.1:dns".parse::<Nameserver>().is_err());
+    }
+
+    #[test]
+    fn it_display() {
+        let lines = [
+            "nameserver 127.0.0.53",
+            "domain example.com",
+            "search corp.example.com example.com",
+            "sortlist 130.155.160.0/255.255.240.0 130.155.0.0",
+            "options ndots:3 timeout:5 attempts:2 rotate no-aaaa no-check-names inet6 ip6-bytestring ip6-dotint no-ip6-dotint edns0 single-request single-request-reopen no-tld-query use-vc no-reload trust-ad debug",
+        ];
+
+        for line in lines {
+            assert_eq!(line, line.parse::<ConfigItem>().unwrap().to_string());
+        }
+
+        assert_eq!(
+            "options vendor vendor-flag:2",
+            ConfigItem::parse("options vendor vendor-flag:2", true)
+                .unwrap()
+                .to_string()
+        );
+    }
 }
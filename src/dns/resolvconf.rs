@@ -0,0 +1,288 @@
+use std::{
+    fs,
+    io::{self, BufRead, BufReader},
+    path::Path,
+};
+
+use super::resolv::{self, Config, ParseConfigError};
+
+/// Default interface order used by openresolv when `interface_order` is not set.
+pub const DEFAULT_INTERFACE_ORDER: [&str; 8] = [
+    "lo", "lo[0-9]*", "lo.*", "tun*", "tap*", "hso*", "ppp*", "wg*",
+];
+
+/// A resolv.conf fragment contributed by a single interface.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct InterfaceConfig {
+    pub interface: String,
+    pub config: Config,
+}
+
+/// Variables of the `/etc/resolvconf.conf` file.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct Settings {
+    pub variables: Vec<(String, String)>,
+}
+
+impl Settings {
+    pub fn get(&self, name: &str) -> Option<&str> {
+        self.variables
+            .iter()
+            .rev()
+            .find(|(key, _)| key == name)
+            .map(|(_, value)| value.as_str())
+    }
+
+    pub fn resolv_conf(&self) -> &str {
+        self.get("resolv_conf").unwrap_or("/etc/resolv.conf")
+    }
+
+    pub fn name_servers(&self) -> Vec<String> {
+        self.list("name_servers")
+    }
+
+    pub fn search_domains(&self) -> Vec<String> {
+        self.list("search_domains")
+    }
+
+    pub fn interface_order(&self) -> Vec<String> {
+        match self.get("interface_order") {
+            Some(order) => order.split_whitespace().map(String::from).collect(),
+            None => DEFAULT_INTERFACE_ORDER.map(String::from).to_vec(),
+        }
+    }
+
+    pub fn dynamic_order(&self) -> Vec<String> {
+        self.list("dynamic_order")
+    }
+
+    fn list(&self, name: &str) -> Vec<String> {
+        self.get(name)
+            .unwrap_or_default()
+            .split_whitespace()
+            .map(String::from)
+            .collect()
+    }
+}
+
+///
+/// Parses the `/etc/resolvconf.conf` shell variables.
+///
+/// ```no_run
+/// let settings = unixism::dns::resolvconf::parse(std::fs::File::open("/etc/resolvconf.conf").unwrap()).unwrap();
+///
+/// println!("{}", settings.resolv_conf());
+///
+/// for nameserver in settings.name_servers() {
+///     println!("{nameserver}");
+/// }
+/// ```
+pub fn parse<R>(reader: R) -> Result<Settings, ParseConfigError>
+where
+    R: io::Read,
+{
+    let mut settings = Settings::default();
+
+    for line in BufReader::new(reader).lines() {
+        let line = line?;
+        let line = line.trim();
+
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+
+        let (name, value) = line
+            .split_once('=')
+            .ok_or_else(|| ParseConfigError::UnknownOption(line.to_owned()))?;
+
+        settings
+            .variables
+            .push((name.trim().to_owned(), unquote(value.trim())));
+    }
+
+    Ok(settings)
+}
+
+///
+/// Same as parse, but parses the `/etc/resolvconf.conf` as default.
+///
+/// ```no_run
+/// let settings = unixism::dns::resolvconf::parse_default().unwrap();
+/// ```
+pub fn parse_default() -> Result<Settings, ParseConfigError> {
+    parse(fs::File::open("/etc/resolvconf.conf")?)
+}
+
+///
+/// Parses every per-interface resolv.conf fragment in `dir`, sorted by file name.
+/// Unknown options are kept, as fragments often come from patched DHCP clients.
+///
+/// ```no_run
+/// for fragment in unixism::dns::resolvconf::parse_interfaces("/run/resolvconf/interface").unwrap() {
+///     println!("{}: {:?}", fragment.interface, fragment.config.nameservers);
+/// }
+/// ```
+pub fn parse_interfaces<P>(dir: P) -> Result<Vec<InterfaceConfig>, ParseConfigError>
+where
+    P: AsRef<Path>,
+{
+    let mut interfaces = Vec::new();
+
+    for entry in fs::read_dir(dir)? {
+        let entry = entry?;
+
+        if !entry.file_type()?.is_file() {
+            continue;
+        }
+
+        interfaces.push(InterfaceConfig {
+            interface: entry.file_name().to_string_lossy().into_owned(),
+            config: resolv::parse_lenient(fs::File::open(entry.path())?)?,
+        });
+    }
+
+    interfaces.sort_by(|a, b| a.interface.cmp(&b.interface));
+
+    Ok(interfaces)
+}
+
+///
+/// Same as parse_interfaces, but reads the `/run/resolvconf/interface` as default.
+///
+/// ```no_run
+/// let fragments = unixism::dns::resolvconf::parse_interfaces_default().unwrap();
+/// ```
+pub fn parse_interfaces_default() -> Result<Vec<InterfaceConfig>, ParseConfigError> {
+    parse_interfaces("/run/resolvconf/interface")
+}
+
+///
+/// Orders the fragments the way openresolv does: interfaces matching `order` patterns first,
+/// in pattern order, then the rest in their current order.
+///
+/// ```no_run
+/// use unixism::dns::{resolv, resolvconf};
+///
+/// let settings = resolvconf::parse_default().unwrap();
+/// let mut fragments = resolvconf::parse_interfaces_default().unwrap();
+///
+/// resolvconf::sort_interfaces(&mut fragments, &settings.interface_order());
+///
+/// let configs = fragments.into_iter().map(|fragment| fragment.config).collect::<Vec<_>>();
+/// let config = resolv::merge(&configs, resolv::MergeStrategy::First);
+/// ```
+pub fn sort_interfaces(interfaces: &mut [InterfaceConfig], order: &[String]) {
+    interfaces.sort_by_key(|interface| {
+        order
+            .iter()
+            .position(|pattern| matches(pattern, &interface.interface))
+            .unwrap_or(order.len())
+    });
+}
+
+/// Matches shell-like patterns supporting `*` and `[...]` classes of single characters.
+fn matches(pattern: &str, name: &str) -> bool {
+    let pattern = pattern.chars().collect::<Vec<_>>();
+    let name = name.chars().collect::<Vec<_>>();
+
+    fn go(pattern: &[char], name: &[char]) -> bool {
+        match pattern.first() {
+            None => name.is_empty(),
+            Some('*') => (0..=name.len()).any(|i| go(&pattern[1..], &name[i..])),
+            Some('[') => match pattern.iter().position(|c| *c == ']') {
+                Some(end) if !name.is_empty() => {
+                    class_matches(&pattern[1..end], name[0]) && go(&pattern[end + 1..], &name[1..])
+                }
+                _ => false,
+            },
+            Some(c) => name.first() == Some(c) && go(&pattern[1..], &name[1..]),
+        }
+    }
+
+    fn class_matches(class: &[char], c: char) -> bool {
+        let mut i = 0;
+
+        while i < class.len() {
+            if i + 2 < class.len() && class[i + 1] == '-' {
+                if class[i] <= c && c <= class[i + 2] {
+                    return true;
+                }
+                i += 3;
+            } else {
+                if class[i] == c {
+                    return true;
+                }
+                i += 1;
+            }
+        }
+
+        false
+    }
+
+    go(&pattern, &name)
+}
+
+fn unquote(value: &str) -> String {
+    for quote in ['"', '\''] {
+        if let Some(inner) = value
+            .strip_prefix(quote)
+            .and_then(|value| value.strip_suffix(quote))
+        {
+            return inner.to_owned();
+        }
+    }
+
+    value.to_owned()
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Cursor;
+
+    use super::*;
+
+    #[test]
+    fn it_parse() {
+        let settings = parse(Cursor::new(
+            r#"
+# Configuration for resolvconf(8)
+resolv_conf=/etc/resolv.conf
+name_servers="127.0.0.1 ::1"
+search_domains='corp lan'
+interface_order="lo tun* eth*"
+        "#,
+        ))
+        .unwrap();
+
+        assert_eq!("/etc/resolv.conf", settings.resolv_conf());
+        assert_eq!(vec!["127.0.0.1", "::1"], settings.name_servers());
+        assert_eq!(vec!["corp", "lan"], settings.search_domains());
+        assert_eq!(vec!["lo", "tun*", "eth*"], settings.interface_order());
+        assert!(settings.dynamic_order().is_empty());
+    }
+
+    #[test]
+    fn it_sort_interfaces() {
+        let fragment = |interface: &str| InterfaceConfig {
+            interface: interface.to_owned(),
+            config: Config::default(),
+        };
+
+        let mut interfaces = vec![
+            fragment("eth0.dhcp"),
+            fragment("wg0"),
+            fragment("lo1"),
+            fragment("tun0"),
+        ];
+
+        sort_interfaces(&mut interfaces, &Settings::default().interface_order());
+
+        assert_eq!(
+            vec!["lo1", "tun0", "wg0", "eth0.dhcp"],
+            interfaces
+                .iter()
+                .map(|interface| interface.interface.as_str())
+                .collect::<Vec<_>>()
+        );
+    }
+}
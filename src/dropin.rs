@@ -0,0 +1,60 @@
+use std::{
+    collections::BTreeMap,
+    fs, io,
+    path::{Path, PathBuf},
+};
+
+///
+/// Collects the systemd style drop-in files of `dirs` ending with `suffix`, ordered by file name.
+/// A file name found in several directories is taken from the first one, so `dirs` go from
+/// the most to the least important, e.g. `/etc` before `/run` before `/usr/lib`.
+/// Missing directories are skipped.
+pub(crate) fn files<P>(dirs: &[P], suffix: &str) -> io::Result<Vec<PathBuf>>
+where
+    P: AsRef<Path>,
+{
+    let mut files = BTreeMap::new();
+
+    for dir in dirs {
+        let entries = match fs::read_dir(dir) {
+            Ok(entries) => entries,
+            Err(error) if error.kind() == io::ErrorKind::NotFound => continue,
+            Err(error) => return Err(error),
+        };
+
+        for entry in entries {
+            let entry = entry?;
+            let name = entry.file_name();
+
+            if name.to_str().is_some_and(|name| name.ends_with(suffix)) && !entry.path().is_dir() {
+                files.entry(name).or_insert_with(|| entry.path());
+            }
+        }
+    }
+
+    Ok(files.into_values().collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tempdir::TempDir;
+
+    #[test]
+    fn it_files() {
+        let root = TempDir::new("dropin");
+        let (etc, usr) = (root.join("etc"), root.join("usr"));
+        fs::create_dir_all(&etc).unwrap();
+        fs::create_dir_all(&usr).unwrap();
+
+        fs::write(usr.join("10-vendor.conf"), "").unwrap();
+        fs::write(usr.join("50-default.conf"), "").unwrap();
+        fs::write(etc.join("50-default.conf"), "").unwrap();
+        fs::write(etc.join("README"), "").unwrap();
+
+        assert_eq!(
+            vec![usr.join("10-vendor.conf"), etc.join("50-default.conf")],
+            files(&[&etc, &root.join("run"), &usr], ".conf").unwrap()
+        );
+    }
+}
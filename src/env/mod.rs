@@ -0,0 +1,346 @@
+use std::{
+    fmt, fs,
+    path::{Path, PathBuf},
+};
+
+use crate::{cgroup, proc::process};
+
+/// What the system runs on, from the most specific hint found.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Runtime {
+    BareMetal,
+    Docker,
+    Podman,
+    Lxc,
+    Nspawn,
+    Kubernetes,
+    /// A container of another engine, named by its `container` variable.
+    Container(String),
+    Wsl,
+    Kvm,
+    Qemu,
+    VMware,
+    VirtualBox,
+    HyperV,
+    Xen,
+    Amazon,
+    Google,
+    /// A virtual machine of another hypervisor, named by `/sys/hypervisor/type`.
+    VirtualMachine(String),
+}
+
+impl Runtime {
+    pub fn is_container(&self) -> bool {
+        matches!(
+            self,
+            Runtime::Docker
+                | Runtime::Podman
+                | Runtime::Lxc
+                | Runtime::Nspawn
+                | Runtime::Kubernetes
+                | Runtime::Container(_)
+        )
+    }
+
+    pub fn is_virtual_machine(&self) -> bool {
+        matches!(
+            self,
+            Runtime::Wsl
+                | Runtime::Kvm
+                | Runtime::Qemu
+                | Runtime::VMware
+                | Runtime::VirtualBox
+                | Runtime::HyperV
+                | Runtime::Xen
+                | Runtime::Amazon
+                | Runtime::Google
+                | Runtime::VirtualMachine(_)
+        )
+    }
+}
+
+impl From<&str> for Runtime {
+    /// Maps the `container` variable systemd and container engines set for init.
+    fn from(s: &str) -> Self {
+        match s {
+            "docker" => Runtime::Docker,
+            "podman" => Runtime::Podman,
+            "lxc" | "lxc-libvirt" => Runtime::Lxc,
+            "systemd-nspawn" => Runtime::Nspawn,
+            container => Runtime::Container(container.to_owned()),
+        }
+    }
+}
+
+impl fmt::Display for Runtime {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Runtime::BareMetal => write!(f, "none"),
+            Runtime::Docker => write!(f, "docker"),
+            Runtime::Podman => write!(f, "podman"),
+            Runtime::Lxc => write!(f, "lxc"),
+            Runtime::Nspawn => write!(f, "systemd-nspawn"),
+            Runtime::Kubernetes => write!(f, "kubernetes"),
+            Runtime::Container(name) => write!(f, "{}", name),
+            Runtime::Wsl => write!(f, "wsl"),
+            Runtime::Kvm => write!(f, "kvm"),
+            Runtime::Qemu => write!(f, "qemu"),
+            Runtime::VMware => write!(f, "vmware"),
+            Runtime::VirtualBox => write!(f, "oracle"),
+            Runtime::HyperV => write!(f, "microsoft"),
+            Runtime::Xen => write!(f, "xen"),
+            Runtime::Amazon => write!(f, "amazon"),
+            Runtime::Google => write!(f, "google"),
+            Runtime::VirtualMachine(name) => write!(f, "{}", name),
+        }
+    }
+}
+
+/// Reads the files relative to the root the system is inspected from.
+struct Probe {
+    root: PathBuf,
+}
+
+impl Probe {
+    fn path(&self, path: &str) -> PathBuf {
+        self.root.join(path.trim_start_matches('/'))
+    }
+
+    fn read(&self, path: &str) -> Option<String> {
+        fs::read_to_string(self.path(path))
+            .ok()
+            .map(|content| content.trim().to_owned())
+            .filter(|content| !content.is_empty())
+    }
+
+    fn container(&self) -> Option<Runtime> {
+        if self.path("/.dockerenv").exists() {
+            return Some(Runtime::Docker);
+        }
+
+        if self.path("/run/.containerenv").exists() {
+            return Some(Runtime::Podman);
+        }
+
+        // Written by systemd from the `container` variable, readable without privileges.
+        if let Some(container) = self.read("/run/systemd/container") {
+            return Some(Runtime::from(container.as_str()));
+        }
+
+        let environ = fs::File::open(self.path("/proc/1/environ"))
+            .ok()
+            .and_then(|file| process::parse_environ(file).ok());
+
+        if let Some(container) = environ
+            .as_ref()
+            .and_then(|environ| environ.get(std::ffi::OsStr::new("container")))
+            .and_then(|container| container.to_str())
+        {
+            return Some(Runtime::from(container));
+        }
+
+        let entries = fs::File::open(self.path("/proc/1/cgroup"))
+            .ok()
+            .and_then(|file| cgroup::parse(file).ok())
+            .into_iter()
+            .flatten();
+
+        for entry in entries {
+            if entry.path.contains("kubepods") {
+                return Some(Runtime::Kubernetes);
+            }
+
+            if entry.path.contains("/docker/") || entry.path.contains("/docker-") {
+                return Some(Runtime::Docker);
+            }
+
+            if entry.path.contains("libpod") {
+                return Some(Runtime::Podman);
+            }
+
+            if entry.path.starts_with("/lxc/") || entry.path.contains("/lxc.payload") {
+                return Some(Runtime::Lxc);
+            }
+        }
+
+        None
+    }
+
+    fn wsl(&self) -> bool {
+        self.read("/proc/version")
+            .is_some_and(|version| version.to_lowercase().contains("microsoft"))
+    }
+
+    fn virtual_machine(&self) -> Option<Runtime> {
+        let dmi = |file: &str| {
+            self.read(&format!("/sys/class/dmi/id/{}", file))
+                .unwrap_or_default()
+        };
+        let (vendor, product, bios) = (dmi("sys_vendor"), dmi("product_name"), dmi("bios_vendor"));
+
+        if vendor.contains("Microsoft") && product.contains("Virtual Machine") {
+            return Some(Runtime::HyperV);
+        }
+
+        let hints = [
+            (&product, "KVM", Runtime::Kvm),
+            (&vendor, "KVM", Runtime::Kvm),
+            (&vendor, "QEMU", Runtime::Qemu),
+            (&vendor, "VMware", Runtime::VMware),
+            (&vendor, "innotek", Runtime::VirtualBox),
+            (&product, "VirtualBox", Runtime::VirtualBox),
+            (&vendor, "Xen", Runtime::Xen),
+            (&bios, "Xen", Runtime::Xen),
+            (&vendor, "Amazon EC2", Runtime::Amazon),
+            (&product, "Google Compute Engine", Runtime::Google),
+        ];
+
+        if let Some((_, _, runtime)) = hints
+            .into_iter()
+            .find(|(field, needle, _)| field.contains(needle))
+        {
+            return Some(runtime);
+        }
+
+        match self.read("/sys/hypervisor/type")?.as_str() {
+            "xen" => Some(Runtime::Xen),
+            hypervisor => Some(Runtime::VirtualMachine(hypervisor.to_owned())),
+        }
+    }
+}
+
+/// Same as detect, but inspects the system under `root` instead of `/`.
+pub fn detect_at<P>(root: P) -> Runtime
+where
+    P: AsRef<Path>,
+{
+    let probe = Probe {
+        root: root.as_ref().to_path_buf(),
+    };
+
+    if let Some(container) = probe.container() {
+        return container;
+    }
+
+    if probe.wsl() {
+        return Runtime::Wsl;
+    }
+
+    probe.virtual_machine().unwrap_or(Runtime::BareMetal)
+}
+
+///
+/// Classifies the runtime from the container markers, the cgroup and environment of init,
+/// `/proc/version` and the DMI tables, like `systemd-detect-virt` does.
+/// Containers win over the virtual machine they may run in. Unreadable hints are skipped.
+///
+/// ```no_run
+/// let runtime = unixism::env::detect();
+///
+/// if runtime.is_container() {
+///     println!("running in {}", runtime);
+/// }
+/// ```
+pub fn detect() -> Runtime {
+    detect_at("/")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tempdir::TempDir;
+
+    fn root(name: &str, files: &[(&str, &str)]) -> TempDir {
+        let root = TempDir::new(&format!("env-{}", name));
+
+        for (path, content) in files {
+            let path = root.join(path);
+            fs::create_dir_all(path.parent().unwrap()).unwrap();
+            fs::write(path, content).unwrap();
+        }
+
+        root
+    }
+
+    #[test]
+    fn it_detect_at() {
+        for (name, files, expected) in [
+            (
+                "bare",
+                vec![("sys/class/dmi/id/sys_vendor", "Dell Inc.\n")],
+                Runtime::BareMetal,
+            ),
+            ("docker", vec![(".dockerenv", "")], Runtime::Docker),
+            (
+                "podman",
+                vec![("run/.containerenv", "engine=\"podman\"\n")],
+                Runtime::Podman,
+            ),
+            (
+                "nspawn",
+                vec![("run/systemd/container", "systemd-nspawn\n")],
+                Runtime::Nspawn,
+            ),
+            (
+                "environ",
+                vec![("proc/1/environ", "HOME=/\0container=lxc\0")],
+                Runtime::Lxc,
+            ),
+            (
+                "k8s",
+                vec![("proc/1/cgroup", "0::/kubepods/burstable/pod1/abc\n")],
+                Runtime::Kubernetes,
+            ),
+            (
+                "wsl",
+                vec![
+                    (
+                        "proc/version",
+                        "Linux version 5.15.90.1-microsoft-standard-WSL2\n",
+                    ),
+                    ("sys/class/dmi/id/sys_vendor", "Microsoft Corporation\n"),
+                    ("sys/class/dmi/id/product_name", "Virtual Machine\n"),
+                ],
+                Runtime::Wsl,
+            ),
+            (
+                "hyperv",
+                vec![
+                    ("sys/class/dmi/id/sys_vendor", "Microsoft Corporation\n"),
+                    ("sys/class/dmi/id/product_name", "Virtual Machine\n"),
+                ],
+                Runtime::HyperV,
+            ),
+            (
+                "kvm",
+                vec![
+                    ("sys/class/dmi/id/sys_vendor", "QEMU\n"),
+                    ("sys/class/dmi/id/product_name", "KVM\n"),
+                ],
+                Runtime::Kvm,
+            ),
+            (
+                "qemu",
+                vec![("sys/class/dmi/id/sys_vendor", "QEMU\n")],
+                Runtime::Qemu,
+            ),
+            (
+                "docker-in-vm",
+                vec![
+                    ("proc/1/cgroup", "12:cpu:/docker/abc\n0::/\n"),
+                    ("sys/class/dmi/id/sys_vendor", "VMware, Inc.\n"),
+                ],
+                Runtime::Docker,
+            ),
+        ] {
+            let root = root(name, &files);
+
+            assert_eq!(expected, detect_at(&root), "{}", name);
+        }
+
+        assert!(Runtime::Podman.is_container());
+        assert!(Runtime::Wsl.is_virtual_machine());
+        assert!(!Runtime::BareMetal.is_container());
+        assert_eq!("systemd-nspawn", Runtime::Nspawn.to_string());
+    }
+}
@@ -0,0 +1,204 @@
+use std::{
+    error, fmt, fs,
+    io::{self, BufRead, BufReader},
+    num::ParseIntError,
+    str::FromStr,
+};
+
+/// A 48-bit Ethernet hardware address.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct MacAddr(pub [u8; 6]);
+
+impl MacAddr {
+    pub fn octets(&self) -> [u8; 6] {
+        self.0
+    }
+
+    pub fn is_broadcast(&self) -> bool {
+        self.0 == [0xff; 6]
+    }
+
+    /// Whether the address is a group address, the least significant bit of the first octet is set.
+    pub fn is_multicast(&self) -> bool {
+        self.0[0] & 0x01 != 0
+    }
+
+    /// Whether the address is locally administered rather than assigned by a vendor.
+    pub fn is_local(&self) -> bool {
+        self.0[0] & 0x02 != 0
+    }
+}
+
+impl FromStr for MacAddr {
+    type Err = ParseEthersError;
+
+    /// Accepts colon or dash separated octets of one or two hex digits, like `ether_aton(3)`.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let parts = s.split([':', '-']).collect::<Vec<_>>();
+
+        if parts.len() != 6 || parts.iter().any(|part| part.is_empty() || part.len() > 2) {
+            return Err(ParseEthersError::InvalidMacAddr(s.to_owned()));
+        }
+
+        let mut octets = [0u8; 6];
+        for (octet, part) in octets.iter_mut().zip(parts) {
+            *octet = u8::from_str_radix(part, 16)?;
+        }
+
+        Ok(MacAddr(octets))
+    }
+}
+
+impl fmt::Display for MacAddr {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let [a, b, c, d, e, g] = self.0;
+
+        write!(
+            f,
+            "{:02x}:{:02x}:{:02x}:{:02x}:{:02x}:{:02x}",
+            a, b, c, d, e, g
+        )
+    }
+}
+
+/// A line of `/etc/ethers`, the host is a host name or an IP address.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Entry {
+    pub mac: MacAddr,
+    pub host: String,
+}
+
+impl FromStr for Entry {
+    type Err = ParseEthersError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let fields = s.split_whitespace().collect::<Vec<_>>();
+
+        let [mac, host] = fields[..] else {
+            return Err(ParseEthersError::InvalidEntry(s.to_owned()));
+        };
+
+        Ok(Entry {
+            mac: mac.parse()?,
+            host: host.to_owned(),
+        })
+    }
+}
+
+impl fmt::Display for Entry {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} {}", self.mac, self.host)
+    }
+}
+
+#[derive(Debug)]
+pub enum ParseEthersError {
+    InvalidEntry(String),
+    InvalidMacAddr(String),
+    ParseIntError(ParseIntError),
+    IOError(io::Error),
+}
+
+impl error::Error for ParseEthersError {}
+
+impl fmt::Display for ParseEthersError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::InvalidEntry(entry) => write!(f, "invalid ethers entry: {}", entry),
+            Self::InvalidMacAddr(mac) => write!(f, "invalid MAC address: {}", mac),
+            Self::ParseIntError(error) => write!(f, "{}", error),
+            Self::IOError(error) => write!(f, "{}", error),
+        }
+    }
+}
+
+impl From<ParseIntError> for ParseEthersError {
+    fn from(value: ParseIntError) -> Self {
+        ParseEthersError::ParseIntError(value)
+    }
+}
+
+impl From<io::Error> for ParseEthersError {
+    fn from(value: io::Error) -> Self {
+        ParseEthersError::IOError(value)
+    }
+}
+
+///
+/// ```no_run
+/// let entries = unixism::ethers::parse(std::fs::File::open("/etc/ethers").unwrap()).unwrap();
+///
+/// for entry in entries {
+///     println!("{} is {}", entry.mac, entry.host);
+/// }
+/// ```
+pub fn parse<R>(reader: R) -> Result<impl Iterator<Item = Entry>, ParseEthersError>
+where
+    R: io::Read,
+{
+    let mut entries = Vec::new();
+
+    for line in BufReader::new(reader).lines() {
+        let line = line?;
+        let line = line.split('#').next().unwrap_or_default().trim();
+
+        if line.is_empty() {
+            continue;
+        }
+
+        entries.push(line.parse::<Entry>()?);
+    }
+
+    Ok(entries.into_iter())
+}
+
+///
+/// Same as parse, but parses the `/etc/ethers` as default.
+///
+/// ```no_run
+/// let entries = unixism::ethers::parse_default().unwrap();
+/// ```
+pub fn parse_default() -> Result<impl Iterator<Item = Entry>, ParseEthersError> {
+    parse(fs::File::open("/etc/ethers")?)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Cursor;
+
+    use super::*;
+
+    #[test]
+    fn it_parse() {
+        let entries = parse(Cursor::new(
+            r#"
+# MAC address to host name mapping
+8:0:20:1:2:3        printer.lan
+52-54-00-AB-cd-EF   192.168.1.10   # virtual machine
+        "#,
+        ))
+        .unwrap()
+        .collect::<Vec<_>>();
+
+        assert_eq!(2, entries.len());
+        assert_eq!(
+            Entry {
+                mac: MacAddr([0x08, 0x00, 0x20, 0x01, 0x02, 0x03]),
+                host: "printer.lan".to_owned(),
+            },
+            entries[0]
+        );
+        assert_eq!("52:54:00:ab:cd:ef 192.168.1.10", entries[1].to_string());
+        assert!(entries[1].mac.is_local());
+        assert!(!entries[1].mac.is_multicast());
+        assert!("ff:ff:ff:ff:ff:ff"
+            .parse::<MacAddr>()
+            .unwrap()
+            .is_broadcast());
+
+        assert!(parse(Cursor::new("8:0:20:1:2 printer")).is_err());
+        assert!(parse(Cursor::new("8:0:20:1:2:3")).is_err());
+        assert!(parse(Cursor::new("8:0:20:1:2:zz printer")).is_err());
+        assert!(parse(Cursor::new("8:0:20:1:2:333 printer")).is_err());
+    }
+}
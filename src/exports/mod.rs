@@ -0,0 +1,474 @@
+use std::{
+    error, fmt, fs,
+    io::{self, BufRead, BufReader},
+    net::IpAddr,
+    num::ParseIntError,
+    str::FromStr,
+};
+
+/// An option of an export client, the list is the one documented in exports(5).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ExportOption {
+    Rw,
+    Ro,
+    Sync,
+    Async,
+    Secure,
+    Insecure,
+    Wdelay,
+    NoWdelay,
+    Hide,
+    Nohide,
+    Crossmnt,
+    SubtreeCheck,
+    NoSubtreeCheck,
+    RootSquash,
+    NoRootSquash,
+    AllSquash,
+    NoAllSquash,
+    Fsid(String),
+    Anonuid(u32),
+    Anongid(u32),
+    Sec(Vec<String>),
+    /// Options with a value unknown to this crate.
+    Value {
+        name: String,
+        value: String,
+    },
+    /// Flags unknown to this crate.
+    Flag(String),
+}
+
+impl FromStr for ExportOption {
+    type Err = ParseExportsError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let option = match s.split_once('=') {
+            None => match s {
+                "rw" => ExportOption::Rw,
+                "ro" => ExportOption::Ro,
+                "sync" => ExportOption::Sync,
+                "async" => ExportOption::Async,
+                "secure" => ExportOption::Secure,
+                "insecure" => ExportOption::Insecure,
+                "wdelay" => ExportOption::Wdelay,
+                "no_wdelay" => ExportOption::NoWdelay,
+                "hide" => ExportOption::Hide,
+                "nohide" => ExportOption::Nohide,
+                "crossmnt" => ExportOption::Crossmnt,
+                "subtree_check" => ExportOption::SubtreeCheck,
+                "no_subtree_check" => ExportOption::NoSubtreeCheck,
+                "root_squash" => ExportOption::RootSquash,
+                "no_root_squash" => ExportOption::NoRootSquash,
+                "all_squash" => ExportOption::AllSquash,
+                "no_all_squash" => ExportOption::NoAllSquash,
+                flag => ExportOption::Flag(flag.to_owned()),
+            },
+            Some(("fsid", fsid)) => ExportOption::Fsid(fsid.to_owned()),
+            Some(("anonuid", uid)) => ExportOption::Anonuid(uid.parse()?),
+            Some(("anongid", gid)) => ExportOption::Anongid(gid.parse()?),
+            Some(("sec", flavors)) => {
+                ExportOption::Sec(flavors.split(':').map(String::from).collect())
+            }
+            Some((name, value)) => ExportOption::Value {
+                name: name.to_owned(),
+                value: value.to_owned(),
+            },
+        };
+
+        Ok(option)
+    }
+}
+
+impl fmt::Display for ExportOption {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ExportOption::Rw => write!(f, "rw"),
+            ExportOption::Ro => write!(f, "ro"),
+            ExportOption::Sync => write!(f, "sync"),
+            ExportOption::Async => write!(f, "async"),
+            ExportOption::Secure => write!(f, "secure"),
+            ExportOption::Insecure => write!(f, "insecure"),
+            ExportOption::Wdelay => write!(f, "wdelay"),
+            ExportOption::NoWdelay => write!(f, "no_wdelay"),
+            ExportOption::Hide => write!(f, "hide"),
+            ExportOption::Nohide => write!(f, "nohide"),
+            ExportOption::Crossmnt => write!(f, "crossmnt"),
+            ExportOption::SubtreeCheck => write!(f, "subtree_check"),
+            ExportOption::NoSubtreeCheck => write!(f, "no_subtree_check"),
+            ExportOption::RootSquash => write!(f, "root_squash"),
+            ExportOption::NoRootSquash => write!(f, "no_root_squash"),
+            ExportOption::AllSquash => write!(f, "all_squash"),
+            ExportOption::NoAllSquash => write!(f, "no_all_squash"),
+            ExportOption::Fsid(fsid) => write!(f, "fsid={}", fsid),
+            ExportOption::Anonuid(uid) => write!(f, "anonuid={}", uid),
+            ExportOption::Anongid(gid) => write!(f, "anongid={}", gid),
+            ExportOption::Sec(flavors) => write!(f, "sec={}", flavors.join(":")),
+            ExportOption::Value { name, value } => write!(f, "{}={}", name, value),
+            ExportOption::Flag(flag) => write!(f, "{}", flag),
+        }
+    }
+}
+
+/// Machines an export is shared with.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ClientSpec {
+    /// Options without a host, which export to the world.
+    Anyone,
+    Host(String),
+    /// Host names with `*`, `?` or `[...]` wildcards.
+    Wildcard(String),
+    /// An IP address or a network in the `address/prefix` or `address/netmask` form.
+    Network(String),
+    Netgroup(String),
+}
+
+impl From<&str> for ClientSpec {
+    fn from(s: &str) -> Self {
+        if s.is_empty() {
+            ClientSpec::Anyone
+        } else if let Some(netgroup) = s.strip_prefix('@') {
+            ClientSpec::Netgroup(netgroup.to_owned())
+        } else if s.contains(['*', '?', '[']) {
+            ClientSpec::Wildcard(s.to_owned())
+        } else if s.contains('/') || s.parse::<IpAddr>().is_ok() {
+            ClientSpec::Network(s.to_owned())
+        } else {
+            ClientSpec::Host(s.to_owned())
+        }
+    }
+}
+
+impl fmt::Display for ClientSpec {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ClientSpec::Anyone => Ok(()),
+            ClientSpec::Host(host) | ClientSpec::Wildcard(host) | ClientSpec::Network(host) => {
+                write!(f, "{}", host)
+            }
+            ClientSpec::Netgroup(netgroup) => write!(f, "@{}", netgroup),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Client {
+    pub spec: ClientSpec,
+    pub options: Vec<ExportOption>,
+}
+
+impl FromStr for Client {
+    type Err = ParseExportsError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (spec, options) = match s.split_once('(') {
+            Some((spec, options)) => (
+                spec,
+                options
+                    .strip_suffix(')')
+                    .ok_or_else(|| ParseExportsError::InvalidEntry(s.to_owned()))?,
+            ),
+            None => (s, ""),
+        };
+
+        Ok(Client {
+            spec: ClientSpec::from(spec),
+            options: parse_options(options)?,
+        })
+    }
+}
+
+impl fmt::Display for Client {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.spec)?;
+
+        if !self.options.is_empty() || self.spec == ClientSpec::Anyone {
+            write!(f, "({})", join_options(&self.options))?;
+        }
+
+        Ok(())
+    }
+}
+
+/// A line of the `/etc/exports`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Export {
+    pub path: String,
+    /// Options given as `-option` after the path, applied to every client.
+    pub default_options: Vec<ExportOption>,
+    pub clients: Vec<Client>,
+}
+
+impl FromStr for Export {
+    type Err = ParseExportsError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut words = split_words(s).into_iter();
+
+        let path = words
+            .next()
+            .ok_or_else(|| ParseExportsError::InvalidEntry(s.to_owned()))?;
+
+        let mut export = Export {
+            path,
+            default_options: Vec::new(),
+            clients: Vec::new(),
+        };
+
+        for word in words {
+            match word.strip_prefix('-') {
+                Some(options) => export.default_options.extend(parse_options(options)?),
+                None => export.clients.push(word.parse()?),
+            }
+        }
+
+        Ok(export)
+    }
+}
+
+impl fmt::Display for Export {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.path.contains(char::is_whitespace) {
+            write!(f, "\"{}\"", self.path)?;
+        } else {
+            write!(f, "{}", self.path)?;
+        }
+
+        if !self.default_options.is_empty() {
+            write!(f, " -{}", join_options(&self.default_options))?;
+        }
+
+        for client in &self.clients {
+            write!(f, " {}", client)?;
+        }
+
+        Ok(())
+    }
+}
+
+fn parse_options(options: &str) -> Result<Vec<ExportOption>, ParseExportsError> {
+    options
+        .split(',')
+        .filter(|option| !option.is_empty())
+        .map(str::parse)
+        .collect()
+}
+
+fn join_options(options: &[ExportOption]) -> String {
+    options
+        .iter()
+        .map(ExportOption::to_string)
+        .collect::<Vec<_>>()
+        .join(",")
+}
+
+/// Splits the line by whitespace outside of double quotes, removing the quotes.
+fn split_words(s: &str) -> Vec<String> {
+    let mut words = Vec::new();
+    let mut word = String::new();
+    let mut quoted = false;
+
+    for c in s.chars() {
+        match c {
+            '"' => quoted = !quoted,
+            c if c.is_whitespace() && !quoted => {
+                if !word.is_empty() {
+                    words.push(std::mem::take(&mut word));
+                }
+            }
+            c => word.push(c),
+        }
+    }
+
+    if !word.is_empty() {
+        words.push(word);
+    }
+
+    words
+}
+
+#[derive(Debug)]
+pub enum ParseExportsError {
+    InvalidEntry(String),
+    ParseIntError(ParseIntError),
+    IOError(io::Error),
+}
+
+impl error::Error for ParseExportsError {}
+
+impl fmt::Display for ParseExportsError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::InvalidEntry(entry) => write!(f, "invalid export: {}", entry),
+            Self::ParseIntError(error) => write!(f, "{}", error),
+            Self::IOError(error) => write!(f, "{}", error),
+        }
+    }
+}
+
+impl From<ParseIntError> for ParseExportsError {
+    fn from(value: ParseIntError) -> Self {
+        ParseExportsError::ParseIntError(value)
+    }
+}
+
+impl From<io::Error> for ParseExportsError {
+    fn from(value: io::Error) -> Self {
+        ParseExportsError::IOError(value)
+    }
+}
+
+///
+/// Lines ending with a backslash are joined with the next one.
+///
+/// ```no_run
+/// let exports = unixism::exports::parse(std::fs::File::open("/etc/exports").unwrap()).unwrap();
+///
+/// for export in exports {
+///     println!("{} -> {:?}", export.path, export.clients);
+/// }
+/// ```
+pub fn parse<R>(reader: R) -> Result<impl Iterator<Item = Export>, ParseExportsError>
+where
+    R: io::Read,
+{
+    let mut exports = Vec::new();
+    let mut pending = String::new();
+
+    for line in BufReader::new(reader).lines() {
+        let line = line?;
+
+        if let Some(line) = line.strip_suffix('\\') {
+            pending.push_str(line);
+            pending.push(' ');
+            continue;
+        }
+
+        pending.push_str(&line);
+        let line = std::mem::take(&mut pending);
+        let line = line.trim();
+
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+
+        exports.push(line.parse::<Export>()?);
+    }
+
+    if !pending.trim().is_empty() {
+        exports.push(pending.trim().parse::<Export>()?);
+    }
+
+    Ok(exports.into_iter())
+}
+
+///
+/// Same as parse, but parses the `/etc/exports` as default.
+///
+/// ```no_run
+/// let exports = unixism::exports::parse_default().unwrap();
+/// ```
+pub fn parse_default() -> Result<impl Iterator<Item = Export>, ParseExportsError> {
+    parse(fs::File::open("/etc/exports")?)
+}
+
+///
+/// Writes the exports one per line, in the format accepted by parse.
+///
+/// ```no_run
+/// let exports = unixism::exports::parse_default().unwrap().collect::<Vec<_>>();
+///
+/// unixism::exports::write(std::fs::File::create("/etc/exports").unwrap(), &exports).unwrap();
+/// ```
+pub fn write<W>(mut writer: W, exports: &[Export]) -> io::Result<()>
+where
+    W: io::Write,
+{
+    for export in exports {
+        writeln!(writer, "{}", export)?;
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Cursor;
+
+    use super::*;
+
+    #[test]
+    fn it_parse() {
+        let exports = parse(Cursor::new(
+            r#"
+# /etc/exports: the access control list for filesystems which may be exported
+/srv/nfs4        192.168.1.0/24(rw,sync,fsid=0,crossmnt,no_subtree_check)
+/srv/nfs4/home   -sync,no_subtree_check \
+                 client1.lan(rw,anonuid=1000,anongid=1000) *.lan(ro) @trusted(rw,no_root_squash)
+"/srv/my share"  (ro,sec=krb5:krb5i)
+/pub             *
+        "#,
+        ))
+        .unwrap()
+        .collect::<Vec<_>>();
+
+        assert_eq!(4, exports.len());
+        assert_eq!(
+            Export {
+                path: "/srv/nfs4".to_owned(),
+                default_options: vec![],
+                clients: vec![Client {
+                    spec: ClientSpec::Network("192.168.1.0/24".to_owned()),
+                    options: vec![
+                        ExportOption::Rw,
+                        ExportOption::Sync,
+                        ExportOption::Fsid("0".to_owned()),
+                        ExportOption::Crossmnt,
+                        ExportOption::NoSubtreeCheck,
+                    ],
+                }],
+            },
+            exports[0]
+        );
+        assert_eq!(
+            vec![ExportOption::Sync, ExportOption::NoSubtreeCheck],
+            exports[1].default_options
+        );
+        assert_eq!(
+            vec![
+                ClientSpec::Host("client1.lan".to_owned()),
+                ClientSpec::Wildcard("*.lan".to_owned()),
+                ClientSpec::Netgroup("trusted".to_owned()),
+            ],
+            exports[1]
+                .clients
+                .iter()
+                .map(|client| client.spec.clone())
+                .collect::<Vec<_>>()
+        );
+        assert!(exports[1].clients[0]
+            .options
+            .contains(&ExportOption::Anonuid(1000)));
+        assert_eq!("/srv/my share", exports[2].path);
+        assert_eq!(ClientSpec::Anyone, exports[2].clients[0].spec);
+        assert_eq!(
+            ClientSpec::Wildcard("*".to_owned()),
+            exports[3].clients[0].spec
+        );
+
+        let mut written = Vec::new();
+        write(&mut written, &exports).unwrap();
+
+        assert_eq!(
+            r#"/srv/nfs4 192.168.1.0/24(rw,sync,fsid=0,crossmnt,no_subtree_check)
+/srv/nfs4/home -sync,no_subtree_check client1.lan(rw,anonuid=1000,anongid=1000) *.lan(ro) @trusted(rw,no_root_squash)
+"/srv/my share" (ro,sec=krb5:krb5i)
+/pub *
+"#,
+            String::from_utf8(written).unwrap()
+        );
+
+        assert!(parse(Cursor::new("/srv host(rw")).is_err());
+        assert!(parse(Cursor::new("/srv host(anonuid=x)")).is_err());
+    }
+}
@@ -0,0 +1,217 @@
+use std::{
+    fs,
+    io::{self, Write},
+    os::unix::fs::MetadataExt,
+    path::{Path, PathBuf},
+};
+
+#[cfg(any(feature = "group", feature = "passwd"))]
+use std::{
+    collections::BTreeMap,
+    os::unix::{fs::OpenOptionsExt, io::AsRawFd},
+    sync::{Arc, Mutex, Weak},
+    thread,
+    time::{Duration, Instant},
+};
+
+#[cfg(any(feature = "group", feature = "passwd"))]
+/// How long to wait for the lock, the same as glibc's `lckpwdf`.
+const LOCK_TIMEOUT: Duration = Duration::from_secs(15);
+
+#[cfg(any(feature = "group", feature = "passwd"))]
+/// The lock files held by the process. `fcntl` locks belong to the process and closing any
+/// descriptor of the file releases them, so every `PasswdLock` of a path shares one descriptor.
+static HELD: Mutex<BTreeMap<PathBuf, Weak<fs::File>>> = Mutex::new(BTreeMap::new());
+
+#[cfg(any(feature = "group", feature = "passwd"))]
+///
+/// The `/etc/.pwd.lock` lock taken by `vipw`, `useradd` and friends via `lckpwdf(3)`.
+/// Locks of the same path taken by the process share the lock, which is released when the last one is dropped.
+#[derive(Debug)]
+pub struct PasswdLock {
+    _file: Arc<fs::File>,
+}
+
+#[cfg(any(feature = "group", feature = "passwd"))]
+impl PasswdLock {
+    pub const PATH: &'static str = "/etc/.pwd.lock";
+
+    pub fn acquire() -> io::Result<Self> {
+        Self::acquire_at(Self::PATH)
+    }
+
+    pub fn acquire_at<P>(path: P) -> io::Result<Self>
+    where
+        P: AsRef<Path>,
+    {
+        let path = path.as_ref();
+        let key = fs::canonicalize(path).unwrap_or_else(|_| path.to_path_buf());
+
+        let mut held = HELD.lock().unwrap_or_else(|error| error.into_inner());
+        held.retain(|_, file| file.strong_count() > 0);
+
+        if let Some(file) = held.get(&key).and_then(Weak::upgrade) {
+            return Ok(Self { _file: file });
+        }
+
+        let file = fs::OpenOptions::new()
+            .write(true)
+            .create(true)
+            .truncate(false)
+            .mode(0o600)
+            .open(path)?;
+
+        let lock = libc::flock {
+            l_type: libc::F_WRLCK as libc::c_short,
+            l_whence: libc::SEEK_SET as libc::c_short,
+            l_start: 0,
+            l_len: 0,
+            l_pid: 0,
+        };
+
+        let started = Instant::now();
+
+        // SAFETY: the descriptor is owned by `file` and `lock` is a valid flock struct.
+        while unsafe { libc::fcntl(file.as_raw_fd(), libc::F_SETLK, &lock) } == -1 {
+            let error = io::Error::last_os_error();
+
+            match error.raw_os_error() {
+                Some(libc::EACCES | libc::EAGAIN) if started.elapsed() < LOCK_TIMEOUT => {
+                    thread::sleep(Duration::from_millis(100));
+                }
+                Some(libc::EACCES | libc::EAGAIN) => {
+                    return Err(io::Error::new(
+                        io::ErrorKind::TimedOut,
+                        "timed out waiting for the password file lock",
+                    ))
+                }
+                _ => return Err(error),
+            }
+        }
+
+        let file = Arc::new(file);
+        held.insert(key, Arc::downgrade(&file));
+
+        Ok(Self { _file: file })
+    }
+}
+
+///
+/// Replaces the file at `path` with `contents` via a temporary file and rename,
+/// keeping the original permissions and owner. With `backup`, the original is left as `path-`
+/// like shadow-utils does for the password files.
+pub(crate) fn write_atomic(path: &Path, contents: &[u8], backup: bool) -> io::Result<()> {
+    let temp = sibling(path, "+");
+    let metadata = fs::metadata(path).ok();
+
+    let mut file = fs::File::create(&temp)?;
+    file.write_all(contents)?;
+
+    if let Some(metadata) = &metadata {
+        file.set_permissions(metadata.permissions())?;
+        // Changing the owner requires privileges, which the caller may not have
+        // when editing its own copy of the file.
+        let _ = std::os::unix::fs::fchown(&file, Some(metadata.uid()), Some(metadata.gid()));
+    }
+
+    file.sync_all()?;
+    drop(file);
+
+    if backup && metadata.is_some() {
+        fs::copy(path, sibling(path, "-"))?;
+    }
+
+    fs::rename(&temp, path)
+}
+
+fn sibling(path: &Path, suffix: &str) -> PathBuf {
+    let mut name = path.as_os_str().to_owned();
+    name.push(suffix);
+
+    PathBuf::from(name)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tempdir::TempDir;
+
+    #[test]
+    fn it_write_atomic() {
+        let dir = TempDir::new("file");
+
+        let path = dir.join("passwd");
+        fs::write(&path, "old\n").unwrap();
+
+        #[cfg(any(feature = "group", feature = "passwd"))]
+        let _lock = PasswdLock::acquire_at(dir.join(".pwd.lock")).unwrap();
+        write_atomic(&path, b"new\n", true).unwrap();
+
+        assert_eq!("new\n", fs::read_to_string(&path).unwrap());
+        assert_eq!("old\n", fs::read_to_string(dir.join("passwd-")).unwrap());
+        assert!(!dir.join("passwd+").exists());
+
+        let path = dir.join("fstab");
+        fs::write(&path, "old\n").unwrap();
+        write_atomic(&path, b"new\n", false).unwrap();
+
+        assert_eq!("new\n", fs::read_to_string(&path).unwrap());
+        assert!(!dir.join("fstab-").exists());
+        assert!(!dir.join("fstab+").exists());
+    }
+
+    /// Whether another process would see the file locked, checked from a forked child
+    /// as the locks of this process never conflict with each other.
+    #[cfg(all(feature = "group", feature = "passwd"))]
+    fn locked_for_others(path: &Path) -> bool {
+        use std::{ffi::CString, os::unix::ffi::OsStrExt};
+
+        let path = CString::new(path.as_os_str().as_bytes()).unwrap();
+
+        // SAFETY: the child only calls async-signal-safe functions before exiting.
+        match unsafe { libc::fork() } {
+            -1 => panic!("fork failed: {}", io::Error::last_os_error()),
+            0 => unsafe {
+                let fd = libc::open(path.as_ptr(), libc::O_RDWR);
+                let mut lock = libc::flock {
+                    l_type: libc::F_WRLCK as libc::c_short,
+                    l_whence: libc::SEEK_SET as libc::c_short,
+                    l_start: 0,
+                    l_len: 0,
+                    l_pid: 0,
+                };
+                let locked = fd != -1
+                    && libc::fcntl(fd, libc::F_GETLK, &mut lock) != -1
+                    && lock.l_type != libc::F_UNLCK as libc::c_short;
+
+                libc::_exit(locked as libc::c_int)
+            },
+            pid => {
+                let mut status = 0;
+                // SAFETY: waits for the child forked above.
+                assert_eq!(pid, unsafe { libc::waitpid(pid, &mut status, 0) });
+
+                libc::WIFEXITED(status) && libc::WEXITSTATUS(status) == 1
+            }
+        }
+    }
+
+    #[test]
+    #[cfg(all(feature = "group", feature = "passwd"))]
+    fn it_share_lock() {
+        let dir = TempDir::new("file-lock");
+        fs::write(dir.join("passwd"), "root:x:0:0:root:/root:/bin/bash\n").unwrap();
+        fs::write(dir.join("group"), "root:x:0:\n").unwrap();
+
+        let lock = dir.join(".pwd.lock");
+        let passwd = crate::passwd::Editor::open_at(dir.join("passwd"), &lock).unwrap();
+        let group = crate::group::Editor::open_at(dir.join("group"), &lock).unwrap();
+        assert!(locked_for_others(&lock));
+
+        drop(passwd);
+        assert!(locked_for_others(&lock));
+
+        drop(group);
+        assert!(!locked_for_others(&lock));
+    }
+}
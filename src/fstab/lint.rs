@@ -0,0 +1,194 @@
+use std::{fmt, path::Path};
+
+use super::{FstabEntry, MountOption, Spec};
+
+/// A problem found in the fstab entries.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Warning {
+    DuplicateMountPoint(String),
+    NetworkPassno { file: String, passno: u32 },
+    MissingNofail { spec: String, file: String },
+    SwapType { spec: String, vfstype: String },
+    MissingMountPoint(String),
+}
+
+impl fmt::Display for Warning {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::DuplicateMountPoint(file) => write!(f, "{} is mounted more than once", file),
+            Self::NetworkPassno { file, passno } => {
+                write!(
+                    f,
+                    "network filesystem {} has fs_passno {}, fsck can't check it",
+                    file, passno
+                )
+            }
+            Self::MissingNofail { spec, file } => {
+                write!(
+                    f,
+                    "removable device {} mounted on {} has no nofail option",
+                    spec, file
+                )
+            }
+            Self::SwapType { spec, vfstype } => {
+                write!(
+                    f,
+                    "swap entry {} has type {} instead of swap",
+                    spec, vfstype
+                )
+            }
+            Self::MissingMountPoint(file) => write!(f, "mount point {} does not exist", file),
+        }
+    }
+}
+
+///
+/// Checks the entries for duplicate mount points, network filesystems with a non-zero fs_passno,
+/// removable devices without `nofail` and swap entries with a type other than swap.
+/// Devices count as removable by their name: optical and MMC drives, USB ids and optical filesystems.
+///
+/// ```no_run
+/// let entries = unixism::fstab::parse_default().unwrap().collect::<Vec<_>>();
+///
+/// for warning in unixism::fstab::lint(&entries) {
+///     println!("{warning}");
+/// }
+/// ```
+pub fn lint(entries: &[FstabEntry]) -> Vec<Warning> {
+    let mut warnings = Vec::new();
+    let mut seen = Vec::new();
+
+    for entry in entries.iter().filter(|entry| !is_swap(entry)) {
+        let file = entry.file.trim_end_matches('/');
+
+        if seen.contains(&file) {
+            let warning = Warning::DuplicateMountPoint(entry.file.clone());
+            if !warnings.contains(&warning) {
+                warnings.push(warning);
+            }
+        } else {
+            seen.push(file);
+        }
+    }
+
+    for entry in entries {
+        if entry.is_network() && entry.passno != 0 {
+            warnings.push(Warning::NetworkPassno {
+                file: entry.file.clone(),
+                passno: entry.passno,
+            });
+        }
+
+        if is_removable(entry)
+            && !entry.mntops.contains(&MountOption::Nofail)
+            && !entry.mntops.contains(&MountOption::Noauto)
+        {
+            warnings.push(Warning::MissingNofail {
+                spec: entry.spec.to_string(),
+                file: entry.file.clone(),
+            });
+        }
+
+        if is_swap(entry) && entry.vfstype != "swap" {
+            warnings.push(Warning::SwapType {
+                spec: entry.spec.to_string(),
+                vfstype: entry.vfstype.clone(),
+            });
+        }
+    }
+
+    warnings
+}
+
+///
+/// Checks that the mount points exist on disk, skipping swap and `noauto` entries.
+///
+/// ```no_run
+/// let entries = unixism::fstab::parse_default().unwrap().collect::<Vec<_>>();
+///
+/// for warning in unixism::fstab::lint_mount_points(&entries) {
+///     println!("{warning}");
+/// }
+/// ```
+pub fn lint_mount_points(entries: &[FstabEntry]) -> Vec<Warning> {
+    entries
+        .iter()
+        .filter(|entry| !is_swap(entry) && !entry.mntops.contains(&MountOption::Noauto))
+        .filter(|entry| !Path::new(&entry.file).is_dir())
+        .map(|entry| Warning::MissingMountPoint(entry.file.clone()))
+        .collect()
+}
+
+fn is_swap(entry: &FstabEntry) -> bool {
+    entry.vfstype == "swap"
+        || entry.file == "swap"
+        || entry.mntops.contains(&MountOption::Flag("sw".to_owned()))
+}
+
+fn is_removable(entry: &FstabEntry) -> bool {
+    let removable_device = match &entry.spec {
+        Spec::Device(device) => ["/dev/sr", "/dev/mmcblk", "/dev/disk/by-id/usb-"]
+            .iter()
+            .any(|prefix| device.starts_with(prefix)),
+        Spec::Id(id) => id.starts_with("usb-"),
+        _ => false,
+    };
+
+    removable_device || matches!(entry.vfstype.as_str(), "iso9660" | "udf")
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Cursor;
+
+    use super::*;
+    use crate::fstab::parse;
+
+    #[test]
+    fn it_lint() {
+        let entries = parse(Cursor::new(
+            r#"
+/dev/sda2                 /           ext4     defaults      0 1
+/dev/sda3                 /home       ext4     defaults      0 2
+/dev/sdb1                 /home/      xfs      defaults      0 2
+nas:/export               /mnt/nas    nfs      defaults      0 2
+/dev/sr0                  /media/cd   iso9660  ro            0 0
+/dev/mmcblk0p1            /media/sd   vfat     nofail        0 0
+/dev/sda4                 none        ext4     sw            0 0
+/swapfile                 none        swap     sw            0 0
+        "#,
+        ))
+        .unwrap()
+        .collect::<Vec<_>>();
+
+        assert_eq!(
+            vec![
+                Warning::DuplicateMountPoint("/home/".to_owned()),
+                Warning::NetworkPassno {
+                    file: "/mnt/nas".to_owned(),
+                    passno: 2
+                },
+                Warning::MissingNofail {
+                    spec: "/dev/sr0".to_owned(),
+                    file: "/media/cd".to_owned()
+                },
+                Warning::SwapType {
+                    spec: "/dev/sda4".to_owned(),
+                    vfstype: "ext4".to_owned()
+                },
+            ],
+            lint(&entries)
+        );
+
+        assert_eq!(
+            vec![Warning::MissingMountPoint("/definitely/missing".to_owned())],
+            lint_mount_points(&[
+                "/dev/sda2 / ext4".parse().unwrap(),
+                "/dev/sdc1 /definitely/missing ext4".parse().unwrap(),
+                "/dev/sdc2 /definitely/missing/too ext4 noauto"
+                    .parse()
+                    .unwrap(),
+            ])
+        );
+    }
+}
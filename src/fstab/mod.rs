@@ -0,0 +1,964 @@
+use std::{
+    error, fmt, fs,
+    io::{self, BufRead, BufReader},
+    num::ParseIntError,
+    path::{Path, PathBuf},
+    str::FromStr,
+};
+
+mod lint;
+pub mod systemd;
+
+pub use lint::{lint, lint_mount_points, Warning};
+
+/// Filesystem types mounted over the network.
+const NETWORK_FILESYSTEMS: [&str; 13] = [
+    "afs",
+    "ceph",
+    "cifs",
+    "smbfs",
+    "smb3",
+    "sshfs",
+    "fuse.sshfs",
+    "ncpfs",
+    "ncp",
+    "nfs",
+    "nfs4",
+    "glusterfs",
+    "9p",
+];
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FstabEntry {
+    pub spec: Spec,
+    pub file: String,
+    pub vfstype: String,
+    pub mntops: MountOptions,
+    pub freq: u32,
+    pub passno: u32,
+}
+
+impl FstabEntry {
+    /// Whether the filesystem needs the network, by its type or the `_netdev` option.
+    pub fn is_network(&self) -> bool {
+        NETWORK_FILESYSTEMS.contains(&self.vfstype.as_str())
+            || self.mntops.contains(&MountOption::Netdev)
+    }
+}
+
+impl FromStr for FstabEntry {
+    type Err = ParseFstabError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let fields = s.split_whitespace().collect::<Vec<_>>();
+
+        let (spec, file, vfstype, mntops, freq, passno) = match fields[..] {
+            [spec, file] => (spec, file, "auto", "defaults", None, None),
+            [spec, file, vfstype] => (spec, file, vfstype, "defaults", None, None),
+            [spec, file, vfstype, mntops] => (spec, file, vfstype, mntops, None, None),
+            [spec, file, vfstype, mntops, freq] => (spec, file, vfstype, mntops, Some(freq), None),
+            [spec, file, vfstype, mntops, freq, passno] => {
+                (spec, file, vfstype, mntops, Some(freq), Some(passno))
+            }
+            _ => return Err(ParseFstabError::InvalidEntry(s.to_owned())),
+        };
+
+        Ok(FstabEntry {
+            spec: Spec::from(unescape(spec).as_str()),
+            file: unescape(file),
+            vfstype: unescape(vfstype),
+            mntops: MountOptions::from(unescape(mntops).as_str()),
+            freq: freq.map(str::parse).transpose()?.unwrap_or_default(),
+            passno: passno.map(str::parse).transpose()?.unwrap_or_default(),
+        })
+    }
+}
+
+/// The `fs_spec` field, the block device or remote filesystem to be mounted.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Spec {
+    Uuid(String),
+    Label(String),
+    PartUuid(String),
+    PartLabel(String),
+    Id(String),
+    /// A device path or any other source, like `tmpfs` or `server:/export`.
+    Device(String),
+}
+
+impl Spec {
+    ///
+    /// Maps the spec to a device path via the `/dev/disk/by-*` symlinks, following them to the device node.
+    ///
+    /// ```no_run
+    /// for entry in unixism::fstab::parse_default().unwrap() {
+    ///     println!("{} is backed by {:?}", entry.file, entry.spec.resolve());
+    /// }
+    /// ```
+    pub fn resolve(&self) -> io::Result<PathBuf> {
+        self.resolve_at("/dev/disk")
+    }
+
+    /// Same as resolve, but looks for the `by-*` directories in `dir`.
+    pub fn resolve_at<P>(&self, dir: P) -> io::Result<PathBuf>
+    where
+        P: AsRef<Path>,
+    {
+        let link = match self {
+            Spec::Uuid(uuid) => dir.as_ref().join("by-uuid").join(encode_devnode(uuid)),
+            Spec::Label(label) => dir.as_ref().join("by-label").join(encode_devnode(label)),
+            Spec::PartUuid(uuid) => dir.as_ref().join("by-partuuid").join(encode_devnode(uuid)),
+            Spec::PartLabel(label) => dir
+                .as_ref()
+                .join("by-partlabel")
+                .join(encode_devnode(label)),
+            Spec::Id(id) => dir.as_ref().join("by-id").join(encode_devnode(id)),
+            Spec::Device(device) => return Ok(PathBuf::from(device)),
+        };
+
+        fs::canonicalize(link)
+    }
+}
+
+impl From<&str> for Spec {
+    fn from(s: &str) -> Self {
+        let Some((tag, value)) = s.split_once('=') else {
+            return Spec::Device(s.to_owned());
+        };
+
+        let value = value
+            .strip_prefix('"')
+            .and_then(|value| value.strip_suffix('"'))
+            .unwrap_or(value)
+            .to_owned();
+
+        match tag {
+            "UUID" => Spec::Uuid(value),
+            "LABEL" => Spec::Label(value),
+            "PARTUUID" => Spec::PartUuid(value),
+            "PARTLABEL" => Spec::PartLabel(value),
+            "ID" => Spec::Id(value),
+            _ => Spec::Device(s.to_owned()),
+        }
+    }
+}
+
+impl fmt::Display for Spec {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Spec::Uuid(uuid) => write!(f, "UUID={}", uuid),
+            Spec::Label(label) => write!(f, "LABEL={}", label),
+            Spec::PartUuid(uuid) => write!(f, "PARTUUID={}", uuid),
+            Spec::PartLabel(label) => write!(f, "PARTLABEL={}", label),
+            Spec::Id(id) => write!(f, "ID={}", id),
+            Spec::Device(device) => write!(f, "{}", device),
+        }
+    }
+}
+
+/// Encodes the name the way udev does for the `/dev/disk/by-*` symlinks, e.g. `/` as `\x2f`.
+fn encode_devnode(name: &str) -> String {
+    let mut encoded = String::with_capacity(name.len());
+
+    for c in name.chars() {
+        match c {
+            c if c.is_ascii_alphanumeric() || "#+-.:=@_".contains(c) => encoded.push(c),
+            c if !c.is_ascii() => encoded.push(c),
+            c => encoded.push_str(&format!("\\x{:02x}", c as u32)),
+        }
+    }
+
+    encoded
+}
+
+/// A single option of the comma-separated `fs_mntops` field.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum MountOption {
+    Defaults,
+    Ro,
+    Rw,
+    Atime,
+    Noatime,
+    Relatime,
+    Norelatime,
+    Strictatime,
+    Nostrictatime,
+    Lazytime,
+    Nolazytime,
+    Diratime,
+    Nodiratime,
+    Suid,
+    Nosuid,
+    Dev,
+    Nodev,
+    Exec,
+    Noexec,
+    Sync,
+    Async,
+    Dirsync,
+    Mand,
+    Nomand,
+    Silent,
+    Loud,
+    Bind,
+    Rbind,
+    Remount,
+    Auto,
+    Noauto,
+    User,
+    Nouser,
+    Users,
+    Owner,
+    Group,
+    Nofail,
+    Netdev,
+    /// Options with a value, like `uid=1000`, `size=2G` or `subvol=@home`.
+    Value {
+        name: String,
+        value: String,
+    },
+    /// Filesystem specific flags unknown to this crate.
+    Flag(String),
+}
+
+impl From<&str> for MountOption {
+    fn from(s: &str) -> Self {
+        match s {
+            "defaults" => MountOption::Defaults,
+            "ro" => MountOption::Ro,
+            "rw" => MountOption::Rw,
+            "atime" => MountOption::Atime,
+            "noatime" => MountOption::Noatime,
+            "relatime" => MountOption::Relatime,
+            "norelatime" => MountOption::Norelatime,
+            "strictatime" => MountOption::Strictatime,
+            "nostrictatime" => MountOption::Nostrictatime,
+            "lazytime" => MountOption::Lazytime,
+            "nolazytime" => MountOption::Nolazytime,
+            "diratime" => MountOption::Diratime,
+            "nodiratime" => MountOption::Nodiratime,
+            "suid" => MountOption::Suid,
+            "nosuid" => MountOption::Nosuid,
+            "dev" => MountOption::Dev,
+            "nodev" => MountOption::Nodev,
+            "exec" => MountOption::Exec,
+            "noexec" => MountOption::Noexec,
+            "sync" => MountOption::Sync,
+            "async" => MountOption::Async,
+            "dirsync" => MountOption::Dirsync,
+            "mand" => MountOption::Mand,
+            "nomand" => MountOption::Nomand,
+            "silent" => MountOption::Silent,
+            "loud" => MountOption::Loud,
+            "bind" => MountOption::Bind,
+            "rbind" => MountOption::Rbind,
+            "remount" => MountOption::Remount,
+            "auto" => MountOption::Auto,
+            "noauto" => MountOption::Noauto,
+            "user" => MountOption::User,
+            "nouser" => MountOption::Nouser,
+            "users" => MountOption::Users,
+            "owner" => MountOption::Owner,
+            "group" => MountOption::Group,
+            "nofail" => MountOption::Nofail,
+            "_netdev" => MountOption::Netdev,
+            s => match s.split_once('=') {
+                Some((name, value)) => MountOption::Value {
+                    name: name.to_owned(),
+                    value: value.to_owned(),
+                },
+                None => MountOption::Flag(s.to_owned()),
+            },
+        }
+    }
+}
+
+impl fmt::Display for MountOption {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            MountOption::Defaults => "defaults",
+            MountOption::Ro => "ro",
+            MountOption::Rw => "rw",
+            MountOption::Atime => "atime",
+            MountOption::Noatime => "noatime",
+            MountOption::Relatime => "relatime",
+            MountOption::Norelatime => "norelatime",
+            MountOption::Strictatime => "strictatime",
+            MountOption::Nostrictatime => "nostrictatime",
+            MountOption::Lazytime => "lazytime",
+            MountOption::Nolazytime => "nolazytime",
+            MountOption::Diratime => "diratime",
+            MountOption::Nodiratime => "nodiratime",
+            MountOption::Suid => "suid",
+            MountOption::Nosuid => "nosuid",
+            MountOption::Dev => "dev",
+            MountOption::Nodev => "nodev",
+            MountOption::Exec => "exec",
+            MountOption::Noexec => "noexec",
+            MountOption::Sync => "sync",
+            MountOption::Async => "async",
+            MountOption::Dirsync => "dirsync",
+            MountOption::Mand => "mand",
+            MountOption::Nomand => "nomand",
+            MountOption::Silent => "silent",
+            MountOption::Loud => "loud",
+            MountOption::Bind => "bind",
+            MountOption::Rbind => "rbind",
+            MountOption::Remount => "remount",
+            MountOption::Auto => "auto",
+            MountOption::Noauto => "noauto",
+            MountOption::User => "user",
+            MountOption::Nouser => "nouser",
+            MountOption::Users => "users",
+            MountOption::Owner => "owner",
+            MountOption::Group => "group",
+            MountOption::Nofail => "nofail",
+            MountOption::Netdev => "_netdev",
+            MountOption::Value { name, value } => return write!(f, "{}={}", name, value),
+            MountOption::Flag(flag) => flag,
+        };
+
+        write!(f, "{}", name)
+    }
+}
+
+/// The parsed `fs_mntops` field, keeping the options in their original order.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct MountOptions(pub Vec<MountOption>);
+
+impl MountOptions {
+    pub fn contains(&self, option: &MountOption) -> bool {
+        self.0.contains(option)
+    }
+
+    /// Returns the value of the last `name=value` option.
+    pub fn get(&self, name: &str) -> Option<&str> {
+        self.0.iter().rev().find_map(|option| match option {
+            MountOption::Value { name: key, value } if key == name => Some(value.as_str()),
+            _ => None,
+        })
+    }
+
+    ///
+    /// Converts the options to the `mountflags` of `mount(2)`, later options override earlier ones.
+    /// Options which are not kernel flags are ignored, see [`MountOptions::data`].
+    pub fn flags(&self) -> libc::c_ulong {
+        let mut flags = 0;
+
+        for option in &self.0 {
+            let (set, clear) = match option {
+                MountOption::Defaults | MountOption::Rw => (0, libc::MS_RDONLY),
+                MountOption::Ro => (libc::MS_RDONLY, 0),
+                MountOption::Atime => (0, libc::MS_NOATIME),
+                MountOption::Noatime => (libc::MS_NOATIME, 0),
+                MountOption::Relatime => (libc::MS_RELATIME, 0),
+                MountOption::Norelatime => (0, libc::MS_RELATIME),
+                MountOption::Strictatime => (libc::MS_STRICTATIME, 0),
+                MountOption::Nostrictatime => (0, libc::MS_STRICTATIME),
+                MountOption::Lazytime => (libc::MS_LAZYTIME, 0),
+                MountOption::Nolazytime => (0, libc::MS_LAZYTIME),
+                MountOption::Diratime => (0, libc::MS_NODIRATIME),
+                MountOption::Nodiratime => (libc::MS_NODIRATIME, 0),
+                MountOption::Suid => (0, libc::MS_NOSUID),
+                MountOption::Nosuid => (libc::MS_NOSUID, 0),
+                MountOption::Dev => (0, libc::MS_NODEV),
+                MountOption::Nodev => (libc::MS_NODEV, 0),
+                MountOption::Exec => (0, libc::MS_NOEXEC),
+                MountOption::Noexec => (libc::MS_NOEXEC, 0),
+                MountOption::Sync => (libc::MS_SYNCHRONOUS, 0),
+                MountOption::Async => (0, libc::MS_SYNCHRONOUS),
+                MountOption::Dirsync => (libc::MS_DIRSYNC, 0),
+                MountOption::Mand => (libc::MS_MANDLOCK, 0),
+                MountOption::Nomand => (0, libc::MS_MANDLOCK),
+                MountOption::Silent => (libc::MS_SILENT, 0),
+                MountOption::Loud => (0, libc::MS_SILENT),
+                MountOption::Bind => (libc::MS_BIND, 0),
+                MountOption::Rbind => (libc::MS_BIND | libc::MS_REC, 0),
+                MountOption::Remount => (libc::MS_REMOUNT, 0),
+                // The user options imply nosuid, nodev and noexec unless overridden later,
+                // owner and group only nosuid and nodev.
+                MountOption::User | MountOption::Users => {
+                    (libc::MS_NOSUID | libc::MS_NODEV | libc::MS_NOEXEC, 0)
+                }
+                MountOption::Owner | MountOption::Group => (libc::MS_NOSUID | libc::MS_NODEV, 0),
+                _ => (0, 0),
+            };
+
+            flags = (flags | set) & !clear;
+        }
+
+        flags
+    }
+
+    ///
+    /// Returns the filesystem specific options passed as `data` to `mount(2)`,
+    /// which are the `name=value` options and unknown flags.
+    pub fn data(&self) -> String {
+        self.0
+            .iter()
+            .filter(|option| matches!(option, MountOption::Value { .. } | MountOption::Flag(_)))
+            .map(MountOption::to_string)
+            .collect::<Vec<_>>()
+            .join(",")
+    }
+}
+
+impl From<&str> for MountOptions {
+    fn from(s: &str) -> Self {
+        MountOptions(
+            split_options(s)
+                .into_iter()
+                .filter(|option| !option.is_empty())
+                .map(MountOption::from)
+                .collect(),
+        )
+    }
+}
+
+impl fmt::Display for MountOptions {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (i, option) in self.0.iter().enumerate() {
+            if i > 0 {
+                write!(f, ",")?;
+            }
+            write!(f, "{}", option)?;
+        }
+
+        Ok(())
+    }
+}
+
+/// Splits the options by commas outside of double quotes, as in `context="a,b"`.
+fn split_options(s: &str) -> Vec<&str> {
+    let mut options = Vec::new();
+    let mut start = 0;
+    let mut quoted = false;
+
+    for (i, c) in s.char_indices() {
+        match c {
+            '"' => quoted = !quoted,
+            ',' if !quoted => {
+                options.push(&s[start..i]);
+                start = i + 1;
+            }
+            _ => {}
+        }
+    }
+    options.push(&s[start..]);
+
+    options
+}
+
+impl fmt::Display for FstabEntry {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} {} {} {} {} {}",
+            escape(&self.spec.to_string()),
+            escape(&self.file),
+            escape(&self.vfstype),
+            escape(&self.mntops.to_string()),
+            self.freq,
+            self.passno
+        )
+    }
+}
+
+/// Decodes the octal escapes (`\040` for a space, `\011` for a tab, ...) used in fstab fields.
+pub fn unescape(s: &str) -> String {
+    let bytes = s.as_bytes();
+    let mut decoded = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+
+    while i < bytes.len() {
+        let octal = bytes
+            .get(i + 1..i + 4)
+            .filter(|digits| bytes[i] == b'\\' && digits.iter().all(|d| (b'0'..=b'7').contains(d)))
+            .and_then(|digits| u8::from_str_radix(std::str::from_utf8(digits).ok()?, 8).ok());
+
+        match octal {
+            Some(byte) => {
+                decoded.push(byte);
+                i += 4;
+            }
+            None => {
+                decoded.push(bytes[i]);
+                i += 1;
+            }
+        }
+    }
+
+    String::from_utf8_lossy(&decoded).into_owned()
+}
+
+/// Encodes whitespace and backslashes as octal escapes, the inverse of unescape.
+pub fn escape(s: &str) -> String {
+    let mut encoded = String::with_capacity(s.len());
+
+    for c in s.chars() {
+        match c {
+            ' ' | '\t' | '\n' | '\\' => encoded.push_str(&format!("\\{:03o}", c as u32)),
+            c => encoded.push(c),
+        }
+    }
+
+    encoded
+}
+
+#[derive(Debug)]
+pub enum ParseFstabError {
+    InvalidEntry(String),
+    DuplicateEntry(String),
+    ParseIntError(ParseIntError),
+    IOError(io::Error),
+}
+
+impl error::Error for ParseFstabError {}
+
+impl fmt::Display for ParseFstabError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::InvalidEntry(entry) => write!(f, "invalid fstab entry: {}", entry),
+            Self::DuplicateEntry(file) => write!(f, "{} is already in fstab", file),
+            Self::ParseIntError(error) => write!(f, "{}", error),
+            Self::IOError(error) => write!(f, "{}", error),
+        }
+    }
+}
+
+impl From<ParseIntError> for ParseFstabError {
+    fn from(value: ParseIntError) -> Self {
+        ParseFstabError::ParseIntError(value)
+    }
+}
+
+impl From<io::Error> for ParseFstabError {
+    fn from(value: io::Error) -> Self {
+        ParseFstabError::IOError(value)
+    }
+}
+
+///
+/// ```no_run
+/// let entries = unixism::fstab::parse(std::fs::File::open("/etc/fstab").unwrap()).unwrap();
+///
+/// for entry in entries {
+///     println!("{} on {} type {} ({})", entry.spec, entry.file, entry.vfstype, entry.mntops);
+/// }
+/// ```
+pub fn parse<R>(reader: R) -> Result<impl Iterator<Item = FstabEntry>, ParseFstabError>
+where
+    R: io::Read,
+{
+    let mut entries = Vec::new();
+
+    for line in BufReader::new(reader).lines() {
+        let line = line?;
+        let line = line.trim();
+
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+
+        entries.push(line.parse::<FstabEntry>()?);
+    }
+
+    Ok(entries.into_iter())
+}
+
+///
+/// Same as parse, but parses the `/etc/fstab` as default.
+///
+/// ```no_run
+/// let entries = unixism::fstab::parse_default().unwrap();
+/// ```
+pub fn parse_default() -> Result<impl Iterator<Item = FstabEntry>, ParseFstabError> {
+    parse(fs::File::open("/etc/fstab")?)
+}
+
+#[derive(Debug)]
+enum Line {
+    /// A parsed entry, `raw` keeps the original text until the entry is modified.
+    Entry {
+        raw: Option<String>,
+        entry: FstabEntry,
+    },
+    Other(String),
+}
+
+///
+/// Edits the `/etc/fstab` keeping comments and untouched lines exactly as they were.
+/// Added and modified entries are padded to the column widths of the first entry in the file.
+/// Changes are written via a temporary file and rename on save.
+///
+/// ```no_run
+/// use unixism::fstab::{Editor, FstabEntry, MountOptions, Spec};
+///
+/// let mut editor = Editor::open().unwrap();
+///
+/// editor
+///     .add(FstabEntry {
+///         spec: Spec::Device("/dev/sdb1".to_owned()),
+///         file: "/srv/data".to_owned(),
+///         vfstype: "ext4".to_owned(),
+///         mntops: MountOptions::from("defaults,noatime"),
+///         freq: 0,
+///         passno: 2,
+///     })
+///     .unwrap();
+///
+/// editor.update("/tmp", |entry| entry.mntops = MountOptions::from("defaults,size=4G"));
+/// editor.save().unwrap();
+/// ```
+#[derive(Debug)]
+pub struct Editor {
+    path: PathBuf,
+    lines: Vec<Line>,
+    /// Widths of the first five columns, taken from the first entry of the file.
+    widths: [usize; 5],
+}
+
+impl Editor {
+    pub fn open() -> Result<Self, ParseFstabError> {
+        Self::open_at("/etc/fstab")
+    }
+
+    pub fn open_at<P>(path: P) -> Result<Self, ParseFstabError>
+    where
+        P: AsRef<Path>,
+    {
+        let content = fs::read_to_string(path.as_ref())?;
+
+        let mut lines = Vec::new();
+        for line in content.lines() {
+            let trimmed = line.trim();
+
+            if trimmed.is_empty() || trimmed.starts_with('#') {
+                lines.push(Line::Other(line.to_owned()));
+            } else {
+                lines.push(Line::Entry {
+                    entry: trimmed.parse::<FstabEntry>()?,
+                    raw: Some(line.to_owned()),
+                });
+            }
+        }
+
+        let mut widths = [0; 5];
+
+        if let Some(Line::Entry { raw: Some(raw), .. }) =
+            lines.iter().find(|line| matches!(line, Line::Entry { .. }))
+        {
+            let columns = columns(raw);
+
+            for (i, width) in widths.iter_mut().enumerate() {
+                if let (Some(start), Some(end)) = (columns.get(i), columns.get(i + 1)) {
+                    *width = end - start;
+                }
+            }
+        }
+
+        Ok(Self {
+            path: path.as_ref().to_owned(),
+            lines,
+            widths,
+        })
+    }
+
+    pub fn entries(&self) -> impl Iterator<Item = &FstabEntry> {
+        self.lines.iter().filter_map(|line| match line {
+            Line::Entry { entry, .. } => Some(entry),
+            Line::Other(_) => None,
+        })
+    }
+
+    /// Returns the entry mounted at `file`.
+    pub fn get(&self, file: &str) -> Option<&FstabEntry> {
+        self.entries().find(|entry| entry.file == file)
+    }
+
+    /// Appends the entry, swap entries may share the `none` mount point.
+    pub fn add(&mut self, entry: FstabEntry) -> Result<(), ParseFstabError> {
+        if entry.vfstype != "swap" && self.get(&entry.file).is_some() {
+            return Err(ParseFstabError::DuplicateEntry(entry.file));
+        }
+
+        self.lines.push(Line::Entry { raw: None, entry });
+
+        Ok(())
+    }
+
+    /// Applies `f` to the entry mounted at `file`, returns false if there is no such entry.
+    pub fn update<F>(&mut self, file: &str, f: F) -> bool
+    where
+        F: FnOnce(&mut FstabEntry),
+    {
+        for line in &mut self.lines {
+            if let Line::Entry { raw, entry } = line {
+                if entry.file == file {
+                    f(entry);
+                    *raw = None;
+
+                    return true;
+                }
+            }
+        }
+
+        false
+    }
+
+    pub fn remove(&mut self, file: &str) -> Option<FstabEntry> {
+        let position = self
+            .lines
+            .iter()
+            .position(|line| matches!(line, Line::Entry { entry, .. } if entry.file == file))?;
+
+        match self.lines.remove(position) {
+            Line::Entry { entry, .. } => Some(entry),
+            Line::Other(_) => None,
+        }
+    }
+
+    pub fn save(&self) -> Result<(), ParseFstabError> {
+        Ok(crate::file::write_atomic(
+            &self.path,
+            self.to_string().as_bytes(),
+            false,
+        )?)
+    }
+}
+
+impl fmt::Display for Editor {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for line in &self.lines {
+            match line {
+                Line::Entry { raw: Some(raw), .. } | Line::Other(raw) => writeln!(f, "{}", raw)?,
+                Line::Entry { raw: None, entry } => {
+                    let fields = [
+                        escape(&entry.spec.to_string()),
+                        escape(&entry.file),
+                        escape(&entry.vfstype),
+                        escape(&entry.mntops.to_string()),
+                        entry.freq.to_string(),
+                    ];
+
+                    for (field, width) in fields.iter().zip(self.widths) {
+                        let len = field.chars().count();
+                        write!(f, "{}{}", field, " ".repeat(width.max(len + 1) - len))?;
+                    }
+
+                    writeln!(f, "{}", entry.passno)?;
+                }
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// Display columns where each field of the line starts, tabs are expanded to 8 columns.
+fn columns(line: &str) -> Vec<usize> {
+    let mut columns = Vec::new();
+    let mut column = 0;
+    let mut in_field = false;
+
+    for c in line.chars() {
+        let blank = c == ' ' || c == '\t';
+
+        if !blank && !in_field {
+            columns.push(column);
+        }
+        in_field = !blank;
+
+        column = if c == '\t' {
+            (column / 8 + 1) * 8
+        } else {
+            column + 1
+        };
+    }
+
+    columns
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Cursor;
+
+    use super::*;
+    use crate::tempdir::TempDir;
+
+    #[test]
+    fn it_parse() {
+        let entries = parse(Cursor::new(
+            r#"
+# <file system> <mount point>   <type>  <options>       <dump>  <pass>
+UUID=3a1c6f2e-0b7d-4f0e-9d6f-1a2b3c4d5e6f /               ext4    errors=remount-ro 0       1
+/dev/sdb1       /mnt/My\040Disk vfat    noauto,user     0       0
+tmpfs           /tmp            tmpfs   defaults,size=2G
+/swapfile       none            swap    sw              0       0
+        "#,
+        ))
+        .unwrap()
+        .collect::<Vec<_>>();
+
+        assert_eq!(4, entries.len());
+        assert_eq!(
+            FstabEntry {
+                spec: Spec::Uuid("3a1c6f2e-0b7d-4f0e-9d6f-1a2b3c4d5e6f".to_owned()),
+                file: "/".to_owned(),
+                vfstype: "ext4".to_owned(),
+                mntops: MountOptions::from("errors=remount-ro"),
+                freq: 0,
+                passno: 1,
+            },
+            entries[0]
+        );
+        assert_eq!("/mnt/My Disk", entries[1].file);
+        assert_eq!(0, entries[2].passno);
+        assert_eq!("defaults,size=2G", entries[2].mntops.to_string());
+        assert_eq!(Some("2G"), entries[2].mntops.get("size"));
+        assert!(entries[1].mntops.contains(&MountOption::Noauto));
+        assert_eq!("swap", entries[3].vfstype);
+
+        assert_eq!("/mnt/My\\040Disk", escape(&entries[1].file));
+        assert_eq!("a\\134b", escape("a\\b"));
+
+        assert!(parse(Cursor::new("/dev/sda1")).is_err());
+        assert!(parse(Cursor::new("/dev/sda1 / ext4 defaults x 1")).is_err());
+    }
+
+    #[test]
+    fn it_edit() {
+        let dir = TempDir::new("fstab");
+
+        let path = dir.join("fstab");
+        fs::write(
+            &path,
+            "# <file system> <mount point> <type> <options> <dump> <pass>\nUUID=abcd      /             ext4   defaults  0      1\ntmpfs\t\t/tmp\ttmpfs\tdefaults\t0 0\n/dev/sdc1       /old          xfs    defaults  0      2\n",
+        )
+        .unwrap();
+
+        let mut editor = Editor::open_at(&path).unwrap();
+
+        assert!(editor
+            .add(FstabEntry {
+                spec: Spec::Device("/dev/sdb1".to_owned()),
+                file: "/srv/my data".to_owned(),
+                vfstype: "ext4".to_owned(),
+                mntops: MountOptions::from("noatime"),
+                freq: 0,
+                passno: 2,
+            })
+            .is_ok());
+        assert!(matches!(
+            editor.add(editor.get("/").unwrap().clone()),
+            Err(ParseFstabError::DuplicateEntry(file)) if file == "/"
+        ));
+        assert!(editor.update("/", |entry| entry.mntops = MountOptions::from("noatime")));
+        assert!(!editor.update("/missing", |_| {}));
+        assert_eq!("xfs", editor.remove("/old").unwrap().vfstype);
+
+        editor.save().unwrap();
+
+        assert_eq!(
+            "# <file system> <mount point> <type> <options> <dump> <pass>\nUUID=abcd      /             ext4   noatime   0      1\ntmpfs\t\t/tmp\ttmpfs\tdefaults\t0 0\n/dev/sdb1      /srv/my\\040data ext4   noatime   0      2\n",
+            fs::read_to_string(&path).unwrap()
+        );
+        assert!(!dir.join("fstab-").exists());
+    }
+
+    #[test]
+    fn it_parse_options() {
+        let options = MountOptions::from("defaults,noatime,uid=1000,context=\"system_u:object_r:tmp_t:s0:c127,c456\",discard,ro,rw");
+
+        assert_eq!(
+            vec![
+                MountOption::Defaults,
+                MountOption::Noatime,
+                MountOption::Value {
+                    name: "uid".to_owned(),
+                    value: "1000".to_owned()
+                },
+                MountOption::Value {
+                    name: "context".to_owned(),
+                    value: "\"system_u:object_r:tmp_t:s0:c127,c456\"".to_owned()
+                },
+                MountOption::Flag("discard".to_owned()),
+                MountOption::Ro,
+                MountOption::Rw,
+            ],
+            options.0
+        );
+        assert_eq!(
+            "defaults,noatime,uid=1000,context=\"system_u:object_r:tmp_t:s0:c127,c456\",discard,ro,rw",
+            options.to_string()
+        );
+        assert_eq!(Some("1000"), options.get("uid"));
+        assert_eq!(libc::MS_NOATIME, options.flags());
+        assert_eq!(
+            "uid=1000,context=\"system_u:object_r:tmp_t:s0:c127,c456\",discard",
+            options.data()
+        );
+
+        let options = MountOptions::from("user,exec,rbind,nofail");
+        assert_eq!(
+            libc::MS_NOSUID | libc::MS_NODEV | libc::MS_BIND | libc::MS_REC,
+            options.flags()
+        );
+        assert!(options.contains(&MountOption::Nofail));
+        assert!(options.data().is_empty());
+
+        assert_eq!(
+            libc::MS_NOSUID | libc::MS_NODEV | libc::MS_NOEXEC,
+            MountOptions::from("users").flags()
+        );
+        assert_eq!(
+            libc::MS_NOSUID | libc::MS_NODEV,
+            MountOptions::from("owner").flags()
+        );
+        assert_eq!(
+            libc::MS_NOSUID | libc::MS_NODEV,
+            MountOptions::from("group").flags()
+        );
+    }
+
+    #[test]
+    fn it_resolve_spec() {
+        assert_eq!(
+            Spec::Label("my data".to_owned()),
+            Spec::from("LABEL=\"my data\"")
+        );
+        assert_eq!(
+            Spec::PartUuid("0a1b-02".to_owned()),
+            Spec::from("PARTUUID=0a1b-02")
+        );
+        assert_eq!(Spec::Device("tmpfs".to_owned()), Spec::from("tmpfs"));
+        assert_eq!(Spec::Device("FOO=bar".to_owned()), Spec::from("FOO=bar"));
+        assert_eq!(
+            "PARTLABEL=esp",
+            Spec::PartLabel("esp".to_owned()).to_string()
+        );
+
+        let dir = TempDir::new("fstab-spec");
+        fs::create_dir_all(dir.join("by-label")).unwrap();
+        fs::write(dir.join("sdb1"), "").unwrap();
+        std::os::unix::fs::symlink(
+            "../sdb1",
+            dir.join("by-label").join("my\\x20data\\x2fbackup"),
+        )
+        .unwrap();
+
+        assert_eq!(
+            fs::canonicalize(dir.join("sdb1")).unwrap(),
+            Spec::Label("my data/backup".to_owned())
+                .resolve_at(&dir)
+                .unwrap()
+        );
+        assert!(Spec::Uuid("missing".to_owned()).resolve_at(&dir).is_err());
+        assert_eq!(
+            PathBuf::from("/dev/sda1"),
+            Spec::Device("/dev/sda1".to_owned())
+                .resolve_at(&dir)
+                .unwrap()
+        );
+    }
+}
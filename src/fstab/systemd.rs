@@ -0,0 +1,185 @@
+use super::{encode_devnode, FstabEntry, MountOption, Spec};
+
+/// A systemd unit generated from an fstab entry.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Unit {
+    /// The unit file name, like `srv-data.mount`.
+    pub name: String,
+    pub contents: String,
+}
+
+///
+/// Escapes the path the way `systemd-escape --path` does, so `/srv/my data` becomes `srv-my\x20data`.
+///
+/// ```
+/// use unixism::fstab::systemd::escape_path;
+///
+/// assert_eq!("var-lib-docker", escape_path("/var/lib/docker/"));
+/// assert_eq!("srv-my\\x20data", escape_path("/srv/my data"));
+/// ```
+pub fn escape_path(path: &str) -> String {
+    let components = path
+        .split('/')
+        .filter(|component| !component.is_empty() && *component != ".")
+        .collect::<Vec<_>>();
+
+    if components.is_empty() {
+        return "-".to_owned();
+    }
+
+    let mut escaped = String::with_capacity(path.len());
+
+    for (i, byte) in components.join("/").bytes().enumerate() {
+        match byte {
+            b'/' => escaped.push('-'),
+            b'.' if i == 0 => escaped.push_str("\\x2e"),
+            b if b.is_ascii_alphanumeric() || b == b':' || b == b'_' || b == b'.' => {
+                escaped.push(b as char)
+            }
+            b => escaped.push_str(&format!("\\x{:02x}", b)),
+        }
+    }
+
+    escaped
+}
+
+///
+/// Converts the entry to a `.mount` unit the way systemd-fstab-generator does,
+/// honoring `noauto`, `nofail` and `_netdev` and dropping the `x-systemd.*` options.
+///
+/// ```no_run
+/// for entry in unixism::fstab::parse_default().unwrap() {
+///     let unit = unixism::fstab::systemd::mount_unit(&entry);
+///
+///     std::fs::write(format!("/etc/systemd/system/{}", unit.name), unit.contents).unwrap();
+/// }
+/// ```
+pub fn mount_unit(entry: &FstabEntry) -> Unit {
+    let options = &entry.mntops;
+    let target = if entry.is_network() {
+        "remote-fs.target"
+    } else {
+        "local-fs.target"
+    };
+
+    let mut contents =
+        String::from("[Unit]\nDocumentation=man:fstab(5) man:systemd-fstab-generator(8)\n");
+
+    if entry.is_network() {
+        contents.push_str("After=network-online.target\nWants=network-online.target\n");
+    }
+    if !options.contains(&MountOption::Nofail) {
+        contents.push_str(&format!("Before={}\n", target));
+    }
+
+    contents.push_str("\n[Mount]\n");
+    contents.push_str(&format!("What={}\n", specifiers(&what(&entry.spec))));
+    contents.push_str(&format!("Where={}\n", specifiers(&entry.file)));
+
+    if entry.vfstype != "auto" {
+        contents.push_str(&format!("Type={}\n", specifiers(&entry.vfstype)));
+    }
+
+    let kept = options
+        .0
+        .iter()
+        .filter(|option| {
+            !matches!(
+                option,
+                MountOption::Auto | MountOption::Noauto | MountOption::Nofail
+            ) && !option.to_string().starts_with("x-systemd.")
+        })
+        .map(MountOption::to_string)
+        .collect::<Vec<_>>();
+
+    if !kept.is_empty() {
+        contents.push_str(&format!("Options={}\n", specifiers(&kept.join(","))));
+    }
+
+    if !options.contains(&MountOption::Noauto) {
+        contents.push_str(&format!("\n[Install]\nWantedBy={}\n", target));
+    }
+
+    Unit {
+        name: format!("{}.mount", escape_path(&entry.file)),
+        contents,
+    }
+}
+
+/// The device path systemd waits for, tags are resolved via the `/dev/disk/by-*` symlinks.
+fn what(spec: &Spec) -> String {
+    match spec {
+        Spec::Uuid(uuid) => format!("/dev/disk/by-uuid/{}", encode_devnode(uuid)),
+        Spec::Label(label) => format!("/dev/disk/by-label/{}", encode_devnode(label)),
+        Spec::PartUuid(uuid) => format!("/dev/disk/by-partuuid/{}", encode_devnode(uuid)),
+        Spec::PartLabel(label) => format!("/dev/disk/by-partlabel/{}", encode_devnode(label)),
+        Spec::Id(id) => format!("/dev/disk/by-id/{}", encode_devnode(id)),
+        Spec::Device(device) => device.clone(),
+    }
+}
+
+/// Escapes `%`, which starts a specifier in unit files.
+fn specifiers(value: &str) -> String {
+    value.replace('%', "%%")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn it_escape_path() {
+        assert_eq!("-", escape_path("/"));
+        assert_eq!("srv-data", escape_path("//srv/./data/"));
+        assert_eq!("mnt-my\\x20disk-a\\x2db", escape_path("/mnt/my disk/a-b"));
+        assert_eq!("\\x2esnapshots", escape_path("/.snapshots"));
+        assert_eq!("mnt-caf\\xc3\\xa9", escape_path("/mnt/café"));
+    }
+
+    #[test]
+    fn it_mount_unit() {
+        let entry = "UUID=3a1c-0b7d /srv/my\\040data ext4 defaults,noatime,nofail,x-systemd.device-timeout=5 0 2"
+            .parse::<FstabEntry>()
+            .unwrap();
+
+        assert_eq!(
+            Unit {
+                name: "srv-my\\x20data.mount".to_owned(),
+                contents: r#"[Unit]
+Documentation=man:fstab(5) man:systemd-fstab-generator(8)
+
+[Mount]
+What=/dev/disk/by-uuid/3a1c-0b7d
+Where=/srv/my data
+Type=ext4
+Options=defaults,noatime
+
+[Install]
+WantedBy=local-fs.target
+"#
+                .to_owned(),
+            },
+            mount_unit(&entry)
+        );
+
+        let entry = "nas:/export/50% /mnt/nas nfs noauto,_netdev"
+            .parse::<FstabEntry>()
+            .unwrap();
+
+        assert_eq!(
+            r#"[Unit]
+Documentation=man:fstab(5) man:systemd-fstab-generator(8)
+After=network-online.target
+Wants=network-online.target
+Before=remote-fs.target
+
+[Mount]
+What=nas:/export/50%%
+Where=/mnt/nas
+Type=nfs
+Options=_netdev
+"#,
+            mount_unit(&entry).contents
+        );
+    }
+}
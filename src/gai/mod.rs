@@ -0,0 +1,390 @@
+use std::{
+    error, fmt, fs,
+    io::{self, BufRead, BufReader},
+    net::{IpAddr, Ipv4Addr, Ipv6Addr},
+    num::ParseIntError,
+    str::FromStr,
+};
+
+/// The label table glibc uses when gai.conf has no `label` rules.
+pub const DEFAULT_LABELS: [(&str, u32); 8] = [
+    ("::1/128", 0),
+    ("::/0", 1),
+    ("2002::/16", 2),
+    ("::/96", 3),
+    ("::ffff:0:0/96", 4),
+    ("fec0::/10", 5),
+    ("fc00::/7", 6),
+    ("2001::/32", 7),
+];
+
+/// The precedence table glibc uses when gai.conf has no `precedence` rules.
+pub const DEFAULT_PRECEDENCES: [(&str, u32); 5] = [
+    ("::1/128", 50),
+    ("::/0", 40),
+    ("2002::/16", 30),
+    ("::/96", 20),
+    ("::ffff:0:0/96", 10),
+];
+
+/// An IPv6 network the label and precedence rules match against.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Prefix {
+    pub address: Ipv6Addr,
+    pub len: u8,
+}
+
+impl Prefix {
+    pub fn contains(&self, address: &Ipv6Addr) -> bool {
+        let mask = u128::MAX.checked_shl(128 - self.len as u32).unwrap_or(0);
+
+        u128::from(self.address) & mask == u128::from(*address) & mask
+    }
+}
+
+impl FromStr for Prefix {
+    type Err = ParseGaiError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (address, len) = match s.split_once('/') {
+            Some((address, len)) => (address, len.parse()?),
+            None => (s, 128),
+        };
+
+        let address = address
+            .parse()
+            .map_err(|_| ParseGaiError::InvalidEntry(s.to_owned()))?;
+
+        if len > 128 {
+            return Err(ParseGaiError::InvalidEntry(s.to_owned()));
+        }
+
+        Ok(Prefix { address, len })
+    }
+}
+
+impl fmt::Display for Prefix {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}/{}", self.address, self.len)
+    }
+}
+
+/// An IPv4 network of a `scopev4` rule, written either dotted or IPv4-mapped.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Prefix4 {
+    pub address: Ipv4Addr,
+    pub len: u8,
+}
+
+impl Prefix4 {
+    pub fn contains(&self, address: &Ipv4Addr) -> bool {
+        let mask = u32::MAX.checked_shl(32 - self.len as u32).unwrap_or(0);
+
+        u32::from(self.address) & mask == u32::from(*address) & mask
+    }
+}
+
+impl FromStr for Prefix4 {
+    type Err = ParseGaiError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (address, len) = match s.split_once('/') {
+            Some((address, len)) => (address, Some(len.parse::<u8>()?)),
+            None => (s, None),
+        };
+
+        let invalid = || ParseGaiError::InvalidEntry(s.to_owned());
+
+        match address.parse::<IpAddr>().map_err(|_| invalid())? {
+            IpAddr::V4(address) => match len.unwrap_or(32) {
+                len @ 0..=32 => Ok(Prefix4 { address, len }),
+                _ => Err(invalid()),
+            },
+            IpAddr::V6(address) => match (address.to_ipv4_mapped(), len.unwrap_or(128)) {
+                (Some(address), len @ 96..=128) => Ok(Prefix4 {
+                    address,
+                    len: len - 96,
+                }),
+                _ => Err(invalid()),
+            },
+        }
+    }
+}
+
+impl fmt::Display for Prefix4 {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}/{}", self.address, self.len)
+    }
+}
+
+/// A directive of gai.conf.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Rule {
+    Label { prefix: Prefix, label: u32 },
+    Precedence { prefix: Prefix, precedence: u32 },
+    ScopeV4 { prefix: Prefix4, scope: u32 },
+    Reload(bool),
+}
+
+impl FromStr for Rule {
+    type Err = ParseGaiError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let fields = s.split_whitespace().collect::<Vec<_>>();
+
+        match fields[..] {
+            ["label", prefix, label] => Ok(Rule::Label {
+                prefix: prefix.parse()?,
+                label: label.parse()?,
+            }),
+            ["precedence", prefix, precedence] => Ok(Rule::Precedence {
+                prefix: prefix.parse()?,
+                precedence: precedence.parse()?,
+            }),
+            ["scopev4", prefix, scope] => Ok(Rule::ScopeV4 {
+                prefix: prefix.parse()?,
+                scope: scope.parse()?,
+            }),
+            ["reload", "yes"] => Ok(Rule::Reload(true)),
+            ["reload", "no"] => Ok(Rule::Reload(false)),
+            _ => Err(ParseGaiError::InvalidEntry(s.to_owned())),
+        }
+    }
+}
+
+impl fmt::Display for Rule {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Label { prefix, label } => write!(f, "label {} {}", prefix, label),
+            Self::Precedence { prefix, precedence } => {
+                write!(f, "precedence {} {}", prefix, precedence)
+            }
+            Self::ScopeV4 { prefix, scope } => write!(f, "scopev4 {} {}", prefix, scope),
+            Self::Reload(reload) => write!(f, "reload {}", if *reload { "yes" } else { "no" }),
+        }
+    }
+}
+
+/// The rules of gai.conf in file order.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct Config {
+    pub rules: Vec<Rule>,
+}
+
+impl Config {
+    pub fn reload(&self) -> bool {
+        self.rules
+            .iter()
+            .rev()
+            .find_map(|rule| match rule {
+                Rule::Reload(reload) => Some(*reload),
+                _ => None,
+            })
+            .unwrap_or(false)
+    }
+
+    ///
+    /// Returns the label of the address by the longest matching prefix.
+    /// Any `label` rule replaces the whole default table, as in glibc.
+    ///
+    /// ```no_run
+    /// let config = unixism::gai::parse_default().unwrap();
+    ///
+    /// println!("{:?}", config.label(&"2001:db8::1".parse().unwrap()));
+    /// ```
+    pub fn label(&self, address: &Ipv6Addr) -> Option<u32> {
+        let labels = self
+            .rules
+            .iter()
+            .filter_map(|rule| match rule {
+                Rule::Label { prefix, label } => Some((*prefix, *label)),
+                _ => None,
+            })
+            .collect::<Vec<_>>();
+
+        lookup(&labels, &DEFAULT_LABELS, address)
+    }
+
+    ///
+    /// Returns the precedence of the address by the longest matching prefix.
+    /// Any `precedence` rule replaces the whole default table, as in glibc.
+    ///
+    /// ```no_run
+    /// let config = unixism::gai::parse_default().unwrap();
+    ///
+    /// println!("{:?}", config.precedence(&"::ffff:192.0.2.1".parse().unwrap()));
+    /// ```
+    pub fn precedence(&self, address: &Ipv6Addr) -> Option<u32> {
+        let precedences = self
+            .rules
+            .iter()
+            .filter_map(|rule| match rule {
+                Rule::Precedence { prefix, precedence } => Some((*prefix, *precedence)),
+                _ => None,
+            })
+            .collect::<Vec<_>>();
+
+        lookup(&precedences, &DEFAULT_PRECEDENCES, address)
+    }
+
+    /// Returns the scope of the IPv4 address by the longest matching `scopev4` rule.
+    pub fn scope(&self, address: &Ipv4Addr) -> Option<u32> {
+        self.rules
+            .iter()
+            .filter_map(|rule| match rule {
+                Rule::ScopeV4 { prefix, scope } if prefix.contains(address) => {
+                    Some((prefix.len, *scope))
+                }
+                _ => None,
+            })
+            .max_by_key(|(len, _)| *len)
+            .map(|(_, scope)| scope)
+    }
+}
+
+fn lookup(rules: &[(Prefix, u32)], defaults: &[(&str, u32)], address: &Ipv6Addr) -> Option<u32> {
+    let defaults = defaults
+        .iter()
+        .filter_map(|(prefix, value)| Some((prefix.parse::<Prefix>().ok()?, *value)))
+        .collect::<Vec<_>>();
+
+    let rules = if rules.is_empty() { &defaults } else { rules };
+
+    rules
+        .iter()
+        .filter(|(prefix, _)| prefix.contains(address))
+        .max_by_key(|(prefix, _)| prefix.len)
+        .map(|(_, value)| *value)
+}
+
+#[derive(Debug)]
+pub enum ParseGaiError {
+    InvalidEntry(String),
+    ParseIntError(ParseIntError),
+    IOError(io::Error),
+}
+
+impl error::Error for ParseGaiError {}
+
+impl fmt::Display for ParseGaiError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::InvalidEntry(entry) => write!(f, "invalid gai.conf entry: {}", entry),
+            Self::ParseIntError(error) => write!(f, "{}", error),
+            Self::IOError(error) => write!(f, "{}", error),
+        }
+    }
+}
+
+impl From<ParseIntError> for ParseGaiError {
+    fn from(value: ParseIntError) -> Self {
+        ParseGaiError::ParseIntError(value)
+    }
+}
+
+impl From<io::Error> for ParseGaiError {
+    fn from(value: io::Error) -> Self {
+        ParseGaiError::IOError(value)
+    }
+}
+
+///
+/// ```no_run
+/// let config = unixism::gai::parse(std::fs::File::open("/etc/gai.conf").unwrap()).unwrap();
+///
+/// for rule in &config.rules {
+///     println!("{rule}");
+/// }
+/// ```
+pub fn parse<R>(reader: R) -> Result<Config, ParseGaiError>
+where
+    R: io::Read,
+{
+    let mut config = Config::default();
+
+    for line in BufReader::new(reader).lines() {
+        let line = line?;
+        let line = line.split('#').next().unwrap_or_default().trim();
+
+        if line.is_empty() {
+            continue;
+        }
+
+        config.rules.push(line.parse::<Rule>()?);
+    }
+
+    Ok(config)
+}
+
+///
+/// Same as parse, but parses the `/etc/gai.conf` as default.
+///
+/// ```no_run
+/// let config = unixism::gai::parse_default().unwrap();
+/// ```
+pub fn parse_default() -> Result<Config, ParseGaiError> {
+    parse(fs::File::open("/etc/gai.conf")?)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Cursor;
+
+    use super::*;
+
+    #[test]
+    fn it_parse() {
+        let config = parse(Cursor::new(
+            r#"
+# Configuration for getaddrinfo(3).
+#label ::1/128       0
+reload yes
+precedence  ::1/128       50
+precedence  ::/0          40
+precedence  2002::/16     30
+precedence ::/96          20
+precedence ::ffff:0:0/96  100   # prefer IPv4
+scopev4 ::ffff:169.254.0.0/112  2
+scopev4 127.0.0.0/8             2
+scopev4 0.0.0.0/0               14
+        "#,
+        ))
+        .unwrap();
+
+        assert_eq!(9, config.rules.len());
+        assert!(config.reload());
+        assert_eq!(
+            Rule::Precedence {
+                prefix: "::ffff:0:0/96".parse().unwrap(),
+                precedence: 100
+            },
+            config.rules[5]
+        );
+        assert_eq!(
+            Rule::ScopeV4 {
+                prefix: Prefix4 {
+                    address: Ipv4Addr::new(169, 254, 0, 0),
+                    len: 16
+                },
+                scope: 2
+            },
+            config.rules[6]
+        );
+        assert_eq!("scopev4 169.254.0.0/16 2", config.rules[6].to_string());
+
+        assert_eq!(
+            Some(100),
+            config.precedence(&"::ffff:192.0.2.1".parse().unwrap())
+        );
+        assert_eq!(Some(40), config.precedence(&"2001:db8::1".parse().unwrap()));
+        assert_eq!(Some(7), config.label(&"2001::1".parse().unwrap()));
+        assert_eq!(Some(1), config.label(&"2a00::1".parse().unwrap()));
+        assert_eq!(Some(2), config.scope(&Ipv4Addr::new(169, 254, 1, 1)));
+        assert_eq!(Some(14), config.scope(&Ipv4Addr::new(8, 8, 8, 8)));
+
+        assert!(parse(Cursor::new("label ::1/129 0")).is_err());
+        assert!(parse(Cursor::new("scopev4 2001:db8::/112 2")).is_err());
+        assert!(parse(Cursor::new("reload maybe")).is_err());
+        assert!(parse(Cursor::new("weight ::/0 1")).is_err());
+    }
+}
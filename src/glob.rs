@@ -0,0 +1,28 @@
+///
+/// Matches the value against a shell pattern with `*` and `?`, without character classes.
+pub(crate) fn glob(pattern: &str, value: &str) -> bool {
+    fn go(pattern: &[u8], value: &[u8]) -> bool {
+        match pattern.first() {
+            None => value.is_empty(),
+            Some(b'*') => (0..=value.len()).any(|i| go(&pattern[1..], &value[i..])),
+            Some(b'?') => !value.is_empty() && go(&pattern[1..], &value[1..]),
+            Some(c) => value.first() == Some(c) && go(&pattern[1..], &value[1..]),
+        }
+    }
+
+    go(pattern.as_bytes(), value.as_bytes())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn it_glob() {
+        assert!(glob("*.conf", "libc.conf"));
+        assert!(glob("eth?", "eth0"));
+        assert!(glob("*", ""));
+        assert!(!glob("eth?", "eth10"));
+        assert!(!glob("*.conf", "libc.conf~"));
+    }
+}
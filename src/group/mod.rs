@@ -0,0 +1,673 @@
+use std::{
+    collections::HashMap,
+    error, fmt, fs,
+    io::{self, BufRead, BufReader},
+    num::ParseIntError,
+    ops::RangeInclusive,
+    path::{Path, PathBuf},
+    str::FromStr,
+};
+
+pub use crate::{
+    compat::{Compat, CompatTarget},
+    file::PasswdLock as Lock,
+};
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Group {
+    pub name: String,
+    pub passwd: String,
+    pub gid: u32,
+    pub members: Vec<String>,
+}
+
+impl FromStr for Group {
+    type Err = ParseGroupError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let fields = s.split(':').collect::<Vec<_>>();
+
+        let [name, passwd, gid, members] = fields[..] else {
+            return Err(ParseGroupError::InvalidEntry {
+                line: 0,
+                entry: s.to_owned(),
+            });
+        };
+
+        Ok(Group {
+            name: name.to_owned(),
+            passwd: passwd.to_owned(),
+            gid: gid
+                .parse()
+                .map_err(|error| ParseGroupError::ParseIntError { line: 0, error })?,
+            members: members
+                .split(',')
+                .filter(|member| !member.is_empty())
+                .map(String::from)
+                .collect(),
+        })
+    }
+}
+
+impl Group {
+    /// Checks the group can be written as a single line, without a `:` or a newline in a field
+    /// and without a `,` in a member name.
+    fn validate(&self) -> Result<(), ParseGroupError> {
+        let invalid = |field: &String| field.contains([':', '\n']);
+
+        if !crate::compat::valid_name(&self.name)
+            || invalid(&self.name)
+            || invalid(&self.passwd)
+            || self
+                .members
+                .iter()
+                .any(|member| member.is_empty() || invalid(member) || member.contains(','))
+        {
+            return Err(ParseGroupError::InvalidEntry {
+                line: 0,
+                entry: self.to_string().escape_debug().to_string(),
+            });
+        }
+
+        Ok(())
+    }
+}
+
+impl fmt::Display for Group {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{}:{}:{}:{}",
+            self.name,
+            self.passwd,
+            self.gid,
+            self.members.join(",")
+        )
+    }
+}
+
+#[derive(Debug)]
+pub enum ParseGroupError {
+    InvalidEntry { line: usize, entry: String },
+    DuplicateEntry(String),
+    ParseIntError { line: usize, error: ParseIntError },
+    IOError(io::Error),
+}
+
+impl ParseGroupError {
+    fn at(self, line: usize) -> Self {
+        match self {
+            Self::InvalidEntry { entry, .. } => Self::InvalidEntry { line, entry },
+            Self::ParseIntError { error, .. } => Self::ParseIntError { line, error },
+            error => error,
+        }
+    }
+}
+
+impl error::Error for ParseGroupError {}
+
+impl fmt::Display for ParseGroupError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::InvalidEntry { line, entry } => {
+                write!(f, "line {}: invalid group entry: {}", line, entry)
+            }
+            Self::DuplicateEntry(name) => write!(f, "group {} already exists", name),
+            Self::ParseIntError { line, error } => write!(f, "line {}: {}", line, error),
+            Self::IOError(error) => write!(f, "{}", error),
+        }
+    }
+}
+
+impl From<io::Error> for ParseGroupError {
+    fn from(value: io::Error) -> Self {
+        ParseGroupError::IOError(value)
+    }
+}
+
+/// A line of the /etc/group file in glibc compat mode.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Record {
+    Group(Group),
+    Compat(Compat),
+}
+
+///
+/// ```no_run
+/// let groups = unixism::group::parse(std::fs::File::open("/etc/group").unwrap()).unwrap();
+///
+/// for group in groups {
+///     println!("name: {}, gid: {}, members: {:?}", group.name, group.gid, group.members);
+/// }
+/// ```
+pub fn parse<R>(reader: R) -> Result<impl Iterator<Item = Group>, ParseGroupError>
+where
+    R: io::Read,
+{
+    Ok(parse_compat(reader)?.filter_map(|record| match record {
+        Record::Group(group) => Some(group),
+        Record::Compat(_) => None,
+    }))
+}
+
+///
+/// Same as parse, but keeps the NSS compat lines (`+`, `+name`, `-name`, `+@netgroup`)
+/// as typed records instead of skipping them.
+///
+/// ```no_run
+/// use unixism::group::{self, Compat, Record};
+///
+/// for record in group::parse_compat(std::fs::File::open("/etc/group").unwrap()).unwrap() {
+///     if let Record::Compat(Compat::Include { target, .. }) = record {
+///         println!("included from NIS: {:?}", target);
+///     }
+/// }
+/// ```
+pub fn parse_compat<R>(reader: R) -> Result<impl Iterator<Item = Record>, ParseGroupError>
+where
+    R: io::Read,
+{
+    let mut records = Vec::new();
+
+    for (number, line) in BufReader::new(reader).lines().enumerate() {
+        let line = line?;
+
+        if line.trim().is_empty() || line.starts_with('#') {
+            continue;
+        }
+
+        match Compat::parse(&line) {
+            Some(compat) => records.push(Record::Compat(compat)),
+            None => records.push(Record::Group(
+                line.parse::<Group>()
+                    .map_err(|error| error.at(number + 1))?,
+            )),
+        }
+    }
+
+    Ok(records.into_iter())
+}
+
+///
+/// Same as parse, but parses the `/etc/group` as default.
+///
+/// ```no_run
+/// let groups = unixism::group::parse_default().unwrap();
+/// ```
+pub fn parse_default() -> Result<impl Iterator<Item = Group>, ParseGroupError> {
+    parse(fs::File::open("/etc/group")?)
+}
+
+///
+/// Lookup index over parsed groups, an in-memory equivalent of `getgrnam` and `getgrgid`.
+/// Like libc, the first group wins when names or ids are duplicated.
+///
+/// ```no_run
+/// let index = unixism::group::Index::new(unixism::group::parse_default().unwrap());
+///
+/// if let Some(group) = index.by_gid(0) {
+///     println!("{}", group.name);
+/// }
+/// ```
+#[derive(Debug, Default, Clone)]
+pub struct Index {
+    groups: Vec<Group>,
+    by_name: HashMap<String, usize>,
+    by_gid: HashMap<u32, usize>,
+}
+
+impl Index {
+    pub fn new<I>(groups: I) -> Self
+    where
+        I: IntoIterator<Item = Group>,
+    {
+        groups.into_iter().collect()
+    }
+
+    pub fn by_name(&self, name: &str) -> Option<&Group> {
+        self.by_name.get(name).map(|i| &self.groups[*i])
+    }
+
+    pub fn by_gid(&self, gid: u32) -> Option<&Group> {
+        self.by_gid.get(&gid).map(|i| &self.groups[*i])
+    }
+
+    pub fn groups(&self) -> &[Group] {
+        &self.groups
+    }
+}
+
+///
+/// Returns the lowest GID in `range` not taken by any group in `index`.
+/// Ranges usually come from `/etc/login.defs`, see `logindefs::LoginDefs::gid_range`.
+///
+/// ```no_run
+/// use unixism::group;
+///
+/// let index = group::Index::new(group::parse_default().unwrap());
+///
+/// let gid = group::next_free_gid(&index, 1000..=60000);
+/// ```
+pub fn next_free_gid(index: &Index, range: RangeInclusive<u32>) -> Option<u32> {
+    range.into_iter().find(|gid| index.by_gid(*gid).is_none())
+}
+
+impl FromIterator<Group> for Index {
+    fn from_iter<I>(iter: I) -> Self
+    where
+        I: IntoIterator<Item = Group>,
+    {
+        let mut index = Index::default();
+
+        for group in iter {
+            let i = index.groups.len();
+
+            index.by_name.entry(group.name.clone()).or_insert(i);
+            index.by_gid.entry(group.gid).or_insert(i);
+            index.groups.push(group);
+        }
+
+        index
+    }
+}
+
+///
+/// Resolves every group `user` belongs to, like `id`: the primary group from passwd first,
+/// then the supplementary groups listing the user as a member.
+///
+/// ```no_run
+/// use unixism::{group, passwd};
+///
+/// let users = passwd::Index::new(passwd::parse_default().unwrap());
+/// let groups = group::Index::new(group::parse_default().unwrap());
+///
+/// for group in group::groups_of("root", &users, &groups) {
+///     println!("{}({})", group.name, group.gid);
+/// }
+/// ```
+#[cfg(feature = "passwd")]
+pub fn groups_of(user: &str, users: &crate::passwd::Index, groups: &Index) -> Vec<Group> {
+    let mut resolved: Vec<Group> = Vec::new();
+
+    if let Some(primary) = users.by_name(user).and_then(|user| groups.by_gid(user.gid)) {
+        resolved.push(primary.clone());
+    }
+
+    for group in groups.groups() {
+        if group.members.iter().any(|member| member == user)
+            && !resolved.iter().any(|resolved| resolved.gid == group.gid)
+        {
+            resolved.push(group.clone());
+        }
+    }
+
+    resolved
+}
+
+///
+/// Resolves every member of `group`: the users listed in the group followed by
+/// the users whose primary group it is.
+///
+/// ```no_run
+/// use unixism::{group, passwd};
+///
+/// let users = passwd::Index::new(passwd::parse_default().unwrap());
+/// let groups = group::Index::new(group::parse_default().unwrap());
+///
+/// println!("{:?}", group::members_of("sudo", &users, &groups));
+/// ```
+#[cfg(feature = "passwd")]
+pub fn members_of(group: &str, users: &crate::passwd::Index, groups: &Index) -> Vec<String> {
+    let Some(group) = groups.by_name(group) else {
+        return Vec::new();
+    };
+
+    let mut members = group.members.clone();
+
+    for user in users.entries() {
+        if user.gid == group.gid && !members.contains(&user.name) {
+            members.push(user.name.clone());
+        }
+    }
+
+    members
+}
+
+#[derive(Debug)]
+enum Line {
+    /// A parsed group, `raw` keeps the original text until the group is modified.
+    Group {
+        raw: Option<String>,
+        group: Group,
+    },
+    Other(String),
+}
+
+///
+/// Edits the `/etc/group` under the `/etc/.pwd.lock` lock, keeping untouched lines exactly as they were.
+/// Changes are written via a temporary file and rename on save.
+///
+/// ```no_run
+/// use unixism::group::{Editor, Group};
+///
+/// let mut editor = Editor::open().unwrap();
+///
+/// editor
+///     .add(Group {
+///         name: "deploy".to_owned(),
+///         passwd: "x".to_owned(),
+///         gid: 1001,
+///         members: vec![],
+///     })
+///     .unwrap();
+///
+/// editor.update("docker", |group| group.members.push("deploy".to_owned()));
+/// editor.save().unwrap();
+/// ```
+#[derive(Debug)]
+pub struct Editor {
+    path: PathBuf,
+    lines: Vec<Line>,
+    _lock: Lock,
+}
+
+impl Editor {
+    pub fn open() -> Result<Self, ParseGroupError> {
+        Self::open_at("/etc/group", Lock::PATH)
+    }
+
+    pub fn open_at<P, L>(path: P, lock: L) -> Result<Self, ParseGroupError>
+    where
+        P: AsRef<Path>,
+        L: AsRef<Path>,
+    {
+        let lock = Lock::acquire_at(lock)?;
+        let content = fs::read_to_string(path.as_ref())?;
+
+        let mut lines = Vec::new();
+        for (number, line) in content.lines().enumerate() {
+            if line.trim().is_empty() || line.starts_with('#') || Compat::parse(line).is_some() {
+                lines.push(Line::Other(line.to_owned()));
+            } else {
+                lines.push(Line::Group {
+                    group: line
+                        .parse::<Group>()
+                        .map_err(|error| error.at(number + 1))?,
+                    raw: Some(line.to_owned()),
+                });
+            }
+        }
+
+        Ok(Self {
+            path: path.as_ref().to_owned(),
+            lines,
+            _lock: lock,
+        })
+    }
+
+    pub fn groups(&self) -> impl Iterator<Item = &Group> {
+        self.lines.iter().filter_map(|line| match line {
+            Line::Group { group, .. } => Some(group),
+            Line::Other(_) => None,
+        })
+    }
+
+    pub fn get(&self, name: &str) -> Option<&Group> {
+        self.groups().find(|group| group.name == name)
+    }
+
+    pub fn add(&mut self, group: Group) -> Result<(), ParseGroupError> {
+        group.validate()?;
+
+        if self.get(&group.name).is_some() {
+            return Err(ParseGroupError::DuplicateEntry(group.name));
+        }
+
+        self.lines.push(Line::Group { raw: None, group });
+
+        Ok(())
+    }
+
+    /// Applies `f` to the group named `name`, returns false if there is no such group.
+    pub fn update<F>(&mut self, name: &str, f: F) -> bool
+    where
+        F: FnOnce(&mut Group),
+    {
+        for line in &mut self.lines {
+            if let Line::Group { raw, group } = line {
+                if group.name == name {
+                    f(group);
+                    *raw = None;
+
+                    return true;
+                }
+            }
+        }
+
+        false
+    }
+
+    pub fn remove(&mut self, name: &str) -> Option<Group> {
+        let position = self
+            .lines
+            .iter()
+            .position(|line| matches!(line, Line::Group { group, .. } if group.name == name))?;
+
+        match self.lines.remove(position) {
+            Line::Group { group, .. } => Some(group),
+            Line::Other(_) => None,
+        }
+    }
+
+    pub fn save(&self) -> Result<(), ParseGroupError> {
+        let mut content = String::new();
+
+        for line in &self.lines {
+            match line {
+                Line::Group { raw: Some(raw), .. } | Line::Other(raw) => content.push_str(raw),
+                Line::Group { raw: None, group } => {
+                    // Updated groups are checked here, an invalid field would inject a line.
+                    group.validate()?;
+                    content.push_str(&group.to_string());
+                }
+            }
+            content.push('\n');
+        }
+
+        Ok(crate::file::write_atomic(
+            &self.path,
+            content.as_bytes(),
+            true,
+        )?)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Cursor;
+
+    use super::*;
+    use crate::tempdir::TempDir;
+
+    #[test]
+    fn it_parse() {
+        let groups = parse(Cursor::new(
+            r#"
+root:x:0:
+sudo:x:27:kafkiansky
+docker:x:999:kafkiansky,deploy
+        "#,
+        ))
+        .unwrap()
+        .collect::<Vec<_>>();
+
+        assert_eq!(
+            vec![
+                Group {
+                    name: "root".to_owned(),
+                    passwd: "x".to_owned(),
+                    gid: 0,
+                    members: vec![],
+                },
+                Group {
+                    name: "sudo".to_owned(),
+                    passwd: "x".to_owned(),
+                    gid: 27,
+                    members: vec!["kafkiansky".to_owned()],
+                },
+                Group {
+                    name: "docker".to_owned(),
+                    passwd: "x".to_owned(),
+                    gid: 999,
+                    members: vec!["kafkiansky".to_owned(), "deploy".to_owned()],
+                },
+            ],
+            groups
+        );
+
+        let error = parse(Cursor::new("root:x:0:\nwheel:x:ten:\n"))
+            .err()
+            .unwrap();
+        assert!(matches!(
+            error,
+            ParseGroupError::ParseIntError { line: 2, .. }
+        ));
+        assert_eq!("line 2: invalid digit found in string", error.to_string());
+
+        let error = parse(Cursor::new("\nwheel:x:10\n")).err().unwrap();
+        assert!(matches!(
+            error,
+            ParseGroupError::InvalidEntry { line: 2, .. }
+        ));
+    }
+
+    #[test]
+    fn it_edit() {
+        let dir = TempDir::new("group");
+
+        let path = dir.join("group");
+        fs::write(&path, "root:x:0:\nsudo:x:27:kafkiansky\n").unwrap();
+
+        let mut editor = Editor::open_at(&path, dir.join(".pwd.lock")).unwrap();
+
+        assert!(editor
+            .add(Group {
+                name: "deploy".to_owned(),
+                passwd: "x".to_owned(),
+                gid: 1002,
+                members: vec![],
+            })
+            .is_ok());
+        assert!(matches!(
+            editor.add(Group {
+                name: "evil".to_owned(),
+                passwd: "x".to_owned(),
+                gid: 1003,
+                members: vec!["a\nroot2:x:0:".to_owned()],
+            }),
+            Err(ParseGroupError::InvalidEntry { .. })
+        ));
+        for name in ["+", "+@admins", "-wheel", "\twheel"] {
+            assert!(matches!(
+                editor.add(Group {
+                    name: name.to_owned(),
+                    passwd: "x".to_owned(),
+                    gid: 1004,
+                    members: vec![],
+                }),
+                Err(ParseGroupError::InvalidEntry { .. })
+            ));
+        }
+        assert!(editor.update("sudo", |group| group.members.push("a,b".to_owned())));
+        assert!(matches!(
+            editor.save(),
+            Err(ParseGroupError::InvalidEntry { .. })
+        ));
+        assert!(editor.update("sudo", |group| {
+            group.members.pop();
+            group.members.push("deploy".to_owned());
+        }));
+
+        editor.save().unwrap();
+
+        assert_eq!(
+            "root:x:0:\nsudo:x:27:kafkiansky,deploy\ndeploy:x:1002:\n",
+            fs::read_to_string(&path).unwrap()
+        );
+    }
+
+    #[test]
+    fn it_index() {
+        let index = Index::new(parse(Cursor::new("root:x:0:\nwheel:x:10:root")).unwrap());
+
+        assert_eq!("wheel", index.by_gid(10).unwrap().name);
+        assert_eq!(0, index.by_name("root").unwrap().gid);
+        assert!(index.by_gid(100).is_none());
+
+        assert_eq!(Some(11), next_free_gid(&index, 10..=20));
+        assert_eq!(None, next_free_gid(&index, 10..=10));
+    }
+
+    #[cfg(feature = "passwd")]
+    #[test]
+    fn it_resolve_membership() {
+        let users = crate::passwd::Index::new(
+            crate::passwd::parse(Cursor::new(
+                "root:x:0:0::/root:/bin/sh\nalice:x:1000:1000::/home/alice:/bin/sh\nbob:x:1001:27::/home/bob:/bin/sh",
+            ))
+            .unwrap(),
+        );
+        let groups = Index::new(
+            parse(Cursor::new(
+                "root:x:0:\nsudo:x:27:alice\nalice:x:1000:\ndocker:x:999:alice,bob\nstaff:x:50:alice",
+            ))
+            .unwrap(),
+        );
+
+        assert_eq!(
+            vec!["alice", "sudo", "docker", "staff"],
+            groups_of("alice", &users, &groups)
+                .iter()
+                .map(|group| group.name.as_str())
+                .collect::<Vec<_>>()
+        );
+        assert_eq!(
+            vec!["sudo", "docker"],
+            groups_of("bob", &users, &groups)
+                .iter()
+                .map(|group| group.name.as_str())
+                .collect::<Vec<_>>()
+        );
+
+        assert_eq!(vec!["alice", "bob"], members_of("sudo", &users, &groups));
+        assert!(members_of("missing", &users, &groups).is_empty());
+    }
+
+    #[test]
+    fn it_parse_compat() {
+        let records = parse_compat(Cursor::new("wheel:x:10:root\n-games\n+@staff\n+"))
+            .unwrap()
+            .collect::<Vec<_>>();
+
+        assert_eq!(
+            vec![
+                Record::Group(Group {
+                    name: "wheel".to_owned(),
+                    passwd: "x".to_owned(),
+                    gid: 10,
+                    members: vec!["root".to_owned()],
+                }),
+                Record::Compat(Compat::Exclude(CompatTarget::Name("games".to_owned()))),
+                Record::Compat(Compat::Include {
+                    target: CompatTarget::Netgroup("staff".to_owned()),
+                    overrides: vec![],
+                }),
+                Record::Compat(Compat::Include {
+                    target: CompatTarget::All,
+                    overrides: vec![],
+                }),
+            ],
+            records
+        );
+    }
+}
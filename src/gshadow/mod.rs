@@ -0,0 +1,147 @@
+use std::{
+    error, fmt, fs,
+    io::{self, BufRead, BufReader},
+    str::FromStr,
+};
+
+use crate::shadow::Password;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Entry {
+    pub name: String,
+    pub password: Password,
+    pub administrators: Vec<String>,
+    pub members: Vec<String>,
+}
+
+impl FromStr for Entry {
+    type Err = ParseGshadowError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let fields = s.split(':').collect::<Vec<_>>();
+
+        let [name, password, administrators, members] = fields[..] else {
+            return Err(ParseGshadowError::InvalidEntry(s.to_owned()));
+        };
+
+        Ok(Entry {
+            name: name.to_owned(),
+            password: Password::from(password),
+            administrators: split_list(administrators),
+            members: split_list(members),
+        })
+    }
+}
+
+fn split_list(s: &str) -> Vec<String> {
+    s.split(',')
+        .filter(|name| !name.is_empty())
+        .map(String::from)
+        .collect()
+}
+
+#[derive(Debug)]
+pub enum ParseGshadowError {
+    InvalidEntry(String),
+    IOError(io::Error),
+}
+
+impl error::Error for ParseGshadowError {}
+
+impl fmt::Display for ParseGshadowError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::InvalidEntry(entry) => write!(f, "invalid gshadow entry: {}", entry),
+            Self::IOError(error) => write!(f, "{}", error),
+        }
+    }
+}
+
+impl From<io::Error> for ParseGshadowError {
+    fn from(value: io::Error) -> Self {
+        ParseGshadowError::IOError(value)
+    }
+}
+
+///
+/// ```no_run
+/// let entries = unixism::gshadow::parse(std::fs::File::open("/etc/gshadow").unwrap()).unwrap();
+///
+/// for entry in entries {
+///     println!("group: {}, administrators: {:?}", entry.name, entry.administrators);
+/// }
+/// ```
+pub fn parse<R>(reader: R) -> Result<impl Iterator<Item = Entry>, ParseGshadowError>
+where
+    R: io::Read,
+{
+    let mut entries = Vec::new();
+
+    for line in BufReader::new(reader).lines() {
+        let line = line?;
+
+        if line.trim().is_empty() || line.starts_with('#') {
+            continue;
+        }
+
+        entries.push(line.parse::<Entry>()?);
+    }
+
+    Ok(entries.into_iter())
+}
+
+///
+/// Same as parse, but parses the `/etc/gshadow` as default.
+///
+/// ```no_run
+/// let entries = unixism::gshadow::parse_default().unwrap();
+/// ```
+pub fn parse_default() -> Result<impl Iterator<Item = Entry>, ParseGshadowError> {
+    parse(fs::File::open("/etc/gshadow")?)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Cursor;
+
+    use super::*;
+
+    #[test]
+    fn it_parse() {
+        let entries = parse(Cursor::new(
+            r#"
+root:*::
+sudo:*::kafkiansky
+devs:$6$salt$hash:lead:lead,dev1,dev2
+        "#,
+        ))
+        .unwrap()
+        .collect::<Vec<_>>();
+
+        assert_eq!(
+            vec![
+                Entry {
+                    name: "root".to_owned(),
+                    password: Password::Locked("*".to_owned()),
+                    administrators: vec![],
+                    members: vec![],
+                },
+                Entry {
+                    name: "sudo".to_owned(),
+                    password: Password::Locked("*".to_owned()),
+                    administrators: vec![],
+                    members: vec!["kafkiansky".to_owned()],
+                },
+                Entry {
+                    name: "devs".to_owned(),
+                    password: Password::Hash("$6$salt$hash".to_owned()),
+                    administrators: vec!["lead".to_owned()],
+                    members: vec!["lead".to_owned(), "dev1".to_owned(), "dev2".to_owned()],
+                },
+            ],
+            entries
+        );
+
+        assert!(parse(Cursor::new("root:*:")).is_err());
+    }
+}
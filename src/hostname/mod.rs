@@ -0,0 +1,207 @@
+use std::{
+    error, fmt, fs,
+    io::{self, BufRead, BufReader},
+    path::Path,
+};
+
+/// Maximum length of a host name, `HOST_NAME_MAX` of the kernel.
+pub const HOST_NAME_MAX: usize = 64;
+
+/// Maximum length of a single label, as RFC 1035 defines.
+pub const LABEL_MAX: usize = 63;
+
+#[derive(Debug)]
+pub enum ParseHostnameError {
+    Empty,
+    TooLong(String),
+    InvalidLabel(String),
+    IOError(io::Error),
+}
+
+impl error::Error for ParseHostnameError {}
+
+impl fmt::Display for ParseHostnameError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Empty => write!(f, "host name is empty"),
+            Self::TooLong(name) => write!(
+                f,
+                "host name {} is longer than {} characters",
+                name, HOST_NAME_MAX
+            ),
+            Self::InvalidLabel(label) => write!(f, "invalid host name label: {}", label),
+            Self::IOError(error) => write!(f, "{}", error),
+        }
+    }
+}
+
+impl From<io::Error> for ParseHostnameError {
+    fn from(value: io::Error) -> Self {
+        ParseHostnameError::IOError(value)
+    }
+}
+
+///
+/// Checks the name against RFC 1123: dot separated labels of up to 63 letters, digits
+/// and hyphens, not starting or ending with a hyphen, and at most `HOST_NAME_MAX` in total.
+///
+/// ```
+/// assert!(unixism::hostname::validate("web-01.example.com").is_ok());
+/// assert!(unixism::hostname::validate("-web").is_err());
+/// ```
+pub fn validate(name: &str) -> Result<(), ParseHostnameError> {
+    if name.is_empty() {
+        return Err(ParseHostnameError::Empty);
+    }
+
+    if name.len() > HOST_NAME_MAX {
+        return Err(ParseHostnameError::TooLong(name.to_owned()));
+    }
+
+    for label in name.strip_suffix('.').unwrap_or(name).split('.') {
+        let valid = !label.is_empty()
+            && label.len() <= LABEL_MAX
+            && !label.starts_with('-')
+            && !label.ends_with('-')
+            && label.chars().all(|c| c.is_ascii_alphanumeric() || c == '-');
+
+        if !valid {
+            return Err(ParseHostnameError::InvalidLabel(label.to_owned()));
+        }
+    }
+
+    Ok(())
+}
+
+///
+/// Reads the first line that is not a comment, the way systemd reads `/etc/hostname`.
+///
+/// ```no_run
+/// let hostname = unixism::hostname::parse(std::fs::File::open("/etc/hostname").unwrap()).unwrap();
+///
+/// println!("{hostname}");
+/// ```
+pub fn parse<R>(reader: R) -> Result<String, ParseHostnameError>
+where
+    R: io::Read,
+{
+    for line in BufReader::new(reader).lines() {
+        let line = line?;
+        let line = line.trim();
+
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+
+        validate(line)?;
+
+        return Ok(line.to_owned());
+    }
+
+    Err(ParseHostnameError::Empty)
+}
+
+///
+/// Same as parse, but parses the `/etc/hostname` as default.
+///
+/// ```no_run
+/// let hostname = unixism::hostname::parse_default().unwrap();
+/// ```
+pub fn parse_default() -> Result<String, ParseHostnameError> {
+    parse(fs::File::open("/etc/hostname")?)
+}
+
+///
+/// Reads the host name the kernel currently uses, which differs from `/etc/hostname`
+/// until the next boot or `hostname(1)` call.
+///
+/// ```no_run
+/// if unixism::hostname::kernel().unwrap() != unixism::hostname::parse_default().unwrap() {
+///     println!("the host name was changed since boot");
+/// }
+/// ```
+pub fn kernel() -> Result<String, ParseHostnameError> {
+    parse(fs::File::open("/proc/sys/kernel/hostname")?)
+}
+
+///
+/// Validates the name and atomically replaces the `/etc/hostname` with it.
+///
+/// ```no_run
+/// unixism::hostname::write("web-01").unwrap();
+/// ```
+pub fn write(name: &str) -> Result<(), ParseHostnameError> {
+    write_at("/etc/hostname", name)
+}
+
+/// Same as write, but writes the file at `path`.
+pub fn write_at<P>(path: P, name: &str) -> Result<(), ParseHostnameError>
+where
+    P: AsRef<Path>,
+{
+    validate(name)?;
+
+    Ok(crate::file::write_atomic(
+        path.as_ref(),
+        format!("{}\n", name).as_bytes(),
+        false,
+    )?)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Cursor;
+
+    use super::*;
+    use crate::tempdir::TempDir;
+
+    #[test]
+    fn it_parse() {
+        assert_eq!(
+            "web-01.example.com",
+            parse(Cursor::new(
+                "# managed by ansible\n\n  web-01.example.com  \nignored\n"
+            ))
+            .unwrap()
+        );
+        assert!(matches!(
+            parse(Cursor::new("# nothing\n")),
+            Err(ParseHostnameError::Empty)
+        ));
+        assert!(matches!(
+            parse(Cursor::new("web_01\n")),
+            Err(ParseHostnameError::InvalidLabel(label)) if label == "web_01"
+        ));
+    }
+
+    #[test]
+    fn it_validate() {
+        assert!(validate("localhost").is_ok());
+        assert!(validate("web-01.example.com.").is_ok());
+        assert!(validate("1.example").is_ok());
+        assert!(validate("").is_err());
+        assert!(validate("web..example").is_err());
+        assert!(validate("web-").is_err());
+        assert!(validate("exämple").is_err());
+        assert!(matches!(
+            validate(&"a.".repeat(33)),
+            Err(ParseHostnameError::TooLong(_))
+        ));
+        assert!(validate(&"a".repeat(64)).is_err());
+    }
+
+    #[test]
+    fn it_write() {
+        let dir = TempDir::new("hostname");
+
+        let path = dir.join("hostname");
+        write_at(&path, "web-01").unwrap();
+        assert_eq!("web-01\n", fs::read_to_string(&path).unwrap());
+
+        assert!(write_at(&path, "bad name").is_err());
+        assert_eq!("web-01", parse(fs::File::open(&path).unwrap()).unwrap());
+
+        write_at(&path, "web-02").unwrap();
+        assert!(!dir.join("hostname-").exists());
+    }
+}
@@ -5,7 +5,7 @@ use std::{
     str::FromStr,
 };
 
-#[derive(Debug, PartialEq, Eq)]
+#[derive(Debug, Clone, PartialEq, Eq)]
 pub struct Host {
     pub ip: net::IpAddr,
     pub names: Vec<String>,
@@ -94,6 +94,42 @@ pub fn parse_default() -> Result<impl Iterator<Item = Host>, ParseHostsError> {
     parse(fs::File::open("/etc/hosts")?)
 }
 
+///
+/// Same as parse, but reads from `tokio::io::AsyncRead`.
+///
+/// ```no_run
+/// # async fn run() {
+/// let file = tokio::fs::File::open("/etc/hosts").await.unwrap();
+///
+/// for host in unixism::hosts::parse_async(file).await.unwrap() {
+///     println!("ip: {}, names: {:#?}", host.ip, host.names);
+/// }
+/// # }
+/// ```
+#[cfg(feature = "async")]
+pub async fn parse_async<R>(mut reader: R) -> Result<impl Iterator<Item = Host>, ParseHostsError>
+where
+    R: tokio::io::AsyncRead + Unpin,
+{
+    let mut buf = Vec::new();
+    tokio::io::AsyncReadExt::read_to_end(&mut reader, &mut buf).await?;
+
+    parse(io::Cursor::new(buf))
+}
+
+///
+/// Same as parse_default, but reads the `/etc/hosts` asynchronously.
+///
+/// ```no_run
+/// # async fn run() {
+/// let hosts = unixism::hosts::parse_default_async().await.unwrap();
+/// # }
+/// ```
+#[cfg(feature = "async")]
+pub async fn parse_default_async() -> Result<impl Iterator<Item = Host>, ParseHostsError> {
+    parse_async(tokio::fs::File::open("/etc/hosts").await?).await
+}
+
 #[cfg(test)]
 mod tests {
     use std::io::Cursor;
@@ -134,4 +170,21 @@ ff02::2 ip6-allrouters
             hosts
         );
     }
+
+    #[cfg(feature = "async")]
+    #[tokio::test]
+    async fn it_parse_async() {
+        let hosts = parse_async(Cursor::new("127.0.0.1 localhost\n::1 ip6-localhost"))
+            .await
+            .unwrap()
+            .collect::<Vec<_>>();
+
+        assert_eq!(
+            vec![
+                Host::new("127.0.0.1".parse().unwrap(), vec!["localhost".to_owned()]),
+                Host::new("::1".parse().unwrap(), vec!["ip6-localhost".to_owned()]),
+            ],
+            hosts
+        );
+    }
 }
@@ -0,0 +1,355 @@
+use std::{
+    error, fmt, fs, io,
+    net::{IpAddr, Ipv4Addr},
+    path::Path,
+};
+
+use crate::shellvars::{self, ParseShellVarsError as ShellVarsError};
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum BootProto {
+    None,
+    Static,
+    Dhcp,
+    Bootp,
+    Other(String),
+}
+
+impl From<&str> for BootProto {
+    fn from(s: &str) -> Self {
+        match s.to_ascii_lowercase().as_str() {
+            "none" | "" => BootProto::None,
+            "static" => BootProto::Static,
+            "dhcp" => BootProto::Dhcp,
+            "bootp" => BootProto::Bootp,
+            _ => BootProto::Other(s.to_owned()),
+        }
+    }
+}
+
+/// An IPv4 address with its prefix length, from `IPADDR`/`PREFIX` or `IPADDRn`/`PREFIXn`/`NETMASKn`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Address {
+    pub ip: Ipv4Addr,
+    pub prefix: Option<u8>,
+}
+
+/// An `ifcfg-*` file of the RHEL network-scripts.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct Ifcfg {
+    pub device: Option<String>,
+    pub name: Option<String>,
+    /// The `TYPE` variable, like `Ethernet` or `Bridge`.
+    pub kind: Option<String>,
+    pub bootproto: Option<BootProto>,
+    pub onboot: bool,
+    pub addresses: Vec<Address>,
+    pub gateway: Option<IpAddr>,
+    pub dns: Vec<IpAddr>,
+    pub hwaddr: Option<String>,
+    pub mtu: Option<u32>,
+    /// Every variable of the file in order, with the quoting removed.
+    pub variables: Vec<(String, String)>,
+}
+
+impl Ifcfg {
+    pub fn get(&self, name: &str) -> Option<&str> {
+        self.variables
+            .iter()
+            .rev()
+            .find(|(key, _)| key == name)
+            .map(|(_, value)| value.as_str())
+    }
+
+    /// Same as get, but an empty value is unset, as initscripts treats it.
+    fn value(&self, name: &str) -> Option<&str> {
+        self.get(name).filter(|value| !value.is_empty())
+    }
+
+    fn from_variables(variables: Vec<(String, String)>) -> Result<Self, ParseIfcfgError> {
+        let mut ifcfg = Ifcfg {
+            variables,
+            ..Default::default()
+        };
+
+        let parse_ip = |name: &str, value: &str| {
+            value
+                .parse::<IpAddr>()
+                .map_err(|_| ParseIfcfgError::InvalidValue {
+                    name: name.to_owned(),
+                    value: value.to_owned(),
+                })
+        };
+
+        ifcfg.device = ifcfg.value("DEVICE").map(String::from);
+        ifcfg.name = ifcfg.value("NAME").map(String::from);
+        ifcfg.kind = ifcfg.value("TYPE").map(String::from);
+        ifcfg.bootproto = ifcfg.value("BOOTPROTO").map(BootProto::from);
+        ifcfg.onboot = ifcfg.value("ONBOOT").is_some_and(|onboot| {
+            matches!(onboot.to_ascii_lowercase().as_str(), "yes" | "true" | "1")
+        });
+        ifcfg.hwaddr = ifcfg.value("HWADDR").map(String::from);
+        ifcfg.mtu = ifcfg
+            .value("MTU")
+            .map(|mtu| {
+                mtu.parse().map_err(|_| ParseIfcfgError::InvalidValue {
+                    name: "MTU".to_owned(),
+                    value: mtu.to_owned(),
+                })
+            })
+            .transpose()?;
+        ifcfg.gateway = ifcfg
+            .value("GATEWAY")
+            .map(|gateway| parse_ip("GATEWAY", gateway))
+            .transpose()?;
+
+        for suffix in std::iter::once(String::new()).chain((0..256).map(|i| i.to_string())) {
+            let name = format!("IPADDR{}", suffix);
+            let Some(ip) = ifcfg.value(&name) else {
+                continue;
+            };
+
+            let IpAddr::V4(ip) = parse_ip(&name, ip)? else {
+                return Err(ParseIfcfgError::InvalidValue {
+                    name,
+                    value: ip.to_owned(),
+                });
+            };
+
+            let prefix = match (
+                ifcfg.value(&format!("PREFIX{}", suffix)),
+                ifcfg.value(&format!("NETMASK{}", suffix)),
+            ) {
+                (Some(prefix), _) => {
+                    Some(prefix.parse().map_err(|_| ParseIfcfgError::InvalidValue {
+                        name: format!("PREFIX{}", suffix),
+                        value: prefix.to_owned(),
+                    })?)
+                }
+                (None, Some(netmask)) => match parse_ip("NETMASK", netmask)? {
+                    IpAddr::V4(netmask) => Some(u32::from(netmask).leading_ones() as u8),
+                    IpAddr::V6(_) => None,
+                },
+                (None, None) => None,
+            };
+
+            ifcfg.addresses.push(Address { ip, prefix });
+        }
+
+        for i in 1.. {
+            let name = format!("DNS{}", i);
+            let Some(dns) = ifcfg.value(&name) else {
+                break;
+            };
+
+            let dns = parse_ip(&name, dns)?;
+            ifcfg.dns.push(dns);
+        }
+
+        Ok(ifcfg)
+    }
+}
+
+#[derive(Debug)]
+pub enum ParseIfcfgError {
+    InvalidEntry(String),
+    InvalidValue { name: String, value: String },
+    IOError(io::Error),
+}
+
+impl error::Error for ParseIfcfgError {}
+
+impl fmt::Display for ParseIfcfgError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::InvalidEntry(entry) => write!(f, "invalid ifcfg line: {}", entry),
+            Self::InvalidValue { name, value } => write!(f, "invalid value of {}: {}", name, value),
+            Self::IOError(error) => write!(f, "{}", error),
+        }
+    }
+}
+
+impl From<ShellVarsError> for ParseIfcfgError {
+    fn from(value: ShellVarsError) -> Self {
+        match value {
+            ShellVarsError::InvalidEntry(entry) => ParseIfcfgError::InvalidEntry(entry),
+            ShellVarsError::IOError(error) => ParseIfcfgError::IOError(error),
+        }
+    }
+}
+
+impl From<io::Error> for ParseIfcfgError {
+    fn from(value: io::Error) -> Self {
+        ParseIfcfgError::IOError(value)
+    }
+}
+
+///
+/// Parses the shell variable assignments of an ifcfg file, removing the quotes the way sh does.
+///
+/// ```no_run
+/// let ifcfg = unixism::ifcfg::parse(std::fs::File::open("/etc/sysconfig/network-scripts/ifcfg-eth0").unwrap()).unwrap();
+///
+/// println!("{:?} {:?} {:?}", ifcfg.device, ifcfg.bootproto, ifcfg.addresses);
+/// ```
+pub fn parse<R>(reader: R) -> Result<Ifcfg, ParseIfcfgError>
+where
+    R: io::Read,
+{
+    Ifcfg::from_variables(shellvars::parse(reader)?.variables)
+}
+
+///
+/// Parses every `ifcfg-*` file of the directory, sorted by file name.
+/// Backups like `ifcfg-eth0.bak` or `ifcfg-eth0.rpmnew` are skipped, as initscripts does.
+/// The device name is taken from the file name when `DEVICE` is not set.
+///
+/// ```no_run
+/// for ifcfg in unixism::ifcfg::parse_dir("/etc/sysconfig/network-scripts").unwrap() {
+///     println!("{:?} onboot={}", ifcfg.device, ifcfg.onboot);
+/// }
+/// ```
+pub fn parse_dir<P>(dir: P) -> Result<Vec<Ifcfg>, ParseIfcfgError>
+where
+    P: AsRef<Path>,
+{
+    let mut files = fs::read_dir(dir)?
+        .filter_map(Result::ok)
+        .filter_map(|entry| {
+            let name = entry
+                .file_name()
+                .to_str()?
+                .strip_prefix("ifcfg-")
+                .filter(|name| !is_ignored(name))?
+                .to_owned();
+
+            Some((name, entry.path()))
+        })
+        .collect::<Vec<_>>();
+    files.sort();
+
+    let mut configs = Vec::new();
+    for (name, path) in files {
+        let mut ifcfg = parse(fs::File::open(path)?)?;
+        ifcfg.device.get_or_insert(name);
+        configs.push(ifcfg);
+    }
+
+    Ok(configs)
+}
+
+/// Whether the file is a backup or package manager leftover, which initscripts skips.
+fn is_ignored(name: &str) -> bool {
+    name.ends_with('~')
+        || [".bak", ".old", ".orig", ".rpmnew", ".rpmorig", ".rpmsave"]
+            .iter()
+            .any(|suffix| name.ends_with(suffix))
+}
+
+///
+/// Same as parse_dir, but parses the `/etc/sysconfig/network-scripts` as default.
+///
+/// ```no_run
+/// let configs = unixism::ifcfg::parse_default().unwrap();
+/// ```
+pub fn parse_default() -> Result<Vec<Ifcfg>, ParseIfcfgError> {
+    parse_dir("/etc/sysconfig/network-scripts")
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Cursor;
+
+    use super::*;
+    use crate::tempdir::TempDir;
+
+    #[test]
+    fn it_parse() {
+        let ifcfg = parse(Cursor::new(
+            r#"
+# Generated by the installer
+TYPE=Ethernet
+DEVICE=eth0
+NAME="System eth0"
+BOOTPROTO=none
+ONBOOT=yes
+IPADDR=192.168.1.10
+PREFIX=24
+IPADDR1='10.0.0.5'
+NETMASK1=255.255.0.0
+GATEWAY=192.168.1.1
+DNS1=1.1.1.1
+DNS2="8.8.8.8"   # fallback
+MTU=9000
+export ETHTOOL_OPTS="-K \"eth0\" tso off"
+DESCRIPTION=it\'s' a 'mix"ed $value"
+        "#,
+        ))
+        .unwrap();
+
+        assert_eq!(Some("eth0"), ifcfg.device.as_deref());
+        assert_eq!(Some("System eth0"), ifcfg.name.as_deref());
+        assert_eq!(Some("Ethernet"), ifcfg.kind.as_deref());
+        assert_eq!(Some(BootProto::None), ifcfg.bootproto);
+        assert!(ifcfg.onboot);
+        assert_eq!(
+            vec![
+                Address {
+                    ip: Ipv4Addr::new(192, 168, 1, 10),
+                    prefix: Some(24)
+                },
+                Address {
+                    ip: Ipv4Addr::new(10, 0, 0, 5),
+                    prefix: Some(16)
+                },
+            ],
+            ifcfg.addresses
+        );
+        assert_eq!(Some("192.168.1.1".parse().unwrap()), ifcfg.gateway);
+        assert_eq!(
+            vec![
+                "1.1.1.1".parse::<IpAddr>().unwrap(),
+                "8.8.8.8".parse().unwrap()
+            ],
+            ifcfg.dns
+        );
+        assert_eq!(Some(9000), ifcfg.mtu);
+        assert_eq!(Some("-K \"eth0\" tso off"), ifcfg.get("ETHTOOL_OPTS"));
+        assert_eq!(Some("it's a mixed $value"), ifcfg.get("DESCRIPTION"));
+
+        let empty = parse(Cursor::new("DEVICE=eth0\nIPADDR=\nGATEWAY=\"\"\nMTU=''\n")).unwrap();
+        assert!(empty.addresses.is_empty());
+        assert_eq!(None, empty.gateway);
+        assert_eq!(None, empty.mtu);
+
+        assert!(parse(Cursor::new("IPADDR=not-an-ip")).is_err());
+        assert!(parse(Cursor::new("NAME=\"unterminated")).is_err());
+        assert!(parse(Cursor::new("NAME=two words")).is_err());
+        assert!(parse(Cursor::new("1NAME=x")).is_err());
+    }
+
+    #[test]
+    fn it_parse_dir() {
+        let dir = TempDir::new("ifcfg");
+
+        fs::write(dir.join("ifcfg-lo"), "DEVICE=lo\nIPADDR=127.0.0.1\n").unwrap();
+        fs::write(dir.join("ifcfg-eth0"), "BOOTPROTO=dhcp\n").unwrap();
+        fs::write(dir.join("route-eth0"), "default via 10.0.0.1\n").unwrap();
+        for backup in [
+            "eth0.bak",
+            "eth0.rpmnew",
+            "eth0.rpmsave",
+            "eth0.orig",
+            "eth0~",
+        ] {
+            fs::write(dir.join(format!("ifcfg-{}", backup)), "BOOTPROTO=dhcp\n").unwrap();
+        }
+
+        let configs = parse_dir(&dir).unwrap();
+
+        assert_eq!(2, configs.len());
+        assert_eq!(Some("eth0"), configs[0].device.as_deref());
+        assert_eq!(Some(BootProto::Dhcp), configs[0].bootproto);
+        assert_eq!(Some("lo"), configs[1].device.as_deref());
+    }
+}
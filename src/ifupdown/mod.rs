@@ -0,0 +1,466 @@
+use std::{
+    error, fmt, fs,
+    io::{self, BufRead, BufReader},
+    path::{Path, PathBuf},
+};
+
+use crate::glob::glob;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Family {
+    Inet,
+    Inet6,
+    Ipx,
+    Can,
+    Other(String),
+}
+
+impl From<&str> for Family {
+    fn from(s: &str) -> Self {
+        match s {
+            "inet" => Family::Inet,
+            "inet6" => Family::Inet6,
+            "ipx" => Family::Ipx,
+            "can" => Family::Can,
+            family => Family::Other(family.to_owned()),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Method {
+    Loopback,
+    Static,
+    Dhcp,
+    Manual,
+    Auto,
+    Bootp,
+    Ppp,
+    Tunnel,
+    Other(String),
+}
+
+impl From<&str> for Method {
+    fn from(s: &str) -> Self {
+        match s {
+            "loopback" => Method::Loopback,
+            "static" => Method::Static,
+            "dhcp" => Method::Dhcp,
+            "manual" => Method::Manual,
+            "auto" => Method::Auto,
+            "bootp" => Method::Bootp,
+            "ppp" => Method::Ppp,
+            "tunnel" | "v4tunnel" => Method::Tunnel,
+            method => Method::Other(method.to_owned()),
+        }
+    }
+}
+
+/// An `iface` stanza with its option lines.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Iface {
+    pub name: String,
+    pub family: Family,
+    pub method: Method,
+    pub options: Vec<(String, String)>,
+}
+
+impl Iface {
+    /// Returns the value of the last option named `name`, like `address` or `gateway`.
+    pub fn get(&self, name: &str) -> Option<&str> {
+        self.options
+            .iter()
+            .rev()
+            .find(|(key, _)| key == name)
+            .map(|(_, value)| value.as_str())
+    }
+}
+
+/// A `mapping` stanza choosing the logical interface via a script.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Mapping {
+    pub patterns: Vec<String>,
+    pub options: Vec<(String, String)>,
+}
+
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct Config {
+    /// Interfaces of the `auto` lines, brought up at boot.
+    pub auto: Vec<String>,
+    /// Interfaces of the `allow-<class>` lines, like `("hotplug", "wlan0")`.
+    pub allow: Vec<(String, String)>,
+    pub interfaces: Vec<Iface>,
+    pub mappings: Vec<Mapping>,
+    /// Patterns of the `source` lines, only resolved by parse_file.
+    pub sources: Vec<String>,
+    /// Directories of the `source-directory` lines, only resolved by parse_file.
+    pub source_directories: Vec<String>,
+}
+
+impl Config {
+    /// Returns the stanzas of the interface, one per address family.
+    pub fn iface(&self, name: &str) -> impl Iterator<Item = &Iface> {
+        let name = name.to_owned();
+
+        self.interfaces
+            .iter()
+            .filter(move |iface| iface.name == name)
+    }
+
+    pub fn is_auto(&self, name: &str) -> bool {
+        self.auto.iter().any(|auto| auto == name)
+    }
+
+    fn extend(&mut self, other: Config) {
+        self.auto.extend(other.auto);
+        self.allow.extend(other.allow);
+        self.interfaces.extend(other.interfaces);
+        self.mappings.extend(other.mappings);
+    }
+}
+
+#[derive(Debug)]
+pub enum ParseInterfacesError {
+    InvalidEntry(String),
+    IOError(io::Error),
+}
+
+impl error::Error for ParseInterfacesError {}
+
+impl fmt::Display for ParseInterfacesError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::InvalidEntry(entry) => write!(f, "invalid interfaces line: {}", entry),
+            Self::IOError(error) => write!(f, "{}", error),
+        }
+    }
+}
+
+impl From<io::Error> for ParseInterfacesError {
+    fn from(value: io::Error) -> Self {
+        ParseInterfacesError::IOError(value)
+    }
+}
+
+enum Stanza {
+    None,
+    Iface,
+    Mapping,
+}
+
+///
+/// Parses the `interfaces(5)` format, keeping the `source` and `source-directory` lines unresolved.
+///
+/// ```no_run
+/// let config = unixism::ifupdown::parse(std::fs::File::open("/etc/network/interfaces").unwrap()).unwrap();
+///
+/// for iface in config.interfaces {
+///     println!("{} {:?} {:?} {:?}", iface.name, iface.family, iface.method, iface.get("address"));
+/// }
+/// ```
+pub fn parse<R>(reader: R) -> Result<Config, ParseInterfacesError>
+where
+    R: io::Read,
+{
+    let mut config = Config::default();
+    let mut stanza = Stanza::None;
+    let mut pending = String::new();
+
+    for line in BufReader::new(reader).lines() {
+        let line = line?;
+
+        if let Some(line) = line.strip_suffix('\\') {
+            pending.push_str(line);
+            pending.push(' ');
+            continue;
+        }
+
+        pending.push_str(&line);
+        let line = std::mem::take(&mut pending);
+        let line = line.trim();
+
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+
+        let invalid = || ParseInterfacesError::InvalidEntry(line.to_owned());
+        let mut words = line.split_whitespace();
+        let keyword = words.next().unwrap_or_default();
+
+        match keyword {
+            "auto" => {
+                config.auto.extend(words.map(String::from));
+                stanza = Stanza::None;
+            }
+            "iface" => {
+                let (Some(name), Some(family), Some(method)) =
+                    (words.next(), words.next(), words.next())
+                else {
+                    return Err(invalid());
+                };
+
+                config.interfaces.push(Iface {
+                    name: name.to_owned(),
+                    family: Family::from(family),
+                    method: Method::from(method),
+                    options: Vec::new(),
+                });
+                stanza = Stanza::Iface;
+            }
+            "mapping" => {
+                config.mappings.push(Mapping {
+                    patterns: words.map(String::from).collect(),
+                    options: Vec::new(),
+                });
+                stanza = Stanza::Mapping;
+            }
+            "source" => {
+                config.sources.extend(words.map(String::from));
+                stanza = Stanza::None;
+            }
+            "source-directory" => {
+                config.source_directories.extend(words.map(String::from));
+                stanza = Stanza::None;
+            }
+            keyword if keyword.starts_with("allow-") => {
+                let class = &keyword["allow-".len()..];
+
+                config
+                    .allow
+                    .extend(words.map(|name| (class.to_owned(), name.to_owned())));
+                stanza = Stanza::None;
+            }
+            "no-auto-down" | "no-scripts" | "rename" => stanza = Stanza::None,
+            option => {
+                let value = line[option.len()..].trim().to_owned();
+
+                match stanza {
+                    Stanza::Iface => config.interfaces.last_mut().ok_or_else(invalid)?,
+                    Stanza::Mapping => {
+                        config
+                            .mappings
+                            .last_mut()
+                            .ok_or_else(invalid)?
+                            .options
+                            .push((option.to_owned(), value));
+                        continue;
+                    }
+                    Stanza::None => return Err(invalid()),
+                }
+                .options
+                .push((option.to_owned(), value));
+            }
+        }
+    }
+
+    Ok(config)
+}
+
+///
+/// Same as parse, but reads the file at `path` and merges the files it includes.
+/// Relative includes are resolved against the directory of the including file, `source`
+/// patterns may use `*` and `?` in the file name, and `source-directory` only includes
+/// files named with letters, digits, underscores and hyphens, as run-parts does.
+///
+/// ```no_run
+/// let config = unixism::ifupdown::parse_file("/etc/network/interfaces").unwrap();
+/// ```
+pub fn parse_file<P>(path: P) -> Result<Config, ParseInterfacesError>
+where
+    P: AsRef<Path>,
+{
+    parse_file_nested(path.as_ref(), &mut Vec::new())
+}
+
+fn parse_file_nested(
+    path: &Path,
+    stack: &mut Vec<PathBuf>,
+) -> Result<Config, ParseInterfacesError> {
+    let path = fs::canonicalize(path)?;
+
+    // Guards against files including themselves.
+    if stack.contains(&path) {
+        return Ok(Config::default());
+    }
+
+    let mut config = parse(fs::File::open(&path)?)?;
+    let base = path.parent().unwrap_or(Path::new("/")).to_owned();
+
+    stack.push(path.clone());
+
+    for source in std::mem::take(&mut config.sources) {
+        for file in expand(&base.join(source))? {
+            let included = parse_file_nested(&file, stack)?;
+            config.extend(included);
+        }
+    }
+
+    for directory in std::mem::take(&mut config.source_directories) {
+        let mut files = fs::read_dir(base.join(directory))?
+            .filter_map(Result::ok)
+            .filter(|entry| {
+                entry.file_name().to_str().is_some_and(|name| {
+                    name.chars()
+                        .all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '-')
+                })
+            })
+            .map(|entry| entry.path())
+            .collect::<Vec<_>>();
+        files.sort();
+
+        for file in files {
+            let included = parse_file_nested(&file, stack)?;
+            config.extend(included);
+        }
+    }
+
+    stack.pop();
+
+    Ok(config)
+}
+
+/// Expands `*` and `?` in the file name of the pattern, sorted like the shell does.
+fn expand(pattern: &Path) -> io::Result<Vec<PathBuf>> {
+    let name = pattern
+        .file_name()
+        .map(|name| name.to_string_lossy().into_owned())
+        .unwrap_or_default();
+
+    if !name.contains(['*', '?']) {
+        return Ok(vec![pattern.to_owned()]);
+    }
+
+    let dir = pattern.parent().unwrap_or(Path::new("/"));
+    let mut files = Vec::new();
+
+    for entry in fs::read_dir(dir)? {
+        let entry = entry?;
+        let file_name = entry.file_name().to_string_lossy().into_owned();
+
+        if !file_name.starts_with('.') && glob(&name, &file_name) && entry.file_type()?.is_file() {
+            files.push(entry.path());
+        }
+    }
+    files.sort();
+
+    Ok(files)
+}
+
+///
+/// Same as parse_file, but parses the `/etc/network/interfaces` as default.
+///
+/// ```no_run
+/// let config = unixism::ifupdown::parse_default().unwrap();
+/// ```
+pub fn parse_default() -> Result<Config, ParseInterfacesError> {
+    parse_file("/etc/network/interfaces")
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Cursor;
+
+    use super::*;
+    use crate::tempdir::TempDir;
+
+    #[test]
+    fn it_parse() {
+        let config = parse(Cursor::new(
+            r#"
+# The loopback network interface
+auto lo eth0
+allow-hotplug wlan0
+
+iface lo inet loopback
+
+iface eth0 inet static
+    address 192.168.1.10/24
+    gateway 192.168.1.1
+    dns-nameservers 1.1.1.1 \
+        8.8.8.8
+iface eth0 inet6 auto
+
+mapping eth1
+    script /usr/local/sbin/map-scheme
+    map HOME eth1-home
+
+source interfaces.d/*
+source-directory /etc/network/interfaces.d
+        "#,
+        ))
+        .unwrap();
+
+        assert_eq!(vec!["lo", "eth0"], config.auto);
+        assert_eq!(
+            vec![("hotplug".to_owned(), "wlan0".to_owned())],
+            config.allow
+        );
+        assert_eq!(3, config.interfaces.len());
+
+        let eth0 = config.iface("eth0").collect::<Vec<_>>();
+        assert_eq!(2, eth0.len());
+        assert_eq!(Method::Static, eth0[0].method);
+        assert_eq!(Some("192.168.1.10/24"), eth0[0].get("address"));
+        assert_eq!(
+            Some("1.1.1.1          8.8.8.8"),
+            eth0[0].get("dns-nameservers")
+        );
+        assert_eq!(Family::Inet6, eth0[1].family);
+        assert_eq!(Method::Auto, eth0[1].method);
+        assert!(config.is_auto("eth0"));
+        assert!(!config.is_auto("wlan0"));
+
+        assert_eq!(vec!["eth1"], config.mappings[0].patterns);
+        assert_eq!(2, config.mappings[0].options.len());
+        assert_eq!(vec!["interfaces.d/*"], config.sources);
+        assert_eq!(vec!["/etc/network/interfaces.d"], config.source_directories);
+
+        assert!(parse(Cursor::new("address 10.0.0.1")).is_err());
+        assert!(parse(Cursor::new("iface eth0 inet")).is_err());
+    }
+
+    #[test]
+    fn it_parse_file() {
+        let dir = TempDir::new("ifupdown");
+        fs::create_dir_all(dir.join("interfaces.d")).unwrap();
+        fs::create_dir_all(dir.join("extra")).unwrap();
+
+        fs::write(
+            dir.join("interfaces"),
+            "auto lo\niface lo inet loopback\nsource interfaces.d/*.cfg\nsource-directory extra\n",
+        )
+        .unwrap();
+        fs::write(
+            dir.join("interfaces.d/eth0.cfg"),
+            "iface eth0 inet dhcp\nsource ../interfaces\n",
+        )
+        .unwrap();
+        fs::write(
+            dir.join("interfaces.d/eth1.disabled"),
+            "iface eth1 inet dhcp\n",
+        )
+        .unwrap();
+        fs::write(
+            dir.join("extra/wlan0"),
+            "allow-hotplug wlan0\niface wlan0 inet dhcp\n",
+        )
+        .unwrap();
+        fs::write(dir.join("extra/wlan1.bak"), "iface wlan1 inet dhcp\n").unwrap();
+
+        let config = parse_file(dir.join("interfaces")).unwrap();
+
+        assert_eq!(
+            vec!["lo", "eth0", "wlan0"],
+            config
+                .interfaces
+                .iter()
+                .map(|iface| iface.name.as_str())
+                .collect::<Vec<_>>()
+        );
+        assert_eq!(
+            vec![("hotplug".to_owned(), "wlan0".to_owned())],
+            config.allow
+        );
+        assert!(config.sources.is_empty());
+    }
+}
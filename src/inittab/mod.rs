@@ -0,0 +1,250 @@
+use std::{
+    error, fmt, fs,
+    io::{self, BufRead, BufReader},
+    str::FromStr,
+};
+
+/// What init does with the process of an entry.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Action {
+    Respawn,
+    Wait,
+    Once,
+    Boot,
+    Bootwait,
+    Off,
+    Ondemand,
+    Initdefault,
+    Sysinit,
+    Powerwait,
+    Powerfail,
+    Powerokwait,
+    Powerfailnow,
+    Ctrlaltdel,
+    Kbrequest,
+    Other(String),
+}
+
+impl From<&str> for Action {
+    fn from(s: &str) -> Self {
+        match s {
+            "respawn" => Action::Respawn,
+            "wait" => Action::Wait,
+            "once" => Action::Once,
+            "boot" => Action::Boot,
+            "bootwait" => Action::Bootwait,
+            "off" => Action::Off,
+            "ondemand" => Action::Ondemand,
+            "initdefault" => Action::Initdefault,
+            "sysinit" => Action::Sysinit,
+            "powerwait" => Action::Powerwait,
+            "powerfail" => Action::Powerfail,
+            "powerokwait" => Action::Powerokwait,
+            "powerfailnow" => Action::Powerfailnow,
+            "ctrlaltdel" => Action::Ctrlaltdel,
+            "kbrequest" => Action::Kbrequest,
+            action => Action::Other(action.to_owned()),
+        }
+    }
+}
+
+impl fmt::Display for Action {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Action::Respawn => write!(f, "respawn"),
+            Action::Wait => write!(f, "wait"),
+            Action::Once => write!(f, "once"),
+            Action::Boot => write!(f, "boot"),
+            Action::Bootwait => write!(f, "bootwait"),
+            Action::Off => write!(f, "off"),
+            Action::Ondemand => write!(f, "ondemand"),
+            Action::Initdefault => write!(f, "initdefault"),
+            Action::Sysinit => write!(f, "sysinit"),
+            Action::Powerwait => write!(f, "powerwait"),
+            Action::Powerfail => write!(f, "powerfail"),
+            Action::Powerokwait => write!(f, "powerokwait"),
+            Action::Powerfailnow => write!(f, "powerfailnow"),
+            Action::Ctrlaltdel => write!(f, "ctrlaltdel"),
+            Action::Kbrequest => write!(f, "kbrequest"),
+            Action::Other(action) => write!(f, "{}", action),
+        }
+    }
+}
+
+/// A line of `/etc/inittab`, `id:runlevels:action:process`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Entry {
+    pub id: String,
+    /// The runlevels the entry applies to, empty meaning all of them.
+    pub runlevels: String,
+    pub action: Action,
+    pub process: String,
+}
+
+impl Entry {
+    /// Whether the entry applies to the runlevel.
+    pub fn runs_in(&self, runlevel: char) -> bool {
+        self.runlevels.is_empty()
+            || self
+                .runlevels
+                .chars()
+                .any(|level| level.eq_ignore_ascii_case(&runlevel))
+    }
+}
+
+impl FromStr for Entry {
+    type Err = ParseInittabError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let fields = s.splitn(4, ':').collect::<Vec<_>>();
+
+        let [id, runlevels, action, process] = fields[..] else {
+            return Err(ParseInittabError::InvalidEntry(s.to_owned()));
+        };
+
+        if id.is_empty() || action.is_empty() {
+            return Err(ParseInittabError::InvalidEntry(s.to_owned()));
+        }
+
+        Ok(Entry {
+            id: id.to_owned(),
+            runlevels: runlevels.to_owned(),
+            action: Action::from(action),
+            process: process.trim().to_owned(),
+        })
+    }
+}
+
+impl fmt::Display for Entry {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{}:{}:{}:{}",
+            self.id, self.runlevels, self.action, self.process
+        )
+    }
+}
+
+#[derive(Debug)]
+pub enum ParseInittabError {
+    InvalidEntry(String),
+    IOError(io::Error),
+}
+
+impl error::Error for ParseInittabError {}
+
+impl fmt::Display for ParseInittabError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::InvalidEntry(entry) => write!(f, "invalid entry: {}", entry),
+            Self::IOError(error) => write!(f, "{}", error),
+        }
+    }
+}
+
+impl From<io::Error> for ParseInittabError {
+    fn from(value: io::Error) -> Self {
+        ParseInittabError::IOError(value)
+    }
+}
+
+///
+/// ```no_run
+/// let entries = unixism::inittab::parse(std::fs::File::open("/etc/inittab").unwrap()).unwrap();
+///
+/// for entry in entries {
+///     println!("{} {} {}", entry.id, entry.action, entry.process);
+/// }
+/// ```
+pub fn parse<R>(reader: R) -> Result<impl Iterator<Item = Entry>, ParseInittabError>
+where
+    R: io::Read,
+{
+    let mut entries = Vec::new();
+
+    for line in BufReader::new(reader).lines() {
+        let line = line?;
+        let line = line.trim();
+
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+
+        entries.push(line.parse::<Entry>()?);
+    }
+
+    Ok(entries.into_iter())
+}
+
+///
+/// Same as parse, but parses the `/etc/inittab` as default.
+///
+/// ```no_run
+/// let entries = unixism::inittab::parse_default().unwrap();
+/// ```
+pub fn parse_default() -> Result<impl Iterator<Item = Entry>, ParseInittabError> {
+    parse(fs::File::open("/etc/inittab")?)
+}
+
+///
+/// Returns the runlevel of the `initdefault` entry, which init enters on boot.
+///
+/// ```no_run
+/// let entries = unixism::inittab::parse_default().unwrap().collect::<Vec<_>>();
+///
+/// println!("{:?}", unixism::inittab::default_runlevel(&entries));
+/// ```
+pub fn default_runlevel(entries: &[Entry]) -> Option<char> {
+    entries
+        .iter()
+        .find(|entry| entry.action == Action::Initdefault)
+        .and_then(|entry| entry.runlevels.chars().next())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Cursor;
+
+    use super::*;
+
+    #[test]
+    fn it_parse() {
+        let entries = parse(Cursor::new(
+            r#"
+# The default runlevel.
+id:2:initdefault:
+
+si::sysinit:/etc/init.d/rcS
+l2:2:wait:/etc/init.d/rc 2
+ca:12345:ctrlaltdel:/sbin/shutdown -t1 -a -r now
+1:2345:respawn:/sbin/getty 38400 tty1
+T0:23:respawn:/sbin/getty -L ttyS0 9600 vt100
+        "#,
+        ))
+        .unwrap()
+        .collect::<Vec<_>>();
+
+        assert_eq!(6, entries.len());
+        assert_eq!(Some('2'), default_runlevel(&entries));
+        assert_eq!(
+            Entry {
+                id: "l2".to_owned(),
+                runlevels: "2".to_owned(),
+                action: Action::Wait,
+                process: "/etc/init.d/rc 2".to_owned(),
+            },
+            entries[2]
+        );
+        assert_eq!(
+            "1:2345:respawn:/sbin/getty 38400 tty1",
+            entries[4].to_string()
+        );
+
+        assert!(entries[1].runs_in('S'));
+        assert!(entries[4].runs_in('3'));
+        assert!(!entries[4].runs_in('1'));
+
+        assert!(parse(Cursor::new("id:2:initdefault")).is_err());
+        assert!(parse(Cursor::new(":2:wait:/bin/true")).is_err());
+    }
+}
@@ -0,0 +1,369 @@
+use std::{
+    collections::HashMap,
+    error, fmt, fs,
+    io::{self, BufRead, BufReader},
+    num::ParseIntError,
+    path::Path,
+    str::FromStr,
+};
+
+/// Directories iproute2 reads its tables from, the vendor one first.
+pub const CONFIG_DIRS: [&str; 2] = ["/usr/share/iproute2", "/etc/iproute2"];
+
+/// Routing tables iproute2 knows without any file.
+pub const DEFAULT_TABLES: [(u32, &str); 4] = [
+    (0, "unspec"),
+    (253, "default"),
+    (254, "main"),
+    (255, "local"),
+];
+
+/// Route protocols iproute2 knows without any file.
+pub const DEFAULT_PROTOS: [(u32, &str); 23] = [
+    (0, "unspec"),
+    (1, "redirect"),
+    (2, "kernel"),
+    (3, "boot"),
+    (4, "static"),
+    (8, "gated"),
+    (9, "ra"),
+    (10, "mrt"),
+    (11, "zebra"),
+    (12, "bird"),
+    (13, "dnrouted"),
+    (14, "xorp"),
+    (15, "ntk"),
+    (16, "dhcp"),
+    (18, "keepalived"),
+    (42, "babel"),
+    (99, "openr"),
+    (186, "bgp"),
+    (187, "isis"),
+    (188, "ospf"),
+    (189, "rip"),
+    (192, "eigrp"),
+    (193, "dhcp6"),
+];
+
+/// A line of an iproute2 mapping file like `rt_tables`, `rt_protos` or `rt_scopes`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Entry {
+    pub id: u32,
+    pub name: String,
+}
+
+impl FromStr for Entry {
+    type Err = ParseIproute2Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let fields = s.split_whitespace().collect::<Vec<_>>();
+
+        let [id, name] = fields[..] else {
+            return Err(ParseIproute2Error::InvalidEntry(s.to_owned()));
+        };
+
+        let id = match id.strip_prefix("0x").or_else(|| id.strip_prefix("0X")) {
+            Some(hex) => u32::from_str_radix(hex, 16)?,
+            None => id.parse()?,
+        };
+
+        Ok(Entry {
+            id,
+            name: name.to_owned(),
+        })
+    }
+}
+
+impl fmt::Display for Entry {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}\t{}", self.id, self.name)
+    }
+}
+
+///
+/// Two-way mapping between ids and names.
+/// Unlike the libc databases, later entries override earlier ones, as iproute2 does.
+///
+/// ```no_run
+/// let tables = unixism::iproute2::tables().unwrap();
+///
+/// assert_eq!("main", tables.format(254));
+/// assert_eq!(Some(254), tables.id("main"));
+/// ```
+#[derive(Debug, Default, Clone)]
+pub struct Names {
+    by_id: HashMap<u32, String>,
+    by_name: HashMap<String, u32>,
+}
+
+impl Names {
+    pub fn new<I>(entries: I) -> Self
+    where
+        I: IntoIterator<Item = Entry>,
+    {
+        entries.into_iter().collect()
+    }
+
+    pub fn name(&self, id: u32) -> Option<&str> {
+        self.by_id.get(&id).map(String::as_str)
+    }
+
+    /// Looks up the id of the name, accepting plain numbers like `ip` does.
+    pub fn id(&self, name: &str) -> Option<u32> {
+        self.by_name
+            .get(name)
+            .copied()
+            .or_else(|| name.parse().ok())
+    }
+
+    /// Returns the name of the id, or the number itself when it has none.
+    pub fn format(&self, id: u32) -> String {
+        self.name(id)
+            .map(String::from)
+            .unwrap_or_else(|| id.to_string())
+    }
+}
+
+impl Extend<Entry> for Names {
+    fn extend<I>(&mut self, iter: I)
+    where
+        I: IntoIterator<Item = Entry>,
+    {
+        for entry in iter {
+            self.by_name.insert(entry.name.clone(), entry.id);
+            self.by_id.insert(entry.id, entry.name);
+        }
+    }
+}
+
+impl FromIterator<Entry> for Names {
+    fn from_iter<I>(iter: I) -> Self
+    where
+        I: IntoIterator<Item = Entry>,
+    {
+        let mut names = Names::default();
+        names.extend(iter);
+
+        names
+    }
+}
+
+#[derive(Debug)]
+pub enum ParseIproute2Error {
+    InvalidEntry(String),
+    ParseIntError(ParseIntError),
+    IOError(io::Error),
+}
+
+impl error::Error for ParseIproute2Error {}
+
+impl fmt::Display for ParseIproute2Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::InvalidEntry(entry) => write!(f, "invalid entry: {}", entry),
+            Self::ParseIntError(error) => write!(f, "{}", error),
+            Self::IOError(error) => write!(f, "{}", error),
+        }
+    }
+}
+
+impl From<ParseIntError> for ParseIproute2Error {
+    fn from(value: ParseIntError) -> Self {
+        ParseIproute2Error::ParseIntError(value)
+    }
+}
+
+impl From<io::Error> for ParseIproute2Error {
+    fn from(value: io::Error) -> Self {
+        ParseIproute2Error::IOError(value)
+    }
+}
+
+///
+/// ```no_run
+/// let entries = unixism::iproute2::parse(std::fs::File::open("/etc/iproute2/rt_tables").unwrap()).unwrap();
+///
+/// for entry in entries {
+///     println!("{} {}", entry.id, entry.name);
+/// }
+/// ```
+pub fn parse<R>(reader: R) -> Result<impl Iterator<Item = Entry>, ParseIproute2Error>
+where
+    R: io::Read,
+{
+    let mut entries = Vec::new();
+
+    for line in BufReader::new(reader).lines() {
+        let line = line?;
+        let line = line.split('#').next().unwrap_or_default().trim();
+
+        if line.is_empty() {
+            continue;
+        }
+
+        entries.push(line.parse::<Entry>()?);
+    }
+
+    Ok(entries.into_iter())
+}
+
+///
+/// Reads the mapping `file` the way iproute2 does: the file of the last directory having it,
+/// then every `file.d/*.conf` fragment of all directories. Missing files are skipped.
+///
+/// ```no_run
+/// let realms = unixism::iproute2::load_at(&["/usr/share/iproute2", "/etc/iproute2"], "rt_realms").unwrap();
+/// ```
+pub fn load_at<P>(dirs: &[P], file: &str) -> Result<Vec<Entry>, ParseIproute2Error>
+where
+    P: AsRef<Path>,
+{
+    let mut entries = Vec::new();
+
+    if let Some(path) = dirs
+        .iter()
+        .rev()
+        .map(|dir| dir.as_ref().join(file))
+        .find(|path| path.exists())
+    {
+        entries.extend(parse(fs::File::open(path)?)?);
+    }
+
+    for dir in dirs {
+        let fragments = match fs::read_dir(dir.as_ref().join(format!("{}.d", file))) {
+            Ok(fragments) => fragments,
+            Err(error) if error.kind() == io::ErrorKind::NotFound => continue,
+            Err(error) => return Err(error.into()),
+        };
+
+        let mut paths = fragments
+            .filter_map(Result::ok)
+            .map(|fragment| fragment.path())
+            .filter(|path| {
+                path.extension()
+                    .is_some_and(|extension| extension == "conf")
+            })
+            .collect::<Vec<_>>();
+        paths.sort();
+
+        for path in paths {
+            entries.extend(parse(fs::File::open(path)?)?);
+        }
+    }
+
+    Ok(entries)
+}
+
+///
+/// Returns the routing table names, the built-in ones overridden by `rt_tables`.
+///
+/// ```no_run
+/// let tables = unixism::iproute2::tables().unwrap();
+/// ```
+pub fn tables() -> Result<Names, ParseIproute2Error> {
+    Ok(with_defaults(
+        &DEFAULT_TABLES,
+        load_at(&CONFIG_DIRS, "rt_tables")?,
+    ))
+}
+
+///
+/// Returns the route protocol names, the built-in ones overridden by `rt_protos`.
+///
+/// ```no_run
+/// let protos = unixism::iproute2::protos().unwrap();
+///
+/// println!("{}", protos.format(4));
+/// ```
+pub fn protos() -> Result<Names, ParseIproute2Error> {
+    Ok(with_defaults(
+        &DEFAULT_PROTOS,
+        load_at(&CONFIG_DIRS, "rt_protos")?,
+    ))
+}
+
+fn with_defaults(defaults: &[(u32, &str)], entries: Vec<Entry>) -> Names {
+    let mut names = defaults
+        .iter()
+        .map(|(id, name)| Entry {
+            id: *id,
+            name: (*name).to_owned(),
+        })
+        .collect::<Names>();
+    names.extend(entries);
+
+    names
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Cursor;
+
+    use super::*;
+    use crate::tempdir::TempDir;
+
+    #[test]
+    fn it_parse() {
+        let entries = parse(Cursor::new(
+            r#"
+#
+# reserved values
+#
+255	local
+254	main
+253	default
+0	unspec
+#
+# local
+#
+#1	inr.ruhep
+0x64	vpn   # wireguard
+        "#,
+        ))
+        .unwrap()
+        .collect::<Vec<_>>();
+
+        assert_eq!(5, entries.len());
+        assert_eq!(
+            Entry {
+                id: 100,
+                name: "vpn".to_owned()
+            },
+            entries[4]
+        );
+
+        let names = Names::new(entries);
+        assert_eq!(Some("main"), names.name(254));
+        assert_eq!(Some(100), names.id("vpn"));
+        assert_eq!(Some(42), names.id("42"));
+        assert_eq!(None, names.id("missing"));
+        assert_eq!("1000", names.format(1000));
+
+        assert!(parse(Cursor::new("main")).is_err());
+        assert!(parse(Cursor::new("x12 main")).is_err());
+    }
+
+    #[test]
+    fn it_load_at() {
+        let root = TempDir::new("iproute2");
+        let (usr, etc) = (root.join("usr"), root.join("etc"));
+        fs::create_dir_all(usr.join("rt_tables.d")).unwrap();
+        fs::create_dir_all(etc.join("rt_tables.d")).unwrap();
+
+        fs::write(usr.join("rt_tables"), "254 main\n10 vendor\n").unwrap();
+        fs::write(etc.join("rt_tables"), "254 main\n20 admin\n").unwrap();
+        fs::write(usr.join("rt_tables.d").join("a.conf"), "30 fragment\n").unwrap();
+        fs::write(etc.join("rt_tables.d").join("b.conf"), "20 renamed\n").unwrap();
+        fs::write(etc.join("rt_tables.d").join("README"), "not a table\n").unwrap();
+
+        let names = with_defaults(
+            &DEFAULT_TABLES,
+            load_at(&[&usr, &etc], "rt_tables").unwrap(),
+        );
+
+        assert_eq!(None, names.name(10));
+        assert_eq!(Some("renamed"), names.name(20));
+        assert_eq!(Some("fragment"), names.name(30));
+        assert_eq!(Some("local"), names.name(255));
+    }
+}
@@ -0,0 +1,407 @@
+use std::{
+    error, fmt, fs, io, mem,
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+use crate::osrelease::{self, OsRelease};
+
+const WEEKDAYS: [&str; 7] = ["Sun", "Mon", "Tue", "Wed", "Thu", "Fri", "Sat"];
+
+const MONTHS: [&str; 12] = [
+    "Jan", "Feb", "Mar", "Apr", "May", "Jun", "Jul", "Aug", "Sep", "Oct", "Nov", "Dec",
+];
+
+/// An agetty escape sequence of the issue file.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Escape {
+    /// `\n`, the host name.
+    Nodename,
+    /// `\o`, the NIS domain name.
+    Domainname,
+    /// `\l`, the name of the tty line.
+    Line,
+    /// `\s`, the kernel name.
+    System,
+    /// `\r`, the kernel release.
+    Release,
+    /// `\v`, the kernel version.
+    Version,
+    /// `\m`, the machine architecture.
+    Machine,
+    /// `\d`, the current date.
+    Date,
+    /// `\t`, the current time.
+    Time,
+    /// `\S` for `PRETTY_NAME` or `\S{VARIABLE}` of os-release.
+    OsRelease(Option<String>),
+    /// An escape this module doesn't expand, kept verbatim.
+    Other(String),
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Token {
+    Text(String),
+    Escape(Escape),
+}
+
+///
+/// A parsed issue file, expanded with `render`.
+///
+/// ```no_run
+/// use unixism::issue::{self, Context};
+///
+/// let issue = issue::parse_default().unwrap();
+///
+/// print!("{}", issue.render(&Context::current(Some("tty1")).unwrap()));
+/// ```
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct Issue {
+    pub tokens: Vec<Token>,
+}
+
+impl Issue {
+    pub fn render(&self, context: &Context) -> String {
+        let mut rendered = String::new();
+
+        for token in &self.tokens {
+            match token {
+                Token::Text(text) => rendered.push_str(text),
+                Token::Escape(escape) => rendered.push_str(&context.expand(escape)),
+            }
+        }
+
+        rendered
+    }
+}
+
+impl From<&str> for Issue {
+    fn from(s: &str) -> Self {
+        let mut tokens = Vec::new();
+        let mut text = String::new();
+        let mut chars = s.chars();
+
+        while let Some(c) = chars.next() {
+            if c != '\\' {
+                text.push(c);
+                continue;
+            }
+
+            let escape = match chars.next() {
+                Some('n') => Escape::Nodename,
+                Some('o') => Escape::Domainname,
+                Some('l') => Escape::Line,
+                Some('s') => Escape::System,
+                Some('r') => Escape::Release,
+                Some('v') => Escape::Version,
+                Some('m') => Escape::Machine,
+                Some('d') => Escape::Date,
+                Some('t') => Escape::Time,
+                Some('S') => match chars
+                    .as_str()
+                    .strip_prefix('{')
+                    .and_then(|rest| rest.split_once('}'))
+                {
+                    Some((name, rest)) => {
+                        chars = rest.chars();
+                        Escape::OsRelease(Some(name.to_owned()))
+                    }
+                    None => Escape::OsRelease(None),
+                },
+                Some('\\') => {
+                    text.push('\\');
+                    continue;
+                }
+                Some(c) => Escape::Other(format!("\\{}", c)),
+                None => Escape::Other("\\".to_owned()),
+            };
+
+            if !text.is_empty() {
+                tokens.push(Token::Text(mem::take(&mut text)));
+            }
+            tokens.push(Token::Escape(escape));
+        }
+
+        if !text.is_empty() {
+            tokens.push(Token::Text(text));
+        }
+
+        Issue { tokens }
+    }
+}
+
+/// A broken down local time.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct Time {
+    pub year: i32,
+    /// From 1 to 12.
+    pub month: u32,
+    pub day: u32,
+    /// From 0 for Sunday to 6.
+    pub weekday: u32,
+    pub hour: u32,
+    pub minute: u32,
+    pub second: u32,
+}
+
+impl Time {
+    /// Returns the current time in the local time zone.
+    pub fn now() -> io::Result<Self> {
+        let seconds = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))?
+            .as_secs() as libc::time_t;
+
+        let mut tm = unsafe { mem::zeroed::<libc::tm>() };
+        if unsafe { libc::localtime_r(&seconds, &mut tm) }.is_null() {
+            return Err(io::Error::last_os_error());
+        }
+
+        Ok(Time {
+            year: tm.tm_year + 1900,
+            month: tm.tm_mon as u32 + 1,
+            day: tm.tm_mday as u32,
+            weekday: tm.tm_wday as u32,
+            hour: tm.tm_hour as u32,
+            minute: tm.tm_min as u32,
+            second: tm.tm_sec as u32,
+        })
+    }
+
+    /// Formats the date the way agetty does, like `Thu Oct 16 2026`.
+    pub fn date(&self) -> String {
+        format!(
+            "{} {} {:>2} {}",
+            WEEKDAYS[self.weekday as usize % 7],
+            MONTHS[(self.month as usize + 11) % 12],
+            self.day,
+            self.year
+        )
+    }
+
+    /// Formats the time the way agetty does, like `09:05:00`.
+    pub fn time(&self) -> String {
+        format!("{:02}:{:02}:{:02}", self.hour, self.minute, self.second)
+    }
+}
+
+///
+/// The values escapes expand to.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct Context {
+    pub nodename: String,
+    pub domainname: Option<String>,
+    pub line: Option<String>,
+    pub sysname: String,
+    pub release: String,
+    pub version: String,
+    pub machine: String,
+    pub os_release: Option<OsRelease>,
+    pub time: Time,
+}
+
+impl Context {
+    ///
+    /// Collects the context of the running system: `uname(2)`,
+    /// os-release if it is readable and the local time. `line` is the tty the banner is shown on.
+    ///
+    /// ```no_run
+    /// let context = unixism::issue::Context::current(Some("ttyS0")).unwrap();
+    /// ```
+    pub fn current(line: Option<&str>) -> Result<Self, ParseIssueError> {
+        let mut uts = unsafe { mem::zeroed::<libc::utsname>() };
+        if unsafe { libc::uname(&mut uts) } == -1 {
+            return Err(io::Error::last_os_error().into());
+        }
+
+        let domainname = field(&uts.domainname);
+
+        Ok(Context {
+            // Taken as is, like agetty does, even when it isn't a valid host name.
+            nodename: field(&uts.nodename),
+            domainname: (!domainname.is_empty() && domainname != "(none)").then_some(domainname),
+            line: line.map(String::from),
+            sysname: field(&uts.sysname),
+            release: field(&uts.release),
+            version: field(&uts.version),
+            machine: field(&uts.machine),
+            os_release: osrelease::parse_default().ok(),
+            time: Time::now()?,
+        })
+    }
+
+    pub fn expand(&self, escape: &Escape) -> String {
+        match escape {
+            Escape::Nodename => self.nodename.clone(),
+            Escape::Domainname => self
+                .domainname
+                .clone()
+                .unwrap_or_else(|| "unknown_domain".to_owned()),
+            Escape::Line => self.line.clone().unwrap_or_default(),
+            Escape::System => self.sysname.clone(),
+            Escape::Release => self.release.clone(),
+            Escape::Version => self.version.clone(),
+            Escape::Machine => self.machine.clone(),
+            Escape::Date => self.time.date(),
+            Escape::Time => self.time.time(),
+            Escape::OsRelease(None) => self
+                .os_release
+                .as_ref()
+                .map(|release| release.pretty_name.clone())
+                .unwrap_or_else(|| self.sysname.clone()),
+            Escape::OsRelease(Some(name)) => self
+                .os_release
+                .as_ref()
+                .and_then(|release| release.get(name))
+                .map(String::from)
+                .unwrap_or_default(),
+            Escape::Other(raw) => raw.clone(),
+        }
+    }
+}
+
+fn field(value: &[libc::c_char]) -> String {
+    let bytes = value
+        .iter()
+        .take_while(|c| **c != 0)
+        .map(|c| *c as u8)
+        .collect::<Vec<_>>();
+
+    String::from_utf8_lossy(&bytes).into_owned()
+}
+
+#[derive(Debug)]
+pub enum ParseIssueError {
+    IOError(io::Error),
+}
+
+impl error::Error for ParseIssueError {}
+
+impl fmt::Display for ParseIssueError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::IOError(error) => write!(f, "{}", error),
+        }
+    }
+}
+
+impl From<io::Error> for ParseIssueError {
+    fn from(value: io::Error) -> Self {
+        ParseIssueError::IOError(value)
+    }
+}
+
+///
+/// ```no_run
+/// let issue = unixism::issue::parse(std::fs::File::open("/etc/issue").unwrap()).unwrap();
+/// ```
+pub fn parse<R>(mut reader: R) -> Result<Issue, ParseIssueError>
+where
+    R: io::Read,
+{
+    let mut content = String::new();
+    reader.read_to_string(&mut content)?;
+
+    Ok(Issue::from(content.as_str()))
+}
+
+///
+/// Same as parse, but parses the `/etc/issue` as default.
+///
+/// ```no_run
+/// let issue = unixism::issue::parse_default().unwrap();
+/// ```
+pub fn parse_default() -> Result<Issue, ParseIssueError> {
+    parse(fs::File::open("/etc/issue")?)
+}
+
+///
+/// Renders the `/etc/issue` for the `line` with the context of the running system.
+///
+/// ```no_run
+/// print!("{}", unixism::issue::banner(Some("tty1")).unwrap());
+/// ```
+pub fn banner(line: Option<&str>) -> Result<String, ParseIssueError> {
+    Ok(parse_default()?.render(&Context::current(line)?))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Cursor;
+
+    use super::*;
+
+    fn context() -> Context {
+        Context {
+            nodename: "web-01".to_owned(),
+            domainname: None,
+            line: Some("tty1".to_owned()),
+            sysname: "Linux".to_owned(),
+            release: "6.5.0-14-generic".to_owned(),
+            version: "#14-Ubuntu SMP".to_owned(),
+            machine: "x86_64".to_owned(),
+            os_release: osrelease::parse(Cursor::new(
+                "PRETTY_NAME=\"Ubuntu 22.04.3 LTS\"\nVERSION_ID=22.04\n",
+            ))
+            .ok(),
+            time: Time {
+                year: 2026,
+                month: 10,
+                day: 6,
+                weekday: 2,
+                hour: 9,
+                minute: 5,
+                second: 0,
+            },
+        }
+    }
+
+    #[test]
+    fn it_parse() {
+        let issue = parse(Cursor::new("\\S \\n \\l\n")).unwrap();
+
+        assert_eq!(
+            vec![
+                Token::Escape(Escape::OsRelease(None)),
+                Token::Text(" ".to_owned()),
+                Token::Escape(Escape::Nodename),
+                Token::Text(" ".to_owned()),
+                Token::Escape(Escape::Line),
+                Token::Text("\n".to_owned()),
+            ],
+            issue.tokens
+        );
+
+        let issue = Issue::from("\\S{VERSION_ID} \\4{eth0} \\\\ \\S{UNTERMINATED");
+        assert_eq!(
+            Token::Escape(Escape::OsRelease(Some("VERSION_ID".to_owned()))),
+            issue.tokens[0]
+        );
+        assert_eq!(
+            Token::Escape(Escape::Other("\\4".to_owned())),
+            issue.tokens[2]
+        );
+        assert_eq!(Token::Text("{eth0} \\ ".to_owned()), issue.tokens[3]);
+        assert_eq!(Token::Escape(Escape::OsRelease(None)), issue.tokens[4]);
+    }
+
+    #[test]
+    fn it_render() {
+        let issue = Issue::from(
+            r#"\S \n \l
+Kernel \s \r on an \m (\o)
+\d \t \S{VERSION_ID}\S{MISSING} \x
+"#,
+        );
+
+        assert_eq!(
+            "Ubuntu 22.04.3 LTS web-01 tty1\nKernel Linux 6.5.0-14-generic on an x86_64 (unknown_domain)\nTue Oct  6 2026 09:05:00 22.04 \\x\n",
+            issue.render(&context())
+        );
+
+        let context = Context {
+            os_release: None,
+            ..context()
+        };
+        assert_eq!("Linux", Issue::from("\\S").render(&context));
+    }
+}
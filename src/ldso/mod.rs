@@ -0,0 +1,319 @@
+use std::{
+    error, fmt, fs,
+    io::{self, BufRead, BufReader},
+    path::{Path, PathBuf},
+};
+
+use crate::glob::glob;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Directive {
+    /// A directory searched for libraries.
+    Directory(PathBuf),
+    /// An `include` of the files matching the pattern.
+    Include(String),
+    /// The obsolete `hwcap` line, ignored by modern ldconfig.
+    Hwcap(String),
+}
+
+#[derive(Debug)]
+pub enum ParseLdSoError {
+    InvalidEntry(String),
+    IOError(io::Error),
+}
+
+impl error::Error for ParseLdSoError {}
+
+impl fmt::Display for ParseLdSoError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::InvalidEntry(entry) => write!(f, "invalid entry: {}", entry),
+            Self::IOError(error) => write!(f, "{}", error),
+        }
+    }
+}
+
+impl From<io::Error> for ParseLdSoError {
+    fn from(value: io::Error) -> Self {
+        ParseLdSoError::IOError(value)
+    }
+}
+
+///
+/// ```no_run
+/// let directives = unixism::ldso::parse(std::fs::File::open("/etc/ld.so.conf").unwrap()).unwrap();
+///
+/// for directive in directives {
+///     println!("{:?}", directive);
+/// }
+/// ```
+pub fn parse<R>(reader: R) -> Result<impl Iterator<Item = Directive>, ParseLdSoError>
+where
+    R: io::Read,
+{
+    let mut directives = Vec::new();
+
+    for line in BufReader::new(reader).lines() {
+        let line = line?;
+        let line = line.split('#').next().unwrap_or_default().trim();
+
+        if line.is_empty() {
+            continue;
+        }
+
+        let (keyword, rest) = line.split_once(char::is_whitespace).unwrap_or((line, ""));
+
+        match keyword {
+            "include" => {
+                let patterns = rest.split_whitespace().collect::<Vec<_>>();
+
+                if patterns.is_empty() {
+                    return Err(ParseLdSoError::InvalidEntry(line.to_owned()));
+                }
+
+                directives.extend(
+                    patterns
+                        .into_iter()
+                        .map(|pattern| Directive::Include(pattern.to_owned())),
+                );
+            }
+            "hwcap" => directives.push(Directive::Hwcap(rest.trim().to_owned())),
+            _ => {
+                let dir = line.trim_end_matches('/');
+
+                directives.push(Directive::Directory(PathBuf::from(if dir.is_empty() {
+                    "/"
+                } else {
+                    dir
+                })));
+            }
+        }
+    }
+
+    Ok(directives.into_iter())
+}
+
+///
+/// Reads the configuration at `path` and returns the directories in search order, the way ldconfig does:
+/// `include` patterns are expanded in place, relative ones against the directory of the including file,
+/// and directories listed twice are kept at their first position.
+///
+/// ```no_run
+/// let dirs = unixism::ldso::directories_at("/mnt/etc/ld.so.conf").unwrap();
+/// ```
+pub fn directories_at<P>(path: P) -> Result<Vec<PathBuf>, ParseLdSoError>
+where
+    P: AsRef<Path>,
+{
+    let mut directories = Vec::new();
+    collect(path.as_ref(), &mut Vec::new(), &mut directories)?;
+
+    Ok(directories)
+}
+
+fn collect(
+    path: &Path,
+    stack: &mut Vec<PathBuf>,
+    directories: &mut Vec<PathBuf>,
+) -> Result<(), ParseLdSoError> {
+    let path = fs::canonicalize(path)?;
+
+    // Guards against files including themselves.
+    if stack.contains(&path) {
+        return Ok(());
+    }
+
+    let base = path.parent().unwrap_or(Path::new("/")).to_owned();
+    let directives = parse(fs::File::open(&path)?)?;
+
+    stack.push(path);
+
+    for directive in directives {
+        match directive {
+            Directive::Directory(dir) => {
+                if !directories.contains(&dir) {
+                    directories.push(dir);
+                }
+            }
+            Directive::Include(pattern) => {
+                for file in expand(&base.join(pattern))? {
+                    collect(&file, stack, directories)?;
+                }
+            }
+            Directive::Hwcap(_) => {}
+        }
+    }
+
+    stack.pop();
+
+    Ok(())
+}
+
+/// Expands `*` and `?` in the file name of the pattern, sorted like glob(3) does.
+fn expand(pattern: &Path) -> io::Result<Vec<PathBuf>> {
+    let name = pattern
+        .file_name()
+        .map(|name| name.to_string_lossy().into_owned())
+        .unwrap_or_default();
+
+    if !name.contains(['*', '?']) {
+        return Ok(if pattern.exists() {
+            vec![pattern.to_owned()]
+        } else {
+            Vec::new()
+        });
+    }
+
+    let dir = pattern.parent().unwrap_or(Path::new("/"));
+    let entries = match fs::read_dir(dir) {
+        Ok(entries) => entries,
+        Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok(Vec::new()),
+        Err(e) => return Err(e),
+    };
+
+    let mut files = Vec::new();
+
+    for entry in entries {
+        let entry = entry?;
+        let file_name = entry.file_name().to_string_lossy().into_owned();
+
+        if !file_name.starts_with('.') && glob(&name, &file_name) && entry.path().is_file() {
+            files.push(entry.path());
+        }
+    }
+    files.sort();
+
+    Ok(files)
+}
+
+///
+/// Same as directories_at, but reads the `/etc/ld.so.conf` as default.
+///
+/// ```no_run
+/// for dir in unixism::ldso::directories().unwrap() {
+///     println!("{}", dir.display());
+/// }
+/// ```
+pub fn directories() -> Result<Vec<PathBuf>, ParseLdSoError> {
+    directories_at("/etc/ld.so.conf")
+}
+
+///
+/// Parses the libraries of `/etc/ld.so.preload`, separated by whitespace or colons.
+///
+/// ```no_run
+/// let libraries = unixism::ldso::parse_preload(std::fs::File::open("/etc/ld.so.preload").unwrap()).unwrap();
+/// ```
+pub fn parse_preload<R>(reader: R) -> Result<Vec<PathBuf>, ParseLdSoError>
+where
+    R: io::Read,
+{
+    let mut libraries = Vec::new();
+
+    for line in BufReader::new(reader).lines() {
+        let line = line?;
+        let line = line.split('#').next().unwrap_or_default();
+
+        libraries.extend(
+            line.split(|c: char| c.is_whitespace() || c == ':')
+                .filter(|library| !library.is_empty())
+                .map(PathBuf::from),
+        );
+    }
+
+    Ok(libraries)
+}
+
+///
+/// Same as parse_preload, but parses the `/etc/ld.so.preload` as default.
+/// A missing file means nothing is preloaded, as for the dynamic linker.
+///
+/// ```no_run
+/// let libraries = unixism::ldso::preload().unwrap();
+/// ```
+pub fn preload() -> Result<Vec<PathBuf>, ParseLdSoError> {
+    match fs::File::open("/etc/ld.so.preload") {
+        Ok(file) => parse_preload(file),
+        Err(e) if e.kind() == io::ErrorKind::NotFound => Ok(Vec::new()),
+        Err(e) => Err(e.into()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Cursor;
+
+    use super::*;
+    use crate::tempdir::TempDir;
+
+    #[test]
+    fn it_parse() {
+        let directives = parse(Cursor::new(
+            r#"
+include /etc/ld.so.conf.d/*.conf
+# comment
+/usr/local/lib/
+/opt/lib # trailing
+hwcap 0 nosegneg
+include a.conf b.conf
+        "#,
+        ))
+        .unwrap()
+        .collect::<Vec<_>>();
+
+        assert_eq!(
+            vec![
+                Directive::Include("/etc/ld.so.conf.d/*.conf".to_owned()),
+                Directive::Directory(PathBuf::from("/usr/local/lib")),
+                Directive::Directory(PathBuf::from("/opt/lib")),
+                Directive::Hwcap("0 nosegneg".to_owned()),
+                Directive::Include("a.conf".to_owned()),
+                Directive::Include("b.conf".to_owned()),
+            ],
+            directives
+        );
+
+        assert!(parse(Cursor::new("include")).is_err());
+    }
+
+    #[test]
+    fn it_directories_at() {
+        let root = TempDir::new("ldso");
+        let conf_d = root.join("ld.so.conf.d");
+        fs::create_dir_all(&conf_d).unwrap();
+
+        fs::write(
+            root.join("ld.so.conf"),
+            "/usr/local/lib\ninclude ld.so.conf.d/*.conf\ninclude missing/*.conf\n/usr/lib\n",
+        )
+        .unwrap();
+        fs::write(conf_d.join("b.conf"), "/opt/b/lib\n/usr/local/lib\n").unwrap();
+        fs::write(conf_d.join("a.conf"), "/opt/a/lib\ninclude ../ld.so.conf\n").unwrap();
+        fs::write(conf_d.join("README"), "/not/included\n").unwrap();
+
+        assert_eq!(
+            vec![
+                PathBuf::from("/usr/local/lib"),
+                PathBuf::from("/opt/a/lib"),
+                PathBuf::from("/opt/b/lib"),
+                PathBuf::from("/usr/lib"),
+            ],
+            directories_at(root.join("ld.so.conf")).unwrap()
+        );
+    }
+
+    #[test]
+    fn it_parse_preload() {
+        assert_eq!(
+            vec![
+                PathBuf::from("/usr/lib/libjemalloc.so.2"),
+                PathBuf::from("/lib/a.so"),
+                PathBuf::from("/lib/b.so"),
+            ],
+            parse_preload(Cursor::new(
+                "# preloaded\n/usr/lib/libjemalloc.so.2\n/lib/a.so:/lib/b.so # two\n"
+            ))
+            .unwrap()
+        );
+    }
+}
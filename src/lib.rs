@@ -1,5 +1,187 @@
+#[cfg(feature = "access")]
+pub mod access;
+
+#[cfg(feature = "accounts")]
+pub mod accounts;
+
+#[cfg(feature = "cgroup")]
+pub mod cgroup;
+
+#[cfg(feature = "cron")]
+pub mod cron;
+
+#[cfg(feature = "crypttab")]
+pub mod crypttab;
+
 #[cfg(feature = "dns")]
 pub mod dns;
 
+#[cfg(any(feature = "group", feature = "passwd"))]
+mod compat;
+
+#[cfg(any(
+    feature = "modprobe",
+    feature = "proc",
+    feature = "sysctl",
+    feature = "systemdconf",
+    feature = "tmpfiles",
+    feature = "udev"
+))]
+mod dropin;
+
+#[cfg(feature = "env")]
+pub mod env;
+
+#[cfg(feature = "ethers")]
+pub mod ethers;
+
+#[cfg(feature = "exports")]
+pub mod exports;
+
+#[cfg(any(
+    feature = "fstab",
+    feature = "group",
+    feature = "hostname",
+    feature = "passwd"
+))]
+mod file;
+
+#[cfg(feature = "fstab")]
+pub mod fstab;
+
+#[cfg(feature = "gai")]
+pub mod gai;
+
+#[cfg(any(
+    feature = "ifupdown",
+    feature = "ldso",
+    feature = "modprobe",
+    feature = "tcpwrappers"
+))]
+mod glob;
+
+#[cfg(feature = "group")]
+pub mod group;
+
+#[cfg(feature = "gshadow")]
+pub mod gshadow;
+
+#[cfg(feature = "hostname")]
+pub mod hostname;
+
 #[cfg(feature = "hosts")]
 pub mod hosts;
+
+#[cfg(feature = "ifcfg")]
+pub mod ifcfg;
+
+#[cfg(feature = "ifupdown")]
+pub mod ifupdown;
+
+#[cfg(feature = "inittab")]
+pub mod inittab;
+
+#[cfg(feature = "iproute2")]
+pub mod iproute2;
+
+#[cfg(feature = "issue")]
+pub mod issue;
+
+#[cfg(feature = "ldso")]
+pub mod ldso;
+
+#[cfg(feature = "locale")]
+pub mod locale;
+
+#[cfg(feature = "logindefs")]
+pub mod logindefs;
+
+#[cfg(feature = "lookup")]
+pub mod lookup;
+
+#[cfg(feature = "machineid")]
+pub mod machineid;
+
+#[cfg(feature = "modprobe")]
+pub mod modprobe;
+
+#[cfg(feature = "mounts")]
+pub mod mounts;
+
+#[cfg(feature = "netgroup")]
+pub mod netgroup;
+
+#[cfg(feature = "networkmanager")]
+pub mod networkmanager;
+
+#[cfg(feature = "networks")]
+pub mod networks;
+
+#[cfg(feature = "nsswitch")]
+pub mod nsswitch;
+
+#[cfg(feature = "osrelease")]
+pub mod osrelease;
+
+#[cfg(feature = "pam")]
+pub mod pam;
+
+#[cfg(feature = "passwd")]
+pub mod passwd;
+
+#[cfg(feature = "proc")]
+pub mod proc;
+
+#[cfg(feature = "rhosts")]
+pub mod rhosts;
+
+#[cfg(feature = "securetty")]
+pub mod securetty;
+
+#[cfg(feature = "selinux")]
+pub mod selinux;
+
+#[cfg(feature = "services")]
+pub mod services;
+
+#[cfg(feature = "shadow")]
+pub mod shadow;
+
+#[cfg(feature = "shellvars")]
+pub mod shellvars;
+
+#[cfg(feature = "shells")]
+pub mod shells;
+
+#[cfg(feature = "subid")]
+pub mod subid;
+
+#[cfg(feature = "sudoers")]
+pub mod sudoers;
+
+#[cfg(feature = "sysctl")]
+pub mod sysctl;
+
+#[cfg(feature = "sysfs")]
+pub mod sysfs;
+
+#[cfg(feature = "systemdconf")]
+pub mod systemdconf;
+
+#[cfg(feature = "tcpwrappers")]
+pub mod tcpwrappers;
+
+#[cfg(test)]
+mod tempdir;
+
+#[cfg(feature = "timezone")]
+pub mod timezone;
+
+#[cfg(feature = "tmpfiles")]
+pub mod tmpfiles;
+
+#[cfg(feature = "udev")]
+pub mod udev;
+
+#[cfg(feature = "wireguard")]
+pub mod wireguard;
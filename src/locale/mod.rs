@@ -0,0 +1,601 @@
+use std::{
+    error, fmt, fs,
+    io::{self, BufRead, BufReader},
+    path::Path,
+    str::FromStr,
+};
+
+use crate::shellvars::{self, quote, ParseShellVarsError as ShellVarsError};
+
+/// Where the system locale is looked for, systemd's file first and Debian's second.
+pub const PATHS: [&str; 2] = ["/etc/locale.conf", "/etc/default/locale"];
+
+/// The locale categories which can be set besides `LANG` and `LANGUAGE`.
+pub const CATEGORIES: [&str; 13] = [
+    "LC_CTYPE",
+    "LC_NUMERIC",
+    "LC_TIME",
+    "LC_COLLATE",
+    "LC_MONETARY",
+    "LC_MESSAGES",
+    "LC_PAPER",
+    "LC_NAME",
+    "LC_ADDRESS",
+    "LC_TELEPHONE",
+    "LC_MEASUREMENT",
+    "LC_IDENTIFICATION",
+    "LC_ALL",
+];
+
+///
+/// A locale name like `en_US.UTF-8` or `sr_RS@latin`, `language[_territory][.codeset][@modifier]`.
+///
+/// ```
+/// let locale = "de_DE.UTF-8@euro".parse::<unixism::locale::Locale>().unwrap();
+///
+/// assert_eq!("de", locale.language);
+/// assert_eq!(Some("DE"), locale.territory.as_deref());
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Locale {
+    pub language: String,
+    pub territory: Option<String>,
+    pub codeset: Option<String>,
+    pub modifier: Option<String>,
+}
+
+impl Locale {
+    /// Whether this is the `C` or `POSIX` locale, with any codeset.
+    pub fn is_posix(&self) -> bool {
+        self.territory.is_none() && matches!(self.language.as_str(), "C" | "POSIX")
+    }
+
+    /// Whether the codeset is UTF-8, however it is spelled.
+    pub fn is_utf8(&self) -> bool {
+        self.codeset.as_deref().is_some_and(|codeset| {
+            codeset.eq_ignore_ascii_case("utf-8") || codeset.eq_ignore_ascii_case("utf8")
+        })
+    }
+}
+
+impl FromStr for Locale {
+    type Err = ParseLocaleError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (rest, modifier) = match s.split_once('@') {
+            Some((rest, modifier)) => (rest, Some(modifier)),
+            None => (s, None),
+        };
+        let (rest, codeset) = match rest.split_once('.') {
+            Some((rest, codeset)) => (rest, Some(codeset)),
+            None => (rest, None),
+        };
+        let (language, territory) = match rest.split_once('_') {
+            Some((language, territory)) => (language, Some(territory)),
+            None => (rest, None),
+        };
+
+        let valid = |part: &str| {
+            !part.is_empty()
+                && part
+                    .chars()
+                    .all(|c| c.is_ascii_alphanumeric() || matches!(c, '-' | '_'))
+        };
+
+        if !valid(language)
+            || ![territory, codeset, modifier]
+                .into_iter()
+                .flatten()
+                .all(valid)
+        {
+            return Err(ParseLocaleError::InvalidEntry(s.to_owned()));
+        }
+
+        Ok(Locale {
+            language: language.to_owned(),
+            territory: territory.map(String::from),
+            codeset: codeset.map(String::from),
+            modifier: modifier.map(String::from),
+        })
+    }
+}
+
+impl fmt::Display for Locale {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.language)?;
+
+        if let Some(territory) = &self.territory {
+            write!(f, "_{}", territory)?;
+        }
+
+        if let Some(codeset) = &self.codeset {
+            write!(f, ".{}", codeset)?;
+        }
+
+        if let Some(modifier) = &self.modifier {
+            write!(f, "@{}", modifier)?;
+        }
+
+        Ok(())
+    }
+}
+
+///
+/// The variables of `/etc/locale.conf` or `/etc/default/locale`, in file order.
+///
+/// ```no_run
+/// let mut config = unixism::locale::parse_default().unwrap();
+///
+/// println!("{:?}", config.effective("LC_TIME"));
+///
+/// config.set("LC_TIME", "en_GB.UTF-8");
+/// ```
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct Config {
+    pub variables: Vec<(String, String)>,
+}
+
+impl Config {
+    pub fn get(&self, name: &str) -> Option<&str> {
+        self.variables
+            .iter()
+            .rev()
+            .find(|(variable, _)| variable == name)
+            .map(|(_, value)| value.as_str())
+    }
+
+    /// Returns the variable as a locale, `None` when it is unset or not a valid locale name.
+    pub fn locale(&self, name: &str) -> Option<Locale> {
+        self.get(name)?.parse().ok()
+    }
+
+    pub fn lang(&self) -> Option<Locale> {
+        self.locale("LANG")
+    }
+
+    /// Returns the `LANGUAGE` priority list used for message translations.
+    pub fn language(&self) -> Vec<String> {
+        self.get("LANGUAGE")
+            .map(|language| {
+                language
+                    .split(':')
+                    .filter(|language| !language.is_empty())
+                    .map(String::from)
+                    .collect()
+            })
+            .unwrap_or_default()
+    }
+
+    /// Resolves the locale of the category the way libc does: `LC_ALL`, then the category itself, then `LANG`.
+    pub fn effective(&self, category: &str) -> Option<Locale> {
+        self.locale("LC_ALL")
+            .or_else(|| self.locale(category))
+            .or_else(|| self.lang())
+    }
+
+    /// Sets the variable, replacing it in place when it is already set.
+    pub fn set(&mut self, name: &str, value: &str) {
+        match self
+            .variables
+            .iter_mut()
+            .find(|(variable, _)| variable == name)
+        {
+            Some((_, current)) => *current = value.to_owned(),
+            None => self.variables.push((name.to_owned(), value.to_owned())),
+        }
+    }
+
+    /// Removes the variable, returning whether it was set.
+    pub fn remove(&mut self, name: &str) -> bool {
+        let len = self.variables.len();
+        self.variables.retain(|(variable, _)| variable != name);
+
+        len != self.variables.len()
+    }
+}
+
+impl fmt::Display for Config {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (name, value) in &self.variables {
+            writeln!(f, "{}={}", name, quote(value))?;
+        }
+
+        Ok(())
+    }
+}
+
+///
+/// A line of `/etc/locale.gen`, a locale and its charset like `en_US.UTF-8 UTF-8`.
+/// Commented out lines of the same shape are the candidates `locale-gen` skips.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct GenEntry {
+    pub locale: Locale,
+    pub charset: String,
+    pub enabled: bool,
+}
+
+impl FromStr for GenEntry {
+    type Err = ParseLocaleError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let fields = s.split_whitespace().collect::<Vec<_>>();
+
+        let [locale, charset] = fields[..] else {
+            return Err(ParseLocaleError::InvalidEntry(s.to_owned()));
+        };
+
+        Ok(GenEntry {
+            locale: locale.parse()?,
+            charset: charset.to_owned(),
+            enabled: true,
+        })
+    }
+}
+
+impl fmt::Display for GenEntry {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if !self.enabled {
+            write!(f, "# ")?;
+        }
+
+        write!(f, "{} {}", self.locale, self.charset)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum GenLine {
+    Entry(GenEntry),
+    Comment(String),
+}
+
+///
+/// The lines of `/etc/locale.gen`, comments are kept so it can be written back as it was.
+///
+/// ```no_run
+/// let mut gen = unixism::locale::parse_gen_default().unwrap();
+///
+/// if gen.enable("de_DE.UTF-8") {
+///     unixism::locale::write_gen(std::fs::File::create("/etc/locale.gen").unwrap(), &gen).unwrap();
+/// }
+/// ```
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct LocaleGen {
+    pub lines: Vec<GenLine>,
+}
+
+impl LocaleGen {
+    pub fn entries(&self) -> impl Iterator<Item = &GenEntry> {
+        self.lines.iter().filter_map(|line| match line {
+            GenLine::Entry(entry) => Some(entry),
+            GenLine::Comment(_) => None,
+        })
+    }
+
+    /// Returns the locales `locale-gen` builds.
+    pub fn enabled(&self) -> impl Iterator<Item = &GenEntry> {
+        self.entries().filter(|entry| entry.enabled)
+    }
+
+    /// Returns the commented out locales which can be enabled.
+    pub fn candidates(&self) -> impl Iterator<Item = &GenEntry> {
+        self.entries().filter(|entry| !entry.enabled)
+    }
+
+    /// Uncomments the locale, returning false when it isn't listed at all.
+    pub fn enable(&mut self, locale: &str) -> bool {
+        self.toggle(locale, true)
+    }
+
+    /// Comments the locale out, returning false when it isn't listed at all.
+    pub fn disable(&mut self, locale: &str) -> bool {
+        self.toggle(locale, false)
+    }
+
+    fn toggle(&mut self, locale: &str, enabled: bool) -> bool {
+        let mut found = false;
+
+        for line in &mut self.lines {
+            if let GenLine::Entry(entry) = line {
+                if entry.locale.to_string() == locale {
+                    entry.enabled = enabled;
+                    found = true;
+                }
+            }
+        }
+
+        found
+    }
+}
+
+impl fmt::Display for LocaleGen {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for line in &self.lines {
+            match line {
+                GenLine::Entry(entry) => writeln!(f, "{}", entry)?,
+                GenLine::Comment(comment) => writeln!(f, "{}", comment)?,
+            }
+        }
+
+        Ok(())
+    }
+}
+
+#[derive(Debug)]
+pub enum ParseLocaleError {
+    InvalidEntry(String),
+    IOError(io::Error),
+}
+
+impl error::Error for ParseLocaleError {}
+
+impl fmt::Display for ParseLocaleError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::InvalidEntry(entry) => write!(f, "invalid entry: {}", entry),
+            Self::IOError(error) => write!(f, "{}", error),
+        }
+    }
+}
+
+impl From<ShellVarsError> for ParseLocaleError {
+    fn from(value: ShellVarsError) -> Self {
+        match value {
+            ShellVarsError::InvalidEntry(entry) => ParseLocaleError::InvalidEntry(entry),
+            ShellVarsError::IOError(error) => ParseLocaleError::IOError(error),
+        }
+    }
+}
+
+impl From<io::Error> for ParseLocaleError {
+    fn from(value: io::Error) -> Self {
+        ParseLocaleError::IOError(value)
+    }
+}
+
+///
+/// ```no_run
+/// let config = unixism::locale::parse(std::fs::File::open("/etc/locale.conf").unwrap()).unwrap();
+///
+/// println!("{:?}", config.lang());
+/// ```
+pub fn parse<R>(reader: R) -> Result<Config, ParseLocaleError>
+where
+    R: io::Read,
+{
+    Ok(Config {
+        variables: shellvars::parse(reader)?.variables,
+    })
+}
+
+///
+/// Parses the first of `paths` which exists.
+///
+/// ```no_run
+/// let config = unixism::locale::load_at(&["/mnt/etc/locale.conf", "/mnt/etc/default/locale"]).unwrap();
+/// ```
+pub fn load_at<P>(paths: &[P]) -> Result<Config, ParseLocaleError>
+where
+    P: AsRef<Path>,
+{
+    let mut error = io::Error::from(io::ErrorKind::NotFound);
+
+    for path in paths {
+        match fs::File::open(path) {
+            Ok(file) => return parse(file),
+            Err(e) if e.kind() == io::ErrorKind::NotFound => error = e,
+            Err(e) => return Err(e.into()),
+        }
+    }
+
+    Err(error.into())
+}
+
+///
+/// Same as load_at, but reads `/etc/locale.conf` falling back to `/etc/default/locale`.
+///
+/// ```no_run
+/// let config = unixism::locale::parse_default().unwrap();
+/// ```
+pub fn parse_default() -> Result<Config, ParseLocaleError> {
+    load_at(&PATHS)
+}
+
+///
+/// Writes the variables one per line, quoting the values which need it.
+/// Comments of the original file are not preserved.
+///
+/// ```no_run
+/// let mut config = unixism::locale::parse_default().unwrap();
+/// config.set("LANG", "en_US.UTF-8");
+///
+/// unixism::locale::write(std::fs::File::create("/etc/locale.conf").unwrap(), &config).unwrap();
+/// ```
+pub fn write<W>(mut writer: W, config: &Config) -> io::Result<()>
+where
+    W: io::Write,
+{
+    write!(writer, "{}", config)
+}
+
+///
+/// ```no_run
+/// let gen = unixism::locale::parse_gen(std::fs::File::open("/etc/locale.gen").unwrap()).unwrap();
+///
+/// for entry in gen.enabled() {
+///     println!("{} {}", entry.locale, entry.charset);
+/// }
+/// ```
+pub fn parse_gen<R>(reader: R) -> Result<LocaleGen, ParseLocaleError>
+where
+    R: io::Read,
+{
+    let mut gen = LocaleGen::default();
+
+    for line in BufReader::new(reader).lines() {
+        let line = line?;
+        let trimmed = line.trim();
+
+        let entry = match trimmed.strip_prefix('#') {
+            Some(candidate) => candidate.parse::<GenEntry>().ok().map(|entry| GenEntry {
+                enabled: false,
+                ..entry
+            }),
+            None if trimmed.is_empty() => None,
+            None => Some(trimmed.parse::<GenEntry>()?),
+        };
+
+        gen.lines.push(match entry {
+            Some(entry) => GenLine::Entry(entry),
+            None => GenLine::Comment(line),
+        });
+    }
+
+    Ok(gen)
+}
+
+///
+/// Same as parse_gen, but parses the `/etc/locale.gen` as default.
+///
+/// ```no_run
+/// let gen = unixism::locale::parse_gen_default().unwrap();
+/// ```
+pub fn parse_gen_default() -> Result<LocaleGen, ParseLocaleError> {
+    parse_gen(fs::File::open("/etc/locale.gen")?)
+}
+
+///
+/// Writes the locale.gen back, candidates as `# locale charset`.
+///
+/// ```no_run
+/// let mut gen = unixism::locale::parse_gen_default().unwrap();
+/// gen.disable("en_GB.UTF-8");
+///
+/// unixism::locale::write_gen(std::fs::File::create("/etc/locale.gen").unwrap(), &gen).unwrap();
+/// ```
+pub fn write_gen<W>(mut writer: W, gen: &LocaleGen) -> io::Result<()>
+where
+    W: io::Write,
+{
+    write!(writer, "{}", gen)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Cursor;
+
+    use super::*;
+
+    #[test]
+    fn it_parse_locale() {
+        let locale = "sr_RS.UTF-8@latin".parse::<Locale>().unwrap();
+
+        assert_eq!(
+            Locale {
+                language: "sr".to_owned(),
+                territory: Some("RS".to_owned()),
+                codeset: Some("UTF-8".to_owned()),
+                modifier: Some("latin".to_owned()),
+            },
+            locale
+        );
+        assert_eq!("sr_RS.UTF-8@latin", locale.to_string());
+        assert!(locale.is_utf8());
+        assert!(!locale.is_posix());
+
+        assert!("C.utf8".parse::<Locale>().unwrap().is_posix());
+        assert!("POSIX".parse::<Locale>().unwrap().is_posix());
+        assert!("".parse::<Locale>().is_err());
+        assert!("en_US.".parse::<Locale>().is_err());
+        assert!("en US".parse::<Locale>().is_err());
+    }
+
+    #[test]
+    fn it_parse() {
+        let mut config = parse(Cursor::new(
+            r#"
+# generated by localectl
+LANG=en_US.UTF-8
+LANGUAGE="en_US:en"
+export LC_TIME='en_GB.UTF-8'
+LC_PAPER="bogus locale"
+        "#,
+        ))
+        .unwrap();
+
+        assert_eq!("en_US.UTF-8", config.lang().unwrap().to_string());
+        assert_eq!(vec!["en_US", "en"], config.language());
+        assert_eq!(
+            "en_GB.UTF-8",
+            config.effective("LC_TIME").unwrap().to_string()
+        );
+        assert_eq!(
+            "en_US.UTF-8",
+            config.effective("LC_NUMERIC").unwrap().to_string()
+        );
+        assert_eq!(None, config.locale("LC_PAPER"));
+
+        config.set("LC_ALL", "C.UTF-8");
+        assert_eq!("C.UTF-8", config.effective("LC_TIME").unwrap().to_string());
+
+        config.set("LANG", "de_DE.UTF-8");
+        assert!(config.remove("LC_ALL"));
+        assert!(!config.remove("LC_ALL"));
+
+        let mut written = Vec::new();
+        write(&mut written, &config).unwrap();
+        assert_eq!(
+            "LANG=de_DE.UTF-8\nLANGUAGE=en_US:en\nLC_TIME=en_GB.UTF-8\nLC_PAPER=\"bogus locale\"\n",
+            String::from_utf8(written.clone()).unwrap()
+        );
+        assert_eq!(config, parse(Cursor::new(written)).unwrap());
+
+        assert!(parse(Cursor::new("LANG=en_US.UTF-8 trailing")).is_err());
+        assert!(parse(Cursor::new("LANG")).is_err());
+    }
+
+    #[test]
+    fn it_parse_gen() {
+        let mut gen = parse_gen(Cursor::new(
+            r#"# This file lists locales that you wish to have built. You can find a list
+# of valid supported locales at /usr/share/i18n/SUPPORTED, and you can add
+# user defined locales to /usr/local/share/i18n/SUPPORTED.
+#
+# de_DE.UTF-8 UTF-8
+# en_GB.UTF-8 UTF-8
+#en_GB ISO-8859-1
+en_US.UTF-8 UTF-8
+"#,
+        ))
+        .unwrap();
+
+        assert_eq!(8, gen.lines.len());
+        assert_eq!(
+            vec!["en_US.UTF-8"],
+            gen.enabled()
+                .map(|entry| entry.locale.to_string())
+                .collect::<Vec<_>>()
+        );
+        assert_eq!(
+            vec!["de_DE.UTF-8", "en_GB.UTF-8", "en_GB"],
+            gen.candidates()
+                .map(|entry| entry.locale.to_string())
+                .collect::<Vec<_>>()
+        );
+        assert_eq!("ISO-8859-1", gen.candidates().last().unwrap().charset);
+
+        assert!(gen.enable("de_DE.UTF-8"));
+        assert!(gen.disable("en_US.UTF-8"));
+        assert!(!gen.enable("fr_FR.UTF-8"));
+
+        let mut written = Vec::new();
+        write_gen(&mut written, &gen).unwrap();
+        let written = String::from_utf8(written).unwrap();
+
+        assert!(written.starts_with("# This file lists locales"));
+        assert!(written.ends_with(
+            "#\nde_DE.UTF-8 UTF-8\n# en_GB.UTF-8 UTF-8\n# en_GB ISO-8859-1\n# en_US.UTF-8 UTF-8\n"
+        ));
+
+        assert!(parse_gen(Cursor::new("en_US.UTF-8")).is_err());
+    }
+}
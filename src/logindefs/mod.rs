@@ -0,0 +1,167 @@
+use std::{
+    error, fmt, fs,
+    io::{self, BufRead, BufReader},
+    ops::RangeInclusive,
+};
+
+const UID_MIN: u32 = 1000;
+const UID_MAX: u32 = 60000;
+const SYS_UID_MIN: u32 = 101;
+
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct LoginDefs {
+    pub values: Vec<(String, String)>,
+}
+
+impl LoginDefs {
+    pub fn get(&self, key: &str) -> Option<&str> {
+        self.values
+            .iter()
+            .rev()
+            .find(|(name, _)| name == key)
+            .map(|(_, value)| value.as_str())
+    }
+
+    /// Returns the numeric value of `key`, `None` if it is missing or not a number.
+    pub fn get_number(&self, key: &str) -> Option<u32> {
+        let value = self.get(key)?;
+
+        match value
+            .strip_prefix("0x")
+            .or_else(|| value.strip_prefix("0X"))
+        {
+            Some(hex) => u32::from_str_radix(hex, 16).ok(),
+            None if value.len() > 1 && value.starts_with('0') => {
+                u32::from_str_radix(&value[1..], 8).ok()
+            }
+            None => value.parse().ok(),
+        }
+    }
+
+    /// The `UID_MIN..=UID_MAX` range used for regular users, with shadow-utils defaults.
+    pub fn uid_range(&self) -> RangeInclusive<u32> {
+        self.range("UID", false)
+    }
+
+    /// The `SYS_UID_MIN..=SYS_UID_MAX` range used for system users, with shadow-utils defaults.
+    pub fn sys_uid_range(&self) -> RangeInclusive<u32> {
+        self.range("UID", true)
+    }
+
+    /// The `GID_MIN..=GID_MAX` range used for regular groups, with shadow-utils defaults.
+    pub fn gid_range(&self) -> RangeInclusive<u32> {
+        self.range("GID", false)
+    }
+
+    /// The `SYS_GID_MIN..=SYS_GID_MAX` range used for system groups, with shadow-utils defaults.
+    pub fn sys_gid_range(&self) -> RangeInclusive<u32> {
+        self.range("GID", true)
+    }
+
+    fn range(&self, kind: &str, system: bool) -> RangeInclusive<u32> {
+        let min = self.get_number(&format!("{}_MIN", kind)).unwrap_or(UID_MIN);
+
+        if system {
+            self.get_number(&format!("SYS_{}_MIN", kind))
+                .unwrap_or(SYS_UID_MIN)
+                ..=self
+                    .get_number(&format!("SYS_{}_MAX", kind))
+                    .unwrap_or(min.saturating_sub(1))
+        } else {
+            min..=self.get_number(&format!("{}_MAX", kind)).unwrap_or(UID_MAX)
+        }
+    }
+}
+
+#[derive(Debug)]
+pub enum ParseLoginDefsError {
+    IOError(io::Error),
+}
+
+impl error::Error for ParseLoginDefsError {}
+
+impl fmt::Display for ParseLoginDefsError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::IOError(error) => write!(f, "{}", error),
+        }
+    }
+}
+
+impl From<io::Error> for ParseLoginDefsError {
+    fn from(value: io::Error) -> Self {
+        ParseLoginDefsError::IOError(value)
+    }
+}
+
+///
+/// ```no_run
+/// let defs = unixism::logindefs::parse(std::fs::File::open("/etc/login.defs").unwrap()).unwrap();
+///
+/// println!("{:?}", defs.uid_range());
+/// println!("{:?}", defs.get("ENCRYPT_METHOD"));
+/// ```
+pub fn parse<R>(reader: R) -> Result<LoginDefs, ParseLoginDefsError>
+where
+    R: io::Read,
+{
+    let mut defs = LoginDefs::default();
+
+    for line in BufReader::new(reader).lines() {
+        let line = line?;
+        let line = line.trim();
+
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+
+        let (key, value) = line.split_once(char::is_whitespace).unwrap_or((line, ""));
+
+        defs.values
+            .push((key.to_owned(), value.trim().trim_matches('"').to_owned()));
+    }
+
+    Ok(defs)
+}
+
+///
+/// Same as parse, but parses the `/etc/login.defs` as default.
+///
+/// ```no_run
+/// let defs = unixism::logindefs::parse_default().unwrap();
+/// ```
+pub fn parse_default() -> Result<LoginDefs, ParseLoginDefsError> {
+    parse(fs::File::open("/etc/login.defs")?)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Cursor;
+
+    use super::*;
+
+    #[test]
+    fn it_parse() {
+        let defs = parse(Cursor::new(
+            r#"
+# Min/max values for automatic uid selection in useradd
+UID_MIN			 1000
+UID_MAX			60000
+SYS_UID_MIN		  201
+GID_MIN			 0x3e8
+UMASK		022
+ENCRYPT_METHOD SHA512
+        "#,
+        ))
+        .unwrap();
+
+        assert_eq!(Some("SHA512"), defs.get("ENCRYPT_METHOD"));
+        assert_eq!(Some(0o22), defs.get_number("UMASK"));
+        assert_eq!(1000..=60000, defs.uid_range());
+        assert_eq!(201..=999, defs.sys_uid_range());
+        assert_eq!(1000..=60000, defs.gid_range());
+        assert_eq!(101..=999, defs.sys_gid_range());
+
+        assert_eq!(1000..=60000, LoginDefs::default().uid_range());
+    }
+}
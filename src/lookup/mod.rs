@@ -0,0 +1,253 @@
+use std::{
+    error, fmt, fs,
+    net::{IpAddr, Ipv4Addr, Ipv6Addr},
+};
+
+use crate::{
+    dns::{
+        client::{Client, ClientError},
+        resolv::{self, ParseConfigError},
+    },
+    hosts::{self, Host, ParseHostsError},
+    nsswitch::{self, Action, Criterion, ParseNsswitchError, Service, Source, Status},
+};
+
+#[derive(Debug)]
+pub enum LookupError {
+    Nsswitch(ParseNsswitchError),
+    Hosts(ParseHostsError),
+    Resolv(ParseConfigError),
+}
+
+impl error::Error for LookupError {}
+
+impl fmt::Display for LookupError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Nsswitch(error) => write!(f, "{}", error),
+            Self::Hosts(error) => write!(f, "{}", error),
+            Self::Resolv(error) => write!(f, "{}", error),
+        }
+    }
+}
+
+impl From<ParseNsswitchError> for LookupError {
+    fn from(value: ParseNsswitchError) -> Self {
+        LookupError::Nsswitch(value)
+    }
+}
+
+impl From<ParseHostsError> for LookupError {
+    fn from(value: ParseHostsError) -> Self {
+        LookupError::Hosts(value)
+    }
+}
+
+impl From<ParseConfigError> for LookupError {
+    fn from(value: ParseConfigError) -> Self {
+        LookupError::Resolv(value)
+    }
+}
+
+///
+/// Resolves host names following the `hosts:` database of nsswitch.conf, like getaddrinfo does.
+///
+/// Supports the `files`, `dns` and `myhostname` services, other services are treated as unavailable.
+/// When nsswitch.conf has no `hosts:` line the glibc default `dns [!UNAVAIL=return] files` is used.
+///
+/// ```no_run
+/// use unixism::{dns::resolv, hosts, lookup::Resolver, nsswitch};
+///
+/// let resolver = Resolver::new(
+///     &nsswitch::parse_default().unwrap(),
+///     hosts::parse_default().unwrap().collect(),
+///     resolv::parse_default().unwrap(),
+/// );
+///
+/// for ip in resolver.host("example.com") {
+///     println!("{ip}");
+/// }
+/// ```
+#[derive(Debug)]
+pub struct Resolver {
+    sources: Vec<Source>,
+    hosts: Vec<Host>,
+    client: Client,
+    hostname: Option<String>,
+}
+
+impl Resolver {
+    pub fn new(nsswitch: &nsswitch::Config, hosts: Vec<Host>, resolv: resolv::Config) -> Self {
+        let sources = match nsswitch.get("hosts") {
+            Some(database) => database.sources.clone(),
+            None => vec![
+                Source {
+                    service: Service::Dns,
+                    criteria: vec![Criterion {
+                        negated: true,
+                        status: Status::Unavail,
+                        action: Action::Return,
+                    }],
+                },
+                Source {
+                    service: Service::Files,
+                    criteria: Vec::new(),
+                },
+            ],
+        };
+
+        Self {
+            sources,
+            hosts,
+            client: Client::new(resolv),
+            hostname: fs::read_to_string("/proc/sys/kernel/hostname")
+                .ok()
+                .map(|hostname| hostname.trim().to_owned()),
+        }
+    }
+
+    /// Returns the addresses of `name` found by the sources, empty if none has it.
+    pub fn host(&self, name: &str) -> Vec<IpAddr> {
+        let mut ips = Vec::new();
+
+        for source in &self.sources {
+            let (status, found) = self.query(&source.service, name);
+
+            if status == Status::Success {
+                for ip in found {
+                    if !ips.contains(&ip) {
+                        ips.push(ip);
+                    }
+                }
+            }
+
+            match source.action(status) {
+                Action::Return => return ips,
+                Action::Continue => ips.clear(),
+                Action::Merge => {}
+            }
+        }
+
+        ips
+    }
+
+    fn query(&self, service: &Service, name: &str) -> (Status, Vec<IpAddr>) {
+        let ips = match service {
+            Service::Files => self
+                .hosts
+                .iter()
+                .filter(|host| {
+                    host.names
+                        .iter()
+                        .any(|alias| alias.eq_ignore_ascii_case(name))
+                })
+                .map(|host| host.ip)
+                .collect(),
+            Service::Dns => match self.client.lookup_ip(name) {
+                Ok(ips) => ips,
+                Err(ClientError::ServerFailure(_)) => return (Status::TryAgain, Vec::new()),
+                Err(_) => return (Status::Unavail, Vec::new()),
+            },
+            Service::Myhostname => self.myhostname(name),
+            _ => return (Status::Unavail, Vec::new()),
+        };
+
+        match ips.is_empty() {
+            true => (Status::NotFound, ips),
+            false => (Status::Success, ips),
+        }
+    }
+
+    /// Resolves `localhost` names and the local host name the way nss-myhostname does
+    /// when no addresses are configured.
+    fn myhostname(&self, name: &str) -> Vec<IpAddr> {
+        let name = name.trim_end_matches('.').to_ascii_lowercase();
+
+        if name == "localhost" || name == "localhost.localdomain" || name.ends_with(".localhost") {
+            return vec![Ipv4Addr::LOCALHOST.into(), Ipv6Addr::LOCALHOST.into()];
+        }
+
+        match &self.hostname {
+            Some(hostname) if hostname.eq_ignore_ascii_case(&name) => {
+                vec![
+                    Ipv4Addr::new(127, 0, 0, 2).into(),
+                    Ipv6Addr::LOCALHOST.into(),
+                ]
+            }
+            _ => Vec::new(),
+        }
+    }
+}
+
+///
+/// Resolves `name` using the `/etc/nsswitch.conf`, `/etc/hosts` and `/etc/resolv.conf` files.
+///
+/// ```no_run
+/// for ip in unixism::lookup::host("example.com").unwrap() {
+///     println!("{ip}");
+/// }
+/// ```
+pub fn host(name: &str) -> Result<Vec<IpAddr>, LookupError> {
+    let resolver = Resolver::new(
+        &nsswitch::parse_default()?,
+        hosts::parse_default()?.collect(),
+        resolv::parse_default()?,
+    );
+
+    Ok(resolver.host(name))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Cursor;
+
+    use super::*;
+
+    fn with_nsswitch(nsswitch: &str) -> Resolver {
+        Resolver::new(
+            &nsswitch::parse(Cursor::new(nsswitch)).unwrap(),
+            hosts::parse(Cursor::new(
+                "10.0.0.1 db.lan db\n10.0.0.2 web.lan\n10.0.0.3 DB",
+            ))
+            .unwrap()
+            .collect(),
+            resolv::Config::default(),
+        )
+    }
+
+    #[test]
+    fn it_lookup_host() {
+        let resolver = with_nsswitch("hosts: files myhostname dns");
+
+        assert_eq!(
+            vec![
+                "10.0.0.1".parse::<IpAddr>().unwrap(),
+                "10.0.0.3".parse().unwrap()
+            ],
+            resolver.host("db")
+        );
+        assert_eq!(
+            vec![
+                IpAddr::from(Ipv4Addr::LOCALHOST),
+                IpAddr::from(Ipv6Addr::LOCALHOST)
+            ],
+            resolver.host("app.localhost")
+        );
+        assert!(resolver.host("missing.example").is_empty());
+
+        let resolver = with_nsswitch("hosts: dns [!UNAVAIL=return] files");
+        assert_eq!(
+            vec!["10.0.0.2".parse::<IpAddr>().unwrap()],
+            resolver.host("web.lan")
+        );
+
+        let resolver = with_nsswitch("hosts: dns [UNAVAIL=return] files");
+        assert!(resolver.host("web.lan").is_empty());
+
+        let resolver = with_nsswitch("passwd: files");
+        assert_eq!(
+            vec!["10.0.0.2".parse::<IpAddr>().unwrap()],
+            resolver.host("web.lan")
+        );
+    }
+}
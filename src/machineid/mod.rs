@@ -0,0 +1,173 @@
+use std::{error, fmt, fs, io, str::FromStr};
+
+/// The value systemd writes to `/etc/machine-id` until the first boot is complete.
+pub const UNINITIALIZED: &str = "uninitialized";
+
+///
+/// A 128-bit identifier like the machine id or the boot id.
+/// Parses from 32 hex digits or the hyphenated UUID form, displays as 32 lowercase hex digits.
+///
+/// ```
+/// let id = "4d1e2f5c8b9a4e3f9c7d6b5a4f3e2d1c".parse::<unixism::machineid::Id>().unwrap();
+///
+/// assert_eq!("4d1e2f5c-8b9a-4e3f-9c7d-6b5a4f3e2d1c", id.uuid());
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Id(pub u128);
+
+impl Id {
+    pub fn as_u128(&self) -> u128 {
+        self.0
+    }
+
+    pub fn to_bytes(&self) -> [u8; 16] {
+        self.0.to_be_bytes()
+    }
+
+    /// Formats the id as a hyphenated UUID, the way `/proc/sys/kernel/random/boot_id` shows it.
+    pub fn uuid(&self) -> String {
+        let hex = self.to_string();
+
+        format!(
+            "{}-{}-{}-{}-{}",
+            &hex[..8],
+            &hex[8..12],
+            &hex[12..16],
+            &hex[16..20],
+            &hex[20..]
+        )
+    }
+}
+
+impl FromStr for Id {
+    type Err = ParseMachineIdError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let hex = s.replace('-', "");
+
+        if hex.len() != 32 || !hex.chars().all(|c| c.is_ascii_hexdigit()) {
+            return Err(ParseMachineIdError::InvalidEntry(s.to_owned()));
+        }
+
+        u128::from_str_radix(&hex, 16)
+            .map(Id)
+            .map_err(|_| ParseMachineIdError::InvalidEntry(s.to_owned()))
+    }
+}
+
+impl fmt::Display for Id {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{:032x}", self.0)
+    }
+}
+
+#[derive(Debug)]
+pub enum ParseMachineIdError {
+    InvalidEntry(String),
+    IOError(io::Error),
+}
+
+impl error::Error for ParseMachineIdError {}
+
+impl fmt::Display for ParseMachineIdError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::InvalidEntry(entry) => write!(f, "invalid id: {}", entry),
+            Self::IOError(error) => write!(f, "{}", error),
+        }
+    }
+}
+
+impl From<io::Error> for ParseMachineIdError {
+    fn from(value: io::Error) -> Self {
+        ParseMachineIdError::IOError(value)
+    }
+}
+
+///
+/// Parses a machine id, `None` when it is empty or `uninitialized`, as on the first boot or in an image.
+///
+/// ```no_run
+/// match unixism::machineid::parse(std::fs::File::open("/etc/machine-id").unwrap()).unwrap() {
+///     Some(id) => println!("{}", id),
+///     None => println!("not initialized yet"),
+/// }
+/// ```
+pub fn parse<R>(mut reader: R) -> Result<Option<Id>, ParseMachineIdError>
+where
+    R: io::Read,
+{
+    let mut content = String::new();
+    reader.read_to_string(&mut content)?;
+
+    match content.trim() {
+        "" | UNINITIALIZED => Ok(None),
+        id if id.contains('-') => Err(ParseMachineIdError::InvalidEntry(id.to_owned())),
+        id => id.parse().map(Some),
+    }
+}
+
+///
+/// Same as parse, but parses the `/etc/machine-id` as default.
+///
+/// ```no_run
+/// let id = unixism::machineid::parse_default().unwrap();
+/// ```
+pub fn parse_default() -> Result<Option<Id>, ParseMachineIdError> {
+    parse(fs::File::open("/etc/machine-id")?)
+}
+
+///
+/// Reads the random id the kernel generates on every boot.
+///
+/// ```no_run
+/// let id = unixism::machineid::boot_id().unwrap();
+///
+/// println!("{}", id.uuid());
+/// ```
+pub fn boot_id() -> Result<Id, ParseMachineIdError> {
+    fs::read_to_string("/proc/sys/kernel/random/boot_id")?
+        .trim()
+        .parse()
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Cursor;
+
+    use super::*;
+
+    #[test]
+    fn it_parse() {
+        let id = parse(Cursor::new("4d1e2f5c8b9a4e3f9c7d6b5a4f3e2d1c\n"))
+            .unwrap()
+            .unwrap();
+
+        assert_eq!(0x4d1e2f5c8b9a4e3f9c7d6b5a4f3e2d1c, id.as_u128());
+        assert_eq!("4d1e2f5c8b9a4e3f9c7d6b5a4f3e2d1c", id.to_string());
+        assert_eq!(0x4d, id.to_bytes()[0]);
+        assert_eq!(
+            Some(id),
+            parse(Cursor::new("4D1E2F5C8B9A4E3F9C7D6B5A4F3E2D1C")).unwrap()
+        );
+
+        assert_eq!(None, parse(Cursor::new("uninitialized\n")).unwrap());
+        assert_eq!(None, parse(Cursor::new("")).unwrap());
+
+        assert!(parse(Cursor::new("4d1e2f5c8b9a4e3f")).is_err());
+        assert!(parse(Cursor::new("zd1e2f5c8b9a4e3f9c7d6b5a4f3e2d1c")).is_err());
+        assert!(parse(Cursor::new("+d1e2f5c8b9a4e3f9c7d6b5a4f3e2d1c")).is_err());
+        assert!(parse(Cursor::new("4d1e2f5c-8b9a-4e3f-9c7d-6b5a4f3e2d1c")).is_err());
+    }
+
+    #[test]
+    fn it_parse_uuid() {
+        let id = "0f5d8a2e-3c1b-4a9d-8e7f-6b5c4d3e2f1a"
+            .parse::<Id>()
+            .unwrap();
+
+        assert_eq!("0f5d8a2e3c1b4a9d8e7f6b5c4d3e2f1a", id.to_string());
+        assert_eq!("0f5d8a2e-3c1b-4a9d-8e7f-6b5c4d3e2f1a", id.uuid());
+        assert_eq!("00000000-0000-0000-0000-000000000001", Id(1).uuid());
+    }
+}
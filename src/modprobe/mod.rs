@@ -0,0 +1,447 @@
+use std::{
+    collections::BTreeMap,
+    error, fmt, fs,
+    io::{self, BufRead, BufReader},
+    path::Path,
+    str::FromStr,
+};
+
+use crate::glob::glob;
+
+/// Directories kmod reads its configuration from, the most important first.
+pub const CONFIG_DIRS: [&str; 5] = [
+    "/etc/modprobe.d",
+    "/run/modprobe.d",
+    "/usr/local/lib/modprobe.d",
+    "/usr/lib/modprobe.d",
+    "/lib/modprobe.d",
+];
+
+/// A line of a `modprobe.d` file.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Directive {
+    /// `alias wildcard module`, the wildcard may contain `*` and `?`.
+    Alias {
+        pattern: String,
+        module: String,
+    },
+    Options {
+        module: String,
+        options: Vec<String>,
+    },
+    Install {
+        module: String,
+        command: String,
+    },
+    Remove {
+        module: String,
+        command: String,
+    },
+    Blacklist(String),
+    Softdep {
+        module: String,
+        pre: Vec<String>,
+        post: Vec<String>,
+    },
+    /// A directive this parser doesn't know, kept verbatim.
+    Other(String),
+}
+
+impl FromStr for Directive {
+    type Err = ParseModprobeError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let invalid = || ParseModprobeError::InvalidEntry(s.to_owned());
+
+        let (keyword, rest) = s.split_once(char::is_whitespace).unwrap_or((s, ""));
+        let rest = rest.trim();
+        let fields = rest.split_whitespace().collect::<Vec<_>>();
+        let command = |module: &str| rest[module.len()..].trim().to_owned();
+
+        Ok(match (keyword, &fields[..]) {
+            ("alias", [pattern, module]) => Directive::Alias {
+                pattern: normalize(pattern),
+                module: normalize(module),
+            },
+            ("options", [module, options @ ..]) => Directive::Options {
+                module: normalize(module),
+                options: options.iter().map(|option| option.to_string()).collect(),
+            },
+            ("install", [module, _, ..]) => Directive::Install {
+                module: normalize(module),
+                command: command(module),
+            },
+            ("remove", [module, _, ..]) => Directive::Remove {
+                module: normalize(module),
+                command: command(module),
+            },
+            ("blacklist", [module]) => Directive::Blacklist(normalize(module)),
+            ("softdep", [module, deps @ ..]) => {
+                let (mut pre, mut post) = (Vec::new(), Vec::new());
+                let mut target = None;
+
+                for dep in deps {
+                    match *dep {
+                        "pre:" => target = Some(&mut pre),
+                        "post:" => target = Some(&mut post),
+                        dep => target.as_mut().ok_or_else(invalid)?.push(normalize(dep)),
+                    }
+                }
+
+                Directive::Softdep {
+                    module: normalize(module),
+                    pre,
+                    post,
+                }
+            }
+            ("alias" | "options" | "install" | "remove" | "blacklist" | "softdep", _) => {
+                return Err(invalid())
+            }
+            _ => Directive::Other(s.to_owned()),
+        })
+    }
+}
+
+impl fmt::Display for Directive {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Directive::Alias { pattern, module } => write!(f, "alias {} {}", pattern, module),
+            Directive::Options { module, options } => {
+                write!(f, "options {}", module)?;
+
+                for option in options {
+                    write!(f, " {}", option)?;
+                }
+
+                Ok(())
+            }
+            Directive::Install { module, command } => write!(f, "install {} {}", module, command),
+            Directive::Remove { module, command } => write!(f, "remove {} {}", module, command),
+            Directive::Blacklist(module) => write!(f, "blacklist {}", module),
+            Directive::Softdep { module, pre, post } => {
+                write!(f, "softdep {}", module)?;
+
+                if !pre.is_empty() {
+                    write!(f, " pre: {}", pre.join(" "))?;
+                }
+
+                if !post.is_empty() {
+                    write!(f, " post: {}", post.join(" "))?;
+                }
+
+                Ok(())
+            }
+            Directive::Other(line) => write!(f, "{}", line),
+        }
+    }
+}
+
+/// kmod treats `-` and `_` in module names as the same character.
+fn normalize(name: &str) -> String {
+    name.replace('-', "_")
+}
+
+/// Modules to load before and after a module.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct Softdep {
+    pub pre: Vec<String>,
+    pub post: Vec<String>,
+}
+
+/// Everything the configuration says about one module.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct ModuleConfig {
+    pub name: String,
+    /// Options of every `options` line, in order, so later values override earlier ones.
+    pub options: Vec<String>,
+    /// Alias patterns resolving to the module.
+    pub aliases: Vec<String>,
+    /// Whether the aliases of the module are ignored, loading it by name still works.
+    pub blacklisted: bool,
+    /// The first `install` command, run instead of loading the module.
+    pub install: Option<String>,
+    /// The first `remove` command, run instead of unloading the module.
+    pub remove: Option<String>,
+    /// The first `softdep` of the module.
+    pub softdep: Option<Softdep>,
+}
+
+///
+/// The merged `modprobe.d` configuration.
+///
+/// ```no_run
+/// let config = unixism::modprobe::load().unwrap();
+///
+/// let nvidia = config.module("nvidia");
+/// println!("{:?} blacklisted: {}", nvidia.options, nvidia.blacklisted);
+/// ```
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct Config {
+    pub directives: Vec<Directive>,
+}
+
+impl Config {
+    pub fn module(&self, name: &str) -> ModuleConfig {
+        let name = normalize(name);
+        let mut config = ModuleConfig {
+            name: name.clone(),
+            ..ModuleConfig::default()
+        };
+
+        for directive in &self.directives {
+            match directive {
+                Directive::Alias { pattern, module } if *module == name => {
+                    config.aliases.push(pattern.clone())
+                }
+                Directive::Options { module, options } if *module == name => {
+                    config.options.extend(options.iter().cloned())
+                }
+                Directive::Install { module, command } if *module == name => {
+                    config.install.get_or_insert_with(|| command.clone());
+                }
+                Directive::Remove { module, command } if *module == name => {
+                    config.remove.get_or_insert_with(|| command.clone());
+                }
+                Directive::Blacklist(module) if *module == name => config.blacklisted = true,
+                Directive::Softdep { module, pre, post } if *module == name => {
+                    config.softdep.get_or_insert_with(|| Softdep {
+                        pre: pre.clone(),
+                        post: post.clone(),
+                    });
+                }
+                _ => {}
+            }
+        }
+
+        config
+    }
+
+    /// Every module the configuration mentions, by name.
+    pub fn modules(&self) -> BTreeMap<String, ModuleConfig> {
+        self.directives
+            .iter()
+            .filter_map(|directive| match directive {
+                Directive::Alias { module, .. }
+                | Directive::Options { module, .. }
+                | Directive::Install { module, .. }
+                | Directive::Remove { module, .. }
+                | Directive::Blacklist(module)
+                | Directive::Softdep { module, .. } => Some(module),
+                Directive::Other(_) => None,
+            })
+            .map(|module| (module.clone(), self.module(module)))
+            .collect()
+    }
+
+    /// Returns the modules an alias like `pci:v00008086d*` or `char-major-10-*` resolves to.
+    pub fn resolve(&self, alias: &str) -> Vec<&str> {
+        let alias = normalize(alias);
+
+        self.directives
+            .iter()
+            .filter_map(|directive| match directive {
+                Directive::Alias { pattern, module } if glob(pattern, &alias) => {
+                    Some(module.as_str())
+                }
+                _ => None,
+            })
+            .collect()
+    }
+}
+
+impl FromIterator<Directive> for Config {
+    fn from_iter<I>(iter: I) -> Self
+    where
+        I: IntoIterator<Item = Directive>,
+    {
+        Config {
+            directives: iter.into_iter().collect(),
+        }
+    }
+}
+
+#[derive(Debug)]
+pub enum ParseModprobeError {
+    InvalidEntry(String),
+    IOError(io::Error),
+}
+
+impl error::Error for ParseModprobeError {}
+
+impl fmt::Display for ParseModprobeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::InvalidEntry(entry) => write!(f, "invalid directive: {}", entry),
+            Self::IOError(error) => write!(f, "{}", error),
+        }
+    }
+}
+
+impl From<io::Error> for ParseModprobeError {
+    fn from(value: io::Error) -> Self {
+        ParseModprobeError::IOError(value)
+    }
+}
+
+///
+/// Parses a `modprobe.d` file, joining the lines ending with a backslash.
+///
+/// ```no_run
+/// let directives = unixism::modprobe::parse(std::fs::File::open("/etc/modprobe.d/blacklist.conf").unwrap()).unwrap();
+///
+/// for directive in directives {
+///     println!("{}", directive);
+/// }
+/// ```
+pub fn parse<R>(reader: R) -> Result<impl Iterator<Item = Directive>, ParseModprobeError>
+where
+    R: io::Read,
+{
+    let mut directives = Vec::new();
+    let mut pending = String::new();
+
+    for line in BufReader::new(reader).lines() {
+        let line = line?;
+
+        if let Some(line) = line.strip_suffix('\\') {
+            pending.push_str(line);
+            pending.push(' ');
+            continue;
+        }
+
+        pending.push_str(&line);
+        let line = std::mem::take(&mut pending);
+        let line = line.trim();
+
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+
+        directives.push(line.parse::<Directive>()?);
+    }
+
+    Ok(directives.into_iter())
+}
+
+///
+/// Reads the `*.conf` files of `dirs` in file name order, a file in an earlier directory
+/// overriding the file with the same name in later ones.
+///
+/// ```no_run
+/// let config = unixism::modprobe::load_at(&["/etc/modprobe.d", "/lib/modprobe.d"]).unwrap();
+/// ```
+pub fn load_at<P>(dirs: &[P]) -> Result<Config, ParseModprobeError>
+where
+    P: AsRef<Path>,
+{
+    let mut directives = Vec::new();
+
+    for path in crate::dropin::files(dirs, ".conf")? {
+        directives.extend(parse(fs::File::open(path)?)?);
+    }
+
+    Ok(Config { directives })
+}
+
+///
+/// Same as load_at, but reads the standard `modprobe.d` directories.
+///
+/// ```no_run
+/// let config = unixism::modprobe::load().unwrap();
+/// ```
+pub fn load() -> Result<Config, ParseModprobeError> {
+    load_at(&CONFIG_DIRS)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Cursor;
+
+    use super::*;
+    use crate::tempdir::TempDir;
+
+    #[test]
+    fn it_parse() {
+        let config = parse(Cursor::new(
+            r#"
+# nvidia
+blacklist nouveau
+options nvidia-drm modeset=1
+options nvidia_drm fbdev=1
+alias pci:v000010DEd*sv*sd*bc03sc*i* nvidia
+install usb-storage /bin/true
+install usb_storage /sbin/modprobe --ignore-install usb-storage
+softdep nvidia pre: i2c_core \
+    post: nvidia-drm
+        "#,
+        ))
+        .unwrap()
+        .collect::<Config>();
+
+        assert_eq!(7, config.directives.len());
+        assert_eq!(
+            Directive::Softdep {
+                module: "nvidia".to_owned(),
+                pre: vec!["i2c_core".to_owned()],
+                post: vec!["nvidia_drm".to_owned()],
+            },
+            config.directives[6]
+        );
+        assert_eq!(
+            "options nvidia_drm modeset=1",
+            config.directives[1].to_string()
+        );
+
+        let drm = config.module("nvidia-drm");
+        assert_eq!(vec!["modeset=1", "fbdev=1"], drm.options);
+        assert!(!drm.blacklisted);
+
+        assert!(config.module("nouveau").blacklisted);
+        assert_eq!(
+            Some("/bin/true"),
+            config.module("usb_storage").install.as_deref()
+        );
+        assert_eq!(
+            vec!["pci:v000010DEd*sv*sd*bc03sc*i*".to_owned()],
+            config.module("nvidia").aliases
+        );
+        assert_eq!(
+            vec!["nvidia"],
+            config.resolve("pci:v000010DEd00002204sv00001458sd0000403Bbc03sc00i00")
+        );
+        assert!(config.resolve("pci:v00008086d00001234").is_empty());
+        assert_eq!(4, config.modules().len());
+
+        assert!(parse(Cursor::new("blacklist")).is_err());
+        assert!(parse(Cursor::new("alias only-pattern")).is_err());
+        assert!(parse(Cursor::new("softdep nvidia i2c_core")).is_err());
+        assert_eq!(
+            Directive::Other("weakdep nvidia i2c_core".to_owned()),
+            parse(Cursor::new("weakdep nvidia i2c_core"))
+                .unwrap()
+                .next()
+                .unwrap()
+        );
+    }
+
+    #[test]
+    fn it_load_at() {
+        let root = TempDir::new("modprobe");
+        let (etc, lib) = (root.join("etc"), root.join("lib"));
+        fs::create_dir_all(&etc).unwrap();
+        fs::create_dir_all(&lib).unwrap();
+
+        fs::write(lib.join("kvm.conf"), "options kvm_intel nested=0\n").unwrap();
+        fs::write(lib.join("fbdev.conf"), "blacklist vesafb\n").unwrap();
+        fs::write(etc.join("kvm.conf"), "options kvm_intel nested=1\n").unwrap();
+        fs::write(etc.join("local.conf"), "options kvm_intel enable_apicv=0\n").unwrap();
+
+        let config = load_at(&[&etc, &lib]).unwrap();
+
+        assert_eq!(
+            vec!["nested=1", "enable_apicv=0"],
+            config.module("kvm_intel").options
+        );
+        assert!(config.module("vesafb").blacklisted);
+    }
+}
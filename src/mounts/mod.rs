@@ -0,0 +1,381 @@
+use std::{
+    error, fmt, fs,
+    io::{self, BufRead, BufReader},
+    num::ParseIntError,
+    str::FromStr,
+};
+
+use crate::fstab::{self, FstabEntry, MountOption, MountOptions, ParseFstabError};
+
+/// Propagation and other optional fields of a mountinfo line.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum OptionalField {
+    Shared(u32),
+    Master(u32),
+    PropagateFrom(u32),
+    Unbindable,
+    Other(String),
+}
+
+impl FromStr for OptionalField {
+    type Err = ParseMountsError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Ok(match s.split_once(':') {
+            Some(("shared", id)) => OptionalField::Shared(id.parse()?),
+            Some(("master", id)) => OptionalField::Master(id.parse()?),
+            Some(("propagate_from", id)) => OptionalField::PropagateFrom(id.parse()?),
+            None if s == "unbindable" => OptionalField::Unbindable,
+            _ => OptionalField::Other(s.to_owned()),
+        })
+    }
+}
+
+/// A line of `/proc/<pid>/mountinfo`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MountInfo {
+    pub mount_id: u32,
+    pub parent_id: u32,
+    pub major: u32,
+    pub minor: u32,
+    pub root: String,
+    pub mount_point: String,
+    /// Per-mount options, like `rw,nosuid,relatime`.
+    pub options: MountOptions,
+    pub optional_fields: Vec<OptionalField>,
+    pub fstype: String,
+    pub source: String,
+    /// Per-superblock options, like `rw,errors=remount-ro`.
+    pub super_options: MountOptions,
+}
+
+impl FromStr for MountInfo {
+    type Err = ParseMountsError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let invalid = || ParseMountsError::InvalidEntry(s.to_owned());
+
+        let (head, tail) = s.split_once(" - ").ok_or_else(invalid)?;
+        let mut head = head.split_whitespace();
+        let mut tail = tail.split_whitespace();
+
+        let mount_id = head.next().ok_or_else(invalid)?.parse()?;
+        let parent_id = head.next().ok_or_else(invalid)?.parse()?;
+        let (major, minor) = head
+            .next()
+            .and_then(|device| device.split_once(':'))
+            .ok_or_else(invalid)?;
+        let root = fstab::unescape(head.next().ok_or_else(invalid)?);
+        let mount_point = fstab::unescape(head.next().ok_or_else(invalid)?);
+        let options = MountOptions::from(head.next().ok_or_else(invalid)?);
+        let optional_fields = head
+            .map(str::parse)
+            .collect::<Result<Vec<OptionalField>, ParseMountsError>>()?;
+
+        Ok(MountInfo {
+            mount_id,
+            parent_id,
+            major: major.parse()?,
+            minor: minor.parse()?,
+            root,
+            mount_point,
+            options,
+            optional_fields,
+            fstype: tail.next().ok_or_else(invalid)?.to_owned(),
+            source: fstab::unescape(tail.next().ok_or_else(invalid)?),
+            super_options: MountOptions::from(tail.next().unwrap_or_default()),
+        })
+    }
+}
+
+#[derive(Debug)]
+pub enum ParseMountsError {
+    InvalidEntry(String),
+    ParseIntError(ParseIntError),
+    IOError(io::Error),
+}
+
+impl error::Error for ParseMountsError {}
+
+impl fmt::Display for ParseMountsError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::InvalidEntry(entry) => write!(f, "invalid mountinfo entry: {}", entry),
+            Self::ParseIntError(error) => write!(f, "{}", error),
+            Self::IOError(error) => write!(f, "{}", error),
+        }
+    }
+}
+
+impl From<ParseIntError> for ParseMountsError {
+    fn from(value: ParseIntError) -> Self {
+        ParseMountsError::ParseIntError(value)
+    }
+}
+
+impl From<io::Error> for ParseMountsError {
+    fn from(value: io::Error) -> Self {
+        ParseMountsError::IOError(value)
+    }
+}
+
+///
+/// ```no_run
+/// let mounts = unixism::mounts::parse_mountinfo(std::fs::File::open("/proc/self/mountinfo").unwrap()).unwrap();
+///
+/// for mount in mounts {
+///     println!("{} on {} type {} ({:?})", mount.source, mount.mount_point, mount.fstype, mount.optional_fields);
+/// }
+/// ```
+pub fn parse_mountinfo<R>(reader: R) -> Result<impl Iterator<Item = MountInfo>, ParseMountsError>
+where
+    R: io::Read,
+{
+    let mut mounts = Vec::new();
+
+    for line in BufReader::new(reader).lines() {
+        let line = line?;
+
+        if line.trim().is_empty() {
+            continue;
+        }
+
+        mounts.push(line.parse::<MountInfo>()?);
+    }
+
+    Ok(mounts.into_iter())
+}
+
+///
+/// Same as parse_mountinfo, but parses the `/proc/self/mountinfo` as default.
+///
+/// ```no_run
+/// let mounts = unixism::mounts::parse_mountinfo_default().unwrap();
+/// ```
+pub fn parse_mountinfo_default() -> Result<impl Iterator<Item = MountInfo>, ParseMountsError> {
+    parse_mountinfo(fs::File::open("/proc/self/mountinfo")?)
+}
+
+///
+/// Parses the `/proc/mounts` format, which is the same as fstab.
+///
+/// ```no_run
+/// let mounts = unixism::mounts::parse_mounts(std::fs::File::open("/proc/mounts").unwrap()).unwrap();
+///
+/// for mount in mounts {
+///     println!("{} on {} type {}", mount.spec, mount.file, mount.vfstype);
+/// }
+/// ```
+pub fn parse_mounts<R>(reader: R) -> Result<impl Iterator<Item = FstabEntry>, ParseFstabError>
+where
+    R: io::Read,
+{
+    fstab::parse(reader)
+}
+
+///
+/// Same as parse_mounts, but parses the `/proc/mounts` as default.
+///
+/// ```no_run
+/// let mounts = unixism::mounts::parse_mounts_default().unwrap();
+/// ```
+pub fn parse_mounts_default() -> Result<impl Iterator<Item = FstabEntry>, ParseFstabError> {
+    parse_mounts(fs::File::open("/proc/mounts")?)
+}
+
+/// A difference between the fstab and the live mount table.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Mismatch {
+    /// Declared in fstab but not mounted.
+    NotMounted(FstabEntry),
+    /// Mounted but not declared in fstab.
+    NotDeclared(MountInfo),
+    /// Mounted with flags other than declared in fstab.
+    OptionsDiffer { entry: FstabEntry, mount: MountInfo },
+}
+
+impl fmt::Display for Mismatch {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::NotMounted(entry) => write!(f, "{} is declared but not mounted", entry.file),
+            Self::NotDeclared(mount) => {
+                write!(f, "{} is mounted but not declared", mount.mount_point)
+            }
+            Self::OptionsDiffer { entry, mount } => write!(
+                f,
+                "{} is mounted with {} instead of {}",
+                mount.mount_point, mount.options, entry.mntops
+            ),
+        }
+    }
+}
+
+/// Per-mount flags shown in mountinfo, the atime ones are compared only when fstab sets them.
+const COMPARED_FLAGS: libc::c_ulong =
+    libc::MS_RDONLY | libc::MS_NOSUID | libc::MS_NODEV | libc::MS_NOEXEC | libc::MS_NODIRATIME;
+
+const ATIME_FLAGS: libc::c_ulong = libc::MS_NOATIME | libc::MS_RELATIME | libc::MS_STRICTATIME;
+
+///
+/// Compares the fstab against the mount table, matching entries by mount point.
+/// Swap and `noauto` entries are not expected to be mounted, and when a mount point
+/// is mounted over several times only the topmost mount is considered.
+/// Every mount absent from fstab is reported, including pseudo filesystems like `proc`,
+/// so callers usually filter `NotDeclared` by the filesystem type.
+///
+/// ```no_run
+/// use unixism::{fstab, mounts};
+///
+/// let fstab = fstab::parse_default().unwrap().collect::<Vec<_>>();
+/// let mountinfo = mounts::parse_mountinfo_default().unwrap().collect::<Vec<_>>();
+///
+/// for mismatch in mounts::reconcile(&fstab, &mountinfo) {
+///     println!("{mismatch}");
+/// }
+/// ```
+pub fn reconcile(fstab: &[FstabEntry], mountinfo: &[MountInfo]) -> Vec<Mismatch> {
+    let mut mismatches = Vec::new();
+
+    let mounted = |file: &str| {
+        mountinfo
+            .iter()
+            .rev()
+            .find(|mount| normalize(&mount.mount_point) == normalize(file))
+    };
+
+    for entry in fstab {
+        if entry.vfstype == "swap" || entry.mntops.contains(&MountOption::Noauto) {
+            continue;
+        }
+
+        match mounted(&entry.file) {
+            None => mismatches.push(Mismatch::NotMounted(entry.clone())),
+            Some(mount) => {
+                let mut mask = COMPARED_FLAGS;
+                if entry.mntops.flags() & ATIME_FLAGS != 0 {
+                    mask |= ATIME_FLAGS;
+                }
+
+                if entry.mntops.flags() & mask != mount.options.flags() & mask {
+                    mismatches.push(Mismatch::OptionsDiffer {
+                        entry: entry.clone(),
+                        mount: mount.clone(),
+                    });
+                }
+            }
+        }
+    }
+
+    for mount in mountinfo {
+        if !fstab
+            .iter()
+            .any(|entry| normalize(&entry.file) == normalize(&mount.mount_point))
+        {
+            mismatches.push(Mismatch::NotDeclared(mount.clone()));
+        }
+    }
+
+    mismatches
+}
+
+fn normalize(path: &str) -> &str {
+    match path.trim_end_matches('/') {
+        "" => "/",
+        trimmed => trimmed,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Cursor;
+
+    use super::*;
+
+    #[test]
+    fn it_parse_mountinfo() {
+        let mounts = parse_mountinfo(Cursor::new(
+            r#"
+22 28 0:21 / /proc rw,nosuid,nodev,noexec,relatime shared:12 - proc proc rw
+28 1 8:2 / / rw,relatime shared:1 master:3 - ext4 /dev/sda2 rw,errors=remount-ro
+65 28 0:40 /data /mnt/my\040data rw,relatime unbindable - btrfs /dev/sdb1 rw,subvol=/data
+        "#,
+        ))
+        .unwrap()
+        .collect::<Vec<_>>();
+
+        assert_eq!(3, mounts.len());
+        assert_eq!(
+            MountInfo {
+                mount_id: 28,
+                parent_id: 1,
+                major: 8,
+                minor: 2,
+                root: "/".to_owned(),
+                mount_point: "/".to_owned(),
+                options: MountOptions::from("rw,relatime"),
+                optional_fields: vec![OptionalField::Shared(1), OptionalField::Master(3)],
+                fstype: "ext4".to_owned(),
+                source: "/dev/sda2".to_owned(),
+                super_options: MountOptions::from("rw,errors=remount-ro"),
+            },
+            mounts[1]
+        );
+        assert_eq!("/mnt/my data", mounts[2].mount_point);
+        assert_eq!("/data", mounts[2].root);
+        assert_eq!(vec![OptionalField::Unbindable], mounts[2].optional_fields);
+
+        assert!(parse_mountinfo(Cursor::new("22 28 0:21 / /proc rw")).is_err());
+    }
+
+    #[test]
+    fn it_parse_mounts() {
+        let mounts = parse_mounts(Cursor::new(
+            "proc /proc proc rw,nosuid,nodev,noexec,relatime 0 0\n/dev/sda2 / ext4 rw,relatime 0 0\n",
+        ))
+        .unwrap()
+        .collect::<Vec<_>>();
+
+        assert_eq!(2, mounts.len());
+        assert_eq!(fstab::Spec::Device("/dev/sda2".to_owned()), mounts[1].spec);
+        assert_eq!("ext4", mounts[1].vfstype);
+    }
+
+    #[test]
+    fn it_reconcile() {
+        let fstab = fstab::parse(Cursor::new(
+            r#"
+/dev/sda2  /          ext4   defaults          0 1
+/dev/sda1  /boot/     vfat   ro,noatime        0 2
+/dev/sdb1  /srv       xfs    nosuid,nodev      0 2
+/dev/sdc1  /backup    ext4   noauto            0 0
+/swapfile  none       swap   sw                0 0
+        "#,
+        ))
+        .unwrap()
+        .collect::<Vec<_>>();
+
+        let mountinfo = parse_mountinfo(Cursor::new(
+            r#"
+28 1 8:2 / / rw,relatime shared:1 - ext4 /dev/sda2 rw
+29 28 8:1 / /boot rw,relatime shared:2 - vfat /dev/sda1 rw
+22 28 0:21 / /proc rw,nosuid,nodev,noexec,relatime shared:12 - proc proc rw
+        "#,
+        ))
+        .unwrap()
+        .collect::<Vec<_>>();
+
+        let mismatches = reconcile(&fstab, &mountinfo);
+
+        assert_eq!(3, mismatches.len());
+        assert!(matches!(
+            &mismatches[0],
+            Mismatch::OptionsDiffer { entry, mount } if entry.file == "/boot/" && mount.mount_point == "/boot"
+        ));
+        assert_eq!(Mismatch::NotMounted(fstab[2].clone()), mismatches[1]);
+        assert_eq!(Mismatch::NotDeclared(mountinfo[2].clone()), mismatches[2]);
+        assert_eq!(
+            "/proc is mounted but not declared",
+            mismatches[2].to_string()
+        );
+    }
+}
@@ -0,0 +1,378 @@
+use std::{
+    collections::HashMap,
+    error, fmt, fs,
+    io::{self, BufRead, BufReader},
+    str::FromStr,
+};
+
+/// A field of a netgroup triple.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Field {
+    /// An empty field, matching any value.
+    Wildcard,
+    /// A `-` field, matching no value.
+    Nothing,
+    Value(String),
+}
+
+impl Field {
+    fn new(s: &str) -> Self {
+        match s.trim() {
+            "" => Field::Wildcard,
+            "-" => Field::Nothing,
+            value => Field::Value(value.to_owned()),
+        }
+    }
+
+    /// Whether the field allows `value`, `None` being a value that is not asked for.
+    pub fn matches(&self, value: Option<&str>) -> bool {
+        match (self, value) {
+            (_, None) | (Field::Wildcard, _) => true,
+            (Field::Nothing, Some(_)) => false,
+            (Field::Value(field), Some(value)) => field == value,
+        }
+    }
+}
+
+impl fmt::Display for Field {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Field::Wildcard => Ok(()),
+            Field::Nothing => write!(f, "-"),
+            Field::Value(value) => write!(f, "{}", value),
+        }
+    }
+}
+
+/// A `(host,user,domain)` triple.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Triple {
+    pub host: Field,
+    pub user: Field,
+    pub domain: Field,
+}
+
+impl fmt::Display for Triple {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "({},{},{})", self.host, self.user, self.domain)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Member {
+    Triple(Triple),
+    /// A reference to another netgroup.
+    Group(String),
+}
+
+/// A line of `/etc/netgroup`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Netgroup {
+    pub name: String,
+    pub members: Vec<Member>,
+}
+
+impl FromStr for Netgroup {
+    type Err = ParseNetgroupError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let invalid = || ParseNetgroupError::InvalidEntry(s.to_owned());
+
+        let s = s.trim();
+        let end = s.find(char::is_whitespace).unwrap_or(s.len());
+        let mut netgroup = Netgroup {
+            name: s[..end].to_owned(),
+            members: Vec::new(),
+        };
+
+        let mut rest = s[end..].trim_start();
+        while !rest.is_empty() {
+            if let Some(triple) = rest.strip_prefix('(') {
+                let (triple, tail) = triple.split_once(')').ok_or_else(invalid)?;
+                let fields = triple.split(',').collect::<Vec<_>>();
+
+                let [host, user, domain] = fields[..] else {
+                    return Err(invalid());
+                };
+
+                netgroup.members.push(Member::Triple(Triple {
+                    host: Field::new(host),
+                    user: Field::new(user),
+                    domain: Field::new(domain),
+                }));
+                rest = tail.trim_start();
+            } else {
+                let end = rest.find(char::is_whitespace).unwrap_or(rest.len());
+
+                netgroup.members.push(Member::Group(rest[..end].to_owned()));
+                rest = rest[end..].trim_start();
+            }
+        }
+
+        Ok(netgroup)
+    }
+}
+
+impl fmt::Display for Netgroup {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.name)?;
+
+        for member in &self.members {
+            match member {
+                Member::Triple(triple) => write!(f, " {}", triple)?,
+                Member::Group(group) => write!(f, " {}", group)?,
+            }
+        }
+
+        Ok(())
+    }
+}
+
+///
+/// Netgroups by name, expanding nested groups into their triples.
+///
+/// ```no_run
+/// let netgroups = unixism::netgroup::parse_default().unwrap().collect::<unixism::netgroup::Netgroups>();
+///
+/// for triple in netgroups.expand("trusted").unwrap() {
+///     println!("{triple}");
+/// }
+/// ```
+#[derive(Debug, Default, Clone)]
+pub struct Netgroups {
+    netgroups: HashMap<String, Netgroup>,
+}
+
+impl Netgroups {
+    pub fn get(&self, name: &str) -> Option<&Netgroup> {
+        self.netgroups.get(name)
+    }
+
+    ///
+    /// Returns the triples of the netgroup and of the groups nested in it, without duplicates.
+    /// References to unknown groups are skipped, like libc does, and a group that
+    /// includes itself through its members is reported as an error.
+    pub fn expand(&self, name: &str) -> Result<Vec<Triple>, ParseNetgroupError> {
+        let mut triples = Vec::new();
+        self.expand_into(name, &mut Vec::new(), &mut triples)?;
+
+        Ok(triples)
+    }
+
+    fn expand_into<'a>(
+        &'a self,
+        name: &'a str,
+        path: &mut Vec<&'a str>,
+        triples: &mut Vec<Triple>,
+    ) -> Result<(), ParseNetgroupError> {
+        if path.contains(&name) {
+            let mut cycle = path
+                .iter()
+                .map(|group| group.to_string())
+                .collect::<Vec<_>>();
+            cycle.push(name.to_owned());
+
+            return Err(ParseNetgroupError::Cycle(cycle));
+        }
+
+        let Some(netgroup) = self.netgroups.get(name) else {
+            return Ok(());
+        };
+
+        path.push(name);
+
+        for member in &netgroup.members {
+            match member {
+                Member::Triple(triple) => {
+                    if !triples.contains(triple) {
+                        triples.push(triple.clone());
+                    }
+                }
+                Member::Group(group) => self.expand_into(group, path, triples)?,
+            }
+        }
+
+        path.pop();
+
+        Ok(())
+    }
+
+    /// Whether the netgroup contains the given host, user and domain, like `innetgr(3)`.
+    pub fn contains(
+        &self,
+        name: &str,
+        host: Option<&str>,
+        user: Option<&str>,
+        domain: Option<&str>,
+    ) -> Result<bool, ParseNetgroupError> {
+        Ok(self.expand(name)?.iter().any(|triple| {
+            triple.host.matches(host) && triple.user.matches(user) && triple.domain.matches(domain)
+        }))
+    }
+}
+
+impl FromIterator<Netgroup> for Netgroups {
+    fn from_iter<I>(iter: I) -> Self
+    where
+        I: IntoIterator<Item = Netgroup>,
+    {
+        let mut netgroups = Netgroups::default();
+
+        for netgroup in iter {
+            netgroups
+                .netgroups
+                .entry(netgroup.name.clone())
+                .or_insert(netgroup);
+        }
+
+        netgroups
+    }
+}
+
+#[derive(Debug)]
+pub enum ParseNetgroupError {
+    InvalidEntry(String),
+    Cycle(Vec<String>),
+    IOError(io::Error),
+}
+
+impl error::Error for ParseNetgroupError {}
+
+impl fmt::Display for ParseNetgroupError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::InvalidEntry(entry) => write!(f, "invalid netgroup: {}", entry),
+            Self::Cycle(groups) => write!(f, "netgroup cycle: {}", groups.join(" -> ")),
+            Self::IOError(error) => write!(f, "{}", error),
+        }
+    }
+}
+
+impl From<io::Error> for ParseNetgroupError {
+    fn from(value: io::Error) -> Self {
+        ParseNetgroupError::IOError(value)
+    }
+}
+
+///
+/// Lines ending with a backslash are joined with the next one.
+///
+/// ```no_run
+/// let netgroups = unixism::netgroup::parse(std::fs::File::open("/etc/netgroup").unwrap()).unwrap();
+///
+/// for netgroup in netgroups {
+///     println!("{netgroup}");
+/// }
+/// ```
+pub fn parse<R>(reader: R) -> Result<impl Iterator<Item = Netgroup>, ParseNetgroupError>
+where
+    R: io::Read,
+{
+    let mut netgroups = Vec::new();
+    let mut pending = String::new();
+
+    for line in BufReader::new(reader).lines() {
+        let line = line?;
+
+        if let Some(line) = line.strip_suffix('\\') {
+            pending.push_str(line);
+            pending.push(' ');
+            continue;
+        }
+
+        pending.push_str(&line);
+        let line = std::mem::take(&mut pending);
+        let line = line.trim();
+
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+
+        netgroups.push(line.parse::<Netgroup>()?);
+    }
+
+    Ok(netgroups.into_iter())
+}
+
+///
+/// Same as parse, but parses the `/etc/netgroup` as default.
+///
+/// ```no_run
+/// let netgroups = unixism::netgroup::parse_default().unwrap();
+/// ```
+pub fn parse_default() -> Result<impl Iterator<Item = Netgroup>, ParseNetgroupError> {
+    parse(fs::File::open("/etc/netgroup")?)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Cursor;
+
+    use super::*;
+
+    #[test]
+    fn it_parse() {
+        let netgroups = parse(Cursor::new(
+            r#"
+# /etc/netgroup
+admins   (-,alice,) (-,bob,example.com)
+servers  (web1,-,) (db1,-,) \
+         (db2,-,)
+trusted  admins servers (laptop,alice,) missing
+loop     trusted again
+again    loop
+        "#,
+        ))
+        .unwrap()
+        .collect::<Vec<_>>();
+
+        assert_eq!(5, netgroups.len());
+        assert_eq!(
+            Netgroup {
+                name: "admins".to_owned(),
+                members: vec![
+                    Member::Triple(Triple {
+                        host: Field::Nothing,
+                        user: Field::Value("alice".to_owned()),
+                        domain: Field::Wildcard,
+                    }),
+                    Member::Triple(Triple {
+                        host: Field::Nothing,
+                        user: Field::Value("bob".to_owned()),
+                        domain: Field::Value("example.com".to_owned()),
+                    }),
+                ],
+            },
+            netgroups[0]
+        );
+        assert_eq!(
+            "servers (web1,-,) (db1,-,) (db2,-,)",
+            netgroups[1].to_string()
+        );
+
+        let netgroups = netgroups.into_iter().collect::<Netgroups>();
+
+        assert_eq!(6, netgroups.expand("trusted").unwrap().len());
+        assert!(netgroups.expand("unknown").unwrap().is_empty());
+        assert!(matches!(
+            netgroups.expand("loop"),
+            Err(ParseNetgroupError::Cycle(groups)) if groups == ["loop", "again", "loop"]
+        ));
+
+        assert!(netgroups
+            .contains("trusted", Some("db2"), None, None)
+            .unwrap());
+        assert!(netgroups
+            .contains("trusted", None, Some("bob"), Some("example.com"))
+            .unwrap());
+        assert!(!netgroups
+            .contains("trusted", None, Some("bob"), Some("corp"))
+            .unwrap());
+        assert!(!netgroups
+            .contains("admins", Some("web1"), None, None)
+            .unwrap());
+
+        assert!(parse(Cursor::new("admins (-,alice)")).is_err());
+        assert!(parse(Cursor::new("admins (-,alice,")).is_err());
+    }
+}
@@ -0,0 +1,620 @@
+use std::{
+    error, fmt, fs,
+    io::{self, BufRead, BufReader},
+    net::IpAddr,
+    path::Path,
+};
+
+/// A `[group]` of a keyfile with its keys in file order.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct Group {
+    pub name: String,
+    pub entries: Vec<(String, String)>,
+}
+
+impl Group {
+    pub fn get(&self, key: &str) -> Option<&str> {
+        self.entries
+            .iter()
+            .rev()
+            .find(|(name, _)| name == key)
+            .map(|(_, value)| value.as_str())
+    }
+}
+
+/// The `method` of the `[ipv4]` and `[ipv6]` groups.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Method {
+    Auto,
+    Dhcp,
+    Manual,
+    LinkLocal,
+    Shared,
+    Disabled,
+    Ignore,
+    Other(String),
+}
+
+impl From<&str> for Method {
+    fn from(s: &str) -> Self {
+        match s {
+            "auto" => Method::Auto,
+            "dhcp" => Method::Dhcp,
+            "manual" => Method::Manual,
+            "link-local" => Method::LinkLocal,
+            "shared" => Method::Shared,
+            "disabled" => Method::Disabled,
+            "ignore" => Method::Ignore,
+            s => Method::Other(s.to_owned()),
+        }
+    }
+}
+
+/// A static address of an `addressN` or legacy `addressesN` key.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Address {
+    pub address: IpAddr,
+    pub prefix: u8,
+    pub gateway: Option<IpAddr>,
+}
+
+/// The `[ipv4]` or `[ipv6]` group.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct IpConfig {
+    pub method: Method,
+    pub addresses: Vec<Address>,
+    pub gateway: Option<IpAddr>,
+    pub dns: Vec<IpAddr>,
+    pub dns_search: Vec<String>,
+    pub ignore_auto_dns: bool,
+    pub never_default: bool,
+}
+
+/// The `[wifi]` group, with the key management of `[wifi-security]`.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct Wifi {
+    pub ssid: String,
+    pub mode: Option<String>,
+    pub hidden: bool,
+    pub key_mgmt: Option<String>,
+}
+
+/// A connection profile of NetworkManager.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Connection {
+    pub id: String,
+    pub uuid: String,
+    /// The connection type, like `ethernet`, `wifi` or `wireguard`.
+    pub kind: String,
+    pub interface_name: Option<String>,
+    pub autoconnect: bool,
+    pub ipv4: Option<IpConfig>,
+    pub ipv6: Option<IpConfig>,
+    pub wifi: Option<Wifi>,
+    /// Every group of the file, for the settings without a typed field.
+    pub groups: Vec<Group>,
+}
+
+impl Connection {
+    /// Returns the group, also by the long names like `802-11-wireless` NetworkManager accepts.
+    pub fn group(&self, name: &str) -> Option<&Group> {
+        let alias = match name {
+            "wifi" => "802-11-wireless",
+            "wifi-security" => "802-11-wireless-security",
+            "ethernet" => "802-3-ethernet",
+            "802-11-wireless" => "wifi",
+            "802-11-wireless-security" => "wifi-security",
+            "802-3-ethernet" => "ethernet",
+            name => name,
+        };
+
+        self.groups
+            .iter()
+            .find(|group| group.name == name || group.name == alias)
+    }
+
+    pub fn get(&self, group: &str, key: &str) -> Option<&str> {
+        self.group(group).and_then(|group| group.get(key))
+    }
+
+    fn from_groups(groups: Vec<Group>) -> Result<Self, ParseNetworkManagerError> {
+        let connection = Connection {
+            id: String::new(),
+            uuid: String::new(),
+            kind: String::new(),
+            interface_name: None,
+            autoconnect: true,
+            ipv4: None,
+            ipv6: None,
+            wifi: None,
+            groups,
+        };
+
+        let required = |key: &str| {
+            connection
+                .get("connection", key)
+                .map(String::from)
+                .ok_or_else(|| ParseNetworkManagerError::MissingKey(format!("connection.{}", key)))
+        };
+
+        let (id, uuid, kind) = (required("id")?, required("uuid")?, required("type")?);
+        let interface_name = connection
+            .get("connection", "interface-name")
+            .map(String::from);
+        let autoconnect = boolean(&connection, "connection", "autoconnect")?.unwrap_or(true);
+        let ipv4 = ip_config(&connection, "ipv4")?;
+        let ipv6 = ip_config(&connection, "ipv6")?;
+        let wifi = match connection.group("wifi") {
+            Some(wifi) => Some(Wifi {
+                ssid: wifi.get("ssid").map(ssid).unwrap_or_default(),
+                mode: wifi.get("mode").map(String::from),
+                hidden: boolean(&connection, "wifi", "hidden")?.unwrap_or(false),
+                key_mgmt: connection
+                    .get("wifi-security", "key-mgmt")
+                    .map(String::from),
+            }),
+            None => None,
+        };
+
+        Ok(Connection {
+            id,
+            uuid,
+            kind,
+            interface_name,
+            autoconnect,
+            ipv4,
+            ipv6,
+            wifi,
+            ..connection
+        })
+    }
+}
+
+fn invalid(key: &str, value: &str) -> ParseNetworkManagerError {
+    ParseNetworkManagerError::InvalidValue {
+        key: key.to_owned(),
+        value: value.to_owned(),
+    }
+}
+
+fn boolean(
+    connection: &Connection,
+    group: &str,
+    key: &str,
+) -> Result<Option<bool>, ParseNetworkManagerError> {
+    connection
+        .get(group, key)
+        .map(|value| match value {
+            "true" | "yes" | "1" => Ok(true),
+            "false" | "no" | "0" => Ok(false),
+            value => Err(invalid(key, value)),
+        })
+        .transpose()
+}
+
+/// Splits a keyfile list, which is `;` separated with an optional trailing `;`.
+fn list(value: &str) -> impl Iterator<Item = &str> {
+    value
+        .split(';')
+        .map(str::trim)
+        .filter(|item| !item.is_empty())
+}
+
+/// Decodes an SSID written either as text or as the `;` separated byte list of older versions.
+fn ssid(value: &str) -> String {
+    let bytes = list(value)
+        .map(|byte| byte.parse::<u8>())
+        .collect::<Result<Vec<_>, _>>();
+
+    match bytes {
+        Ok(bytes) if value.contains(';') => String::from_utf8_lossy(&bytes).into_owned(),
+        _ => value.to_owned(),
+    }
+}
+
+fn ip_config(
+    connection: &Connection,
+    name: &str,
+) -> Result<Option<IpConfig>, ParseNetworkManagerError> {
+    let Some(group) = connection.group(name) else {
+        return Ok(None);
+    };
+
+    let parse_ip =
+        |key: &str, value: &str| value.parse::<IpAddr>().map_err(|_| invalid(key, value));
+
+    let mut numbered = group
+        .entries
+        .iter()
+        .filter_map(|(key, value)| {
+            let n = key
+                .strip_prefix("addresses")
+                .or_else(|| key.strip_prefix("address"))?;
+            let n = if n.is_empty() {
+                0
+            } else {
+                n.parse::<u32>().ok()?
+            };
+
+            Some((n, key, value))
+        })
+        .collect::<Vec<_>>();
+    numbered.sort_by_key(|(n, _, _)| *n);
+
+    // NetworkManager defaults a missing prefix length to these, with a warning.
+    let default_prefix = if name == "ipv6" { 64 } else { 24 };
+
+    let mut addresses = Vec::new();
+    for (_, key, value) in numbered {
+        // Either `10.0.0.2/24,10.0.0.1` or the older `10.0.0.2;24;10.0.0.1;`.
+        let mut fields = value
+            .split([',', ';'])
+            .map(str::trim)
+            .filter(|field| !field.is_empty())
+            .peekable();
+
+        let first = fields.next().ok_or_else(|| invalid(key, value))?;
+        let (address, prefix) = match first.split_once('/') {
+            Some((address, prefix)) => (address, Some(prefix)),
+            None => (
+                first,
+                fields.next_if(|field| field.bytes().all(|b| b.is_ascii_digit())),
+            ),
+        };
+
+        addresses.push(Address {
+            address: parse_ip(key, address)?,
+            prefix: match prefix {
+                Some(prefix) => prefix.parse().map_err(|_| invalid(key, value))?,
+                None => default_prefix,
+            },
+            gateway: fields
+                .next()
+                .map(|gateway| parse_ip(key, gateway))
+                .transpose()?,
+        });
+    }
+
+    Ok(Some(IpConfig {
+        method: group
+            .get("method")
+            .map(Method::from)
+            .unwrap_or(Method::Auto),
+        gateway: group
+            .get("gateway")
+            .map(|gateway| parse_ip("gateway", gateway))
+            .transpose()?,
+        dns: list(group.get("dns").unwrap_or_default())
+            .map(|dns| parse_ip("dns", dns))
+            .collect::<Result<_, _>>()?,
+        dns_search: list(group.get("dns-search").unwrap_or_default())
+            .map(String::from)
+            .collect(),
+        ignore_auto_dns: boolean(connection, name, "ignore-auto-dns")?.unwrap_or(false),
+        never_default: boolean(connection, name, "never-default")?.unwrap_or(false),
+        addresses,
+    }))
+}
+
+#[derive(Debug)]
+pub enum ParseNetworkManagerError {
+    InvalidEntry(String),
+    InvalidValue { key: String, value: String },
+    MissingKey(String),
+    IOError(io::Error),
+}
+
+impl error::Error for ParseNetworkManagerError {}
+
+impl fmt::Display for ParseNetworkManagerError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::InvalidEntry(entry) => write!(f, "invalid keyfile line: {}", entry),
+            Self::InvalidValue { key, value } => write!(f, "invalid value of {}: {}", key, value),
+            Self::MissingKey(key) => write!(f, "missing required key {}", key),
+            Self::IOError(error) => write!(f, "{}", error),
+        }
+    }
+}
+
+impl From<io::Error> for ParseNetworkManagerError {
+    fn from(value: io::Error) -> Self {
+        ParseNetworkManagerError::IOError(value)
+    }
+}
+
+/// Decodes the GLib keyfile escapes `\s`, `\n`, `\t`, `\r` and `\\`.
+fn unescape(value: &str) -> String {
+    let mut unescaped = String::with_capacity(value.len());
+    let mut chars = value.chars();
+
+    while let Some(c) = chars.next() {
+        if c != '\\' {
+            unescaped.push(c);
+            continue;
+        }
+
+        match chars.next() {
+            Some('s') => unescaped.push(' '),
+            Some('n') => unescaped.push('\n'),
+            Some('t') => unescaped.push('\t'),
+            Some('r') => unescaped.push('\r'),
+            Some(c) => unescaped.push(c),
+            None => unescaped.push('\\'),
+        }
+    }
+
+    unescaped
+}
+
+///
+/// Parses a `.nmconnection` keyfile.
+/// Comments start at the beginning of a line only, `#` inside values is kept.
+///
+/// ```no_run
+/// let connection = unixism::networkmanager::parse(
+///     std::fs::File::open("/etc/NetworkManager/system-connections/Wired.nmconnection").unwrap(),
+/// )
+/// .unwrap();
+///
+/// println!("{} ({}) {:?}", connection.id, connection.kind, connection.ipv4);
+/// ```
+pub fn parse<R>(reader: R) -> Result<Connection, ParseNetworkManagerError>
+where
+    R: io::Read,
+{
+    let mut groups: Vec<Group> = Vec::new();
+
+    for line in BufReader::new(reader).lines() {
+        let line = line?;
+        let line = line.trim();
+
+        if line.is_empty() || line.starts_with('#') || line.starts_with(';') {
+            continue;
+        }
+
+        if let Some(name) = line
+            .strip_prefix('[')
+            .and_then(|line| line.strip_suffix(']'))
+        {
+            groups.push(Group {
+                name: name.to_owned(),
+                entries: Vec::new(),
+            });
+
+            continue;
+        }
+
+        let (key, value) = line
+            .split_once('=')
+            .ok_or_else(|| ParseNetworkManagerError::InvalidEntry(line.to_owned()))?;
+        let group = groups
+            .last_mut()
+            .ok_or_else(|| ParseNetworkManagerError::InvalidEntry(line.to_owned()))?;
+
+        group
+            .entries
+            .push((key.trim().to_owned(), unescape(value.trim())));
+    }
+
+    Connection::from_groups(groups)
+}
+
+///
+/// Parses every `*.nmconnection` keyfile of the directory, sorted by file name.
+///
+/// ```no_run
+/// for connection in unixism::networkmanager::parse_dir("/run/NetworkManager/system-connections").unwrap() {
+///     println!("{} {}", connection.uuid, connection.id);
+/// }
+/// ```
+pub fn parse_dir<P>(dir: P) -> Result<Vec<Connection>, ParseNetworkManagerError>
+where
+    P: AsRef<Path>,
+{
+    let mut paths = fs::read_dir(dir)?
+        .filter_map(Result::ok)
+        .map(|entry| entry.path())
+        .filter(|path| {
+            path.extension()
+                .is_some_and(|extension| extension == "nmconnection")
+        })
+        .collect::<Vec<_>>();
+    paths.sort();
+
+    paths
+        .into_iter()
+        .map(|path| parse(fs::File::open(path)?))
+        .collect()
+}
+
+///
+/// Same as parse_dir, but parses the `/etc/NetworkManager/system-connections` as default.
+///
+/// ```no_run
+/// let connections = unixism::networkmanager::parse_default().unwrap();
+/// ```
+pub fn parse_default() -> Result<Vec<Connection>, ParseNetworkManagerError> {
+    parse_dir("/etc/NetworkManager/system-connections")
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Cursor;
+
+    use super::*;
+
+    #[test]
+    fn it_parse() {
+        let connection = parse(Cursor::new(
+            r#"
+[connection]
+id=Office\sWiFi
+uuid=5f4b1c3e-8d2a-4e6b-9a7c-1b2d3e4f5a6b
+type=wifi
+interface-name=wlan0
+autoconnect=false
+permissions=
+
+[wifi]
+mode=infrastructure
+ssid=Office#5
+
+[wifi-security]
+key-mgmt=wpa-psk
+psk=correct horse battery staple
+
+[ipv4]
+address2=10.0.0.3/24
+address1=10.0.0.2/24,10.0.0.1
+dns=1.1.1.1;8.8.8.8;
+dns-search=corp.example;
+ignore-auto-dns=true
+method=manual
+
+[ipv6]
+addr-gen-mode=stable-privacy
+method=auto
+
+[proxy]
+        "#,
+        ))
+        .unwrap();
+
+        assert_eq!("Office WiFi", connection.id);
+        assert_eq!("wifi", connection.kind);
+        assert_eq!(Some("wlan0"), connection.interface_name.as_deref());
+        assert!(!connection.autoconnect);
+
+        let ipv4 = connection.ipv4.as_ref().unwrap();
+        assert_eq!(Method::Manual, ipv4.method);
+        assert_eq!(
+            vec![
+                Address {
+                    address: "10.0.0.2".parse().unwrap(),
+                    prefix: 24,
+                    gateway: Some("10.0.0.1".parse().unwrap()),
+                },
+                Address {
+                    address: "10.0.0.3".parse().unwrap(),
+                    prefix: 24,
+                    gateway: None,
+                },
+            ],
+            ipv4.addresses
+        );
+        assert_eq!(
+            vec![
+                "1.1.1.1".parse::<IpAddr>().unwrap(),
+                "8.8.8.8".parse().unwrap()
+            ],
+            ipv4.dns
+        );
+        assert_eq!(vec!["corp.example"], ipv4.dns_search);
+        assert!(ipv4.ignore_auto_dns);
+
+        assert_eq!(Method::Auto, connection.ipv6.as_ref().unwrap().method);
+        assert_eq!(
+            Some(Wifi {
+                ssid: "Office#5".to_owned(),
+                mode: Some("infrastructure".to_owned()),
+                hidden: false,
+                key_mgmt: Some("wpa-psk".to_owned()),
+            }),
+            connection.wifi
+        );
+        assert_eq!(
+            Some("correct horse battery staple"),
+            connection.get("802-11-wireless-security", "psk")
+        );
+
+        let connection = parse(Cursor::new(
+            r#"
+[connection]
+id=Legacy
+uuid=7d3c2b1a-0000-1111-2222-333344445555
+type=ethernet
+
+[ipv4]
+method=manual
+addresses1=192.168.0.2;16;192.168.0.1;
+address2=192.168.0.3
+address3=192.168.0.4,192.168.0.1
+
+[ipv6]
+method=manual
+address1=2001:db8::2
+        "#,
+        ))
+        .unwrap();
+
+        assert_eq!(
+            vec![
+                Address {
+                    address: "192.168.0.2".parse().unwrap(),
+                    prefix: 16,
+                    gateway: Some("192.168.0.1".parse().unwrap()),
+                },
+                Address {
+                    address: "192.168.0.3".parse().unwrap(),
+                    prefix: 24,
+                    gateway: None,
+                },
+                Address {
+                    address: "192.168.0.4".parse().unwrap(),
+                    prefix: 24,
+                    gateway: Some("192.168.0.1".parse().unwrap()),
+                },
+            ],
+            connection.ipv4.unwrap().addresses
+        );
+        assert_eq!(64, connection.ipv6.unwrap().addresses[0].prefix);
+
+        assert!(parse(Cursor::new("[connection]\nid=x\ntype=ethernet")).is_err());
+        assert!(parse(Cursor::new("id=x")).is_err());
+    }
+
+    #[test]
+    fn it_parse_legacy_names() {
+        let connection = parse(Cursor::new(
+            r#"
+[connection]
+id=Home
+uuid=0b7e6c2a-1111-2222-3333-444455556666
+type=802-11-wireless
+
+[802-11-wireless]
+ssid=72;111;109;101;
+hidden=true
+
+[ipv4]
+method=auto
+address1=192.168.1.300/24
+        "#,
+        ));
+
+        assert!(matches!(
+            connection,
+            Err(ParseNetworkManagerError::InvalidValue { .. })
+        ));
+
+        let connection = parse(Cursor::new(
+            r#"
+[connection]
+id=Home
+uuid=0b7e6c2a-1111-2222-3333-444455556666
+type=802-11-wireless
+
+[802-11-wireless]
+ssid=72;111;109;101;
+hidden=true
+        "#,
+        ))
+        .unwrap();
+
+        let wifi = connection.wifi.unwrap();
+        assert_eq!("Home", wifi.ssid);
+        assert!(wifi.hidden);
+        assert_eq!(None, connection.ipv4);
+    }
+}
@@ -0,0 +1,224 @@
+use std::{
+    error, fmt, fs,
+    io::{self, BufRead, BufReader},
+    net::Ipv4Addr,
+    num::ParseIntError,
+    str::FromStr,
+};
+
+/// A line of `/etc/networks`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Network {
+    pub name: String,
+    /// The network number, with the omitted trailing octets filled with zeros.
+    pub number: Ipv4Addr,
+    /// The prefix length implied by the number of octets written, or by the address class for all four.
+    pub prefix: u8,
+    pub aliases: Vec<String>,
+}
+
+impl FromStr for Network {
+    type Err = ParseNetworksError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut fields = s.split_whitespace();
+
+        let (Some(name), Some(number)) = (fields.next(), fields.next()) else {
+            return Err(ParseNetworksError::InvalidEntry(s.to_owned()));
+        };
+
+        let (number, prefix) = parse_number(number)?;
+
+        Ok(Network {
+            name: name.to_owned(),
+            number,
+            prefix,
+            aliases: fields.map(String::from).collect(),
+        })
+    }
+}
+
+impl fmt::Display for Network {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let octets = self.number.octets();
+
+        // Only the octets covered by the prefix are written, so the number re-parses to the
+        // same prefix. A number with bits set past its prefix is written in full.
+        let mut len = (self.prefix as usize).div_ceil(8).clamp(1, 4);
+        if octets[len..].iter().any(|&octet| octet != 0) {
+            len = 4;
+        }
+
+        write!(f, "{}", self.name)?;
+        for (i, octet) in octets[..len].iter().enumerate() {
+            write!(f, "{}{}", if i == 0 { ' ' } else { '.' }, octet)?;
+        }
+
+        for alias in &self.aliases {
+            write!(f, " {}", alias)?;
+        }
+
+        Ok(())
+    }
+}
+
+///
+/// Parses the network number in the `inet_network(3)` notation, where `10` means `10.0.0.0/8`
+/// and `192.168.1` means `192.168.1.0/24`. Octets may be written in hex with a `0x` prefix.
+fn parse_number(s: &str) -> Result<(Ipv4Addr, u8), ParseNetworksError> {
+    let parts = s.split('.').collect::<Vec<_>>();
+
+    if parts.is_empty() || parts.len() > 4 {
+        return Err(ParseNetworksError::InvalidEntry(s.to_owned()));
+    }
+
+    let mut octets = [0u8; 4];
+    for (octet, part) in octets.iter_mut().zip(&parts) {
+        *octet = match part.strip_prefix("0x").or_else(|| part.strip_prefix("0X")) {
+            Some(hex) => u8::from_str_radix(hex, 16)?,
+            None => part.parse()?,
+        };
+    }
+
+    let prefix = match parts.len() {
+        4 => match octets[0] {
+            0..=127 => 8,
+            128..=191 => 16,
+            192..=223 => 24,
+            _ => 32,
+        },
+        len => len as u8 * 8,
+    };
+
+    Ok((Ipv4Addr::from(octets), prefix))
+}
+
+#[derive(Debug)]
+pub enum ParseNetworksError {
+    InvalidEntry(String),
+    ParseIntError(ParseIntError),
+    IOError(io::Error),
+}
+
+impl error::Error for ParseNetworksError {}
+
+impl fmt::Display for ParseNetworksError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::InvalidEntry(entry) => write!(f, "invalid network: {}", entry),
+            Self::ParseIntError(error) => write!(f, "{}", error),
+            Self::IOError(error) => write!(f, "{}", error),
+        }
+    }
+}
+
+impl From<ParseIntError> for ParseNetworksError {
+    fn from(value: ParseIntError) -> Self {
+        ParseNetworksError::ParseIntError(value)
+    }
+}
+
+impl From<io::Error> for ParseNetworksError {
+    fn from(value: io::Error) -> Self {
+        ParseNetworksError::IOError(value)
+    }
+}
+
+///
+/// ```no_run
+/// let networks = unixism::networks::parse(std::fs::File::open("/etc/networks").unwrap()).unwrap();
+///
+/// for network in networks {
+///     println!("{} {}/{}", network.name, network.number, network.prefix);
+/// }
+/// ```
+pub fn parse<R>(reader: R) -> Result<impl Iterator<Item = Network>, ParseNetworksError>
+where
+    R: io::Read,
+{
+    let mut networks = Vec::new();
+
+    for line in BufReader::new(reader).lines() {
+        let line = line?;
+        let line = line.split('#').next().unwrap_or_default().trim();
+
+        if line.is_empty() {
+            continue;
+        }
+
+        networks.push(line.parse::<Network>()?);
+    }
+
+    Ok(networks.into_iter())
+}
+
+///
+/// Same as parse, but parses the `/etc/networks` as default.
+///
+/// ```no_run
+/// let networks = unixism::networks::parse_default().unwrap();
+/// ```
+pub fn parse_default() -> Result<impl Iterator<Item = Network>, ParseNetworksError> {
+    parse(fs::File::open("/etc/networks")?)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Cursor;
+
+    use super::*;
+
+    #[test]
+    fn it_parse() {
+        let networks = parse(Cursor::new(
+            r#"
+# symbolic names for networks, see networks(5) for more information
+default         0.0.0.0
+loopback        127
+link-local      169.254.0.0
+corp            10 intranet    # the whole corporate network
+lab             192.168.0x1f
+        "#,
+        ))
+        .unwrap()
+        .collect::<Vec<_>>();
+
+        assert_eq!(5, networks.len());
+        assert_eq!(
+            Network {
+                name: "loopback".to_owned(),
+                number: Ipv4Addr::new(127, 0, 0, 0),
+                prefix: 8,
+                aliases: vec![],
+            },
+            networks[1]
+        );
+        assert_eq!(16, networks[2].prefix);
+        assert_eq!(vec!["intranet".to_owned()], networks[3].aliases);
+        assert_eq!(Ipv4Addr::new(192, 168, 31, 0), networks[4].number);
+        assert_eq!(24, networks[4].prefix);
+        assert_eq!("corp 10 intranet", networks[3].to_string());
+        assert_eq!("link-local 169.254", networks[2].to_string());
+        assert_eq!("lab 192.168.31", networks[4].to_string());
+
+        for network in &networks {
+            assert_eq!(*network, network.to_string().parse::<Network>().unwrap());
+        }
+
+        let network = "net 10.1".parse::<Network>().unwrap();
+        assert_eq!(16, network.prefix);
+        assert_eq!("net 10.1", network.to_string());
+
+        let host = Network {
+            name: "host".to_owned(),
+            number: Ipv4Addr::new(10, 1, 2, 3),
+            prefix: 8,
+            aliases: vec![],
+        };
+        assert_eq!("host 10.1.2.3", host.to_string());
+
+        assert!(parse(Cursor::new("loopback")).is_err());
+        assert!(parse(Cursor::new("loopback 127.0.0.0.1")).is_err());
+        assert!(parse(Cursor::new("loopback 300")).is_err());
+    }
+}
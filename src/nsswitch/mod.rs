@@ -0,0 +1,439 @@
+use std::{
+    error, fmt, fs,
+    io::{self, BufRead, BufReader},
+    str::FromStr,
+};
+
+/// A service providing a database, like `files` or `dns`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Service {
+    Files,
+    Dns,
+    Compat,
+    Db,
+    Nis,
+    Nisplus,
+    Ldap,
+    Sss,
+    Systemd,
+    Myhostname,
+    Mymachines,
+    Resolve,
+    Mdns,
+    Mdns4,
+    Mdns6,
+    MdnsMinimal,
+    Mdns4Minimal,
+    Mdns6Minimal,
+    Wins,
+    /// Services unknown to this crate.
+    Other(String),
+}
+
+impl From<&str> for Service {
+    fn from(s: &str) -> Self {
+        match s {
+            "files" => Service::Files,
+            "dns" => Service::Dns,
+            "compat" => Service::Compat,
+            "db" => Service::Db,
+            "nis" => Service::Nis,
+            "nisplus" => Service::Nisplus,
+            "ldap" => Service::Ldap,
+            "sss" => Service::Sss,
+            "systemd" => Service::Systemd,
+            "myhostname" => Service::Myhostname,
+            "mymachines" => Service::Mymachines,
+            "resolve" => Service::Resolve,
+            "mdns" => Service::Mdns,
+            "mdns4" => Service::Mdns4,
+            "mdns6" => Service::Mdns6,
+            "mdns_minimal" => Service::MdnsMinimal,
+            "mdns4_minimal" => Service::Mdns4Minimal,
+            "mdns6_minimal" => Service::Mdns6Minimal,
+            "wins" => Service::Wins,
+            service => Service::Other(service.to_owned()),
+        }
+    }
+}
+
+impl fmt::Display for Service {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            Service::Files => "files",
+            Service::Dns => "dns",
+            Service::Compat => "compat",
+            Service::Db => "db",
+            Service::Nis => "nis",
+            Service::Nisplus => "nisplus",
+            Service::Ldap => "ldap",
+            Service::Sss => "sss",
+            Service::Systemd => "systemd",
+            Service::Myhostname => "myhostname",
+            Service::Mymachines => "mymachines",
+            Service::Resolve => "resolve",
+            Service::Mdns => "mdns",
+            Service::Mdns4 => "mdns4",
+            Service::Mdns6 => "mdns6",
+            Service::MdnsMinimal => "mdns_minimal",
+            Service::Mdns4Minimal => "mdns4_minimal",
+            Service::Mdns6Minimal => "mdns6_minimal",
+            Service::Wins => "wins",
+            Service::Other(service) => service,
+        };
+
+        write!(f, "{}", name)
+    }
+}
+
+/// The result of a service lookup.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Status {
+    Success,
+    NotFound,
+    Unavail,
+    TryAgain,
+}
+
+impl FromStr for Status {
+    type Err = ParseNsswitchError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_ascii_uppercase().as_str() {
+            "SUCCESS" => Ok(Status::Success),
+            "NOTFOUND" => Ok(Status::NotFound),
+            "UNAVAIL" => Ok(Status::Unavail),
+            "TRYAGAIN" => Ok(Status::TryAgain),
+            _ => Err(ParseNsswitchError::InvalidEntry(s.to_owned())),
+        }
+    }
+}
+
+impl fmt::Display for Status {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Status::Success => write!(f, "SUCCESS"),
+            Status::NotFound => write!(f, "NOTFOUND"),
+            Status::Unavail => write!(f, "UNAVAIL"),
+            Status::TryAgain => write!(f, "TRYAGAIN"),
+        }
+    }
+}
+
+/// What to do after a service returned a status.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Action {
+    Return,
+    Continue,
+    Merge,
+}
+
+impl FromStr for Action {
+    type Err = ParseNsswitchError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_ascii_lowercase().as_str() {
+            "return" => Ok(Action::Return),
+            "continue" => Ok(Action::Continue),
+            "merge" => Ok(Action::Merge),
+            _ => Err(ParseNsswitchError::InvalidEntry(s.to_owned())),
+        }
+    }
+}
+
+impl fmt::Display for Action {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Action::Return => write!(f, "return"),
+            Action::Continue => write!(f, "continue"),
+            Action::Merge => write!(f, "merge"),
+        }
+    }
+}
+
+/// An action specifier item, like `NOTFOUND=return` or `!UNAVAIL=return`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Criterion {
+    /// Applies the action to every status except `status`.
+    pub negated: bool,
+    pub status: Status,
+    pub action: Action,
+}
+
+impl FromStr for Criterion {
+    type Err = ParseNsswitchError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (status, action) = s
+            .split_once('=')
+            .ok_or_else(|| ParseNsswitchError::InvalidEntry(s.to_owned()))?;
+
+        let (negated, status) = match status.trim().strip_prefix('!') {
+            Some(status) => (true, status),
+            None => (false, status.trim()),
+        };
+
+        Ok(Criterion {
+            negated,
+            status: status.parse()?,
+            action: action.trim().parse()?,
+        })
+    }
+}
+
+impl fmt::Display for Criterion {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.negated {
+            write!(f, "!")?;
+        }
+
+        write!(f, "{}={}", self.status, self.action)
+    }
+}
+
+/// A service with the action specifiers following it.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Source {
+    pub service: Service,
+    pub criteria: Vec<Criterion>,
+}
+
+impl Source {
+    /// The action taken after the service returned `status`, `SUCCESS=return` and `continue` otherwise by default.
+    pub fn action(&self, status: Status) -> Action {
+        self.criteria
+            .iter()
+            .rev()
+            .find(|criterion| (criterion.status == status) != criterion.negated)
+            .map(|criterion| criterion.action)
+            .unwrap_or(match status {
+                Status::Success => Action::Return,
+                _ => Action::Continue,
+            })
+    }
+}
+
+/// A database line, like `hosts: files dns`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Database {
+    pub name: String,
+    pub sources: Vec<Source>,
+}
+
+impl FromStr for Database {
+    type Err = ParseNsswitchError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (name, sources) = s
+            .split_once(':')
+            .ok_or_else(|| ParseNsswitchError::InvalidEntry(s.to_owned()))?;
+
+        let mut database = Database {
+            name: name.trim().to_owned(),
+            sources: Vec::new(),
+        };
+
+        let mut rest = sources.trim();
+        while !rest.is_empty() {
+            if let Some(specifier) = rest.strip_prefix('[') {
+                let (criteria, tail) = specifier
+                    .split_once(']')
+                    .ok_or_else(|| ParseNsswitchError::InvalidEntry(s.to_owned()))?;
+
+                let source = database
+                    .sources
+                    .last_mut()
+                    .ok_or_else(|| ParseNsswitchError::InvalidEntry(s.to_owned()))?;
+
+                for criterion in criteria.split_whitespace() {
+                    source.criteria.push(criterion.parse()?);
+                }
+
+                rest = tail.trim_start();
+            } else {
+                let end = rest
+                    .find(|c: char| c.is_whitespace() || c == '[')
+                    .unwrap_or(rest.len());
+
+                database.sources.push(Source {
+                    service: Service::from(&rest[..end]),
+                    criteria: Vec::new(),
+                });
+
+                rest = rest[end..].trim_start();
+            }
+        }
+
+        Ok(database)
+    }
+}
+
+impl fmt::Display for Database {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}:", self.name)?;
+
+        for source in &self.sources {
+            write!(f, " {}", source.service)?;
+
+            if !source.criteria.is_empty() {
+                let criteria = source
+                    .criteria
+                    .iter()
+                    .map(Criterion::to_string)
+                    .collect::<Vec<_>>();
+
+                write!(f, " [{}]", criteria.join(" "))?;
+            }
+        }
+
+        Ok(())
+    }
+}
+
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct Config {
+    pub databases: Vec<Database>,
+}
+
+impl Config {
+    pub fn get(&self, name: &str) -> Option<&Database> {
+        self.databases.iter().find(|database| database.name == name)
+    }
+}
+
+#[derive(Debug)]
+pub enum ParseNsswitchError {
+    InvalidEntry(String),
+    IOError(io::Error),
+}
+
+impl error::Error for ParseNsswitchError {}
+
+impl fmt::Display for ParseNsswitchError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::InvalidEntry(entry) => write!(f, "invalid nsswitch entry: {}", entry),
+            Self::IOError(error) => write!(f, "{}", error),
+        }
+    }
+}
+
+impl From<io::Error> for ParseNsswitchError {
+    fn from(value: io::Error) -> Self {
+        ParseNsswitchError::IOError(value)
+    }
+}
+
+///
+/// ```no_run
+/// let config = unixism::nsswitch::parse(std::fs::File::open("/etc/nsswitch.conf").unwrap()).unwrap();
+///
+/// if let Some(hosts) = config.get("hosts") {
+///     for source in &hosts.sources {
+///         println!("{} {:?}", source.service, source.criteria);
+///     }
+/// }
+/// ```
+pub fn parse<R>(reader: R) -> Result<Config, ParseNsswitchError>
+where
+    R: io::Read,
+{
+    let mut config = Config::default();
+
+    for line in BufReader::new(reader).lines() {
+        let line = line?;
+        let line = line.split('#').next().unwrap_or_default().trim();
+
+        if line.is_empty() {
+            continue;
+        }
+
+        config.databases.push(line.parse::<Database>()?);
+    }
+
+    Ok(config)
+}
+
+///
+/// Same as parse, but parses the `/etc/nsswitch.conf` as default.
+///
+/// ```no_run
+/// let config = unixism::nsswitch::parse_default().unwrap();
+/// ```
+pub fn parse_default() -> Result<Config, ParseNsswitchError> {
+    parse(fs::File::open("/etc/nsswitch.conf")?)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Cursor;
+
+    use super::*;
+
+    #[test]
+    fn it_parse() {
+        let config = parse(Cursor::new(
+            r#"
+# /etc/nsswitch.conf
+passwd:         files systemd
+group:          files [SUCCESS=merge] sss
+hosts:          files mdns4_minimal [NOTFOUND=return] dns myhostname # trailing comment
+networks:       files dns[!UNAVAIL=return TRYAGAIN=continue]
+        "#,
+        ))
+        .unwrap();
+
+        assert_eq!(4, config.databases.len());
+
+        let hosts = config.get("hosts").unwrap();
+        assert_eq!(
+            vec![
+                Service::Files,
+                Service::Mdns4Minimal,
+                Service::Dns,
+                Service::Myhostname
+            ],
+            hosts
+                .sources
+                .iter()
+                .map(|source| source.service.clone())
+                .collect::<Vec<_>>()
+        );
+        assert_eq!(
+            vec![Criterion {
+                negated: false,
+                status: Status::NotFound,
+                action: Action::Return,
+            }],
+            hosts.sources[1].criteria
+        );
+        assert_eq!(Action::Return, hosts.sources[1].action(Status::NotFound));
+        assert_eq!(Action::Continue, hosts.sources[1].action(Status::Unavail));
+        assert_eq!(Action::Return, hosts.sources[0].action(Status::Success));
+
+        let networks = config.get("networks").unwrap();
+        assert_eq!(Action::Return, networks.sources[1].action(Status::NotFound));
+        assert_eq!(
+            Action::Continue,
+            networks.sources[1].action(Status::TryAgain)
+        );
+        assert_eq!(
+            Action::Continue,
+            networks.sources[1].action(Status::Unavail)
+        );
+        assert_eq!(
+            "networks: files dns [!UNAVAIL=return TRYAGAIN=continue]",
+            networks.to_string()
+        );
+
+        assert_eq!(
+            Action::Merge,
+            config.get("group").unwrap().sources[0].action(Status::Success)
+        );
+        assert!(config.get("shadow").is_none());
+
+        assert!(parse(Cursor::new("hosts files")).is_err());
+        assert!(parse(Cursor::new("hosts: [NOTFOUND=return] files")).is_err());
+        assert!(parse(Cursor::new("hosts: files [NOTFOUND=retry]")).is_err());
+    }
+}
@@ -0,0 +1,91 @@
+use std::{fs, io};
+
+use super::ParseOsReleaseError;
+use crate::shellvars;
+
+///
+/// The legacy `/etc/lsb-release` of Debian and Ubuntu derived systems.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct LsbRelease {
+    pub id: Option<String>,
+    pub release: Option<String>,
+    pub codename: Option<String>,
+    pub description: Option<String>,
+    /// Every variable in file order, including the ones without a field.
+    pub variables: Vec<(String, String)>,
+}
+
+impl LsbRelease {
+    pub fn get(&self, name: &str) -> Option<&str> {
+        self.variables
+            .iter()
+            .rev()
+            .find(|(variable, _)| variable == name)
+            .map(|(_, value)| value.as_str())
+    }
+}
+
+///
+/// ```no_run
+/// let lsb = unixism::osrelease::lsb::parse(std::fs::File::open("/etc/lsb-release").unwrap()).unwrap();
+///
+/// println!("{:?} {:?}", lsb.id, lsb.release);
+/// ```
+pub fn parse<R>(reader: R) -> Result<LsbRelease, ParseOsReleaseError>
+where
+    R: io::Read,
+{
+    let mut lsb = LsbRelease::default();
+
+    for (name, value) in shellvars::parse(reader)?.variables {
+        match name.as_str() {
+            "DISTRIB_ID" => lsb.id = Some(value.clone()),
+            "DISTRIB_RELEASE" => lsb.release = Some(value.clone()),
+            "DISTRIB_CODENAME" => lsb.codename = Some(value.clone()),
+            "DISTRIB_DESCRIPTION" => lsb.description = Some(value.clone()),
+            _ => {}
+        }
+
+        lsb.variables.push((name, value));
+    }
+
+    Ok(lsb)
+}
+
+///
+/// Same as parse, but parses the `/etc/lsb-release` as default.
+///
+/// ```no_run
+/// let lsb = unixism::osrelease::lsb::parse_default().unwrap();
+/// ```
+pub fn parse_default() -> Result<LsbRelease, ParseOsReleaseError> {
+    parse(fs::File::open("/etc/lsb-release")?)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Cursor;
+
+    use super::*;
+
+    #[test]
+    fn it_parse() {
+        let lsb = parse(Cursor::new(
+            r#"
+DISTRIB_ID=Ubuntu
+DISTRIB_RELEASE=22.04
+DISTRIB_CODENAME=jammy
+DISTRIB_DESCRIPTION="Ubuntu 22.04.3 LTS"
+        "#,
+        ))
+        .unwrap();
+
+        assert_eq!(Some("Ubuntu"), lsb.id.as_deref());
+        assert_eq!(Some("22.04"), lsb.release.as_deref());
+        assert_eq!(Some("jammy"), lsb.codename.as_deref());
+        assert_eq!(Some("Ubuntu 22.04.3 LTS"), lsb.description.as_deref());
+        assert_eq!(Some("jammy"), lsb.get("DISTRIB_CODENAME"));
+
+        assert!(parse(Cursor::new("DISTRIB_ID")).is_err());
+    }
+}
@@ -0,0 +1,416 @@
+use std::{cmp::Ordering, error, fmt, fs, io, path::Path};
+
+use crate::shellvars::{self, ParseShellVarsError as ShellVarsError};
+
+pub mod lsb;
+
+use lsb::LsbRelease;
+
+/// Where os-release is looked for, the first existing file wins.
+pub const PATHS: [&str; 2] = ["/etc/os-release", "/usr/lib/os-release"];
+
+///
+/// The operating system identification of `os-release(5)`.
+/// `ID`, `NAME` and `PRETTY_NAME` get the documented defaults when missing.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct OsRelease {
+    pub id: String,
+    /// The distributions this one derives from, the closest first.
+    pub id_like: Vec<String>,
+    pub name: String,
+    pub pretty_name: String,
+    pub version: Option<String>,
+    pub version_id: Option<String>,
+    pub version_codename: Option<String>,
+    pub variant_id: Option<String>,
+    pub build_id: Option<String>,
+    /// Every variable in file order, including the ones without a field.
+    pub variables: Vec<(String, String)>,
+}
+
+impl Default for OsRelease {
+    fn default() -> Self {
+        OsRelease {
+            id: "linux".to_owned(),
+            id_like: Vec::new(),
+            name: "Linux".to_owned(),
+            pretty_name: "Linux".to_owned(),
+            version: None,
+            version_id: None,
+            version_codename: None,
+            variant_id: None,
+            build_id: None,
+            variables: Vec::new(),
+        }
+    }
+}
+
+impl OsRelease {
+    pub fn get(&self, name: &str) -> Option<&str> {
+        self.variables
+            .iter()
+            .rev()
+            .find(|(variable, _)| variable == name)
+            .map(|(_, value)| value.as_str())
+    }
+
+    /// Whether the system is the distribution or derives from it, like `id_like_contains("debian")` on Ubuntu.
+    pub fn id_like_contains(&self, id: &str) -> bool {
+        self.id == id || self.id_like.iter().any(|like| like == id)
+    }
+
+    /// Compares `VERSION_ID` with the version, `None` when the system has no `VERSION_ID`.
+    pub fn version_cmp(&self, version: &str) -> Option<Ordering> {
+        Some(compare_versions(self.version_id.as_deref()?, version))
+    }
+
+    /// Whether `VERSION_ID` is the version or a later one, false without `VERSION_ID`.
+    pub fn version_at_least(&self, version: &str) -> bool {
+        self.version_cmp(version).is_some_and(Ordering::is_ge)
+    }
+
+    fn from_variables(variables: Vec<(String, String)>) -> Self {
+        let mut release = OsRelease::default();
+
+        for (name, value) in &variables {
+            match name.as_str() {
+                "ID" => release.id = value.clone(),
+                "ID_LIKE" => release.id_like = value.split_whitespace().map(String::from).collect(),
+                "NAME" => release.name = value.clone(),
+                "PRETTY_NAME" => release.pretty_name = value.clone(),
+                "VERSION" => release.version = Some(value.clone()),
+                "VERSION_ID" => release.version_id = Some(value.clone()),
+                "VERSION_CODENAME" => release.version_codename = Some(value.clone()),
+                "VARIANT_ID" => release.variant_id = Some(value.clone()),
+                "BUILD_ID" => release.build_id = Some(value.clone()),
+                _ => {}
+            }
+        }
+
+        release.variables = variables;
+        release
+    }
+}
+
+///
+/// Distribution identification merged from os-release and the legacy lsb-release,
+/// os-release wins where both have a value.
+///
+/// ```no_run
+/// let distro = unixism::osrelease::distro().unwrap();
+///
+/// println!("{} {:?} ({})", distro.id, distro.version_id, distro.description);
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Distro {
+    pub id: String,
+    pub id_like: Vec<String>,
+    pub name: String,
+    pub version_id: Option<String>,
+    pub codename: Option<String>,
+    pub description: String,
+}
+
+impl Distro {
+    pub fn new(release: Option<&OsRelease>, lsb: Option<&LsbRelease>) -> Self {
+        let release_value = |name: &str| release.and_then(|release| release.get(name));
+        let lsb_value = |name: &str| lsb.and_then(|lsb| lsb.get(name));
+
+        let name = release_value("NAME")
+            .or_else(|| lsb_value("DISTRIB_ID"))
+            .unwrap_or("Linux")
+            .to_owned();
+
+        Distro {
+            id: release_value("ID")
+                .map(String::from)
+                .or_else(|| lsb_value("DISTRIB_ID").map(str::to_lowercase))
+                .unwrap_or_else(|| "linux".to_owned()),
+            id_like: release
+                .map(|release| release.id_like.clone())
+                .unwrap_or_default(),
+            version_id: release_value("VERSION_ID")
+                .or_else(|| lsb_value("DISTRIB_RELEASE"))
+                .map(String::from),
+            codename: release_value("VERSION_CODENAME")
+                .or_else(|| lsb_value("DISTRIB_CODENAME"))
+                .map(String::from),
+            description: release_value("PRETTY_NAME")
+                .or_else(|| lsb_value("DISTRIB_DESCRIPTION"))
+                .map(String::from)
+                .unwrap_or_else(|| name.clone()),
+            name,
+        }
+    }
+
+    pub fn id_like_contains(&self, id: &str) -> bool {
+        self.id == id || self.id_like.iter().any(|like| like == id)
+    }
+
+    pub fn version_cmp(&self, version: &str) -> Option<Ordering> {
+        Some(compare_versions(self.version_id.as_deref()?, version))
+    }
+
+    pub fn version_at_least(&self, version: &str) -> bool {
+        self.version_cmp(version).is_some_and(Ordering::is_ge)
+    }
+}
+
+///
+/// Compares dotted versions like `22.04` and `9.10` segment by segment,
+/// numbers numerically and the rest lexically. Missing segments are lower, so `9` < `9.0`.
+///
+/// ```
+/// use std::cmp::Ordering;
+///
+/// assert_eq!(Ordering::Less, unixism::osrelease::compare_versions("9.2", "9.10"));
+/// ```
+pub fn compare_versions(a: &str, b: &str) -> Ordering {
+    let mut a = a.split(['.', '-', '_']);
+    let mut b = b.split(['.', '-', '_']);
+
+    loop {
+        let ordering = match (a.next(), b.next()) {
+            (None, None) => return Ordering::Equal,
+            (None, Some(_)) => return Ordering::Less,
+            (Some(_), None) => return Ordering::Greater,
+            (Some(a), Some(b)) => match (a.parse::<u64>(), b.parse::<u64>()) {
+                (Ok(a), Ok(b)) => a.cmp(&b),
+                _ => a.cmp(b),
+            },
+        };
+
+        if ordering.is_ne() {
+            return ordering;
+        }
+    }
+}
+
+#[derive(Debug)]
+pub enum ParseOsReleaseError {
+    InvalidEntry(String),
+    IOError(io::Error),
+}
+
+impl error::Error for ParseOsReleaseError {}
+
+impl fmt::Display for ParseOsReleaseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::InvalidEntry(entry) => write!(f, "invalid entry: {}", entry),
+            Self::IOError(error) => write!(f, "{}", error),
+        }
+    }
+}
+
+impl From<ShellVarsError> for ParseOsReleaseError {
+    fn from(value: ShellVarsError) -> Self {
+        match value {
+            ShellVarsError::InvalidEntry(entry) => ParseOsReleaseError::InvalidEntry(entry),
+            ShellVarsError::IOError(error) => ParseOsReleaseError::IOError(error),
+        }
+    }
+}
+
+impl From<io::Error> for ParseOsReleaseError {
+    fn from(value: io::Error) -> Self {
+        ParseOsReleaseError::IOError(value)
+    }
+}
+
+///
+/// ```no_run
+/// let release = unixism::osrelease::parse(std::fs::File::open("/etc/os-release").unwrap()).unwrap();
+///
+/// println!("{} {:?}", release.pretty_name, release.version_id);
+/// ```
+pub fn parse<R>(reader: R) -> Result<OsRelease, ParseOsReleaseError>
+where
+    R: io::Read,
+{
+    Ok(OsRelease::from_variables(
+        shellvars::parse(reader)?.variables,
+    ))
+}
+
+///
+/// Parses the first of `paths` which exists.
+///
+/// ```no_run
+/// let release = unixism::osrelease::load_at(&["/mnt/etc/os-release", "/mnt/usr/lib/os-release"]).unwrap();
+/// ```
+pub fn load_at<P>(paths: &[P]) -> Result<OsRelease, ParseOsReleaseError>
+where
+    P: AsRef<Path>,
+{
+    let mut error = io::Error::from(io::ErrorKind::NotFound);
+
+    for path in paths {
+        match fs::File::open(path) {
+            Ok(file) => return parse(file),
+            Err(e) if e.kind() == io::ErrorKind::NotFound => error = e,
+            Err(e) => return Err(e.into()),
+        }
+    }
+
+    Err(error.into())
+}
+
+///
+/// Same as load_at, but reads `/etc/os-release` falling back to `/usr/lib/os-release`.
+///
+/// ```no_run
+/// let release = unixism::osrelease::parse_default().unwrap();
+///
+/// if release.id_like_contains("debian") && release.version_at_least("11") {
+///     println!("apt is recent enough");
+/// }
+/// ```
+pub fn parse_default() -> Result<OsRelease, ParseOsReleaseError> {
+    load_at(&PATHS)
+}
+
+///
+/// Merges the first existing of `paths` with the lsb-release at `lsb_path`, either may be missing but not both.
+///
+/// ```no_run
+/// let distro = unixism::osrelease::distro_at(&["/mnt/etc/os-release"], "/mnt/etc/lsb-release").unwrap();
+/// ```
+pub fn distro_at<P, L>(paths: &[P], lsb_path: L) -> Result<Distro, ParseOsReleaseError>
+where
+    P: AsRef<Path>,
+    L: AsRef<Path>,
+{
+    let release = match load_at(paths) {
+        Ok(release) => Some(release),
+        Err(ParseOsReleaseError::IOError(e)) if e.kind() == io::ErrorKind::NotFound => None,
+        Err(e) => return Err(e),
+    };
+
+    let lsb = match fs::File::open(lsb_path) {
+        Ok(file) => Some(lsb::parse(file)?),
+        Err(e) if e.kind() == io::ErrorKind::NotFound && release.is_some() => None,
+        Err(e) => return Err(e.into()),
+    };
+
+    Ok(Distro::new(release.as_ref(), lsb.as_ref()))
+}
+
+///
+/// Same as distro_at, but reads the default os-release paths and `/etc/lsb-release`.
+///
+/// ```no_run
+/// let distro = unixism::osrelease::distro().unwrap();
+/// ```
+pub fn distro() -> Result<Distro, ParseOsReleaseError> {
+    distro_at(&PATHS, "/etc/lsb-release")
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Cursor;
+
+    use super::*;
+    use crate::tempdir::TempDir;
+
+    #[test]
+    fn it_parse() {
+        let release = parse(Cursor::new(
+            r#"
+PRETTY_NAME="Ubuntu 22.04.3 LTS"
+NAME="Ubuntu"
+VERSION_ID="22.04"
+VERSION="22.04.3 LTS (Jammy Jellyfish)"
+VERSION_CODENAME=jammy
+ID=ubuntu
+ID_LIKE=debian
+HOME_URL="https://www.ubuntu.com/"
+# comment
+SUPPORT_URL='https://help.ubuntu.com/'
+ESCAPED="a \"quoted\" \$value"
+        "#,
+        ))
+        .unwrap();
+
+        assert_eq!("ubuntu", release.id);
+        assert_eq!(vec!["debian"], release.id_like);
+        assert_eq!("Ubuntu 22.04.3 LTS", release.pretty_name);
+        assert_eq!(Some("22.04"), release.version_id.as_deref());
+        assert_eq!(Some("jammy"), release.version_codename.as_deref());
+        assert_eq!(Some("https://help.ubuntu.com/"), release.get("SUPPORT_URL"));
+        assert_eq!(Some("a \"quoted\" $value"), release.get("ESCAPED"));
+        assert!(release.id_like_contains("debian"));
+        assert!(release.id_like_contains("ubuntu"));
+        assert!(!release.id_like_contains("rhel"));
+        assert!(release.version_at_least("20.04"));
+        assert!(release.version_at_least("22.04"));
+        assert!(!release.version_at_least("22.10"));
+        assert_eq!(Some(Ordering::Greater), release.version_cmp("9"));
+
+        let empty = parse(Cursor::new("")).unwrap();
+        assert_eq!("linux", empty.id);
+        assert_eq!("Linux", empty.pretty_name);
+        assert_eq!(None, empty.version_cmp("1"));
+        assert!(!empty.version_at_least("1"));
+
+        assert!(parse(Cursor::new("NAME=\"unterminated")).is_err());
+        assert!(parse(Cursor::new("NO VALUE")).is_err());
+    }
+
+    #[test]
+    fn it_compare_versions() {
+        assert_eq!(Ordering::Less, compare_versions("9.2", "9.10"));
+        assert_eq!(Ordering::Equal, compare_versions("8.6", "8.6"));
+        assert_eq!(Ordering::Less, compare_versions("9", "9.0"));
+        assert_eq!(Ordering::Greater, compare_versions("40", "39"));
+        assert_eq!(Ordering::Greater, compare_versions("3.18.4", "3.18"));
+        assert_eq!(Ordering::Less, compare_versions("rolling", "tumbleweed"));
+    }
+
+    #[test]
+    fn it_load_at() {
+        let root = TempDir::new("osrelease");
+        fs::write(root.join("usr-os-release"), "ID=fedora\nVERSION_ID=39\n").unwrap();
+
+        let release = load_at(&[root.join("etc-os-release"), root.join("usr-os-release")]).unwrap();
+        assert_eq!("fedora", release.id);
+
+        assert!(load_at(&[root.join("missing")]).is_err());
+    }
+
+    #[test]
+    fn it_distro_at() {
+        let root = TempDir::new("distro");
+        fs::write(
+            root.join("lsb-release"),
+            "DISTRIB_ID=Ubuntu\nDISTRIB_RELEASE=14.04\nDISTRIB_CODENAME=trusty\nDISTRIB_DESCRIPTION=\"Ubuntu 14.04.6 LTS\"\n",
+        )
+        .unwrap();
+
+        let distro = distro_at(&[root.join("os-release")], root.join("lsb-release")).unwrap();
+        assert_eq!("ubuntu", distro.id);
+        assert_eq!("Ubuntu", distro.name);
+        assert_eq!(Some("trusty"), distro.codename.as_deref());
+        assert_eq!("Ubuntu 14.04.6 LTS", distro.description);
+        assert!(distro.version_at_least("12.04"));
+
+        fs::write(
+            root.join("os-release"),
+            "ID=linuxmint\nID_LIKE=\"ubuntu debian\"\nNAME=\"Linux Mint\"\nVERSION_ID=\"21.2\"\n",
+        )
+        .unwrap();
+
+        let distro = distro_at(&[root.join("os-release")], root.join("lsb-release")).unwrap();
+        assert_eq!("linuxmint", distro.id);
+        assert_eq!("Linux Mint", distro.name);
+        assert_eq!("Ubuntu 14.04.6 LTS", distro.description);
+        assert_eq!(Some("21.2"), distro.version_id.as_deref());
+        assert_eq!(Some("trusty"), distro.codename.as_deref());
+        assert!(distro.id_like_contains("debian"));
+
+        let distro = distro_at(&[root.join("os-release")], root.join("missing")).unwrap();
+        assert_eq!(None, distro.codename);
+
+        assert!(distro_at(&[root.join("missing")], root.join("missing")).is_err());
+    }
+}
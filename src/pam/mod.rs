@@ -0,0 +1,611 @@
+use std::{
+    error, fmt, fs,
+    io::{self, BufRead, BufReader},
+    path::Path,
+    str::FromStr,
+};
+
+/// Directories pam.d files are looked for in, the first having a service wins.
+pub const CONFIG_DIRS: [&str; 2] = ["/etc/pam.d", "/usr/lib/pam.d"];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ModuleType {
+    Auth,
+    Account,
+    Password,
+    Session,
+}
+
+impl FromStr for ModuleType {
+    type Err = ParsePamError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_ascii_lowercase().as_str() {
+            "auth" => Ok(ModuleType::Auth),
+            "account" => Ok(ModuleType::Account),
+            "password" => Ok(ModuleType::Password),
+            "session" => Ok(ModuleType::Session),
+            _ => Err(ParsePamError::InvalidEntry(s.to_owned())),
+        }
+    }
+}
+
+impl fmt::Display for ModuleType {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ModuleType::Auth => write!(f, "auth"),
+            ModuleType::Account => write!(f, "account"),
+            ModuleType::Password => write!(f, "password"),
+            ModuleType::Session => write!(f, "session"),
+        }
+    }
+}
+
+/// What happens to the stack on a module return value, of the bracketed control syntax.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Action {
+    Ignore,
+    Bad,
+    Die,
+    Ok,
+    Done,
+    Reset,
+    /// Skips the next N modules of the stack.
+    Jump(u32),
+}
+
+impl FromStr for Action {
+    type Err = ParsePamError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_ascii_lowercase().as_str() {
+            "ignore" => Ok(Action::Ignore),
+            "bad" => Ok(Action::Bad),
+            "die" => Ok(Action::Die),
+            "ok" => Ok(Action::Ok),
+            "done" => Ok(Action::Done),
+            "reset" => Ok(Action::Reset),
+            jump => jump
+                .parse()
+                .map(Action::Jump)
+                .map_err(|_| ParsePamError::InvalidEntry(s.to_owned())),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Control {
+    Required,
+    Requisite,
+    Sufficient,
+    Optional,
+    /// Includes the lines of the same type of the service named by the module path.
+    Include,
+    /// Same as include, but the included stack is evaluated on its own.
+    Substack,
+    /// The `[value=action ...]` syntax, in order.
+    Values(Vec<(String, Action)>),
+}
+
+impl FromStr for Control {
+    type Err = ParsePamError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        if let Some(values) = s.strip_prefix('[').and_then(|s| s.strip_suffix(']')) {
+            return values
+                .split_whitespace()
+                .map(|value| {
+                    let (value, action) = value
+                        .split_once('=')
+                        .ok_or_else(|| ParsePamError::InvalidEntry(s.to_owned()))?;
+
+                    Ok((value.to_ascii_lowercase(), action.parse()?))
+                })
+                .collect::<Result<Vec<_>, _>>()
+                .map(Control::Values);
+        }
+
+        match s.to_ascii_lowercase().as_str() {
+            "required" => Ok(Control::Required),
+            "requisite" => Ok(Control::Requisite),
+            "sufficient" => Ok(Control::Sufficient),
+            "optional" => Ok(Control::Optional),
+            "include" => Ok(Control::Include),
+            "substack" => Ok(Control::Substack),
+            _ => Err(ParsePamError::InvalidEntry(s.to_owned())),
+        }
+    }
+}
+
+/// A line of a pam.d file or of `/etc/pam.conf`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Rule {
+    /// The service of a pam.conf line, or the pam.d file a flattened rule comes from.
+    pub service: Option<String>,
+    pub kind: ModuleType,
+    /// Whether the type was prefixed with `-`, which skips the line silently when the module is missing.
+    pub silent: bool,
+    pub control: Control,
+    /// The module path, or the service of an include or substack.
+    pub module: String,
+    pub args: Vec<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Entry {
+    Rule(Rule),
+    /// `@include`, the Debian way of including every line of another service.
+    Include(String),
+}
+
+/// A line of a flattened stack.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum StackEntry {
+    Rule(Rule),
+    /// A `substack` line with the flattened lines of its service. They are kept apart because
+    /// jumps, `done` and `die` inside a substack only end the substack, not the whole stack.
+    Substack {
+        rule: Rule,
+        entries: Vec<StackEntry>,
+    },
+}
+
+impl StackEntry {
+    /// The rule, or the `substack` line itself.
+    pub fn rule(&self) -> &Rule {
+        match self {
+            StackEntry::Rule(rule) | StackEntry::Substack { rule, .. } => rule,
+        }
+    }
+}
+
+/// Splits the line into fields, keeping the brackets of the `[ ... ]` ones which may contain spaces.
+fn fields(line: &str) -> Option<Vec<String>> {
+    let mut fields = Vec::new();
+    let mut chars = line.chars().peekable();
+
+    while let Some(&c) = chars.peek() {
+        if c.is_whitespace() {
+            chars.next();
+            continue;
+        }
+
+        let mut field = String::new();
+
+        if c == '[' {
+            chars.next();
+            field.push('[');
+
+            loop {
+                match chars.next()? {
+                    '\\' if chars.peek() == Some(&']') => field.push(chars.next()?),
+                    ']' => break,
+                    c => field.push(c),
+                }
+            }
+
+            field.push(']');
+        } else {
+            while let Some(&c) = chars.peek() {
+                if c.is_whitespace() {
+                    break;
+                }
+
+                field.push(c);
+                chars.next();
+            }
+        }
+
+        fields.push(field);
+    }
+
+    Some(fields)
+}
+
+fn parse_rule(service: Option<String>, fields: &[String]) -> Result<Rule, ParsePamError> {
+    let invalid = || ParsePamError::InvalidEntry(fields.join(" "));
+
+    let [kind, control, module, args @ ..] = fields else {
+        return Err(invalid());
+    };
+
+    let (kind, silent) = match kind.strip_prefix('-') {
+        Some(kind) => (kind, true),
+        None => (kind.as_str(), false),
+    };
+
+    Ok(Rule {
+        service,
+        kind: kind.parse()?,
+        silent,
+        control: control.parse()?,
+        module: module.clone(),
+        args: args
+            .iter()
+            .map(|arg| {
+                arg.strip_prefix('[')
+                    .and_then(|arg| arg.strip_suffix(']'))
+                    .unwrap_or(arg)
+                    .to_owned()
+            })
+            .collect(),
+    })
+}
+
+fn parse_lines<R>(reader: R, conf: bool) -> Result<Vec<Entry>, ParsePamError>
+where
+    R: io::Read,
+{
+    let mut entries = Vec::new();
+    let mut pending = String::new();
+
+    for line in BufReader::new(reader).lines() {
+        let line = line?;
+        let line = line.split('#').next().unwrap_or_default();
+
+        if let Some(line) = line.strip_suffix('\\') {
+            pending.push_str(line);
+            pending.push(' ');
+            continue;
+        }
+
+        pending.push_str(line);
+        let line = std::mem::take(&mut pending);
+
+        let fields = fields(&line).ok_or_else(|| ParsePamError::InvalidEntry(line.clone()))?;
+
+        match &fields[..] {
+            [] => {}
+            [include, service] if include == "@include" && !conf => {
+                entries.push(Entry::Include(service.clone()))
+            }
+            [service, rule @ ..] if conf => {
+                entries.push(Entry::Rule(parse_rule(Some(service.clone()), rule)?))
+            }
+            rule => entries.push(Entry::Rule(parse_rule(None, rule)?)),
+        }
+    }
+
+    Ok(entries)
+}
+
+#[derive(Debug)]
+pub enum ParsePamError {
+    InvalidEntry(String),
+    /// A service including itself, the chain of services.
+    Cycle(Vec<String>),
+    IOError(io::Error),
+}
+
+impl error::Error for ParsePamError {}
+
+impl fmt::Display for ParsePamError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::InvalidEntry(entry) => write!(f, "invalid entry: {}", entry),
+            Self::Cycle(services) => write!(f, "include cycle: {}", services.join(" -> ")),
+            Self::IOError(error) => write!(f, "{}", error),
+        }
+    }
+}
+
+impl From<io::Error> for ParsePamError {
+    fn from(value: io::Error) -> Self {
+        ParsePamError::IOError(value)
+    }
+}
+
+///
+/// Parses a pam.d service file.
+///
+/// ```no_run
+/// let entries = unixism::pam::parse(std::fs::File::open("/etc/pam.d/sshd").unwrap()).unwrap();
+///
+/// for entry in entries {
+///     println!("{:?}", entry);
+/// }
+/// ```
+pub fn parse<R>(reader: R) -> Result<impl Iterator<Item = Entry>, ParsePamError>
+where
+    R: io::Read,
+{
+    Ok(parse_lines(reader, false)?.into_iter())
+}
+
+///
+/// Parses `/etc/pam.conf`, where every line starts with the service it belongs to.
+///
+/// ```no_run
+/// let entries = unixism::pam::parse_conf(std::fs::File::open("/etc/pam.conf").unwrap()).unwrap();
+/// ```
+pub fn parse_conf<R>(reader: R) -> Result<impl Iterator<Item = Entry>, ParsePamError>
+where
+    R: io::Read,
+{
+    Ok(parse_lines(reader, true)?.into_iter())
+}
+
+/// Resolves includes and substacks of the service, tagging each rule with the service it comes from.
+fn flatten<F>(
+    service: &str,
+    kind: Option<ModuleType>,
+    load: &F,
+    path: &mut Vec<String>,
+    stack: &mut Vec<StackEntry>,
+) -> Result<(), ParsePamError>
+where
+    F: Fn(&str) -> Result<Vec<Entry>, ParsePamError>,
+{
+    if path.iter().any(|included| included == service) {
+        let mut cycle = path.clone();
+        cycle.push(service.to_owned());
+
+        return Err(ParsePamError::Cycle(cycle));
+    }
+
+    path.push(service.to_owned());
+
+    for entry in load(service)? {
+        match entry {
+            Entry::Include(included) => flatten(&included, kind, load, path, stack)?,
+            Entry::Rule(rule) if kind.is_some_and(|kind| kind != rule.kind) => {}
+            Entry::Rule(rule) if rule.control == Control::Include => {
+                flatten(&rule.module, Some(rule.kind), load, path, stack)?
+            }
+            Entry::Rule(rule) if rule.control == Control::Substack => {
+                let mut entries = Vec::new();
+                flatten(&rule.module, Some(rule.kind), load, path, &mut entries)?;
+
+                stack.push(StackEntry::Substack {
+                    rule: Rule {
+                        service: Some(service.to_owned()),
+                        ..rule
+                    },
+                    entries,
+                });
+            }
+            Entry::Rule(rule) => stack.push(StackEntry::Rule(Rule {
+                service: Some(service.to_owned()),
+                ..rule
+            })),
+        }
+    }
+
+    path.pop();
+
+    Ok(())
+}
+
+///
+/// Returns the flattened stack of the service: `include` and `@include` lines are replaced by the lines
+/// of the same type of the named services, and every rule carries the service it comes from.
+/// A `substack` line is kept with the flattened lines of its service nested under it.
+/// A service looked for is read from the first of `dirs` having it, absolute includes are read as is.
+///
+/// ```no_run
+/// use unixism::pam::{self, ModuleType};
+///
+/// let stack = pam::stack_at(&["/etc/pam.d", "/usr/lib/pam.d"], "sshd").unwrap();
+///
+/// for rule in stack.iter().map(|entry| entry.rule()).filter(|rule| rule.kind == ModuleType::Auth) {
+///     println!("{:?} {} {:?}", rule.control, rule.module, rule.args);
+/// }
+/// ```
+pub fn stack_at<P>(dirs: &[P], service: &str) -> Result<Vec<StackEntry>, ParsePamError>
+where
+    P: AsRef<Path>,
+{
+    let load = |service: &str| {
+        let path = match Path::new(service) {
+            path if path.is_absolute() => path.to_path_buf(),
+            _ => dirs
+                .iter()
+                .map(|dir| dir.as_ref().join(service))
+                .find(|path| path.exists())
+                .ok_or_else(|| io::Error::new(io::ErrorKind::NotFound, service.to_owned()))?,
+        };
+
+        parse_lines(fs::File::open(path)?, false)
+    };
+
+    let mut stack = Vec::new();
+    flatten(service, None, &load, &mut Vec::new(), &mut stack)?;
+
+    Ok(stack)
+}
+
+///
+/// Same as stack_at, but reads the services of the default pam.d directories.
+///
+/// ```no_run
+/// let rules = unixism::pam::stack("login").unwrap();
+/// ```
+pub fn stack(service: &str) -> Result<Vec<StackEntry>, ParsePamError> {
+    stack_at(&CONFIG_DIRS, service)
+}
+
+///
+/// Same as stack_at, but takes the services from the lines of a parsed pam.conf.
+///
+/// ```no_run
+/// let entries = unixism::pam::parse_conf(std::fs::File::open("/etc/pam.conf").unwrap()).unwrap().collect::<Vec<_>>();
+///
+/// let rules = unixism::pam::stack_conf(&entries, "login").unwrap();
+/// ```
+pub fn stack_conf(entries: &[Entry], service: &str) -> Result<Vec<StackEntry>, ParsePamError> {
+    let load = |service: &str| {
+        Ok(entries
+            .iter()
+            .filter(|entry| {
+                matches!(entry, Entry::Rule(rule) if rule.service.as_deref() == Some(service))
+            })
+            .cloned()
+            .collect())
+    };
+
+    let mut stack = Vec::new();
+    flatten(service, None, &load, &mut Vec::new(), &mut stack)?;
+
+    Ok(stack)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Cursor;
+
+    use super::*;
+    use crate::tempdir::TempDir;
+
+    #[test]
+    fn it_parse() {
+        let entries = parse(Cursor::new(
+            r#"
+#%PAM-1.0
+auth	[success=1 default=ignore]	pam_unix.so nullok # comment
+auth	requisite			pam_deny.so
+-session optional pam_systemd.so
+account required pam_access.so \
+    accessfile=/etc/security/access-local.conf
+session required pam_exec.so [quiet log=/tmp/a\]b]
+password include common-password
+@include common-session
+        "#,
+        ))
+        .unwrap()
+        .collect::<Vec<_>>();
+
+        assert_eq!(7, entries.len());
+        assert_eq!(
+            Entry::Rule(Rule {
+                service: None,
+                kind: ModuleType::Auth,
+                silent: false,
+                control: Control::Values(vec![
+                    ("success".to_owned(), Action::Jump(1)),
+                    ("default".to_owned(), Action::Ignore),
+                ]),
+                module: "pam_unix.so".to_owned(),
+                args: vec!["nullok".to_owned()],
+            }),
+            entries[0]
+        );
+
+        let Entry::Rule(rule) = &entries[2] else {
+            panic!("not a rule: {:?}", entries[2]);
+        };
+        assert!(rule.silent);
+        assert_eq!(ModuleType::Session, rule.kind);
+
+        let Entry::Rule(rule) = &entries[3] else {
+            panic!("not a rule: {:?}", entries[3]);
+        };
+        assert_eq!(
+            vec!["accessfile=/etc/security/access-local.conf"],
+            rule.args
+        );
+
+        let Entry::Rule(rule) = &entries[4] else {
+            panic!("not a rule: {:?}", entries[4]);
+        };
+        assert_eq!(vec!["quiet log=/tmp/a]b"], rule.args);
+
+        assert_eq!(Entry::Include("common-session".to_owned()), entries[6]);
+
+        assert!(parse(Cursor::new("auth required")).is_err());
+        assert!(parse(Cursor::new("login required pam_unix.so")).is_err());
+        assert!(parse(Cursor::new("auth [success=maybe] pam_unix.so")).is_err());
+        assert!(parse(Cursor::new("auth [success=ok pam_unix.so")).is_err());
+    }
+
+    #[test]
+    fn it_stack_at() {
+        let root = TempDir::new("pam");
+        let (etc, usr) = (root.join("etc"), root.join("usr"));
+        fs::create_dir_all(&etc).unwrap();
+        fs::create_dir_all(&usr).unwrap();
+
+        fs::write(
+            etc.join("sshd"),
+            "auth substack password-auth\naccount required pam_nologin.so\n@include common-session\n",
+        )
+        .unwrap();
+        fs::write(
+            usr.join("password-auth"),
+            "auth required pam_env.so\nauth include system-auth\naccount required pam_unix.so\n",
+        )
+        .unwrap();
+        fs::write(usr.join("system-auth"), "auth sufficient pam_unix.so\n").unwrap();
+        fs::write(
+            etc.join("common-session"),
+            "session required pam_limits.so\n",
+        )
+        .unwrap();
+        fs::write(
+            usr.join("common-session"),
+            "session required pam_vendor.so\n",
+        )
+        .unwrap();
+
+        let stack = stack_at(&[&etc, &usr], "sshd").unwrap();
+        let services = |stack: &[StackEntry]| {
+            stack
+                .iter()
+                .map(|entry| {
+                    let rule = entry.rule();
+                    (rule.service.clone().unwrap(), rule.module.clone())
+                })
+                .collect::<Vec<_>>()
+        };
+
+        assert_eq!(
+            vec![
+                ("sshd".to_owned(), "password-auth".to_owned()),
+                ("sshd".to_owned(), "pam_nologin.so".to_owned()),
+                ("common-session".to_owned(), "pam_limits.so".to_owned()),
+            ],
+            services(&stack)
+        );
+
+        let StackEntry::Substack { rule, entries } = &stack[0] else {
+            panic!("not a substack: {:?}", stack[0]);
+        };
+        assert_eq!(Control::Substack, rule.control);
+        assert_eq!(
+            vec![
+                ("password-auth".to_owned(), "pam_env.so".to_owned()),
+                ("system-auth".to_owned(), "pam_unix.so".to_owned()),
+            ],
+            services(entries)
+        );
+
+        fs::write(usr.join("system-auth"), "auth include password-auth\n").unwrap();
+        assert!(matches!(
+            stack_at(&[&etc, &usr], "sshd"),
+            Err(ParsePamError::Cycle(cycle)) if cycle.len() == 4
+        ));
+    }
+
+    #[test]
+    fn it_stack_conf() {
+        let entries = parse_conf(Cursor::new(
+            r#"
+login auth include other
+login account required pam_unix.so
+other auth required pam_deny.so
+other account required pam_deny.so
+        "#,
+        ))
+        .unwrap()
+        .collect::<Vec<_>>();
+
+        let stack = stack_conf(&entries, "login").unwrap();
+        assert_eq!(
+            vec!["pam_deny.so", "pam_unix.so"],
+            stack
+                .iter()
+                .map(|entry| entry.rule().module.as_str())
+                .collect::<Vec<_>>()
+        );
+        assert_eq!(Some("other"), stack[0].rule().service.as_deref());
+    }
+}
@@ -0,0 +1,581 @@
+use std::{
+    collections::HashMap,
+    error, fmt, fs,
+    io::{self, BufRead, BufReader},
+    num::ParseIntError,
+    ops::RangeInclusive,
+    path::{Path, PathBuf},
+    str::FromStr,
+};
+
+pub use crate::{
+    compat::{Compat, CompatTarget},
+    file::PasswdLock as Lock,
+};
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Entry {
+    pub name: String,
+    pub passwd: String,
+    pub uid: u32,
+    pub gid: u32,
+    pub gecos: String,
+    pub home: String,
+    pub shell: String,
+}
+
+impl FromStr for Entry {
+    type Err = ParsePasswdError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let fields = s.split(':').collect::<Vec<_>>();
+
+        let [name, passwd, uid, gid, gecos, home, shell] = fields[..] else {
+            return Err(ParsePasswdError::InvalidEntry(s.to_owned()));
+        };
+
+        Ok(Entry {
+            name: name.to_owned(),
+            passwd: passwd.to_owned(),
+            uid: uid.parse()?,
+            gid: gid.parse()?,
+            gecos: gecos.to_owned(),
+            home: home.to_owned(),
+            shell: shell.to_owned(),
+        })
+    }
+}
+
+impl Entry {
+    /// Checks the entry can be written as a single line, without a `:` or a newline in a field.
+    fn validate(&self) -> Result<(), ParsePasswdError> {
+        let fields = [
+            &self.name,
+            &self.passwd,
+            &self.gecos,
+            &self.home,
+            &self.shell,
+        ];
+
+        if !crate::compat::valid_name(&self.name)
+            || fields.iter().any(|field| field.contains([':', '\n']))
+        {
+            return Err(ParsePasswdError::InvalidEntry(
+                self.to_string().escape_debug().to_string(),
+            ));
+        }
+
+        Ok(())
+    }
+}
+
+impl fmt::Display for Entry {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{}:{}:{}:{}:{}:{}:{}",
+            self.name, self.passwd, self.uid, self.gid, self.gecos, self.home, self.shell
+        )
+    }
+}
+
+#[derive(Debug)]
+pub enum ParsePasswdError {
+    InvalidEntry(String),
+    DuplicateEntry(String),
+    ParseIntError(ParseIntError),
+    IOError(io::Error),
+}
+
+impl error::Error for ParsePasswdError {}
+
+impl fmt::Display for ParsePasswdError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::InvalidEntry(entry) => write!(f, "invalid passwd entry: {}", entry),
+            Self::DuplicateEntry(name) => write!(f, "user {} already exists", name),
+            Self::ParseIntError(error) => write!(f, "{}", error),
+            Self::IOError(error) => write!(f, "{}", error),
+        }
+    }
+}
+
+impl From<ParseIntError> for ParsePasswdError {
+    fn from(value: ParseIntError) -> Self {
+        ParsePasswdError::ParseIntError(value)
+    }
+}
+
+impl From<io::Error> for ParsePasswdError {
+    fn from(value: io::Error) -> Self {
+        ParsePasswdError::IOError(value)
+    }
+}
+
+/// A line of the /etc/passwd file in glibc compat mode.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Record {
+    Entry(Entry),
+    Compat(Compat),
+}
+
+///
+/// ```no_run
+/// let entries = unixism::passwd::parse(std::fs::File::open("/etc/passwd").unwrap()).unwrap();
+///
+/// for entry in entries {
+///     println!("name: {}, uid: {}, home: {}", entry.name, entry.uid, entry.home);
+/// }
+/// ```
+pub fn parse<R>(reader: R) -> Result<impl Iterator<Item = Entry>, ParsePasswdError>
+where
+    R: io::Read,
+{
+    Ok(parse_compat(reader)?.filter_map(|record| match record {
+        Record::Entry(entry) => Some(entry),
+        Record::Compat(_) => None,
+    }))
+}
+
+///
+/// Same as parse, but keeps the NSS compat lines (`+`, `+name`, `-name`, `+@netgroup`)
+/// as typed records instead of skipping them.
+///
+/// ```no_run
+/// use unixism::passwd::{self, Compat, Record};
+///
+/// for record in passwd::parse_compat(std::fs::File::open("/etc/passwd").unwrap()).unwrap() {
+///     if let Record::Compat(Compat::Include { target, .. }) = record {
+///         println!("included from NIS: {:?}", target);
+///     }
+/// }
+/// ```
+pub fn parse_compat<R>(reader: R) -> Result<impl Iterator<Item = Record>, ParsePasswdError>
+where
+    R: io::Read,
+{
+    let mut records = Vec::new();
+
+    for line in BufReader::new(reader).lines() {
+        let line = line?;
+
+        if line.trim().is_empty() || line.starts_with('#') {
+            continue;
+        }
+
+        match Compat::parse(&line) {
+            Some(compat) => records.push(Record::Compat(compat)),
+            None => records.push(Record::Entry(line.parse::<Entry>()?)),
+        }
+    }
+
+    Ok(records.into_iter())
+}
+
+///
+/// Same as parse, but parses the `/etc/passwd` as default.
+///
+/// ```no_run
+/// let entries = unixism::passwd::parse_default().unwrap();
+/// ```
+pub fn parse_default() -> Result<impl Iterator<Item = Entry>, ParsePasswdError> {
+    parse(fs::File::open("/etc/passwd")?)
+}
+
+///
+/// Lookup index over parsed entries, an in-memory equivalent of `getpwnam` and `getpwuid`.
+/// Like libc, the first entry wins when names or ids are duplicated.
+///
+/// ```no_run
+/// let index = unixism::passwd::Index::new(unixism::passwd::parse_default().unwrap());
+///
+/// if let Some(entry) = index.by_uid(0) {
+///     println!("{}", entry.name);
+/// }
+/// ```
+#[derive(Debug, Default, Clone)]
+pub struct Index {
+    entries: Vec<Entry>,
+    by_name: HashMap<String, usize>,
+    by_uid: HashMap<u32, usize>,
+}
+
+impl Index {
+    pub fn new<I>(entries: I) -> Self
+    where
+        I: IntoIterator<Item = Entry>,
+    {
+        entries.into_iter().collect()
+    }
+
+    pub fn by_name(&self, name: &str) -> Option<&Entry> {
+        self.by_name.get(name).map(|i| &self.entries[*i])
+    }
+
+    pub fn by_uid(&self, uid: u32) -> Option<&Entry> {
+        self.by_uid.get(&uid).map(|i| &self.entries[*i])
+    }
+
+    pub fn entries(&self) -> &[Entry] {
+        &self.entries
+    }
+}
+
+///
+/// Returns the lowest UID in `range` not taken by any user in `index`.
+/// Ranges usually come from `/etc/login.defs`, see `logindefs::LoginDefs::uid_range`.
+///
+/// ```no_run
+/// use unixism::passwd;
+///
+/// let index = passwd::Index::new(passwd::parse_default().unwrap());
+///
+/// let uid = passwd::next_free_uid(&index, 1000..=60000);
+/// ```
+pub fn next_free_uid(index: &Index, range: RangeInclusive<u32>) -> Option<u32> {
+    range.into_iter().find(|uid| index.by_uid(*uid).is_none())
+}
+
+impl FromIterator<Entry> for Index {
+    fn from_iter<I>(iter: I) -> Self
+    where
+        I: IntoIterator<Item = Entry>,
+    {
+        let mut index = Index::default();
+
+        for entry in iter {
+            let i = index.entries.len();
+
+            index.by_name.entry(entry.name.clone()).or_insert(i);
+            index.by_uid.entry(entry.uid).or_insert(i);
+            index.entries.push(entry);
+        }
+
+        index
+    }
+}
+
+#[derive(Debug)]
+enum Line {
+    /// A parsed entry, `raw` keeps the original text until the entry is modified.
+    Entry {
+        raw: Option<String>,
+        entry: Entry,
+    },
+    Other(String),
+}
+
+///
+/// Edits the `/etc/passwd` under the `/etc/.pwd.lock` lock, keeping untouched lines exactly as they were.
+/// Changes are written via a temporary file and rename on save.
+///
+/// ```no_run
+/// use unixism::passwd::{Editor, Entry};
+///
+/// let mut editor = Editor::open().unwrap();
+///
+/// editor
+///     .add(Entry {
+///         name: "deploy".to_owned(),
+///         passwd: "x".to_owned(),
+///         uid: 1001,
+///         gid: 1001,
+///         gecos: "".to_owned(),
+///         home: "/home/deploy".to_owned(),
+///         shell: "/bin/sh".to_owned(),
+///     })
+///     .unwrap();
+///
+/// editor.update("deploy", |entry| entry.shell = "/bin/bash".to_owned());
+/// editor.save().unwrap();
+/// ```
+#[derive(Debug)]
+pub struct Editor {
+    path: PathBuf,
+    lines: Vec<Line>,
+    _lock: Lock,
+}
+
+impl Editor {
+    pub fn open() -> Result<Self, ParsePasswdError> {
+        Self::open_at("/etc/passwd", Lock::PATH)
+    }
+
+    pub fn open_at<P, L>(path: P, lock: L) -> Result<Self, ParsePasswdError>
+    where
+        P: AsRef<Path>,
+        L: AsRef<Path>,
+    {
+        let lock = Lock::acquire_at(lock)?;
+        let content = fs::read_to_string(path.as_ref())?;
+
+        let mut lines = Vec::new();
+        for line in content.lines() {
+            if line.trim().is_empty() || line.starts_with('#') || Compat::parse(line).is_some() {
+                lines.push(Line::Other(line.to_owned()));
+            } else {
+                lines.push(Line::Entry {
+                    entry: line.parse::<Entry>()?,
+                    raw: Some(line.to_owned()),
+                });
+            }
+        }
+
+        Ok(Self {
+            path: path.as_ref().to_owned(),
+            lines,
+            _lock: lock,
+        })
+    }
+
+    pub fn entries(&self) -> impl Iterator<Item = &Entry> {
+        self.lines.iter().filter_map(|line| match line {
+            Line::Entry { entry, .. } => Some(entry),
+            Line::Other(_) => None,
+        })
+    }
+
+    pub fn get(&self, name: &str) -> Option<&Entry> {
+        self.entries().find(|entry| entry.name == name)
+    }
+
+    pub fn add(&mut self, entry: Entry) -> Result<(), ParsePasswdError> {
+        entry.validate()?;
+
+        if self.get(&entry.name).is_some() {
+            return Err(ParsePasswdError::DuplicateEntry(entry.name));
+        }
+
+        self.lines.push(Line::Entry { raw: None, entry });
+
+        Ok(())
+    }
+
+    /// Applies `f` to the entry named `name`, returns false if there is no such entry.
+    pub fn update<F>(&mut self, name: &str, f: F) -> bool
+    where
+        F: FnOnce(&mut Entry),
+    {
+        for line in &mut self.lines {
+            if let Line::Entry { raw, entry } = line {
+                if entry.name == name {
+                    f(entry);
+                    *raw = None;
+
+                    return true;
+                }
+            }
+        }
+
+        false
+    }
+
+    pub fn remove(&mut self, name: &str) -> Option<Entry> {
+        let position = self
+            .lines
+            .iter()
+            .position(|line| matches!(line, Line::Entry { entry, .. } if entry.name == name))?;
+
+        match self.lines.remove(position) {
+            Line::Entry { entry, .. } => Some(entry),
+            Line::Other(_) => None,
+        }
+    }
+
+    pub fn save(&self) -> Result<(), ParsePasswdError> {
+        let mut content = String::new();
+
+        for line in &self.lines {
+            match line {
+                Line::Entry { raw: Some(raw), .. } | Line::Other(raw) => content.push_str(raw),
+                Line::Entry { raw: None, entry } => {
+                    // Updated entries are checked here, an invalid field would inject a line.
+                    entry.validate()?;
+                    content.push_str(&entry.to_string());
+                }
+            }
+            content.push('\n');
+        }
+
+        Ok(crate::file::write_atomic(
+            &self.path,
+            content.as_bytes(),
+            true,
+        )?)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Cursor;
+
+    use super::*;
+    use crate::tempdir::TempDir;
+
+    #[test]
+    fn it_parse() {
+        let entries = parse(Cursor::new(
+            r#"
+root:x:0:0:root:/root:/bin/bash
+nobody:x:65534:65534:nobody:/nonexistent:/usr/sbin/nologin
+kafkiansky:x:1000:1000:Kafkiansky,,,:/home/kafkiansky:/bin/zsh
+        "#,
+        ))
+        .unwrap()
+        .collect::<Vec<_>>();
+
+        assert_eq!(3, entries.len());
+        assert_eq!(
+            Entry {
+                name: "root".to_owned(),
+                passwd: "x".to_owned(),
+                uid: 0,
+                gid: 0,
+                gecos: "root".to_owned(),
+                home: "/root".to_owned(),
+                shell: "/bin/bash".to_owned(),
+            },
+            entries[0]
+        );
+        assert_eq!(65534, entries[1].uid);
+        assert_eq!("Kafkiansky,,,", entries[2].gecos);
+        assert_eq!("/bin/zsh", entries[2].shell);
+
+        assert!(parse(Cursor::new("root:x:0:0:root:/root")).is_err());
+        assert!(parse(Cursor::new("root:x:zero:0:root:/root:/bin/sh")).is_err());
+    }
+
+    #[test]
+    fn it_edit() {
+        let dir = TempDir::new("passwd");
+
+        let path = dir.join("passwd");
+        fs::write(
+            &path,
+            "root:x:0:0:root:/root:/bin/bash\n# comment\nold:x:1001:1001::/home/old:/bin/sh\n",
+        )
+        .unwrap();
+
+        let mut editor = Editor::open_at(&path, dir.join(".pwd.lock")).unwrap();
+
+        assert!(editor
+            .add(Entry {
+                name: "deploy".to_owned(),
+                passwd: "x".to_owned(),
+                uid: 1002,
+                gid: 1002,
+                gecos: "".to_owned(),
+                home: "/home/deploy".to_owned(),
+                shell: "/bin/sh".to_owned(),
+            })
+            .is_ok());
+        assert!(matches!(
+            editor.add(editor.get("root").unwrap().clone()),
+            Err(ParsePasswdError::DuplicateEntry(name)) if name == "root"
+        ));
+        assert!(matches!(
+            editor.add(Entry {
+                name: "evil".to_owned(),
+                gecos: "x\nroot2::0:0::/:/bin/sh".to_owned(),
+                ..editor.get("deploy").unwrap().clone()
+            }),
+            Err(ParsePasswdError::InvalidEntry(_))
+        ));
+        for name in ["+", "+@admins", "-root", " root"] {
+            assert!(matches!(
+                editor.add(Entry {
+                    name: name.to_owned(),
+                    ..editor.get("deploy").unwrap().clone()
+                }),
+                Err(ParsePasswdError::InvalidEntry(_))
+            ));
+        }
+        assert!(editor.update("deploy", |entry| entry.shell = "/bin/sh:x".to_owned()));
+        assert!(matches!(
+            editor.save(),
+            Err(ParsePasswdError::InvalidEntry(_))
+        ));
+        assert!(editor.update("deploy", |entry| entry.shell = "/bin/bash".to_owned()));
+        assert!(!editor.update("missing", |_| {}));
+        assert_eq!(1001, editor.remove("old").unwrap().uid);
+
+        editor.save().unwrap();
+
+        assert_eq!(
+            "root:x:0:0:root:/root:/bin/bash\n# comment\ndeploy:x:1002:1002::/home/deploy:/bin/bash\n",
+            fs::read_to_string(&path).unwrap()
+        );
+    }
+
+    #[test]
+    fn it_index() {
+        let index = parse(Cursor::new(
+            "root:x:0:0:root:/root:/bin/bash\ntoor:x:0:0::/root:/bin/sh\nnobody:x:65534:65534::/:/bin/false",
+        ))
+        .unwrap()
+        .collect::<Index>();
+
+        assert_eq!("root", index.by_uid(0).unwrap().name);
+        assert_eq!(0, index.by_name("toor").unwrap().uid);
+        assert_eq!(65534, index.by_name("nobody").unwrap().uid);
+        assert!(index.by_uid(1000).is_none());
+        assert!(index.by_name("missing").is_none());
+        assert_eq!(3, index.entries().len());
+
+        assert_eq!(Some(1), next_free_uid(&index, 0..=10));
+        assert_eq!(Some(65533), next_free_uid(&index, 65533..=65535));
+        assert_eq!(None, next_free_uid(&index, 65534..=65534));
+    }
+
+    #[test]
+    fn it_parse_compat() {
+        let input = r#"
+root:x:0:0:root:/root:/bin/bash
++@admins::::::/bin/zsh
+-baduser:::::::
++alice
++
+        "#;
+
+        let records = parse_compat(Cursor::new(input))
+            .unwrap()
+            .collect::<Vec<_>>();
+
+        assert_eq!(5, records.len());
+        assert!(matches!(&records[0], Record::Entry(entry) if entry.name == "root"));
+        assert_eq!(
+            Record::Compat(Compat::Include {
+                target: CompatTarget::Netgroup("admins".to_owned()),
+                overrides: vec![
+                    "".to_owned(),
+                    "".to_owned(),
+                    "".to_owned(),
+                    "".to_owned(),
+                    "".to_owned(),
+                    "/bin/zsh".to_owned()
+                ],
+            }),
+            records[1]
+        );
+        assert_eq!(
+            Record::Compat(Compat::Exclude(CompatTarget::Name("baduser".to_owned()))),
+            records[2]
+        );
+        assert_eq!(
+            Record::Compat(Compat::Include {
+                target: CompatTarget::Name("alice".to_owned()),
+                overrides: vec![],
+            }),
+            records[3]
+        );
+        assert_eq!(
+            Record::Compat(Compat::Include {
+                target: CompatTarget::All,
+                overrides: vec![],
+            }),
+            records[4]
+        );
+
+        assert_eq!(1, parse(Cursor::new(input)).unwrap().count());
+    }
+}
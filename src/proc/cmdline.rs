@@ -0,0 +1,184 @@
+use std::{fs, io, str::FromStr};
+
+use super::ParseProcError;
+
+/// A parameter of the kernel command line.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Param {
+    Flag(String),
+    Value { key: String, value: String },
+}
+
+impl Param {
+    pub fn key(&self) -> &str {
+        match self {
+            Param::Flag(key) | Param::Value { key, .. } => key,
+        }
+    }
+}
+
+///
+/// The kernel command line, parameters in order.
+/// Values may be double-quoted to contain spaces, the quotes are removed.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct Cmdline {
+    pub params: Vec<Param>,
+    /// Arguments after `--`, passed to init.
+    pub init_args: Vec<String>,
+}
+
+impl Cmdline {
+    /// Returns the last value of the key, as the last one wins for most parameters.
+    pub fn get(&self, key: &str) -> Option<&str> {
+        self.get_all(key).pop()
+    }
+
+    /// Returns every value of the key, for repeatable parameters like `console`.
+    pub fn get_all(&self, key: &str) -> Vec<&str> {
+        self.params
+            .iter()
+            .filter_map(|param| match param {
+                Param::Value { key: name, value } if name == key => Some(value.as_str()),
+                _ => None,
+            })
+            .collect()
+    }
+
+    pub fn has(&self, flag: &str) -> bool {
+        self.params
+            .iter()
+            .any(|param| matches!(param, Param::Flag(name) if name == flag))
+    }
+
+    pub fn root(&self) -> Option<&str> {
+        self.get("root")
+    }
+
+    /// Whether the root is mounted read-only first, the last of `ro` and `rw` wins.
+    pub fn ro(&self) -> bool {
+        self.params
+            .iter()
+            .rev()
+            .find_map(|param| match param {
+                Param::Flag(flag) if flag == "ro" => Some(true),
+                Param::Flag(flag) if flag == "rw" => Some(false),
+                _ => None,
+            })
+            .unwrap_or_default()
+    }
+
+    pub fn init(&self) -> Option<&str> {
+        self.get("init")
+    }
+
+    pub fn consoles(&self) -> Vec<&str> {
+        self.get_all("console")
+    }
+}
+
+impl FromStr for Cmdline {
+    type Err = ParseProcError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut tokens = Vec::new();
+        let mut token = None::<String>;
+        let mut quoted = false;
+
+        for c in s.chars() {
+            match c {
+                '"' => {
+                    quoted = !quoted;
+                    token.get_or_insert_with(String::new);
+                }
+                c if c.is_whitespace() && !quoted => tokens.extend(token.take()),
+                c => token.get_or_insert_with(String::new).push(c),
+            }
+        }
+
+        if quoted {
+            return Err(ParseProcError::InvalidEntry(s.to_owned()));
+        }
+
+        tokens.extend(token);
+
+        let mut cmdline = Cmdline::default();
+        let mut tokens = tokens.into_iter();
+
+        for token in tokens.by_ref() {
+            if token == "--" {
+                break;
+            }
+
+            cmdline.params.push(match token.split_once('=') {
+                Some((key, value)) => Param::Value {
+                    key: key.to_owned(),
+                    value: value.to_owned(),
+                },
+                None => Param::Flag(token),
+            });
+        }
+
+        cmdline.init_args = tokens.collect();
+
+        Ok(cmdline)
+    }
+}
+
+///
+/// ```no_run
+/// let cmdline = unixism::proc::cmdline::parse(std::fs::File::open("/proc/cmdline").unwrap()).unwrap();
+///
+/// println!("root={:?} ro={} consoles={:?}", cmdline.root(), cmdline.ro(), cmdline.consoles());
+/// ```
+pub fn parse<R>(mut reader: R) -> Result<Cmdline, ParseProcError>
+where
+    R: io::Read,
+{
+    let mut content = String::new();
+    reader.read_to_string(&mut content)?;
+
+    content.parse()
+}
+
+///
+/// Same as parse, but parses the `/proc/cmdline` as default.
+///
+/// ```no_run
+/// let cmdline = unixism::proc::cmdline::parse_default().unwrap();
+/// ```
+pub fn parse_default() -> Result<Cmdline, ParseProcError> {
+    parse(fs::File::open("/proc/cmdline")?)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Cursor;
+
+    use super::*;
+
+    #[test]
+    fn it_parse() {
+        let cmdline = parse(Cursor::new(
+            "BOOT_IMAGE=/vmlinuz-6.1.0 root=UUID=1234-abcd ro quiet console=tty0 console=ttyS0,115200n8 dyndbg=\"file drivers/* +p\" rw init=/sbin/init -- single \"emergency mode\"\n",
+        ))
+        .unwrap();
+
+        assert_eq!(9, cmdline.params.len());
+        assert_eq!(Some("UUID=1234-abcd"), cmdline.root());
+        assert_eq!(Some("/sbin/init"), cmdline.init());
+        assert!(!cmdline.ro());
+        assert!(cmdline.has("quiet"));
+        assert!(!cmdline.has("root"));
+        assert_eq!(vec!["tty0", "ttyS0,115200n8"], cmdline.consoles());
+        assert_eq!(Some("ttyS0,115200n8"), cmdline.get("console"));
+        assert_eq!(Some("file drivers/* +p"), cmdline.get("dyndbg"));
+        assert_eq!("BOOT_IMAGE", cmdline.params[0].key());
+        assert_eq!(vec!["single", "emergency mode"], cmdline.init_args);
+
+        let cmdline = "root=/dev/sda1 ro".parse::<Cmdline>().unwrap();
+        assert!(cmdline.ro());
+        assert!(cmdline.init_args.is_empty());
+
+        assert!(parse(Cursor::new("dyndbg=\"unterminated")).is_err());
+    }
+}
@@ -0,0 +1,249 @@
+use std::{
+    collections::HashSet,
+    fs,
+    io::{self, BufRead, BufReader},
+};
+
+use super::ParseProcError;
+
+/// A processor block of `/proc/cpuinfo`.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct Processor {
+    pub processor: u32,
+    pub vendor_id: Option<String>,
+    /// The `model name`, present on x86 and on ARM with recent kernels.
+    pub model_name: Option<String>,
+    pub physical_id: Option<u32>,
+    pub core_id: Option<u32>,
+    /// The `flags` on x86 or the `Features` on ARM.
+    pub flags: HashSet<String>,
+    /// Every field of the block in order.
+    pub fields: Vec<(String, String)>,
+}
+
+impl Processor {
+    pub fn get(&self, key: &str) -> Option<&str> {
+        get(&self.fields, key)
+    }
+
+    pub fn has(&self, flag: &str) -> bool {
+        self.flags.contains(flag)
+    }
+
+    fn from_fields(fields: Vec<(String, String)>) -> Result<Self, ParseProcError> {
+        let number = |key: &str| -> Result<Option<u32>, ParseProcError> {
+            Ok(get(&fields, key).map(str::parse).transpose()?)
+        };
+
+        Ok(Processor {
+            processor: number("processor")?.unwrap_or_default(),
+            vendor_id: get(&fields, "vendor_id").map(String::from),
+            model_name: get(&fields, "model name").map(String::from),
+            physical_id: number("physical id")?,
+            core_id: number("core id")?,
+            flags: get(&fields, "flags")
+                .or_else(|| get(&fields, "Features"))
+                .unwrap_or_default()
+                .split_whitespace()
+                .map(String::from)
+                .collect(),
+            fields,
+        })
+    }
+}
+
+/// The processors of `/proc/cpuinfo` with the fields outside of them, like `Hardware` on ARM.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct CpuInfo {
+    pub processors: Vec<Processor>,
+    pub fields: Vec<(String, String)>,
+}
+
+impl CpuInfo {
+    pub fn get(&self, key: &str) -> Option<&str> {
+        get(&self.fields, key)
+    }
+
+    /// Number of logical processors.
+    pub fn logical(&self) -> usize {
+        self.processors.len()
+    }
+
+    /// Number of physical packages, one when the layout doesn't report them as on most ARM systems.
+    pub fn sockets(&self) -> usize {
+        let sockets = self
+            .processors
+            .iter()
+            .filter_map(|processor| processor.physical_id)
+            .collect::<HashSet<_>>()
+            .len();
+
+        match sockets {
+            0 => usize::from(!self.processors.is_empty()),
+            sockets => sockets,
+        }
+    }
+
+    /// Number of physical cores, the logical processors when cores aren't reported.
+    pub fn cores(&self) -> usize {
+        let cores = self
+            .processors
+            .iter()
+            .filter_map(|processor| Some((processor.physical_id, processor.core_id?)))
+            .collect::<HashSet<_>>()
+            .len();
+
+        match cores {
+            0 => self.processors.len(),
+            cores => cores,
+        }
+    }
+
+    /// Whether every processor has the flag, like `avx2` on x86 or `asimd` on ARM.
+    pub fn has(&self, flag: &str) -> bool {
+        !self.processors.is_empty() && self.processors.iter().all(|processor| processor.has(flag))
+    }
+
+    pub fn model_name(&self) -> Option<&str> {
+        self.processors
+            .iter()
+            .find_map(|processor| processor.model_name.as_deref())
+    }
+}
+
+fn get<'a>(fields: &'a [(String, String)], key: &str) -> Option<&'a str> {
+    fields
+        .iter()
+        .find(|(name, _)| name == key)
+        .map(|(_, value)| value.as_str())
+}
+
+///
+/// ```no_run
+/// let cpuinfo = unixism::proc::cpuinfo::parse(std::fs::File::open("/proc/cpuinfo").unwrap()).unwrap();
+///
+/// println!("{:?}: {} sockets, {} cores, {} threads", cpuinfo.model_name(), cpuinfo.sockets(), cpuinfo.cores(), cpuinfo.logical());
+/// println!("avx2: {}", cpuinfo.has("avx2"));
+/// ```
+pub fn parse<R>(reader: R) -> Result<CpuInfo, ParseProcError>
+where
+    R: io::Read,
+{
+    let mut cpuinfo = CpuInfo::default();
+    let mut block = Vec::new();
+
+    let mut flush = |block: &mut Vec<(String, String)>| -> Result<(), ParseProcError> {
+        if block.iter().any(|(key, _)| key == "processor") {
+            cpuinfo
+                .processors
+                .push(Processor::from_fields(std::mem::take(block))?);
+        } else {
+            cpuinfo.fields.append(block);
+        }
+
+        Ok(())
+    };
+
+    for line in BufReader::new(reader).lines() {
+        let line = line?;
+
+        if line.trim().is_empty() {
+            flush(&mut block)?;
+            continue;
+        }
+
+        let (key, value) = line
+            .split_once(':')
+            .ok_or_else(|| ParseProcError::InvalidEntry(line.clone()))?;
+
+        block.push((key.trim().to_owned(), value.trim().to_owned()));
+    }
+
+    flush(&mut block)?;
+
+    Ok(cpuinfo)
+}
+
+///
+/// Same as parse, but parses the `/proc/cpuinfo` as default.
+///
+/// ```no_run
+/// let cpuinfo = unixism::proc::cpuinfo::parse_default().unwrap();
+/// ```
+pub fn parse_default() -> Result<CpuInfo, ParseProcError> {
+    parse(fs::File::open("/proc/cpuinfo")?)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Cursor;
+
+    use super::*;
+
+    #[test]
+    fn it_parse_x86() {
+        let mut content = String::new();
+
+        for (processor, physical, core) in [(0, 0, 0), (1, 0, 1), (2, 0, 0), (3, 0, 1), (4, 1, 0)] {
+            content.push_str(&format!(
+                "processor\t: {processor}\nvendor_id\t: GenuineIntel\nmodel name\t: Intel(R) Xeon(R) CPU E5-2680 v4 @ 2.40GHz\nphysical id\t: {physical}\ncore id\t\t: {core}\nflags\t\t: fpu sse2 avx2{}\n\n",
+                if processor == 4 { "" } else { " avx512f" },
+            ));
+        }
+
+        let cpuinfo = parse(Cursor::new(content)).unwrap();
+
+        assert_eq!(5, cpuinfo.logical());
+        assert_eq!(2, cpuinfo.sockets());
+        assert_eq!(3, cpuinfo.cores());
+        assert!(cpuinfo.has("avx2"));
+        assert!(!cpuinfo.has("avx512f"));
+        assert!(cpuinfo.processors[0].has("avx512f"));
+        assert_eq!(
+            Some("GenuineIntel"),
+            cpuinfo.processors[1].vendor_id.as_deref()
+        );
+        assert_eq!(
+            Some("Intel(R) Xeon(R) CPU E5-2680 v4 @ 2.40GHz"),
+            cpuinfo.model_name()
+        );
+        assert_eq!(Some(1), cpuinfo.processors[4].physical_id);
+    }
+
+    #[test]
+    fn it_parse_arm() {
+        let cpuinfo = parse(Cursor::new(
+            r#"
+processor	: 0
+BogoMIPS	: 108.00
+Features	: fp asimd evtstrm crc32 cpuid
+CPU implementer	: 0x41
+CPU part	: 0xd08
+
+processor	: 1
+BogoMIPS	: 108.00
+Features	: fp asimd evtstrm crc32 cpuid
+CPU implementer	: 0x41
+CPU part	: 0xd08
+
+Hardware	: BCM2835
+Revision	: c03111
+Serial		: 10000000abcdef01
+Model		: Raspberry Pi 4 Model B Rev 1.1
+        "#,
+        ))
+        .unwrap();
+
+        assert_eq!(2, cpuinfo.logical());
+        assert_eq!(1, cpuinfo.sockets());
+        assert_eq!(2, cpuinfo.cores());
+        assert!(cpuinfo.has("asimd"));
+        assert_eq!(None, cpuinfo.model_name());
+        assert_eq!(Some("0xd08"), cpuinfo.processors[1].get("CPU part"));
+        assert_eq!(Some("BCM2835"), cpuinfo.get("Hardware"));
+        assert_eq!(Some("Raspberry Pi 4 Model B Rev 1.1"), cpuinfo.get("Model"));
+
+        assert!(parse(Cursor::new("processor: x")).is_err());
+        assert!(parse(Cursor::new("garbage")).is_err());
+    }
+}
@@ -0,0 +1,249 @@
+use std::{
+    fs,
+    io::{self, BufRead, BufReader},
+    str::FromStr,
+    time::Duration,
+};
+
+use super::{counter_delta, ParseProcError};
+
+/// The size of a sector in diskstats, which is always 512 bytes regardless of the device.
+pub const SECTOR_SIZE: u64 = 512;
+
+///
+/// A row of `/proc/diskstats`, times are in milliseconds.
+/// The discard and flush counters are zero on kernels that don't report them.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct DiskStats {
+    pub major: u32,
+    pub minor: u32,
+    pub name: String,
+    pub reads_completed: u64,
+    pub reads_merged: u64,
+    pub sectors_read: u64,
+    pub time_reading: u64,
+    pub writes_completed: u64,
+    pub writes_merged: u64,
+    pub sectors_written: u64,
+    pub time_writing: u64,
+    pub ios_in_progress: u64,
+    pub time_io: u64,
+    pub weighted_time_io: u64,
+    pub discards_completed: u64,
+    pub discards_merged: u64,
+    pub sectors_discarded: u64,
+    pub time_discarding: u64,
+    pub flushes_completed: u64,
+    pub time_flushing: u64,
+}
+
+impl FromStr for DiskStats {
+    type Err = ParseProcError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let fields = s.split_whitespace().collect::<Vec<_>>();
+
+        if !matches!(fields.len(), 14 | 18 | 20) {
+            return Err(ParseProcError::InvalidEntry(s.to_owned()));
+        }
+
+        let counters = fields[3..]
+            .iter()
+            .map(|field| field.parse::<u64>())
+            .collect::<Result<Vec<_>, _>>()?;
+        let counter = |i: usize| counters.get(i).copied().unwrap_or_default();
+
+        Ok(DiskStats {
+            major: fields[0].parse()?,
+            minor: fields[1].parse()?,
+            name: fields[2].to_owned(),
+            reads_completed: counter(0),
+            reads_merged: counter(1),
+            sectors_read: counter(2),
+            time_reading: counter(3),
+            writes_completed: counter(4),
+            writes_merged: counter(5),
+            sectors_written: counter(6),
+            time_writing: counter(7),
+            ios_in_progress: counter(8),
+            time_io: counter(9),
+            weighted_time_io: counter(10),
+            discards_completed: counter(11),
+            discards_merged: counter(12),
+            sectors_discarded: counter(13),
+            time_discarding: counter(14),
+            flushes_completed: counter(15),
+            time_flushing: counter(16),
+        })
+    }
+}
+
+/// Rates of a device between two samples.
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+pub struct Delta {
+    pub reads_per_sec: f64,
+    pub writes_per_sec: f64,
+    pub read_bytes_per_sec: f64,
+    pub write_bytes_per_sec: f64,
+    /// Average time of a read request in milliseconds, including the time in queue.
+    pub read_await: f64,
+    /// Average time of a write request in milliseconds, including the time in queue.
+    pub write_await: f64,
+    /// Share of the time the device had requests in flight, from 0.0 to 1.0.
+    pub utilization: f64,
+}
+
+impl Delta {
+    ///
+    /// Computes the rates between the `previous` and `current` samples of the same device taken `elapsed` apart.
+    /// Counters which wrapped at 32 bits, as they do on 32-bit kernels, are accounted for.
+    ///
+    /// ```no_run
+    /// use std::{thread, time::Duration};
+    ///
+    /// use unixism::proc::diskstats::{self, Delta};
+    ///
+    /// let previous = diskstats::parse_default().unwrap().collect::<Vec<_>>();
+    /// thread::sleep(Duration::from_secs(1));
+    /// let current = diskstats::parse_default().unwrap().collect::<Vec<_>>();
+    ///
+    /// for (previous, current) in previous.iter().zip(&current) {
+    ///     let delta = Delta::new(previous, current, Duration::from_secs(1));
+    ///
+    ///     println!("{}: {:.1} r/s {:.1} w/s {:.0}% util", current.name, delta.reads_per_sec, delta.writes_per_sec, delta.utilization * 100.0);
+    /// }
+    /// ```
+    pub fn new(previous: &DiskStats, current: &DiskStats, elapsed: Duration) -> Self {
+        let seconds = elapsed.as_secs_f64();
+        if seconds == 0.0 {
+            return Delta::default();
+        }
+
+        let reads = counter_delta(previous.reads_completed, current.reads_completed) as f64;
+        let writes = counter_delta(previous.writes_completed, current.writes_completed) as f64;
+        let sectors_read = counter_delta(previous.sectors_read, current.sectors_read) as f64;
+        let sectors_written =
+            counter_delta(previous.sectors_written, current.sectors_written) as f64;
+        let time_reading = counter_delta(previous.time_reading, current.time_reading) as f64;
+        let time_writing = counter_delta(previous.time_writing, current.time_writing) as f64;
+        let time_io = counter_delta(previous.time_io, current.time_io) as f64;
+
+        let average = |time: f64, count: f64| if count == 0.0 { 0.0 } else { time / count };
+
+        Delta {
+            reads_per_sec: reads / seconds,
+            writes_per_sec: writes / seconds,
+            read_bytes_per_sec: sectors_read * SECTOR_SIZE as f64 / seconds,
+            write_bytes_per_sec: sectors_written * SECTOR_SIZE as f64 / seconds,
+            read_await: average(time_reading, reads),
+            write_await: average(time_writing, writes),
+            utilization: (time_io / (seconds * 1000.0)).min(1.0),
+        }
+    }
+}
+
+///
+/// ```no_run
+/// let stats = unixism::proc::diskstats::parse(std::fs::File::open("/proc/diskstats").unwrap()).unwrap();
+///
+/// for device in stats {
+///     println!("{}: {} reads, {} writes", device.name, device.reads_completed, device.writes_completed);
+/// }
+/// ```
+pub fn parse<R>(reader: R) -> Result<impl Iterator<Item = DiskStats>, ParseProcError>
+where
+    R: io::Read,
+{
+    let mut stats = Vec::new();
+
+    for line in BufReader::new(reader).lines() {
+        let line = line?;
+        let line = line.trim();
+
+        if line.is_empty() {
+            continue;
+        }
+
+        stats.push(line.parse::<DiskStats>()?);
+    }
+
+    Ok(stats.into_iter())
+}
+
+///
+/// Same as parse, but parses the `/proc/diskstats` as default.
+///
+/// ```no_run
+/// let stats = unixism::proc::diskstats::parse_default().unwrap();
+/// ```
+pub fn parse_default() -> Result<impl Iterator<Item = DiskStats>, ParseProcError> {
+    parse(fs::File::open("/proc/diskstats")?)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Cursor;
+
+    use super::*;
+
+    #[test]
+    fn it_parse() {
+        let stats = parse(Cursor::new(
+            r#"
+ 259       0 nvme0n1 1000 10 80000 500 2000 20 160000 3000 0 2500 3500 5 0 40 1 300 20
+ 259       1 nvme0n1p1 100 0 800 50 0 0 0 0 0 40 50
+   8       0 sda 10 0 80 5 0 0 0 0 0 4 5 0 0 0 0
+        "#,
+        ))
+        .unwrap()
+        .collect::<Vec<_>>();
+
+        assert_eq!(3, stats.len());
+        assert_eq!("nvme0n1", stats[0].name);
+        assert_eq!(160000, stats[0].sectors_written);
+        assert_eq!(300, stats[0].flushes_completed);
+        assert_eq!(20, stats[0].time_flushing);
+        assert_eq!(50, stats[1].weighted_time_io);
+        assert_eq!(0, stats[1].discards_completed);
+        assert_eq!(8, stats[2].major);
+
+        assert!(parse(Cursor::new("8 0 sda 10 0 80")).is_err());
+    }
+
+    #[test]
+    fn it_delta() {
+        let previous = "8 0 sda 100 0 1000 50 200 0 4000 400 0 1000 0"
+            .parse::<DiskStats>()
+            .unwrap();
+        let current = "8 0 sda 300 0 3000 250 300 0 6048 900 1 1500 0"
+            .parse::<DiskStats>()
+            .unwrap();
+
+        let delta = Delta::new(&previous, &current, Duration::from_secs(2));
+
+        assert_eq!(100.0, delta.reads_per_sec);
+        assert_eq!(50.0, delta.writes_per_sec);
+        assert_eq!(512000.0, delta.read_bytes_per_sec);
+        assert_eq!(524288.0, delta.write_bytes_per_sec);
+        assert_eq!(1.0, delta.read_await);
+        assert_eq!(5.0, delta.write_await);
+        assert_eq!(0.25, delta.utilization);
+
+        assert_eq!(
+            Delta::default(),
+            Delta::new(&previous, &current, Duration::ZERO)
+        );
+
+        // The reads wrapped at 32 bits, the 64-bit sectors counter was reset.
+        let previous = "8 0 sda 4294967196 0 5000000000 0 0 0 0 0 0 0 0"
+            .parse::<DiskStats>()
+            .unwrap();
+        let current = "8 0 sda 100 0 10 0 0 0 0 0 0 0 0"
+            .parse::<DiskStats>()
+            .unwrap();
+        let delta = Delta::new(&previous, &current, Duration::from_secs(1));
+
+        assert_eq!(200.0, delta.reads_per_sec);
+        assert_eq!(0.0, delta.read_bytes_per_sec);
+    }
+}
@@ -0,0 +1,101 @@
+use std::{
+    fs,
+    io::{self, BufRead, BufReader},
+    str::FromStr,
+};
+
+use super::ParseProcError;
+
+/// A filesystem type the kernel supports.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Filesystem {
+    pub name: String,
+    /// Whether the filesystem doesn't need a block device, like `proc` or `tmpfs`.
+    pub nodev: bool,
+}
+
+impl FromStr for Filesystem {
+    type Err = ParseProcError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let fields = s.split_whitespace().collect::<Vec<_>>();
+
+        match fields[..] {
+            ["nodev", name] => Ok(Filesystem {
+                name: name.to_owned(),
+                nodev: true,
+            }),
+            [name] => Ok(Filesystem {
+                name: name.to_owned(),
+                nodev: false,
+            }),
+            _ => Err(ParseProcError::InvalidEntry(s.to_owned())),
+        }
+    }
+}
+
+///
+/// ```no_run
+/// let filesystems = unixism::proc::filesystems::parse(std::fs::File::open("/proc/filesystems").unwrap()).unwrap();
+///
+/// for filesystem in filesystems.filter(|filesystem| !filesystem.nodev) {
+///     println!("{}", filesystem.name);
+/// }
+/// ```
+pub fn parse<R>(reader: R) -> Result<impl Iterator<Item = Filesystem>, ParseProcError>
+where
+    R: io::Read,
+{
+    let mut filesystems = Vec::new();
+
+    for line in BufReader::new(reader).lines() {
+        let line = line?;
+        let line = line.trim();
+
+        if line.is_empty() {
+            continue;
+        }
+
+        filesystems.push(line.parse::<Filesystem>()?);
+    }
+
+    Ok(filesystems.into_iter())
+}
+
+///
+/// Same as parse, but parses the `/proc/filesystems` as default.
+///
+/// ```no_run
+/// let filesystems = unixism::proc::filesystems::parse_default().unwrap();
+/// ```
+pub fn parse_default() -> Result<impl Iterator<Item = Filesystem>, ParseProcError> {
+    parse(fs::File::open("/proc/filesystems")?)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Cursor;
+
+    use super::*;
+
+    #[test]
+    fn it_parse() {
+        let filesystems = parse(Cursor::new(
+            "nodev\tsysfs\nnodev\ttmpfs\n\text4\n\tvfat\nnodev\tfuse\n",
+        ))
+        .unwrap()
+        .collect::<Vec<_>>();
+
+        assert_eq!(5, filesystems.len());
+        assert_eq!(
+            Filesystem {
+                name: "ext4".to_owned(),
+                nodev: false,
+            },
+            filesystems[2]
+        );
+        assert!(filesystems[4].nodev);
+
+        assert!(parse(Cursor::new("nodev ext4 extra")).is_err());
+    }
+}
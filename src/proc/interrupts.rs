@@ -0,0 +1,210 @@
+use std::{
+    fs,
+    io::{self, BufRead, BufReader},
+};
+
+use super::ParseProcError;
+
+/// A row of `/proc/interrupts`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Irq {
+    /// The IRQ number or the architecture specific name, like `NMI` or `LOC`.
+    pub irq: String,
+    /// The counts per CPU, in the order of [`Interrupts::cpus`]. Some rows like `ERR` have a single total.
+    pub counts: Vec<u64>,
+    /// The interrupt controller, like `IO-APIC` or `IR-PCI-MSI`, for numbered IRQs only.
+    pub chip: Option<String>,
+    /// The hardware IRQ with its trigger, like `2-edge`.
+    pub hwirq: Option<String>,
+    /// The devices using the IRQ, or the description of the named ones.
+    pub name: String,
+}
+
+impl Irq {
+    pub fn total(&self) -> u64 {
+        self.counts.iter().sum()
+    }
+}
+
+/// The content of `/proc/interrupts`.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct Interrupts {
+    /// The online CPUs, the columns of the counters.
+    pub cpus: Vec<u32>,
+    pub irqs: Vec<Irq>,
+}
+
+impl Interrupts {
+    pub fn get(&self, irq: &str) -> Option<&Irq> {
+        self.irqs.iter().find(|row| row.irq == irq)
+    }
+
+    /// Returns the count of the IRQ on the CPU, `None` when the CPU is offline.
+    pub fn count(&self, irq: &str, cpu: u32) -> Option<u64> {
+        let column = self.cpus.iter().position(|id| *id == cpu)?;
+
+        self.get(irq)?.counts.get(column).copied()
+    }
+}
+
+/// Parses the `CPU0 CPU1 ...` header shared with `/proc/softirqs`.
+pub(super) fn parse_cpus(line: &str) -> Result<Vec<u32>, ParseProcError> {
+    line.split_whitespace()
+        .map(|cpu| {
+            cpu.strip_prefix("CPU")
+                .ok_or_else(|| ParseProcError::InvalidEntry(line.to_owned()))?
+                .parse()
+                .map_err(ParseProcError::from)
+        })
+        .collect()
+}
+
+/// Splits a `name: counts... rest` row, at most `cpus` counts are taken.
+pub(super) fn parse_counts(
+    line: &str,
+    cpus: usize,
+) -> Result<(&str, Vec<u64>, Vec<&str>), ParseProcError> {
+    let (name, rest) = line
+        .split_once(':')
+        .ok_or_else(|| ParseProcError::InvalidEntry(line.to_owned()))?;
+
+    let mut fields = rest.split_whitespace().peekable();
+    let mut counts = Vec::new();
+
+    while counts.len() < cpus {
+        match fields.peek().map(|field| field.parse::<u64>()) {
+            Some(Ok(count)) => {
+                counts.push(count);
+                fields.next();
+            }
+            _ => break,
+        }
+    }
+
+    Ok((name.trim(), counts, fields.collect()))
+}
+
+///
+/// Parses the counters, splitting the chip and hardware IRQ from the devices of numbered IRQs
+/// when the row has them. The layout of these columns varies between architectures and kernels.
+///
+/// ```no_run
+/// let interrupts = unixism::proc::interrupts::parse(std::fs::File::open("/proc/interrupts").unwrap()).unwrap();
+///
+/// for irq in &interrupts.irqs {
+///     println!("{:>4}: {:>10} {}", irq.irq, irq.total(), irq.name);
+/// }
+/// ```
+pub fn parse<R>(reader: R) -> Result<Interrupts, ParseProcError>
+where
+    R: io::Read,
+{
+    let mut interrupts = Interrupts::default();
+    let mut lines = BufReader::new(reader).lines();
+
+    for line in lines.by_ref() {
+        let line = line?;
+
+        if !line.trim().is_empty() {
+            interrupts.cpus = parse_cpus(&line)?;
+            break;
+        }
+    }
+
+    for line in lines {
+        let line = line?;
+
+        if line.trim().is_empty() {
+            continue;
+        }
+
+        let (irq, counts, rest) = parse_counts(&line, interrupts.cpus.len())?;
+
+        let (chip, hwirq, name) = match rest[..] {
+            [chip, hwirq, ref name @ ..] if is_numbered(irq) && !name.is_empty() => {
+                (Some(chip), Some(hwirq), name)
+            }
+            [chip, ref name @ ..] if is_numbered(irq) && !name.is_empty() => {
+                (Some(chip), None, name)
+            }
+            ref name => (None, None, name),
+        };
+
+        interrupts.irqs.push(Irq {
+            irq: irq.to_owned(),
+            counts,
+            chip: chip.map(String::from),
+            hwirq: hwirq.map(String::from),
+            name: name.join(" "),
+        });
+    }
+
+    Ok(interrupts)
+}
+
+fn is_numbered(irq: &str) -> bool {
+    !irq.is_empty() && irq.bytes().all(|c| c.is_ascii_digit())
+}
+
+///
+/// Same as parse, but parses the `/proc/interrupts` as default.
+///
+/// ```no_run
+/// let interrupts = unixism::proc::interrupts::parse_default().unwrap();
+/// ```
+pub fn parse_default() -> Result<Interrupts, ParseProcError> {
+    parse(fs::File::open("/proc/interrupts")?)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Cursor;
+
+    use super::*;
+
+    #[test]
+    fn it_parse() {
+        let interrupts = parse(Cursor::new(
+            r#"
+           CPU0       CPU2       
+  0:         35          0   IO-APIC    2-edge      timer
+  9:          0          4   IO-APIC    9-fasteoi   acpi
+ 16:        100        200   IO-APIC   16-fasteoi   ehci_hcd:usb1, i801_smbus
+ 24:          0          0  XT-PIC  cascade
+NMI:          3          1   Non-maskable interrupts
+LOC:    1234567    7654321   Local timer interrupts
+ERR:          0
+MIS:          0
+        "#,
+        ))
+        .unwrap();
+
+        assert_eq!(vec![0, 2], interrupts.cpus);
+        assert_eq!(8, interrupts.irqs.len());
+        assert_eq!(
+            Irq {
+                irq: "16".to_owned(),
+                counts: vec![100, 200],
+                chip: Some("IO-APIC".to_owned()),
+                hwirq: Some("16-fasteoi".to_owned()),
+                name: "ehci_hcd:usb1, i801_smbus".to_owned(),
+            },
+            interrupts.irqs[2]
+        );
+        assert_eq!(None, interrupts.irqs[3].hwirq);
+        assert_eq!("cascade", interrupts.irqs[3].name);
+        assert_eq!(None, interrupts.get("LOC").unwrap().chip);
+        assert_eq!(
+            "Local timer interrupts",
+            interrupts.get("LOC").unwrap().name
+        );
+        assert_eq!(Some(7654321), interrupts.count("LOC", 2));
+        assert_eq!(None, interrupts.count("LOC", 1));
+        assert_eq!(300, interrupts.get("16").unwrap().total());
+        assert_eq!(vec![0], interrupts.get("ERR").unwrap().counts);
+        assert_eq!("", interrupts.get("MIS").unwrap().name);
+
+        assert!(parse(Cursor::new("CPU0 CPUx\n")).is_err());
+        assert!(parse(Cursor::new("CPU0\n  0 35 IO-APIC timer\n")).is_err());
+    }
+}
@@ -0,0 +1,93 @@
+use std::{fs, io, str::FromStr};
+
+use super::ParseProcError;
+
+/// The content of `/proc/loadavg`.
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+pub struct LoadAvg {
+    pub one: f64,
+    pub five: f64,
+    pub fifteen: f64,
+    /// Tasks currently runnable, including the one reading the file.
+    pub runnable: u32,
+    /// Tasks existing in the system.
+    pub total: u32,
+    pub last_pid: u32,
+}
+
+impl FromStr for LoadAvg {
+    type Err = ParseProcError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let fields = s.split_whitespace().collect::<Vec<_>>();
+
+        let [one, five, fifteen, tasks, last_pid] = fields[..] else {
+            return Err(ParseProcError::InvalidEntry(s.to_owned()));
+        };
+
+        let (runnable, total) = tasks
+            .split_once('/')
+            .ok_or_else(|| ParseProcError::InvalidEntry(s.to_owned()))?;
+
+        Ok(LoadAvg {
+            one: one.parse()?,
+            five: five.parse()?,
+            fifteen: fifteen.parse()?,
+            runnable: runnable.parse()?,
+            total: total.parse()?,
+            last_pid: last_pid.parse()?,
+        })
+    }
+}
+
+///
+/// ```no_run
+/// let loadavg = unixism::proc::loadavg::parse(std::fs::File::open("/proc/loadavg").unwrap()).unwrap();
+///
+/// println!("load average: {:.2}, {:.2}, {:.2}", loadavg.one, loadavg.five, loadavg.fifteen);
+/// ```
+pub fn parse<R>(mut reader: R) -> Result<LoadAvg, ParseProcError>
+where
+    R: io::Read,
+{
+    let mut content = String::new();
+    reader.read_to_string(&mut content)?;
+
+    content.trim().parse()
+}
+
+///
+/// Same as parse, but parses the `/proc/loadavg` as default.
+///
+/// ```no_run
+/// let loadavg = unixism::proc::loadavg::parse_default().unwrap();
+/// ```
+pub fn parse_default() -> Result<LoadAvg, ParseProcError> {
+    parse(fs::File::open("/proc/loadavg")?)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Cursor;
+
+    use super::*;
+
+    #[test]
+    fn it_parse() {
+        assert_eq!(
+            LoadAvg {
+                one: 0.2,
+                five: 0.18,
+                fifteen: 1.5,
+                runnable: 2,
+                total: 812,
+                last_pid: 11206,
+            },
+            parse(Cursor::new("0.20 0.18 1.50 2/812 11206\n")).unwrap()
+        );
+
+        assert!(parse(Cursor::new("0.20 0.18 1.50 812 11206")).is_err());
+        assert!(parse(Cursor::new("0.20 x 1.50 2/812 11206")).is_err());
+        assert!(parse(Cursor::new("")).is_err());
+    }
+}
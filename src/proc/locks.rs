@@ -0,0 +1,304 @@
+use std::{
+    fs,
+    io::{self, BufRead, BufReader},
+    os::unix::fs::MetadataExt,
+    path::{Path, PathBuf},
+    str::FromStr,
+};
+
+use super::{
+    process::{FdTarget, Process},
+    ParseProcError,
+};
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum LockKind {
+    /// `fcntl` and `lockf` record locks, owned by a process.
+    Posix,
+    Flock,
+    /// Open file description locks, owned by the open file rather than a process.
+    Ofd,
+    Lease,
+    Other(String),
+}
+
+impl From<&str> for LockKind {
+    fn from(s: &str) -> Self {
+        match s {
+            "POSIX" => LockKind::Posix,
+            "FLOCK" => LockKind::Flock,
+            "OFDLCK" => LockKind::Ofd,
+            "LEASE" => LockKind::Lease,
+            kind => LockKind::Other(kind.to_owned()),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum LockMode {
+    Advisory,
+    Mandatory,
+    /// The state of a lease, like `ACTIVE` or `BREAKING`.
+    Other(String),
+}
+
+impl From<&str> for LockMode {
+    fn from(s: &str) -> Self {
+        match s {
+            "ADVISORY" => LockMode::Advisory,
+            "MANDATORY" => LockMode::Mandatory,
+            mode => LockMode::Other(mode.to_owned()),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum LockAccess {
+    Read,
+    Write,
+    Other(String),
+}
+
+impl From<&str> for LockAccess {
+    fn from(s: &str) -> Self {
+        match s {
+            "READ" => LockAccess::Read,
+            "WRITE" => LockAccess::Write,
+            access => LockAccess::Other(access.to_owned()),
+        }
+    }
+}
+
+/// A row of `/proc/locks`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Lock {
+    pub id: u32,
+    /// Whether this is a request waiting for the lock `id`, shown with `->`.
+    pub blocked: bool,
+    pub kind: LockKind,
+    pub mode: LockMode,
+    pub access: LockAccess,
+    /// `None` for OFD locks and locks held by processes of other pid namespaces.
+    pub pid: Option<u32>,
+    pub major: u32,
+    pub minor: u32,
+    pub inode: u64,
+    pub start: u64,
+    /// The last locked byte, `None` up to the end of the file.
+    pub end: Option<u64>,
+}
+
+impl Lock {
+    /// Same as path, but with the proc filesystem mounted at `root`.
+    pub fn path_at<P>(&self, root: P) -> Option<PathBuf>
+    where
+        P: AsRef<Path>,
+    {
+        let process = Process::at(root, self.pid?);
+
+        process.fds().ok()?.into_iter().find_map(|fd| {
+            let FdTarget::File(path) = fd.target else {
+                return None;
+            };
+
+            // The metadata of the fd link is the one of the open file, even in other mount namespaces.
+            let metadata = fs::metadata(process.path().join("fd").join(fd.fd.to_string())).ok()?;
+
+            (metadata.ino() == self.inode
+                && libc::major(metadata.dev()) == self.major
+                && libc::minor(metadata.dev()) == self.minor)
+                .then_some(path)
+        })
+    }
+
+    ///
+    /// Resolves the locked file by looking for it in the open files of the owning process.
+    /// Returns `None` when the process has no pid, exited or can't be inspected.
+    ///
+    /// ```no_run
+    /// for lock in unixism::proc::locks::parse_default().unwrap() {
+    ///     println!("{:?} {:?} {:?}", lock.kind, lock.pid, lock.path());
+    /// }
+    /// ```
+    pub fn path(&self) -> Option<PathBuf> {
+        self.path_at("/proc")
+    }
+}
+
+impl FromStr for Lock {
+    type Err = ParseProcError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let invalid = || ParseProcError::InvalidEntry(s.to_owned());
+
+        let (id, rest) = s.split_once(':').ok_or_else(invalid)?;
+        let rest = rest.trim_start();
+        let (blocked, rest) = match rest.strip_prefix("->") {
+            Some(rest) => (true, rest),
+            None => (false, rest),
+        };
+
+        let fields = rest.split_whitespace().collect::<Vec<_>>();
+
+        let [kind, mode, access, pid, device, start, end] = fields[..] else {
+            return Err(invalid());
+        };
+
+        let mut device = device.split(':');
+        let (Some(major), Some(minor), Some(inode), None) =
+            (device.next(), device.next(), device.next(), device.next())
+        else {
+            return Err(invalid());
+        };
+
+        Ok(Lock {
+            id: id.trim().parse()?,
+            blocked,
+            kind: LockKind::from(kind),
+            mode: LockMode::from(mode),
+            access: LockAccess::from(access),
+            pid: match pid.parse::<i64>().map_err(|_| invalid())? {
+                pid if pid > 0 => Some(u32::try_from(pid).map_err(|_| invalid())?),
+                _ => None,
+            },
+            major: u32::from_str_radix(major, 16)?,
+            minor: u32::from_str_radix(minor, 16)?,
+            inode: inode.parse()?,
+            start: start.parse()?,
+            end: match end {
+                "EOF" => None,
+                end => Some(end.parse()?),
+            },
+        })
+    }
+}
+
+///
+/// ```no_run
+/// let locks = unixism::proc::locks::parse(std::fs::File::open("/proc/locks").unwrap()).unwrap();
+///
+/// for lock in locks {
+///     println!("{} {:?} {:?} {:?} inode {}", lock.id, lock.kind, lock.access, lock.pid, lock.inode);
+/// }
+/// ```
+pub fn parse<R>(reader: R) -> Result<impl Iterator<Item = Lock>, ParseProcError>
+where
+    R: io::Read,
+{
+    let mut locks = Vec::new();
+
+    for line in BufReader::new(reader).lines() {
+        let line = line?;
+        let line = line.trim();
+
+        if line.is_empty() {
+            continue;
+        }
+
+        locks.push(line.parse::<Lock>()?);
+    }
+
+    Ok(locks.into_iter())
+}
+
+///
+/// Same as parse, but parses the `/proc/locks` as default.
+///
+/// ```no_run
+/// let locks = unixism::proc::locks::parse_default().unwrap();
+/// ```
+pub fn parse_default() -> Result<impl Iterator<Item = Lock>, ParseProcError> {
+    parse(fs::File::open("/proc/locks")?)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{io::Cursor, os::unix::fs::symlink};
+
+    use super::*;
+    use crate::tempdir::TempDir;
+
+    #[test]
+    fn it_parse() {
+        let locks = parse(Cursor::new(
+            r#"
+1: POSIX  ADVISORY  WRITE 1234 08:01:1234567 0 EOF
+1: -> POSIX  ADVISORY  WRITE 4321 08:01:1234567 0 EOF
+2: FLOCK  ADVISORY  READ  5678 fd:00:98765 0 EOF
+3: OFDLCK ADVISORY  WRITE -1 00:2a:42 100 199
+4: LEASE  BREAKING  READ  999 103:02:7 0 EOF
+        "#,
+        ))
+        .unwrap()
+        .collect::<Vec<_>>();
+
+        assert_eq!(5, locks.len());
+        assert_eq!(
+            Lock {
+                id: 1,
+                blocked: false,
+                kind: LockKind::Posix,
+                mode: LockMode::Advisory,
+                access: LockAccess::Write,
+                pid: Some(1234),
+                major: 8,
+                minor: 1,
+                inode: 1234567,
+                start: 0,
+                end: None,
+            },
+            locks[0]
+        );
+        assert!(locks[1].blocked);
+        assert_eq!(Some(4321), locks[1].pid);
+        assert_eq!(LockKind::Flock, locks[2].kind);
+        assert_eq!(253, locks[2].major);
+        assert_eq!(None, locks[3].pid);
+        assert_eq!(LockKind::Ofd, locks[3].kind);
+        assert_eq!(42, locks[3].minor);
+        assert_eq!(Some(199), locks[3].end);
+        assert_eq!(LockMode::Other("BREAKING".to_owned()), locks[4].mode);
+        assert_eq!(259, locks[4].major);
+
+        assert!(parse(Cursor::new("1: POSIX ADVISORY WRITE 1234 08:01 0 EOF")).is_err());
+        assert!(parse(Cursor::new("1: POSIX ADVISORY WRITE 1234 08:01:5 0")).is_err());
+    }
+
+    #[test]
+    fn it_path_at() {
+        let root = TempDir::new("locks");
+        let fd = root.join("42").join("fd");
+        fs::create_dir_all(&fd).unwrap();
+
+        let file = root.join("locked");
+        fs::write(&file, "").unwrap();
+        symlink("/dev/null", fd.join("0")).unwrap();
+        symlink(&file, fd.join("3")).unwrap();
+
+        let metadata = fs::metadata(&file).unwrap();
+        let lock = Lock {
+            id: 1,
+            blocked: false,
+            kind: LockKind::Flock,
+            mode: LockMode::Advisory,
+            access: LockAccess::Write,
+            pid: Some(42),
+            major: libc::major(metadata.dev()),
+            minor: libc::minor(metadata.dev()),
+            inode: metadata.ino(),
+            start: 0,
+            end: None,
+        };
+
+        assert_eq!(Some(file), lock.path_at(&root));
+        assert_eq!(
+            None,
+            Lock {
+                inode: 0,
+                ..lock.clone()
+            }
+            .path_at(&root)
+        );
+        assert_eq!(None, Lock { pid: None, ..lock }.path_at(&root));
+    }
+}
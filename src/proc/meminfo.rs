@@ -0,0 +1,189 @@
+use std::{
+    collections::HashMap,
+    fmt, fs,
+    io::{self, BufRead, BufReader},
+};
+
+use super::ParseProcError;
+
+/// A quantity of memory in bytes.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Bytes(pub u64);
+
+impl Bytes {
+    pub fn from_kib(kib: u64) -> Self {
+        Bytes(kib.saturating_mul(1024))
+    }
+
+    pub fn as_u64(self) -> u64 {
+        self.0
+    }
+
+    pub fn as_kib(self) -> u64 {
+        self.0 / 1024
+    }
+}
+
+impl fmt::Display for Bytes {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+///
+/// The `/proc/meminfo` fields, the kernel `kB` values converted to bytes.
+/// `MemAvailable` is missing before Linux 3.14, the huge page fields without hugetlbfs support.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct MemInfo {
+    pub mem_total: Bytes,
+    pub mem_free: Bytes,
+    pub mem_available: Option<Bytes>,
+    pub buffers: Bytes,
+    pub cached: Bytes,
+    pub swap_cached: Bytes,
+    pub swap_total: Bytes,
+    pub swap_free: Bytes,
+    pub hugepages_total: u64,
+    pub hugepages_free: u64,
+    pub hugepages_rsvd: u64,
+    pub hugepages_surp: u64,
+    pub hugepage_size: Option<Bytes>,
+    pub hugetlb: Option<Bytes>,
+    /// Remaining fields, in bytes when the kernel reports them in `kB`.
+    pub others: HashMap<String, u64>,
+}
+
+impl MemInfo {
+    /// Share of the memory available for new allocations, estimated from the free and cache memory on old kernels.
+    pub fn available_percent(&self) -> f64 {
+        if self.mem_total.0 == 0 {
+            return 0.0;
+        }
+
+        let available = self
+            .mem_available
+            .unwrap_or(Bytes(self.mem_free.0 + self.buffers.0 + self.cached.0));
+
+        available.0 as f64 * 100.0 / self.mem_total.0 as f64
+    }
+
+    pub fn swap_used(&self) -> Bytes {
+        Bytes(self.swap_total.0.saturating_sub(self.swap_free.0))
+    }
+}
+
+///
+/// ```no_run
+/// let meminfo = unixism::proc::meminfo::parse(std::fs::File::open("/proc/meminfo").unwrap()).unwrap();
+///
+/// println!("{} of {} bytes available ({:.1}%)", meminfo.mem_available.unwrap_or_default(), meminfo.mem_total, meminfo.available_percent());
+/// ```
+pub fn parse<R>(reader: R) -> Result<MemInfo, ParseProcError>
+where
+    R: io::Read,
+{
+    let mut meminfo = MemInfo::default();
+
+    for line in BufReader::new(reader).lines() {
+        let line = line?;
+        let line = line.trim();
+
+        if line.is_empty() {
+            continue;
+        }
+
+        let (key, value) = line
+            .split_once(':')
+            .ok_or_else(|| ParseProcError::InvalidEntry(line.to_owned()))?;
+
+        let value = match value.split_whitespace().collect::<Vec<_>>()[..] {
+            [value] => value.parse::<u64>()?,
+            [value, "kB"] => Bytes::from_kib(value.parse()?).0,
+            _ => return Err(ParseProcError::InvalidEntry(line.to_owned())),
+        };
+
+        match key {
+            "MemTotal" => meminfo.mem_total = Bytes(value),
+            "MemFree" => meminfo.mem_free = Bytes(value),
+            "MemAvailable" => meminfo.mem_available = Some(Bytes(value)),
+            "Buffers" => meminfo.buffers = Bytes(value),
+            "Cached" => meminfo.cached = Bytes(value),
+            "SwapCached" => meminfo.swap_cached = Bytes(value),
+            "SwapTotal" => meminfo.swap_total = Bytes(value),
+            "SwapFree" => meminfo.swap_free = Bytes(value),
+            "HugePages_Total" => meminfo.hugepages_total = value,
+            "HugePages_Free" => meminfo.hugepages_free = value,
+            "HugePages_Rsvd" => meminfo.hugepages_rsvd = value,
+            "HugePages_Surp" => meminfo.hugepages_surp = value,
+            "Hugepagesize" => meminfo.hugepage_size = Some(Bytes(value)),
+            "Hugetlb" => meminfo.hugetlb = Some(Bytes(value)),
+            key => {
+                meminfo.others.insert(key.to_owned(), value);
+            }
+        }
+    }
+
+    Ok(meminfo)
+}
+
+///
+/// Same as parse, but parses the `/proc/meminfo` as default.
+///
+/// ```no_run
+/// let meminfo = unixism::proc::meminfo::parse_default().unwrap();
+/// ```
+pub fn parse_default() -> Result<MemInfo, ParseProcError> {
+    parse(fs::File::open("/proc/meminfo")?)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Cursor;
+
+    use super::*;
+
+    #[test]
+    fn it_parse() {
+        let meminfo = parse(Cursor::new(
+            r#"
+MemTotal:       16000000 kB
+MemFree:         2000000 kB
+MemAvailable:    8000000 kB
+Buffers:          500000 kB
+Cached:          4000000 kB
+SwapCached:            0 kB
+SwapTotal:       2097148 kB
+SwapFree:        1048574 kB
+Shmem:            300000 kB
+AnonHugePages:         0 kB
+HugePages_Total:       4
+HugePages_Free:        2
+HugePages_Rsvd:        1
+HugePages_Surp:        0
+Hugepagesize:       2048 kB
+Hugetlb:            8192 kB
+        "#,
+        ))
+        .unwrap();
+
+        assert_eq!(Bytes::from_kib(16000000), meminfo.mem_total);
+        assert_eq!(Some(Bytes(8192000000)), meminfo.mem_available);
+        assert_eq!(Bytes::from_kib(1048574), meminfo.swap_used());
+        assert_eq!(4, meminfo.hugepages_total);
+        assert_eq!(1, meminfo.hugepages_rsvd);
+        assert_eq!(Some(Bytes(2097152)), meminfo.hugepage_size);
+        assert_eq!(Some(&307200000), meminfo.others.get("Shmem"));
+        assert_eq!(50.0, meminfo.available_percent());
+
+        let meminfo = MemInfo {
+            mem_available: None,
+            ..meminfo
+        };
+        assert_eq!(40.625, meminfo.available_percent());
+        assert_eq!(0.0, MemInfo::default().available_percent());
+
+        assert!(parse(Cursor::new("MemTotal 16000000 kB")).is_err());
+        assert!(parse(Cursor::new("MemTotal: 16000000 MB")).is_err());
+        assert!(parse(Cursor::new("MemTotal: x kB")).is_err());
+    }
+}
@@ -0,0 +1,75 @@
+use std::{
+    error, fmt, io,
+    num::{ParseFloatError, ParseIntError},
+};
+
+pub mod cmdline;
+pub mod cpuinfo;
+pub mod diskstats;
+pub mod filesystems;
+pub mod interrupts;
+pub mod loadavg;
+pub mod locks;
+pub mod meminfo;
+pub mod modules;
+pub mod net;
+pub mod partitions;
+pub mod process;
+pub mod softirqs;
+pub mod stat;
+pub mod swaps;
+pub mod uptime;
+pub mod vmstat;
+
+pub use process::processes;
+
+#[derive(Debug)]
+pub enum ParseProcError {
+    InvalidEntry(String),
+    ParseIntError(ParseIntError),
+    ParseFloatError(ParseFloatError),
+    IOError(io::Error),
+}
+
+impl error::Error for ParseProcError {}
+
+impl fmt::Display for ParseProcError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::InvalidEntry(entry) => write!(f, "invalid entry: {}", entry),
+            Self::ParseIntError(error) => write!(f, "{}", error),
+            Self::ParseFloatError(error) => write!(f, "{}", error),
+            Self::IOError(error) => write!(f, "{}", error),
+        }
+    }
+}
+
+impl From<ParseIntError> for ParseProcError {
+    fn from(value: ParseIntError) -> Self {
+        ParseProcError::ParseIntError(value)
+    }
+}
+
+impl From<ParseFloatError> for ParseProcError {
+    fn from(value: ParseFloatError) -> Self {
+        ParseProcError::ParseFloatError(value)
+    }
+}
+
+impl From<io::Error> for ParseProcError {
+    fn from(value: io::Error) -> Self {
+        ParseProcError::IOError(value)
+    }
+}
+
+/// Returns how much a counter grew between two samples. A counter that went back while the previous sample
+/// fit in 32 bits wrapped as a 32-bit counter, which is added back. Any other decrease, like a reset, is 0.
+pub(crate) fn counter_delta(previous: u64, current: u64) -> u64 {
+    if current >= previous {
+        current - previous
+    } else if previous <= u64::from(u32::MAX) {
+        current + (1 << 32) - previous
+    } else {
+        0
+    }
+}
@@ -0,0 +1,243 @@
+use std::{
+    fs,
+    io::{self, BufRead, BufReader},
+    path::Path,
+    str::FromStr,
+};
+
+use super::ParseProcError;
+
+/// Directories systemd-modules-load reads, the most important first.
+pub const LOAD_DIRS: [&str; 5] = [
+    "/etc/modules-load.d",
+    "/run/modules-load.d",
+    "/usr/local/lib/modules-load.d",
+    "/usr/lib/modules-load.d",
+    "/lib/modules-load.d",
+];
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ModuleState {
+    Live,
+    Loading,
+    Unloading,
+    Other(String),
+}
+
+impl From<&str> for ModuleState {
+    fn from(s: &str) -> Self {
+        match s {
+            "Live" => ModuleState::Live,
+            "Loading" => ModuleState::Loading,
+            "Unloading" => ModuleState::Unloading,
+            state => ModuleState::Other(state.to_owned()),
+        }
+    }
+}
+
+/// A row of `/proc/modules`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Module {
+    pub name: String,
+    /// Memory size in bytes.
+    pub size: u64,
+    /// `None` when the kernel can't unload modules.
+    pub refcount: Option<u32>,
+    /// Modules using this one.
+    pub dependents: Vec<String>,
+    pub state: ModuleState,
+    /// Taint flags like `O` for out-of-tree or `E` for unsigned, without the parentheses.
+    pub taints: Option<String>,
+}
+
+impl FromStr for Module {
+    type Err = ParseProcError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let fields = s.split_whitespace().collect::<Vec<_>>();
+
+        if fields.len() < 5 {
+            return Err(ParseProcError::InvalidEntry(s.to_owned()));
+        }
+
+        Ok(Module {
+            name: fields[0].to_owned(),
+            size: fields[1].parse()?,
+            refcount: match fields[2] {
+                "-" => None,
+                refcount => Some(refcount.parse()?),
+            },
+            dependents: fields[3]
+                .split(',')
+                .filter(|dependent| !dependent.is_empty() && *dependent != "-")
+                .map(String::from)
+                .collect(),
+            state: ModuleState::from(fields[4]),
+            taints: fields
+                .get(6)
+                .and_then(|taints| taints.strip_prefix('('))
+                .and_then(|taints| taints.strip_suffix(')'))
+                .map(String::from),
+        })
+    }
+}
+
+///
+/// ```no_run
+/// let modules = unixism::proc::modules::parse(std::fs::File::open("/proc/modules").unwrap()).unwrap();
+///
+/// for module in modules {
+///     println!("{} {} {}", module.name, module.size, module.dependents.join(","));
+/// }
+/// ```
+pub fn parse<R>(reader: R) -> Result<impl Iterator<Item = Module>, ParseProcError>
+where
+    R: io::Read,
+{
+    let mut modules = Vec::new();
+
+    for line in BufReader::new(reader).lines() {
+        let line = line?;
+        let line = line.trim();
+
+        if line.is_empty() {
+            continue;
+        }
+
+        modules.push(line.parse::<Module>()?);
+    }
+
+    Ok(modules.into_iter())
+}
+
+///
+/// Same as parse, but parses the `/proc/modules` as default.
+///
+/// ```no_run
+/// let modules = unixism::proc::modules::parse_default().unwrap();
+/// ```
+pub fn parse_default() -> Result<impl Iterator<Item = Module>, ParseProcError> {
+    parse(fs::File::open("/proc/modules")?)
+}
+
+///
+/// Parses a `modules-load.d` file, one module name per line with `#` and `;` comments.
+///
+/// ```no_run
+/// let modules = unixism::proc::modules::parse_load(std::fs::File::open("/etc/modules-load.d/modules.conf").unwrap()).unwrap();
+/// ```
+pub fn parse_load<R>(reader: R) -> Result<impl Iterator<Item = String>, ParseProcError>
+where
+    R: io::Read,
+{
+    let mut modules = Vec::new();
+
+    for line in BufReader::new(reader).lines() {
+        let line = line?;
+        let line = line.trim();
+
+        if line.is_empty() || line.starts_with('#') || line.starts_with(';') {
+            continue;
+        }
+
+        modules.push(line.to_owned());
+    }
+
+    Ok(modules.into_iter())
+}
+
+///
+/// Returns the modules configured to load at boot by the `*.conf` files of `dirs`, in load order.
+/// A file in an earlier directory overrides the file with the same name in later ones.
+///
+/// ```no_run
+/// let modules = unixism::proc::modules::load_at(&["/etc/modules-load.d", "/usr/lib/modules-load.d"]).unwrap();
+/// ```
+pub fn load_at<P>(dirs: &[P]) -> Result<Vec<String>, ParseProcError>
+where
+    P: AsRef<Path>,
+{
+    let mut modules = Vec::new();
+
+    for path in crate::dropin::files(dirs, ".conf")? {
+        modules.extend(parse_load(fs::File::open(path)?)?);
+    }
+
+    Ok(modules)
+}
+
+///
+/// Same as load_at, but reads the standard `modules-load.d` directories.
+///
+/// ```no_run
+/// for module in unixism::proc::modules::load().unwrap() {
+///     println!("{}", module);
+/// }
+/// ```
+pub fn load() -> Result<Vec<String>, ParseProcError> {
+    load_at(&LOAD_DIRS)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Cursor;
+
+    use super::*;
+    use crate::tempdir::TempDir;
+
+    #[test]
+    fn it_parse() {
+        let modules = parse(Cursor::new(
+            r#"
+nf_nat 49152 3 xt_nat,nft_chain_nat,xt_MASQUERADE, Live 0x0000000000000000
+snd_hda_intel 57344 3 - Live 0x0000000000000000
+vboxdrv 696320 2 vboxnetadp,vboxnetflt, Live 0x0000000000000000 (OE)
+loop 40960 - - Loading 0x0000000000000000
+        "#,
+        ))
+        .unwrap()
+        .collect::<Vec<_>>();
+
+        assert_eq!(4, modules.len());
+        assert_eq!(
+            Module {
+                name: "nf_nat".to_owned(),
+                size: 49152,
+                refcount: Some(3),
+                dependents: vec![
+                    "xt_nat".to_owned(),
+                    "nft_chain_nat".to_owned(),
+                    "xt_MASQUERADE".to_owned()
+                ],
+                state: ModuleState::Live,
+                taints: None,
+            },
+            modules[0]
+        );
+        assert!(modules[1].dependents.is_empty());
+        assert_eq!(Some("OE"), modules[2].taints.as_deref());
+        assert_eq!(None, modules[3].refcount);
+        assert_eq!(ModuleState::Loading, modules[3].state);
+
+        assert!(parse(Cursor::new("loop 40960 0 -")).is_err());
+        assert!(parse(Cursor::new("loop x 0 - Live")).is_err());
+    }
+
+    #[test]
+    fn it_load_at() {
+        let root = TempDir::new("modules-load");
+        let (etc, usr) = (root.join("etc"), root.join("usr"));
+        fs::create_dir_all(&etc).unwrap();
+        fs::create_dir_all(&usr).unwrap();
+
+        fs::write(usr.join("virtualbox.conf"), "vboxdrv\nvboxnetflt\n").unwrap();
+        fs::write(usr.join("br.conf"), "br_netfilter\n").unwrap();
+        fs::write(etc.join("virtualbox.conf"), "# disabled\n").unwrap();
+        fs::write(etc.join("wg.conf"), "; comment\nwireguard\n").unwrap();
+
+        assert_eq!(
+            vec!["br_netfilter".to_owned(), "wireguard".to_owned()],
+            load_at(&[&etc, &usr]).unwrap()
+        );
+    }
+}
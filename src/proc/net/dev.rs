@@ -0,0 +1,246 @@
+use std::{
+    fs,
+    io::{self, BufRead, BufReader},
+    str::FromStr,
+    time::Duration,
+};
+
+use super::super::{counter_delta, ParseProcError};
+
+/// A row of `/proc/net/dev`.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct InterfaceStats {
+    pub name: String,
+    pub rx_bytes: u64,
+    pub rx_packets: u64,
+    pub rx_errors: u64,
+    pub rx_dropped: u64,
+    pub rx_fifo: u64,
+    pub rx_frame: u64,
+    pub rx_compressed: u64,
+    pub rx_multicast: u64,
+    pub tx_bytes: u64,
+    pub tx_packets: u64,
+    pub tx_errors: u64,
+    pub tx_dropped: u64,
+    pub tx_fifo: u64,
+    pub tx_collisions: u64,
+    pub tx_carrier: u64,
+    pub tx_compressed: u64,
+}
+
+impl FromStr for InterfaceStats {
+    type Err = ParseProcError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (name, counters) = s
+            .split_once(':')
+            .ok_or_else(|| ParseProcError::InvalidEntry(s.to_owned()))?;
+
+        let counters = counters
+            .split_whitespace()
+            .map(|counter| counter.parse::<u64>())
+            .collect::<Result<Vec<_>, _>>()?;
+
+        let [rx_bytes, rx_packets, rx_errors, rx_dropped, rx_fifo, rx_frame, rx_compressed, rx_multicast, tx_bytes, tx_packets, tx_errors, tx_dropped, tx_fifo, tx_collisions, tx_carrier, tx_compressed] =
+            counters[..]
+        else {
+            return Err(ParseProcError::InvalidEntry(s.to_owned()));
+        };
+
+        Ok(InterfaceStats {
+            name: name.trim().to_owned(),
+            rx_bytes,
+            rx_packets,
+            rx_errors,
+            rx_dropped,
+            rx_fifo,
+            rx_frame,
+            rx_compressed,
+            rx_multicast,
+            tx_bytes,
+            tx_packets,
+            tx_errors,
+            tx_dropped,
+            tx_fifo,
+            tx_collisions,
+            tx_carrier,
+            tx_compressed,
+        })
+    }
+}
+
+/// Rates of an interface between two samples.
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+pub struct Delta {
+    pub rx_bytes_per_sec: f64,
+    pub rx_packets_per_sec: f64,
+    pub rx_errors_per_sec: f64,
+    pub rx_dropped_per_sec: f64,
+    pub tx_bytes_per_sec: f64,
+    pub tx_packets_per_sec: f64,
+    pub tx_errors_per_sec: f64,
+    pub tx_dropped_per_sec: f64,
+}
+
+impl Delta {
+    ///
+    /// Computes the rates between the `previous` and `current` samples of the same interface taken `elapsed` apart.
+    /// Counters which wrapped at 32 bits, as they do with some drivers, are accounted for.
+    ///
+    /// ```no_run
+    /// use std::{thread, time::Duration};
+    ///
+    /// use unixism::proc::net::dev::{self, Delta};
+    ///
+    /// let previous = dev::parse_default().unwrap().collect::<Vec<_>>();
+    /// thread::sleep(Duration::from_secs(1));
+    /// let current = dev::parse_default().unwrap().collect::<Vec<_>>();
+    ///
+    /// for current in &current {
+    ///     if let Some(previous) = previous.iter().find(|previous| previous.name == current.name) {
+    ///         let delta = Delta::new(previous, current, Duration::from_secs(1));
+    ///
+    ///         println!("{}: rx {:.0} B/s tx {:.0} B/s", current.name, delta.rx_bytes_per_sec, delta.tx_bytes_per_sec);
+    ///     }
+    /// }
+    /// ```
+    pub fn new(previous: &InterfaceStats, current: &InterfaceStats, elapsed: Duration) -> Self {
+        let seconds = elapsed.as_secs_f64();
+        if seconds == 0.0 {
+            return Delta::default();
+        }
+
+        let rate = |previous: u64, current: u64| counter_delta(previous, current) as f64 / seconds;
+
+        Delta {
+            rx_bytes_per_sec: rate(previous.rx_bytes, current.rx_bytes),
+            rx_packets_per_sec: rate(previous.rx_packets, current.rx_packets),
+            rx_errors_per_sec: rate(previous.rx_errors, current.rx_errors),
+            rx_dropped_per_sec: rate(previous.rx_dropped, current.rx_dropped),
+            tx_bytes_per_sec: rate(previous.tx_bytes, current.tx_bytes),
+            tx_packets_per_sec: rate(previous.tx_packets, current.tx_packets),
+            tx_errors_per_sec: rate(previous.tx_errors, current.tx_errors),
+            tx_dropped_per_sec: rate(previous.tx_dropped, current.tx_dropped),
+        }
+    }
+}
+
+///
+/// ```no_run
+/// let stats = unixism::proc::net::dev::parse(std::fs::File::open("/proc/net/dev").unwrap()).unwrap();
+///
+/// for interface in stats {
+///     println!("{}: {} bytes in, {} bytes out", interface.name, interface.rx_bytes, interface.tx_bytes);
+/// }
+/// ```
+pub fn parse<R>(reader: R) -> Result<impl Iterator<Item = InterfaceStats>, ParseProcError>
+where
+    R: io::Read,
+{
+    let mut stats = Vec::new();
+
+    for line in BufReader::new(reader).lines() {
+        let line = line?;
+        let line = line.trim();
+
+        // The two header lines are the only ones with a `|`.
+        if line.is_empty() || line.contains('|') {
+            continue;
+        }
+
+        stats.push(line.parse::<InterfaceStats>()?);
+    }
+
+    Ok(stats.into_iter())
+}
+
+///
+/// Same as parse, but parses the `/proc/net/dev` as default.
+///
+/// ```no_run
+/// let stats = unixism::proc::net::dev::parse_default().unwrap();
+/// ```
+pub fn parse_default() -> Result<impl Iterator<Item = InterfaceStats>, ParseProcError> {
+    parse(fs::File::open("/proc/net/dev")?)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Cursor;
+
+    use super::*;
+
+    #[test]
+    fn it_parse() {
+        let stats = parse(Cursor::new(
+            r#"
+Inter-|   Receive                                                |  Transmit
+ face |bytes    packets errs drop fifo frame compressed multicast|bytes    packets errs drop fifo colls carrier compressed
+    lo:  104857     1024    0    0    0     0          0         0   104857     1024    0    0    0     0       0          0
+  eth0:4294967296 3000000    2    5    0     1          0       120 52428800   400000    0    1    0     3       0          0
+        "#,
+        ))
+        .unwrap()
+        .collect::<Vec<_>>();
+
+        assert_eq!(2, stats.len());
+        assert_eq!("lo", stats[0].name);
+        assert_eq!(104857, stats[0].tx_bytes);
+        assert_eq!(
+            InterfaceStats {
+                name: "eth0".to_owned(),
+                rx_bytes: 4294967296,
+                rx_packets: 3000000,
+                rx_errors: 2,
+                rx_dropped: 5,
+                rx_frame: 1,
+                rx_multicast: 120,
+                tx_bytes: 52428800,
+                tx_packets: 400000,
+                tx_dropped: 1,
+                tx_collisions: 3,
+                ..Default::default()
+            },
+            stats[1]
+        );
+
+        assert!(parse(Cursor::new("eth0: 1 2 3")).is_err());
+        assert!(parse(Cursor::new("eth0 1 2 3 4 5 6 7 8 9 10 11 12 13 14 15 16")).is_err());
+    }
+
+    #[test]
+    fn it_delta() {
+        let previous = "eth0: 1000 10 0 0 0 0 0 0 2000 20 0 0 0 0 0 0"
+            .parse::<InterfaceStats>()
+            .unwrap();
+        let current = "eth0: 5000 50 2 0 0 0 0 0 3000 30 0 4 0 0 0 0"
+            .parse::<InterfaceStats>()
+            .unwrap();
+
+        let delta = Delta::new(&previous, &current, Duration::from_secs(2));
+
+        assert_eq!(2000.0, delta.rx_bytes_per_sec);
+        assert_eq!(20.0, delta.rx_packets_per_sec);
+        assert_eq!(1.0, delta.rx_errors_per_sec);
+        assert_eq!(500.0, delta.tx_bytes_per_sec);
+        assert_eq!(2.0, delta.tx_dropped_per_sec);
+
+        assert_eq!(
+            Delta::default(),
+            Delta::new(&previous, &current, Duration::ZERO)
+        );
+
+        let previous = "eth0: 4294967000 10 0 0 0 0 0 0 2000 20 0 0 0 0 0 0"
+            .parse::<InterfaceStats>()
+            .unwrap();
+        let current = "eth0: 704 10 0 0 0 0 0 0 2000 20 0 0 0 0 0 0"
+            .parse::<InterfaceStats>()
+            .unwrap();
+
+        assert_eq!(
+            1000.0,
+            Delta::new(&previous, &current, Duration::from_secs(1)).rx_bytes_per_sec
+        );
+    }
+}
@@ -0,0 +1,6 @@
+pub mod dev;
+pub mod owners;
+pub mod route;
+pub mod snmp;
+pub mod sockets;
+pub mod unix;
@@ -0,0 +1,189 @@
+use std::{collections::HashMap, fs, io, path::Path};
+
+use super::super::ParseProcError;
+
+/// A process holding a socket open.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Process {
+    pub pid: u32,
+    /// The command name from `/proc/<pid>/comm`.
+    pub name: String,
+    pub fd: u32,
+}
+
+/// Socket inodes mapped to the processes having them open.
+#[derive(Debug, Default, Clone)]
+pub struct Owners {
+    by_inode: HashMap<u64, Vec<Process>>,
+}
+
+impl Owners {
+    /// Returns the processes owning the socket inode, empty for unknown inodes.
+    pub fn get(&self, inode: u64) -> &[Process] {
+        self.by_inode
+            .get(&inode)
+            .map(Vec::as_slice)
+            .unwrap_or_default()
+    }
+
+    pub fn len(&self) -> usize {
+        self.by_inode.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.by_inode.is_empty()
+    }
+}
+
+///
+/// Walks the `fd` directories of every process under `root`, collecting `socket:[inode]` links.
+/// Processes which vanish or can't be inspected are skipped, as `ss -p` does.
+///
+/// ```no_run
+/// let owners = unixism::proc::net::owners::scan_at("/proc").unwrap();
+/// ```
+pub fn scan_at<P>(root: P) -> Result<Owners, ParseProcError>
+where
+    P: AsRef<Path>,
+{
+    let mut owners = Owners::default();
+
+    for entry in fs::read_dir(root)? {
+        let entry = entry?;
+
+        let Some(pid) = entry
+            .file_name()
+            .to_str()
+            .and_then(|pid| pid.parse::<u32>().ok())
+        else {
+            continue;
+        };
+
+        let fds = match fs::read_dir(entry.path().join("fd")) {
+            Ok(fds) => fds,
+            Err(error) if skippable(&error) => continue,
+            Err(error) => return Err(error.into()),
+        };
+
+        let name = fs::read_to_string(entry.path().join("comm"))
+            .map(|comm| comm.trim_end_matches('\n').to_owned())
+            .unwrap_or_default();
+
+        for fd in fds.filter_map(Result::ok) {
+            let Some(fd_number) = fd
+                .file_name()
+                .to_str()
+                .and_then(|fd| fd.parse::<u32>().ok())
+            else {
+                continue;
+            };
+
+            let Ok(target) = fs::read_link(fd.path()) else {
+                continue;
+            };
+
+            let Some(inode) = target
+                .to_str()
+                .and_then(|target| target.strip_prefix("socket:["))
+                .and_then(|target| target.strip_suffix(']'))
+                .and_then(|inode| inode.parse::<u64>().ok())
+            else {
+                continue;
+            };
+
+            owners.by_inode.entry(inode).or_default().push(Process {
+                pid,
+                name: name.clone(),
+                fd: fd_number,
+            });
+        }
+    }
+
+    for processes in owners.by_inode.values_mut() {
+        processes.sort_by_key(|process| (process.pid, process.fd));
+    }
+
+    Ok(owners)
+}
+
+///
+/// Same as scan_at, but walks the `/proc` as default.
+///
+/// ```no_run
+/// use unixism::proc::net::{owners, sockets};
+///
+/// let owners = owners::scan().unwrap();
+///
+/// for socket in sockets::parse_default(sockets::Protocol::Tcp).unwrap() {
+///     for process in owners.get(socket.inode) {
+///         println!("{} users:((\"{}\",pid={},fd={}))", socket.local, process.name, process.pid, process.fd);
+///     }
+/// }
+/// ```
+pub fn scan() -> Result<Owners, ParseProcError> {
+    scan_at("/proc")
+}
+
+fn skippable(error: &io::Error) -> bool {
+    matches!(
+        error.kind(),
+        io::ErrorKind::NotFound | io::ErrorKind::PermissionDenied
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use std::os::unix::fs::symlink;
+
+    use super::*;
+    use crate::tempdir::TempDir;
+
+    #[test]
+    fn it_scan() {
+        let root = TempDir::new("owners");
+
+        for (pid, comm, fds) in [
+            (
+                "42",
+                "sshd\n",
+                vec![("0", "/dev/null"), ("3", "socket:[1001]")],
+            ),
+            (
+                "77",
+                "nginx\n",
+                vec![("6", "socket:[2002]"), ("4", "socket:[1001]")],
+            ),
+        ] {
+            let dir = root.join(pid).join("fd");
+            fs::create_dir_all(&dir).unwrap();
+            fs::write(root.join(pid).join("comm"), comm).unwrap();
+
+            for (fd, target) in fds {
+                symlink(target, dir.join(fd)).unwrap();
+            }
+        }
+        fs::create_dir_all(root.join("self")).unwrap();
+        fs::create_dir_all(root.join("99")).unwrap();
+
+        let owners = scan_at(&root).unwrap();
+
+        assert_eq!(2, owners.len());
+        assert_eq!(
+            vec![
+                Process {
+                    pid: 42,
+                    name: "sshd".to_owned(),
+                    fd: 3
+                },
+                Process {
+                    pid: 77,
+                    name: "nginx".to_owned(),
+                    fd: 4
+                },
+            ],
+            owners.get(1001)
+        );
+        assert_eq!(77, owners.get(2002)[0].pid);
+        assert!(owners.get(3003).is_empty());
+    }
+}
@@ -0,0 +1,357 @@
+use std::{
+    fs,
+    io::{self, BufRead, BufReader},
+    net::{Ipv4Addr, Ipv6Addr},
+    str::FromStr,
+};
+
+use super::super::ParseProcError;
+
+/// The `RTF_*` flags of a route.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct RouteFlags(pub u32);
+
+impl RouteFlags {
+    pub const UP: u32 = 0x0001;
+    pub const GATEWAY: u32 = 0x0002;
+    pub const HOST: u32 = 0x0004;
+    pub const REINSTATE: u32 = 0x0008;
+    pub const DYNAMIC: u32 = 0x0010;
+    pub const MODIFIED: u32 = 0x0020;
+    pub const MTU: u32 = 0x0040;
+    pub const WINDOW: u32 = 0x0080;
+    pub const IRTT: u32 = 0x0100;
+    pub const REJECT: u32 = 0x0200;
+    pub const DEFAULT: u32 = 0x0001_0000;
+    pub const ADDRCONF: u32 = 0x0004_0000;
+    pub const CACHE: u32 = 0x0100_0000;
+
+    pub fn contains(&self, flag: u32) -> bool {
+        self.0 & flag == flag
+    }
+
+    pub fn is_up(&self) -> bool {
+        self.contains(Self::UP)
+    }
+
+    pub fn is_gateway(&self) -> bool {
+        self.contains(Self::GATEWAY)
+    }
+
+    pub fn is_host(&self) -> bool {
+        self.contains(Self::HOST)
+    }
+}
+
+/// A row of `/proc/net/route`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Route {
+    pub iface: String,
+    pub destination: Ipv4Addr,
+    pub gateway: Ipv4Addr,
+    pub flags: RouteFlags,
+    pub refcnt: u32,
+    pub use_count: u32,
+    pub metric: u32,
+    pub mask: Ipv4Addr,
+    pub mtu: u32,
+    pub window: u32,
+    pub irtt: u32,
+}
+
+impl Route {
+    pub fn prefix(&self) -> u8 {
+        u32::from(self.mask).leading_ones() as u8
+    }
+
+    pub fn is_default(&self) -> bool {
+        self.destination.is_unspecified() && self.mask.is_unspecified()
+    }
+}
+
+impl FromStr for Route {
+    type Err = ParseProcError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let fields = s.split_whitespace().collect::<Vec<_>>();
+
+        let [iface, destination, gateway, flags, refcnt, use_count, metric, mask, mtu, window, irtt] =
+            fields[..]
+        else {
+            return Err(ParseProcError::InvalidEntry(s.to_owned()));
+        };
+
+        Ok(Route {
+            iface: iface.to_owned(),
+            destination: ipv4(destination)?,
+            gateway: ipv4(gateway)?,
+            flags: RouteFlags(u32::from_str_radix(flags, 16)?),
+            refcnt: refcnt.parse()?,
+            use_count: use_count.parse()?,
+            metric: metric.parse()?,
+            mask: ipv4(mask)?,
+            mtu: mtu.parse()?,
+            window: window.parse()?,
+            irtt: irtt.parse()?,
+        })
+    }
+}
+
+/// A row of `/proc/net/ipv6_route`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Route6 {
+    pub destination: Ipv6Addr,
+    pub destination_prefix: u8,
+    pub source: Ipv6Addr,
+    pub source_prefix: u8,
+    pub next_hop: Ipv6Addr,
+    pub metric: u32,
+    pub refcnt: u32,
+    pub use_count: u32,
+    pub flags: RouteFlags,
+    pub iface: String,
+}
+
+impl Route6 {
+    pub fn is_default(&self) -> bool {
+        self.destination.is_unspecified() && self.destination_prefix == 0
+    }
+}
+
+impl FromStr for Route6 {
+    type Err = ParseProcError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let fields = s.split_whitespace().collect::<Vec<_>>();
+
+        let [destination, destination_prefix, source, source_prefix, next_hop, metric, refcnt, use_count, flags, iface] =
+            fields[..]
+        else {
+            return Err(ParseProcError::InvalidEntry(s.to_owned()));
+        };
+
+        Ok(Route6 {
+            destination: ipv6(destination)?,
+            destination_prefix: u8::from_str_radix(destination_prefix, 16)?,
+            source: ipv6(source)?,
+            source_prefix: u8::from_str_radix(source_prefix, 16)?,
+            next_hop: ipv6(next_hop)?,
+            metric: u32::from_str_radix(metric, 16)?,
+            refcnt: u32::from_str_radix(refcnt, 16)?,
+            use_count: u32::from_str_radix(use_count, 16)?,
+            flags: RouteFlags(u32::from_str_radix(flags, 16)?),
+            iface: iface.to_owned(),
+        })
+    }
+}
+
+/// Decodes an address the kernel prints as a native-endian `%08X` of the network-order value.
+fn ipv4(s: &str) -> Result<Ipv4Addr, ParseProcError> {
+    if s.len() != 8 {
+        return Err(ParseProcError::InvalidEntry(s.to_owned()));
+    }
+
+    Ok(Ipv4Addr::from(u32::from_str_radix(s, 16)?.to_ne_bytes()))
+}
+
+/// Decodes an address the kernel prints as 32 hex digits in network order.
+fn ipv6(s: &str) -> Result<Ipv6Addr, ParseProcError> {
+    if s.len() != 32 {
+        return Err(ParseProcError::InvalidEntry(s.to_owned()));
+    }
+
+    Ok(Ipv6Addr::from(u128::from_str_radix(s, 16)?))
+}
+
+///
+/// ```no_run
+/// let routes = unixism::proc::net::route::parse(std::fs::File::open("/proc/net/route").unwrap()).unwrap();
+///
+/// for route in routes {
+///     println!("{}/{} via {} dev {}", route.destination, route.prefix(), route.gateway, route.iface);
+/// }
+/// ```
+pub fn parse<R>(reader: R) -> Result<impl Iterator<Item = Route>, ParseProcError>
+where
+    R: io::Read,
+{
+    let mut routes = Vec::new();
+
+    for line in BufReader::new(reader).lines() {
+        let line = line?;
+        let line = line.trim();
+
+        if line.is_empty() || line.starts_with("Iface") {
+            continue;
+        }
+
+        routes.push(line.parse::<Route>()?);
+    }
+
+    Ok(routes.into_iter())
+}
+
+///
+/// Same as parse, but parses the `/proc/net/route` as default.
+///
+/// ```no_run
+/// let routes = unixism::proc::net::route::parse_default().unwrap();
+/// ```
+pub fn parse_default() -> Result<impl Iterator<Item = Route>, ParseProcError> {
+    parse(fs::File::open("/proc/net/route")?)
+}
+
+///
+/// ```no_run
+/// let routes = unixism::proc::net::route::parse_ipv6(std::fs::File::open("/proc/net/ipv6_route").unwrap()).unwrap();
+///
+/// for route in routes {
+///     println!("{}/{} via {} dev {}", route.destination, route.destination_prefix, route.next_hop, route.iface);
+/// }
+/// ```
+pub fn parse_ipv6<R>(reader: R) -> Result<impl Iterator<Item = Route6>, ParseProcError>
+where
+    R: io::Read,
+{
+    let mut routes = Vec::new();
+
+    for line in BufReader::new(reader).lines() {
+        let line = line?;
+        let line = line.trim();
+
+        if line.is_empty() {
+            continue;
+        }
+
+        routes.push(line.parse::<Route6>()?);
+    }
+
+    Ok(routes.into_iter())
+}
+
+///
+/// Same as parse_ipv6, but parses the `/proc/net/ipv6_route` as default.
+///
+/// ```no_run
+/// let routes = unixism::proc::net::route::parse_ipv6_default().unwrap();
+/// ```
+pub fn parse_ipv6_default() -> Result<impl Iterator<Item = Route6>, ParseProcError> {
+    parse_ipv6(fs::File::open("/proc/net/ipv6_route")?)
+}
+
+///
+/// Picks the usable IPv4 default route with the lowest metric.
+///
+/// ```no_run
+/// use unixism::proc::net::route;
+///
+/// let routes = route::parse_default().unwrap().collect::<Vec<_>>();
+///
+/// if let Some(route) = route::default_gateway(&routes) {
+///     println!("default via {} dev {}", route.gateway, route.iface);
+/// }
+/// ```
+pub fn default_gateway(routes: &[Route]) -> Option<&Route> {
+    routes
+        .iter()
+        .filter(|route| route.is_default() && route.flags.is_up() && route.flags.is_gateway())
+        .min_by_key(|route| route.metric)
+}
+
+///
+/// Picks the usable IPv6 default route with the lowest metric.
+///
+/// ```no_run
+/// use unixism::proc::net::route;
+///
+/// let routes = route::parse_ipv6_default().unwrap().collect::<Vec<_>>();
+///
+/// if let Some(route) = route::default_gateway_ipv6(&routes) {
+///     println!("default via {} dev {}", route.next_hop, route.iface);
+/// }
+/// ```
+pub fn default_gateway_ipv6(routes: &[Route6]) -> Option<&Route6> {
+    routes
+        .iter()
+        .filter(|route| route.is_default() && route.flags.is_up() && route.flags.is_gateway())
+        .min_by_key(|route| route.metric)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Cursor;
+
+    use super::*;
+
+    #[test]
+    #[cfg(target_endian = "little")]
+    fn it_parse() {
+        let routes = parse(Cursor::new(
+            r#"
+Iface	Destination	Gateway 	Flags	RefCnt	Use	Metric	Mask		MTU	Window	IRTT
+wlan0	00000000	0101A8C0	0003	0	0	600	00000000	0	0	0
+eth0	00000000	FE01A8C0	0003	0	0	100	00000000	0	0	0
+eth0	0001A8C0	00000000	0001	0	0	100	00FFFFFF	0	0	0
+        "#,
+        ))
+        .unwrap()
+        .collect::<Vec<_>>();
+
+        assert_eq!(3, routes.len());
+        assert_eq!(
+            Route {
+                iface: "eth0".to_owned(),
+                destination: Ipv4Addr::new(192, 168, 1, 0),
+                gateway: Ipv4Addr::UNSPECIFIED,
+                flags: RouteFlags(RouteFlags::UP),
+                refcnt: 0,
+                use_count: 0,
+                metric: 100,
+                mask: Ipv4Addr::new(255, 255, 255, 0),
+                mtu: 0,
+                window: 0,
+                irtt: 0,
+            },
+            routes[2]
+        );
+        assert_eq!(24, routes[2].prefix());
+        assert!(!routes[2].is_default());
+
+        let gateway = default_gateway(&routes).unwrap();
+        assert_eq!("eth0", gateway.iface);
+        assert_eq!(Ipv4Addr::new(192, 168, 1, 254), gateway.gateway);
+
+        assert!(parse(Cursor::new("eth0 00000000 0101A8C0 0003")).is_err());
+        assert!(parse(Cursor::new(
+            "eth0 0000000G 0101A8C0 0003 0 0 0 00000000 0 0 0"
+        ))
+        .is_err());
+    }
+
+    #[test]
+    fn it_parse_ipv6() {
+        let routes = parse_ipv6(Cursor::new(
+            r#"
+fe800000000000000000000000000000 40 00000000000000000000000000000000 00 00000000000000000000000000000000 00000100 00000001 00000000 00000001     eth0
+00000000000000000000000000000000 00 00000000000000000000000000000000 00 fe800000000000000000000000000001 00000400 00000001 00000000 00450003     eth0
+00000000000000000000000000000000 00 00000000000000000000000000000000 00 00000000000000000000000000000000 ffffffff 00000001 00000000 00200200       lo
+        "#,
+        ))
+        .unwrap()
+        .collect::<Vec<_>>();
+
+        assert_eq!(3, routes.len());
+        assert_eq!("fe80::".parse::<Ipv6Addr>().unwrap(), routes[0].destination);
+        assert_eq!(64, routes[0].destination_prefix);
+        assert_eq!(256, routes[0].metric);
+        assert!(routes[1]
+            .flags
+            .contains(RouteFlags::DEFAULT | RouteFlags::ADDRCONF));
+
+        let gateway = default_gateway_ipv6(&routes).unwrap();
+        assert_eq!("fe80::1".parse::<Ipv6Addr>().unwrap(), gateway.next_hop);
+        assert_eq!(1024, gateway.metric);
+
+        assert!(parse_ipv6(Cursor::new("fe80 40 eth0")).is_err());
+    }
+}
@@ -0,0 +1,393 @@
+use std::{
+    collections::BTreeMap,
+    fs,
+    io::{self, BufRead, BufReader},
+};
+
+use super::super::ParseProcError;
+
+type Group = BTreeMap<String, i64>;
+
+fn counter(group: Option<&Group>, name: &str) -> u64 {
+    group
+        .and_then(|group| group.get(name))
+        .map(|value| (*value).max(0) as u64)
+        .unwrap_or_default()
+}
+
+/// The `Ip` counters.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct Ip {
+    pub in_receives: u64,
+    pub in_hdr_errors: u64,
+    pub in_addr_errors: u64,
+    pub forw_datagrams: u64,
+    pub in_unknown_protos: u64,
+    pub in_discards: u64,
+    pub in_delivers: u64,
+    pub out_requests: u64,
+    pub out_discards: u64,
+    pub out_no_routes: u64,
+    pub reasm_fails: u64,
+    pub frag_fails: u64,
+}
+
+impl Ip {
+    fn from_group(group: Option<&Group>) -> Self {
+        Ip {
+            in_receives: counter(group, "InReceives"),
+            in_hdr_errors: counter(group, "InHdrErrors"),
+            in_addr_errors: counter(group, "InAddrErrors"),
+            forw_datagrams: counter(group, "ForwDatagrams"),
+            in_unknown_protos: counter(group, "InUnknownProtos"),
+            in_discards: counter(group, "InDiscards"),
+            in_delivers: counter(group, "InDelivers"),
+            out_requests: counter(group, "OutRequests"),
+            out_discards: counter(group, "OutDiscards"),
+            out_no_routes: counter(group, "OutNoRoutes"),
+            reasm_fails: counter(group, "ReasmFails"),
+            frag_fails: counter(group, "FragFails"),
+        }
+    }
+}
+
+/// The `Icmp` counters.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct Icmp {
+    pub in_msgs: u64,
+    pub in_errors: u64,
+    pub in_csum_errors: u64,
+    pub in_dest_unreachs: u64,
+    pub in_echos: u64,
+    pub in_echo_reps: u64,
+    pub out_msgs: u64,
+    pub out_errors: u64,
+    pub out_dest_unreachs: u64,
+    pub out_echos: u64,
+    pub out_echo_reps: u64,
+}
+
+impl Icmp {
+    fn from_group(group: Option<&Group>) -> Self {
+        Icmp {
+            in_msgs: counter(group, "InMsgs"),
+            in_errors: counter(group, "InErrors"),
+            in_csum_errors: counter(group, "InCsumErrors"),
+            in_dest_unreachs: counter(group, "InDestUnreachs"),
+            in_echos: counter(group, "InEchos"),
+            in_echo_reps: counter(group, "InEchoReps"),
+            out_msgs: counter(group, "OutMsgs"),
+            out_errors: counter(group, "OutErrors"),
+            out_dest_unreachs: counter(group, "OutDestUnreachs"),
+            out_echos: counter(group, "OutEchos"),
+            out_echo_reps: counter(group, "OutEchoReps"),
+        }
+    }
+}
+
+/// The `Tcp` counters, `curr_estab` is a gauge rather than a counter.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct Tcp {
+    pub active_opens: u64,
+    pub passive_opens: u64,
+    pub attempt_fails: u64,
+    pub estab_resets: u64,
+    pub curr_estab: u64,
+    pub in_segs: u64,
+    pub out_segs: u64,
+    pub retrans_segs: u64,
+    pub in_errs: u64,
+    pub out_rsts: u64,
+    pub in_csum_errors: u64,
+}
+
+impl Tcp {
+    fn from_group(group: Option<&Group>) -> Self {
+        Tcp {
+            active_opens: counter(group, "ActiveOpens"),
+            passive_opens: counter(group, "PassiveOpens"),
+            attempt_fails: counter(group, "AttemptFails"),
+            estab_resets: counter(group, "EstabResets"),
+            curr_estab: counter(group, "CurrEstab"),
+            in_segs: counter(group, "InSegs"),
+            out_segs: counter(group, "OutSegs"),
+            retrans_segs: counter(group, "RetransSegs"),
+            in_errs: counter(group, "InErrs"),
+            out_rsts: counter(group, "OutRsts"),
+            in_csum_errors: counter(group, "InCsumErrors"),
+        }
+    }
+}
+
+/// The `Udp` counters.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct Udp {
+    pub in_datagrams: u64,
+    pub no_ports: u64,
+    pub in_errors: u64,
+    pub out_datagrams: u64,
+    pub rcvbuf_errors: u64,
+    pub sndbuf_errors: u64,
+    pub in_csum_errors: u64,
+}
+
+impl Udp {
+    fn from_group(group: Option<&Group>) -> Self {
+        Udp {
+            in_datagrams: counter(group, "InDatagrams"),
+            no_ports: counter(group, "NoPorts"),
+            in_errors: counter(group, "InErrors"),
+            out_datagrams: counter(group, "OutDatagrams"),
+            rcvbuf_errors: counter(group, "RcvbufErrors"),
+            sndbuf_errors: counter(group, "SndbufErrors"),
+            in_csum_errors: counter(group, "InCsumErrors"),
+        }
+    }
+}
+
+/// The most used `TcpExt` counters of `/proc/net/netstat`.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct TcpExt {
+    pub syncookies_sent: u64,
+    pub syncookies_recv: u64,
+    pub syncookies_failed: u64,
+    pub listen_overflows: u64,
+    pub listen_drops: u64,
+    pub tcp_timeouts: u64,
+    pub tcp_fast_retrans: u64,
+    pub tcp_slow_start_retrans: u64,
+    pub tcp_lost_retransmit: u64,
+    pub tcp_backlog_drop: u64,
+    pub tcp_abort_on_data: u64,
+    pub tcp_abort_on_timeout: u64,
+    pub tcp_abort_on_memory: u64,
+    pub tcp_retrans_fail: u64,
+}
+
+impl TcpExt {
+    fn from_group(group: Option<&Group>) -> Self {
+        TcpExt {
+            syncookies_sent: counter(group, "SyncookiesSent"),
+            syncookies_recv: counter(group, "SyncookiesRecv"),
+            syncookies_failed: counter(group, "SyncookiesFailed"),
+            listen_overflows: counter(group, "ListenOverflows"),
+            listen_drops: counter(group, "ListenDrops"),
+            tcp_timeouts: counter(group, "TCPTimeouts"),
+            tcp_fast_retrans: counter(group, "TCPFastRetrans"),
+            tcp_slow_start_retrans: counter(group, "TCPSlowStartRetrans"),
+            tcp_lost_retransmit: counter(group, "TCPLostRetransmit"),
+            tcp_backlog_drop: counter(group, "TCPBacklogDrop"),
+            tcp_abort_on_data: counter(group, "TCPAbortOnData"),
+            tcp_abort_on_timeout: counter(group, "TCPAbortOnTimeout"),
+            tcp_abort_on_memory: counter(group, "TCPAbortOnMemory"),
+            tcp_retrans_fail: counter(group, "TCPRetransFail"),
+        }
+    }
+}
+
+///
+/// The counters of `/proc/net/snmp` and `/proc/net/netstat` by group and name,
+/// like `Tcp` and `RetransSegs`, with typed views of the common groups.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct Counters {
+    pub groups: BTreeMap<String, BTreeMap<String, i64>>,
+}
+
+impl Counters {
+    pub fn get(&self, group: &str, name: &str) -> Option<i64> {
+        self.groups.get(group)?.get(name).copied()
+    }
+
+    pub fn ip(&self) -> Ip {
+        Ip::from_group(self.groups.get("Ip"))
+    }
+
+    pub fn icmp(&self) -> Icmp {
+        Icmp::from_group(self.groups.get("Icmp"))
+    }
+
+    pub fn tcp(&self) -> Tcp {
+        Tcp::from_group(self.groups.get("Tcp"))
+    }
+
+    pub fn udp(&self) -> Udp {
+        Udp::from_group(self.groups.get("Udp"))
+    }
+
+    pub fn tcp_ext(&self) -> TcpExt {
+        TcpExt::from_group(self.groups.get("TcpExt"))
+    }
+
+    ///
+    /// Computes the counters increase between the `previous` sample and this one.
+    /// Gauges like `CurrEstab` or `Forwarding` are subtracted too and meaningless in the result.
+    ///
+    /// ```no_run
+    /// use std::{thread, time::Duration};
+    ///
+    /// use unixism::proc::net::snmp;
+    ///
+    /// let previous = snmp::parse_default().unwrap();
+    /// thread::sleep(Duration::from_secs(1));
+    /// let current = snmp::parse_default().unwrap();
+    ///
+    /// println!("{} retransmits/s", current.delta(&previous).tcp().retrans_segs);
+    /// ```
+    pub fn delta(&self, previous: &Counters) -> Counters {
+        Counters {
+            groups: self
+                .groups
+                .iter()
+                .map(|(group, counters)| {
+                    let counters = counters
+                        .iter()
+                        .map(|(name, value)| {
+                            let previous = previous.get(group, name).unwrap_or_default();
+
+                            (name.clone(), value.wrapping_sub(previous))
+                        })
+                        .collect();
+
+                    (group.clone(), counters)
+                })
+                .collect(),
+        }
+    }
+}
+
+impl Extend<(String, BTreeMap<String, i64>)> for Counters {
+    fn extend<I>(&mut self, iter: I)
+    where
+        I: IntoIterator<Item = (String, BTreeMap<String, i64>)>,
+    {
+        for (group, counters) in iter {
+            self.groups.entry(group).or_default().extend(counters);
+        }
+    }
+}
+
+///
+/// Parses the pairs of name and value lines both `/proc/net/snmp` and `/proc/net/netstat` use.
+///
+/// ```no_run
+/// let counters = unixism::proc::net::snmp::parse(std::fs::File::open("/proc/net/snmp").unwrap()).unwrap();
+///
+/// println!("{} segments retransmitted", counters.tcp().retrans_segs);
+/// ```
+pub fn parse<R>(reader: R) -> Result<Counters, ParseProcError>
+where
+    R: io::Read,
+{
+    let mut counters = Counters::default();
+    let mut lines = BufReader::new(reader).lines();
+
+    while let Some(names) = lines.next() {
+        let names = names?;
+
+        if names.trim().is_empty() {
+            continue;
+        }
+
+        let values = lines
+            .next()
+            .transpose()?
+            .ok_or_else(|| ParseProcError::InvalidEntry(names.clone()))?;
+
+        let (Some((group, names)), Some((value_group, values))) =
+            (names.split_once(':'), values.split_once(':'))
+        else {
+            return Err(ParseProcError::InvalidEntry(names));
+        };
+
+        let names = names.split_whitespace().collect::<Vec<_>>();
+        let values = values
+            .split_whitespace()
+            .map(str::parse::<i64>)
+            .collect::<Result<Vec<_>, _>>()?;
+
+        if group != value_group || names.len() != values.len() {
+            return Err(ParseProcError::InvalidEntry(group.to_owned()));
+        }
+
+        counters.extend([(
+            group.to_owned(),
+            names.into_iter().map(String::from).zip(values).collect(),
+        )]);
+    }
+
+    Ok(counters)
+}
+
+///
+/// Same as parse, but parses the `/proc/net/snmp` and `/proc/net/netstat` together as default.
+///
+/// ```no_run
+/// let counters = unixism::proc::net::snmp::parse_default().unwrap();
+///
+/// println!("{} listen overflows", counters.tcp_ext().listen_overflows);
+/// ```
+pub fn parse_default() -> Result<Counters, ParseProcError> {
+    let mut counters = parse(fs::File::open("/proc/net/snmp")?)?;
+    counters.extend(parse(fs::File::open("/proc/net/netstat")?)?.groups);
+
+    Ok(counters)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Cursor;
+
+    use super::*;
+
+    #[test]
+    fn it_parse() {
+        let previous = parse(Cursor::new(
+            r#"
+Ip: Forwarding DefaultTTL InReceives InHdrErrors InAddrErrors ForwDatagrams InUnknownProtos InDiscards InDelivers OutRequests OutDiscards OutNoRoutes
+Ip: 1 64 1000 0 0 0 0 0 990 800 0 2
+Tcp: RtoAlgorithm RtoMin RtoMax MaxConn ActiveOpens PassiveOpens AttemptFails EstabResets CurrEstab InSegs OutSegs RetransSegs InErrs OutRsts InCsumErrors
+Tcp: 1 200 120000 -1 50 20 3 4 10 5000 4500 12 0 7 0
+Udp: InDatagrams NoPorts InErrors OutDatagrams RcvbufErrors SndbufErrors InCsumErrors IgnoredMulti
+Udp: 300 5 0 310 0 0 0 2
+        "#,
+        ))
+        .unwrap();
+
+        assert_eq!(Some(-1), previous.get("Tcp", "MaxConn"));
+        assert_eq!(1000, previous.ip().in_receives);
+        assert_eq!(2, previous.ip().out_no_routes);
+        assert_eq!(12, previous.tcp().retrans_segs);
+        assert_eq!(10, previous.tcp().curr_estab);
+        assert_eq!(5, previous.udp().no_ports);
+        assert_eq!(Icmp::default(), previous.icmp());
+
+        let mut current = parse(Cursor::new(
+            r#"
+Tcp: RtoAlgorithm RtoMin RtoMax MaxConn ActiveOpens PassiveOpens AttemptFails EstabResets CurrEstab InSegs OutSegs RetransSegs InErrs OutRsts InCsumErrors
+Tcp: 1 200 120000 -1 60 20 3 4 12 6000 5400 20 0 7 0
+        "#,
+        ))
+        .unwrap();
+        current.extend(
+            parse(Cursor::new(
+                "TcpExt: SyncookiesSent ListenOverflows ListenDrops\nTcpExt: 0 3 3\n",
+            ))
+            .unwrap()
+            .groups,
+        );
+
+        assert_eq!(3, current.tcp_ext().listen_drops);
+
+        let delta = current.delta(&previous);
+        assert_eq!(10, delta.tcp().active_opens);
+        assert_eq!(900, delta.tcp().out_segs);
+        assert_eq!(8, delta.tcp().retrans_segs);
+        assert_eq!(Some(0), delta.get("Tcp", "MaxConn"));
+        assert_eq!(3, delta.tcp_ext().listen_overflows);
+
+        assert!(parse(Cursor::new("Tcp: ActiveOpens\n")).is_err());
+        assert!(parse(Cursor::new("Tcp: ActiveOpens\nUdp: 1\n")).is_err());
+        assert!(parse(Cursor::new("Tcp: ActiveOpens PassiveOpens\nTcp: 1\n")).is_err());
+        assert!(parse(Cursor::new("Tcp: ActiveOpens\nTcp: x\n")).is_err());
+    }
+}
@@ -0,0 +1,245 @@
+use std::{
+    fs,
+    io::{self, BufRead, BufReader},
+    net::{IpAddr, Ipv4Addr, Ipv6Addr, SocketAddr},
+    str::FromStr,
+};
+
+use super::super::ParseProcError;
+
+/// The socket tables under `/proc/net`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Protocol {
+    Tcp,
+    Tcp6,
+    Udp,
+    Udp6,
+}
+
+impl Protocol {
+    pub fn path(&self) -> &'static str {
+        match self {
+            Self::Tcp => "/proc/net/tcp",
+            Self::Tcp6 => "/proc/net/tcp6",
+            Self::Udp => "/proc/net/udp",
+            Self::Udp6 => "/proc/net/udp6",
+        }
+    }
+}
+
+/// The kernel socket state, numbered as `TCP_ESTABLISHED` and friends.
+/// UDP sockets report `Established` when connected and `Close` otherwise.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum State {
+    Established,
+    SynSent,
+    SynRecv,
+    FinWait1,
+    FinWait2,
+    TimeWait,
+    Close,
+    CloseWait,
+    LastAck,
+    Listen,
+    Closing,
+    NewSynRecv,
+    Other(u8),
+}
+
+impl From<u8> for State {
+    fn from(state: u8) -> Self {
+        match state {
+            1 => State::Established,
+            2 => State::SynSent,
+            3 => State::SynRecv,
+            4 => State::FinWait1,
+            5 => State::FinWait2,
+            6 => State::TimeWait,
+            7 => State::Close,
+            8 => State::CloseWait,
+            9 => State::LastAck,
+            10 => State::Listen,
+            11 => State::Closing,
+            12 => State::NewSynRecv,
+            state => State::Other(state),
+        }
+    }
+}
+
+/// A row of `/proc/net/{tcp,tcp6,udp,udp6}`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Socket {
+    pub slot: u32,
+    pub local: SocketAddr,
+    pub remote: SocketAddr,
+    pub state: State,
+    pub tx_queue: u32,
+    pub rx_queue: u32,
+    pub uid: u32,
+    pub inode: u64,
+}
+
+impl FromStr for Socket {
+    type Err = ParseProcError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let fields = s.split_whitespace().collect::<Vec<_>>();
+
+        let [slot, local, remote, state, queues, _, _, uid, _, inode, ..] = fields[..] else {
+            return Err(ParseProcError::InvalidEntry(s.to_owned()));
+        };
+
+        let (tx_queue, rx_queue) = queues
+            .split_once(':')
+            .ok_or_else(|| ParseProcError::InvalidEntry(s.to_owned()))?;
+
+        Ok(Socket {
+            slot: slot.trim_end_matches(':').parse()?,
+            local: socket_addr(local)?,
+            remote: socket_addr(remote)?,
+            state: State::from(u8::from_str_radix(state, 16)?),
+            tx_queue: u32::from_str_radix(tx_queue, 16)?,
+            rx_queue: u32::from_str_radix(rx_queue, 16)?,
+            uid: uid.parse()?,
+            inode: inode.parse()?,
+        })
+    }
+}
+
+/// Decodes the `ADDR:PORT` pair, where the address is printed as native-endian 32-bit words.
+fn socket_addr(s: &str) -> Result<SocketAddr, ParseProcError> {
+    let (addr, port) = s
+        .split_once(':')
+        .ok_or_else(|| ParseProcError::InvalidEntry(s.to_owned()))?;
+
+    let ip = match addr.len() {
+        8 => IpAddr::V4(Ipv4Addr::from(u32::from_str_radix(addr, 16)?.to_ne_bytes())),
+        32 => {
+            let mut octets = [0u8; 16];
+
+            for (i, chunk) in octets.chunks_mut(4).enumerate() {
+                let word = u32::from_str_radix(&addr[i * 8..i * 8 + 8], 16)?;
+                chunk.copy_from_slice(&word.to_ne_bytes());
+            }
+
+            IpAddr::V6(Ipv6Addr::from(octets))
+        }
+        _ => return Err(ParseProcError::InvalidEntry(s.to_owned())),
+    };
+
+    Ok(SocketAddr::new(ip, u16::from_str_radix(port, 16)?))
+}
+
+///
+/// ```no_run
+/// let sockets = unixism::proc::net::sockets::parse(std::fs::File::open("/proc/net/tcp").unwrap()).unwrap();
+///
+/// for socket in sockets {
+///     println!("{} -> {} {:?}", socket.local, socket.remote, socket.state);
+/// }
+/// ```
+pub fn parse<R>(reader: R) -> Result<impl Iterator<Item = Socket>, ParseProcError>
+where
+    R: io::Read,
+{
+    let mut sockets = Vec::new();
+
+    for line in BufReader::new(reader).lines() {
+        let line = line?;
+        let line = line.trim();
+
+        if line.is_empty() || line.starts_with("sl") {
+            continue;
+        }
+
+        sockets.push(line.parse::<Socket>()?);
+    }
+
+    Ok(sockets.into_iter())
+}
+
+///
+/// Same as parse, but parses the table of the protocol.
+///
+/// ```no_run
+/// use unixism::proc::net::sockets::{self, Protocol, State};
+///
+/// let listening = sockets::parse_default(Protocol::Tcp)
+///     .unwrap()
+///     .filter(|socket| socket.state == State::Listen);
+/// ```
+pub fn parse_default(protocol: Protocol) -> Result<impl Iterator<Item = Socket>, ParseProcError> {
+    parse(fs::File::open(protocol.path())?)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Cursor;
+
+    use super::*;
+
+    #[test]
+    #[cfg(target_endian = "little")]
+    fn it_parse() {
+        let sockets = parse(Cursor::new(
+            r#"
+  sl  local_address rem_address   st tx_queue rx_queue tr tm->when retrnsmt   uid  timeout inode
+   0: 0100007F:0277 00000000:0000 0A 00000000:00000000 00:00000000 00000000     0        0 21733 1 0000000000000000 100 0 0 10 0
+   1: 0A01A8C0:D2F4 22D8BA8C:01BB 01 00000000:00000010 02:00000A2B 00000000  1000        0 553712 2 0000000000000000 20 4 30 10 -1
+        "#,
+        ))
+        .unwrap()
+        .collect::<Vec<_>>();
+
+        assert_eq!(2, sockets.len());
+        assert_eq!(
+            Socket {
+                slot: 0,
+                local: "127.0.0.1:631".parse().unwrap(),
+                remote: "0.0.0.0:0".parse().unwrap(),
+                state: State::Listen,
+                tx_queue: 0,
+                rx_queue: 0,
+                uid: 0,
+                inode: 21733,
+            },
+            sockets[0]
+        );
+        assert_eq!(
+            "192.168.1.10:54004".parse::<SocketAddr>().unwrap(),
+            sockets[1].local
+        );
+        assert_eq!(
+            "140.186.216.34:443".parse::<SocketAddr>().unwrap(),
+            sockets[1].remote
+        );
+        assert_eq!(State::Established, sockets[1].state);
+        assert_eq!(16, sockets[1].rx_queue);
+        assert_eq!(1000, sockets[1].uid);
+
+        assert!(parse(Cursor::new("0: 0100007F:0277 00000000:0000 0A")).is_err());
+    }
+
+    #[test]
+    #[cfg(target_endian = "little")]
+    fn it_parse_ipv6() {
+        let sockets = parse(Cursor::new(
+            r#"
+  sl  local_address                         remote_address                        st tx_queue rx_queue tr tm->when retrnsmt   uid  timeout inode
+   0: 00000000000000000000000001000000:0035 00000000000000000000000000000000:0000 07 00000000:00000000 00:00000000 00000000   101        0 18060 2 0000000000000000 0
+   1: 000080FE00000000FF005450B6AD1DFE:0222 00000000000000000000000000000000:0000 07 00000000:00000000 00:00000000 00000000     0        0 31337 2 0000000000000000 0
+        "#,
+        ))
+        .unwrap()
+        .collect::<Vec<_>>();
+
+        assert_eq!("[::1]:53".parse::<SocketAddr>().unwrap(), sockets[0].local);
+        assert_eq!(State::Close, sockets[0].state);
+        assert_eq!(
+            "[fe80::5054:ff:fe1d:adb6]:546"
+                .parse::<SocketAddr>()
+                .unwrap(),
+            sockets[1].local
+        );
+    }
+}
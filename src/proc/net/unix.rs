@@ -0,0 +1,224 @@
+use std::{
+    fs,
+    io::{self, BufRead, BufReader},
+    str::FromStr,
+};
+
+use super::super::ParseProcError;
+
+/// The `__SO_ACCEPTCON` flag of listening sockets.
+pub const ACCEPTING: u32 = 0x10000;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SocketType {
+    Stream,
+    Datagram,
+    SeqPacket,
+    Other(u16),
+}
+
+impl From<u16> for SocketType {
+    fn from(kind: u16) -> Self {
+        match kind {
+            1 => SocketType::Stream,
+            2 => SocketType::Datagram,
+            5 => SocketType::SeqPacket,
+            kind => SocketType::Other(kind),
+        }
+    }
+}
+
+/// The `SS_*` socket state.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum UnixState {
+    Unconnected,
+    Connecting,
+    Connected,
+    Disconnecting,
+    Other(u8),
+}
+
+impl From<u8> for UnixState {
+    fn from(state: u8) -> Self {
+        match state {
+            1 => UnixState::Unconnected,
+            2 => UnixState::Connecting,
+            3 => UnixState::Connected,
+            4 => UnixState::Disconnecting,
+            state => UnixState::Other(state),
+        }
+    }
+}
+
+/// The name of a socket. The kernel prints names as raw bytes, the ones which aren't UTF-8 are converted lossily.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Address {
+    /// A socket without a name, like the ends of `socketpair` or accepted connections.
+    Unnamed,
+    Path(String),
+    /// A name in the abstract namespace, without the `@` the kernel shows for the leading NUL.
+    Abstract(String),
+}
+
+/// A row of `/proc/net/unix`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct UnixSocket {
+    pub ref_count: u32,
+    pub flags: u32,
+    pub kind: SocketType,
+    pub state: UnixState,
+    pub inode: u64,
+    pub address: Address,
+}
+
+impl UnixSocket {
+    pub fn is_listening(&self) -> bool {
+        self.flags & ACCEPTING != 0
+    }
+}
+
+impl FromStr for UnixSocket {
+    type Err = ParseProcError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let fields = s.split_whitespace().collect::<Vec<_>>();
+
+        if fields.len() < 7 {
+            return Err(ParseProcError::InvalidEntry(s.to_owned()));
+        }
+
+        // The path is the rest of the line, it may contain spaces.
+        let mut path = s.trim();
+        for _ in 0..7 {
+            path = path[path.find(char::is_whitespace).unwrap_or(path.len())..].trim_start();
+        }
+
+        Ok(UnixSocket {
+            ref_count: u32::from_str_radix(fields[1], 16)?,
+            flags: u32::from_str_radix(fields[3], 16)?,
+            kind: SocketType::from(u16::from_str_radix(fields[4], 16)?),
+            state: UnixState::from(u8::from_str_radix(fields[5], 16)?),
+            inode: fields[6].parse()?,
+            address: if path.is_empty() {
+                Address::Unnamed
+            } else if let Some(name) = path.strip_prefix('@') {
+                Address::Abstract(name.to_owned())
+            } else {
+                Address::Path(path.to_owned())
+            },
+        })
+    }
+}
+
+///
+/// ```no_run
+/// let sockets = unixism::proc::net::unix::parse(std::fs::File::open("/proc/net/unix").unwrap()).unwrap();
+///
+/// for socket in sockets.filter(|socket| socket.is_listening()) {
+///     println!("{:?} inode {}", socket.address, socket.inode);
+/// }
+/// ```
+pub fn parse<R>(reader: R) -> Result<impl Iterator<Item = UnixSocket>, ParseProcError>
+where
+    R: io::Read,
+{
+    let mut sockets = Vec::new();
+    let mut reader = BufReader::new(reader);
+    let mut buf = Vec::new();
+
+    loop {
+        buf.clear();
+        if reader.read_until(b'\n', &mut buf)? == 0 {
+            break;
+        }
+
+        let line = String::from_utf8_lossy(&buf);
+        let line = line.trim();
+
+        if line.is_empty() || line.starts_with("Num") {
+            continue;
+        }
+
+        sockets.push(line.parse::<UnixSocket>()?);
+    }
+
+    Ok(sockets.into_iter())
+}
+
+///
+/// Same as parse, but parses the `/proc/net/unix` as default.
+///
+/// ```no_run
+/// let sockets = unixism::proc::net::unix::parse_default().unwrap();
+/// ```
+pub fn parse_default() -> Result<impl Iterator<Item = UnixSocket>, ParseProcError> {
+    parse(fs::File::open("/proc/net/unix")?)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Cursor;
+
+    use super::*;
+
+    #[test]
+    fn it_parse() {
+        let sockets = parse(Cursor::new(
+            r#"
+Num       RefCount Protocol Flags    Type St Inode Path
+0000000000000000: 00000002 00000000 00010000 0001 01 12345 /run/systemd/private
+0000000000000000: 00000003 00000000 00000000 0001 03 23456
+0000000000000000: 00000002 00000000 00000000 0002 01 34567 @/tmp/.X11-unix/X0
+0000000000000000: 00000002 00000000 00010000 0005 01 45678 /run/my app/socket
+        "#,
+        ))
+        .unwrap()
+        .collect::<Vec<_>>();
+
+        assert_eq!(4, sockets.len());
+        assert_eq!(
+            UnixSocket {
+                ref_count: 2,
+                flags: ACCEPTING,
+                kind: SocketType::Stream,
+                state: UnixState::Unconnected,
+                inode: 12345,
+                address: Address::Path("/run/systemd/private".to_owned()),
+            },
+            sockets[0]
+        );
+        assert!(sockets[0].is_listening());
+        assert_eq!(Address::Unnamed, sockets[1].address);
+        assert_eq!(UnixState::Connected, sockets[1].state);
+        assert!(!sockets[1].is_listening());
+        assert_eq!(
+            Address::Abstract("/tmp/.X11-unix/X0".to_owned()),
+            sockets[2].address
+        );
+        assert_eq!(SocketType::Datagram, sockets[2].kind);
+        assert_eq!(SocketType::SeqPacket, sockets[3].kind);
+        assert_eq!(
+            Address::Path("/run/my app/socket".to_owned()),
+            sockets[3].address
+        );
+
+        let binary = parse(Cursor::new(
+            b"0000000000000000: 00000002 00000000 00000000 0001 01 56789 @\xff\xfeid\n".to_vec(),
+        ))
+        .unwrap()
+        .collect::<Vec<_>>();
+        assert_eq!(
+            Address::Abstract("\u{fffd}\u{fffd}id".to_owned()),
+            binary[0].address
+        );
+
+        assert!(parse(Cursor::new(
+            "0000000000000000: 00000002 00000000 00010000 0001 01"
+        ))
+        .is_err());
+        assert!(parse(Cursor::new(
+            "0000000000000000: 00000002 00000000 00010000 0001 01 x"
+        ))
+        .is_err());
+    }
+}
@@ -0,0 +1,110 @@
+use std::{
+    fs,
+    io::{self, BufRead, BufReader},
+    str::FromStr,
+};
+
+use super::ParseProcError;
+
+/// A row of `/proc/partitions`, the size is in 1 KiB blocks.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Partition {
+    pub major: u32,
+    pub minor: u32,
+    pub blocks: u64,
+    pub name: String,
+}
+
+impl FromStr for Partition {
+    type Err = ParseProcError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let fields = s.split_whitespace().collect::<Vec<_>>();
+
+        let [major, minor, blocks, name] = fields[..] else {
+            return Err(ParseProcError::InvalidEntry(s.to_owned()));
+        };
+
+        Ok(Partition {
+            major: major.parse()?,
+            minor: minor.parse()?,
+            blocks: blocks.parse()?,
+            name: name.to_owned(),
+        })
+    }
+}
+
+///
+/// ```no_run
+/// let partitions = unixism::proc::partitions::parse(std::fs::File::open("/proc/partitions").unwrap()).unwrap();
+///
+/// for partition in partitions {
+///     println!("{} ({}:{}): {} KiB", partition.name, partition.major, partition.minor, partition.blocks);
+/// }
+/// ```
+pub fn parse<R>(reader: R) -> Result<impl Iterator<Item = Partition>, ParseProcError>
+where
+    R: io::Read,
+{
+    let mut partitions = Vec::new();
+
+    for line in BufReader::new(reader).lines() {
+        let line = line?;
+        let line = line.trim();
+
+        if line.is_empty() || line.starts_with("major") {
+            continue;
+        }
+
+        partitions.push(line.parse::<Partition>()?);
+    }
+
+    Ok(partitions.into_iter())
+}
+
+///
+/// Same as parse, but parses the `/proc/partitions` as default.
+///
+/// ```no_run
+/// let partitions = unixism::proc::partitions::parse_default().unwrap();
+/// ```
+pub fn parse_default() -> Result<impl Iterator<Item = Partition>, ParseProcError> {
+    parse(fs::File::open("/proc/partitions")?)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Cursor;
+
+    use super::*;
+
+    #[test]
+    fn it_parse() {
+        let partitions = parse(Cursor::new(
+            r#"
+major minor  #blocks  name
+
+ 259        0  500107608 nvme0n1
+ 259        1     524288 nvme0n1p1
+   8       16 1953514584 sdb
+        "#,
+        ))
+        .unwrap()
+        .collect::<Vec<_>>();
+
+        assert_eq!(3, partitions.len());
+        assert_eq!(
+            Partition {
+                major: 259,
+                minor: 1,
+                blocks: 524288,
+                name: "nvme0n1p1".to_owned(),
+            },
+            partitions[1]
+        );
+        assert_eq!("sdb", partitions[2].name);
+
+        assert!(parse(Cursor::new("8 16 sdb")).is_err());
+        assert!(parse(Cursor::new("8 16 x sdb")).is_err());
+    }
+}
@@ -0,0 +1,130 @@
+use std::{collections::HashMap, ffi::OsString, fs, io, os::unix::ffi::OsStringExt};
+
+use super::super::ParseProcError;
+
+fn split_nul(content: &[u8]) -> impl Iterator<Item = &[u8]> {
+    content
+        .strip_suffix(b"\0")
+        .unwrap_or(content)
+        .split(|byte| *byte == 0)
+        .filter(|_| !content.is_empty())
+}
+
+///
+/// Splits the NUL-delimited arguments, kernel threads and zombies have none.
+///
+/// ```no_run
+/// let args = unixism::proc::process::parse_cmdline(std::fs::File::open("/proc/self/cmdline").unwrap()).unwrap();
+///
+/// println!("{:?}", args);
+/// ```
+pub fn parse_cmdline<R>(mut reader: R) -> Result<Vec<OsString>, ParseProcError>
+where
+    R: io::Read,
+{
+    let mut content = Vec::new();
+    reader.read_to_end(&mut content)?;
+
+    Ok(split_nul(&content)
+        .map(|arg| OsString::from_vec(arg.to_vec()))
+        .collect())
+}
+
+///
+/// Same as parse_cmdline, but parses the `/proc/<pid>/cmdline` of the process.
+///
+/// ```no_run
+/// let args = unixism::proc::process::cmdline(1).unwrap();
+/// ```
+pub fn cmdline(pid: u32) -> Result<Vec<OsString>, ParseProcError> {
+    parse_cmdline(fs::File::open(format!("/proc/{}/cmdline", pid))?)
+}
+
+///
+/// Splits the NUL-delimited `NAME=value` variables, a variable without `=` gets an empty value.
+///
+/// ```no_run
+/// let environ = unixism::proc::process::parse_environ(std::fs::File::open("/proc/self/environ").unwrap()).unwrap();
+///
+/// println!("{:?}", environ.get(std::ffi::OsStr::new("PATH")));
+/// ```
+pub fn parse_environ<R>(mut reader: R) -> Result<HashMap<OsString, OsString>, ParseProcError>
+where
+    R: io::Read,
+{
+    let mut content = Vec::new();
+    reader.read_to_end(&mut content)?;
+
+    Ok(split_nul(&content)
+        .filter(|variable| !variable.is_empty())
+        .map(|variable| {
+            let (name, value) = match variable.iter().position(|byte| *byte == b'=') {
+                Some(i) => (&variable[..i], &variable[i + 1..]),
+                None => (variable, &[][..]),
+            };
+
+            (
+                OsString::from_vec(name.to_vec()),
+                OsString::from_vec(value.to_vec()),
+            )
+        })
+        .collect())
+}
+
+///
+/// Same as parse_environ, but parses the `/proc/<pid>/environ` of the process.
+///
+/// ```no_run
+/// let environ = unixism::proc::process::environ(1).unwrap();
+/// ```
+pub fn environ(pid: u32) -> Result<HashMap<OsString, OsString>, ParseProcError> {
+    parse_environ(fs::File::open(format!("/proc/{}/environ", pid))?)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Cursor;
+
+    use super::*;
+
+    #[test]
+    fn it_parse_cmdline() {
+        assert_eq!(
+            vec![
+                OsString::from("/usr/bin/python3"),
+                OsString::from(""),
+                OsString::from("my script.py"),
+            ],
+            parse_cmdline(Cursor::new(b"/usr/bin/python3\0\0my script.py\0")).unwrap()
+        );
+        assert_eq!(
+            vec![OsString::from("nginx: worker process")],
+            parse_cmdline(Cursor::new(b"nginx: worker process")).unwrap()
+        );
+        assert!(parse_cmdline(Cursor::new(b"")).unwrap().is_empty());
+    }
+
+    #[test]
+    fn it_parse_environ() {
+        let environ = parse_environ(Cursor::new(
+            b"PATH=/usr/bin:/bin\0EMPTY=\0EQ=a=b\0BARE\0\xffRAW=\xfe\0".to_vec(),
+        ))
+        .unwrap();
+
+        assert_eq!(5, environ.len());
+        assert_eq!(
+            Some(&OsString::from("/usr/bin:/bin")),
+            environ.get(&OsString::from("PATH"))
+        );
+        assert_eq!(
+            Some(&OsString::from("a=b")),
+            environ.get(&OsString::from("EQ"))
+        );
+        assert_eq!(Some(&OsString::new()), environ.get(&OsString::from("BARE")));
+        assert_eq!(
+            Some(&OsString::from_vec(vec![0xfe])),
+            environ.get(&OsString::from_vec(b"\xffRAW".to_vec()))
+        );
+        assert!(parse_environ(Cursor::new(b"")).unwrap().is_empty());
+    }
+}
@@ -0,0 +1,138 @@
+use std::{
+    fs,
+    path::{Path, PathBuf},
+};
+
+use super::super::ParseProcError;
+
+/// What an open file descriptor refers to, from its `/proc/<pid>/fd` link.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum FdTarget {
+    File(PathBuf),
+    /// A socket inode, as in `/proc/net/tcp`.
+    Socket(u64),
+    /// A pipe inode, shared by both ends.
+    Pipe(u64),
+    /// An anonymous inode like `eventfd`, `inotify` or `[timerfd]`, without the brackets.
+    AnonInode(String),
+    Other(PathBuf),
+}
+
+impl From<PathBuf> for FdTarget {
+    fn from(path: PathBuf) -> Self {
+        let Some(target) = path.to_str() else {
+            return FdTarget::File(path);
+        };
+
+        let inode = |prefix: &str| {
+            target
+                .strip_prefix(prefix)
+                .and_then(|target| target.strip_prefix('['))
+                .and_then(|target| target.strip_suffix(']'))
+                .and_then(|inode| inode.parse::<u64>().ok())
+        };
+
+        if let Some(inode) = inode("socket:") {
+            return FdTarget::Socket(inode);
+        }
+
+        if let Some(inode) = inode("pipe:") {
+            return FdTarget::Pipe(inode);
+        }
+
+        if let Some(kind) = target.strip_prefix("anon_inode:") {
+            let kind = kind.strip_prefix('[').unwrap_or(kind);
+
+            return FdTarget::AnonInode(kind.strip_suffix(']').unwrap_or(kind).to_owned());
+        }
+
+        if path.is_absolute() {
+            FdTarget::File(path)
+        } else {
+            FdTarget::Other(path)
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Fd {
+    pub fd: u32,
+    pub target: FdTarget,
+}
+
+/// Reads the `fd` directory of a process, descriptors closed meanwhile are skipped.
+pub(super) fn read_fds(path: &Path) -> Result<Vec<Fd>, ParseProcError> {
+    let mut fds = Vec::new();
+
+    for entry in fs::read_dir(path.join("fd"))?.filter_map(Result::ok) {
+        let Some(fd) = entry
+            .file_name()
+            .to_str()
+            .and_then(|fd| fd.parse::<u32>().ok())
+        else {
+            continue;
+        };
+
+        let Ok(target) = fs::read_link(entry.path()) else {
+            continue;
+        };
+
+        fds.push(Fd {
+            fd,
+            target: FdTarget::from(target),
+        });
+    }
+
+    fds.sort_by_key(|fd| fd.fd);
+
+    Ok(fds)
+}
+
+///
+/// Lists the open file descriptors of the process, sorted by number.
+///
+/// ```no_run
+/// use unixism::proc::process::{self, FdTarget};
+///
+/// for fd in process::fds(1).unwrap() {
+///     if let FdTarget::Socket(inode) = fd.target {
+///         println!("fd {} is socket {}", fd.fd, inode);
+///     }
+/// }
+/// ```
+pub fn fds(pid: u32) -> Result<Vec<Fd>, ParseProcError> {
+    read_fds(Path::new(&format!("/proc/{}", pid)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn it_classify() {
+        for (target, expected) in [
+            ("/var/log/syslog", FdTarget::File("/var/log/syslog".into())),
+            (
+                "/tmp/x (deleted)",
+                FdTarget::File("/tmp/x (deleted)".into()),
+            ),
+            ("socket:[12345]", FdTarget::Socket(12345)),
+            ("pipe:[678]", FdTarget::Pipe(678)),
+            (
+                "anon_inode:[eventfd]",
+                FdTarget::AnonInode("eventfd".to_owned()),
+            ),
+            (
+                "anon_inode:inotify",
+                FdTarget::AnonInode("inotify".to_owned()),
+            ),
+            (
+                "net:[4026531840]",
+                FdTarget::Other("net:[4026531840]".into()),
+            ),
+            ("socket:[x]", FdTarget::Other("socket:[x]".into())),
+        ] {
+            assert_eq!(expected, FdTarget::from(PathBuf::from(target)));
+        }
+    }
+}
@@ -0,0 +1,106 @@
+use std::{
+    fs,
+    io::{self, BufRead, BufReader},
+};
+
+use super::super::ParseProcError;
+
+///
+/// The IO counters of `/proc/<pid>/io`, in bytes or system calls.
+/// `rchar` and `wchar` count everything passed to read and write, `read_bytes` and `write_bytes` only what hit the storage.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct Io {
+    pub rchar: u64,
+    pub wchar: u64,
+    pub syscr: u64,
+    pub syscw: u64,
+    pub read_bytes: u64,
+    pub write_bytes: u64,
+    pub cancelled_write_bytes: u64,
+}
+
+///
+/// ```no_run
+/// let io = unixism::proc::process::parse_io(std::fs::File::open("/proc/self/io").unwrap()).unwrap();
+///
+/// println!("{} bytes read from storage, {} written", io.read_bytes, io.write_bytes);
+/// ```
+pub fn parse_io<R>(reader: R) -> Result<Io, ParseProcError>
+where
+    R: io::Read,
+{
+    let mut counters = Io::default();
+
+    for line in BufReader::new(reader).lines() {
+        let line = line?;
+
+        if line.trim().is_empty() {
+            continue;
+        }
+
+        let (key, value) = line
+            .split_once(':')
+            .ok_or_else(|| ParseProcError::InvalidEntry(line.clone()))?;
+        let value = value.trim().parse()?;
+
+        match key {
+            "rchar" => counters.rchar = value,
+            "wchar" => counters.wchar = value,
+            "syscr" => counters.syscr = value,
+            "syscw" => counters.syscw = value,
+            "read_bytes" => counters.read_bytes = value,
+            "write_bytes" => counters.write_bytes = value,
+            "cancelled_write_bytes" => counters.cancelled_write_bytes = value,
+            _ => {}
+        }
+    }
+
+    Ok(counters)
+}
+
+///
+/// Same as parse_io, but parses the `/proc/<pid>/io` of the process, readable by its owner or root only.
+///
+/// ```no_run
+/// let io = unixism::proc::process::io(1).unwrap();
+/// ```
+pub fn io(pid: u32) -> Result<Io, ParseProcError> {
+    parse_io(fs::File::open(format!("/proc/{}/io", pid))?)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Cursor;
+
+    use super::*;
+
+    #[test]
+    fn it_parse_io() {
+        assert_eq!(
+            Io {
+                rchar: 323934931,
+                wchar: 323929600,
+                syscr: 632687,
+                syscw: 632675,
+                read_bytes: 4096,
+                write_bytes: 323932160,
+                cancelled_write_bytes: 0,
+            },
+            parse_io(Cursor::new(
+                r#"
+rchar: 323934931
+wchar: 323929600
+syscr: 632687
+syscw: 632675
+read_bytes: 4096
+write_bytes: 323932160
+cancelled_write_bytes: 0
+            "#,
+            ))
+            .unwrap()
+        );
+
+        assert!(parse_io(Cursor::new("rchar 1")).is_err());
+        assert!(parse_io(Cursor::new("rchar: x")).is_err());
+    }
+}
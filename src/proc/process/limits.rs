@@ -0,0 +1,146 @@
+use std::{
+    fs,
+    io::{self, BufRead, BufReader},
+};
+
+use super::super::ParseProcError;
+
+/// A row of `/proc/<pid>/limits`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Limit {
+    /// The description, like `Max open files`.
+    pub name: String,
+    /// `None` when unlimited.
+    pub soft: Option<u64>,
+    /// `None` when unlimited.
+    pub hard: Option<u64>,
+    /// The unit, like `files` or `bytes`, missing for the nice and realtime priorities.
+    pub unit: Option<String>,
+}
+
+fn value(s: &str) -> Result<Option<u64>, ParseProcError> {
+    match s {
+        "unlimited" => Ok(None),
+        s => Ok(Some(s.parse()?)),
+    }
+}
+
+///
+/// Parses the limits table using the columns of its header, as the names contain spaces.
+///
+/// ```no_run
+/// let limits = unixism::proc::process::parse_limits(std::fs::File::open("/proc/self/limits").unwrap()).unwrap();
+///
+/// for limit in limits {
+///     println!("{}: {:?}/{:?} {}", limit.name, limit.soft, limit.hard, limit.unit.unwrap_or_default());
+/// }
+/// ```
+pub fn parse_limits<R>(reader: R) -> Result<impl Iterator<Item = Limit>, ParseProcError>
+where
+    R: io::Read,
+{
+    let mut limits = Vec::new();
+    let mut columns = None;
+
+    for line in BufReader::new(reader).lines() {
+        let line = line?;
+
+        if line.trim().is_empty() {
+            continue;
+        }
+
+        let Some((soft, hard, unit)) = columns else {
+            columns = match (
+                line.find("Soft Limit"),
+                line.find("Hard Limit"),
+                line.find("Units"),
+            ) {
+                (Some(soft), Some(hard), Some(unit)) if soft < hard && hard < unit => {
+                    Some((soft, hard, unit))
+                }
+                _ => return Err(ParseProcError::InvalidEntry(line)),
+            };
+            continue;
+        };
+
+        let column = |from: usize, to: usize| line.get(from..to.min(line.len())).map(str::trim);
+
+        let (Some(name), Some(soft), Some(hard)) =
+            (column(0, soft), column(soft, hard), column(hard, unit))
+        else {
+            return Err(ParseProcError::InvalidEntry(line));
+        };
+
+        if name.is_empty() || soft.is_empty() || hard.is_empty() {
+            return Err(ParseProcError::InvalidEntry(line));
+        }
+
+        limits.push(Limit {
+            name: name.to_owned(),
+            soft: value(soft)?,
+            hard: value(hard)?,
+            unit: column(unit, line.len())
+                .filter(|unit| !unit.is_empty())
+                .map(String::from),
+        });
+    }
+
+    Ok(limits.into_iter())
+}
+
+///
+/// Same as parse_limits, but parses the `/proc/<pid>/limits` of the process.
+///
+/// ```no_run
+/// let nofile = unixism::proc::process::limits(1)
+///     .unwrap()
+///     .find(|limit| limit.name == "Max open files");
+/// ```
+pub fn limits(pid: u32) -> Result<impl Iterator<Item = Limit>, ParseProcError> {
+    parse_limits(fs::File::open(format!("/proc/{}/limits", pid))?)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Cursor;
+
+    use super::*;
+
+    #[test]
+    fn it_parse_limits() {
+        let limits = parse_limits(Cursor::new(
+            r#"
+Limit                     Soft Limit           Hard Limit           Units     
+Max cpu time              unlimited            unlimited            seconds   
+Max stack size            8388608              unlimited            bytes     
+Max open files            1024                 524288               files     
+Max nice priority         0                    0                    
+Max realtime timeout      unlimited            unlimited            us        
+        "#,
+        ))
+        .unwrap()
+        .collect::<Vec<_>>();
+
+        assert_eq!(5, limits.len());
+        assert_eq!(
+            Limit {
+                name: "Max open files".to_owned(),
+                soft: Some(1024),
+                hard: Some(524288),
+                unit: Some("files".to_owned()),
+            },
+            limits[2]
+        );
+        assert_eq!(None, limits[0].soft);
+        assert_eq!(Some(8388608), limits[1].soft);
+        assert_eq!(None, limits[1].hard);
+        assert_eq!(None, limits[3].unit);
+        assert_eq!(Some("us"), limits[4].unit.as_deref());
+
+        assert!(parse_limits(Cursor::new("Max open files 1024 4096 files")).is_err());
+        assert!(parse_limits(Cursor::new(
+            "Limit          Soft Limit Hard Limit Units\nMax open files x          4096       files"
+        ))
+        .is_err());
+    }
+}
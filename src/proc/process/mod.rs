@@ -0,0 +1,286 @@
+use std::{
+    collections::HashMap,
+    ffi::OsString,
+    fs,
+    path::{Path, PathBuf},
+};
+
+use super::ParseProcError;
+
+mod cmdline;
+mod fd;
+mod io;
+mod limits;
+mod smaps;
+mod stat;
+mod status;
+
+pub use cmdline::{cmdline, environ, parse_cmdline, parse_environ};
+pub use fd::{fds, Fd, FdTarget};
+pub use io::{io, parse_io, Io};
+pub use limits::{limits, parse_limits, Limit};
+pub use smaps::{parse_smaps_rollup, smaps_rollup, SmapsRollup};
+pub use stat::{parse_stat, stat, Stat};
+pub use status::{parse_status, status, Ids, Status};
+
+/// The scheduling state of a process, as the letter of `/proc/<pid>/stat` and `/proc/<pid>/status`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum State {
+    Running,
+    Sleeping,
+    DiskSleep,
+    Stopped,
+    TracingStop,
+    Zombie,
+    Dead,
+    Idle,
+    Other(char),
+}
+
+impl From<char> for State {
+    fn from(c: char) -> Self {
+        match c {
+            'R' => State::Running,
+            'S' => State::Sleeping,
+            'D' => State::DiskSleep,
+            'T' => State::Stopped,
+            't' => State::TracingStop,
+            'Z' => State::Zombie,
+            'X' | 'x' => State::Dead,
+            'I' => State::Idle,
+            c => State::Other(c),
+        }
+    }
+}
+
+///
+/// A process directory under `/proc`, read lazily by its accessors.
+/// The process may exit at any time, accessors then fail with a `NotFound` IO error.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Process {
+    pub pid: u32,
+    path: PathBuf,
+}
+
+impl Process {
+    pub fn new(pid: u32) -> Self {
+        Process::at("/proc", pid)
+    }
+
+    /// Same as new, but with the proc filesystem mounted at `root`.
+    pub fn at<P>(root: P, pid: u32) -> Self
+    where
+        P: AsRef<Path>,
+    {
+        Process {
+            pid,
+            path: root.as_ref().join(pid.to_string()),
+        }
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    pub fn status(&self) -> Result<Status, ParseProcError> {
+        parse_status(fs::File::open(self.path.join("status"))?)
+    }
+
+    pub fn stat(&self) -> Result<Stat, ParseProcError> {
+        parse_stat(fs::File::open(self.path.join("stat"))?)
+    }
+
+    pub fn cmdline(&self) -> Result<Vec<OsString>, ParseProcError> {
+        parse_cmdline(fs::File::open(self.path.join("cmdline"))?)
+    }
+
+    pub fn environ(&self) -> Result<HashMap<OsString, OsString>, ParseProcError> {
+        parse_environ(fs::File::open(self.path.join("environ"))?)
+    }
+
+    /// The command name, truncated by the kernel to 15 bytes.
+    pub fn comm(&self) -> Result<String, ParseProcError> {
+        Ok(fs::read_to_string(self.path.join("comm"))?
+            .trim_end_matches('\n')
+            .to_owned())
+    }
+
+    pub fn cwd(&self) -> Result<PathBuf, ParseProcError> {
+        Ok(fs::read_link(self.path.join("cwd"))?)
+    }
+
+    /// The executable, with a ` (deleted)` suffix when it was removed or replaced.
+    pub fn exe(&self) -> Result<PathBuf, ParseProcError> {
+        Ok(fs::read_link(self.path.join("exe"))?)
+    }
+
+    /// The open file descriptors, sorted by number.
+    pub fn fds(&self) -> Result<Vec<Fd>, ParseProcError> {
+        fd::read_fds(&self.path)
+    }
+
+    pub fn io(&self) -> Result<Io, ParseProcError> {
+        parse_io(fs::File::open(self.path.join("io"))?)
+    }
+
+    pub fn smaps_rollup(&self) -> Result<SmapsRollup, ParseProcError> {
+        parse_smaps_rollup(fs::File::open(self.path.join("smaps_rollup"))?)
+    }
+
+    pub fn limits(&self) -> Result<impl Iterator<Item = Limit>, ParseProcError> {
+        parse_limits(fs::File::open(self.path.join("limits"))?)
+    }
+}
+
+/// Iterator over the processes of a proc filesystem, see [`processes`].
+#[derive(Debug)]
+pub struct Processes {
+    root: PathBuf,
+    entries: fs::ReadDir,
+}
+
+impl Iterator for Processes {
+    type Item = Process;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        for entry in self.entries.by_ref() {
+            let Ok(entry) = entry else {
+                continue;
+            };
+
+            if let Some(pid) = entry
+                .file_name()
+                .to_str()
+                .and_then(|pid| pid.parse::<u32>().ok())
+            {
+                return Some(Process::at(&self.root, pid));
+            }
+        }
+
+        None
+    }
+}
+
+///
+/// Iterates the numeric entries of the proc filesystem mounted at `root`.
+///
+/// ```no_run
+/// let processes = unixism::proc::process::processes_at("/proc").unwrap();
+/// ```
+pub fn processes_at<P>(root: P) -> Result<Processes, ParseProcError>
+where
+    P: AsRef<Path>,
+{
+    Ok(Processes {
+        root: root.as_ref().to_path_buf(),
+        entries: fs::read_dir(root)?,
+    })
+}
+
+///
+/// Same as processes_at, but iterates the `/proc` as default.
+/// Processes which exit while iterating are still yielded, their accessors fail.
+///
+/// ```no_run
+/// for process in unixism::proc::processes().unwrap() {
+///     if let (Ok(stat), Ok(cmdline)) = (process.stat(), process.cmdline()) {
+///         println!("{:>7} {:?} {:?}", process.pid, stat.state, cmdline);
+///     }
+/// }
+/// ```
+pub fn processes() -> Result<Processes, ParseProcError> {
+    processes_at("/proc")
+}
+
+/// Parses a `1234 kB` value into bytes.
+fn bytes(value: &str) -> Result<u64, ParseProcError> {
+    match value.split_whitespace().collect::<Vec<_>>()[..] {
+        [value, "kB"] => Ok(value.parse::<u64>()?.saturating_mul(1024)),
+        [value] => Ok(value.parse()?),
+        _ => Err(ParseProcError::InvalidEntry(value.to_owned())),
+    }
+}
+
+/// Returns the `USER_HZ` the kernel reports times with, 100 on about every system.
+pub fn clock_ticks() -> u64 {
+    match unsafe { libc::sysconf(libc::_SC_CLK_TCK) } {
+        ticks if ticks > 0 => ticks as u64,
+        _ => 100,
+    }
+}
+
+/// Returns the size of a memory page in bytes.
+pub fn page_size() -> u64 {
+    match unsafe { libc::sysconf(libc::_SC_PAGESIZE) } {
+        size if size > 0 => size as u64,
+        _ => 4096,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::os::unix::fs::symlink;
+
+    use super::*;
+    use crate::tempdir::TempDir;
+
+    #[test]
+    fn it_processes_at() {
+        let root = TempDir::new("processes");
+
+        for pid in ["1", "42"] {
+            let dir = root.join(pid);
+            fs::create_dir_all(dir.join("fd")).unwrap();
+            fs::write(dir.join("comm"), format!("proc{}\n", pid)).unwrap();
+            fs::write(dir.join("cmdline"), format!("/bin/proc{}\0-v\0", pid)).unwrap();
+            fs::write(
+                dir.join("stat"),
+                format!(
+                    "{} (proc{}) R 0 1 1 0 -1 0 0 0 0 0 100 0 0 0 20 0 1 0 5 1000 10",
+                    pid, pid
+                ),
+            )
+            .unwrap();
+            symlink("/srv", dir.join("cwd")).unwrap();
+            symlink("/dev/null", dir.join("fd").join("10")).unwrap();
+            symlink("socket:[777]", dir.join("fd").join("2")).unwrap();
+        }
+        fs::create_dir_all(root.join("acpi")).unwrap();
+        symlink("42", root.join("self")).unwrap();
+
+        let mut processes = processes_at(&root).unwrap().collect::<Vec<_>>();
+        processes.sort_by_key(|process| process.pid);
+
+        assert_eq!(
+            vec![1, 42],
+            processes
+                .iter()
+                .map(|process| process.pid)
+                .collect::<Vec<_>>()
+        );
+
+        let process = &processes[1];
+        assert_eq!("proc42", process.comm().unwrap());
+        assert_eq!(
+            vec![OsString::from("/bin/proc42"), OsString::from("-v")],
+            process.cmdline().unwrap()
+        );
+        assert_eq!(State::Running, process.stat().unwrap().state);
+        assert_eq!(PathBuf::from("/srv"), process.cwd().unwrap());
+        assert_eq!(
+            vec![
+                Fd {
+                    fd: 2,
+                    target: FdTarget::Socket(777),
+                },
+                Fd {
+                    fd: 10,
+                    target: FdTarget::File("/dev/null".into()),
+                },
+            ],
+            process.fds().unwrap()
+        );
+        assert!(process.exe().is_err());
+        assert!(process.status().is_err());
+    }
+}
@@ -0,0 +1,142 @@
+use std::{
+    collections::HashMap,
+    fs,
+    io::{self, BufRead, BufReader},
+};
+
+use super::{super::ParseProcError, bytes};
+
+///
+/// The memory summary of `/proc/<pid>/smaps_rollup` (Linux 4.14 and later), in bytes.
+/// The `Pss_*` split appeared in Linux 5.8 and is `None` before.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct SmapsRollup {
+    pub rss: u64,
+    /// Proportional set size, shared pages divided between the processes mapping them.
+    pub pss: u64,
+    pub pss_anon: Option<u64>,
+    pub pss_file: Option<u64>,
+    pub pss_shmem: Option<u64>,
+    pub shared_clean: u64,
+    pub shared_dirty: u64,
+    pub private_clean: u64,
+    pub private_dirty: u64,
+    pub swap: u64,
+    /// Proportional swap usage, like `pss` for swapped out shared pages.
+    pub swap_pss: u64,
+    /// Remaining fields, in bytes when the kernel reports them in `kB`.
+    pub others: HashMap<String, u64>,
+}
+
+impl SmapsRollup {
+    /// Memory used only by this process, what would be freed if it exited.
+    pub fn uss(&self) -> u64 {
+        self.private_clean + self.private_dirty
+    }
+}
+
+///
+/// ```no_run
+/// let rollup = unixism::proc::process::parse_smaps_rollup(std::fs::File::open("/proc/self/smaps_rollup").unwrap()).unwrap();
+///
+/// println!("rss {} pss {} swap {}", rollup.rss, rollup.pss, rollup.swap);
+/// ```
+pub fn parse_smaps_rollup<R>(reader: R) -> Result<SmapsRollup, ParseProcError>
+where
+    R: io::Read,
+{
+    let mut rollup = SmapsRollup::default();
+
+    for line in BufReader::new(reader).lines() {
+        let line = line?;
+
+        let Some((key, value)) = line.split_once(':') else {
+            if line.trim().is_empty() {
+                continue;
+            }
+
+            return Err(ParseProcError::InvalidEntry(line));
+        };
+
+        // The `[rollup]` header line spanning all the mappings.
+        if key.contains(char::is_whitespace) {
+            continue;
+        }
+
+        let value = bytes(value)?;
+
+        match key {
+            "Rss" => rollup.rss = value,
+            "Pss" => rollup.pss = value,
+            "Pss_Anon" => rollup.pss_anon = Some(value),
+            "Pss_File" => rollup.pss_file = Some(value),
+            "Pss_Shmem" => rollup.pss_shmem = Some(value),
+            "Shared_Clean" => rollup.shared_clean = value,
+            "Shared_Dirty" => rollup.shared_dirty = value,
+            "Private_Clean" => rollup.private_clean = value,
+            "Private_Dirty" => rollup.private_dirty = value,
+            "Swap" => rollup.swap = value,
+            "SwapPss" => rollup.swap_pss = value,
+            key => {
+                rollup.others.insert(key.to_owned(), value);
+            }
+        }
+    }
+
+    Ok(rollup)
+}
+
+///
+/// Same as parse_smaps_rollup, but parses the `/proc/<pid>/smaps_rollup` of the process.
+///
+/// ```no_run
+/// let rollup = unixism::proc::process::smaps_rollup(1).unwrap();
+/// ```
+pub fn smaps_rollup(pid: u32) -> Result<SmapsRollup, ParseProcError> {
+    parse_smaps_rollup(fs::File::open(format!("/proc/{}/smaps_rollup", pid))?)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Cursor;
+
+    use super::*;
+
+    #[test]
+    fn it_parse_smaps_rollup() {
+        let rollup = parse_smaps_rollup(Cursor::new(
+            r#"
+55d1c8a4e000-7ffd2b1f8000 ---p 00000000 00:00 0                          [rollup]
+Rss:                3888 kB
+Pss:                 730 kB
+Pss_Anon:            200 kB
+Pss_File:            530 kB
+Pss_Shmem:             0 kB
+Shared_Clean:       3100 kB
+Shared_Dirty:          0 kB
+Private_Clean:       100 kB
+Private_Dirty:       688 kB
+Referenced:         3888 kB
+Anonymous:           688 kB
+Swap:                 16 kB
+SwapPss:               8 kB
+Locked:                0 kB
+        "#,
+        ))
+        .unwrap();
+
+        assert_eq!(3888 * 1024, rollup.rss);
+        assert_eq!(730 * 1024, rollup.pss);
+        assert_eq!(Some(200 * 1024), rollup.pss_anon);
+        assert_eq!(16 * 1024, rollup.swap);
+        assert_eq!(8 * 1024, rollup.swap_pss);
+        assert_eq!(788 * 1024, rollup.uss());
+        assert_eq!(Some(&(688 * 1024)), rollup.others.get("Anonymous"));
+
+        let old = parse_smaps_rollup(Cursor::new("Rss: 4 kB\nPss: 2 kB\n")).unwrap();
+        assert_eq!(None, old.pss_anon);
+
+        assert!(parse_smaps_rollup(Cursor::new("Rss: x kB")).is_err());
+        assert!(parse_smaps_rollup(Cursor::new("garbage")).is_err());
+    }
+}
@@ -0,0 +1,188 @@
+use std::{fs, io, str::FromStr, time::Duration};
+
+use super::{super::ParseProcError, clock_ticks, page_size, State};
+
+///
+/// The content of `/proc/<pid>/stat`, times converted from clock ticks.
+/// Only the commonly used fields are kept, kernels append new ones at the end.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Stat {
+    pub pid: u32,
+    /// The command name, which may contain spaces and parentheses. The kernel prints it as raw bytes,
+    /// so invalid UTF-8 is replaced when read by parse_stat.
+    pub comm: String,
+    pub state: State,
+    pub ppid: u32,
+    pub pgrp: u32,
+    pub session: u32,
+    pub tty_nr: u32,
+    pub minflt: u64,
+    pub majflt: u64,
+    pub utime: Duration,
+    pub stime: Duration,
+    /// User time of the waited-for children.
+    pub cutime: Duration,
+    /// System time of the waited-for children.
+    pub cstime: Duration,
+    pub priority: i32,
+    pub nice: i32,
+    pub num_threads: u32,
+    /// Time the process started after the system boot.
+    pub starttime: Duration,
+    /// Virtual memory size in bytes.
+    pub vsize: u64,
+    /// Resident set size in pages.
+    pub rss: u64,
+}
+
+impl Stat {
+    pub fn rss_bytes(&self) -> u64 {
+        self.rss.saturating_mul(page_size())
+    }
+
+    /// Parses the stat line, converting times with the `ticks` clock ticks per second.
+    pub fn parse_with_ticks(s: &str, ticks: u64) -> Result<Self, ParseProcError> {
+        if ticks == 0 {
+            return Err(ParseProcError::InvalidEntry(format!(
+                "{} clock ticks per second",
+                ticks
+            )));
+        }
+
+        let (pid, rest) = s
+            .split_once(" (")
+            .ok_or_else(|| ParseProcError::InvalidEntry(s.to_owned()))?;
+        let (comm, rest) = rest
+            .rsplit_once(')')
+            .ok_or_else(|| ParseProcError::InvalidEntry(s.to_owned()))?;
+
+        let fields = rest.split_whitespace().collect::<Vec<_>>();
+        if fields.len() < 22 {
+            return Err(ParseProcError::InvalidEntry(s.to_owned()));
+        }
+
+        let duration = |i: usize| -> Result<Duration, ParseProcError> {
+            let value = fields[i].parse::<i64>()?.max(0) as u64;
+
+            Ok(Duration::from_secs(value / ticks)
+                + Duration::from_nanos(value % ticks * 1_000_000_000 / ticks))
+        };
+
+        Ok(Stat {
+            pid: pid.trim().parse()?,
+            comm: comm.to_owned(),
+            state: fields[0]
+                .chars()
+                .next()
+                .map(State::from)
+                .ok_or_else(|| ParseProcError::InvalidEntry(s.to_owned()))?,
+            ppid: fields[1].parse()?,
+            pgrp: fields[2].parse()?,
+            session: fields[3].parse()?,
+            tty_nr: fields[4].parse()?,
+            minflt: fields[7].parse()?,
+            majflt: fields[9].parse()?,
+            utime: duration(11)?,
+            stime: duration(12)?,
+            cutime: duration(13)?,
+            cstime: duration(14)?,
+            priority: fields[15].parse()?,
+            nice: fields[16].parse()?,
+            num_threads: fields[17].parse()?,
+            starttime: duration(19)?,
+            vsize: fields[20].parse()?,
+            rss: fields[21].parse::<i64>()?.max(0) as u64,
+        })
+    }
+}
+
+impl FromStr for Stat {
+    type Err = ParseProcError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Stat::parse_with_ticks(s, clock_ticks())
+    }
+}
+
+///
+/// ```no_run
+/// let stat = unixism::proc::process::parse_stat(std::fs::File::open("/proc/self/stat").unwrap()).unwrap();
+///
+/// println!("{} ({:?}): {:?} user, {:?} system", stat.comm, stat.state, stat.utime, stat.stime);
+/// ```
+pub fn parse_stat<R>(mut reader: R) -> Result<Stat, ParseProcError>
+where
+    R: io::Read,
+{
+    let mut content = Vec::new();
+    reader.read_to_end(&mut content)?;
+
+    // Only the comm may be invalid UTF-8, the replacement characters can't be mistaken for its `)`.
+    String::from_utf8_lossy(&content).trim().parse()
+}
+
+///
+/// Same as parse_stat, but parses the `/proc/<pid>/stat` of the process.
+///
+/// ```no_run
+/// let stat = unixism::proc::process::stat(1).unwrap();
+/// ```
+pub fn stat(pid: u32) -> Result<Stat, ParseProcError> {
+    parse_stat(fs::File::open(format!("/proc/{}/stat", pid))?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn it_parse_stat() {
+        let stat = Stat::parse_with_ticks(
+            "1234 (my (odd) proc) S 1 1234 1234 34816 1234 4194560 1500 0 3 0 250 75 10 5 20 0 4 0 123456 262144000 3000 18446744073709551615 1 1 0 0 0 0 0 4096 0 0 0 0 17 3 0 0 0 0 0 0 0 0 0 0 0 0 0",
+            100,
+        )
+        .unwrap();
+
+        assert_eq!(1234, stat.pid);
+        assert_eq!("my (odd) proc", stat.comm);
+        assert_eq!(State::Sleeping, stat.state);
+        assert_eq!(1, stat.ppid);
+        assert_eq!(1234, stat.pgrp);
+        assert_eq!(34816, stat.tty_nr);
+        assert_eq!(1500, stat.minflt);
+        assert_eq!(3, stat.majflt);
+        assert_eq!(Duration::from_millis(2500), stat.utime);
+        assert_eq!(Duration::from_millis(750), stat.stime);
+        assert_eq!(Duration::from_millis(50), stat.cstime);
+        assert_eq!(20, stat.priority);
+        assert_eq!(0, stat.nice);
+        assert_eq!(4, stat.num_threads);
+        assert_eq!(Duration::from_millis(1234560), stat.starttime);
+        assert_eq!(262144000, stat.vsize);
+        assert_eq!(3000, stat.rss);
+
+        let stat = Stat::parse_with_ticks(
+            "2 (kthreadd) S 0 0 0 0 -1 2129984 0 0 0 0 0 3 0 0 20 -5 1 0 10 0 0",
+            250,
+        )
+        .unwrap();
+        assert_eq!(-5, stat.nice);
+        assert_eq!(Duration::from_millis(12), stat.stime);
+
+        let stat =
+            parse_stat(&b"77 (a\xff) b)) R 1 77 77 0 -1 0 0 0 0 0 1 1 0 0 20 0 1 0 10 0 0\n"[..])
+                .unwrap();
+        assert_eq!("a\u{fffd}) b)", stat.comm);
+        assert_eq!(State::Running, stat.state);
+
+        assert!(matches!(
+            Stat::parse_with_ticks(
+                "2 (kthreadd) S 0 0 0 0 -1 2129984 0 0 0 0 0 3 0 0 20 -5 1 0 10 0 0",
+                0
+            ),
+            Err(ParseProcError::InvalidEntry(_))
+        ));
+        assert!("1234 (bash) S 1 2 3".parse::<Stat>().is_err());
+        assert!("1234 bash S".parse::<Stat>().is_err());
+    }
+}
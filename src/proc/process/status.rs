@@ -0,0 +1,258 @@
+use std::{
+    collections::HashMap,
+    fs,
+    io::{self, BufRead, BufReader},
+    str::FromStr,
+};
+
+use super::{super::ParseProcError, bytes, State};
+
+/// The real, effective, saved and filesystem ids of the `Uid` and `Gid` lines.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct Ids {
+    pub real: u32,
+    pub effective: u32,
+    pub saved: u32,
+    pub filesystem: u32,
+}
+
+impl FromStr for Ids {
+    type Err = ParseProcError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let fields = s.split_whitespace().collect::<Vec<_>>();
+
+        let [real, effective, saved, filesystem] = fields[..] else {
+            return Err(ParseProcError::InvalidEntry(s.to_owned()));
+        };
+
+        Ok(Ids {
+            real: real.parse()?,
+            effective: effective.parse()?,
+            saved: saved.parse()?,
+            filesystem: filesystem.parse()?,
+        })
+    }
+}
+
+///
+/// The content of `/proc/<pid>/status`, memory sizes converted from `kB` to bytes.
+/// Fields added by later kernels, and the memory of kernel threads, are `None` when missing.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Status {
+    /// The comm, which the kernel prints as raw bytes, so invalid UTF-8 is replaced.
+    pub name: String,
+    pub umask: Option<u32>,
+    pub state: State,
+    pub tgid: u32,
+    pub pid: u32,
+    pub ppid: u32,
+    pub tracer_pid: u32,
+    pub uid: Ids,
+    pub gid: Ids,
+    pub groups: Vec<u32>,
+    pub vm_size: Option<u64>,
+    pub vm_rss: Option<u64>,
+    pub threads: u32,
+    pub cap_inh: u64,
+    pub cap_prm: u64,
+    pub cap_eff: u64,
+    pub cap_bnd: u64,
+    pub cap_amb: Option<u64>,
+    /// Remaining fields with their raw values.
+    pub others: HashMap<String, String>,
+}
+
+impl Default for Status {
+    fn default() -> Self {
+        Status {
+            name: String::new(),
+            umask: None,
+            state: State::Other('?'),
+            tgid: 0,
+            pid: 0,
+            ppid: 0,
+            tracer_pid: 0,
+            uid: Ids::default(),
+            gid: Ids::default(),
+            groups: Vec::new(),
+            vm_size: None,
+            vm_rss: None,
+            threads: 0,
+            cap_inh: 0,
+            cap_prm: 0,
+            cap_eff: 0,
+            cap_bnd: 0,
+            cap_amb: None,
+            others: HashMap::new(),
+        }
+    }
+}
+
+impl Status {
+    /// Whether the capability number, like 21 for `CAP_SYS_ADMIN`, is in the effective set.
+    pub fn has_capability(&self, capability: u32) -> bool {
+        capability < 64 && self.cap_eff & (1 << capability) != 0
+    }
+}
+
+///
+/// ```no_run
+/// let status = unixism::proc::process::parse_status(std::fs::File::open("/proc/self/status").unwrap()).unwrap();
+///
+/// println!("{} ({:?}): uid {}, {:?} bytes resident", status.name, status.state, status.uid.effective, status.vm_rss);
+/// ```
+pub fn parse_status<R>(reader: R) -> Result<Status, ParseProcError>
+where
+    R: io::Read,
+{
+    let mut status = Status::default();
+    let mut reader = BufReader::new(reader);
+    let mut buf = Vec::new();
+
+    loop {
+        buf.clear();
+        if reader.read_until(b'\n', &mut buf)? == 0 {
+            break;
+        }
+
+        let line = String::from_utf8_lossy(&buf);
+        let line = line.trim_end_matches('\n');
+
+        if line.trim().is_empty() {
+            continue;
+        }
+
+        let (key, value) = line
+            .split_once(':')
+            .ok_or_else(|| ParseProcError::InvalidEntry(line.to_owned()))?;
+        let value = value.trim();
+
+        match key {
+            "Name" => status.name = value.to_owned(),
+            "Umask" => status.umask = Some(u32::from_str_radix(value, 8)?),
+            "State" => {
+                status.state = value
+                    .chars()
+                    .next()
+                    .map(State::from)
+                    .ok_or_else(|| ParseProcError::InvalidEntry(line.to_owned()))?
+            }
+            "Tgid" => status.tgid = value.parse()?,
+            "Pid" => status.pid = value.parse()?,
+            "PPid" => status.ppid = value.parse()?,
+            "TracerPid" => status.tracer_pid = value.parse()?,
+            "Uid" => status.uid = value.parse()?,
+            "Gid" => status.gid = value.parse()?,
+            "Groups" => {
+                status.groups = value
+                    .split_whitespace()
+                    .map(str::parse)
+                    .collect::<Result<_, _>>()?
+            }
+            "VmSize" => status.vm_size = Some(bytes(value)?),
+            "VmRSS" => status.vm_rss = Some(bytes(value)?),
+            "Threads" => status.threads = value.parse()?,
+            "CapInh" => status.cap_inh = u64::from_str_radix(value, 16)?,
+            "CapPrm" => status.cap_prm = u64::from_str_radix(value, 16)?,
+            "CapEff" => status.cap_eff = u64::from_str_radix(value, 16)?,
+            "CapBnd" => status.cap_bnd = u64::from_str_radix(value, 16)?,
+            "CapAmb" => status.cap_amb = Some(u64::from_str_radix(value, 16)?),
+            key => {
+                status.others.insert(key.to_owned(), value.to_owned());
+            }
+        }
+    }
+
+    Ok(status)
+}
+
+///
+/// Same as parse_status, but parses the `/proc/<pid>/status` of the process.
+///
+/// ```no_run
+/// let status = unixism::proc::process::status(1).unwrap();
+/// ```
+pub fn status(pid: u32) -> Result<Status, ParseProcError> {
+    parse_status(fs::File::open(format!("/proc/{}/status", pid))?)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Cursor;
+
+    use super::*;
+
+    #[test]
+    fn it_parse_status() {
+        let status = parse_status(Cursor::new(
+            r#"
+Name:	my daemon
+Umask:	0022
+State:	S (sleeping)
+Tgid:	1234
+Ngid:	0
+Pid:	1234
+PPid:	1
+TracerPid:	0
+Uid:	1000	1000	1000	1000
+Gid:	100	100	0	100
+FDSize:	64
+Groups:	4 24 27
+VmPeak:	  300000 kB
+VmSize:	  250000 kB
+VmRSS:	   12000 kB
+Threads:	8
+CapInh:	0000000000000000
+CapPrm:	0000000000000000
+CapEff:	0000000000200000
+CapBnd:	000001ffffffffff
+CapAmb:	0000000000000000
+Seccomp:	2
+        "#,
+        ))
+        .unwrap();
+
+        assert_eq!("my daemon", status.name);
+        assert_eq!(Some(0o022), status.umask);
+        assert_eq!(State::Sleeping, status.state);
+        assert_eq!(1, status.ppid);
+        assert_eq!(
+            Ids {
+                real: 100,
+                effective: 100,
+                saved: 0,
+                filesystem: 100,
+            },
+            status.gid
+        );
+        assert_eq!(vec![4, 24, 27], status.groups);
+        assert_eq!(Some(256000000), status.vm_size);
+        assert_eq!(Some(12288000), status.vm_rss);
+        assert_eq!(8, status.threads);
+        assert!(status.has_capability(21));
+        assert!(!status.has_capability(0));
+        assert_eq!(0x1ffffffffff, status.cap_bnd);
+        assert_eq!(Some("2"), status.others.get("Seccomp").map(String::as_str));
+
+        let kthread = parse_status(Cursor::new(
+            "Name:\tkworker/0:1\nState:\tI (idle)\nPid:\t42\nGroups:\t\nThreads:\t1\n",
+        ))
+        .unwrap();
+
+        assert_eq!(State::Idle, kthread.state);
+        assert!(kthread.groups.is_empty());
+        assert_eq!(None, kthread.vm_rss);
+        assert_eq!(None, kthread.cap_amb);
+
+        let binary = parse_status(Cursor::new(
+            b"Name:\tapp\xff\xfe\nState:\tS (sleeping)\nPid:\t7\n".to_vec(),
+        ))
+        .unwrap();
+        assert_eq!("app\u{fffd}\u{fffd}", binary.name);
+        assert_eq!(7, binary.pid);
+
+        assert!(parse_status(Cursor::new("Uid:\t1000 1000")).is_err());
+        assert!(parse_status(Cursor::new("garbage")).is_err());
+    }
+}
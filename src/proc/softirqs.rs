@@ -0,0 +1,128 @@
+use std::{
+    fs,
+    io::{self, BufRead, BufReader},
+};
+
+use super::{
+    interrupts::{parse_counts, parse_cpus},
+    ParseProcError,
+};
+
+/// A row of `/proc/softirqs`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SoftIrq {
+    /// The softirq, like `TIMER`, `NET_RX` or `BLOCK`.
+    pub name: String,
+    /// The counts per CPU, in the order of [`SoftIrqs::cpus`].
+    pub counts: Vec<u64>,
+}
+
+impl SoftIrq {
+    pub fn total(&self) -> u64 {
+        self.counts.iter().sum()
+    }
+}
+
+/// The content of `/proc/softirqs`.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct SoftIrqs {
+    /// The online CPUs, the columns of the counters.
+    pub cpus: Vec<u32>,
+    pub softirqs: Vec<SoftIrq>,
+}
+
+impl SoftIrqs {
+    pub fn get(&self, name: &str) -> Option<&SoftIrq> {
+        self.softirqs.iter().find(|softirq| softirq.name == name)
+    }
+
+    /// Returns the count of the softirq on the CPU, `None` when the CPU is offline.
+    pub fn count(&self, name: &str, cpu: u32) -> Option<u64> {
+        let column = self.cpus.iter().position(|id| *id == cpu)?;
+
+        self.get(name)?.counts.get(column).copied()
+    }
+}
+
+///
+/// ```no_run
+/// let softirqs = unixism::proc::softirqs::parse(std::fs::File::open("/proc/softirqs").unwrap()).unwrap();
+///
+/// println!("{:?}", softirqs.get("NET_RX").map(|softirq| softirq.total()));
+/// ```
+pub fn parse<R>(reader: R) -> Result<SoftIrqs, ParseProcError>
+where
+    R: io::Read,
+{
+    let mut softirqs = SoftIrqs::default();
+    let mut lines = BufReader::new(reader).lines();
+
+    for line in lines.by_ref() {
+        let line = line?;
+
+        if !line.trim().is_empty() {
+            softirqs.cpus = parse_cpus(&line)?;
+            break;
+        }
+    }
+
+    for line in lines {
+        let line = line?;
+
+        if line.trim().is_empty() {
+            continue;
+        }
+
+        let (name, counts, rest) = parse_counts(&line, softirqs.cpus.len())?;
+
+        if !rest.is_empty() {
+            return Err(ParseProcError::InvalidEntry(line));
+        }
+
+        softirqs.softirqs.push(SoftIrq {
+            name: name.to_owned(),
+            counts,
+        });
+    }
+
+    Ok(softirqs)
+}
+
+///
+/// Same as parse, but parses the `/proc/softirqs` as default.
+///
+/// ```no_run
+/// let softirqs = unixism::proc::softirqs::parse_default().unwrap();
+/// ```
+pub fn parse_default() -> Result<SoftIrqs, ParseProcError> {
+    parse(fs::File::open("/proc/softirqs")?)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Cursor;
+
+    use super::*;
+
+    #[test]
+    fn it_parse() {
+        let softirqs = parse(Cursor::new(
+            r#"
+                    CPU0       CPU1
+          HI:          1          0
+       TIMER:     123456     654321
+      NET_RX:        500        700
+         RCU:      99999      88888
+        "#,
+        ))
+        .unwrap();
+
+        assert_eq!(vec![0, 1], softirqs.cpus);
+        assert_eq!(4, softirqs.softirqs.len());
+        assert_eq!(1200, softirqs.get("NET_RX").unwrap().total());
+        assert_eq!(Some(654321), softirqs.count("TIMER", 1));
+        assert_eq!(None, softirqs.count("TIMER", 5));
+
+        assert!(parse(Cursor::new("CPU0\nHI: 1 x\n")).is_err());
+    }
+}
@@ -0,0 +1,287 @@
+use std::{
+    fs,
+    io::{self, BufRead, BufReader},
+    str::FromStr,
+};
+
+use super::ParseProcError;
+
+///
+/// Time a processor spent in each state, in `USER_HZ` ticks (usually hundredths of a second).
+/// The guest times are already included in `user` and `nice`.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct CpuTimes {
+    pub user: u64,
+    pub nice: u64,
+    pub system: u64,
+    pub idle: u64,
+    pub iowait: u64,
+    pub irq: u64,
+    pub softirq: u64,
+    pub steal: u64,
+    pub guest: u64,
+    pub guest_nice: u64,
+}
+
+impl CpuTimes {
+    /// Ticks elapsed in all the states, without counting the guest time twice.
+    pub fn total(&self) -> u64 {
+        self.user
+            + self.nice
+            + self.system
+            + self.idle
+            + self.iowait
+            + self.irq
+            + self.softirq
+            + self.steal
+    }
+
+    ///
+    /// Computes the share of each state between the `previous` sample and this one.
+    /// A counter that went back, like after a CPU was unplugged, counts as no time spent.
+    ///
+    /// ```no_run
+    /// use std::{thread, time::Duration};
+    ///
+    /// use unixism::proc::stat;
+    ///
+    /// let previous = stat::parse_default().unwrap();
+    /// thread::sleep(Duration::from_secs(1));
+    /// let current = stat::parse_default().unwrap();
+    ///
+    /// println!("{:.1}% busy", current.cpu.delta(&previous.cpu).busy);
+    /// ```
+    pub fn delta(&self, previous: &CpuTimes) -> Utilization {
+        let total = self.total().saturating_sub(previous.total());
+        if total == 0 {
+            return Utilization::default();
+        }
+
+        let percent = |current: u64, previous: u64| {
+            current.saturating_sub(previous) as f64 * 100.0 / total as f64
+        };
+
+        let idle = percent(self.idle, previous.idle);
+        let iowait = percent(self.iowait, previous.iowait);
+
+        Utilization {
+            user: percent(self.user, previous.user),
+            nice: percent(self.nice, previous.nice),
+            system: percent(self.system, previous.system),
+            idle,
+            iowait,
+            irq: percent(self.irq, previous.irq),
+            softirq: percent(self.softirq, previous.softirq),
+            steal: percent(self.steal, previous.steal),
+            guest: percent(self.guest, previous.guest),
+            guest_nice: percent(self.guest_nice, previous.guest_nice),
+            busy: 100.0 - idle - iowait,
+        }
+    }
+}
+
+impl FromStr for CpuTimes {
+    type Err = ParseProcError;
+
+    /// Parses the counters following the `cpu` label, older kernels report fewer of them.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let counters = s
+            .split_whitespace()
+            .map(|field| field.parse::<u64>())
+            .collect::<Result<Vec<_>, _>>()?;
+
+        if !(4..=10).contains(&counters.len()) {
+            return Err(ParseProcError::InvalidEntry(s.to_owned()));
+        }
+
+        let counter = |i: usize| counters.get(i).copied().unwrap_or_default();
+
+        Ok(CpuTimes {
+            user: counter(0),
+            nice: counter(1),
+            system: counter(2),
+            idle: counter(3),
+            iowait: counter(4),
+            irq: counter(5),
+            softirq: counter(6),
+            steal: counter(7),
+            guest: counter(8),
+            guest_nice: counter(9),
+        })
+    }
+}
+
+/// Percentages of the time spent in each state between two samples.
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+pub struct Utilization {
+    pub user: f64,
+    pub nice: f64,
+    pub system: f64,
+    pub idle: f64,
+    pub iowait: f64,
+    pub irq: f64,
+    pub softirq: f64,
+    pub steal: f64,
+    pub guest: f64,
+    pub guest_nice: f64,
+    /// Everything but `idle` and `iowait`.
+    pub busy: f64,
+}
+
+/// A `cpuN` line of `/proc/stat`.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct Cpu {
+    pub id: u32,
+    pub times: CpuTimes,
+}
+
+/// The processor and process counters of `/proc/stat`.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct Stat {
+    /// The sum of all the processors.
+    pub cpu: CpuTimes,
+    /// The online processors, offline ones are missing.
+    pub cpus: Vec<Cpu>,
+    pub context_switches: u64,
+    /// Boot time in seconds since the epoch.
+    pub boot_time: u64,
+    /// Processes created since boot.
+    pub processes: u64,
+    pub procs_running: u64,
+    pub procs_blocked: u64,
+}
+
+impl Stat {
+    pub fn cpu(&self, id: u32) -> Option<&CpuTimes> {
+        self.cpus
+            .iter()
+            .find(|cpu| cpu.id == id)
+            .map(|cpu| &cpu.times)
+    }
+}
+
+///
+/// ```no_run
+/// let stat = unixism::proc::stat::parse(std::fs::File::open("/proc/stat").unwrap()).unwrap();
+///
+/// for cpu in stat.cpus {
+///     println!("cpu{}: {} idle ticks", cpu.id, cpu.times.idle);
+/// }
+/// ```
+pub fn parse<R>(reader: R) -> Result<Stat, ParseProcError>
+where
+    R: io::Read,
+{
+    let mut stat = Stat::default();
+
+    for line in BufReader::new(reader).lines() {
+        let line = line?;
+        let Some((key, value)) = line.trim().split_once(char::is_whitespace) else {
+            continue;
+        };
+
+        match key {
+            "cpu" => stat.cpu = value.parse()?,
+            "ctxt" => stat.context_switches = value.trim().parse()?,
+            "btime" => stat.boot_time = value.trim().parse()?,
+            "processes" => stat.processes = value.trim().parse()?,
+            "procs_running" => stat.procs_running = value.trim().parse()?,
+            "procs_blocked" => stat.procs_blocked = value.trim().parse()?,
+            key => {
+                if let Some(id) = key.strip_prefix("cpu") {
+                    stat.cpus.push(Cpu {
+                        id: id.parse()?,
+                        times: value.parse()?,
+                    });
+                }
+            }
+        }
+    }
+
+    Ok(stat)
+}
+
+///
+/// Same as parse, but parses the `/proc/stat` as default.
+///
+/// ```no_run
+/// let stat = unixism::proc::stat::parse_default().unwrap();
+/// ```
+pub fn parse_default() -> Result<Stat, ParseProcError> {
+    parse(fs::File::open("/proc/stat")?)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Cursor;
+
+    use super::*;
+
+    #[test]
+    fn it_parse() {
+        let stat = parse(Cursor::new(
+            r#"
+cpu  10132153 290696 3084719 46828483 16683 0 25195 0 175628 0
+cpu0 1393280 32966 572056 13343292 6130 0 17875 0 23933 0
+cpu2 1335 0 422 9000
+intr 1462898 44 0 0 0 0 0 0 0 0
+ctxt 115315
+btime 1700000000
+processes 11206
+procs_running 2
+procs_blocked 1
+softirq 29 0 4 0 0 0 0 0 0 0 25
+        "#,
+        ))
+        .unwrap();
+
+        assert_eq!(10132153, stat.cpu.user);
+        assert_eq!(175628, stat.cpu.guest);
+        assert_eq!(2, stat.cpus.len());
+        assert_eq!(Some(17875), stat.cpu(0).map(|cpu| cpu.softirq));
+        assert_eq!(Some(0), stat.cpu(2).map(|cpu| cpu.iowait));
+        assert_eq!(None, stat.cpu(1));
+        assert_eq!(115315, stat.context_switches);
+        assert_eq!(1700000000, stat.boot_time);
+        assert_eq!(11206, stat.processes);
+        assert_eq!(2, stat.procs_running);
+        assert_eq!(1, stat.procs_blocked);
+
+        assert!(parse(Cursor::new("cpu 1 2 3")).is_err());
+        assert!(parse(Cursor::new("cpux 1 2 3 4")).is_err());
+    }
+
+    #[test]
+    fn it_delta() {
+        let previous = "100 0 100 700 100 0 0 0 50 0".parse::<CpuTimes>().unwrap();
+        let current = "400 0 200 1200 200 0 0 0 150 0"
+            .parse::<CpuTimes>()
+            .unwrap();
+
+        let utilization = current.delta(&previous);
+
+        assert_eq!(30.0, utilization.user);
+        assert_eq!(10.0, utilization.system);
+        assert_eq!(50.0, utilization.idle);
+        assert_eq!(10.0, utilization.iowait);
+        assert_eq!(10.0, utilization.guest);
+        assert_eq!(40.0, utilization.busy);
+
+        assert_eq!(Utilization::default(), current.delta(&current));
+        assert_eq!(Utilization::default(), previous.delta(&current));
+    }
+
+    #[test]
+    fn it_delta_decreasing_counter() {
+        let previous = "100 0 100 700 100 0 0 0 0 0".parse::<CpuTimes>().unwrap();
+        let current = "50 0 200 1000 150 0 0 0 0 0".parse::<CpuTimes>().unwrap();
+
+        let utilization = current.delta(&previous);
+
+        assert_eq!(0.0, utilization.user);
+        assert_eq!(25.0, utilization.system);
+        assert_eq!(75.0, utilization.idle);
+        assert_eq!(12.5, utilization.iowait);
+        assert_eq!(12.5, utilization.busy);
+    }
+}
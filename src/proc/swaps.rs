@@ -0,0 +1,132 @@
+use std::{
+    fs,
+    io::{self, BufRead, BufReader},
+    str::FromStr,
+};
+
+use super::ParseProcError;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SwapType {
+    Partition,
+    File,
+    Other(String),
+}
+
+/// A row of `/proc/swaps`, sizes are in kibibytes.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Swap {
+    pub filename: String,
+    pub kind: SwapType,
+    pub size: u64,
+    pub used: u64,
+    pub priority: i32,
+}
+
+impl FromStr for Swap {
+    type Err = ParseProcError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let fields = s.split_whitespace().collect::<Vec<_>>();
+
+        let [filename, kind, size, used, priority] = fields[..] else {
+            return Err(ParseProcError::InvalidEntry(s.to_owned()));
+        };
+
+        Ok(Swap {
+            filename: unescape(filename),
+            kind: match kind {
+                "partition" => SwapType::Partition,
+                "file" => SwapType::File,
+                kind => SwapType::Other(kind.to_owned()),
+            },
+            size: size.parse()?,
+            used: used.parse()?,
+            priority: priority.parse()?,
+        })
+    }
+}
+
+/// Decodes the `\040` style escapes the kernel uses for whitespace in paths.
+fn unescape(s: &str) -> String {
+    s.replace("\\040", " ")
+        .replace("\\011", "\t")
+        .replace("\\012", "\n")
+        .replace("\\134", "\\")
+}
+
+///
+/// ```no_run
+/// let swaps = unixism::proc::swaps::parse(std::fs::File::open("/proc/swaps").unwrap()).unwrap();
+///
+/// for swap in swaps {
+///     println!("{}: {} of {} KiB used", swap.filename, swap.used, swap.size);
+/// }
+/// ```
+pub fn parse<R>(reader: R) -> Result<impl Iterator<Item = Swap>, ParseProcError>
+where
+    R: io::Read,
+{
+    let mut swaps = Vec::new();
+
+    for line in BufReader::new(reader).lines() {
+        let line = line?;
+        let line = line.trim();
+
+        if line.is_empty() || line.starts_with("Filename") {
+            continue;
+        }
+
+        swaps.push(line.parse::<Swap>()?);
+    }
+
+    Ok(swaps.into_iter())
+}
+
+///
+/// Same as parse, but parses the `/proc/swaps` as default.
+///
+/// ```no_run
+/// let swaps = unixism::proc::swaps::parse_default().unwrap();
+/// ```
+pub fn parse_default() -> Result<impl Iterator<Item = Swap>, ParseProcError> {
+    parse(fs::File::open("/proc/swaps")?)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Cursor;
+
+    use super::*;
+
+    #[test]
+    fn it_parse() {
+        let swaps = parse(Cursor::new(
+            r#"
+Filename				Type		Size		Used		Priority
+/dev/sda3                               partition	8388604		1024		-2
+/var/my\040swap                         file		2097148		0		10
+/dev/zram0                              zram		4194300		0		100
+        "#,
+        ))
+        .unwrap()
+        .collect::<Vec<_>>();
+
+        assert_eq!(3, swaps.len());
+        assert_eq!(
+            Swap {
+                filename: "/dev/sda3".to_owned(),
+                kind: SwapType::Partition,
+                size: 8388604,
+                used: 1024,
+                priority: -2,
+            },
+            swaps[0]
+        );
+        assert_eq!("/var/my swap", swaps[1].filename);
+        assert_eq!(SwapType::File, swaps[1].kind);
+        assert_eq!(SwapType::Other("zram".to_owned()), swaps[2].kind);
+
+        assert!(parse(Cursor::new("/dev/sda3 partition 10 0")).is_err());
+    }
+}
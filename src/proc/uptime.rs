@@ -0,0 +1,79 @@
+use std::{fs, io, str::FromStr, time::Duration};
+
+use super::ParseProcError;
+
+/// The content of `/proc/uptime`.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct Uptime {
+    pub uptime: Duration,
+    /// Time spent idle, summed over all the processors so it may exceed the uptime.
+    pub idle: Duration,
+}
+
+impl FromStr for Uptime {
+    type Err = ParseProcError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let fields = s.split_whitespace().collect::<Vec<_>>();
+
+        let [uptime, idle] = fields[..] else {
+            return Err(ParseProcError::InvalidEntry(s.to_owned()));
+        };
+
+        let duration = |field: &str| -> Result<Duration, ParseProcError> {
+            Duration::try_from_secs_f64(field.parse()?)
+                .map_err(|_| ParseProcError::InvalidEntry(s.to_owned()))
+        };
+
+        Ok(Uptime {
+            uptime: duration(uptime)?,
+            idle: duration(idle)?,
+        })
+    }
+}
+
+///
+/// ```no_run
+/// let uptime = unixism::proc::uptime::parse(std::fs::File::open("/proc/uptime").unwrap()).unwrap();
+///
+/// println!("up {} hours", uptime.uptime.as_secs() / 3600);
+/// ```
+pub fn parse<R>(mut reader: R) -> Result<Uptime, ParseProcError>
+where
+    R: io::Read,
+{
+    let mut content = String::new();
+    reader.read_to_string(&mut content)?;
+
+    content.trim().parse()
+}
+
+///
+/// Same as parse, but parses the `/proc/uptime` as default.
+///
+/// ```no_run
+/// let uptime = unixism::proc::uptime::parse_default().unwrap();
+/// ```
+pub fn parse_default() -> Result<Uptime, ParseProcError> {
+    parse(fs::File::open("/proc/uptime")?)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Cursor;
+
+    use super::*;
+
+    #[test]
+    fn it_parse() {
+        let uptime = parse(Cursor::new("350735.47 1402941.50\n")).unwrap();
+
+        assert_eq!(350735, uptime.uptime.as_secs());
+        assert_eq!(470, uptime.uptime.subsec_millis());
+        assert_eq!(Duration::from_millis(1402941500), uptime.idle);
+
+        assert!(parse(Cursor::new("350735.47")).is_err());
+        assert!(parse(Cursor::new("-1 0")).is_err());
+        assert!(parse(Cursor::new("x 0")).is_err());
+    }
+}
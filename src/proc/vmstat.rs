@@ -0,0 +1,186 @@
+use std::{
+    collections::HashMap,
+    fs,
+    io::{self, BufRead, BufReader},
+};
+
+use super::ParseProcError;
+
+///
+/// The counters of `/proc/vmstat`, either page counts or event counts since boot.
+/// `oom_kill` is missing before Linux 4.13.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct VmStat {
+    pub pgpgin: u64,
+    pub pgpgout: u64,
+    pub pswpin: u64,
+    pub pswpout: u64,
+    pub pgfault: u64,
+    pub pgmajfault: u64,
+    pub oom_kill: Option<u64>,
+    /// Remaining counters by name.
+    pub others: HashMap<String, u64>,
+}
+
+impl VmStat {
+    /// Looks up any counter by its name in the file.
+    pub fn get(&self, key: &str) -> Option<u64> {
+        match key {
+            "pgpgin" => Some(self.pgpgin),
+            "pgpgout" => Some(self.pgpgout),
+            "pswpin" => Some(self.pswpin),
+            "pswpout" => Some(self.pswpout),
+            "pgfault" => Some(self.pgfault),
+            "pgmajfault" => Some(self.pgmajfault),
+            "oom_kill" => self.oom_kill,
+            key => self.others.get(key).copied(),
+        }
+    }
+
+    ///
+    /// Computes the counters increase between the `previous` sample and this one.
+    /// Counters missing from the previous sample are kept as they are.
+    ///
+    /// ```no_run
+    /// use std::{thread, time::Duration};
+    ///
+    /// use unixism::proc::vmstat;
+    ///
+    /// let previous = vmstat::parse_default().unwrap();
+    /// thread::sleep(Duration::from_secs(1));
+    /// let current = vmstat::parse_default().unwrap();
+    ///
+    /// let delta = current.delta(&previous);
+    /// println!("{} major faults, {} pages swapped out", delta.pgmajfault, delta.pswpout);
+    /// ```
+    pub fn delta(&self, previous: &VmStat) -> VmStat {
+        VmStat {
+            pgpgin: self.pgpgin.wrapping_sub(previous.pgpgin),
+            pgpgout: self.pgpgout.wrapping_sub(previous.pgpgout),
+            pswpin: self.pswpin.wrapping_sub(previous.pswpin),
+            pswpout: self.pswpout.wrapping_sub(previous.pswpout),
+            pgfault: self.pgfault.wrapping_sub(previous.pgfault),
+            pgmajfault: self.pgmajfault.wrapping_sub(previous.pgmajfault),
+            oom_kill: self
+                .oom_kill
+                .map(|current| current.wrapping_sub(previous.oom_kill.unwrap_or_default())),
+            others: self
+                .others
+                .iter()
+                .map(|(key, current)| {
+                    let previous = previous.others.get(key).copied().unwrap_or_default();
+
+                    (key.clone(), current.wrapping_sub(previous))
+                })
+                .collect(),
+        }
+    }
+}
+
+///
+/// ```no_run
+/// let vmstat = unixism::proc::vmstat::parse(std::fs::File::open("/proc/vmstat").unwrap()).unwrap();
+///
+/// println!("{} page faults, {:?} OOM kills", vmstat.pgfault, vmstat.oom_kill);
+/// ```
+pub fn parse<R>(reader: R) -> Result<VmStat, ParseProcError>
+where
+    R: io::Read,
+{
+    let mut vmstat = VmStat::default();
+
+    for line in BufReader::new(reader).lines() {
+        let line = line?;
+        let line = line.trim();
+
+        if line.is_empty() {
+            continue;
+        }
+
+        let fields = line.split_whitespace().collect::<Vec<_>>();
+
+        let [key, value] = fields[..] else {
+            return Err(ParseProcError::InvalidEntry(line.to_owned()));
+        };
+
+        let value = value.parse::<u64>()?;
+
+        match key {
+            "pgpgin" => vmstat.pgpgin = value,
+            "pgpgout" => vmstat.pgpgout = value,
+            "pswpin" => vmstat.pswpin = value,
+            "pswpout" => vmstat.pswpout = value,
+            "pgfault" => vmstat.pgfault = value,
+            "pgmajfault" => vmstat.pgmajfault = value,
+            "oom_kill" => vmstat.oom_kill = Some(value),
+            key => {
+                vmstat.others.insert(key.to_owned(), value);
+            }
+        }
+    }
+
+    Ok(vmstat)
+}
+
+///
+/// Same as parse, but parses the `/proc/vmstat` as default.
+///
+/// ```no_run
+/// let vmstat = unixism::proc::vmstat::parse_default().unwrap();
+/// ```
+pub fn parse_default() -> Result<VmStat, ParseProcError> {
+    parse(fs::File::open("/proc/vmstat")?)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Cursor;
+
+    use super::*;
+
+    #[test]
+    fn it_parse() {
+        let previous = parse(Cursor::new(
+            r#"
+nr_free_pages 1000
+pgpgin 500
+pgpgout 700
+pswpin 0
+pswpout 10
+pgfault 100000
+pgmajfault 50
+        "#,
+        ))
+        .unwrap();
+
+        assert_eq!(100000, previous.pgfault);
+        assert_eq!(None, previous.oom_kill);
+        assert_eq!(Some(1000), previous.get("nr_free_pages"));
+        assert_eq!(Some(10), previous.get("pswpout"));
+        assert_eq!(None, previous.get("missing"));
+
+        let current = parse(Cursor::new(
+            r#"
+nr_free_pages 800
+pgpgin 600
+pgpgout 700
+pswpin 5
+pswpout 30
+pgfault 100500
+pgmajfault 52
+oom_kill 1
+        "#,
+        ))
+        .unwrap();
+
+        let delta = current.delta(&previous);
+        assert_eq!(100, delta.pgpgin);
+        assert_eq!(20, delta.pswpout);
+        assert_eq!(500, delta.pgfault);
+        assert_eq!(2, delta.pgmajfault);
+        assert_eq!(Some(1), delta.oom_kill);
+
+        assert!(parse(Cursor::new("pgfault")).is_err());
+        assert!(parse(Cursor::new("pgfault x")).is_err());
+    }
+}
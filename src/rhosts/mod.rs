@@ -0,0 +1,241 @@
+use std::{
+    error, fmt, fs,
+    io::{self, BufRead, BufReader},
+    path::Path,
+    str::FromStr,
+};
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Pattern {
+    /// A lone `+`, matching every host or user.
+    Any,
+    Name(String),
+    Netgroup(String),
+}
+
+/// A host or user field, `negated` when it starts with `-` and so denies the trust.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Spec {
+    pub negated: bool,
+    pub pattern: Pattern,
+}
+
+impl From<&str> for Spec {
+    fn from(s: &str) -> Self {
+        let (negated, rest) = match s.strip_prefix('-') {
+            Some(rest) => (true, rest),
+            None => (false, s.strip_prefix('+').unwrap_or(s)),
+        };
+
+        let pattern = match rest {
+            "" => Pattern::Any,
+            rest => match rest.strip_prefix('@') {
+                Some(netgroup) => Pattern::Netgroup(netgroup.to_owned()),
+                None => Pattern::Name(rest.to_owned()),
+            },
+        };
+
+        Spec { negated, pattern }
+    }
+}
+
+impl fmt::Display for Spec {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match (self.negated, &self.pattern) {
+            (true, _) => write!(f, "-")?,
+            (false, Pattern::Any | Pattern::Netgroup(_)) => write!(f, "+")?,
+            (false, Pattern::Name(_)) => {}
+        }
+
+        match &self.pattern {
+            Pattern::Any => Ok(()),
+            Pattern::Name(name) => write!(f, "{}", name),
+            Pattern::Netgroup(netgroup) => write!(f, "@{}", netgroup),
+        }
+    }
+}
+
+/// A line of `/etc/hosts.equiv` or `~/.rhosts`, without a user the remote user must have the same name.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Entry {
+    pub host: Spec,
+    pub user: Option<Spec>,
+}
+
+impl Entry {
+    /// Whether the entry trusts every host, the classic `+` misconfiguration.
+    pub fn trusts_any_host(&self) -> bool {
+        !self.host.negated && self.host.pattern == Pattern::Any
+    }
+
+    /// Whether the entry lets every remote user log in as any local one.
+    pub fn trusts_any_user(&self) -> bool {
+        self.user
+            .as_ref()
+            .is_some_and(|user| !user.negated && user.pattern == Pattern::Any)
+    }
+}
+
+impl FromStr for Entry {
+    type Err = ParseRhostsError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let fields = s.split_whitespace().collect::<Vec<_>>();
+
+        match fields[..] {
+            [host] => Ok(Entry {
+                host: Spec::from(host),
+                user: None,
+            }),
+            [host, user] => Ok(Entry {
+                host: Spec::from(host),
+                user: Some(Spec::from(user)),
+            }),
+            _ => Err(ParseRhostsError::InvalidEntry(s.to_owned())),
+        }
+    }
+}
+
+impl fmt::Display for Entry {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.host)?;
+
+        if let Some(user) = &self.user {
+            write!(f, " {}", user)?;
+        }
+
+        Ok(())
+    }
+}
+
+#[derive(Debug)]
+pub enum ParseRhostsError {
+    InvalidEntry(String),
+    IOError(io::Error),
+}
+
+impl error::Error for ParseRhostsError {}
+
+impl fmt::Display for ParseRhostsError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::InvalidEntry(entry) => write!(f, "invalid rhosts entry: {}", entry),
+            Self::IOError(error) => write!(f, "{}", error),
+        }
+    }
+}
+
+impl From<io::Error> for ParseRhostsError {
+    fn from(value: io::Error) -> Self {
+        ParseRhostsError::IOError(value)
+    }
+}
+
+///
+/// Parses the `hosts.equiv(5)` format shared by `/etc/hosts.equiv` and `~/.rhosts`.
+///
+/// ```no_run
+/// let entries = unixism::rhosts::parse(std::fs::File::open("/etc/hosts.equiv").unwrap()).unwrap();
+///
+/// for entry in entries.filter(|entry| entry.trusts_any_host()) {
+///     println!("{entry}");
+/// }
+/// ```
+pub fn parse<R>(reader: R) -> Result<impl Iterator<Item = Entry>, ParseRhostsError>
+where
+    R: io::Read,
+{
+    let mut entries = Vec::new();
+
+    for line in BufReader::new(reader).lines() {
+        let line = line?;
+        let line = line.trim();
+
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+
+        entries.push(line.parse::<Entry>()?);
+    }
+
+    Ok(entries.into_iter())
+}
+
+///
+/// Same as parse, but parses the `/etc/hosts.equiv` as default.
+///
+/// ```no_run
+/// let entries = unixism::rhosts::parse_default().unwrap();
+/// ```
+pub fn parse_default() -> Result<impl Iterator<Item = Entry>, ParseRhostsError> {
+    parse(fs::File::open("/etc/hosts.equiv")?)
+}
+
+///
+/// Same as parse, but parses the `.rhosts` of the home directory.
+///
+/// ```no_run
+/// let entries = unixism::rhosts::parse_rhosts("/home/alice").unwrap();
+/// ```
+pub fn parse_rhosts<P>(home: P) -> Result<impl Iterator<Item = Entry>, ParseRhostsError>
+where
+    P: AsRef<Path>,
+{
+    parse(fs::File::open(home.as_ref().join(".rhosts"))?)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Cursor;
+
+    use super::*;
+
+    #[test]
+    fn it_parse() {
+        let entries = parse(Cursor::new(
+            r#"
+# trusted hosts
+trusted.example.com
+-evil.example.com
++@admins alice
+build.example.com -@contractors
++ +
+        "#,
+        ))
+        .unwrap()
+        .collect::<Vec<_>>();
+
+        assert_eq!(5, entries.len());
+        assert_eq!(
+            Entry {
+                host: Spec {
+                    negated: false,
+                    pattern: Pattern::Name("trusted.example.com".to_owned()),
+                },
+                user: None,
+            },
+            entries[0]
+        );
+        assert!(entries[1].host.negated);
+        assert_eq!(
+            Pattern::Netgroup("admins".to_owned()),
+            entries[2].host.pattern
+        );
+        assert_eq!(
+            Some(Spec {
+                negated: true,
+                pattern: Pattern::Netgroup("contractors".to_owned()),
+            }),
+            entries[3].user
+        );
+        assert!(entries[4].trusts_any_host());
+        assert!(entries[4].trusts_any_user());
+        assert!(!entries[2].trusts_any_host());
+
+        assert_eq!("+@admins alice", entries[2].to_string());
+        assert_eq!("-evil.example.com", entries[1].to_string());
+        assert_eq!("+ +", entries[4].to_string());
+
+        assert!(parse(Cursor::new("host user extra")).is_err());
+    }
+}
@@ -0,0 +1,107 @@
+use std::{
+    error, fmt, fs,
+    io::{self, BufRead, BufReader},
+};
+
+#[derive(Debug)]
+pub enum ParseSecurettyError {
+    IOError(io::Error),
+}
+
+impl error::Error for ParseSecurettyError {}
+
+impl fmt::Display for ParseSecurettyError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::IOError(error) => write!(f, "{}", error),
+        }
+    }
+}
+
+impl From<io::Error> for ParseSecurettyError {
+    fn from(value: io::Error) -> Self {
+        ParseSecurettyError::IOError(value)
+    }
+}
+
+///
+/// ```no_run
+/// let ttys = unixism::securetty::parse(std::fs::File::open("/etc/securetty").unwrap()).unwrap();
+///
+/// for tty in ttys {
+///     println!("{}", tty);
+/// }
+/// ```
+pub fn parse<R>(reader: R) -> Result<impl Iterator<Item = String>, ParseSecurettyError>
+where
+    R: io::Read,
+{
+    let mut ttys = Vec::new();
+
+    for line in BufReader::new(reader).lines() {
+        let line = line?;
+        let line = line.trim();
+
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+
+        ttys.push(line.to_owned());
+    }
+
+    Ok(ttys.into_iter())
+}
+
+///
+/// Same as parse, but parses the `/etc/securetty` as default.
+///
+/// ```no_run
+/// let ttys = unixism::securetty::parse_default().unwrap();
+/// ```
+pub fn parse_default() -> Result<impl Iterator<Item = String>, ParseSecurettyError> {
+    parse(fs::File::open("/etc/securetty")?)
+}
+
+///
+/// Checks whether root may log in on `tty` the way `pam_securetty` does,
+/// comparing names with the `/dev/` prefix stripped from both sides.
+///
+/// ```no_run
+/// let ttys = unixism::securetty::parse_default().unwrap().collect::<Vec<_>>();
+///
+/// println!("{}", unixism::securetty::is_allowed_tty(&ttys, "/dev/tty1"));
+/// ```
+pub fn is_allowed_tty(ttys: &[String], tty: &str) -> bool {
+    let tty = tty.strip_prefix("/dev/").unwrap_or(tty);
+
+    ttys.iter()
+        .any(|allowed| allowed.strip_prefix("/dev/").unwrap_or(allowed) == tty)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Cursor;
+
+    use super::*;
+
+    #[test]
+    fn it_parse() {
+        let ttys = parse(Cursor::new(
+            r#"
+# /etc/securetty: list of terminals on which root is allowed to login.
+console
+tty1
+/dev/ttyS0
+        "#,
+        ))
+        .unwrap()
+        .collect::<Vec<_>>();
+
+        assert_eq!(vec!["console", "tty1", "/dev/ttyS0"], ttys);
+
+        assert!(is_allowed_tty(&ttys, "tty1"));
+        assert!(is_allowed_tty(&ttys, "/dev/tty1"));
+        assert!(is_allowed_tty(&ttys, "ttyS0"));
+        assert!(!is_allowed_tty(&ttys, "pts/0"));
+    }
+}
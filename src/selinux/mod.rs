@@ -0,0 +1,300 @@
+use std::{
+    error, fmt, fs,
+    io::{self, BufRead, BufReader},
+    path::Path,
+    str::FromStr,
+};
+
+/// Where selinuxfs is mounted when SELinux is enabled.
+pub const SELINUXFS: &str = "sys/fs/selinux";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Mode {
+    Enforcing,
+    Permissive,
+    Disabled,
+}
+
+impl FromStr for Mode {
+    type Err = ParseSelinuxError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_ascii_lowercase().as_str() {
+            "enforcing" => Ok(Mode::Enforcing),
+            "permissive" => Ok(Mode::Permissive),
+            "disabled" => Ok(Mode::Disabled),
+            _ => Err(ParseSelinuxError::InvalidEntry(s.to_owned())),
+        }
+    }
+}
+
+impl fmt::Display for Mode {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Mode::Enforcing => write!(f, "enforcing"),
+            Mode::Permissive => write!(f, "permissive"),
+            Mode::Disabled => write!(f, "disabled"),
+        }
+    }
+}
+
+/// The settings of `/etc/selinux/config` applied on the next boot.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Config {
+    /// `SELINUX=`, disabled when missing.
+    pub mode: Mode,
+    /// `SELINUXTYPE=`, like `targeted` or `mls`.
+    pub policy_type: Option<String>,
+    /// Every variable in file order, including the ones without a field.
+    pub variables: Vec<(String, String)>,
+}
+
+impl Config {
+    pub fn get(&self, name: &str) -> Option<&str> {
+        self.variables
+            .iter()
+            .rev()
+            .find(|(variable, _)| variable == name)
+            .map(|(_, value)| value.as_str())
+    }
+}
+
+///
+/// The runtime state of SELinux together with the configuration.
+///
+/// ```no_run
+/// let status = unixism::selinux::status().unwrap();
+///
+/// if status.mode != status.configured_mode.unwrap_or(status.mode) {
+///     println!("{} now, {:?} after reboot", status.mode, status.configured_mode);
+/// }
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Status {
+    /// The current mode, disabled when selinuxfs is not mounted.
+    pub mode: Mode,
+    /// The mode of the configuration, `None` without a configuration file.
+    pub configured_mode: Option<Mode>,
+    /// The policy type of the configuration.
+    pub policy_type: Option<String>,
+    /// The version of the loaded policy, `None` when disabled.
+    pub policy_version: Option<u32>,
+}
+
+#[derive(Debug)]
+pub enum ParseSelinuxError {
+    InvalidEntry(String),
+    IOError(io::Error),
+}
+
+impl error::Error for ParseSelinuxError {}
+
+impl fmt::Display for ParseSelinuxError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::InvalidEntry(entry) => write!(f, "invalid entry: {}", entry),
+            Self::IOError(error) => write!(f, "{}", error),
+        }
+    }
+}
+
+impl From<io::Error> for ParseSelinuxError {
+    fn from(value: io::Error) -> Self {
+        ParseSelinuxError::IOError(value)
+    }
+}
+
+///
+/// ```no_run
+/// let config = unixism::selinux::parse(std::fs::File::open("/etc/selinux/config").unwrap()).unwrap();
+///
+/// println!("{} {:?}", config.mode, config.policy_type);
+/// ```
+pub fn parse<R>(reader: R) -> Result<Config, ParseSelinuxError>
+where
+    R: io::Read,
+{
+    let mut config = Config {
+        mode: Mode::Disabled,
+        policy_type: None,
+        variables: Vec::new(),
+    };
+
+    for line in BufReader::new(reader).lines() {
+        let line = line?;
+        let line = line.trim();
+
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+
+        let (name, value) = line
+            .split_once('=')
+            .ok_or_else(|| ParseSelinuxError::InvalidEntry(line.to_owned()))?;
+        let (name, value) = (name.trim(), value.trim().trim_matches('"'));
+
+        match name {
+            "SELINUX" => config.mode = value.parse()?,
+            "SELINUXTYPE" => config.policy_type = Some(value.to_owned()),
+            _ => {}
+        }
+
+        config.variables.push((name.to_owned(), value.to_owned()));
+    }
+
+    Ok(config)
+}
+
+///
+/// Same as parse, but parses the `/etc/selinux/config` as default.
+///
+/// ```no_run
+/// let config = unixism::selinux::parse_default().unwrap();
+/// ```
+pub fn parse_default() -> Result<Config, ParseSelinuxError> {
+    parse(fs::File::open("/etc/selinux/config")?)
+}
+
+///
+/// Same as mode, but reads selinuxfs under `root`.
+///
+/// ```no_run
+/// let mode = unixism::selinux::mode_at("/").unwrap();
+/// ```
+pub fn mode_at<P>(root: P) -> Result<Mode, ParseSelinuxError>
+where
+    P: AsRef<Path>,
+{
+    match fs::read_to_string(root.as_ref().join(SELINUXFS).join("enforce")) {
+        Ok(enforce) => match enforce.trim() {
+            "1" => Ok(Mode::Enforcing),
+            "0" => Ok(Mode::Permissive),
+            enforce => Err(ParseSelinuxError::InvalidEntry(enforce.to_owned())),
+        },
+        Err(e) if e.kind() == io::ErrorKind::NotFound => Ok(Mode::Disabled),
+        Err(e) => Err(e.into()),
+    }
+}
+
+///
+/// Returns the current mode of `/sys/fs/selinux/enforce`, disabled when selinuxfs is not mounted.
+///
+/// ```no_run
+/// println!("{}", unixism::selinux::mode().unwrap());
+/// ```
+pub fn mode() -> Result<Mode, ParseSelinuxError> {
+    mode_at("/")
+}
+
+///
+/// Same as status, but reads the files under `root`.
+///
+/// ```no_run
+/// let status = unixism::selinux::status_at("/").unwrap();
+/// ```
+pub fn status_at<P>(root: P) -> Result<Status, ParseSelinuxError>
+where
+    P: AsRef<Path>,
+{
+    let root = root.as_ref();
+
+    let config = match fs::File::open(root.join("etc/selinux/config")) {
+        Ok(file) => Some(parse(file)?),
+        Err(e) if e.kind() == io::ErrorKind::NotFound => None,
+        Err(e) => return Err(e.into()),
+    };
+
+    let policy_version = match fs::read_to_string(root.join(SELINUXFS).join("policyvers")) {
+        Ok(version) => Some(
+            version
+                .trim()
+                .parse()
+                .map_err(|_| ParseSelinuxError::InvalidEntry(version.clone()))?,
+        ),
+        Err(e) if e.kind() == io::ErrorKind::NotFound => None,
+        Err(e) => return Err(e.into()),
+    };
+
+    Ok(Status {
+        mode: mode_at(root)?,
+        configured_mode: config.as_ref().map(|config| config.mode),
+        policy_type: config.and_then(|config| config.policy_type),
+        policy_version,
+    })
+}
+
+///
+/// Returns the current mode along with the configured one and the policy type.
+///
+/// ```no_run
+/// let status = unixism::selinux::status().unwrap();
+///
+/// println!("{} {:?}", status.mode, status.policy_type);
+/// ```
+pub fn status() -> Result<Status, ParseSelinuxError> {
+    status_at("/")
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Cursor;
+
+    use super::*;
+    use crate::tempdir::TempDir;
+
+    #[test]
+    fn it_parse() {
+        let config = parse(Cursor::new(
+            r#"
+# This file controls the state of SELinux on the system.
+SELINUX=permissive
+SELINUXTYPE=targeted
+SETLOCALDEFS="0"
+        "#,
+        ))
+        .unwrap();
+
+        assert_eq!(Mode::Permissive, config.mode);
+        assert_eq!(Some("targeted"), config.policy_type.as_deref());
+        assert_eq!(Some("0"), config.get("SETLOCALDEFS"));
+
+        assert_eq!(Mode::Disabled, parse(Cursor::new("")).unwrap().mode);
+        assert!(parse(Cursor::new("SELINUX=sometimes")).is_err());
+        assert!(parse(Cursor::new("SELINUX")).is_err());
+    }
+
+    #[test]
+    fn it_status_at() {
+        let root = TempDir::new("selinux");
+        fs::create_dir_all(root.join("etc/selinux")).unwrap();
+
+        assert_eq!(
+            Status {
+                mode: Mode::Disabled,
+                configured_mode: None,
+                policy_type: None,
+                policy_version: None,
+            },
+            status_at(&root).unwrap()
+        );
+
+        fs::create_dir_all(root.join(SELINUXFS)).unwrap();
+        fs::write(root.join(SELINUXFS).join("enforce"), "1").unwrap();
+        fs::write(root.join(SELINUXFS).join("policyvers"), "33\n").unwrap();
+        fs::write(
+            root.join("etc/selinux/config"),
+            "SELINUX=permissive\nSELINUXTYPE=mls\n",
+        )
+        .unwrap();
+
+        assert_eq!(
+            Status {
+                mode: Mode::Enforcing,
+                configured_mode: Some(Mode::Permissive),
+                policy_type: Some("mls".to_owned()),
+                policy_version: Some(33),
+            },
+            status_at(&root).unwrap()
+        );
+    }
+}
@@ -0,0 +1,288 @@
+use std::{
+    collections::HashMap,
+    error, fmt, fs,
+    io::{self, BufRead, BufReader},
+    num::ParseIntError,
+    str::FromStr,
+};
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum Protocol {
+    Tcp,
+    Udp,
+    Sctp,
+    Ddp,
+    Other(String),
+}
+
+impl From<&str> for Protocol {
+    fn from(s: &str) -> Self {
+        match s {
+            "tcp" => Protocol::Tcp,
+            "udp" => Protocol::Udp,
+            "sctp" => Protocol::Sctp,
+            "ddp" => Protocol::Ddp,
+            protocol => Protocol::Other(protocol.to_owned()),
+        }
+    }
+}
+
+impl fmt::Display for Protocol {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Protocol::Tcp => write!(f, "tcp"),
+            Protocol::Udp => write!(f, "udp"),
+            Protocol::Sctp => write!(f, "sctp"),
+            Protocol::Ddp => write!(f, "ddp"),
+            Protocol::Other(protocol) => write!(f, "{}", protocol),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Service {
+    pub name: String,
+    pub port: u16,
+    pub protocol: Protocol,
+    pub aliases: Vec<String>,
+}
+
+impl FromStr for Service {
+    type Err = ParseServicesError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut fields = s.split_whitespace();
+
+        let name = fields
+            .next()
+            .ok_or_else(|| ParseServicesError::InvalidEntry(s.to_owned()))?;
+        let (port, protocol) = fields
+            .next()
+            .and_then(|port| port.split_once('/'))
+            .ok_or_else(|| ParseServicesError::InvalidEntry(s.to_owned()))?;
+
+        Ok(Service {
+            name: name.to_owned(),
+            port: port.parse()?,
+            protocol: Protocol::from(protocol),
+            aliases: fields.map(String::from).collect(),
+        })
+    }
+}
+
+impl fmt::Display for Service {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} {}/{}", self.name, self.port, self.protocol)?;
+
+        for alias in &self.aliases {
+            write!(f, " {}", alias)?;
+        }
+
+        Ok(())
+    }
+}
+
+///
+/// Lookup index over parsed services, an in-memory equivalent of `getservbyname` and `getservbyport`.
+/// Like libc, the first service wins when names or ports are duplicated, aliases are looked up too.
+///
+/// ```no_run
+/// use unixism::services::{self, Protocol};
+///
+/// let index = services::Index::new(services::parse_default().unwrap());
+///
+/// if let Some(service) = index.by_port(443, Protocol::Tcp) {
+///     println!("{}", service.name);
+/// }
+/// ```
+#[derive(Debug, Default, Clone)]
+pub struct Index {
+    services: Vec<Service>,
+    by_name: HashMap<String, usize>,
+    by_name_and_protocol: HashMap<(String, Protocol), usize>,
+    by_port: HashMap<(u16, Protocol), usize>,
+}
+
+impl Index {
+    pub fn new<I>(services: I) -> Self
+    where
+        I: IntoIterator<Item = Service>,
+    {
+        services.into_iter().collect()
+    }
+
+    pub fn by_name(&self, name: &str) -> Option<&Service> {
+        self.by_name.get(name).map(|i| &self.services[*i])
+    }
+
+    pub fn by_name_and_protocol(&self, name: &str, protocol: Protocol) -> Option<&Service> {
+        self.by_name_and_protocol
+            .get(&(name.to_owned(), protocol))
+            .map(|i| &self.services[*i])
+    }
+
+    pub fn by_port(&self, port: u16, protocol: Protocol) -> Option<&Service> {
+        self.by_port
+            .get(&(port, protocol))
+            .map(|i| &self.services[*i])
+    }
+
+    pub fn services(&self) -> &[Service] {
+        &self.services
+    }
+}
+
+impl FromIterator<Service> for Index {
+    fn from_iter<I>(iter: I) -> Self
+    where
+        I: IntoIterator<Item = Service>,
+    {
+        let mut index = Index::default();
+
+        for service in iter {
+            let i = index.services.len();
+
+            for name in std::iter::once(&service.name).chain(&service.aliases) {
+                index.by_name.entry(name.clone()).or_insert(i);
+                index
+                    .by_name_and_protocol
+                    .entry((name.clone(), service.protocol.clone()))
+                    .or_insert(i);
+            }
+            index
+                .by_port
+                .entry((service.port, service.protocol.clone()))
+                .or_insert(i);
+            index.services.push(service);
+        }
+
+        index
+    }
+}
+
+#[derive(Debug)]
+pub enum ParseServicesError {
+    InvalidEntry(String),
+    ParseIntError(ParseIntError),
+    IOError(io::Error),
+}
+
+impl error::Error for ParseServicesError {}
+
+impl fmt::Display for ParseServicesError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::InvalidEntry(entry) => write!(f, "invalid service: {}", entry),
+            Self::ParseIntError(error) => write!(f, "{}", error),
+            Self::IOError(error) => write!(f, "{}", error),
+        }
+    }
+}
+
+impl From<ParseIntError> for ParseServicesError {
+    fn from(value: ParseIntError) -> Self {
+        ParseServicesError::ParseIntError(value)
+    }
+}
+
+impl From<io::Error> for ParseServicesError {
+    fn from(value: io::Error) -> Self {
+        ParseServicesError::IOError(value)
+    }
+}
+
+///
+/// ```no_run
+/// let services = unixism::services::parse(std::fs::File::open("/etc/services").unwrap()).unwrap();
+///
+/// for service in services {
+///     println!("{} {}/{}", service.name, service.port, service.protocol);
+/// }
+/// ```
+pub fn parse<R>(reader: R) -> Result<impl Iterator<Item = Service>, ParseServicesError>
+where
+    R: io::Read,
+{
+    let mut services = Vec::new();
+
+    for line in BufReader::new(reader).lines() {
+        let line = line?;
+        let line = line.split('#').next().unwrap_or_default().trim();
+
+        if line.is_empty() {
+            continue;
+        }
+
+        services.push(line.parse::<Service>()?);
+    }
+
+    Ok(services.into_iter())
+}
+
+///
+/// Same as parse, but parses the `/etc/services` as default.
+///
+/// ```no_run
+/// let services = unixism::services::parse_default().unwrap();
+/// ```
+pub fn parse_default() -> Result<impl Iterator<Item = Service>, ParseServicesError> {
+    parse(fs::File::open("/etc/services")?)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Cursor;
+
+    use super::*;
+
+    #[test]
+    fn it_parse() {
+        let services = parse(Cursor::new(
+            r#"
+# Network services, Internet style
+ssh		22/tcp				# SSH Remote Login Protocol
+http		80/tcp		www		# WorldWideWeb HTTP
+https		443/tcp
+https		443/udp
+domain		53/udp
+        "#,
+        ))
+        .unwrap()
+        .collect::<Vec<_>>();
+
+        assert_eq!(5, services.len());
+        assert_eq!(
+            Service {
+                name: "http".to_owned(),
+                port: 80,
+                protocol: Protocol::Tcp,
+                aliases: vec!["www".to_owned()],
+            },
+            services[1]
+        );
+        assert_eq!("http 80/tcp www", services[1].to_string());
+
+        let index = Index::new(services);
+
+        assert_eq!("https", index.by_port(443, Protocol::Tcp).unwrap().name);
+        assert_eq!(
+            Protocol::Udp,
+            index.by_port(443, Protocol::Udp).unwrap().protocol
+        );
+        assert!(index.by_port(53, Protocol::Tcp).is_none());
+        assert_eq!(Protocol::Tcp, index.by_name("https").unwrap().protocol);
+        assert_eq!(80, index.by_name("www").unwrap().port);
+        assert_eq!(
+            53,
+            index
+                .by_name_and_protocol("domain", Protocol::Udp)
+                .unwrap()
+                .port
+        );
+        assert!(index.by_name_and_protocol("ssh", Protocol::Udp).is_none());
+        assert_eq!(5, index.services().len());
+
+        assert!(parse(Cursor::new("http 80")).is_err());
+        assert!(parse(Cursor::new("http 80000/tcp")).is_err());
+    }
+}
@@ -0,0 +1,525 @@
+use std::{
+    error, fmt, fs,
+    io::{self, BufRead, BufReader},
+    num::ParseIntError,
+    str::FromStr,
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
+
+/// Number of seconds in a day, the unit of all shadow aging fields.
+pub const DAY: Duration = Duration::from_secs(24 * 60 * 60);
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Password {
+    /// The account is locked, the raw field (e.g. `!`, `*` or `!$6$...`) is kept as is.
+    Locked(String),
+    /// No password is required to log in.
+    Empty,
+    Hash(String),
+}
+
+impl From<&str> for Password {
+    fn from(value: &str) -> Self {
+        match value {
+            "" => Password::Empty,
+            locked if locked.starts_with('!') || locked.starts_with('*') => {
+                Password::Locked(locked.to_owned())
+            }
+            hash => Password::Hash(hash.to_owned()),
+        }
+    }
+}
+
+impl Password {
+    ///
+    /// Parses the crypt hash of the password, including hashes of locked accounts like `!$6$...`.
+    ///
+    /// ```
+    /// use unixism::shadow::{HashScheme, Password};
+    ///
+    /// let hash = Password::from("$6$rounds=5000$salt$digest").crypt_hash().unwrap();
+    ///
+    /// assert_eq!(HashScheme::Sha512, hash.scheme);
+    /// assert_eq!(Some("rounds=5000"), hash.params.as_deref());
+    /// assert_eq!("salt", hash.salt);
+    /// ```
+    pub fn crypt_hash(&self) -> Option<CryptHash> {
+        match self {
+            Password::Hash(hash) => hash.parse().ok(),
+            Password::Locked(locked) => locked.trim_start_matches(['!', '*']).parse().ok(),
+            Password::Empty => None,
+        }
+    }
+}
+
+/// The crypt(3) hashing method identified by the `$id$` prefix.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum HashScheme {
+    /// Traditional DES crypt without a prefix.
+    Des,
+    Md5,
+    Bcrypt,
+    Sha256,
+    Sha512,
+    Scrypt,
+    Yescrypt,
+    GostYescrypt,
+    Unknown(String),
+}
+
+impl HashScheme {
+    fn from_id(id: &str) -> Self {
+        match id {
+            "1" => Self::Md5,
+            "2" | "2a" | "2b" | "2x" | "2y" => Self::Bcrypt,
+            "5" => Self::Sha256,
+            "6" => Self::Sha512,
+            "7" => Self::Scrypt,
+            "y" => Self::Yescrypt,
+            "gy" => Self::GostYescrypt,
+            unknown => Self::Unknown(unknown.to_owned()),
+        }
+    }
+
+    /// Whether the scheme is considered broken and should be migrated away from.
+    pub fn is_weak(&self) -> bool {
+        matches!(self, Self::Des | Self::Md5)
+    }
+}
+
+/// A crypt(3) hash string split into its parts.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CryptHash {
+    pub scheme: HashScheme,
+    /// Scheme parameters, e.g. `rounds=5000` for SHA-crypt, the cost for bcrypt or the yescrypt parameters.
+    pub params: Option<String>,
+    pub salt: String,
+    pub digest: String,
+}
+
+impl FromStr for CryptHash {
+    type Err = ParseShadowError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let invalid = || ParseShadowError::InvalidEntry(s.to_owned());
+
+        let Some(rest) = s.strip_prefix('$') else {
+            if s.len() != 13
+                || !s
+                    .chars()
+                    .all(|c| c.is_ascii_alphanumeric() || c == '.' || c == '/')
+            {
+                return Err(invalid());
+            }
+
+            return Ok(CryptHash {
+                scheme: HashScheme::Des,
+                params: None,
+                salt: s[..2].to_owned(),
+                digest: s[2..].to_owned(),
+            });
+        };
+
+        let (id, rest) = rest.split_once('$').ok_or_else(invalid)?;
+        let scheme = HashScheme::from_id(id);
+
+        if scheme == HashScheme::Bcrypt {
+            let (cost, rest) = rest.split_once('$').ok_or_else(invalid)?;
+            if rest.len() < 22 || !rest.is_ascii() {
+                return Err(invalid());
+            }
+
+            return Ok(CryptHash {
+                scheme,
+                params: Some(cost.to_owned()),
+                salt: rest[..22].to_owned(),
+                digest: rest[22..].to_owned(),
+            });
+        }
+
+        let parts = rest.split('$').collect::<Vec<_>>();
+
+        let (params, salt, digest) = match parts[..] {
+            [salt, digest] => (None, salt, digest),
+            [params, salt, digest] => (Some(params.to_owned()), salt, digest),
+            _ => return Err(invalid()),
+        };
+
+        Ok(CryptHash {
+            scheme,
+            params,
+            salt: salt.to_owned(),
+            digest: digest.to_owned(),
+        })
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Entry {
+    pub name: String,
+    pub password: Password,
+    pub last_change: Option<SystemTime>,
+    pub min: Option<Duration>,
+    pub max: Option<Duration>,
+    pub warn: Option<Duration>,
+    pub inactive: Option<Duration>,
+    pub expire: Option<SystemTime>,
+    pub reserved: String,
+}
+
+/// State of an account derived from the shadow aging fields, as computed by pam_unix.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AccountStatus {
+    Active,
+    /// The password expires in the given number of days and the user should be warned.
+    Warn(i64),
+    /// The password has expired or was reset by the administrator and must be changed.
+    PasswordExpired,
+    /// The password expired long enough ago to pass the inactivity period, login is refused.
+    Inactive,
+    /// The account itself has expired, login is refused.
+    Expired,
+}
+
+impl Entry {
+    ///
+    /// Computes the account status at `now` using the same day arithmetic as pam_unix.
+    ///
+    /// ```no_run
+    /// use std::time::SystemTime;
+    ///
+    /// for entry in unixism::shadow::parse_default().unwrap() {
+    ///     println!("{}: {:?}", entry.name, entry.status(SystemTime::now()));
+    /// }
+    /// ```
+    pub fn status(&self, now: SystemTime) -> AccountStatus {
+        let today = days_since_epoch(now);
+
+        if let Some(expire) = self.expire {
+            if today >= days_since_epoch(expire) {
+                return AccountStatus::Expired;
+            }
+        }
+
+        let Some(last_change) = self.last_change.map(days_since_epoch) else {
+            return AccountStatus::Active;
+        };
+
+        if last_change == 0 {
+            return AccountStatus::PasswordExpired;
+        }
+
+        if today < last_change {
+            return AccountStatus::Active;
+        }
+
+        let Some(max) = self.max.map(days) else {
+            return AccountStatus::Active;
+        };
+
+        let age = today - last_change;
+
+        if age > max {
+            return match self.inactive.map(days) {
+                Some(inactive) if age > max + inactive => AccountStatus::Inactive,
+                _ => AccountStatus::PasswordExpired,
+            };
+        }
+
+        match self.warn.map(days) {
+            Some(warn) if age > max - warn => AccountStatus::Warn(last_change + max - today),
+            _ => AccountStatus::Active,
+        }
+    }
+
+    /// Whether login is refused because the account expired or the password is inactive.
+    pub fn is_expired(&self, now: SystemTime) -> bool {
+        matches!(
+            self.status(now),
+            AccountStatus::Expired | AccountStatus::Inactive
+        )
+    }
+
+    /// Whether the user has to change the password on the next login.
+    pub fn must_change_password(&self, now: SystemTime) -> bool {
+        self.status(now) == AccountStatus::PasswordExpired
+    }
+
+    /// Days left until the password expires, negative once expired, `None` if it never expires.
+    pub fn days_until_expiry(&self, now: SystemTime) -> Option<i64> {
+        let last_change = days_since_epoch(self.last_change?);
+        let max = days(self.max?);
+
+        Some(last_change + max - days_since_epoch(now))
+    }
+
+    /// Whether the minimum password age has passed and the user may change the password.
+    pub fn can_change_password(&self, now: SystemTime) -> bool {
+        match (self.last_change, self.min) {
+            (Some(last_change), Some(min)) => {
+                let last_change = days_since_epoch(last_change);
+
+                last_change == 0 || days_since_epoch(now) - last_change >= days(min)
+            }
+            _ => true,
+        }
+    }
+}
+
+fn days(duration: Duration) -> i64 {
+    (duration.as_secs() / DAY.as_secs()) as i64
+}
+
+fn days_since_epoch(time: SystemTime) -> i64 {
+    match time.duration_since(UNIX_EPOCH) {
+        Ok(duration) => days(duration),
+        Err(error) => -days(error.duration()) - 1,
+    }
+}
+
+impl FromStr for Entry {
+    type Err = ParseShadowError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let fields = s.split(':').collect::<Vec<_>>();
+
+        let [name, password, last_change, min, max, warn, inactive, expire, reserved] = fields[..]
+        else {
+            return Err(ParseShadowError::InvalidEntry(s.to_owned()));
+        };
+
+        Ok(Entry {
+            name: name.to_owned(),
+            password: Password::from(password),
+            last_change: parse_days(last_change)?.map(|days| UNIX_EPOCH + days),
+            min: parse_days(min)?,
+            max: parse_days(max)?,
+            warn: parse_days(warn)?,
+            inactive: parse_days(inactive)?,
+            expire: parse_days(expire)?.map(|days| UNIX_EPOCH + days),
+            reserved: reserved.to_owned(),
+        })
+    }
+}
+
+fn parse_days(s: &str) -> Result<Option<Duration>, ParseIntError> {
+    match s {
+        "" => Ok(None),
+        days => Ok(Some(DAY * days.parse::<u32>()?)),
+    }
+}
+
+#[derive(Debug)]
+pub enum ParseShadowError {
+    InvalidEntry(String),
+    ParseIntError(ParseIntError),
+    IOError(io::Error),
+}
+
+impl error::Error for ParseShadowError {}
+
+impl fmt::Display for ParseShadowError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::InvalidEntry(entry) => write!(f, "invalid shadow entry: {}", entry),
+            Self::ParseIntError(error) => write!(f, "{}", error),
+            Self::IOError(error) => write!(f, "{}", error),
+        }
+    }
+}
+
+impl From<ParseIntError> for ParseShadowError {
+    fn from(value: ParseIntError) -> Self {
+        ParseShadowError::ParseIntError(value)
+    }
+}
+
+impl From<io::Error> for ParseShadowError {
+    fn from(value: io::Error) -> Self {
+        ParseShadowError::IOError(value)
+    }
+}
+
+///
+/// ```no_run
+/// use unixism::shadow::Password;
+///
+/// let entries = unixism::shadow::parse(std::fs::File::open("/etc/shadow").unwrap()).unwrap();
+///
+/// for entry in entries {
+///     if let Password::Empty = entry.password {
+///         println!("{} has no password", entry.name);
+///     }
+/// }
+/// ```
+pub fn parse<R>(reader: R) -> Result<impl Iterator<Item = Entry>, ParseShadowError>
+where
+    R: io::Read,
+{
+    let mut entries = Vec::new();
+
+    for line in BufReader::new(reader).lines() {
+        let line = line?;
+
+        if line.trim().is_empty() || line.starts_with('#') {
+            continue;
+        }
+
+        entries.push(line.parse::<Entry>()?);
+    }
+
+    Ok(entries.into_iter())
+}
+
+///
+/// Same as parse, but parses the `/etc/shadow` as default.
+///
+/// ```no_run
+/// let entries = unixism::shadow::parse_default().unwrap();
+/// ```
+pub fn parse_default() -> Result<impl Iterator<Item = Entry>, ParseShadowError> {
+    parse(fs::File::open("/etc/shadow")?)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Cursor;
+
+    use super::*;
+
+    #[test]
+    fn it_parse() {
+        let entries = parse(Cursor::new(
+            r#"
+root:$6$salt$hash:19000:0:99999:7:::
+daemon:*:19000:0:99999:7:::
+guest::19000::::14:19500:
+locked:!$6$salt$hash:0:1:90:7:30:20000:
+        "#,
+        ))
+        .unwrap()
+        .collect::<Vec<_>>();
+
+        assert_eq!(4, entries.len());
+        assert_eq!(
+            Entry {
+                name: "root".to_owned(),
+                password: Password::Hash("$6$salt$hash".to_owned()),
+                last_change: Some(UNIX_EPOCH + DAY * 19000),
+                min: Some(Duration::ZERO),
+                max: Some(DAY * 99999),
+                warn: Some(DAY * 7),
+                inactive: None,
+                expire: None,
+                reserved: "".to_owned(),
+            },
+            entries[0]
+        );
+        assert_eq!(Password::Locked("*".to_owned()), entries[1].password);
+        assert_eq!(Password::Empty, entries[2].password);
+        assert_eq!(None, entries[2].max);
+        assert_eq!(Some(DAY * 14), entries[2].inactive);
+        assert_eq!(Some(UNIX_EPOCH + DAY * 19500), entries[2].expire);
+        assert_eq!(
+            Password::Locked("!$6$salt$hash".to_owned()),
+            entries[3].password
+        );
+        assert_eq!(Some(UNIX_EPOCH), entries[3].last_change);
+
+        assert!(parse(Cursor::new("root:x:19000:0:99999:7::")).is_err());
+        assert!(parse(Cursor::new("root:x:soon:0:99999:7:::")).is_err());
+    }
+
+    #[test]
+    fn it_compute_aging() {
+        let entry = "user:$6$x:20000:1:90:7:30::".parse::<Entry>().unwrap();
+        let day = |n: u32| UNIX_EPOCH + DAY * n + Duration::from_secs(3600);
+
+        assert_eq!(AccountStatus::Active, entry.status(day(20000)));
+        assert!(!entry.can_change_password(day(20000)));
+        assert!(entry.can_change_password(day(20001)));
+        assert_eq!(Some(90), entry.days_until_expiry(day(20000)));
+
+        assert_eq!(AccountStatus::Warn(5), entry.status(day(20085)));
+        assert_eq!(AccountStatus::Active, entry.status(day(20083)));
+
+        assert_eq!(AccountStatus::PasswordExpired, entry.status(day(20091)));
+        assert!(entry.must_change_password(day(20091)));
+        assert!(!entry.is_expired(day(20091)));
+        assert_eq!(Some(-1), entry.days_until_expiry(day(20091)));
+
+        assert_eq!(AccountStatus::Inactive, entry.status(day(20121)));
+        assert!(entry.is_expired(day(20121)));
+
+        let reset = "user:$6$x:0:0:99999:7:::".parse::<Entry>().unwrap();
+        assert!(reset.must_change_password(day(20000)));
+
+        let expired = "user:$6$x:20000:::::20010:".parse::<Entry>().unwrap();
+        assert_eq!(AccountStatus::Active, expired.status(day(20009)));
+        assert_eq!(AccountStatus::Expired, expired.status(day(20010)));
+        assert_eq!(None, expired.days_until_expiry(day(20000)));
+    }
+
+    #[test]
+    fn it_identify_hash_scheme() {
+        let cases = [
+            (
+                "$1$saltsalt$digest",
+                HashScheme::Md5,
+                None,
+                "saltsalt",
+                "digest",
+            ),
+            ("$5$salt$digest", HashScheme::Sha256, None, "salt", "digest"),
+            (
+                "$6$rounds=656000$salt$digest",
+                HashScheme::Sha512,
+                Some("rounds=656000"),
+                "salt",
+                "digest",
+            ),
+            (
+                "$2b$12$R9h/cIPz0gi.URNNX3kh2OPST9/PgBkqquzi.Ss7KIUgO2t0jWMUW",
+                HashScheme::Bcrypt,
+                Some("12"),
+                "R9h/cIPz0gi.URNNX3kh2O",
+                "PST9/PgBkqquzi.Ss7KIUgO2t0jWMUW",
+            ),
+            (
+                "$y$j9T$salt$digest",
+                HashScheme::Yescrypt,
+                Some("j9T"),
+                "salt",
+                "digest",
+            ),
+            ("abJnggxhB/yWI", HashScheme::Des, None, "ab", "JnggxhB/yWI"),
+        ];
+
+        for (hash, scheme, params, salt, digest) in cases {
+            let parsed = Password::from(hash).crypt_hash().unwrap();
+
+            assert_eq!(
+                CryptHash {
+                    scheme,
+                    params: params.map(String::from),
+                    salt: salt.to_owned(),
+                    digest: digest.to_owned(),
+                },
+                parsed,
+                "{hash}"
+            );
+        }
+
+        assert_eq!(
+            HashScheme::Sha512,
+            Password::from("!$6$salt$digest")
+                .crypt_hash()
+                .unwrap()
+                .scheme
+        );
+        assert!(HashScheme::Md5.is_weak());
+        assert!(!HashScheme::Yescrypt.is_weak());
+        assert_eq!(None, Password::from("*").crypt_hash());
+        assert_eq!(None, Password::Empty.crypt_hash());
+    }
+}
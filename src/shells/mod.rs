@@ -0,0 +1,143 @@
+use std::{
+    error, fmt, fs,
+    io::{self, BufRead, BufReader},
+    os::unix::fs::PermissionsExt,
+    path::{Path, PathBuf},
+};
+
+#[derive(Debug)]
+pub enum ParseShellsError {
+    IOError(io::Error),
+}
+
+impl error::Error for ParseShellsError {}
+
+impl fmt::Display for ParseShellsError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::IOError(error) => write!(f, "{}", error),
+        }
+    }
+}
+
+impl From<io::Error> for ParseShellsError {
+    fn from(value: io::Error) -> Self {
+        ParseShellsError::IOError(value)
+    }
+}
+
+///
+/// ```no_run
+/// let shells = unixism::shells::parse(std::fs::File::open("/etc/shells").unwrap()).unwrap();
+///
+/// for shell in shells {
+///     println!("{}", shell.display());
+/// }
+/// ```
+pub fn parse<R>(reader: R) -> Result<impl Iterator<Item = PathBuf>, ParseShellsError>
+where
+    R: io::Read,
+{
+    let mut shells = Vec::new();
+
+    for line in BufReader::new(reader).lines() {
+        let line = line?;
+        let line = line.trim();
+
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+
+        shells.push(PathBuf::from(line));
+    }
+
+    Ok(shells.into_iter())
+}
+
+///
+/// Same as parse, but parses the `/etc/shells` as default.
+///
+/// ```no_run
+/// let shells = unixism::shells::parse_default().unwrap();
+/// ```
+pub fn parse_default() -> Result<impl Iterator<Item = PathBuf>, ParseShellsError> {
+    parse(fs::File::open("/etc/shells")?)
+}
+
+///
+/// Checks `path` the way `chsh` does for unprivileged users: the shell must be an absolute path
+/// listed in `shells` and point to an executable file.
+///
+/// ```no_run
+/// let shells = unixism::shells::parse_default().unwrap().collect::<Vec<_>>();
+///
+/// println!("{}", unixism::shells::is_allowed_shell(&shells, "/bin/bash"));
+/// ```
+pub fn is_allowed_shell<P>(shells: &[PathBuf], path: P) -> bool
+where
+    P: AsRef<Path>,
+{
+    let path = path.as_ref();
+
+    path.is_absolute()
+        && shells.iter().any(|shell| shell == path)
+        && fs::metadata(path)
+            .map(|metadata| metadata.is_file() && metadata.permissions().mode() & 0o111 != 0)
+            .unwrap_or(false)
+}
+
+///
+/// Same as is_allowed_shell, but checks against the `/etc/shells` file.
+///
+/// ```no_run
+/// if !unixism::shells::is_valid_shell("/bin/zsh").unwrap() {
+///     eprintln!("/bin/zsh is not an allowed login shell");
+/// }
+/// ```
+pub fn is_valid_shell<P>(path: P) -> Result<bool, ParseShellsError>
+where
+    P: AsRef<Path>,
+{
+    Ok(is_allowed_shell(
+        &parse_default()?.collect::<Vec<_>>(),
+        path,
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Cursor;
+
+    use super::*;
+
+    #[test]
+    fn it_parse() {
+        let shells = parse(Cursor::new(
+            r#"
+# /etc/shells: valid login shells
+/bin/sh
+/bin/bash
+  /usr/bin/zsh
+        "#,
+        ))
+        .unwrap()
+        .collect::<Vec<_>>();
+
+        assert_eq!(
+            vec![
+                PathBuf::from("/bin/sh"),
+                PathBuf::from("/bin/bash"),
+                PathBuf::from("/usr/bin/zsh"),
+            ],
+            shells
+        );
+
+        assert!(is_allowed_shell(&shells, "/bin/sh"));
+        assert!(!is_allowed_shell(&shells, "/bin/false"));
+        assert!(!is_allowed_shell(&shells, "bin/sh"));
+        assert!(!is_allowed_shell(
+            &[PathBuf::from("/nonexistent/shell")],
+            "/nonexistent/shell"
+        ));
+    }
+}
@@ -0,0 +1,395 @@
+use std::{
+    error, fmt, fs,
+    io::{self, BufRead, BufReader},
+};
+
+///
+/// The variables of a file made of shell assignments meant to be sourced,
+/// like `/etc/default/grub`, `/etc/default/useradd` or the sysconfig files, in file order.
+///
+/// ```no_run
+/// let mut grub = unixism::shellvars::parse(std::fs::File::open("/etc/default/grub").unwrap()).unwrap();
+///
+/// println!("{:?}", grub.get("GRUB_CMDLINE_LINUX"));
+///
+/// grub.set("GRUB_TIMEOUT", "5");
+/// ```
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct Variables {
+    pub variables: Vec<(String, String)>,
+}
+
+impl Variables {
+    /// Returns the value of the variable, the last assignment winning as when sourced.
+    pub fn get(&self, name: &str) -> Option<&str> {
+        self.variables
+            .iter()
+            .rev()
+            .find(|(variable, _)| variable == name)
+            .map(|(_, value)| value.as_str())
+    }
+
+    /// Sets the variable, replacing its last assignment in place when it is already set.
+    pub fn set(&mut self, name: &str, value: &str) {
+        match self
+            .variables
+            .iter_mut()
+            .rev()
+            .find(|(variable, _)| variable == name)
+        {
+            Some((_, current)) => *current = value.to_owned(),
+            None => self.variables.push((name.to_owned(), value.to_owned())),
+        }
+    }
+
+    /// Removes every assignment of the variable, returning whether it was set.
+    pub fn remove(&mut self, name: &str) -> bool {
+        let len = self.variables.len();
+        self.variables.retain(|(variable, _)| variable != name);
+
+        len != self.variables.len()
+    }
+}
+
+impl fmt::Display for Variables {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (name, value) in &self.variables {
+            writeln!(f, "{}={}", name, quote(value))?;
+        }
+
+        Ok(())
+    }
+}
+
+impl FromIterator<(String, String)> for Variables {
+    fn from_iter<I>(iter: I) -> Self
+    where
+        I: IntoIterator<Item = (String, String)>,
+    {
+        Variables {
+            variables: iter.into_iter().collect(),
+        }
+    }
+}
+
+/// Whether the name is a valid shell variable name.
+pub fn is_name(name: &str) -> bool {
+    !name.is_empty()
+        && !name.starts_with(|c: char| c.is_ascii_digit())
+        && name.chars().all(|c| c.is_ascii_alphanumeric() || c == '_')
+}
+
+enum Unquoted {
+    Value(String),
+    /// A quote or a trailing backslash continues on the next line.
+    Unterminated,
+    Invalid,
+}
+
+fn scan(value: &str) -> Unquoted {
+    let mut unquoted = String::with_capacity(value.len());
+    let mut chars = value.chars();
+
+    while let Some(c) = chars.next() {
+        match c {
+            '\'' => loop {
+                match chars.next() {
+                    Some('\'') => break,
+                    Some(c) => unquoted.push(c),
+                    None => return Unquoted::Unterminated,
+                }
+            },
+            '"' => loop {
+                match chars.next() {
+                    Some('"') => break,
+                    Some('\\') => match chars.next() {
+                        Some(c @ ('"' | '\\' | '$' | '`')) => unquoted.push(c),
+                        Some('\n') => {}
+                        Some(c) => {
+                            unquoted.push('\\');
+                            unquoted.push(c);
+                        }
+                        None => return Unquoted::Unterminated,
+                    },
+                    Some(c) => unquoted.push(c),
+                    None => return Unquoted::Unterminated,
+                }
+            },
+            // Command substitutions are kept as written, they are not run.
+            '`' => {
+                unquoted.push(c);
+                loop {
+                    match chars.next() {
+                        Some(c) => {
+                            unquoted.push(c);
+                            if c == '`' {
+                                break;
+                            }
+                        }
+                        None => return Unquoted::Unterminated,
+                    }
+                }
+            }
+            '$' if chars.as_str().starts_with('(') => {
+                let mut depth = 0;
+                unquoted.push(c);
+                loop {
+                    match chars.next() {
+                        Some(c) => {
+                            unquoted.push(c);
+                            match c {
+                                '(' => depth += 1,
+                                ')' if depth == 1 => break,
+                                ')' => depth -= 1,
+                                _ => {}
+                            }
+                        }
+                        None => return Unquoted::Unterminated,
+                    }
+                }
+            }
+            '\\' => match chars.next() {
+                Some('\n') => {}
+                Some(c) => unquoted.push(c),
+                None => return Unquoted::Unterminated,
+            },
+            c if c.is_whitespace() => {
+                // Whitespace ends the assignment, the rest can only be a comment.
+                let rest = chars.as_str().trim_start();
+
+                return if rest.is_empty() || rest.starts_with('#') {
+                    Unquoted::Value(unquoted)
+                } else {
+                    Unquoted::Invalid
+                };
+            }
+            c => unquoted.push(c),
+        }
+    }
+
+    Unquoted::Value(unquoted)
+}
+
+///
+/// Removes sh quoting from the value of an assignment, stopping at an unquoted comment.
+/// Returns `None` if a quote is unterminated or an unquoted space is followed by anything but a comment,
+/// which would run a command when sourced.
+///
+/// ```
+/// assert_eq!(Some("it's quoted".to_owned()), unixism::shellvars::unquote(r#"it\'s" quoted" # comment"#));
+/// ```
+pub fn unquote(value: &str) -> Option<String> {
+    match scan(value) {
+        Unquoted::Value(value) => Some(value),
+        Unquoted::Unterminated | Unquoted::Invalid => None,
+    }
+}
+
+///
+/// Quotes the value for an assignment, double quoting it unless it is made of characters the shell takes literally.
+///
+/// ```
+/// assert_eq!("\"quiet splash\"", unixism::shellvars::quote("quiet splash"));
+/// assert_eq!("en_US.UTF-8", unixism::shellvars::quote("en_US.UTF-8"));
+/// ```
+pub fn quote(value: &str) -> String {
+    let literal = !value.is_empty()
+        && value
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || "@%+=:,./_-".contains(c));
+
+    if literal {
+        return value.to_owned();
+    }
+
+    let mut quoted = String::from('"');
+    for c in value.chars() {
+        if matches!(c, '"' | '\\' | '$' | '`') {
+            quoted.push('\\');
+        }
+        quoted.push(c);
+    }
+    quoted.push('"');
+
+    quoted
+}
+
+#[derive(Debug)]
+pub enum ParseShellVarsError {
+    InvalidEntry(String),
+    IOError(io::Error),
+}
+
+impl error::Error for ParseShellVarsError {}
+
+impl fmt::Display for ParseShellVarsError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::InvalidEntry(entry) => write!(f, "invalid assignment: {}", entry),
+            Self::IOError(error) => write!(f, "{}", error),
+        }
+    }
+}
+
+impl From<io::Error> for ParseShellVarsError {
+    fn from(value: io::Error) -> Self {
+        ParseShellVarsError::IOError(value)
+    }
+}
+
+///
+/// Parses `NAME=value` assignments, optionally prefixed with `export`, without running anything:
+/// quotes and escapes are removed the way sh does, comments are skipped, and quoted values or
+/// trailing backslashes continue on the next line. Variable references are kept as written.
+///
+/// ```no_run
+/// let vars = unixism::shellvars::parse(std::fs::File::open("/etc/default/useradd").unwrap()).unwrap();
+///
+/// println!("{:?}", vars.get("SHELL"));
+/// ```
+pub fn parse<R>(reader: R) -> Result<Variables, ParseShellVarsError>
+where
+    R: io::Read,
+{
+    let mut variables = Variables::default();
+    let mut pending = String::new();
+
+    for line in BufReader::new(reader).lines() {
+        let line = line?;
+
+        if pending.is_empty() {
+            let line = line.trim();
+
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+
+            pending.push_str(line);
+        } else {
+            pending.push('\n');
+            pending.push_str(&line);
+        }
+
+        let assignment = pending
+            .strip_prefix("export ")
+            .unwrap_or(&pending)
+            .trim_start();
+        let (name, value) = assignment
+            .split_once('=')
+            .filter(|(name, _)| is_name(name))
+            .ok_or_else(|| ParseShellVarsError::InvalidEntry(pending.clone()))?;
+
+        match scan(value) {
+            Unquoted::Value(value) => {
+                variables.variables.push((name.to_owned(), value));
+                pending.clear();
+            }
+            Unquoted::Unterminated => {}
+            Unquoted::Invalid => return Err(ParseShellVarsError::InvalidEntry(pending)),
+        }
+    }
+
+    if !pending.is_empty() {
+        return Err(ParseShellVarsError::InvalidEntry(pending));
+    }
+
+    Ok(variables)
+}
+
+///
+/// Same as parse, but parses the `/etc/default/<name>` file.
+///
+/// ```no_run
+/// let grub = unixism::shellvars::parse_default("grub").unwrap();
+/// ```
+pub fn parse_default(name: &str) -> Result<Variables, ParseShellVarsError> {
+    parse(fs::File::open(format!("/etc/default/{}", name))?)
+}
+
+///
+/// Writes the variables one per line, quoting the values which need it.
+/// Comments of the original file are not preserved.
+///
+/// ```no_run
+/// let mut grub = unixism::shellvars::parse_default("grub").unwrap();
+/// grub.set("GRUB_TIMEOUT", "5");
+///
+/// unixism::shellvars::write(std::fs::File::create("/etc/default/grub").unwrap(), &grub).unwrap();
+/// ```
+pub fn write<W>(mut writer: W, variables: &Variables) -> io::Result<()>
+where
+    W: io::Write,
+{
+    write!(writer, "{}", variables)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Cursor;
+
+    use super::*;
+
+    #[test]
+    fn it_parse() {
+        let vars = parse(Cursor::new(
+            r#"
+# If you change this file, run 'update-grub' afterwards.
+GRUB_DEFAULT=0
+GRUB_TIMEOUT=5
+GRUB_DISTRIBUTOR=`lsb_release -i -s 2> /dev/null || echo Debian`
+GRUB_CMDLINE_LINUX_DEFAULT="quiet splash"   # defaults
+GRUB_CMDLINE_LINUX="console=tty0
+    console=ttyS0,115200"
+export GRUB_TERMINAL='serial console'
+GRUB_GFXMODE=640x480\
+x32
+GRUB_BACKGROUND="$HOME/\"bg\".png"
+GRUB_DISABLE_OS_PROBER=$(echo "true")
+GRUB_TIMEOUT=10
+        "#,
+        ))
+        .unwrap();
+
+        assert_eq!(10, vars.variables.len());
+        assert_eq!(Some("10"), vars.get("GRUB_TIMEOUT"));
+        assert_eq!(
+            Some("`lsb_release -i -s 2> /dev/null || echo Debian`"),
+            vars.get("GRUB_DISTRIBUTOR")
+        );
+        assert_eq!(Some("quiet splash"), vars.get("GRUB_CMDLINE_LINUX_DEFAULT"));
+        assert_eq!(
+            Some("console=tty0\n    console=ttyS0,115200"),
+            vars.get("GRUB_CMDLINE_LINUX")
+        );
+        assert_eq!(Some("serial console"), vars.get("GRUB_TERMINAL"));
+        assert_eq!(Some("640x480x32"), vars.get("GRUB_GFXMODE"));
+        assert_eq!(Some("$HOME/\"bg\".png"), vars.get("GRUB_BACKGROUND"));
+        assert_eq!(Some("$(echo \"true\")"), vars.get("GRUB_DISABLE_OS_PROBER"));
+        assert_eq!(None, vars.get("GRUB_SAVEDEFAULT"));
+
+        assert!(parse(Cursor::new("NAME=\"unterminated\nOTHER=x")).is_err());
+        assert!(parse(Cursor::new("NAME=two words")).is_err());
+        assert!(parse(Cursor::new("1NAME=x")).is_err());
+        assert!(parse(Cursor::new("echo hello")).is_err());
+    }
+
+    #[test]
+    fn it_write() {
+        let mut vars = parse(Cursor::new("A=1\nB='two words'\nA=3\n")).unwrap();
+
+        vars.set("A", "4");
+        vars.set("C", "it's $HOME");
+        assert!(vars.remove("B"));
+        assert!(!vars.remove("B"));
+
+        let mut written = Vec::new();
+        write(&mut written, &vars).unwrap();
+
+        assert_eq!(
+            "A=1\nA=4\nC=\"it's \\$HOME\"\n",
+            String::from_utf8(written.clone()).unwrap()
+        );
+        assert_eq!(vars, parse(Cursor::new(written)).unwrap());
+        assert_eq!("\"\"", quote(""));
+    }
+}
@@ -0,0 +1,245 @@
+use std::{
+    error, fmt, fs,
+    io::{self, BufRead, BufReader},
+    num::ParseIntError,
+    str::FromStr,
+};
+
+/// Default lowest subordinate id handed out by useradd (`SUB_UID_MIN`).
+pub const SUB_ID_MIN: u32 = 100000;
+
+/// Default highest subordinate id handed out by useradd (`SUB_UID_MAX`).
+pub const SUB_ID_MAX: u32 = 600100000;
+
+/// Default number of subordinate ids per user (`SUB_UID_COUNT`).
+pub const SUB_ID_COUNT: u32 = 65536;
+
+/// A range of subordinate ids delegated to `owner`, which is a user name or a numeric id.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Range {
+    pub owner: String,
+    pub start: u32,
+    pub count: u32,
+}
+
+impl Range {
+    pub fn contains(&self, id: u32) -> bool {
+        id >= self.start && (id as u64) < self.start as u64 + self.count as u64
+    }
+
+    /// The first id after the range.
+    pub fn end(&self) -> u64 {
+        self.start as u64 + self.count as u64
+    }
+}
+
+impl FromStr for Range {
+    type Err = ParseSubidError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let fields = s.split(':').collect::<Vec<_>>();
+
+        let [owner, start, count] = fields[..] else {
+            return Err(ParseSubidError::InvalidEntry(s.to_owned()));
+        };
+
+        Ok(Range {
+            owner: owner.to_owned(),
+            start: start.parse()?,
+            count: count.parse()?,
+        })
+    }
+}
+
+impl fmt::Display for Range {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}:{}:{}", self.owner, self.start, self.count)
+    }
+}
+
+#[derive(Debug)]
+pub enum ParseSubidError {
+    InvalidEntry(String),
+    ParseIntError(ParseIntError),
+    IOError(io::Error),
+}
+
+impl error::Error for ParseSubidError {}
+
+impl fmt::Display for ParseSubidError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::InvalidEntry(entry) => write!(f, "invalid subordinate id entry: {}", entry),
+            Self::ParseIntError(error) => write!(f, "{}", error),
+            Self::IOError(error) => write!(f, "{}", error),
+        }
+    }
+}
+
+impl From<ParseIntError> for ParseSubidError {
+    fn from(value: ParseIntError) -> Self {
+        ParseSubidError::ParseIntError(value)
+    }
+}
+
+impl From<io::Error> for ParseSubidError {
+    fn from(value: io::Error) -> Self {
+        ParseSubidError::IOError(value)
+    }
+}
+
+///
+/// Checks whether `id` falls into one of the ranges delegated to `owner`.
+///
+/// ```no_run
+/// let ranges = unixism::subid::parse_subuid_default().unwrap().collect::<Vec<_>>();
+///
+/// println!("{}", unixism::subid::is_delegated(&ranges, "podman", 100000));
+/// ```
+pub fn is_delegated(ranges: &[Range], owner: &str, id: u32) -> bool {
+    ranges
+        .iter()
+        .any(|range| range.owner == owner && range.contains(id))
+}
+
+///
+/// Finds the lowest start of `count` consecutive ids within `min..=max` not overlapping any range,
+/// the same way useradd allocates subordinate ids.
+///
+/// ```no_run
+/// use unixism::subid;
+///
+/// let ranges = subid::parse_subuid_default().unwrap().collect::<Vec<_>>();
+///
+/// let start = subid::find_free(&ranges, subid::SUB_ID_COUNT, subid::SUB_ID_MIN, subid::SUB_ID_MAX);
+/// ```
+pub fn find_free(ranges: &[Range], count: u32, min: u32, max: u32) -> Option<u32> {
+    let mut sorted = ranges
+        .iter()
+        .filter(|range| range.count > 0)
+        .collect::<Vec<_>>();
+    sorted.sort_by_key(|range| range.start);
+
+    let mut candidate = min as u64;
+    let last = max as u64 + 1;
+
+    for range in sorted {
+        if candidate + count as u64 <= range.start as u64 {
+            break;
+        }
+
+        candidate = candidate.max(range.end());
+    }
+
+    if candidate + count as u64 <= last {
+        Some(candidate as u32)
+    } else {
+        None
+    }
+}
+
+///
+/// ```no_run
+/// let ranges = unixism::subid::parse(std::fs::File::open("/etc/subuid").unwrap()).unwrap();
+///
+/// for range in ranges {
+///     println!("owner: {}, start: {}, count: {}", range.owner, range.start, range.count);
+/// }
+/// ```
+pub fn parse<R>(reader: R) -> Result<impl Iterator<Item = Range>, ParseSubidError>
+where
+    R: io::Read,
+{
+    let mut ranges = Vec::new();
+
+    for line in BufReader::new(reader).lines() {
+        let line = line?;
+
+        if line.trim().is_empty() || line.starts_with('#') {
+            continue;
+        }
+
+        ranges.push(line.parse::<Range>()?);
+    }
+
+    Ok(ranges.into_iter())
+}
+
+///
+/// Same as parse, but parses the `/etc/subuid` as default.
+///
+/// ```no_run
+/// let ranges = unixism::subid::parse_subuid_default().unwrap();
+/// ```
+pub fn parse_subuid_default() -> Result<impl Iterator<Item = Range>, ParseSubidError> {
+    parse(fs::File::open("/etc/subuid")?)
+}
+
+///
+/// Same as parse, but parses the `/etc/subgid` as default.
+///
+/// ```no_run
+/// let ranges = unixism::subid::parse_subgid_default().unwrap();
+/// ```
+pub fn parse_subgid_default() -> Result<impl Iterator<Item = Range>, ParseSubidError> {
+    parse(fs::File::open("/etc/subgid")?)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Cursor;
+
+    use super::*;
+
+    #[test]
+    fn it_parse() {
+        let ranges = parse(Cursor::new(
+            r#"
+alice:100000:65536
+bob:165536:65536
+1002:300000:1000
+        "#,
+        ))
+        .unwrap()
+        .collect::<Vec<_>>();
+
+        assert_eq!(
+            vec![
+                Range {
+                    owner: "alice".to_owned(),
+                    start: 100000,
+                    count: 65536
+                },
+                Range {
+                    owner: "bob".to_owned(),
+                    start: 165536,
+                    count: 65536
+                },
+                Range {
+                    owner: "1002".to_owned(),
+                    start: 300000,
+                    count: 1000
+                },
+            ],
+            ranges
+        );
+
+        assert!(is_delegated(&ranges, "alice", 100000));
+        assert!(is_delegated(&ranges, "alice", 165535));
+        assert!(!is_delegated(&ranges, "alice", 165536));
+        assert!(is_delegated(&ranges, "bob", 165536));
+
+        assert_eq!(
+            Some(231072),
+            find_free(&ranges, SUB_ID_COUNT, SUB_ID_MIN, SUB_ID_MAX)
+        );
+        assert_eq!(
+            Some(231072),
+            find_free(&ranges, 1000, SUB_ID_MIN, SUB_ID_MAX)
+        );
+        assert_eq!(Some(301000), find_free(&ranges, 70000, 231072, SUB_ID_MAX));
+        assert_eq!(None, find_free(&ranges, 65536, 100000, 200000));
+
+        assert!(parse(Cursor::new("alice:100000")).is_err());
+    }
+}
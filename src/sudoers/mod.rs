@@ -0,0 +1,804 @@
+use std::{
+    collections::HashMap,
+    error, fmt, fs,
+    io::{self, BufRead, BufReader},
+    path::{Path, PathBuf},
+};
+
+use crate::netgroup::Netgroups;
+
+mod policy;
+
+pub use policy::{Decision, User};
+
+/// Tags which can prefix a command, like `NOPASSWD:`.
+const TAGS: [&str; 20] = [
+    "NOPASSWD",
+    "PASSWD",
+    "NOEXEC",
+    "EXEC",
+    "SETENV",
+    "NOSETENV",
+    "LOG_INPUT",
+    "NOLOG_INPUT",
+    "LOG_OUTPUT",
+    "NOLOG_OUTPUT",
+    "MAIL",
+    "NOMAIL",
+    "FOLLOW",
+    "NOFOLLOW",
+    "INTERCEPT",
+    "NOINTERCEPT",
+    "SUDOEDIT",
+    "NOSUDOEDIT",
+    "CHECKSUM",
+    "NOCHECKSUM",
+];
+
+/// Options which can precede a command, like `CWD=/tmp`.
+const OPTIONS: [&str; 10] = [
+    "CWD",
+    "CHROOT",
+    "ROLE",
+    "TYPE",
+    "TIMEOUT",
+    "NOTBEFORE",
+    "NOTAFTER",
+    "APPARMOR_PROFILE",
+    "PRIVS",
+    "LIMITPRIVS",
+];
+
+/// A name of a user, host or runas list.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Name {
+    All,
+    Literal(String),
+    /// `%group` or `%:group`.
+    Group(String),
+    /// `+netgroup`.
+    Netgroup(String),
+    /// A reference to an alias, a name of uppercase letters, digits and underscores.
+    Alias(String),
+}
+
+impl From<&str> for Name {
+    fn from(s: &str) -> Self {
+        if s == "ALL" {
+            Name::All
+        } else if let Some(group) = s.strip_prefix("%:").or_else(|| s.strip_prefix('%')) {
+            Name::Group(group.to_owned())
+        } else if let Some(netgroup) = s.strip_prefix('+') {
+            Name::Netgroup(netgroup.to_owned())
+        } else if is_alias(s) {
+            Name::Alias(s.to_owned())
+        } else {
+            Name::Literal(s.to_owned())
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Command {
+    All,
+    Alias(String),
+    /// A command, or every command of a directory when the path ends with `/`.
+    /// Arguments of `None` allow any, `Some("\"\"")` allow none.
+    Path {
+        path: String,
+        args: Option<String>,
+    },
+}
+
+/// A possibly negated `!` item of a list.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Member<T> {
+    pub negated: bool,
+    pub value: T,
+}
+
+/// The `(users:groups)` a command may be run as.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct Runas {
+    pub users: Vec<Member<Name>>,
+    pub groups: Vec<Member<Name>>,
+}
+
+/// The tags of a command, `None` when neither the tag nor its negation is given.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct Tags {
+    /// `PASSWD` or `NOPASSWD`.
+    pub authenticate: Option<bool>,
+    /// `NOEXEC` or `EXEC`.
+    pub noexec: Option<bool>,
+    /// `SETENV` or `NOSETENV`.
+    pub setenv: Option<bool>,
+}
+
+/// A command of a rule, the runas and tags being inherited from the previous command when omitted.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CommandSpec {
+    pub runas: Option<Runas>,
+    pub tags: Tags,
+    pub command: Member<Command>,
+}
+
+/// The `hosts = commands` part of a rule.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Privilege {
+    pub hosts: Vec<Member<Name>>,
+    pub commands: Vec<CommandSpec>,
+}
+
+/// A user specification, `users hosts = (runas) TAGS: commands`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Rule {
+    pub users: Vec<Member<Name>>,
+    pub privileges: Vec<Privilege>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Entry {
+    /// A `Defaults` line, kept as is.
+    Defaults(String),
+    /// `@include` or `#include`.
+    Include(String),
+    /// `@includedir` or `#includedir`.
+    IncludeDir(String),
+    UserAlias(String, Vec<Member<Name>>),
+    RunasAlias(String, Vec<Member<Name>>),
+    HostAlias(String, Vec<Member<Name>>),
+    CmndAlias(String, Vec<Member<Command>>),
+    Rule(Rule),
+}
+
+///
+/// The aliases and rules of a sudoers policy, evaluated with `can_run`.
+///
+/// ```no_run
+/// let sudoers = unixism::sudoers::load().unwrap();
+///
+/// for rule in &sudoers.rules {
+///     println!("{:?}", rule.users);
+/// }
+/// ```
+#[derive(Debug, Default, Clone)]
+pub struct Sudoers {
+    pub defaults: Vec<String>,
+    pub user_aliases: HashMap<String, Vec<Member<Name>>>,
+    pub runas_aliases: HashMap<String, Vec<Member<Name>>>,
+    pub host_aliases: HashMap<String, Vec<Member<Name>>>,
+    pub cmnd_aliases: HashMap<String, Vec<Member<Command>>>,
+    pub rules: Vec<Rule>,
+    /// Netgroups `+netgroup` members are matched against, none unless set.
+    pub netgroups: Netgroups,
+}
+
+impl Extend<Entry> for Sudoers {
+    fn extend<I>(&mut self, iter: I)
+    where
+        I: IntoIterator<Item = Entry>,
+    {
+        for entry in iter {
+            match entry {
+                Entry::Defaults(defaults) => self.defaults.push(defaults),
+                Entry::UserAlias(name, members) => {
+                    self.user_aliases.insert(name, members);
+                }
+                Entry::RunasAlias(name, members) => {
+                    self.runas_aliases.insert(name, members);
+                }
+                Entry::HostAlias(name, members) => {
+                    self.host_aliases.insert(name, members);
+                }
+                Entry::CmndAlias(name, members) => {
+                    self.cmnd_aliases.insert(name, members);
+                }
+                Entry::Rule(rule) => self.rules.push(rule),
+                Entry::Include(_) | Entry::IncludeDir(_) => {}
+            }
+        }
+    }
+}
+
+impl FromIterator<Entry> for Sudoers {
+    fn from_iter<I>(iter: I) -> Self
+    where
+        I: IntoIterator<Item = Entry>,
+    {
+        let mut sudoers = Sudoers::default();
+        sudoers.extend(iter);
+
+        sudoers
+    }
+}
+
+fn is_alias(s: &str) -> bool {
+    s.starts_with(|c: char| c.is_ascii_uppercase())
+        && s.chars()
+            .all(|c| c.is_ascii_uppercase() || c.is_ascii_digit() || c == '_')
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Token {
+    Word(String),
+    Comma,
+    Colon,
+    Equals,
+    Open,
+    Close,
+    Bang,
+}
+
+fn tokenize(line: &str) -> Vec<Token> {
+    let mut tokens = Vec::new();
+    let mut chars = line.char_indices().peekable();
+
+    while let Some(&(i, c)) = chars.peek() {
+        let token = match c {
+            c if c.is_whitespace() => {
+                chars.next();
+                continue;
+            }
+            ',' => Token::Comma,
+            ':' => Token::Colon,
+            '=' => Token::Equals,
+            '(' => Token::Open,
+            ')' => Token::Close,
+            '!' => Token::Bang,
+            // A comment, unless it is a uid like `#0`.
+            '#' if !line[i + 1..].starts_with(|c: char| c.is_ascii_digit()) => break,
+            _ => {
+                let mut word = String::new();
+
+                while let Some(&(_, c)) = chars.peek() {
+                    let separator = matches!(c, ',' | ':' | '=' | '(' | ')');
+
+                    // `%:group` names a non-Unix group.
+                    if c.is_whitespace() || (separator && !(c == ':' && word == "%")) {
+                        break;
+                    }
+
+                    chars.next();
+                    if c == '\\' {
+                        word.extend(chars.next().map(|(_, c)| c));
+                    } else {
+                        word.push(c);
+                    }
+                }
+
+                tokens.push(Token::Word(word));
+                continue;
+            }
+        };
+
+        chars.next();
+        tokens.push(token);
+    }
+
+    tokens
+}
+
+struct Parser {
+    tokens: Vec<Token>,
+    pos: usize,
+}
+
+impl Parser {
+    fn peek(&self) -> Option<&Token> {
+        self.tokens.get(self.pos)
+    }
+
+    fn peek_word(&self, offset: usize) -> Option<&str> {
+        match self.tokens.get(self.pos + offset) {
+            Some(Token::Word(word)) => Some(word),
+            _ => None,
+        }
+    }
+
+    fn eat(&mut self, token: &Token) -> bool {
+        let matches = self.peek() == Some(token);
+        if matches {
+            self.pos += 1;
+        }
+
+        matches
+    }
+
+    fn word(&mut self) -> Option<String> {
+        let word = self.peek_word(0)?.to_owned();
+        self.pos += 1;
+
+        Some(word)
+    }
+
+    fn negated(&mut self) -> bool {
+        let mut negated = false;
+        while self.eat(&Token::Bang) {
+            negated = !negated;
+        }
+
+        negated
+    }
+
+    fn names(&mut self) -> Option<Vec<Member<Name>>> {
+        let mut members = Vec::new();
+
+        loop {
+            let negated = self.negated();
+            members.push(Member {
+                negated,
+                value: Name::from(self.word()?.as_str()),
+            });
+
+            if !self.eat(&Token::Comma) {
+                return Some(members);
+            }
+        }
+    }
+
+    fn command(&mut self) -> Option<Member<Command>> {
+        let negated = self.negated();
+        let path = self.word()?;
+
+        let mut args = Vec::new();
+        while let Some(arg) = self.peek_word(0) {
+            args.push(arg.to_owned());
+            self.pos += 1;
+        }
+
+        let value = if path == "ALL" {
+            Command::All
+        } else if is_alias(&path) && args.is_empty() {
+            Command::Alias(path)
+        } else {
+            Command::Path {
+                path,
+                args: (!args.is_empty()).then(|| args.join(" ")),
+            }
+        };
+
+        Some(Member { negated, value })
+    }
+
+    fn commands(&mut self) -> Option<Vec<Member<Command>>> {
+        let mut members = vec![self.command()?];
+        while self.eat(&Token::Comma) {
+            members.push(self.command()?);
+        }
+
+        Some(members)
+    }
+
+    fn runas(&mut self) -> Option<Runas> {
+        let mut runas = Runas::default();
+
+        if !matches!(self.peek(), Some(Token::Colon | Token::Close)) {
+            runas.users = self.names()?;
+        }
+        if self.eat(&Token::Colon) && self.peek() != Some(&Token::Close) {
+            runas.groups = self.names()?;
+        }
+
+        self.eat(&Token::Close).then_some(runas)
+    }
+
+    fn rule(&mut self) -> Option<Rule> {
+        let mut rule = Rule {
+            users: self.names()?,
+            privileges: Vec::new(),
+        };
+
+        loop {
+            let hosts = self.names()?;
+            if !self.eat(&Token::Equals) {
+                return None;
+            }
+
+            let mut commands = Vec::new();
+            let mut runas = None;
+            let mut tags = Tags::default();
+
+            loop {
+                if self.eat(&Token::Open) {
+                    runas = Some(self.runas()?);
+                }
+
+                while let Some(word) = self.peek_word(0) {
+                    let next = self.tokens.get(self.pos + 1);
+
+                    if TAGS.contains(&word) && next == Some(&Token::Colon) {
+                        match word {
+                            "NOPASSWD" => tags.authenticate = Some(false),
+                            "PASSWD" => tags.authenticate = Some(true),
+                            "NOEXEC" => tags.noexec = Some(true),
+                            "EXEC" => tags.noexec = Some(false),
+                            "SETENV" => tags.setenv = Some(true),
+                            "NOSETENV" => tags.setenv = Some(false),
+                            _ => {}
+                        }
+                        self.pos += 2;
+                    } else if OPTIONS.contains(&word) && next == Some(&Token::Equals) {
+                        self.pos += 2;
+                        self.word()?;
+                    } else {
+                        break;
+                    }
+                }
+
+                commands.push(CommandSpec {
+                    runas: runas.clone(),
+                    tags,
+                    command: self.command()?,
+                });
+
+                if !self.eat(&Token::Comma) {
+                    break;
+                }
+            }
+
+            rule.privileges.push(Privilege { hosts, commands });
+
+            if !self.eat(&Token::Colon) {
+                return Some(rule);
+            }
+        }
+    }
+
+    fn aliases(&mut self, kind: &str) -> Option<Vec<Entry>> {
+        let mut entries = Vec::new();
+
+        loop {
+            let name = self.word().filter(|name| is_alias(name))?;
+            if !self.eat(&Token::Equals) {
+                return None;
+            }
+
+            entries.push(match kind {
+                "User_Alias" => Entry::UserAlias(name, self.names()?),
+                "Runas_Alias" => Entry::RunasAlias(name, self.names()?),
+                "Host_Alias" => Entry::HostAlias(name, self.names()?),
+                _ => Entry::CmndAlias(name, self.commands()?),
+            });
+
+            if !self.eat(&Token::Colon) {
+                return Some(entries);
+            }
+        }
+    }
+
+    fn finished(&self) -> bool {
+        self.pos == self.tokens.len()
+    }
+}
+
+fn parse_line(line: &str) -> Result<Vec<Entry>, ParseSudoersError> {
+    let invalid = || ParseSudoersError::InvalidEntry(line.to_owned());
+
+    let (keyword, rest) = line
+        .split_once(char::is_whitespace)
+        .map(|(keyword, rest)| (keyword, rest.trim()))
+        .unwrap_or((line, ""));
+
+    match keyword {
+        "@include" | "#include" if !rest.is_empty() => {
+            return Ok(vec![Entry::Include(rest.to_owned())])
+        }
+        "@includedir" | "#includedir" if !rest.is_empty() => {
+            return Ok(vec![Entry::IncludeDir(rest.to_owned())])
+        }
+        keyword if keyword.starts_with("Defaults") => {
+            return Ok(vec![Entry::Defaults(line.to_owned())])
+        }
+        _ => {}
+    }
+
+    let mut parser = Parser {
+        tokens: tokenize(line),
+        pos: 0,
+    };
+
+    if parser.finished() {
+        return Ok(Vec::new());
+    }
+
+    let entries = match keyword {
+        "User_Alias" | "Runas_Alias" | "Host_Alias" | "Cmnd_Alias" | "Cmd_Alias" => {
+            parser.pos += 1;
+            parser.aliases(keyword)
+        }
+        _ => parser.rule().map(|rule| vec![Entry::Rule(rule)]),
+    };
+
+    entries.filter(|_| parser.finished()).ok_or_else(invalid)
+}
+
+#[derive(Debug)]
+pub enum ParseSudoersError {
+    InvalidEntry(String),
+    IOError(io::Error),
+}
+
+impl error::Error for ParseSudoersError {}
+
+impl fmt::Display for ParseSudoersError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::InvalidEntry(entry) => write!(f, "invalid entry: {}", entry),
+            Self::IOError(error) => write!(f, "{}", error),
+        }
+    }
+}
+
+impl From<io::Error> for ParseSudoersError {
+    fn from(value: io::Error) -> Self {
+        ParseSudoersError::IOError(value)
+    }
+}
+
+///
+/// Parses the entries of a sudoers file, includes are returned as entries without being followed.
+/// Lines ending with a backslash are joined with the next one.
+///
+/// ```no_run
+/// let entries = unixism::sudoers::parse(std::fs::File::open("/etc/sudoers").unwrap()).unwrap();
+///
+/// for entry in entries {
+///     println!("{:?}", entry);
+/// }
+/// ```
+pub fn parse<R>(reader: R) -> Result<impl Iterator<Item = Entry>, ParseSudoersError>
+where
+    R: io::Read,
+{
+    let mut entries = Vec::new();
+    let mut pending = String::new();
+
+    for line in BufReader::new(reader).lines() {
+        let line = line?;
+
+        if let Some(line) = line.strip_suffix('\\') {
+            pending.push_str(line);
+            pending.push(' ');
+            continue;
+        }
+
+        pending.push_str(&line);
+        let line = std::mem::take(&mut pending);
+        let line = line.trim();
+
+        if line.is_empty() {
+            continue;
+        }
+
+        entries.extend(parse_line(line)?);
+    }
+
+    Ok(entries.into_iter())
+}
+
+///
+/// Same as parse, but reads the file at `path` and follows its includes: relative paths are resolved
+/// against the directory of the including file, and `@includedir` reads the files of the directory
+/// whose names neither end with `~` nor contain a `.`, in lexical order.
+///
+/// ```no_run
+/// let sudoers = unixism::sudoers::load_at("/mnt/etc/sudoers").unwrap();
+/// ```
+pub fn load_at<P>(path: P) -> Result<Sudoers, ParseSudoersError>
+where
+    P: AsRef<Path>,
+{
+    let mut sudoers = Sudoers::default();
+    load_nested(path.as_ref(), &mut Vec::new(), &mut sudoers)?;
+
+    Ok(sudoers)
+}
+
+fn load_nested(
+    path: &Path,
+    stack: &mut Vec<PathBuf>,
+    sudoers: &mut Sudoers,
+) -> Result<(), ParseSudoersError> {
+    let path = fs::canonicalize(path)?;
+
+    // Guards against files including themselves.
+    if stack.contains(&path) {
+        return Ok(());
+    }
+
+    let base = path.parent().unwrap_or(Path::new("/")).to_owned();
+    let entries = parse(fs::File::open(&path)?)?;
+
+    stack.push(path);
+
+    for entry in entries {
+        match entry {
+            Entry::Include(file) => load_nested(&base.join(file), stack, sudoers)?,
+            Entry::IncludeDir(dir) => {
+                let files = match fs::read_dir(base.join(dir)) {
+                    Ok(files) => files,
+                    Err(e) if e.kind() == io::ErrorKind::NotFound => continue,
+                    Err(e) => return Err(e.into()),
+                };
+
+                let mut files = files
+                    .filter_map(Result::ok)
+                    .filter(|file| {
+                        file.file_name()
+                            .to_str()
+                            .is_some_and(|name| !name.ends_with('~') && !name.contains('.'))
+                    })
+                    .map(|file| file.path())
+                    .collect::<Vec<_>>();
+                files.sort();
+
+                for file in files {
+                    load_nested(&file, stack, sudoers)?;
+                }
+            }
+            entry => sudoers.extend([entry]),
+        }
+    }
+
+    stack.pop();
+
+    Ok(())
+}
+
+///
+/// Same as load_at, but reads the `/etc/sudoers` as default.
+///
+/// ```no_run
+/// let sudoers = unixism::sudoers::load().unwrap();
+/// ```
+pub fn load() -> Result<Sudoers, ParseSudoersError> {
+    load_at("/etc/sudoers")
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Cursor;
+
+    use super::*;
+    use crate::tempdir::TempDir;
+
+    #[test]
+    fn it_parse() {
+        let entries = parse(Cursor::new(
+            r#"
+# /etc/sudoers
+Defaults	env_reset
+Defaults:OPERATORS !lecture
+
+User_Alias OPERATORS = alice, %ops : AUDITORS = +audit
+Cmnd_Alias SERVICES = /usr/bin/systemctl restart *, /usr/bin/systemctl status *
+Host_Alias WEB = web-01, web-02
+
+root	ALL=(ALL:ALL) ALL
+%sudo	ALL=(ALL:ALL) ALL
+OPERATORS WEB = (root) NOPASSWD: SERVICES, PASSWD: /usr/bin/journalctl "" : \
+    ALL = (:adm) /usr/bin/less /var/log/*, !/usr/bin/su
+#0 ALL = ALL
+
+@includedir /etc/sudoers.d
+        "#,
+        ))
+        .unwrap()
+        .collect::<Vec<_>>();
+
+        assert_eq!(11, entries.len());
+        assert_eq!(
+            Entry::Defaults("Defaults:OPERATORS !lecture".to_owned()),
+            entries[1]
+        );
+        assert_eq!(
+            Entry::UserAlias(
+                "AUDITORS".to_owned(),
+                vec![Member {
+                    negated: false,
+                    value: Name::Netgroup("audit".to_owned())
+                }]
+            ),
+            entries[3]
+        );
+        assert_eq!(
+            Entry::CmndAlias(
+                "SERVICES".to_owned(),
+                vec![
+                    Member {
+                        negated: false,
+                        value: Command::Path {
+                            path: "/usr/bin/systemctl".to_owned(),
+                            args: Some("restart *".to_owned())
+                        }
+                    },
+                    Member {
+                        negated: false,
+                        value: Command::Path {
+                            path: "/usr/bin/systemctl".to_owned(),
+                            args: Some("status *".to_owned())
+                        }
+                    },
+                ]
+            ),
+            entries[4]
+        );
+
+        let Entry::Rule(rule) = &entries[8] else {
+            panic!("not a rule: {:?}", entries[8]);
+        };
+
+        assert_eq!(Name::Alias("OPERATORS".to_owned()), rule.users[0].value);
+        assert_eq!(2, rule.privileges.len());
+
+        let commands = &rule.privileges[0].commands;
+        assert_eq!(
+            Command::Alias("SERVICES".to_owned()),
+            commands[0].command.value
+        );
+        assert_eq!(Some(false), commands[0].tags.authenticate);
+        assert_eq!(Some(true), commands[1].tags.authenticate);
+        assert_eq!(
+            Some(vec![Member {
+                negated: false,
+                value: Name::Literal("root".to_owned())
+            }]),
+            commands[1].runas.as_ref().map(|runas| runas.users.clone())
+        );
+        assert_eq!(
+            Command::Path {
+                path: "/usr/bin/journalctl".to_owned(),
+                args: Some("\"\"".to_owned())
+            },
+            commands[1].command.value
+        );
+
+        let commands = &rule.privileges[1].commands;
+        assert_eq!(Name::All, rule.privileges[1].hosts[0].value);
+        assert!(commands[1].command.negated);
+        assert!(commands[1].runas.as_ref().unwrap().users.is_empty());
+        assert_eq!(
+            Name::Literal("adm".to_owned()),
+            commands[1].runas.as_ref().unwrap().groups[0].value
+        );
+
+        let Entry::Rule(rule) = &entries[9] else {
+            panic!("not a rule: {:?}", entries[9]);
+        };
+        assert_eq!(Name::Literal("#0".to_owned()), rule.users[0].value);
+
+        assert_eq!(Entry::IncludeDir("/etc/sudoers.d".to_owned()), entries[10]);
+
+        assert!(parse(Cursor::new("alice ALL")).is_err());
+        assert!(parse(Cursor::new("User_Alias lower = alice")).is_err());
+        assert!(parse(Cursor::new("alice ALL = (root ALL")).is_err());
+    }
+
+    #[test]
+    fn it_load_at() {
+        let root = TempDir::new("sudoers");
+        let dir = root.join("sudoers.d");
+        fs::create_dir_all(&dir).unwrap();
+
+        fs::write(
+            root.join("sudoers"),
+            "root ALL=(ALL) ALL\n@includedir sudoers.d\n#include sudoers\n",
+        )
+        .unwrap();
+        fs::write(
+            dir.join("10-deploy"),
+            "deploy ALL=(root) NOPASSWD: /usr/bin/systemctl\n",
+        )
+        .unwrap();
+        fs::write(dir.join("20-backup~"), "backup ALL=ALL\n").unwrap();
+        fs::write(dir.join("README.md"), "not sudoers\n").unwrap();
+
+        let sudoers = load_at(root.join("sudoers")).unwrap();
+        assert_eq!(2, sudoers.rules.len());
+        assert_eq!(
+            Name::Literal("deploy".to_owned()),
+            sudoers.rules[1].users[0].value
+        );
+    }
+}
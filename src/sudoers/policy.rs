@@ -0,0 +1,345 @@
+use std::{collections::HashMap, path::Path};
+
+use super::{Command, Member, Name, Sudoers};
+
+/// How deep aliases may refer to other aliases, which guards against aliases including themselves.
+const MAX_DEPTH: usize = 64;
+
+/// The user asking to run a command.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct User {
+    pub name: String,
+    /// Names of the groups the user is a member of, including the primary one.
+    pub groups: Vec<String>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Decision {
+    /// The command is permitted, `authenticate` is false when the rule is tagged `NOPASSWD`.
+    Allow { authenticate: bool },
+    /// The last matching rule negates the command.
+    Deny,
+    /// No rule matches the user, host, runas user and command, which sudo refuses too.
+    NoMatch,
+}
+
+impl Decision {
+    pub fn is_allowed(&self) -> bool {
+        matches!(self, Decision::Allow { .. })
+    }
+}
+
+impl Sudoers {
+    ///
+    /// Evaluates the rules the way sudo does: aliases, groups, netgroups and wildcards are expanded,
+    /// and the last rule matching the user, host, runas user and command decides.
+    /// Commands without a runas list may only be run as root, `%group` runas members never match
+    /// as the groups of the target user are unknown, and hosts are compared by name only.
+    ///
+    /// ```no_run
+    /// use unixism::sudoers::{self, User};
+    ///
+    /// let sudoers = sudoers::load().unwrap();
+    /// let user = User {
+    ///     name: "alice".to_owned(),
+    ///     groups: vec!["alice".to_owned(), "ops".to_owned()],
+    /// };
+    ///
+    /// println!("{:?}", sudoers.can_run(&user, "web-01", "root", "/usr/bin/systemctl restart nginx"));
+    /// ```
+    pub fn can_run(&self, user: &User, host: &str, runas: &str, command: &str) -> Decision {
+        let mut decision = Decision::NoMatch;
+
+        let user_matches = |name: &Name| match name {
+            Name::All => true,
+            Name::Literal(name) => *name == user.name,
+            Name::Group(group) => user.groups.contains(group),
+            Name::Netgroup(netgroup) => self
+                .netgroups
+                .contains(netgroup, None, Some(&user.name), None)
+                .unwrap_or(false),
+            Name::Alias(_) => false,
+        };
+        let host_matches = |name: &Name| match name {
+            Name::All => true,
+            Name::Literal(name) => {
+                name.eq_ignore_ascii_case(host)
+                    || host
+                        .split_once('.')
+                        .is_some_and(|(short, _)| name.eq_ignore_ascii_case(short))
+            }
+            Name::Netgroup(netgroup) => self
+                .netgroups
+                .contains(netgroup, Some(host), None, None)
+                .unwrap_or(false),
+            Name::Group(_) | Name::Alias(_) => false,
+        };
+        let runas_matches = |name: &Name| match name {
+            Name::All => true,
+            Name::Literal(name) => name == runas,
+            Name::Netgroup(netgroup) => self
+                .netgroups
+                .contains(netgroup, None, Some(runas), None)
+                .unwrap_or(false),
+            Name::Group(_) | Name::Alias(_) => false,
+        };
+
+        for rule in &self.rules {
+            if names(&rule.users, &self.user_aliases, &user_matches, 0) != Some(true) {
+                continue;
+            }
+
+            for privilege in &rule.privileges {
+                if names(&privilege.hosts, &self.host_aliases, &host_matches, 0) != Some(true) {
+                    continue;
+                }
+
+                for spec in &privilege.commands {
+                    let runas_allowed = match &spec.runas {
+                        None => runas == "root",
+                        Some(spec) if spec.users.is_empty() => runas == user.name,
+                        Some(spec) => {
+                            names(&spec.users, &self.runas_aliases, &runas_matches, 0) == Some(true)
+                        }
+                    };
+
+                    if !runas_allowed {
+                        continue;
+                    }
+
+                    match self.commands(std::slice::from_ref(&spec.command), command, 0) {
+                        Some(true) => {
+                            decision = Decision::Allow {
+                                authenticate: spec.tags.authenticate.unwrap_or(true),
+                            }
+                        }
+                        Some(false) => decision = Decision::Deny,
+                        None => {}
+                    }
+                }
+            }
+        }
+
+        decision
+    }
+
+    fn commands(&self, members: &[Member<Command>], command: &str, depth: usize) -> Option<bool> {
+        let mut result = None;
+
+        for member in members {
+            let matched = match &member.value {
+                Command::All => Some(true),
+                Command::Alias(alias) => match self.cmnd_aliases.get(alias) {
+                    Some(members) if depth < MAX_DEPTH => {
+                        self.commands(members, command, depth + 1)
+                    }
+                    _ => None,
+                },
+                Command::Path { path, args } => {
+                    command_matches(path, args.as_deref(), command).then_some(true)
+                }
+            };
+
+            if let Some(matched) = matched {
+                result = Some(matched != member.negated);
+            }
+        }
+
+        result
+    }
+}
+
+/// Matches the list, `None` when no member matches and otherwise whether the last matching one allows.
+fn names<F>(
+    members: &[Member<Name>],
+    aliases: &HashMap<String, Vec<Member<Name>>>,
+    matches: &F,
+    depth: usize,
+) -> Option<bool>
+where
+    F: Fn(&Name) -> bool,
+{
+    let mut result = None;
+
+    for member in members {
+        let matched = match &member.value {
+            Name::Alias(alias) => match aliases.get(alias) {
+                Some(members) if depth < MAX_DEPTH => names(members, aliases, matches, depth + 1),
+                _ => None,
+            },
+            name => matches(name).then_some(true),
+        };
+
+        if let Some(matched) = matched {
+            result = Some(matched != member.negated);
+        }
+    }
+
+    result
+}
+
+fn command_matches(path: &str, args: Option<&str>, command: &str) -> bool {
+    let mut words = command.split_whitespace();
+    let Some(command_path) = words.next() else {
+        return false;
+    };
+    let command_args = words.collect::<Vec<_>>().join(" ");
+
+    let path_matches = match path.strip_suffix('/') {
+        Some(dir) => Path::new(command_path).parent() == Some(Path::new(dir)),
+        None => glob(path, command_path, true),
+    };
+
+    path_matches
+        && match args {
+            None => true,
+            Some("\"\"") => command_args.is_empty(),
+            Some(args) => glob(args, &command_args, false),
+        }
+}
+
+/// Matches `*` and `?`, which don't match a `/` in a path like fnmatch's `FNM_PATHNAME`.
+fn glob(pattern: &str, value: &str, path: bool) -> bool {
+    fn go(pattern: &[u8], value: &[u8], path: bool) -> bool {
+        match pattern.first() {
+            None => value.is_empty(),
+            Some(b'*') => (0..=value.len())
+                .take_while(|i| !path || *i == 0 || value[i - 1] != b'/')
+                .any(|i| go(&pattern[1..], &value[i..], path)),
+            Some(b'?') => {
+                value.first().is_some_and(|c| !path || *c != b'/')
+                    && go(&pattern[1..], &value[1..], path)
+            }
+            Some(c) => value.first() == Some(c) && go(&pattern[1..], &value[1..], path),
+        }
+    }
+
+    go(pattern.as_bytes(), value.as_bytes(), path)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Cursor;
+
+    use crate::netgroup;
+
+    use super::{super::parse, *};
+
+    fn user(name: &str, groups: &[&str]) -> User {
+        User {
+            name: name.to_owned(),
+            groups: groups.iter().map(|group| group.to_string()).collect(),
+        }
+    }
+
+    #[test]
+    fn it_can_run() {
+        let mut sudoers = parse(Cursor::new(
+            r#"
+User_Alias OPERATORS = alice, %ops, !mallory
+Runas_Alias SERVICE = www-data, postgres
+Host_Alias WEB = web-01, web-02
+Cmnd_Alias SERVICES = /usr/bin/systemctl restart *, /usr/bin/systemctl status *
+Cmnd_Alias SHELLS = /bin/sh, /bin/bash
+
+root	ALL=(ALL:ALL) ALL
+%sudo	ALL=(ALL:ALL) ALL, !SHELLS
+OPERATORS WEB = NOPASSWD: SERVICES, (SERVICE) /usr/bin/psql, /usr/bin/id ""
++auditors ALL = (ALL) /usr/bin/less /var/log/*, /usr/local/bin/
+        "#,
+        ))
+        .unwrap()
+        .collect::<Sudoers>();
+
+        sudoers.netgroups = netgroup::parse(Cursor::new("auditors (-,carol,)\n"))
+            .unwrap()
+            .collect();
+
+        let alice = user("alice", &["alice"]);
+        let bob = user("bob", &["bob", "ops"]);
+        let admin = user("admin", &["admin", "sudo"]);
+
+        assert_eq!(
+            Decision::Allow {
+                authenticate: false
+            },
+            sudoers.can_run(&alice, "web-01", "root", "/usr/bin/systemctl restart nginx")
+        );
+        assert!(sudoers
+            .can_run(
+                &bob,
+                "web-02.example.com",
+                "root",
+                "/usr/bin/systemctl status sshd"
+            )
+            .is_allowed());
+        assert_eq!(
+            Decision::NoMatch,
+            sudoers.can_run(&alice, "db-01", "root", "/usr/bin/systemctl restart nginx")
+        );
+        assert_eq!(
+            Decision::NoMatch,
+            sudoers.can_run(&alice, "web-01", "root", "/usr/bin/systemctl stop nginx")
+        );
+        assert_eq!(
+            Decision::NoMatch,
+            sudoers.can_run(
+                &user("mallory", &["ops"]),
+                "web-01",
+                "root",
+                "/usr/bin/systemctl restart nginx"
+            )
+        );
+
+        assert_eq!(
+            Decision::Allow {
+                authenticate: false
+            },
+            sudoers.can_run(&alice, "web-01", "postgres", "/usr/bin/psql")
+        );
+        assert_eq!(
+            Decision::NoMatch,
+            sudoers.can_run(&alice, "web-01", "root", "/usr/bin/psql")
+        );
+        assert!(sudoers
+            .can_run(&alice, "web-01", "www-data", "/usr/bin/id")
+            .is_allowed());
+        assert_eq!(
+            Decision::NoMatch,
+            sudoers.can_run(&alice, "web-01", "www-data", "/usr/bin/id -u")
+        );
+
+        assert_eq!(
+            Decision::Allow { authenticate: true },
+            sudoers.can_run(&admin, "db-01", "postgres", "/usr/bin/vim /etc/hosts")
+        );
+        assert_eq!(
+            Decision::Deny,
+            sudoers.can_run(&admin, "db-01", "root", "/bin/bash")
+        );
+        assert!(sudoers
+            .can_run(&user("root", &["root"]), "db-01", "root", "/bin/bash")
+            .is_allowed());
+
+        let carol = user("carol", &["carol"]);
+        assert!(sudoers
+            .can_run(&carol, "db-01", "root", "/usr/bin/less /var/log/syslog")
+            .is_allowed());
+        assert!(sudoers
+            .can_run(&carol, "db-01", "root", "/usr/local/bin/report")
+            .is_allowed());
+        assert_eq!(
+            Decision::NoMatch,
+            sudoers.can_run(&carol, "db-01", "root", "/usr/local/bin/sub/report")
+        );
+    }
+
+    #[test]
+    fn it_glob() {
+        assert!(glob("/usr/bin/*", "/usr/bin/id", true));
+        assert!(!glob("/usr/bin/*", "/usr/bin/sub/id", true));
+        assert!(glob("/var/log/*", "/var/log/nginx/access.log", false));
+        assert!(glob("/usr/bin/?d", "/usr/bin/id", true));
+        assert!(!glob("/usr/bin/i?", "/usr/bin/i/", true));
+    }
+}
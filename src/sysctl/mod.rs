@@ -0,0 +1,455 @@
+use std::{
+    collections::BTreeMap,
+    error, fmt, fs,
+    io::{self, BufRead, BufReader},
+    path::{Path, PathBuf},
+    str::FromStr,
+};
+
+/// Directories `sysctl --system` reads, the most important first.
+pub const CONFIG_DIRS: [&str; 5] = [
+    "/etc/sysctl.d",
+    "/run/sysctl.d",
+    "/usr/local/lib/sysctl.d",
+    "/usr/lib/sysctl.d",
+    "/lib/sysctl.d",
+];
+
+/// Read after the directories, overriding them.
+pub const CONFIG_FILE: &str = "/etc/sysctl.conf";
+
+/// Where the kernel exposes the parameters.
+pub const PROC_SYS: &str = "/proc/sys";
+
+/// Normalizes a key to the dotted form, `net/ipv4/conf/eth0.100/rp_filter` becoming `net.ipv4.conf.eth0/100.rp_filter`.
+pub fn normalize(key: &str) -> String {
+    match key.find(['.', '/']) {
+        Some(i) if key[i..].starts_with('/') => swap_separators(key),
+        _ => key.to_owned(),
+    }
+}
+
+fn swap_separators(key: &str) -> String {
+    key.chars()
+        .map(|c| match c {
+            '.' => '/',
+            '/' => '.',
+            c => c,
+        })
+        .collect()
+}
+
+///
+/// Returns the `/proc/sys` relative path of a key. Like sysctl(8), keys which are empty, absolute,
+/// or have empty, `.` or `..` components are rejected with `None`, so they can't name files outside.
+pub fn key_path(key: &str) -> Option<PathBuf> {
+    let path = swap_separators(&normalize(key));
+
+    if path.split('/').any(|part| matches!(part, "" | "." | "..")) {
+        return None;
+    }
+
+    Some(PathBuf::from(path))
+}
+
+fn checked_path(root: &Path, key: &str) -> io::Result<PathBuf> {
+    key_path(key)
+        .map(|path| root.join(path))
+        .ok_or_else(|| io::Error::new(io::ErrorKind::InvalidInput, format!("invalid key: {}", key)))
+}
+
+/// An assignment of a sysctl file.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Entry {
+    /// The key in the dotted form, possibly with `*` globs.
+    pub key: String,
+    pub value: String,
+    /// Set by a leading `-`, failures to apply the value are ignored.
+    pub ignore_failure: bool,
+}
+
+impl FromStr for Entry {
+    type Err = ParseSysctlError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (key, value) = s
+            .split_once('=')
+            .ok_or_else(|| ParseSysctlError::InvalidEntry(s.to_owned()))?;
+
+        let key = key.trim();
+        let (key, ignore_failure) = match key.strip_prefix('-') {
+            Some(key) => (key.trim_start(), true),
+            None => (key, false),
+        };
+
+        if key.contains(char::is_whitespace) || key_path(key).is_none() {
+            return Err(ParseSysctlError::InvalidEntry(s.to_owned()));
+        }
+
+        Ok(Entry {
+            key: normalize(key),
+            value: value.trim().to_owned(),
+            ignore_failure,
+        })
+    }
+}
+
+impl fmt::Display for Entry {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.ignore_failure {
+            write!(f, "-")?;
+        }
+
+        write!(f, "{} = {}", self.key, self.value)
+    }
+}
+
+/// A configured value differing from the running kernel.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Drift {
+    pub key: String,
+    pub configured: String,
+    /// `None` when the kernel doesn't have the parameter or it can't be read.
+    pub runtime: Option<String>,
+}
+
+///
+/// The assignments of all the sysctl files, in the order they are applied.
+///
+/// ```no_run
+/// let config = unixism::sysctl::load().unwrap();
+///
+/// println!("{:?}", config.get("net.ipv4.ip_forward"));
+/// ```
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct Config {
+    pub entries: Vec<Entry>,
+}
+
+impl Config {
+    /// Returns the value applied last for the key.
+    pub fn get(&self, key: &str) -> Option<&str> {
+        let key = normalize(key);
+
+        self.entries
+            .iter()
+            .rev()
+            .find(|entry| entry.key == key)
+            .map(|entry| entry.value.as_str())
+    }
+
+    /// The effective value of every key.
+    pub fn values(&self) -> BTreeMap<&str, &str> {
+        self.entries
+            .iter()
+            .map(|entry| (entry.key.as_str(), entry.value.as_str()))
+            .collect()
+    }
+
+    /// Same as drift, but with the kernel parameters under `root` instead of `/proc/sys`.
+    pub fn drift_at<P>(&self, root: P) -> Vec<Drift>
+    where
+        P: AsRef<Path>,
+    {
+        self.values()
+            .into_iter()
+            .filter(|(key, _)| !key.contains('*'))
+            .filter_map(|(key, configured)| {
+                let runtime = read_at(root.as_ref(), key).ok();
+
+                if runtime
+                    .as_deref()
+                    .is_some_and(|runtime| same(runtime, configured))
+                {
+                    return None;
+                }
+
+                Some(Drift {
+                    key: key.to_owned(),
+                    configured: configured.to_owned(),
+                    runtime,
+                })
+            })
+            .collect()
+    }
+
+    ///
+    /// Compares the effective configured values with the running kernel, ignoring whitespace differences
+    /// like the tabs the kernel separates multiple values with. Keys with globs are skipped.
+    ///
+    /// ```no_run
+    /// for drift in unixism::sysctl::load().unwrap().drift() {
+    ///     println!("{}: configured {}, running {:?}", drift.key, drift.configured, drift.runtime);
+    /// }
+    /// ```
+    pub fn drift(&self) -> Vec<Drift> {
+        self.drift_at(PROC_SYS)
+    }
+}
+
+impl FromIterator<Entry> for Config {
+    fn from_iter<I>(iter: I) -> Self
+    where
+        I: IntoIterator<Item = Entry>,
+    {
+        Config {
+            entries: iter.into_iter().collect(),
+        }
+    }
+}
+
+fn same(runtime: &str, configured: &str) -> bool {
+    runtime.split_whitespace().eq(configured.split_whitespace())
+}
+
+#[derive(Debug)]
+pub enum ParseSysctlError {
+    InvalidEntry(String),
+    IOError(io::Error),
+}
+
+impl error::Error for ParseSysctlError {}
+
+impl fmt::Display for ParseSysctlError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::InvalidEntry(entry) => write!(f, "invalid entry: {}", entry),
+            Self::IOError(error) => write!(f, "{}", error),
+        }
+    }
+}
+
+impl From<io::Error> for ParseSysctlError {
+    fn from(value: io::Error) -> Self {
+        ParseSysctlError::IOError(value)
+    }
+}
+
+///
+/// ```no_run
+/// let entries = unixism::sysctl::parse(std::fs::File::open("/etc/sysctl.conf").unwrap()).unwrap();
+///
+/// for entry in entries {
+///     println!("{} = {}", entry.key, entry.value);
+/// }
+/// ```
+pub fn parse<R>(reader: R) -> Result<impl Iterator<Item = Entry>, ParseSysctlError>
+where
+    R: io::Read,
+{
+    let mut entries = Vec::new();
+
+    for line in BufReader::new(reader).lines() {
+        let line = line?;
+        let line = line.trim();
+
+        if line.is_empty() || line.starts_with('#') || line.starts_with(';') {
+            continue;
+        }
+
+        entries.push(line.parse::<Entry>()?);
+    }
+
+    Ok(entries.into_iter())
+}
+
+///
+/// Reads the `*.conf` files of `dirs` in file name order, then `file`, as `sysctl --system` does.
+/// A file in an earlier directory overrides the file with the same name in later ones. Missing files are skipped.
+///
+/// ```no_run
+/// let config = unixism::sysctl::load_at(&["/etc/sysctl.d", "/usr/lib/sysctl.d"], "/etc/sysctl.conf").unwrap();
+/// ```
+pub fn load_at<P, F>(dirs: &[P], file: F) -> Result<Config, ParseSysctlError>
+where
+    P: AsRef<Path>,
+    F: AsRef<Path>,
+{
+    let mut entries = Vec::new();
+
+    for path in crate::dropin::files(dirs, ".conf")? {
+        entries.extend(parse(fs::File::open(path)?)?);
+    }
+
+    match fs::File::open(file) {
+        Ok(file) => entries.extend(parse(file)?),
+        Err(error) if error.kind() == io::ErrorKind::NotFound => {}
+        Err(error) => return Err(error.into()),
+    }
+
+    Ok(Config { entries })
+}
+
+///
+/// Same as load_at, but reads the standard sysctl directories and `/etc/sysctl.conf`.
+///
+/// ```no_run
+/// let config = unixism::sysctl::load().unwrap();
+/// ```
+pub fn load() -> Result<Config, ParseSysctlError> {
+    load_at(&CONFIG_DIRS, CONFIG_FILE)
+}
+
+/// Same as read, but with the kernel parameters under `root` instead of `/proc/sys`.
+pub fn read_at<P>(root: P, key: &str) -> io::Result<String>
+where
+    P: AsRef<Path>,
+{
+    Ok(fs::read_to_string(checked_path(root.as_ref(), key)?)?
+        .trim_end_matches('\n')
+        .to_owned())
+}
+
+///
+/// Reads the running value of the kernel parameter.
+///
+/// ```no_run
+/// println!("{}", unixism::sysctl::read("net.ipv4.ip_forward").unwrap());
+/// ```
+pub fn read(key: &str) -> io::Result<String> {
+    read_at(PROC_SYS, key)
+}
+
+/// Same as write, but with the kernel parameters under `root` instead of `/proc/sys`.
+pub fn write_at<P>(root: P, key: &str, value: &str) -> io::Result<()>
+where
+    P: AsRef<Path>,
+{
+    fs::write(checked_path(root.as_ref(), key)?, value)
+}
+
+///
+/// Sets the running value of the kernel parameter, which usually requires root.
+///
+/// ```no_run
+/// unixism::sysctl::write("net.ipv4.ip_forward", "1").unwrap();
+/// ```
+pub fn write(key: &str, value: &str) -> io::Result<()> {
+    write_at(PROC_SYS, key, value)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Cursor;
+
+    use super::*;
+    use crate::tempdir::TempDir;
+
+    #[test]
+    fn it_parse() {
+        let config = parse(Cursor::new(
+            r#"
+# Kernel sysctl configuration
+; also a comment
+net.ipv4.ip_forward = 1
+net/ipv4/conf/eth0.100/rp_filter=2
+-net.ipv6.conf.all.disable_ipv6 = 1
+net.ipv4.tcp_rmem = 4096 87380 6291456
+net.ipv4.ip_forward = 0
+net.ipv4.conf.*.accept_redirects = 0
+        "#,
+        ))
+        .unwrap()
+        .collect::<Config>();
+
+        assert_eq!(6, config.entries.len());
+        assert_eq!(
+            Entry {
+                key: "net.ipv4.conf.eth0/100.rp_filter".to_owned(),
+                value: "2".to_owned(),
+                ignore_failure: false,
+            },
+            config.entries[1]
+        );
+        assert!(config.entries[2].ignore_failure);
+        assert_eq!(
+            "-net.ipv6.conf.all.disable_ipv6 = 1",
+            config.entries[2].to_string()
+        );
+        assert_eq!(Some("0"), config.get("net/ipv4/ip_forward"));
+        assert_eq!(Some("2"), config.get("net.ipv4.conf.eth0/100.rp_filter"));
+        assert_eq!(5, config.values().len());
+        assert_eq!(
+            Some(PathBuf::from("net/ipv4/conf/eth0.100/rp_filter")),
+            key_path("net.ipv4.conf.eth0/100.rp_filter")
+        );
+
+        assert!(parse(Cursor::new("net.ipv4.ip_forward")).is_err());
+        assert!(parse(Cursor::new(" = 1")).is_err());
+        assert!(parse(Cursor::new("/etc/shadow = x")).is_err());
+        assert!(parse(Cursor::new("net..ipv4 = 1")).is_err());
+    }
+
+    #[test]
+    fn it_reject_escaping_keys() {
+        for key in [
+            "",
+            "/etc/shadow",
+            "kernel/../../../etc/x",
+            "kernel/./hostname",
+            "net..ipv4.ip_forward",
+            "net.ipv4.",
+            ".net.ipv4",
+        ] {
+            assert_eq!(None, key_path(key), "{}", key);
+            assert_eq!(
+                io::ErrorKind::InvalidInput,
+                read_at("/proc/sys", key).unwrap_err().kind()
+            );
+            assert_eq!(
+                io::ErrorKind::InvalidInput,
+                write_at("/proc/sys", key, "1").unwrap_err().kind()
+            );
+        }
+    }
+
+    #[test]
+    fn it_load_at() {
+        let root = TempDir::new("sysctl");
+        let (etc, usr, proc) = (root.join("etc"), root.join("usr"), root.join("proc"));
+        fs::create_dir_all(&etc).unwrap();
+        fs::create_dir_all(&usr).unwrap();
+        fs::create_dir_all(proc.join("net/ipv4")).unwrap();
+        fs::create_dir_all(proc.join("vm")).unwrap();
+
+        fs::write(
+            usr.join("50-default.conf"),
+            "net.ipv4.ip_forward = 0\nvm.swappiness = 60\n",
+        )
+        .unwrap();
+        fs::write(
+            usr.join("60-tcp.conf"),
+            "net.ipv4.tcp_rmem = 4096 87380 6291456\n",
+        )
+        .unwrap();
+        fs::write(etc.join("50-default.conf"), "net.ipv4.ip_forward = 1\n").unwrap();
+        fs::write(root.join("sysctl.conf"), "kernel.missing = 1\n").unwrap();
+
+        let config = load_at(&[&etc, &usr], root.join("sysctl.conf")).unwrap();
+        assert_eq!(3, config.entries.len());
+        assert_eq!(None, config.get("vm.swappiness"));
+
+        fs::write(proc.join("net/ipv4/ip_forward"), "0\n").unwrap();
+        fs::write(proc.join("net/ipv4/tcp_rmem"), "4096\t87380\t6291456\n").unwrap();
+
+        assert_eq!(
+            vec![
+                Drift {
+                    key: "kernel.missing".to_owned(),
+                    configured: "1".to_owned(),
+                    runtime: None,
+                },
+                Drift {
+                    key: "net.ipv4.ip_forward".to_owned(),
+                    configured: "1".to_owned(),
+                    runtime: Some("0".to_owned()),
+                },
+            ],
+            config.drift_at(&proc)
+        );
+
+        write_at(&proc, "net.ipv4.ip_forward", "1").unwrap();
+        assert_eq!("1", read_at(&proc, "net/ipv4/ip_forward").unwrap());
+        assert_eq!(1, config.drift_at(&proc).len());
+    }
+}
@@ -0,0 +1,28 @@
+use std::{error, fmt, io, path::PathBuf};
+
+pub mod net;
+
+#[derive(Debug)]
+pub enum SysfsError {
+    InvalidAttribute { path: PathBuf, value: String },
+    IOError(io::Error),
+}
+
+impl error::Error for SysfsError {}
+
+impl fmt::Display for SysfsError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::InvalidAttribute { path, value } => {
+                write!(f, "invalid value of {}: {}", path.display(), value)
+            }
+            Self::IOError(error) => write!(f, "{}", error),
+        }
+    }
+}
+
+impl From<io::Error> for SysfsError {
+    fn from(value: io::Error) -> Self {
+        SysfsError::IOError(value)
+    }
+}
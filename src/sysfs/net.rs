@@ -0,0 +1,301 @@
+use std::{fs, io, path::Path, str::FromStr};
+
+use super::SysfsError;
+use crate::ethers::MacAddr;
+
+/// The RFC 2863 operational state from `operstate`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum OperState {
+    Unknown,
+    NotPresent,
+    Down,
+    LowerLayerDown,
+    Testing,
+    Dormant,
+    Up,
+    Other(String),
+}
+
+impl From<&str> for OperState {
+    fn from(s: &str) -> Self {
+        match s {
+            "unknown" => OperState::Unknown,
+            "notpresent" => OperState::NotPresent,
+            "down" => OperState::Down,
+            "lowerlayerdown" => OperState::LowerLayerDown,
+            "testing" => OperState::Testing,
+            "dormant" => OperState::Dormant,
+            "up" => OperState::Up,
+            s => OperState::Other(s.to_owned()),
+        }
+    }
+}
+
+/// Counters of the `statistics/` directory, zero when the driver doesn't provide one.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct Statistics {
+    pub rx_bytes: u64,
+    pub rx_packets: u64,
+    pub rx_errors: u64,
+    pub rx_dropped: u64,
+    pub tx_bytes: u64,
+    pub tx_packets: u64,
+    pub tx_errors: u64,
+    pub tx_dropped: u64,
+    pub multicast: u64,
+    pub collisions: u64,
+}
+
+/// A network interface of `/sys/class/net`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Interface {
+    pub name: String,
+    pub ifindex: u32,
+    /// The hardware address, `None` for interfaces without a 48-bit one like tunnels or InfiniBand.
+    pub mac: Option<MacAddr>,
+    pub mtu: u32,
+    pub operstate: OperState,
+    /// The link speed in Mbit/s, `None` when the link is down or the driver doesn't know it.
+    pub speed: Option<u32>,
+    /// Whether the physical link is up, `None` when the interface is administratively down.
+    pub carrier: Option<bool>,
+    pub statistics: Statistics,
+}
+
+impl Interface {
+    fn read(path: &Path) -> Result<Self, SysfsError> {
+        let name = path
+            .file_name()
+            .map(|name| name.to_string_lossy().into_owned())
+            .unwrap_or_default();
+
+        let statistics = path.join("statistics");
+        let counter = |name: &str| -> Result<u64, SysfsError> {
+            Ok(optional(&statistics.join(name))?.unwrap_or_default())
+        };
+
+        // Unlike the other attributes, a missing ifindex means the interface is gone.
+        let ifindex = path.join("ifindex");
+        let value = fs::read_to_string(&ifindex)?;
+
+        Ok(Interface {
+            name,
+            ifindex: value
+                .trim_end()
+                .parse()
+                .map_err(|_| SysfsError::InvalidAttribute {
+                    path: ifindex,
+                    value,
+                })?,
+            mac: read(&path.join("address"))?.and_then(|address| address.parse().ok()),
+            mtu: required(&path.join("mtu"))?,
+            operstate: read(&path.join("operstate"))?
+                .map(|operstate| OperState::from(operstate.as_str()))
+                .unwrap_or(OperState::Unknown),
+            // Drivers report -1 for an unknown speed.
+            speed: optional::<i64>(&path.join("speed"))?
+                .and_then(|speed| u32::try_from(speed).ok()),
+            carrier: optional::<u8>(&path.join("carrier"))?.map(|carrier| carrier == 1),
+            statistics: Statistics {
+                rx_bytes: counter("rx_bytes")?,
+                rx_packets: counter("rx_packets")?,
+                rx_errors: counter("rx_errors")?,
+                rx_dropped: counter("rx_dropped")?,
+                tx_bytes: counter("tx_bytes")?,
+                tx_packets: counter("tx_packets")?,
+                tx_errors: counter("tx_errors")?,
+                tx_dropped: counter("tx_dropped")?,
+                multicast: counter("multicast")?,
+                collisions: counter("collisions")?,
+            },
+        })
+    }
+}
+
+/// Reads the attribute, `None` when it is missing or the driver refuses to report it.
+fn read(path: &Path) -> Result<Option<String>, SysfsError> {
+    match fs::read_to_string(path) {
+        Ok(value) => Ok(Some(value.trim_end().to_owned())),
+        // EINVAL is what drivers return for `speed` or `carrier` of a down link.
+        Err(error)
+            if matches!(
+                error.kind(),
+                io::ErrorKind::NotFound | io::ErrorKind::InvalidInput
+            ) =>
+        {
+            Ok(None)
+        }
+        Err(error) => Err(error.into()),
+    }
+}
+
+fn optional<T: FromStr>(path: &Path) -> Result<Option<T>, SysfsError> {
+    read(path)?
+        .map(|value| {
+            value.parse().map_err(|_| SysfsError::InvalidAttribute {
+                path: path.to_owned(),
+                value,
+            })
+        })
+        .transpose()
+}
+
+fn required<T: FromStr>(path: &Path) -> Result<T, SysfsError> {
+    optional(path)?.ok_or_else(|| SysfsError::InvalidAttribute {
+        path: path.to_owned(),
+        value: String::new(),
+    })
+}
+
+///
+/// Reads the attributes of every interface under `dir`, sorted by ifindex.
+///
+/// ```no_run
+/// for interface in unixism::sysfs::net::interfaces_at("/sys/class/net").unwrap() {
+///     println!("{}: mtu {} {:?}", interface.name, interface.mtu, interface.operstate);
+/// }
+/// ```
+pub fn interfaces_at<P>(dir: P) -> Result<Vec<Interface>, SysfsError>
+where
+    P: AsRef<Path>,
+{
+    let mut interfaces = Vec::new();
+
+    for entry in fs::read_dir(dir)? {
+        let path = entry?.path();
+
+        // `bonding_masters` is a file next to the interfaces when the bonding module is loaded.
+        if !path.is_dir() {
+            continue;
+        }
+
+        match Interface::read(&path) {
+            Ok(interface) => interfaces.push(interface),
+            // The interface went away while it was read.
+            Err(SysfsError::IOError(_)) if !path.join("ifindex").is_file() => continue,
+            Err(error) => return Err(error),
+        }
+    }
+
+    interfaces.sort_by_key(|interface| interface.ifindex);
+
+    Ok(interfaces)
+}
+
+///
+/// Same as interfaces_at, but reads the `/sys/class/net` as default.
+///
+/// ```no_run
+/// for interface in unixism::sysfs::net::interfaces().unwrap() {
+///     if let Some(mac) = interface.mac {
+///         println!("{}: {}", interface.name, mac);
+///     }
+/// }
+/// ```
+pub fn interfaces() -> Result<Vec<Interface>, SysfsError> {
+    interfaces_at("/sys/class/net")
+}
+
+///
+/// Reads the attributes of a single interface of `/sys/class/net`.
+///
+/// ```no_run
+/// let eth0 = unixism::sysfs::net::interface("eth0").unwrap();
+///
+/// println!("{:?} Mbit/s, carrier {:?}", eth0.speed, eth0.carrier);
+/// ```
+pub fn interface(name: &str) -> Result<Interface, SysfsError> {
+    Interface::read(&Path::new("/sys/class/net").join(name))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tempdir::TempDir;
+
+    #[test]
+    fn it_interfaces_at() {
+        let dir = TempDir::new("sysfs-net");
+
+        for (name, attributes) in [
+            (
+                "eth0",
+                vec![
+                    ("ifindex", "2\n"),
+                    ("address", "52:54:00:12:34:56\n"),
+                    ("mtu", "1500\n"),
+                    ("operstate", "up\n"),
+                    ("speed", "1000\n"),
+                    ("carrier", "1\n"),
+                    ("statistics/rx_bytes", "4096\n"),
+                    ("statistics/tx_packets", "12\n"),
+                ],
+            ),
+            (
+                "lo",
+                vec![
+                    ("ifindex", "1\n"),
+                    ("address", "00:00:00:00:00:00\n"),
+                    ("mtu", "65536\n"),
+                    ("operstate", "unknown\n"),
+                ],
+            ),
+            (
+                "tun0",
+                vec![
+                    ("ifindex", "3\n"),
+                    ("address", "\n"),
+                    ("mtu", "1420\n"),
+                    ("operstate", "down\n"),
+                    ("speed", "-1\n"),
+                ],
+            ),
+        ] {
+            fs::create_dir_all(dir.join(name).join("statistics")).unwrap();
+
+            for (attribute, value) in attributes {
+                fs::write(dir.join(name).join(attribute), value).unwrap();
+            }
+        }
+
+        fs::write(dir.join("bonding_masters"), "bond0\n").unwrap();
+
+        let interfaces = interfaces_at(&dir).unwrap();
+
+        assert_eq!(
+            vec!["lo", "eth0", "tun0"],
+            interfaces
+                .iter()
+                .map(|interface| interface.name.as_str())
+                .collect::<Vec<_>>()
+        );
+        assert_eq!(
+            Interface {
+                name: "eth0".to_owned(),
+                ifindex: 2,
+                mac: Some(MacAddr([0x52, 0x54, 0x00, 0x12, 0x34, 0x56])),
+                mtu: 1500,
+                operstate: OperState::Up,
+                speed: Some(1000),
+                carrier: Some(true),
+                statistics: Statistics {
+                    rx_bytes: 4096,
+                    tx_packets: 12,
+                    ..Default::default()
+                },
+            },
+            interfaces[1]
+        );
+        assert_eq!(OperState::Unknown, interfaces[0].operstate);
+        assert_eq!(None, interfaces[2].mac);
+        assert_eq!(None, interfaces[2].speed);
+        assert_eq!(None, interfaces[2].carrier);
+
+        // An interface removed while the directory was read has no attributes left.
+        fs::create_dir_all(dir.join("veth0")).unwrap();
+        assert_eq!(3, interfaces_at(&dir).unwrap().len());
+
+        fs::write(dir.join("tun0").join("mtu"), "big\n").unwrap();
+        assert!(interfaces_at(&dir).is_err());
+    }
+}
@@ -0,0 +1,630 @@
+use std::{
+    error, fmt, fs,
+    io::{self, BufRead, BufReader},
+    path::{Path, PathBuf},
+    str::FromStr,
+    time::Duration,
+};
+
+/// Directories systemd reads its daemon configs and their drop-ins from, the most important first.
+pub const CONFIG_DIRS: [&str; 4] = [
+    "/etc/systemd",
+    "/run/systemd",
+    "/usr/local/lib/systemd",
+    "/usr/lib/systemd",
+];
+
+/// A `[Section]` of a config file with its keys in file order.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct Section {
+    pub name: String,
+    pub entries: Vec<(String, String)>,
+}
+
+impl Section {
+    pub fn get(&self, key: &str) -> Option<&str> {
+        self.entries
+            .iter()
+            .rev()
+            .find(|(name, _)| name == key)
+            .map(|(_, value)| value.as_str())
+    }
+}
+
+///
+/// The sections of a daemon config and of its drop-ins, in the order they were read.
+/// A section may appear several times, the last assignment of a key winning.
+///
+/// ```no_run
+/// let config = unixism::systemdconf::load("logind.conf").unwrap();
+///
+/// println!("{:?}", config.get("Login", "HandleLidSwitch"));
+/// ```
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct Config {
+    pub sections: Vec<Section>,
+}
+
+impl Config {
+    pub fn get(&self, section: &str, key: &str) -> Option<&str> {
+        self.sections
+            .iter()
+            .rev()
+            .filter(|candidate| candidate.name == section)
+            .find_map(|section| section.get(key))
+    }
+
+    /// Parses the value of the key with `parse`, `None` when it is unset or empty.
+    fn value<T>(
+        &self,
+        section: &str,
+        key: &str,
+        parse: fn(&str) -> Option<T>,
+    ) -> Result<Option<T>, ParseSystemdConfError> {
+        match self.get(section, key) {
+            None | Some("") => Ok(None),
+            Some(value) => {
+                parse(value)
+                    .map(Some)
+                    .ok_or_else(|| ParseSystemdConfError::InvalidValue {
+                        key: key.to_owned(),
+                        value: value.to_owned(),
+                    })
+            }
+        }
+    }
+}
+
+impl Extend<Section> for Config {
+    fn extend<I>(&mut self, iter: I)
+    where
+        I: IntoIterator<Item = Section>,
+    {
+        self.sections.extend(iter);
+    }
+}
+
+/// The `Storage=` of journald.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum Storage {
+    Volatile,
+    Persistent,
+    #[default]
+    Auto,
+    None,
+}
+
+impl FromStr for Storage {
+    type Err = ParseSystemdConfError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "volatile" => Ok(Storage::Volatile),
+            "persistent" => Ok(Storage::Persistent),
+            "auto" => Ok(Storage::Auto),
+            "none" => Ok(Storage::None),
+            storage => Err(ParseSystemdConfError::InvalidValue {
+                key: "Storage".to_owned(),
+                value: storage.to_owned(),
+            }),
+        }
+    }
+}
+
+///
+/// The `[Journal]` settings of `journald.conf`, sizes in bytes.
+/// The size limits are `None` when unset, journald then computing them from the file system size.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Journald {
+    pub storage: Storage,
+    pub compress: bool,
+    pub seal: bool,
+    pub system_max_use: Option<u64>,
+    pub system_keep_free: Option<u64>,
+    pub system_max_file_size: Option<u64>,
+    pub runtime_max_use: Option<u64>,
+    pub runtime_keep_free: Option<u64>,
+    pub max_retention: Option<Duration>,
+    pub forward_to_syslog: bool,
+}
+
+impl Journald {
+    pub fn new(config: &Config) -> Result<Self, ParseSystemdConfError> {
+        let size = |key| config.value("Journal", key, parse_size);
+        let boolean = |key| config.value("Journal", key, parse_boolean);
+
+        Ok(Journald {
+            storage: config
+                .value("Journal", "Storage", |value| value.parse().ok())?
+                .unwrap_or_default(),
+            // Compress= also takes a threshold, which enables compression.
+            compress: config
+                .value("Journal", "Compress", |value| {
+                    parse_boolean(value).or_else(|| parse_size(value).map(|_| true))
+                })?
+                .unwrap_or(true),
+            seal: boolean("Seal")?.unwrap_or(true),
+            system_max_use: size("SystemMaxUse")?,
+            system_keep_free: size("SystemKeepFree")?,
+            system_max_file_size: size("SystemMaxFileSize")?,
+            runtime_max_use: size("RuntimeMaxUse")?,
+            runtime_keep_free: size("RuntimeKeepFree")?,
+            max_retention: config
+                .value("Journal", "MaxRetentionSec", parse_timespan)?
+                .filter(|retention| !retention.is_zero()),
+            forward_to_syslog: boolean("ForwardToSyslog")?.unwrap_or(false),
+        })
+    }
+}
+
+/// The `[Login]` settings of `logind.conf`, the actions kept as written, like `suspend` or `ignore`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Logind {
+    pub n_auto_vts: u32,
+    pub kill_user_processes: bool,
+    pub handle_power_key: String,
+    pub handle_suspend_key: String,
+    pub handle_lid_switch: String,
+    pub handle_lid_switch_docked: String,
+    pub idle_action: String,
+    pub idle_action_sec: Duration,
+}
+
+impl Logind {
+    pub fn new(config: &Config) -> Result<Self, ParseSystemdConfError> {
+        let action = |key, default: &str| {
+            config
+                .value("Login", key, |value| Some(value.to_owned()))
+                .map(|action| action.unwrap_or_else(|| default.to_owned()))
+        };
+
+        Ok(Logind {
+            n_auto_vts: config
+                .value("Login", "NAutoVTs", |value| value.parse().ok())?
+                .unwrap_or(6),
+            kill_user_processes: config
+                .value("Login", "KillUserProcesses", parse_boolean)?
+                .unwrap_or(false),
+            handle_power_key: action("HandlePowerKey", "poweroff")?,
+            handle_suspend_key: action("HandleSuspendKey", "suspend")?,
+            handle_lid_switch: action("HandleLidSwitch", "suspend")?,
+            handle_lid_switch_docked: action("HandleLidSwitchDocked", "ignore")?,
+            idle_action: action("IdleAction", "ignore")?,
+            idle_action_sec: config
+                .value("Login", "IdleActionSec", parse_timespan)?
+                .unwrap_or(Duration::from_secs(30 * 60)),
+        })
+    }
+}
+
+/// The `[Manager]` settings of `system.conf` and `user.conf`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Manager {
+    pub log_level: String,
+    pub default_timeout_start: Duration,
+    pub default_timeout_stop: Duration,
+    pub default_restart: Duration,
+    pub crash_reboot: bool,
+    pub runtime_watchdog: Option<Duration>,
+}
+
+impl Manager {
+    pub fn new(config: &Config) -> Result<Self, ParseSystemdConfError> {
+        let timespan = |key| config.value("Manager", key, parse_timespan);
+
+        Ok(Manager {
+            log_level: config
+                .value("Manager", "LogLevel", |value| Some(value.to_owned()))?
+                .unwrap_or_else(|| "info".to_owned()),
+            default_timeout_start: timespan("DefaultTimeoutStartSec")?
+                .unwrap_or(Duration::from_secs(90)),
+            default_timeout_stop: timespan("DefaultTimeoutStopSec")?
+                .unwrap_or(Duration::from_secs(90)),
+            default_restart: timespan("DefaultRestartSec")?.unwrap_or(Duration::from_millis(100)),
+            crash_reboot: config
+                .value("Manager", "CrashReboot", parse_boolean)?
+                .unwrap_or(false),
+            runtime_watchdog: timespan("RuntimeWatchdogSec")?
+                .filter(|watchdog| !watchdog.is_zero()),
+        })
+    }
+}
+
+///
+/// Parses a boolean the way systemd does.
+///
+/// ```
+/// assert_eq!(Some(true), unixism::systemdconf::parse_boolean("yes"));
+/// assert_eq!(Some(false), unixism::systemdconf::parse_boolean("off"));
+/// ```
+pub fn parse_boolean(value: &str) -> Option<bool> {
+    match value.to_ascii_lowercase().as_str() {
+        "1" | "yes" | "y" | "true" | "t" | "on" => Some(true),
+        "0" | "no" | "n" | "false" | "f" | "off" => Some(false),
+        _ => None,
+    }
+}
+
+///
+/// Parses a size in bytes with the optional base 1024 suffixes `K`, `M`, `G`, `T`, `P` and `E`,
+/// fractions like `1.5G` included.
+///
+/// ```
+/// assert_eq!(Some(500 * 1024 * 1024), unixism::systemdconf::parse_size("500M"));
+/// assert_eq!(Some(1536), unixism::systemdconf::parse_size("1.5K"));
+/// ```
+pub fn parse_size(value: &str) -> Option<u64> {
+    let value = value.trim();
+    let end = value
+        .find(|c: char| !c.is_ascii_digit() && c != '.')
+        .unwrap_or(value.len());
+    let (number, suffix) = value.split_at(end);
+
+    let factor: u128 = match suffix.trim_start() {
+        "" | "B" => 1,
+        "K" => 1 << 10,
+        "M" => 1 << 20,
+        "G" => 1 << 30,
+        "T" => 1 << 40,
+        "P" => 1 << 50,
+        "E" => 1 << 60,
+        _ => return None,
+    };
+
+    let (integer, fraction) = number.split_once('.').unwrap_or((number, ""));
+    if integer.is_empty() || !fraction.chars().all(|c| c.is_ascii_digit()) {
+        return None;
+    }
+
+    let mut size = integer.parse::<u128>().ok()?.checked_mul(factor)?;
+    if !fraction.is_empty() {
+        let scale = 10u128.checked_pow(fraction.len() as u32)?;
+        size += fraction.parse::<u128>().ok()? * factor / scale;
+    }
+
+    u64::try_from(size).ok()
+}
+
+///
+/// Parses a time span like `90s`, `1min 30s` or `2h30min`, plain numbers being seconds.
+/// `infinity` is `Duration::MAX`.
+///
+/// ```
+/// use std::time::Duration;
+///
+/// assert_eq!(Some(Duration::from_secs(90)), unixism::systemdconf::parse_timespan("1min 30s"));
+/// assert_eq!(Some(Duration::from_millis(100)), unixism::systemdconf::parse_timespan("100ms"));
+/// ```
+pub fn parse_timespan(value: &str) -> Option<Duration> {
+    let value = value.trim();
+    if value == "infinity" {
+        return Some(Duration::MAX);
+    }
+
+    let mut span = Duration::ZERO;
+    let mut rest = value;
+
+    if rest.is_empty() {
+        return None;
+    }
+
+    while !rest.is_empty() {
+        let end = rest
+            .find(|c: char| !c.is_ascii_digit() && c != '.')
+            .unwrap_or(rest.len());
+        let number = rest[..end].parse::<f64>().ok()?;
+        rest = rest[end..].trim_start();
+
+        let end = rest
+            .find(|c: char| !c.is_ascii_alphabetic())
+            .unwrap_or(rest.len());
+        let unit = match &rest[..end] {
+            "us" | "usec" => 1e-6,
+            "ms" | "msec" => 1e-3,
+            "" | "s" | "sec" | "second" | "seconds" => 1.0,
+            "m" | "min" | "minute" | "minutes" => 60.0,
+            "h" | "hr" | "hour" | "hours" => 3600.0,
+            "d" | "day" | "days" => 86400.0,
+            "w" | "week" | "weeks" => 604800.0,
+            "M" | "month" | "months" => 2629800.0,
+            "y" | "year" | "years" => 31557600.0,
+            _ => return None,
+        };
+        rest = rest[end..].trim_start();
+
+        span = span.checked_add(Duration::try_from_secs_f64(number * unit).ok()?)?;
+    }
+
+    Some(span)
+}
+
+#[derive(Debug)]
+pub enum ParseSystemdConfError {
+    InvalidEntry(String),
+    InvalidValue { key: String, value: String },
+    IOError(io::Error),
+}
+
+impl error::Error for ParseSystemdConfError {}
+
+impl fmt::Display for ParseSystemdConfError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::InvalidEntry(entry) => write!(f, "invalid config line: {}", entry),
+            Self::InvalidValue { key, value } => write!(f, "invalid value of {}: {}", key, value),
+            Self::IOError(error) => write!(f, "{}", error),
+        }
+    }
+}
+
+impl From<io::Error> for ParseSystemdConfError {
+    fn from(value: io::Error) -> Self {
+        ParseSystemdConfError::IOError(value)
+    }
+}
+
+///
+/// Parses the sections of a config file, joining the lines ending with a backslash.
+/// Comments start with `#` or `;` at the beginning of a line only.
+///
+/// ```no_run
+/// let sections = unixism::systemdconf::parse(std::fs::File::open("/etc/systemd/journald.conf").unwrap()).unwrap();
+///
+/// for section in sections {
+///     println!("[{}] {:?}", section.name, section.entries);
+/// }
+/// ```
+pub fn parse<R>(reader: R) -> Result<impl Iterator<Item = Section>, ParseSystemdConfError>
+where
+    R: io::Read,
+{
+    let mut sections: Vec<Section> = Vec::new();
+    let mut pending = String::new();
+
+    for line in BufReader::new(reader).lines() {
+        let line = line?;
+        let line = line.trim();
+
+        if pending.is_empty() && (line.is_empty() || line.starts_with(['#', ';'])) {
+            continue;
+        }
+
+        if let Some(line) = line.strip_suffix('\\') {
+            pending.push_str(line);
+            pending.push(' ');
+            continue;
+        }
+
+        pending.push_str(line);
+        let line = std::mem::take(&mut pending);
+
+        if let Some(name) = line
+            .strip_prefix('[')
+            .and_then(|line| line.strip_suffix(']'))
+        {
+            sections.push(Section {
+                name: name.to_owned(),
+                entries: Vec::new(),
+            });
+
+            continue;
+        }
+
+        let (key, value) = line
+            .split_once('=')
+            .ok_or_else(|| ParseSystemdConfError::InvalidEntry(line.clone()))?;
+        let section = sections
+            .last_mut()
+            .ok_or_else(|| ParseSystemdConfError::InvalidEntry(line.clone()))?;
+
+        section
+            .entries
+            .push((key.trim().to_owned(), value.trim().to_owned()));
+    }
+
+    if !pending.is_empty() {
+        return Err(ParseSystemdConfError::InvalidEntry(pending));
+    }
+
+    Ok(sections.into_iter())
+}
+
+///
+/// Reads the config `name`, like `journald.conf`, the way systemd does: the file of the first directory having it,
+/// then the `name.d/*.conf` drop-ins of all directories in file name order, a drop-in in an earlier directory
+/// overriding the one with the same name in later ones. Missing files are skipped.
+///
+/// ```no_run
+/// let config = unixism::systemdconf::load_at(&["/mnt/etc/systemd", "/mnt/usr/lib/systemd"], "journald.conf").unwrap();
+/// ```
+pub fn load_at<P>(dirs: &[P], name: &str) -> Result<Config, ParseSystemdConfError>
+where
+    P: AsRef<Path>,
+{
+    let mut config = Config::default();
+
+    if let Some(path) = dirs
+        .iter()
+        .map(|dir| dir.as_ref().join(name))
+        .find(|path| path.exists())
+    {
+        config.extend(parse(fs::File::open(path)?)?);
+    }
+
+    let dropins = dirs
+        .iter()
+        .map(|dir| dir.as_ref().join(format!("{}.d", name)))
+        .collect::<Vec<PathBuf>>();
+
+    for path in crate::dropin::files(&dropins, ".conf")? {
+        config.extend(parse(fs::File::open(path)?)?);
+    }
+
+    Ok(config)
+}
+
+///
+/// Same as load_at, but reads the standard systemd directories.
+///
+/// ```no_run
+/// let config = unixism::systemdconf::load("system.conf").unwrap();
+/// ```
+pub fn load(name: &str) -> Result<Config, ParseSystemdConfError> {
+    load_at(&CONFIG_DIRS, name)
+}
+
+///
+/// Returns the journald settings of the system.
+///
+/// ```no_run
+/// let journald = unixism::systemdconf::journald().unwrap();
+///
+/// println!("{:?} {:?}", journald.storage, journald.system_max_use);
+/// ```
+pub fn journald() -> Result<Journald, ParseSystemdConfError> {
+    Journald::new(&load("journald.conf")?)
+}
+
+///
+/// Returns the logind settings of the system.
+///
+/// ```no_run
+/// let logind = unixism::systemdconf::logind().unwrap();
+///
+/// println!("{}", logind.handle_lid_switch);
+/// ```
+pub fn logind() -> Result<Logind, ParseSystemdConfError> {
+    Logind::new(&load("logind.conf")?)
+}
+
+///
+/// Returns the settings of the system manager.
+///
+/// ```no_run
+/// let manager = unixism::systemdconf::manager().unwrap();
+///
+/// println!("{:?}", manager.default_timeout_stop);
+/// ```
+pub fn manager() -> Result<Manager, ParseSystemdConfError> {
+    Manager::new(&load("system.conf")?)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Cursor;
+
+    use super::*;
+    use crate::tempdir::TempDir;
+
+    #[test]
+    fn it_parse() {
+        let config = Config {
+            sections: parse(Cursor::new(
+                r#"
+#  This file is part of systemd.
+[Journal]
+Storage=persistent
+#Compress=yes
+SystemMaxUse=1.5G
+SystemKeepFree = 500M
+MaxRetentionSec=1month
+ForwardToSyslog=\
+    yes
+; another comment
+[Journal]
+SystemMaxFileSize=64M
+Storage=volatile
+        "#,
+            ))
+            .unwrap()
+            .collect(),
+        };
+
+        assert_eq!(2, config.sections.len());
+        assert_eq!(Some("volatile"), config.get("Journal", "Storage"));
+        assert_eq!(Some("500M"), config.get("Journal", "SystemKeepFree"));
+        assert_eq!(Some("yes"), config.get("Journal", "ForwardToSyslog"));
+        assert_eq!(None, config.get("Journal", "Compress"));
+
+        let journald = Journald::new(&config).unwrap();
+        assert_eq!(Storage::Volatile, journald.storage);
+        assert!(journald.compress);
+        assert_eq!(Some(1536 * 1024 * 1024), journald.system_max_use);
+        assert_eq!(Some(500 * 1024 * 1024), journald.system_keep_free);
+        assert_eq!(Some(64 * 1024 * 1024), journald.system_max_file_size);
+        assert_eq!(None, journald.runtime_max_use);
+        assert_eq!(Some(Duration::from_secs(2629800)), journald.max_retention);
+        assert!(journald.forward_to_syslog);
+
+        let invalid = Config {
+            sections: parse(Cursor::new("[Journal]\nSystemMaxUse=lots\n"))
+                .unwrap()
+                .collect(),
+        };
+        assert!(Journald::new(&invalid).is_err());
+
+        assert!(parse(Cursor::new("Storage=auto")).is_err());
+        assert!(parse(Cursor::new("[Journal]\nStorage")).is_err());
+    }
+
+    #[test]
+    fn it_parse_values() {
+        assert_eq!(Some(1024), parse_size("1K"));
+        assert_eq!(Some(10), parse_size("10"));
+        assert_eq!(Some(3 << 29), parse_size("1.5 G"));
+        assert_eq!(None, parse_size("1X"));
+        assert_eq!(None, parse_size("-1K"));
+        assert_eq!(None, parse_size(".5K"));
+
+        assert_eq!(Some(Duration::from_secs(9000)), parse_timespan("2h30min"));
+        assert_eq!(Some(Duration::from_secs(5)), parse_timespan("5"));
+        assert_eq!(Some(Duration::MAX), parse_timespan("infinity"));
+        assert_eq!(Some(Duration::from_secs(604800)), parse_timespan("1w"));
+        assert_eq!(None, parse_timespan("5 fortnights"));
+        assert_eq!(None, parse_timespan(""));
+
+        assert_eq!(None, parse_boolean("maybe"));
+    }
+
+    #[test]
+    fn it_load_at() {
+        let root = TempDir::new("systemdconf");
+        let (etc, usr) = (root.join("etc"), root.join("usr"));
+        fs::create_dir_all(etc.join("logind.conf.d")).unwrap();
+        fs::create_dir_all(usr.join("logind.conf.d")).unwrap();
+
+        fs::write(
+            usr.join("logind.conf"),
+            "[Login]\nHandleLidSwitch=hibernate\n",
+        )
+        .unwrap();
+        fs::write(
+            usr.join("logind.conf.d").join("10-vendor.conf"),
+            "[Login]\nNAutoVTs=2\nIdleAction=lock\n",
+        )
+        .unwrap();
+        fs::write(
+            usr.join("logind.conf.d").join("50-local.conf"),
+            "[Login]\nIdleAction=suspend\n",
+        )
+        .unwrap();
+        fs::write(
+            etc.join("logind.conf.d").join("50-local.conf"),
+            "[Login]\nIdleActionSec=5min\nKillUserProcesses=yes\n",
+        )
+        .unwrap();
+
+        let logind = Logind::new(&load_at(&[&etc, &usr], "logind.conf").unwrap()).unwrap();
+
+        assert_eq!("hibernate", logind.handle_lid_switch);
+        assert_eq!("poweroff", logind.handle_power_key);
+        assert_eq!(2, logind.n_auto_vts);
+        assert_eq!("lock", logind.idle_action);
+        assert_eq!(Duration::from_secs(300), logind.idle_action_sec);
+        assert!(logind.kill_user_processes);
+
+        let manager = Manager::new(&load_at(&[&etc, &usr], "system.conf").unwrap()).unwrap();
+        assert_eq!(Duration::from_secs(90), manager.default_timeout_start);
+        assert_eq!(None, manager.runtime_watchdog);
+    }
+}
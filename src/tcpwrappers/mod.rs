@@ -0,0 +1,509 @@
+use std::{
+    error, fmt, fs,
+    io::{self, BufRead, BufReader},
+    net::IpAddr,
+    str::FromStr,
+};
+
+use crate::glob::glob;
+
+/// A single pattern of a daemon or client list, as described in hosts_access(5).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Pattern {
+    All,
+    /// Host names without a dot.
+    Local,
+    /// Clients whose host name is unknown.
+    Unknown,
+    /// Clients whose host name is known.
+    Known,
+    /// Clients whose name and address don't match, which can't be checked here so it never matches.
+    Paranoid,
+    /// `@group`, never matches as netgroups aren't resolved here.
+    Netgroup(String),
+    /// `.example.com`, matching names ending with it.
+    Suffix(String),
+    /// `192.168.`, matching addresses starting with it.
+    Prefix(String),
+    /// `192.168.0.0/255.255.0.0`, `192.168.0.0/16` or `[fe80::]/10`.
+    Network {
+        addr: IpAddr,
+        prefix: u8,
+    },
+    /// Patterns with `*` or `?`.
+    Wildcard(String),
+    /// `daemon@host` of a daemon list, matching the daemon on a server address matching the host.
+    /// `user@host` of a client list never matches as the user isn't looked up here.
+    At(Box<Pattern>, Box<Pattern>),
+    /// `/path` of a file with more patterns, never matches as the file isn't read here.
+    File(String),
+    Exact(String),
+}
+
+impl From<&str> for Pattern {
+    fn from(s: &str) -> Self {
+        match s.to_ascii_uppercase().as_str() {
+            "ALL" => return Pattern::All,
+            "LOCAL" => return Pattern::Local,
+            "UNKNOWN" => return Pattern::Unknown,
+            "KNOWN" => return Pattern::Known,
+            "PARANOID" => return Pattern::Paranoid,
+            _ => {}
+        }
+
+        if let Some((name, host)) = s.split_once('@').filter(|(name, _)| !name.is_empty()) {
+            return Pattern::At(Box::new(Pattern::from(name)), Box::new(Pattern::from(host)));
+        }
+
+        if let Some(network) = parse_network(s) {
+            return network;
+        }
+
+        if let Some(netgroup) = s.strip_prefix('@') {
+            Pattern::Netgroup(netgroup.to_owned())
+        } else if s.starts_with('/') {
+            Pattern::File(s.to_owned())
+        } else if s.contains(['*', '?']) {
+            Pattern::Wildcard(s.to_owned())
+        } else if s.starts_with('.') {
+            Pattern::Suffix(s.to_owned())
+        } else if s.ends_with('.') {
+            Pattern::Prefix(s.to_owned())
+        } else {
+            Pattern::Exact(s.trim_start_matches('[').trim_end_matches(']').to_owned())
+        }
+    }
+}
+
+fn parse_network(s: &str) -> Option<Pattern> {
+    let (addr, mask) = s.split_once('/')?;
+    let addr = addr
+        .trim_start_matches('[')
+        .trim_end_matches(']')
+        .parse::<IpAddr>()
+        .ok()?;
+
+    let prefix = match mask.parse::<u8>() {
+        Ok(prefix) => prefix,
+        Err(_) => match mask.parse::<IpAddr>().ok()? {
+            IpAddr::V4(mask) => u32::from(mask).leading_ones() as u8,
+            IpAddr::V6(mask) => u128::from(mask).leading_ones() as u8,
+        },
+    };
+
+    Some(Pattern::Network { addr, prefix })
+}
+
+impl Pattern {
+    fn matches_client(&self, ip: IpAddr, name: Option<&str>) -> bool {
+        let addr = ip.to_string();
+
+        match self {
+            Pattern::All => true,
+            Pattern::Local => name.is_some_and(|name| !name.contains('.')),
+            Pattern::Unknown => name.is_none(),
+            Pattern::Known => name.is_some(),
+            Pattern::Paranoid | Pattern::Netgroup(_) | Pattern::File(_) | Pattern::At(..) => false,
+            Pattern::Suffix(suffix) => name.is_some_and(|name| {
+                name.to_ascii_lowercase()
+                    .ends_with(&suffix.to_ascii_lowercase())
+            }),
+            Pattern::Prefix(prefix) => addr.starts_with(prefix.as_str()),
+            Pattern::Network { addr, prefix } => in_network(ip, *addr, *prefix),
+            Pattern::Wildcard(pattern) => {
+                glob(pattern, &addr)
+                    || name.is_some_and(|name| {
+                        glob(&pattern.to_ascii_lowercase(), &name.to_ascii_lowercase())
+                    })
+            }
+            Pattern::Exact(exact) => {
+                exact.parse::<IpAddr>().is_ok_and(|exact| exact == ip)
+                    || name.is_some_and(|name| name.eq_ignore_ascii_case(exact))
+            }
+        }
+    }
+
+    fn matches_daemon(&self, daemon: &str, server: Option<IpAddr>) -> bool {
+        match self {
+            Pattern::All => true,
+            Pattern::Wildcard(pattern) => {
+                glob(&pattern.to_ascii_lowercase(), &daemon.to_ascii_lowercase())
+            }
+            Pattern::Exact(exact) => exact.eq_ignore_ascii_case(daemon),
+            Pattern::At(name, host) => {
+                name.matches_daemon(daemon, server)
+                    && server.is_some_and(|server| host.matches_client(server, None))
+            }
+            _ => false,
+        }
+    }
+}
+
+fn in_network(ip: IpAddr, network: IpAddr, prefix: u8) -> bool {
+    match (ip, network) {
+        (IpAddr::V4(ip), IpAddr::V4(network)) if prefix <= 32 => {
+            let mask = u32::MAX.checked_shl(32 - prefix as u32).unwrap_or(0);
+            u32::from(ip) & mask == u32::from(network) & mask
+        }
+        (IpAddr::V6(ip), IpAddr::V6(network)) if prefix <= 128 => {
+            let mask = u128::MAX.checked_shl(128 - prefix as u32).unwrap_or(0);
+            u128::from(ip) & mask == u128::from(network) & mask
+        }
+        _ => false,
+    }
+}
+
+/// A list of patterns with an optional `EXCEPT` list.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PatternList {
+    pub patterns: Vec<Pattern>,
+    pub except: Option<Box<PatternList>>,
+}
+
+impl From<&str> for PatternList {
+    fn from(s: &str) -> Self {
+        let words = s
+            .split(|c: char| c == ',' || c.is_whitespace())
+            .filter(|word| !word.is_empty())
+            .collect::<Vec<_>>();
+
+        Self::from_words(&words)
+    }
+}
+
+impl PatternList {
+    fn from_words(words: &[&str]) -> Self {
+        match words
+            .iter()
+            .position(|word| word.eq_ignore_ascii_case("EXCEPT"))
+        {
+            Some(i) => PatternList {
+                patterns: words[..i].iter().map(|word| Pattern::from(*word)).collect(),
+                except: Some(Box::new(Self::from_words(&words[i + 1..]))),
+            },
+            None => PatternList {
+                patterns: words.iter().map(|word| Pattern::from(*word)).collect(),
+                except: None,
+            },
+        }
+    }
+
+    fn matches<F>(&self, matches: &F) -> bool
+    where
+        F: Fn(&Pattern) -> bool,
+    {
+        self.patterns.iter().any(matches)
+            && !self
+                .except
+                .as_ref()
+                .is_some_and(|except| except.matches(matches))
+    }
+}
+
+/// A line of hosts.allow or hosts.deny.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Rule {
+    pub daemons: PatternList,
+    pub clients: PatternList,
+    /// The colon separated option fields or shell command.
+    pub options: Vec<String>,
+}
+
+impl Rule {
+    ///
+    /// Whether the rule matches a connection to the daemon listening on the `server` address.
+    /// Daemon names and keywords are compared case-insensitively, as tcpd does.
+    pub fn matches(
+        &self,
+        daemon: &str,
+        server: Option<IpAddr>,
+        ip: IpAddr,
+        name: Option<&str>,
+    ) -> bool {
+        self.daemons
+            .matches(&|pattern: &Pattern| pattern.matches_daemon(daemon, server))
+            && self
+                .clients
+                .matches(&|pattern: &Pattern| pattern.matches_client(ip, name))
+    }
+
+    fn has_option(&self, option: &str) -> bool {
+        self.options
+            .iter()
+            .any(|value| value.eq_ignore_ascii_case(option))
+    }
+}
+
+impl FromStr for Rule {
+    type Err = ParseTcpWrappersError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let fields = split_fields(s);
+
+        if fields.len() < 2 {
+            return Err(ParseTcpWrappersError::InvalidEntry(s.to_owned()));
+        }
+
+        Ok(Rule {
+            daemons: PatternList::from(fields[0]),
+            clients: PatternList::from(fields[1]),
+            options: fields[2..]
+                .iter()
+                .map(|option| option.trim().to_owned())
+                .collect(),
+        })
+    }
+}
+
+/// Splits the rule by colons outside of `[...]` IPv6 addresses.
+fn split_fields(s: &str) -> Vec<&str> {
+    let mut fields = Vec::new();
+    let mut start = 0;
+    let mut bracketed = false;
+
+    for (i, c) in s.char_indices() {
+        match c {
+            '[' => bracketed = true,
+            ']' => bracketed = false,
+            ':' if !bracketed => {
+                fields.push(&s[start..i]);
+                start = i + 1;
+            }
+            _ => {}
+        }
+    }
+    fields.push(&s[start..]);
+
+    fields
+}
+
+///
+/// The access control rules of hosts.allow and hosts.deny.
+///
+/// Access is granted when a rule of hosts.allow matches, denied when a rule of hosts.deny matches,
+/// and granted otherwise. The `allow` and `deny` options of the extended language override the file.
+///
+/// ```no_run
+/// let policy = unixism::tcpwrappers::Policy::load().unwrap();
+///
+/// if policy.matches("sshd", None, "203.0.113.7".parse().unwrap(), Some("host.example.com")) {
+///     println!("sshd would accept the client");
+/// }
+/// ```
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct Policy {
+    pub allow: Vec<Rule>,
+    pub deny: Vec<Rule>,
+}
+
+impl Policy {
+    /// Parses the `/etc/hosts.allow` and `/etc/hosts.deny`, missing files have no rules.
+    pub fn load() -> Result<Self, ParseTcpWrappersError> {
+        let read = |path: &str| match fs::File::open(path) {
+            Ok(file) => Ok(parse(file)?.collect()),
+            Err(error) if error.kind() == io::ErrorKind::NotFound => Ok(Vec::new()),
+            Err(error) => Err(ParseTcpWrappersError::from(error)),
+        };
+
+        Ok(Policy {
+            allow: read("/etc/hosts.allow")?,
+            deny: read("/etc/hosts.deny")?,
+        })
+    }
+
+    ///
+    /// Whether the daemon would accept a connection from the client. The `server` address is the
+    /// one the connection came in on, `daemon@host` patterns never match when it's unknown.
+    pub fn matches(
+        &self,
+        daemon: &str,
+        server: Option<IpAddr>,
+        ip: IpAddr,
+        name: Option<&str>,
+    ) -> bool {
+        let matches = |rule: &&Rule| rule.matches(daemon, server, ip, name);
+
+        if let Some(rule) = self.allow.iter().find(matches) {
+            return !rule.has_option("deny");
+        }
+
+        if let Some(rule) = self.deny.iter().find(matches) {
+            return rule.has_option("allow");
+        }
+
+        true
+    }
+}
+
+#[derive(Debug)]
+pub enum ParseTcpWrappersError {
+    InvalidEntry(String),
+    IOError(io::Error),
+}
+
+impl error::Error for ParseTcpWrappersError {}
+
+impl fmt::Display for ParseTcpWrappersError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::InvalidEntry(entry) => write!(f, "invalid access control rule: {}", entry),
+            Self::IOError(error) => write!(f, "{}", error),
+        }
+    }
+}
+
+impl From<io::Error> for ParseTcpWrappersError {
+    fn from(value: io::Error) -> Self {
+        ParseTcpWrappersError::IOError(value)
+    }
+}
+
+///
+/// Parses hosts.allow or hosts.deny, lines ending with a backslash are joined with the next one.
+///
+/// ```no_run
+/// let rules = unixism::tcpwrappers::parse(std::fs::File::open("/etc/hosts.allow").unwrap()).unwrap();
+///
+/// for rule in rules {
+///     println!("{:?}: {:?}", rule.daemons, rule.clients);
+/// }
+/// ```
+pub fn parse<R>(reader: R) -> Result<impl Iterator<Item = Rule>, ParseTcpWrappersError>
+where
+    R: io::Read,
+{
+    let mut rules = Vec::new();
+    let mut pending = String::new();
+
+    for line in BufReader::new(reader).lines() {
+        let line = line?;
+
+        if let Some(line) = line.strip_suffix('\\') {
+            pending.push_str(line);
+            continue;
+        }
+
+        pending.push_str(&line);
+        let line = std::mem::take(&mut pending);
+        let line = line.trim();
+
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+
+        rules.push(line.parse::<Rule>()?);
+    }
+
+    Ok(rules.into_iter())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Cursor;
+
+    use super::*;
+
+    #[test]
+    fn it_parse() {
+        let rules = parse(Cursor::new(
+            r#"
+# /etc/hosts.allow
+sshd: 192.168.1. .example.com EXCEPT gw.example.com
+sshd, in.ftpd : [2001:db8::]/32 10.0.0.0/255.0.0.0 : spawn /bin/echo %h \
+    >> /var/log/ftp
+ALL: LOCAL
+in.telnetd: ALL : DENY
+        "#,
+        ))
+        .unwrap()
+        .collect::<Vec<_>>();
+
+        assert_eq!(4, rules.len());
+        assert_eq!(
+            PatternList {
+                patterns: vec![
+                    Pattern::Prefix("192.168.1.".to_owned()),
+                    Pattern::Suffix(".example.com".to_owned())
+                ],
+                except: Some(Box::new(PatternList {
+                    patterns: vec![Pattern::Exact("gw.example.com".to_owned())],
+                    except: None,
+                })),
+            },
+            rules[0].clients
+        );
+        assert_eq!(
+            vec![
+                Pattern::Network {
+                    addr: "2001:db8::".parse().unwrap(),
+                    prefix: 32
+                },
+                Pattern::Network {
+                    addr: "10.0.0.0".parse().unwrap(),
+                    prefix: 8
+                },
+            ],
+            rules[1].clients.patterns
+        );
+        assert_eq!(
+            vec!["spawn /bin/echo %h     >> /var/log/ftp".to_owned()],
+            rules[1].options
+        );
+
+        assert!(parse(Cursor::new("sshd")).is_err());
+    }
+
+    #[test]
+    fn it_match() {
+        let policy = Policy {
+            allow: parse(Cursor::new(
+                "sshd: 192.168.1. .example.com EXCEPT gw.example.com\nin.ftpd: [2001:db8::]/32\nin.telnetd: ALL: DENY\n",
+            ))
+            .unwrap()
+            .collect(),
+            deny: parse(Cursor::new("ALL: ALL EXCEPT LOCAL\n"))
+                .unwrap()
+                .collect(),
+        };
+
+        let ip = |ip: &str| ip.parse::<IpAddr>().unwrap();
+
+        assert!(policy.matches("sshd", None, ip("192.168.1.20"), None));
+        assert!(policy.matches("sshd", None, ip("203.0.113.7"), Some("web.EXAMPLE.com")));
+        assert!(!policy.matches("sshd", None, ip("203.0.113.1"), Some("gw.example.com")));
+        assert!(!policy.matches("sshd", None, ip("203.0.113.7"), None));
+        assert!(policy.matches("sshd", None, ip("203.0.113.7"), Some("printer")));
+        assert!(policy.matches("in.ftpd", None, ip("2001:db8::1"), None));
+        assert!(!policy.matches("in.ftpd", None, ip("2001:db9::1"), None));
+        assert!(!policy.matches("in.telnetd", None, ip("192.168.1.20"), Some("printer")));
+        assert!(Policy::default().matches("sshd", None, ip("203.0.113.7"), None));
+
+        let policy = Policy {
+            allow: parse(Cursor::new(
+                "SSHD@192.168.1.1, in.ftpd@[2001:db8::1]: all except 192.168.1.66
+",
+            ))
+            .unwrap()
+            .collect(),
+            deny: parse(Cursor::new(
+                "ALL: ALL
+",
+            ))
+            .unwrap()
+            .collect(),
+        };
+
+        assert_eq!(
+            Pattern::At(
+                Box::new(Pattern::Exact("SSHD".to_owned())),
+                Box::new(Pattern::Exact("192.168.1.1".to_owned()))
+            ),
+            policy.allow[0].daemons.patterns[0]
+        );
+        assert!(policy.matches("sshd", Some(ip("192.168.1.1")), ip("192.168.1.20"), None));
+        assert!(!policy.matches("sshd", Some(ip("10.0.0.1")), ip("192.168.1.20"), None));
+        assert!(!policy.matches("sshd", None, ip("192.168.1.20"), None));
+        assert!(!policy.matches("sshd", Some(ip("192.168.1.1")), ip("192.168.1.66"), None));
+        assert!(policy.matches("in.ftpd", Some(ip("2001:db8::1")), ip("192.168.1.20"), None));
+    }
+}
@@ -0,0 +1,284 @@
+use std::{
+    collections::HashSet,
+    error, fmt, fs,
+    io::{self, BufRead, BufReader},
+    path::{Path, PathBuf},
+};
+
+/// Where the tz database is installed, relative to the root.
+pub const ZONEINFO: &str = "usr/share/zoneinfo";
+
+/// Directories and files of the tz database which aren't zones of their own.
+const SKIPPED: [&str; 5] = ["posix", "right", "posixrules", "localtime", "Factory"];
+
+#[derive(Debug)]
+pub enum ParseTimezoneError {
+    /// Neither `/etc/timezone` nor `/etc/localtime` tell the zone.
+    Unknown,
+    IOError(io::Error),
+}
+
+impl error::Error for ParseTimezoneError {}
+
+impl fmt::Display for ParseTimezoneError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Unknown => write!(f, "unable to determine the time zone"),
+            Self::IOError(error) => write!(f, "{}", error),
+        }
+    }
+}
+
+impl From<io::Error> for ParseTimezoneError {
+    fn from(value: io::Error) -> Self {
+        ParseTimezoneError::IOError(value)
+    }
+}
+
+///
+/// Reads the zone name of Debian's `/etc/timezone`, the first line that is not a comment.
+///
+/// ```no_run
+/// let zone = unixism::timezone::parse(std::fs::File::open("/etc/timezone").unwrap()).unwrap();
+/// ```
+pub fn parse<R>(reader: R) -> Result<String, ParseTimezoneError>
+where
+    R: io::Read,
+{
+    for line in BufReader::new(reader).lines() {
+        let line = line?;
+        let line = line.trim();
+
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+
+        return Ok(line.to_owned());
+    }
+
+    Err(ParseTimezoneError::Unknown)
+}
+
+/// Extracts the zone name from a path into the tz database, like `../usr/share/zoneinfo/Europe/Berlin`.
+fn zone_of(path: &Path) -> Option<String> {
+    let path = path.to_str()?;
+    let (_, zone) = path.rsplit_once("zoneinfo/")?;
+    let zone = zone
+        .strip_prefix("posix/")
+        .or_else(|| zone.strip_prefix("right/"))
+        .unwrap_or(zone);
+
+    (!zone.is_empty()).then(|| zone.to_owned())
+}
+
+/// Finds the zone of the tz database with the same content as the `localtime`.
+fn find_zone(zoneinfo: &Path, localtime: &[u8]) -> io::Result<Option<String>> {
+    let mut dirs = vec![zoneinfo.to_path_buf()];
+    let mut matches = Vec::new();
+
+    while let Some(dir) = dirs.pop() {
+        let mut entries = fs::read_dir(&dir)?
+            .filter_map(Result::ok)
+            .map(|entry| entry.path())
+            .collect::<Vec<PathBuf>>();
+        entries.sort();
+
+        for path in entries {
+            if path
+                .file_name()
+                .and_then(|name| name.to_str())
+                .is_some_and(|name| SKIPPED.contains(&name))
+            {
+                continue;
+            }
+
+            // A dangling symlink is not a zone, it doesn't abort the search.
+            let Ok(metadata) = fs::metadata(&path) else {
+                continue;
+            };
+
+            if metadata.is_dir() {
+                dirs.push(path);
+            } else if metadata.len() == localtime.len() as u64 && fs::read(&path)? == localtime {
+                if let Ok(zone) = path.strip_prefix(zoneinfo) {
+                    matches.extend(zone.to_str().map(String::from));
+                }
+            }
+        }
+    }
+
+    matches.sort();
+
+    // Links like `Arctic/Longyearbyen` share the content of `Europe/Berlin`, the zones of the database are preferred,
+    // then `Etc/UTC` over its aliases, then Area/Location names over the legacy ones like `CET`.
+    let canonical = canonical_zones(zoneinfo);
+
+    Ok(matches
+        .iter()
+        .find(|zone| canonical.contains(*zone))
+        .or_else(|| matches.iter().find(|zone| *zone == "Etc/UTC"))
+        .or_else(|| matches.iter().find(|zone| zone.contains('/')))
+        .or(matches.first())
+        .cloned())
+}
+
+/// Reads the names of the zones, as opposed to links, from `tzdata.zi`, `zone1970.tab` and `zone.tab`.
+fn canonical_zones(zoneinfo: &Path) -> HashSet<String> {
+    let mut zones = HashSet::new();
+
+    if let Ok(tzdata) = fs::read_to_string(zoneinfo.join("tzdata.zi")) {
+        zones.extend(
+            tzdata
+                .lines()
+                .filter_map(|line| line.strip_prefix("Z "))
+                .filter_map(|line| line.split_whitespace().next())
+                .map(String::from),
+        );
+    }
+
+    for tab in ["zone1970.tab", "zone.tab"] {
+        if let Ok(tab) = fs::read_to_string(zoneinfo.join(tab)) {
+            zones.extend(
+                tab.lines()
+                    .filter(|line| !line.starts_with('#'))
+                    .filter_map(|line| line.split('\t').nth(2))
+                    .map(String::from),
+            );
+        }
+    }
+
+    zones
+}
+
+///
+/// Same as current, but looks for the files under `root`.
+///
+/// ```no_run
+/// let zone = unixism::timezone::current_at("/mnt").unwrap();
+/// ```
+pub fn current_at<P>(root: P) -> Result<String, ParseTimezoneError>
+where
+    P: AsRef<Path>,
+{
+    let root = root.as_ref();
+
+    match fs::File::open(root.join("etc/timezone")) {
+        Ok(file) => match parse(file) {
+            Err(ParseTimezoneError::Unknown) => {}
+            zone => return zone,
+        },
+        Err(e) if e.kind() == io::ErrorKind::NotFound => {}
+        Err(e) => return Err(e.into()),
+    }
+
+    let localtime = root.join("etc/localtime");
+
+    match fs::read_link(&localtime) {
+        Ok(target) => {
+            if let Some(zone) = zone_of(&target) {
+                return Ok(zone);
+            }
+
+            if let Some(zone) = fs::canonicalize(&localtime)
+                .ok()
+                .and_then(|path| zone_of(&path))
+            {
+                return Ok(zone);
+            }
+        }
+        Err(e) if e.kind() == io::ErrorKind::NotFound => return Err(ParseTimezoneError::Unknown),
+        Err(e) if e.kind() != io::ErrorKind::InvalidInput => return Err(e.into()),
+        Err(_) => {}
+    }
+
+    find_zone(&root.join(ZONEINFO), &fs::read(&localtime)?)?.ok_or(ParseTimezoneError::Unknown)
+}
+
+///
+/// Returns the IANA name of the system time zone, like `Europe/Berlin`.
+/// It is read from `/etc/timezone` if present, otherwise from the `/etc/localtime` symlink target,
+/// otherwise by finding the file of `/usr/share/zoneinfo` with the same content as `/etc/localtime`.
+///
+/// ```no_run
+/// println!("{}", unixism::timezone::current().unwrap());
+/// ```
+pub fn current() -> Result<String, ParseTimezoneError> {
+    current_at("/")
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{io::Cursor, os::unix::fs::symlink};
+
+    use super::*;
+    use crate::tempdir::TempDir;
+
+    #[test]
+    fn it_parse() {
+        assert_eq!(
+            "Europe/Berlin",
+            parse(Cursor::new("# comment\n\nEurope/Berlin\n")).unwrap()
+        );
+        assert!(matches!(
+            parse(Cursor::new("\n")),
+            Err(ParseTimezoneError::Unknown)
+        ));
+    }
+
+    #[test]
+    fn it_current_at() {
+        let root = TempDir::new("timezone");
+        let zoneinfo = root.join(ZONEINFO);
+        fs::create_dir_all(zoneinfo.join("Europe")).unwrap();
+        fs::create_dir_all(zoneinfo.join("posix/Europe")).unwrap();
+        fs::create_dir_all(root.join("etc")).unwrap();
+
+        fs::write(zoneinfo.join("Europe/Berlin"), "TZif berlin").unwrap();
+        fs::write(zoneinfo.join("posix/Europe/Berlin"), "TZif berlin").unwrap();
+        fs::write(zoneinfo.join("CET"), "TZif berlin").unwrap();
+        fs::write(zoneinfo.join("Europe/Paris"), "TZif paris!").unwrap();
+        fs::write(zoneinfo.join("UTC"), "TZif utc").unwrap();
+        fs::create_dir_all(zoneinfo.join("Arctic")).unwrap();
+        fs::create_dir_all(zoneinfo.join("Etc")).unwrap();
+        fs::write(zoneinfo.join("Arctic/Longyearbyen"), "TZif berlin").unwrap();
+        fs::write(zoneinfo.join("Etc/UCT"), "TZif utc").unwrap();
+        fs::write(zoneinfo.join("Etc/UTC"), "TZif utc").unwrap();
+        fs::write(
+            zoneinfo.join("zone1970.tab"),
+            "#codes\tcoordinates\tTZ\tcomments\nDE,DK,NO,SE,SJ\t+5230+01322\tEurope/Berlin\n",
+        )
+        .unwrap();
+        symlink("Europe/Gone", zoneinfo.join("Dangling")).unwrap();
+
+        assert!(matches!(
+            current_at(&root),
+            Err(ParseTimezoneError::Unknown)
+        ));
+
+        symlink(
+            "../usr/share/zoneinfo/posix/Europe/Berlin",
+            root.join("etc/localtime"),
+        )
+        .unwrap();
+        assert_eq!("Europe/Berlin", current_at(&root).unwrap());
+
+        fs::remove_file(root.join("etc/localtime")).unwrap();
+        fs::write(root.join("etc/localtime"), "TZif paris!").unwrap();
+        assert_eq!("Europe/Paris", current_at(&root).unwrap());
+
+        fs::write(root.join("etc/localtime"), "TZif berlin").unwrap();
+        assert_eq!("Europe/Berlin", current_at(&root).unwrap());
+
+        fs::write(root.join("etc/localtime"), "TZif utc").unwrap();
+        assert_eq!("Etc/UTC", current_at(&root).unwrap());
+
+        // Without the tables, the Area/Location name still wins over the legacy one.
+        fs::remove_file(zoneinfo.join("zone1970.tab")).unwrap();
+        fs::write(root.join("etc/localtime"), "TZif paris!").unwrap();
+        fs::write(zoneinfo.join("MET"), "TZif paris!").unwrap();
+        assert_eq!("Europe/Paris", current_at(&root).unwrap());
+
+        fs::write(root.join("etc/timezone"), "Asia/Tokyo\n").unwrap();
+        assert_eq!("Asia/Tokyo", current_at(&root).unwrap());
+    }
+}
@@ -0,0 +1,618 @@
+use std::{
+    error, fmt, fs,
+    io::{self, BufRead, BufReader},
+    num::ParseIntError,
+    path::Path,
+    str::FromStr,
+    time::Duration,
+};
+
+use crate::systemdconf;
+
+/// Directories `systemd-tmpfiles` reads, the most important first.
+pub const CONFIG_DIRS: [&str; 4] = [
+    "/etc/tmpfiles.d",
+    "/run/tmpfiles.d",
+    "/usr/local/lib/tmpfiles.d",
+    "/usr/lib/tmpfiles.d",
+];
+
+/// The line type, the first character of the type field.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Kind {
+    /// `f`
+    File,
+    /// `F`, deprecated in favour of `f+`.
+    TruncateFile,
+    /// `w`
+    Write,
+    /// `d`
+    Directory,
+    /// `D`, also emptied by `--remove`.
+    RemovableDirectory,
+    /// `e`
+    AdjustDirectory,
+    /// `v`
+    Subvolume,
+    /// `q`
+    SubvolumeQuota,
+    /// `Q`
+    SubvolumeQuotaInherit,
+    /// `p`
+    Fifo,
+    /// `L`
+    Symlink,
+    /// `c`
+    CharDevice,
+    /// `b`
+    BlockDevice,
+    /// `C`
+    Copy,
+    /// `x`
+    Ignore,
+    /// `X`, the path itself but not its content.
+    IgnoreDirectory,
+    /// `r`
+    Remove,
+    /// `R`
+    RemoveRecursive,
+    /// `z`
+    SetMode,
+    /// `Z`
+    SetModeRecursive,
+    /// `t`
+    SetXattr,
+    /// `T`
+    SetXattrRecursive,
+    /// `h`
+    SetAttr,
+    /// `H`
+    SetAttrRecursive,
+    /// `a`
+    SetAcl,
+    /// `A`
+    SetAclRecursive,
+}
+
+impl TryFrom<char> for Kind {
+    type Error = ParseTmpfilesError;
+
+    fn try_from(c: char) -> Result<Self, Self::Error> {
+        let kind = match c {
+            'f' => Kind::File,
+            'F' => Kind::TruncateFile,
+            'w' => Kind::Write,
+            'd' => Kind::Directory,
+            'D' => Kind::RemovableDirectory,
+            'e' => Kind::AdjustDirectory,
+            'v' => Kind::Subvolume,
+            'q' => Kind::SubvolumeQuota,
+            'Q' => Kind::SubvolumeQuotaInherit,
+            'p' => Kind::Fifo,
+            'L' => Kind::Symlink,
+            'c' => Kind::CharDevice,
+            'b' => Kind::BlockDevice,
+            'C' => Kind::Copy,
+            'x' => Kind::Ignore,
+            'X' => Kind::IgnoreDirectory,
+            'r' => Kind::Remove,
+            'R' => Kind::RemoveRecursive,
+            'z' => Kind::SetMode,
+            'Z' => Kind::SetModeRecursive,
+            't' => Kind::SetXattr,
+            'T' => Kind::SetXattrRecursive,
+            'h' => Kind::SetAttr,
+            'H' => Kind::SetAttrRecursive,
+            'a' => Kind::SetAcl,
+            'A' => Kind::SetAclRecursive,
+            c => return Err(ParseTmpfilesError::InvalidEntry(c.to_string())),
+        };
+
+        Ok(kind)
+    }
+}
+
+impl Kind {
+    /// Whether the age field applies, which is for the types creating or ignoring directories.
+    pub fn is_cleaned(&self) -> bool {
+        matches!(
+            self,
+            Kind::Directory
+                | Kind::RemovableDirectory
+                | Kind::AdjustDirectory
+                | Kind::Subvolume
+                | Kind::SubvolumeQuota
+                | Kind::SubvolumeQuotaInherit
+                | Kind::Copy
+        )
+    }
+}
+
+/// The modifiers following the type character.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct Modifiers {
+    /// `!`, only applied at boot.
+    pub boot: bool,
+    /// `-`, failures to create are ignored.
+    pub ignore_errors: bool,
+    /// `+`, existing files are replaced or truncated.
+    pub force: bool,
+    /// `=`, a mismatching file type at the path is removed.
+    pub remove_mismatching: bool,
+    /// `~`, the argument is base64 encoded.
+    pub base64: bool,
+    /// `^`, the argument names a service credential.
+    pub credential: bool,
+}
+
+/// The mode field.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Mode {
+    pub mode: u32,
+    /// `~`, masked by the permissions of an existing file, not set as is.
+    pub masked: bool,
+    /// `:`, only set when the file is created.
+    pub only_create: bool,
+}
+
+impl FromStr for Mode {
+    type Err = ParseTmpfilesError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (only_create, mode) = match s.strip_prefix(':') {
+            Some(mode) => (true, mode),
+            None => (false, s),
+        };
+        let (masked, mode) = match mode.strip_prefix('~') {
+            Some(mode) => (true, mode),
+            None => (false, mode),
+        };
+
+        let mode = u32::from_str_radix(mode, 8)?;
+        if mode > 0o7777 {
+            return Err(ParseTmpfilesError::InvalidEntry(s.to_owned()));
+        }
+
+        Ok(Mode {
+            mode,
+            masked,
+            only_create,
+        })
+    }
+}
+
+/// The user or group field, a name or a numeric id.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Owner {
+    pub name: String,
+    /// `:`, only set when the file is created.
+    pub only_create: bool,
+}
+
+impl From<&str> for Owner {
+    fn from(s: &str) -> Self {
+        match s.strip_prefix(':') {
+            Some(name) => Owner {
+                name: name.to_owned(),
+                only_create: true,
+            },
+            None => Owner {
+                name: s.to_owned(),
+                only_create: false,
+            },
+        }
+    }
+}
+
+/// A timestamp the age of a file is computed from.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Timestamp {
+    Access,
+    Birth,
+    Change,
+    Modification,
+}
+
+impl Timestamp {
+    fn from_specifier(c: char) -> Option<Self> {
+        match c.to_ascii_lowercase() {
+            'a' => Some(Timestamp::Access),
+            'b' => Some(Timestamp::Birth),
+            'c' => Some(Timestamp::Change),
+            'm' => Some(Timestamp::Modification),
+            _ => None,
+        }
+    }
+}
+
+///
+/// The age field: files older than `span` by all the `files` timestamps, and directories older by
+/// all the `directories` ones, are removed when cleaning.
+///
+/// ```
+/// use std::time::Duration;
+///
+/// use unixism::tmpfiles::{Age, Timestamp};
+///
+/// let age = "~cM:10d".parse::<Age>().unwrap();
+///
+/// assert_eq!(Duration::from_secs(10 * 86400), age.span);
+/// assert_eq!(vec![Timestamp::Change], age.files);
+/// assert_eq!(vec![Timestamp::Modification], age.directories);
+/// assert!(age.keep_first_level);
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Age {
+    pub span: Duration,
+    pub files: Vec<Timestamp>,
+    pub directories: Vec<Timestamp>,
+    /// `~`, the entries directly in the directory are kept, only the deeper ones are cleaned.
+    pub keep_first_level: bool,
+}
+
+impl FromStr for Age {
+    type Err = ParseTmpfilesError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let invalid = || ParseTmpfilesError::InvalidEntry(s.to_owned());
+
+        let (keep_first_level, age) = match s.strip_prefix('~') {
+            Some(age) => (true, age),
+            None => (false, s),
+        };
+
+        let (files, directories, span) = match age.split_once(':') {
+            Some((specifiers, span)) => {
+                let (mut files, mut directories) = (Vec::new(), Vec::new());
+
+                for c in specifiers.chars() {
+                    let timestamp = Timestamp::from_specifier(c).ok_or_else(invalid)?;
+                    let timestamps = if c.is_ascii_uppercase() {
+                        &mut directories
+                    } else {
+                        &mut files
+                    };
+
+                    if timestamps.contains(&timestamp) {
+                        return Err(invalid());
+                    }
+                    timestamps.push(timestamp);
+                }
+
+                (files, directories, span)
+            }
+            None => (
+                vec![
+                    Timestamp::Access,
+                    Timestamp::Birth,
+                    Timestamp::Change,
+                    Timestamp::Modification,
+                ],
+                vec![Timestamp::Access, Timestamp::Birth, Timestamp::Modification],
+                age,
+            ),
+        };
+
+        Ok(Age {
+            span: systemdconf::parse_timespan(span).ok_or_else(invalid)?,
+            files,
+            directories,
+            keep_first_level,
+        })
+    }
+}
+
+/// A line of a tmpfiles.d file. The fields written as `-` or left out are `None`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Entry {
+    pub kind: Kind,
+    pub modifiers: Modifiers,
+    pub path: String,
+    pub mode: Option<Mode>,
+    pub user: Option<Owner>,
+    pub group: Option<Owner>,
+    pub age: Option<Age>,
+    pub argument: Option<String>,
+}
+
+impl FromStr for Entry {
+    type Err = ParseTmpfilesError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let invalid = || ParseTmpfilesError::InvalidEntry(s.to_owned());
+
+        let (kind, rest) = split_field(s).ok_or_else(invalid)?;
+        let (path, mut rest) = split_field(rest).ok_or_else(invalid)?;
+
+        let mut chars = kind.chars();
+        let kind = Kind::try_from(chars.next().ok_or_else(invalid)?)?;
+        let mut modifiers = Modifiers::default();
+        for c in chars {
+            let modifier = match c {
+                '!' => &mut modifiers.boot,
+                '-' => &mut modifiers.ignore_errors,
+                '+' => &mut modifiers.force,
+                '=' => &mut modifiers.remove_mismatching,
+                '~' => &mut modifiers.base64,
+                '^' => &mut modifiers.credential,
+                _ => return Err(invalid()),
+            };
+            *modifier = true;
+        }
+
+        let mut fields: [Option<String>; 4] = Default::default();
+        for field in &mut fields {
+            let Some((value, remaining)) = split_field(rest) else {
+                break;
+            };
+
+            *field = Some(value).filter(|value| value != "-");
+            rest = remaining;
+        }
+        let [mode, user, group, age] = fields;
+
+        let argument = rest.trim();
+
+        Ok(Entry {
+            kind,
+            modifiers,
+            path,
+            mode: mode.map(|mode| mode.parse()).transpose()?,
+            user: user.as_deref().map(Owner::from),
+            group: group.as_deref().map(Owner::from),
+            age: age.map(|age| age.parse()).transpose()?,
+            argument: (!argument.is_empty()).then(|| unescape(argument)),
+        })
+    }
+}
+
+/// Splits off the first whitespace separated field, removing its quotes and escapes.
+fn split_field(s: &str) -> Option<(String, &str)> {
+    let s = s.trim_start();
+    if s.is_empty() {
+        return None;
+    }
+
+    let mut field = String::new();
+    let mut quote = None;
+    let mut chars = s.char_indices();
+
+    while let Some((i, c)) = chars.next() {
+        match (quote, c) {
+            (None, c) if c.is_whitespace() => return Some((field, &s[i..])),
+            (None, '"' | '\'') => quote = Some(c),
+            (Some(q), c) if c == q => quote = None,
+            (Some('\''), c) => field.push(c),
+            (_, '\\') => field.push(chars.next()?.1),
+            (_, c) => field.push(c),
+        }
+    }
+
+    quote.is_none().then_some((field, ""))
+}
+
+/// Decodes the C escapes of the argument field.
+fn unescape(s: &str) -> String {
+    let mut unescaped = String::with_capacity(s.len());
+    let mut chars = s.chars();
+
+    while let Some(c) = chars.next() {
+        if c != '\\' {
+            unescaped.push(c);
+            continue;
+        }
+
+        match chars.next() {
+            Some('n') => unescaped.push('\n'),
+            Some('t') => unescaped.push('\t'),
+            Some('r') => unescaped.push('\r'),
+            Some('0') => unescaped.push('\0'),
+            Some(c) => unescaped.push(c),
+            None => unescaped.push('\\'),
+        }
+    }
+
+    unescaped
+}
+
+#[derive(Debug)]
+pub enum ParseTmpfilesError {
+    InvalidEntry(String),
+    ParseIntError(ParseIntError),
+    IOError(io::Error),
+}
+
+impl error::Error for ParseTmpfilesError {}
+
+impl fmt::Display for ParseTmpfilesError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::InvalidEntry(entry) => write!(f, "invalid entry: {}", entry),
+            Self::ParseIntError(error) => write!(f, "{}", error),
+            Self::IOError(error) => write!(f, "{}", error),
+        }
+    }
+}
+
+impl From<ParseIntError> for ParseTmpfilesError {
+    fn from(value: ParseIntError) -> Self {
+        ParseTmpfilesError::ParseIntError(value)
+    }
+}
+
+impl From<io::Error> for ParseTmpfilesError {
+    fn from(value: io::Error) -> Self {
+        ParseTmpfilesError::IOError(value)
+    }
+}
+
+///
+/// ```no_run
+/// let entries = unixism::tmpfiles::parse(std::fs::File::open("/usr/lib/tmpfiles.d/tmp.conf").unwrap()).unwrap();
+///
+/// for entry in entries {
+///     println!("{:?} {} {:?}", entry.kind, entry.path, entry.age);
+/// }
+/// ```
+pub fn parse<R>(reader: R) -> Result<impl Iterator<Item = Entry>, ParseTmpfilesError>
+where
+    R: io::Read,
+{
+    let mut entries = Vec::new();
+
+    for line in BufReader::new(reader).lines() {
+        let line = line?;
+        let line = line.trim();
+
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+
+        entries.push(line.parse::<Entry>()?);
+    }
+
+    Ok(entries.into_iter())
+}
+
+///
+/// Reads the `*.conf` files of `dirs` in file name order, as `systemd-tmpfiles` does.
+/// A file in an earlier directory overrides the file with the same name in later ones. Missing directories are skipped.
+/// When several entries have the same path, systemd-tmpfiles applies the first one.
+///
+/// ```no_run
+/// let entries = unixism::tmpfiles::load_at(&["/mnt/etc/tmpfiles.d", "/mnt/usr/lib/tmpfiles.d"]).unwrap();
+/// ```
+pub fn load_at<P>(dirs: &[P]) -> Result<Vec<Entry>, ParseTmpfilesError>
+where
+    P: AsRef<Path>,
+{
+    let mut entries = Vec::new();
+
+    for path in crate::dropin::files(dirs, ".conf")? {
+        entries.extend(parse(fs::File::open(path)?)?);
+    }
+
+    Ok(entries)
+}
+
+///
+/// Same as load_at, but reads the standard tmpfiles.d directories.
+///
+/// ```no_run
+/// let entries = unixism::tmpfiles::load().unwrap();
+/// ```
+pub fn load() -> Result<Vec<Entry>, ParseTmpfilesError> {
+    load_at(&CONFIG_DIRS)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Cursor;
+
+    use super::*;
+    use crate::tempdir::TempDir;
+
+    #[test]
+    fn it_parse() {
+        let entries = parse(Cursor::new(
+            r#"
+# Clear tmp directories separately, to make them easier to override
+q /tmp 1777 root root 10d
+D! /tmp/.X11-unix 1777 root root -
+d /var/tmp 1777 root root ~30d
+L+ /etc/mtab - - - - ../proc/self/mounts
+f~ /etc/motd.d/hello 0644 - - - aGVsbG8K
+w- "/sys/kernel/mm/transparent hugepage/enabled" - - - - never\n
+e /var/cache/app :~0750 :app - aB:1h
+x /tmp/systemd-private-*
+        "#,
+        ))
+        .unwrap()
+        .collect::<Vec<_>>();
+
+        assert_eq!(8, entries.len());
+        assert_eq!(
+            Entry {
+                kind: Kind::SubvolumeQuota,
+                modifiers: Modifiers::default(),
+                path: "/tmp".to_owned(),
+                mode: Some(Mode {
+                    mode: 0o1777,
+                    masked: false,
+                    only_create: false,
+                }),
+                user: Some(Owner::from("root")),
+                group: Some(Owner::from("root")),
+                age: Some("10d".parse().unwrap()),
+                argument: None,
+            },
+            entries[0]
+        );
+        assert_eq!(
+            Duration::from_secs(10 * 86400),
+            entries[0].age.as_ref().unwrap().span
+        );
+        assert!(entries[1].modifiers.boot);
+        assert_eq!(None, entries[1].age);
+        assert!(entries[2].age.as_ref().unwrap().keep_first_level);
+        assert!(entries[3].modifiers.force);
+        assert_eq!(Some("../proc/self/mounts"), entries[3].argument.as_deref());
+        assert!(entries[4].modifiers.base64);
+        assert!(entries[5].modifiers.ignore_errors);
+        assert_eq!(
+            "/sys/kernel/mm/transparent hugepage/enabled",
+            entries[5].path
+        );
+        assert_eq!(Some("never\n"), entries[5].argument.as_deref());
+
+        let mode = entries[6].mode.unwrap();
+        assert_eq!(0o750, mode.mode);
+        assert!(mode.masked && mode.only_create);
+        assert!(entries[6].user.as_ref().unwrap().only_create);
+        let age = entries[6].age.as_ref().unwrap();
+        assert_eq!(vec![Timestamp::Access], age.files);
+        assert_eq!(vec![Timestamp::Birth], age.directories);
+        assert_eq!(Duration::from_secs(3600), age.span);
+
+        assert_eq!(Kind::Ignore, entries[7].kind);
+        assert_eq!(None, entries[7].mode);
+        assert!(
+            entries
+                .iter()
+                .filter(|entry| entry.kind.is_cleaned())
+                .count()
+                == 4
+        );
+
+        assert!(parse(Cursor::new("y /tmp")).is_err());
+        assert!(parse(Cursor::new("d")).is_err());
+        assert!(parse(Cursor::new("d? /tmp")).is_err());
+        assert!(parse(Cursor::new("d /tmp 0999")).is_err());
+        assert!(parse(Cursor::new("d /tmp 17777")).is_err());
+        assert!(parse(Cursor::new("d /tmp - - - aa:1d")).is_err());
+        assert!(parse(Cursor::new("d /tmp - - - x:1d")).is_err());
+        assert!(parse(Cursor::new("d /tmp - - - 10fortnights")).is_err());
+        assert!(parse(Cursor::new("d \"/tmp")).is_err());
+    }
+
+    #[test]
+    fn it_load_at() {
+        let root = TempDir::new("tmpfiles");
+        let (etc, usr) = (root.join("etc"), root.join("usr"));
+        fs::create_dir_all(&etc).unwrap();
+        fs::create_dir_all(&usr).unwrap();
+
+        fs::write(usr.join("tmp.conf"), "q /tmp 1777 root root 10d\n").unwrap();
+        fs::write(usr.join("var.conf"), "d /var/log 0755 - - -\n").unwrap();
+        fs::write(etc.join("tmp.conf"), "q /tmp 1777 root root 1d\n").unwrap();
+
+        let entries = load_at(&[&etc, &usr]).unwrap();
+
+        assert_eq!(2, entries.len());
+        assert_eq!(
+            Duration::from_secs(86400),
+            entries[0].age.as_ref().unwrap().span
+        );
+        assert_eq!("/var/log", entries[1].path);
+    }
+}
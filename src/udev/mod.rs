@@ -0,0 +1,692 @@
+use std::{
+    error, fmt, fs,
+    io::{self, BufRead, BufReader},
+    path::Path,
+    str::FromStr,
+};
+
+/// Directories udev reads rules from, the most important first.
+pub const CONFIG_DIRS: [&str; 4] = [
+    "/etc/udev/rules.d",
+    "/run/udev/rules.d",
+    "/usr/lib/udev/rules.d",
+    "/lib/udev/rules.d",
+];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Operator {
+    /// `==`
+    Equal,
+    /// `!=`
+    NotEqual,
+    /// `=`
+    Assign,
+    /// `+=`
+    Add,
+    /// `-=`
+    Remove,
+    /// `:=`, assigns and forbids later changes.
+    AssignFinal,
+}
+
+impl Operator {
+    pub fn is_match(&self) -> bool {
+        matches!(self, Operator::Equal | Operator::NotEqual)
+    }
+}
+
+impl FromStr for Operator {
+    type Err = ParseUdevError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "==" => Ok(Operator::Equal),
+            "!=" => Ok(Operator::NotEqual),
+            "=" => Ok(Operator::Assign),
+            "+=" => Ok(Operator::Add),
+            "-=" => Ok(Operator::Remove),
+            ":=" => Ok(Operator::AssignFinal),
+            operator => Err(ParseUdevError::InvalidEntry(operator.to_owned())),
+        }
+    }
+}
+
+impl fmt::Display for Operator {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Operator::Equal => write!(f, "=="),
+            Operator::NotEqual => write!(f, "!="),
+            Operator::Assign => write!(f, "="),
+            Operator::Add => write!(f, "+="),
+            Operator::Remove => write!(f, "-="),
+            Operator::AssignFinal => write!(f, ":="),
+        }
+    }
+}
+
+/// The key of a rule pair, with the attribute given in braces for the keys which take one.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Key {
+    Action,
+    Devpath,
+    Kernel,
+    Kernels,
+    Subsystem,
+    Subsystems,
+    Driver,
+    Drivers,
+    Attr(String),
+    Attrs(String),
+    Sysctl(String),
+    Env(String),
+    Const(String),
+    Tag,
+    Tags,
+    Test(Option<String>),
+    Program,
+    Result,
+    Name,
+    Symlink,
+    Owner,
+    Group,
+    Mode,
+    SecLabel(String),
+    Label,
+    Goto,
+    Import(String),
+    Run(Option<String>),
+    Options,
+    Other(String),
+}
+
+impl Key {
+    /// Whether the key can only be compared, like `KERNEL` or `ATTRS{..}`.
+    pub fn is_match_only(&self) -> bool {
+        matches!(
+            self,
+            Key::Action
+                | Key::Devpath
+                | Key::Kernel
+                | Key::Kernels
+                | Key::Subsystem
+                | Key::Subsystems
+                | Key::Driver
+                | Key::Drivers
+                | Key::Attrs(_)
+                | Key::Const(_)
+                | Key::Tags
+                | Key::Test(_)
+                | Key::Program
+                | Key::Result
+        )
+    }
+
+    /// Whether the key can only be assigned, like `OWNER` or `GOTO`.
+    pub fn is_assign_only(&self) -> bool {
+        matches!(
+            self,
+            Key::Owner
+                | Key::Group
+                | Key::Mode
+                | Key::SecLabel(_)
+                | Key::Label
+                | Key::Goto
+                | Key::Import(_)
+                | Key::Run(_)
+                | Key::Options
+        )
+    }
+}
+
+impl FromStr for Key {
+    type Err = ParseUdevError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (name, attribute) = match s.split_once('{') {
+            Some((name, attribute)) => {
+                let attribute = attribute
+                    .strip_suffix('}')
+                    .filter(|attribute| !attribute.is_empty())
+                    .ok_or_else(|| ParseUdevError::InvalidEntry(s.to_owned()))?;
+
+                (name, Some(attribute.to_owned()))
+            }
+            None => (s, None),
+        };
+
+        let required = |attribute: Option<String>| {
+            attribute.ok_or_else(|| ParseUdevError::InvalidEntry(s.to_owned()))
+        };
+
+        let key = match name {
+            "ATTR" => Key::Attr(required(attribute)?),
+            "ATTRS" => Key::Attrs(required(attribute)?),
+            "SYSCTL" => Key::Sysctl(required(attribute)?),
+            "ENV" => Key::Env(required(attribute)?),
+            "CONST" => Key::Const(required(attribute)?),
+            "SECLABEL" => Key::SecLabel(required(attribute)?),
+            "IMPORT" => Key::Import(required(attribute)?),
+            "TEST" => Key::Test(attribute),
+            "RUN" => Key::Run(attribute),
+            _ if attribute.is_some() => Key::Other(s.to_owned()),
+            "ACTION" => Key::Action,
+            "DEVPATH" => Key::Devpath,
+            "KERNEL" => Key::Kernel,
+            "KERNELS" => Key::Kernels,
+            "SUBSYSTEM" => Key::Subsystem,
+            "SUBSYSTEMS" => Key::Subsystems,
+            "DRIVER" => Key::Driver,
+            "DRIVERS" => Key::Drivers,
+            "TAG" => Key::Tag,
+            "TAGS" => Key::Tags,
+            "PROGRAM" => Key::Program,
+            "RESULT" => Key::Result,
+            "NAME" => Key::Name,
+            "SYMLINK" => Key::Symlink,
+            "OWNER" => Key::Owner,
+            "GROUP" => Key::Group,
+            "MODE" => Key::Mode,
+            "LABEL" => Key::Label,
+            "GOTO" => Key::Goto,
+            "OPTIONS" => Key::Options,
+            _ => Key::Other(s.to_owned()),
+        };
+
+        Ok(key)
+    }
+}
+
+impl fmt::Display for Key {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Key::Action => write!(f, "ACTION"),
+            Key::Devpath => write!(f, "DEVPATH"),
+            Key::Kernel => write!(f, "KERNEL"),
+            Key::Kernels => write!(f, "KERNELS"),
+            Key::Subsystem => write!(f, "SUBSYSTEM"),
+            Key::Subsystems => write!(f, "SUBSYSTEMS"),
+            Key::Driver => write!(f, "DRIVER"),
+            Key::Drivers => write!(f, "DRIVERS"),
+            Key::Attr(attribute) => write!(f, "ATTR{{{}}}", attribute),
+            Key::Attrs(attribute) => write!(f, "ATTRS{{{}}}", attribute),
+            Key::Sysctl(attribute) => write!(f, "SYSCTL{{{}}}", attribute),
+            Key::Env(attribute) => write!(f, "ENV{{{}}}", attribute),
+            Key::Const(attribute) => write!(f, "CONST{{{}}}", attribute),
+            Key::Tag => write!(f, "TAG"),
+            Key::Tags => write!(f, "TAGS"),
+            Key::Test(None) => write!(f, "TEST"),
+            Key::Test(Some(mode)) => write!(f, "TEST{{{}}}", mode),
+            Key::Program => write!(f, "PROGRAM"),
+            Key::Result => write!(f, "RESULT"),
+            Key::Name => write!(f, "NAME"),
+            Key::Symlink => write!(f, "SYMLINK"),
+            Key::Owner => write!(f, "OWNER"),
+            Key::Group => write!(f, "GROUP"),
+            Key::Mode => write!(f, "MODE"),
+            Key::SecLabel(module) => write!(f, "SECLABEL{{{}}}", module),
+            Key::Label => write!(f, "LABEL"),
+            Key::Goto => write!(f, "GOTO"),
+            Key::Import(kind) => write!(f, "IMPORT{{{}}}", kind),
+            Key::Run(None) => write!(f, "RUN"),
+            Key::Run(Some(kind)) => write!(f, "RUN{{{}}}", kind),
+            Key::Options => write!(f, "OPTIONS"),
+            Key::Other(key) => write!(f, "{}", key),
+        }
+    }
+}
+
+/// A `KEY op "value"` pair of a rule, the value unquoted.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Pair {
+    pub key: Key,
+    pub operator: Operator,
+    pub value: String,
+}
+
+impl fmt::Display for Pair {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}{}", self.key, self.operator)?;
+
+        // Plain strings can't hold control characters nor end with a backslash.
+        let escaped = self.value.contains(['"', '\n', '\t', '\r']) || self.value.ends_with('\\');
+        if !escaped {
+            return write!(f, "\"{}\"", self.value);
+        }
+
+        write!(f, "e\"")?;
+        for c in self.value.chars() {
+            match c {
+                '"' => write!(f, "\\\"")?,
+                '\\' => write!(f, "\\\\")?,
+                '\n' => write!(f, "\\n")?,
+                '\t' => write!(f, "\\t")?,
+                '\r' => write!(f, "\\r")?,
+                c => write!(f, "{}", c)?,
+            }
+        }
+        write!(f, "\"")
+    }
+}
+
+///
+/// A rule: the pairs of a line, which apply their assignments when all their matches succeed.
+///
+/// ```
+/// let rule = r#"SUBSYSTEM=="net", ACTION=="add", ATTR{address}=="00:11:22:33:44:55", NAME="lan0""#
+///     .parse::<unixism::udev::Rule>()
+///     .unwrap();
+///
+/// assert_eq!(3, rule.matches().count());
+/// assert_eq!(1, rule.assignments().count());
+/// ```
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct Rule {
+    pub pairs: Vec<Pair>,
+}
+
+impl Rule {
+    pub fn matches(&self) -> impl Iterator<Item = &Pair> {
+        self.pairs.iter().filter(|pair| pair.operator.is_match())
+    }
+
+    pub fn assignments(&self) -> impl Iterator<Item = &Pair> {
+        self.pairs.iter().filter(|pair| !pair.operator.is_match())
+    }
+
+    /// Returns the first pair of the key.
+    pub fn get(&self, key: &Key) -> Option<&Pair> {
+        self.pairs.iter().find(|pair| &pair.key == key)
+    }
+
+    pub fn label(&self) -> Option<&str> {
+        self.get(&Key::Label).map(|pair| pair.value.as_str())
+    }
+
+    pub fn goto(&self) -> Option<&str> {
+        self.get(&Key::Goto).map(|pair| pair.value.as_str())
+    }
+}
+
+impl FromStr for Rule {
+    type Err = ParseUdevError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let invalid = || ParseUdevError::InvalidEntry(s.to_owned());
+        let mut pairs = Vec::new();
+        let mut rest = s;
+
+        loop {
+            rest = rest.trim_start_matches(|c: char| c == ',' || c.is_whitespace());
+            if rest.is_empty() {
+                break;
+            }
+
+            let end = key_end(rest).ok_or_else(invalid)?;
+            let key = rest[..end].parse::<Key>()?;
+            rest = rest[end..].trim_start();
+
+            let end = rest
+                .find(|c: char| !"=!+-:".contains(c))
+                .ok_or_else(invalid)?;
+            let operator = rest[..end].parse::<Operator>().map_err(|_| invalid())?;
+            rest = rest[end..].trim_start();
+
+            let allowed = if operator.is_match() {
+                !key.is_assign_only()
+            } else {
+                !key.is_match_only()
+            };
+            if !allowed {
+                return Err(invalid());
+            }
+
+            let (value, remaining) = unquote(rest).ok_or_else(invalid)?;
+            rest = remaining;
+
+            if !rest.is_empty() && !rest.starts_with(|c: char| c == ',' || c.is_whitespace()) {
+                return Err(invalid());
+            }
+
+            pairs.push(Pair {
+                key,
+                operator,
+                value,
+            });
+        }
+
+        if pairs.is_empty() {
+            return Err(invalid());
+        }
+
+        Ok(Rule { pairs })
+    }
+}
+
+impl fmt::Display for Rule {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (i, pair) in self.pairs.iter().enumerate() {
+            if i > 0 {
+                write!(f, ", ")?;
+            }
+            write!(f, "{}", pair)?;
+        }
+
+        Ok(())
+    }
+}
+
+/// Returns where the key ends: its name, then the attribute in braces, which may contain anything but `}`.
+fn key_end(s: &str) -> Option<usize> {
+    let name = s
+        .find(|c: char| !(c.is_ascii_uppercase() || c.is_ascii_digit() || c == '_'))
+        .unwrap_or(s.len());
+
+    if name == 0 {
+        return None;
+    }
+
+    if s[name..].starts_with('{') {
+        return s[name..].find('}').map(|end| name + end + 1);
+    }
+
+    Some(name)
+}
+
+/// Reads a `"plain"` or `e"escaped"` string, returning its value and what follows it.
+fn unquote(s: &str) -> Option<(String, &str)> {
+    let (escaped, s) = match s.strip_prefix('e') {
+        Some(s) => (true, s),
+        None => (false, s),
+    };
+    let mut chars = s.strip_prefix('"')?.char_indices();
+    let mut value = String::new();
+
+    while let Some((_, c)) = chars.next() {
+        match c {
+            '"' => return Some((value, chars.as_str())),
+            '\\' if escaped => match chars.next()?.1 {
+                'n' => value.push('\n'),
+                't' => value.push('\t'),
+                'r' => value.push('\r'),
+                'a' => value.push('\x07'),
+                c => value.push(c),
+            },
+            '\\' if chars.as_str().starts_with('"') => {
+                chars.next();
+                value.push('"');
+            }
+            c => value.push(c),
+        }
+    }
+
+    None
+}
+
+///
+/// Returns the `GOTO` targets without a `LABEL` after them. udev only jumps forward within a file,
+/// so the rules of a single file are expected.
+///
+/// ```no_run
+/// let rules = unixism::udev::parse(std::fs::File::open("/etc/udev/rules.d/70-net.rules").unwrap())
+///     .unwrap()
+///     .collect::<Vec<_>>();
+///
+/// for target in unixism::udev::unresolved_gotos(&rules) {
+///     println!("missing LABEL=\"{}\"", target);
+/// }
+/// ```
+pub fn unresolved_gotos(rules: &[Rule]) -> Vec<&str> {
+    rules
+        .iter()
+        .enumerate()
+        .filter_map(|(i, rule)| Some((i, rule.goto()?)))
+        .filter(|(i, target)| {
+            !rules[i + 1..]
+                .iter()
+                .any(|rule| rule.label() == Some(*target))
+        })
+        .map(|(_, target)| target)
+        .collect()
+}
+
+#[derive(Debug)]
+pub enum ParseUdevError {
+    InvalidEntry(String),
+    IOError(io::Error),
+}
+
+impl error::Error for ParseUdevError {}
+
+impl fmt::Display for ParseUdevError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::InvalidEntry(entry) => write!(f, "invalid rule: {}", entry),
+            Self::IOError(error) => write!(f, "{}", error),
+        }
+    }
+}
+
+impl From<io::Error> for ParseUdevError {
+    fn from(value: io::Error) -> Self {
+        ParseUdevError::IOError(value)
+    }
+}
+
+///
+/// Parses the rules of a file, joining the lines ending with a backslash.
+///
+/// ```no_run
+/// let rules = unixism::udev::parse(std::fs::File::open("/etc/udev/rules.d/70-net.rules").unwrap()).unwrap();
+///
+/// for rule in rules {
+///     println!("{}", rule);
+/// }
+/// ```
+pub fn parse<R>(reader: R) -> Result<impl Iterator<Item = Rule>, ParseUdevError>
+where
+    R: io::Read,
+{
+    let mut rules = Vec::new();
+    let mut pending = String::new();
+
+    for line in BufReader::new(reader).lines() {
+        let line = line?;
+        let line = line.trim();
+
+        if pending.is_empty() && (line.is_empty() || line.starts_with('#')) {
+            continue;
+        }
+
+        if let Some(line) = line.strip_suffix('\\') {
+            pending.push_str(line);
+            continue;
+        }
+
+        pending.push_str(line);
+        rules.push(pending.parse::<Rule>()?);
+        pending.clear();
+    }
+
+    if !pending.is_empty() {
+        rules.push(pending.parse::<Rule>()?);
+    }
+
+    Ok(rules.into_iter())
+}
+
+///
+/// Reads the `*.rules` files of `dirs` in file name order, as udev does.
+/// A file in an earlier directory overrides the file with the same name in later ones. Missing directories are skipped.
+///
+/// ```no_run
+/// let rules = unixism::udev::load_at(&["/mnt/etc/udev/rules.d", "/mnt/usr/lib/udev/rules.d"]).unwrap();
+/// ```
+pub fn load_at<P>(dirs: &[P]) -> Result<Vec<Rule>, ParseUdevError>
+where
+    P: AsRef<Path>,
+{
+    let mut rules = Vec::new();
+
+    for path in crate::dropin::files(dirs, ".rules")? {
+        rules.extend(parse(fs::File::open(path)?)?);
+    }
+
+    Ok(rules)
+}
+
+///
+/// Same as load_at, but reads the standard udev rules directories.
+///
+/// ```no_run
+/// let rules = unixism::udev::load().unwrap();
+/// ```
+pub fn load() -> Result<Vec<Rule>, ParseUdevError> {
+    load_at(&CONFIG_DIRS)
+}
+
+///
+/// Writes the rules one per line.
+///
+/// ```no_run
+/// let rules = unixism::udev::parse(std::fs::File::open("/etc/udev/rules.d/70-net.rules").unwrap())
+///     .unwrap()
+///     .filter(|rule| rule.get(&unixism::udev::Key::Name).is_none())
+///     .collect::<Vec<_>>();
+///
+/// unixism::udev::write(std::fs::File::create("/etc/udev/rules.d/70-net.rules").unwrap(), &rules).unwrap();
+/// ```
+pub fn write<W>(mut writer: W, rules: &[Rule]) -> io::Result<()>
+where
+    W: io::Write,
+{
+    for rule in rules {
+        writeln!(writer, "{}", rule)?;
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Cursor;
+
+    use super::*;
+    use crate::tempdir::TempDir;
+
+    #[test]
+    fn it_parse() {
+        let rules = parse(Cursor::new(
+            r#"
+# do not edit this file, it will be overwritten on update
+
+ACTION!="add|change", GOTO="net_end"
+SUBSYSTEM=="net", ATTR{address}=="00:11:22:33:44:55", \
+    NAME="lan0"
+KERNEL=="sd*", ATTRS{queue/rotational}=="0", ATTR{queue/scheduler}="none"
+ENV{ID_FS_LABEL}=="backup", SYMLINK+="backup", RUN{program}+="/usr/bin/logger \"backup\""
+ENV{MESSAGE}=e"one\ttwo\n"
+LABEL="net_end"
+        "#,
+        ))
+        .unwrap()
+        .collect::<Vec<_>>();
+
+        assert_eq!(6, rules.len());
+        assert_eq!(
+            Pair {
+                key: Key::Action,
+                operator: Operator::NotEqual,
+                value: "add|change".to_owned(),
+            },
+            rules[0].pairs[0]
+        );
+        assert_eq!(Some("net_end"), rules[0].goto());
+        assert_eq!(
+            Some(&Pair {
+                key: Key::Name,
+                operator: Operator::Assign,
+                value: "lan0".to_owned(),
+            }),
+            rules[1].get(&Key::Name)
+        );
+        assert_eq!(
+            Key::Attrs("queue/rotational".to_owned()),
+            rules[2].pairs[1].key
+        );
+        assert_eq!(
+            "/usr/bin/logger \"backup\"",
+            rules[3]
+                .get(&Key::Run(Some("program".to_owned())))
+                .unwrap()
+                .value
+        );
+        assert_eq!(Operator::Add, rules[3].pairs[1].operator);
+        assert_eq!("one\ttwo\n", rules[4].pairs[0].value);
+        assert_eq!(Some("net_end"), rules[5].label());
+        assert!(unresolved_gotos(&rules).is_empty());
+        assert_eq!(vec!["net_end"], unresolved_gotos(&rules[..5]));
+
+        assert!(parse(Cursor::new(r#"KERNEL="sda""#)).is_err());
+        assert!(parse(Cursor::new(r#"OWNER=="root""#)).is_err());
+        assert!(parse(Cursor::new(r#"ATTR=="x""#)).is_err());
+        assert!(parse(Cursor::new(r#"NAME="unterminated"#)).is_err());
+        assert!(parse(Cursor::new(r#"NAME=lan0"#)).is_err());
+        assert!(parse(Cursor::new(r#"NAME~"lan0""#)).is_err());
+    }
+
+    #[test]
+    fn it_write() {
+        let rules = parse(Cursor::new(
+            r#"
+KERNEL=="sd*",ATTR{queue/scheduler}:="none"
+ENV{MESSAGE}=e"say \"hi\"\n"
+TEST{0644}=="/dev/null", OPTIONS+="last_rule"
+        "#,
+        ))
+        .unwrap()
+        .collect::<Vec<_>>();
+
+        let mut written = Vec::new();
+        write(&mut written, &rules).unwrap();
+
+        assert_eq!(
+            "KERNEL==\"sd*\", ATTR{queue/scheduler}:=\"none\"\nENV{MESSAGE}=e\"say \\\"hi\\\"\\n\"\nTEST{0644}==\"/dev/null\", OPTIONS+=\"last_rule\"\n",
+            String::from_utf8(written.clone()).unwrap()
+        );
+        assert_eq!(
+            rules,
+            parse(Cursor::new(written)).unwrap().collect::<Vec<_>>()
+        );
+    }
+
+    #[test]
+    fn it_load_at() {
+        let root = TempDir::new("udev");
+        let (etc, usr) = (root.join("etc"), root.join("usr"));
+        fs::create_dir_all(&etc).unwrap();
+        fs::create_dir_all(&usr).unwrap();
+
+        fs::write(
+            usr.join("50-default.rules"),
+            "KERNEL==\"null\", MODE=\"0666\"\n",
+        )
+        .unwrap();
+        fs::write(
+            usr.join("60-vendor.rules"),
+            "KERNEL==\"sd*\", GROUP=\"disk\"\n",
+        )
+        .unwrap();
+        fs::write(
+            etc.join("50-default.rules"),
+            "KERNEL==\"null\", MODE=\"0600\"\n",
+        )
+        .unwrap();
+        fs::write(etc.join("README"), "not rules\n").unwrap();
+
+        let rules = load_at(&[&etc, &usr]).unwrap();
+
+        assert_eq!(2, rules.len());
+        assert_eq!("0600", rules[0].get(&Key::Mode).unwrap().value);
+        assert_eq!("disk", rules[1].get(&Key::Group).unwrap().value);
+    }
+}
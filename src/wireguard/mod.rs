@@ -0,0 +1,556 @@
+use std::{
+    error, fmt, fs,
+    io::{self, BufRead, BufReader},
+    net::IpAddr,
+    num::ParseIntError,
+    str::FromStr,
+};
+
+const BASE64: &[u8; 64] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+
+/// A Curve25519 key or a preshared key, written in base64.
+#[derive(Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Key(pub [u8; 32]);
+
+impl FromStr for Key {
+    type Err = ParseWireguardError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let invalid = || ParseWireguardError::InvalidKey(s.to_owned());
+
+        let bytes = s.as_bytes();
+        if bytes.len() != 44 || bytes[43] != b'=' {
+            return Err(invalid());
+        }
+
+        let mut key = [0u8; 32];
+        let mut bits = 0u32;
+        let mut count = 0;
+        let mut i = 0;
+
+        for c in &bytes[..43] {
+            let value = BASE64.iter().position(|b| b == c).ok_or_else(invalid)? as u32;
+
+            bits = bits << 6 | value;
+            count += 6;
+
+            if count >= 8 {
+                count -= 8;
+                key[i] = (bits >> count) as u8;
+                i += 1;
+            }
+        }
+
+        // The last character carries two padding bits, which must be zero.
+        if bits & ((1 << count) - 1) != 0 {
+            return Err(invalid());
+        }
+
+        Ok(Key(key))
+    }
+}
+
+impl fmt::Display for Key {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for chunk in self.0.chunks(3) {
+            let bits = chunk
+                .iter()
+                .enumerate()
+                .fold(0u32, |bits, (i, b)| bits | (*b as u32) << (16 - 8 * i));
+
+            for i in 0..4 {
+                if i <= chunk.len() {
+                    let index = (bits >> (18 - 6 * i)) & 0x3f;
+                    write!(f, "{}", BASE64[index as usize] as char)?;
+                } else {
+                    write!(f, "=")?;
+                }
+            }
+        }
+
+        Ok(())
+    }
+}
+
+impl fmt::Debug for Key {
+    /// Keys are secrets, so only the first characters are shown.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Key({}…)", &self.to_string()[..8])
+    }
+}
+
+/// An address with a prefix length, as in `Address` and `AllowedIPs`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Cidr {
+    pub address: IpAddr,
+    pub prefix: u8,
+}
+
+impl FromStr for Cidr {
+    type Err = ParseWireguardError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let invalid = || ParseWireguardError::InvalidEntry(s.to_owned());
+
+        let (address, prefix) = match s.split_once('/') {
+            Some((address, prefix)) => (address, Some(prefix.parse::<u8>()?)),
+            None => (s, None),
+        };
+
+        let address = address.parse::<IpAddr>().map_err(|_| invalid())?;
+        let max = if address.is_ipv4() { 32 } else { 128 };
+        let prefix = prefix.unwrap_or(max);
+
+        if prefix > max {
+            return Err(invalid());
+        }
+
+        Ok(Cidr { address, prefix })
+    }
+}
+
+impl fmt::Display for Cidr {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}/{}", self.address, self.prefix)
+    }
+}
+
+/// The `[Interface]` section, including the wg-quick extensions.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct Interface {
+    pub private_key: Option<Key>,
+    pub listen_port: Option<u16>,
+    pub fwmark: Option<u32>,
+    pub addresses: Vec<Cidr>,
+    /// DNS servers and search domains, the two are mixed in wg-quick.
+    pub dns: Vec<String>,
+    pub mtu: Option<u32>,
+    pub table: Option<String>,
+    pub pre_up: Vec<String>,
+    pub post_up: Vec<String>,
+    pub pre_down: Vec<String>,
+    pub post_down: Vec<String>,
+    pub save_config: bool,
+    /// Keys this crate doesn't know, kept for writing the file back.
+    pub other: Vec<(String, String)>,
+}
+
+/// A `[Peer]` section.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Peer {
+    pub public_key: Key,
+    pub preshared_key: Option<Key>,
+    pub allowed_ips: Vec<Cidr>,
+    /// The `host:port` of the peer, the host may be a name resolved by wg-quick.
+    pub endpoint: Option<String>,
+    pub persistent_keepalive: Option<u16>,
+    pub other: Vec<(String, String)>,
+}
+
+/// A WireGuard configuration file.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct Config {
+    pub interface: Interface,
+    pub peers: Vec<Peer>,
+}
+
+impl Config {
+    pub fn peer(&self, public_key: &Key) -> Option<&Peer> {
+        self.peers
+            .iter()
+            .find(|peer| &peer.public_key == public_key)
+    }
+}
+
+impl fmt::Display for Config {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let interface = &self.interface;
+
+        writeln!(f, "[Interface]")?;
+        if let Some(key) = &interface.private_key {
+            writeln!(f, "PrivateKey = {}", key)?;
+        }
+        if let Some(port) = interface.listen_port {
+            writeln!(f, "ListenPort = {}", port)?;
+        }
+        if let Some(fwmark) = interface.fwmark {
+            writeln!(f, "FwMark = {}", fwmark)?;
+        }
+        if !interface.addresses.is_empty() {
+            writeln!(f, "Address = {}", join(&interface.addresses))?;
+        }
+        if !interface.dns.is_empty() {
+            writeln!(f, "DNS = {}", interface.dns.join(", "))?;
+        }
+        if let Some(mtu) = interface.mtu {
+            writeln!(f, "MTU = {}", mtu)?;
+        }
+        if let Some(table) = &interface.table {
+            writeln!(f, "Table = {}", table)?;
+        }
+        for (key, commands) in [
+            ("PreUp", &interface.pre_up),
+            ("PostUp", &interface.post_up),
+            ("PreDown", &interface.pre_down),
+            ("PostDown", &interface.post_down),
+        ] {
+            for command in commands {
+                writeln!(f, "{} = {}", key, command)?;
+            }
+        }
+        if interface.save_config {
+            writeln!(f, "SaveConfig = true")?;
+        }
+        for (key, value) in &interface.other {
+            writeln!(f, "{} = {}", key, value)?;
+        }
+
+        for peer in &self.peers {
+            writeln!(f)?;
+            writeln!(f, "[Peer]")?;
+            writeln!(f, "PublicKey = {}", peer.public_key)?;
+            if let Some(key) = &peer.preshared_key {
+                writeln!(f, "PresharedKey = {}", key)?;
+            }
+            if !peer.allowed_ips.is_empty() {
+                writeln!(f, "AllowedIPs = {}", join(&peer.allowed_ips))?;
+            }
+            if let Some(endpoint) = &peer.endpoint {
+                writeln!(f, "Endpoint = {}", endpoint)?;
+            }
+            if let Some(keepalive) = peer.persistent_keepalive {
+                writeln!(f, "PersistentKeepalive = {}", keepalive)?;
+            }
+            for (key, value) in &peer.other {
+                writeln!(f, "{} = {}", key, value)?;
+            }
+        }
+
+        Ok(())
+    }
+}
+
+fn join(cidrs: &[Cidr]) -> String {
+    cidrs
+        .iter()
+        .map(Cidr::to_string)
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+#[derive(Debug)]
+pub enum ParseWireguardError {
+    InvalidEntry(String),
+    InvalidKey(String),
+    MissingPublicKey,
+    ParseIntError(ParseIntError),
+    IOError(io::Error),
+}
+
+impl error::Error for ParseWireguardError {}
+
+impl fmt::Display for ParseWireguardError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::InvalidEntry(entry) => write!(f, "invalid entry: {}", entry),
+            Self::InvalidKey(key) => write!(f, "invalid key: {}", key),
+            Self::MissingPublicKey => write!(f, "peer without a public key"),
+            Self::ParseIntError(error) => write!(f, "{}", error),
+            Self::IOError(error) => write!(f, "{}", error),
+        }
+    }
+}
+
+impl From<ParseIntError> for ParseWireguardError {
+    fn from(value: ParseIntError) -> Self {
+        ParseWireguardError::ParseIntError(value)
+    }
+}
+
+impl From<io::Error> for ParseWireguardError {
+    fn from(value: io::Error) -> Self {
+        ParseWireguardError::IOError(value)
+    }
+}
+
+#[derive(Default)]
+struct PeerBuilder {
+    public_key: Option<Key>,
+    preshared_key: Option<Key>,
+    allowed_ips: Vec<Cidr>,
+    endpoint: Option<String>,
+    persistent_keepalive: Option<u16>,
+    other: Vec<(String, String)>,
+}
+
+impl PeerBuilder {
+    fn build(self) -> Result<Peer, ParseWireguardError> {
+        Ok(Peer {
+            public_key: self
+                .public_key
+                .ok_or(ParseWireguardError::MissingPublicKey)?,
+            preshared_key: self.preshared_key,
+            allowed_ips: self.allowed_ips,
+            endpoint: self.endpoint,
+            persistent_keepalive: self.persistent_keepalive,
+            other: self.other,
+        })
+    }
+}
+
+enum Section {
+    None,
+    Interface,
+    Peer(PeerBuilder),
+}
+
+fn list<T>(value: &str) -> Result<Vec<T>, ParseWireguardError>
+where
+    T: FromStr<Err = ParseWireguardError>,
+{
+    value
+        .split(',')
+        .map(str::trim)
+        .filter(|item| !item.is_empty())
+        .map(str::parse)
+        .collect()
+}
+
+///
+/// Parses a configuration in the format of `wg-quick(8)`, keys are case-insensitive.
+///
+/// ```no_run
+/// let config = unixism::wireguard::parse(std::fs::File::open("/etc/wireguard/wg0.conf").unwrap()).unwrap();
+///
+/// for peer in &config.peers {
+///     println!("{} {:?}", peer.public_key, peer.allowed_ips);
+/// }
+/// ```
+pub fn parse<R>(reader: R) -> Result<Config, ParseWireguardError>
+where
+    R: io::Read,
+{
+    let mut config = Config::default();
+    let mut section = Section::None;
+
+    for line in BufReader::new(reader).lines() {
+        let line = line?;
+        let line = line.split('#').next().unwrap_or_default().trim();
+
+        if line.is_empty() {
+            continue;
+        }
+
+        if line.starts_with('[') {
+            if let Section::Peer(peer) = std::mem::replace(&mut section, Section::None) {
+                config.peers.push(peer.build()?);
+            }
+
+            section = match line.to_ascii_lowercase().as_str() {
+                "[interface]" => Section::Interface,
+                "[peer]" => Section::Peer(PeerBuilder::default()),
+                _ => return Err(ParseWireguardError::InvalidEntry(line.to_owned())),
+            };
+
+            continue;
+        }
+
+        let (key, value) = line
+            .split_once('=')
+            .map(|(key, value)| (key.trim(), value.trim()))
+            .ok_or_else(|| ParseWireguardError::InvalidEntry(line.to_owned()))?;
+
+        match &mut section {
+            Section::None => return Err(ParseWireguardError::InvalidEntry(line.to_owned())),
+            Section::Interface => {
+                let interface = &mut config.interface;
+
+                match key.to_ascii_lowercase().as_str() {
+                    "privatekey" => interface.private_key = Some(value.parse()?),
+                    "listenport" => interface.listen_port = Some(value.parse()?),
+                    "fwmark" => {
+                        interface.fwmark = match value {
+                            "off" => None,
+                            value => Some(match value.strip_prefix("0x") {
+                                Some(hex) => u32::from_str_radix(hex, 16)?,
+                                None => value.parse()?,
+                            }),
+                        }
+                    }
+                    "address" => interface.addresses.extend(list::<Cidr>(value)?),
+                    "dns" => interface.dns.extend(
+                        value
+                            .split(',')
+                            .map(str::trim)
+                            .filter(|dns| !dns.is_empty())
+                            .map(String::from),
+                    ),
+                    "mtu" => interface.mtu = Some(value.parse()?),
+                    "table" => interface.table = Some(value.to_owned()),
+                    "preup" => interface.pre_up.push(value.to_owned()),
+                    "postup" => interface.post_up.push(value.to_owned()),
+                    "predown" => interface.pre_down.push(value.to_owned()),
+                    "postdown" => interface.post_down.push(value.to_owned()),
+                    "saveconfig" => interface.save_config = value == "true",
+                    _ => interface.other.push((key.to_owned(), value.to_owned())),
+                }
+            }
+            Section::Peer(peer) => match key.to_ascii_lowercase().as_str() {
+                "publickey" => peer.public_key = Some(value.parse()?),
+                "presharedkey" => peer.preshared_key = Some(value.parse()?),
+                "allowedips" => peer.allowed_ips.extend(list::<Cidr>(value)?),
+                "endpoint" => peer.endpoint = Some(value.to_owned()),
+                "persistentkeepalive" => {
+                    peer.persistent_keepalive = match value {
+                        "off" => None,
+                        value => Some(value.parse()?),
+                    }
+                }
+                _ => peer.other.push((key.to_owned(), value.to_owned())),
+            },
+        }
+    }
+
+    if let Section::Peer(peer) = section {
+        config.peers.push(peer.build()?);
+    }
+
+    Ok(config)
+}
+
+///
+/// Same as parse, but parses the `/etc/wireguard/<interface>.conf`.
+///
+/// ```no_run
+/// let config = unixism::wireguard::parse_default("wg0").unwrap();
+/// ```
+pub fn parse_default(interface: &str) -> Result<Config, ParseWireguardError> {
+    parse(fs::File::open(format!(
+        "/etc/wireguard/{}.conf",
+        interface
+    ))?)
+}
+
+///
+/// Writes the configuration in the format accepted by parse.
+/// Comments of the original file are not preserved.
+///
+/// ```no_run
+/// let mut config = unixism::wireguard::parse_default("wg0").unwrap();
+/// config.interface.mtu = Some(1380);
+///
+/// unixism::wireguard::write(std::fs::File::create("/etc/wireguard/wg0.conf").unwrap(), &config).unwrap();
+/// ```
+pub fn write<W>(mut writer: W, config: &Config) -> io::Result<()>
+where
+    W: io::Write,
+{
+    write!(writer, "{}", config)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Cursor;
+
+    use super::*;
+
+    const FIXTURE: &str = r#"
+# wg0, managed by hand
+[Interface]
+PrivateKey = yAnz5TF+lXXJte14tji3zlMNq+hd2rYUIgJBgB3fBmk=
+ListenPort = 51820
+Address = 10.192.122.1/24, fd00::1/64
+DNS = 10.192.122.53, corp.example
+PostUp = iptables -A FORWARD -i %i -j ACCEPT
+PostDown = iptables -D FORWARD -i %i -j ACCEPT
+
+[Peer]
+# laptop
+PublicKey = xTIBA5rboUvnH4htodjb6e697QjLERt1NAB4mZqp8Dg=
+AllowedIPs = 10.192.122.3/32, 10.192.124.1/24
+Endpoint = 192.95.5.67:1234
+
+[peer]
+publickey = TrMvSoP4jYQlY6RIzBgbssQqY3vxI2Pi+y71lOWWXX0=
+PresharedKey = /UwcSPg38hW/D9Y3tcS1FOV0K1wuURMbS0sesJEP5ak=
+AllowedIPs = 0.0.0.0/0
+Endpoint = vpn.example.com:51820
+PersistentKeepalive = 25
+"#;
+
+    #[test]
+    fn it_parse() {
+        let config = parse(Cursor::new(FIXTURE)).unwrap();
+
+        assert_eq!(
+            "yAnz5TF+lXXJte14tji3zlMNq+hd2rYUIgJBgB3fBmk=",
+            config.interface.private_key.unwrap().to_string()
+        );
+        assert_eq!(Some(51820), config.interface.listen_port);
+        assert_eq!(
+            vec![
+                "10.192.122.1/24".parse::<Cidr>().unwrap(),
+                "fd00::1/64".parse().unwrap()
+            ],
+            config.interface.addresses
+        );
+        assert_eq!(vec!["10.192.122.53", "corp.example"], config.interface.dns);
+        assert_eq!(1, config.interface.post_up.len());
+
+        assert_eq!(2, config.peers.len());
+        assert_eq!(
+            Some("192.95.5.67:1234"),
+            config.peers[0].endpoint.as_deref()
+        );
+        assert_eq!(
+            Cidr {
+                address: "10.192.124.1".parse().unwrap(),
+                prefix: 24
+            },
+            config.peers[0].allowed_ips[1]
+        );
+        assert_eq!(Some(25), config.peers[1].persistent_keepalive);
+        assert!(config.peers[1].preshared_key.is_some());
+
+        let key = "TrMvSoP4jYQlY6RIzBgbssQqY3vxI2Pi+y71lOWWXX0="
+            .parse()
+            .unwrap();
+        assert_eq!(
+            Some("vpn.example.com:51820"),
+            config.peer(&key).and_then(|peer| peer.endpoint.as_deref())
+        );
+
+        assert!(parse(Cursor::new("PrivateKey = x")).is_err());
+        assert!(parse(Cursor::new("[Peer]\nAllowedIPs = 0.0.0.0/0")).is_err());
+        assert!(parse(Cursor::new("[Peer]\nPublicKey = not-a-key")).is_err());
+        assert!(parse(Cursor::new("[Interface]\nAddress = 10.0.0.1/33")).is_err());
+        assert!(parse(Cursor::new("[Tunnel]")).is_err());
+    }
+
+    #[test]
+    fn it_write() {
+        let config = parse(Cursor::new(FIXTURE)).unwrap();
+
+        let mut written = Vec::new();
+        write(&mut written, &config).unwrap();
+
+        assert_eq!(config, parse(Cursor::new(&written)).unwrap());
+        assert!(String::from_utf8(written).unwrap().starts_with(
+            "[Interface]\nPrivateKey = yAnz5TF+lXXJte14tji3zlMNq+hd2rYUIgJBgB3fBmk=\n"
+        ));
+    }
+
+    #[test]
+    fn it_key() {
+        let key = Key([0xff; 32]);
+        assert_eq!(
+            "//////////////////////////////////////////8=",
+            key.to_string()
+        );
+        assert_eq!(key, key.to_string().parse().unwrap());
+
+        assert!("//////////////////////////////////////////9="
+            .parse::<Key>()
+            .is_err());
+    }
+}